@@ -5,6 +5,8 @@ use bstr::BString;
 
 pub mod immutable;
 mod signature;
+///
+pub mod time;
 
 pub(crate) const SPACE: &[u8; 1] = b" ";
 
@@ -57,3 +57,37 @@ mod write {
         Ok(name)
     }
 }
+
+#[cfg(feature = "git2")]
+mod git2_crate {
+    use crate::{time::convert::Error, Signature, Time};
+    use bstr::ByteSlice;
+    use std::convert::TryFrom;
+
+    impl TryFrom<&git2::Signature<'_>> for Signature {
+        type Error = Error;
+
+        fn try_from(other: &git2::Signature<'_>) -> Result<Self, Self::Error> {
+            Ok(Signature {
+                name: other.name_bytes().into(),
+                email: other.email_bytes().into(),
+                time: Time::try_from(other.when())?,
+            })
+        }
+    }
+
+    /// Fails if name or email aren't valid UTF-8, as `git2` only accepts strings.
+    impl TryFrom<&Signature> for git2::Signature<'static> {
+        type Error = git2::Error;
+
+        fn try_from(other: &Signature) -> Result<Self, Self::Error> {
+            git2::Signature::new(utf8(&other.name)?, utf8(&other.email)?, &other.time.into())
+        }
+    }
+
+    fn utf8(field: &[u8]) -> Result<&str, git2::Error> {
+        field
+            .to_str()
+            .map_err(|_| git2::Error::from_str("Signature name and email must be valid UTF-8"))
+    }
+}
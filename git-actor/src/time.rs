@@ -0,0 +1,372 @@
+//! Format and parse [times][Time] in the formats git supports, and convert them into the types of the `time`, `chrono`
+//! and `git2` crates with the respective features.
+use crate::{Sign, Time};
+use std::{convert::TryFrom, str::FromStr};
+
+/// The formats to [format][Time::format()] times in, named after the corresponding `--date` option of `git log`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Format {
+    /// Seconds since epoch followed by the offset, like `1112911993 +0200`, which is how times are stored in objects.
+    Raw,
+    /// An ISO 8601 like format, for example `2005-04-08 00:13:13 +0200`.
+    Iso8601,
+    /// The strict ISO 8601 format, for example `2005-04-08T00:13:13+02:00`.
+    Iso8601Strict,
+    /// The format used in emails, for example `Fri, 8 Apr 2005 00:13:13 +0200`.
+    Rfc2822,
+}
+
+///
+pub mod parse {
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`Time::parse()`][crate::Time::parse()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            Invalid { input: String } {
+                display("'{}' is not a valid time in the RAW, ISO 8601 or RFC 2822 format", input)
+            }
+        }
+    }
+}
+
+///
+pub mod convert {
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned when converting times from and into the types of the `time`, `chrono` and `git2` crates.
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            TimeOutOfRange { seconds: i64 } {
+                display("The time of {} seconds since epoch is out of range", seconds)
+            }
+            OffsetOutOfRange { offset: i32 } {
+                display("The offset of {} seconds is out of range", offset)
+            }
+        }
+    }
+}
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Formatting and parsing
+impl Time {
+    /// Format this time in the given `format`, using the clock time of the timezone it was recorded in, just like git.
+    pub fn format(&self, format: Format) -> String {
+        if format == Format::Raw {
+            let mut buf = Vec::with_capacity(16);
+            self.write_to(&mut buf).expect("writing to a vec never fails");
+            return String::from_utf8(buf).expect("the raw format is ASCII");
+        }
+
+        let local = self.seconds_with_offset();
+        let days = local.div_euclid(SECONDS_PER_DAY);
+        let seconds_of_day = local.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        let (hours, minutes, seconds) = (seconds_of_day / 3600, seconds_of_day / 60 % 60, seconds_of_day % 60);
+        let sign = match self.sign {
+            Sign::Plus => '+',
+            Sign::Minus => '-',
+        };
+        let offset = self.offset.abs();
+        let (offset_hours, offset_minutes) = (offset / 3600, offset / 60 % 60);
+        match format {
+            Format::Raw => unreachable!("handled above"),
+            Format::Iso8601 => format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02} {}{:02}{:02}",
+                year, month, day, hours, minutes, seconds, sign, offset_hours, offset_minutes
+            ),
+            Format::Iso8601Strict => format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+                year, month, day, hours, minutes, seconds, sign, offset_hours, offset_minutes
+            ),
+            Format::Rfc2822 => format!(
+                "{}, {} {} {} {:02}:{:02}:{:02} {}{:02}{:02}",
+                WEEKDAYS[(days + 4).rem_euclid(7) as usize],
+                day,
+                MONTHS[month as usize - 1],
+                year,
+                hours,
+                minutes,
+                seconds,
+                sign,
+                offset_hours,
+                offset_minutes
+            ),
+        }
+    }
+
+    /// Parse `input` in any of the [formats][Format] git can produce, like `1112911993 +0200`, `2005-04-08 00:13:13 +0200`,
+    /// `2005-04-08T00:13:13+02:00` or `Fri, 8 Apr 2005 00:13:13 +0200`.
+    ///
+    /// In the strict ISO 8601 format, `Z` is accepted as offset of UTC as well.
+    pub fn parse(input: &str) -> Result<Time, parse::Error> {
+        let trimmed = input.trim();
+        parse_raw(trimmed)
+            .or_else(|| parse_iso8601(trimmed))
+            .or_else(|| parse_rfc2822(trimmed))
+            .ok_or_else(|| parse::Error::Invalid { input: input.into() })
+    }
+}
+
+impl FromStr for Time {
+    type Err = parse::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Time::parse(s)
+    }
+}
+
+fn parse_raw(input: &str) -> Option<Time> {
+    let mut tokens = input.split(' ');
+    let seconds = digits(tokens.next()?, 1..=10)?;
+    let (offset, sign) = parse_offset(tokens.next()?, false)?;
+    if tokens.next().is_some() {
+        return None;
+    }
+    Some(Time {
+        time: u32::try_from(seconds).ok()?,
+        offset,
+        sign,
+    })
+}
+
+fn parse_iso8601(input: &str) -> Option<Time> {
+    let date = parse_date(input.get(..10)?)?;
+    let clock = parse_clock(input.get(11..19)?)?;
+    let (offset, sign) = match input.get(10..11)? {
+        " " => parse_offset(input.get(19..)?.strip_prefix(' ')?, false)?,
+        "T" => match input.get(19..)? {
+            "Z" => (0, Sign::Plus),
+            offset => parse_offset(offset, true)?,
+        },
+        _ => return None,
+    };
+    to_time(date, clock, offset, sign)
+}
+
+fn parse_rfc2822(input: &str) -> Option<Time> {
+    let input = match input.find(',') {
+        Some(pos) if WEEKDAYS.contains(&&input[..pos]) => &input[pos + 1..],
+        Some(_) => return None,
+        None => input,
+    };
+    let mut tokens = input.split_whitespace();
+    let day = digits(tokens.next()?, 1..=2)?;
+    let month = tokens.next()?;
+    let month = MONTHS.iter().position(|name| *name == month)? as u32 + 1;
+    let year = digits(tokens.next()?, 4..=4)?;
+    let clock = parse_clock(tokens.next()?)?;
+    let (offset, sign) = parse_offset(tokens.next()?, false)?;
+    if tokens.next().is_some() {
+        return None;
+    }
+    to_time((year as i64, month, day as u32), clock, offset, sign)
+}
+
+/// Parse a date like `2005-04-08` into its year, month and day.
+fn parse_date(input: &str) -> Option<(i64, u32, u32)> {
+    let mut tokens = input.split('-');
+    let year = digits(tokens.next()?, 4..=4)?;
+    let month = digits(tokens.next()?, 2..=2)?;
+    let day = digits(tokens.next()?, 2..=2)?;
+    Some((year as i64, month as u32, day as u32))
+}
+
+/// Parse a clock time like `00:13:13` into the seconds since midnight.
+fn parse_clock(input: &str) -> Option<i64> {
+    let mut tokens = input.split(':');
+    let hours = digits(tokens.next()?, 2..=2)?;
+    let minutes = digits(tokens.next()?, 2..=2)?;
+    let seconds = digits(tokens.next()?, 2..=2)?;
+    if tokens.next().is_some() || hours > 23 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+    Some((hours * 3600 + minutes * 60 + seconds) as i64)
+}
+
+/// Parse an offset like `+0200`, or `+02:00` if `with_colon` is true.
+fn parse_offset(input: &str, with_colon: bool) -> Option<(i32, Sign)> {
+    let (sign, rest) = match input.get(..1)? {
+        "+" => (Sign::Plus, &input[1..]),
+        "-" => (Sign::Minus, &input[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = if with_colon {
+        let mut tokens = rest.split(':');
+        let hours_and_minutes = (tokens.next()?, tokens.next()?);
+        if tokens.next().is_some() {
+            return None;
+        }
+        hours_and_minutes
+    } else {
+        (rest.get(..2)?, rest.get(2..)?)
+    };
+    let (hours, minutes) = (digits(hours, 2..=2)?, digits(minutes, 2..=2)?);
+    if hours > 24 || minutes > 59 {
+        return None;
+    }
+    let offset = (hours * 3600 + minutes * 60) as i32;
+    Some((if sign == Sign::Minus { -offset } else { offset }, sign))
+}
+
+/// Parse `input` as decimal number if it consists only of digits, with their amount within `len`.
+fn digits(input: &str, len: std::ops::RangeInclusive<usize>) -> Option<u64> {
+    if !len.contains(&input.len()) || !input.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    input.parse().ok()
+}
+
+fn to_time((year, month, day): (i64, u32, u32), seconds_of_day: i64, offset: i32, sign: Sign) -> Option<Time> {
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    let seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY + seconds_of_day - i64::from(offset);
+    Some(Time {
+        time: u32::try_from(seconds).ok()?,
+        offset,
+        sign,
+    })
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Return the amount of days since epoch of the given date in the proleptic gregorian calendar.
+///
+/// The algorithm is described in <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The inverse of [`days_from_civil()`], returning the year, month and day of the given amount of days since epoch.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(feature = "time")]
+mod time_crate {
+    use crate::{time::convert::Error, Sign, Time};
+    use std::convert::TryFrom;
+
+    impl TryFrom<Time> for ::time::OffsetDateTime {
+        type Error = Error;
+
+        fn try_from(t: Time) -> Result<Self, Self::Error> {
+            let offset = ::time::UtcOffset::from_whole_seconds(t.offset)
+                .map_err(|_| Error::OffsetOutOfRange { offset: t.offset })?;
+            Ok(::time::OffsetDateTime::from_unix_timestamp(t.time.into())
+                .map_err(|_| Error::TimeOutOfRange { seconds: t.time.into() })?
+                .to_offset(offset))
+        }
+    }
+
+    impl TryFrom<::time::OffsetDateTime> for Time {
+        type Error = Error;
+
+        fn try_from(t: ::time::OffsetDateTime) -> Result<Self, Self::Error> {
+            let seconds = t.unix_timestamp();
+            let offset = t.offset().whole_seconds();
+            Ok(Time {
+                time: u32::try_from(seconds).map_err(|_| Error::TimeOutOfRange { seconds })?,
+                offset,
+                sign: if offset < 0 { Sign::Minus } else { Sign::Plus },
+            })
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_crate {
+    use crate::{time::convert::Error, Sign, Time};
+    use chrono::{DateTime, FixedOffset, Offset, TimeZone, Utc};
+    use std::convert::TryFrom;
+
+    impl TryFrom<Time> for DateTime<FixedOffset> {
+        type Error = Error;
+
+        fn try_from(t: Time) -> Result<Self, Self::Error> {
+            let offset = FixedOffset::east_opt(t.offset).ok_or(Error::OffsetOutOfRange { offset: t.offset })?;
+            Ok(Utc
+                .timestamp_opt(t.time.into(), 0)
+                .single()
+                .ok_or(Error::TimeOutOfRange { seconds: t.time.into() })?
+                .with_timezone(&offset))
+        }
+    }
+
+    impl<Tz: TimeZone> TryFrom<DateTime<Tz>> for Time {
+        type Error = Error;
+
+        fn try_from(t: DateTime<Tz>) -> Result<Self, Self::Error> {
+            let seconds = t.timestamp();
+            let offset = t.offset().fix().local_minus_utc();
+            Ok(Time {
+                time: u32::try_from(seconds).map_err(|_| Error::TimeOutOfRange { seconds })?,
+                offset,
+                sign: if offset < 0 { Sign::Minus } else { Sign::Plus },
+            })
+        }
+    }
+}
+
+#[cfg(feature = "git2")]
+mod git2_crate {
+    use crate::{time::convert::Error, Sign, Time};
+    use std::convert::TryFrom;
+
+    /// Note that `git2` stores offsets in minutes, so seconds are truncated, and negative zero offsets turn positive.
+    impl From<Time> for git2::Time {
+        fn from(t: Time) -> Self {
+            git2::Time::new(t.time.into(), t.offset / 60)
+        }
+    }
+
+    impl TryFrom<git2::Time> for Time {
+        type Error = Error;
+
+        fn try_from(t: git2::Time) -> Result<Self, Self::Error> {
+            let seconds = t.seconds();
+            Ok(Time {
+                time: u32::try_from(seconds).map_err(|_| Error::TimeOutOfRange { seconds })?,
+                offset: t.offset_minutes() * 60,
+                sign: if t.sign() == '-' { Sign::Minus } else { Sign::Plus },
+            })
+        }
+    }
+}
@@ -1,5 +1,82 @@
 use crate::{Sign, Time, SPACE};
-use std::io;
+use std::{
+    cmp::Ordering,
+    io,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Conversion and comparison
+impl Time {
+    /// Return the point in time we represent as [`SystemTime`].
+    pub fn to_system_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.time.into())
+    }
+
+    /// Return the seconds since unix epoch with our `offset` applied, as seen on a clock in the timezone the time was recorded in.
+    pub fn seconds_with_offset(&self) -> i64 {
+        i64::from(self.time) + i64::from(self.offset)
+    }
+
+    /// Return the amount of seconds that passed between `earlier` and this time, which is negative if `earlier` is actually later.
+    ///
+    /// Timezones are irrelevant for this computation.
+    pub fn seconds_since(&self, earlier: &Time) -> i64 {
+        i64::from(self.time) - i64::from(earlier.time)
+    }
+
+    /// Compare the points in time represented by this instance and `other`, ignoring timezones.
+    ///
+    /// Note that the [`Ord`] implementation considers the timezone as well, so times with different offsets are never equal.
+    pub fn cmp_instant(&self, other: &Time) -> Ordering {
+        self.time.cmp(&other.time)
+    }
+
+    /// Format the time that passed between this time and `now` like git does with `--date=relative`, for example `3 days ago`.
+    pub fn format_relative(&self, now: SystemTime) -> String {
+        let now = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        match now.checked_sub(self.time.into()) {
+            Some(seconds) => format_relative_seconds(seconds),
+            None => "in the future".into(),
+        }
+    }
+}
+
+fn format_relative_seconds(seconds: u64) -> String {
+    fn amount(count: u64, unit: &str) -> String {
+        format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+    }
+    if seconds < 90 {
+        return format!("{} ago", amount(seconds, "second"));
+    }
+    let minutes = (seconds + 30) / 60;
+    if minutes < 90 {
+        return format!("{} ago", amount(minutes, "minute"));
+    }
+    let hours = (minutes + 30) / 60;
+    if hours < 36 {
+        return format!("{} ago", amount(hours, "hour"));
+    }
+    let days = (hours + 12) / 24;
+    if days < 14 {
+        return format!("{} ago", amount(days, "day"));
+    }
+    if days < 70 {
+        return format!("{} ago", amount((days + 3) / 7, "week"));
+    }
+    if days < 365 {
+        return format!("{} ago", amount((days + 15) / 30, "month"));
+    }
+    if days < 5 * 365 {
+        let total_months = (days * 12 * 2 + 365) / (365 * 2);
+        let (years, months) = (total_months / 12, total_months % 12);
+        return if months == 0 {
+            format!("{} ago", amount(years, "year"))
+        } else {
+            format!("{}, {} ago", amount(years, "year"), amount(months, "month"))
+        };
+    }
+    format!("{} ago", amount((days + 183) / 365, "year"))
+}
 
 impl Time {
     /// Serialize this instance to `out` in a format suitable for use in header fields of serialized git commits or tags.
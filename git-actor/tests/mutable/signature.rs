@@ -36,9 +36,302 @@ mod time {
         }
         Ok(())
     }
+
+    mod format_and_parse {
+        use git_actor::{time::Format, Sign, Time};
+
+        fn time(seconds: u32, offset: i32) -> Time {
+            Time {
+                time: seconds,
+                offset,
+                sign: if offset < 0 { Sign::Minus } else { Sign::Plus },
+            }
+        }
+
+        #[test]
+        fn all_formats_like_git() -> Result<(), Box<dyn std::error::Error>> {
+            for (time, expected) in &[
+                (
+                    time(1112911993, 7200),
+                    [
+                        "1112911993 +0200",
+                        "2005-04-08 00:13:13 +0200",
+                        "2005-04-08T00:13:13+02:00",
+                        "Fri, 8 Apr 2005 00:13:13 +0200",
+                    ],
+                ),
+                (
+                    time(1112911993, -5400),
+                    [
+                        "1112911993 -0130",
+                        "2005-04-07 20:43:13 -0130",
+                        "2005-04-07T20:43:13-01:30",
+                        "Thu, 7 Apr 2005 20:43:13 -0130",
+                    ],
+                ),
+                (
+                    time(1112892193, 19800),
+                    [
+                        "1112892193 +0530",
+                        "2005-04-07 22:13:13 +0530",
+                        "2005-04-07T22:13:13+05:30",
+                        "Thu, 7 Apr 2005 22:13:13 +0530",
+                    ],
+                ),
+                (
+                    time(951782400, 0),
+                    [
+                        "951782400 +0000",
+                        "2000-02-29 00:00:00 +0000",
+                        "2000-02-29T00:00:00+00:00",
+                        "Tue, 29 Feb 2000 00:00:00 +0000",
+                    ],
+                ),
+                (
+                    time(0, 0),
+                    [
+                        "0 +0000",
+                        "1970-01-01 00:00:00 +0000",
+                        "1970-01-01T00:00:00+00:00",
+                        "Thu, 1 Jan 1970 00:00:00 +0000",
+                    ],
+                ),
+            ] {
+                for (format, expected) in [Format::Raw, Format::Iso8601, Format::Iso8601Strict, Format::Rfc2822]
+                    .iter()
+                    .zip(expected.iter())
+                {
+                    assert_eq!(time.format(*format), *expected, "{:?}", format);
+                    assert_eq!(Time::parse(expected)?, *time, "{:?} round-trips", format);
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn negative_zero_offsets_keep_their_sign() -> Result<(), Box<dyn std::error::Error>> {
+            let time = Time {
+                time: 0,
+                offset: 0,
+                sign: Sign::Minus,
+            };
+            assert_eq!(time.format(Format::Iso8601), "1970-01-01 00:00:00 -0000");
+            assert_eq!(Time::parse("Thu, 1 Jan 1970 00:00:00 -0000")?, time);
+            Ok(())
+        }
+
+        #[test]
+        fn variants_accepted_by_git() -> Result<(), Box<dyn std::error::Error>> {
+            let expected = time(1112937193, -25200);
+            for input in &[
+                "Thu, 07 Apr 2005 22:13:13 -0700",
+                "7 Apr 2005 22:13:13 -0700",
+                "  2005-04-07T22:13:13-07:00\n",
+            ] {
+                assert_eq!(input.parse::<Time>()?, expected, "{:?}", input);
+            }
+            assert_eq!(Time::parse("2005-04-07T22:13:13Z")?, time(1112911993, 0));
+            Ok(())
+        }
+
+        #[test]
+        fn invalid_times() {
+            for input in &[
+                "",
+                "1112911993",
+                "1112911993 +02:00",
+                "4294967296 +0000",
+                "2005-02-30 00:00:00 +0000",
+                "2005-04-07 24:00:00 +0000",
+                "2005-04-07T22:13:13+0200",
+                "1969-12-31 23:59:59 +0000",
+                "Thu 7 Apr 2005 22:13:13 -0700",
+                "Thu, 7 April 2005 22:13:13 -0700",
+            ] {
+                assert!(Time::parse(input).is_err(), "{:?} is invalid", input);
+            }
+            assert_eq!(
+                Time::parse("yesterday").unwrap_err().to_string(),
+                "'yesterday' is not a valid time in the RAW, ISO 8601 or RFC 2822 format"
+            );
+        }
+    }
+
+    mod conversion_and_comparison {
+        use git_actor::{Sign, Time};
+        use std::{
+            cmp::Ordering,
+            time::{Duration, UNIX_EPOCH},
+        };
+
+        fn time(seconds: u32, offset: i32) -> Time {
+            Time {
+                time: seconds,
+                offset,
+                sign: if offset < 0 { Sign::Minus } else { Sign::Plus },
+            }
+        }
+
+        #[test]
+        fn negative_offsets_are_applied_to_the_seconds() {
+            assert_eq!(time(1112911993, -5400).seconds_with_offset(), 1112906593);
+            assert_eq!(time(1112911993, 7200).seconds_with_offset(), 1112919193);
+            assert_eq!(
+                time(0, -3600).seconds_with_offset(),
+                -3600,
+                "clocks west of UTC showed a time before the epoch"
+            );
+        }
+
+        #[test]
+        fn system_time_and_instants_ignore_the_offset() {
+            let west = time(1112911993, -5400);
+            let east = time(1112911993, 7200);
+            assert_eq!(west.to_system_time(), UNIX_EPOCH + Duration::from_secs(1112911993));
+            assert_eq!(west.to_system_time(), east.to_system_time());
+            assert_eq!(west.cmp_instant(&east), Ordering::Equal);
+            assert_ne!(
+                west.cmp(&east),
+                Ordering::Equal,
+                "the Ord implementation considers the offset"
+            );
+            assert_eq!(time(10, 3600).cmp_instant(&time(11, -3600)), Ordering::Less);
+            assert_eq!(time(10, -3600).seconds_since(&time(11, 3600)), -1);
+        }
+
+        #[test]
+        fn relative_formatting_at_unit_boundaries() {
+            const DAY: u32 = 24 * 60 * 60;
+            let now = 1_000_000_000;
+            for (seconds_ago, expected) in &[
+                (0, "0 seconds ago"),
+                (1, "1 second ago"),
+                (89, "89 seconds ago"),
+                (90, "2 minutes ago"),
+                (60 * 60, "60 minutes ago"),
+                (2 * 60 * 60, "2 hours ago"),
+                (35 * 60 * 60, "35 hours ago"),
+                (36 * 60 * 60, "2 days ago"),
+                (13 * DAY, "13 days ago"),
+                (14 * DAY, "2 weeks ago"),
+                (69 * DAY, "10 weeks ago"),
+                (70 * DAY, "2 months ago"),
+                (364 * DAY, "12 months ago"),
+                (365 * DAY, "1 year ago"),
+                (400 * DAY, "1 year, 1 month ago"),
+                (5 * 365 * DAY, "5 years ago"),
+            ] {
+                let then = time(now - seconds_ago, -5400);
+                assert_eq!(
+                    then.format_relative(UNIX_EPOCH + Duration::from_secs(now.into())),
+                    *expected,
+                    "{} seconds ago",
+                    seconds_ago
+                );
+            }
+        }
+
+        #[test]
+        fn relative_formatting_of_times_after_now() {
+            let now = UNIX_EPOCH + Duration::from_secs(100);
+            assert_eq!(time(101, 0).format_relative(now), "in the future");
+            assert_eq!(
+                time(1, 0).format_relative(UNIX_EPOCH - Duration::from_secs(1)),
+                "in the future",
+                "a clock before the epoch is treated as being at the epoch"
+            );
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn conversion_to_and_from_the_time_crate() -> Result<(), Box<dyn std::error::Error>> {
+        use std::convert::TryFrom;
+        let time = Time {
+            time: 1112911993,
+            offset: -5400,
+            sign: Sign::Minus,
+        };
+        let converted = time::OffsetDateTime::try_from(time)?;
+        assert_eq!(converted.unix_timestamp(), 1112911993);
+        assert_eq!(converted.offset().whole_seconds(), -5400);
+        assert_eq!(converted.hour(), 20, "the time is in the original timezone");
+        assert_eq!(Time::try_from(converted)?, time);
+        Ok(())
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn conversion_to_and_from_the_chrono_crate() -> Result<(), Box<dyn std::error::Error>> {
+        use chrono::{DateTime, FixedOffset, Timelike};
+        use std::convert::TryFrom;
+        let time = Time {
+            time: 1112911993,
+            offset: 7200,
+            sign: Sign::Plus,
+        };
+        let converted = DateTime::<FixedOffset>::try_from(time)?;
+        assert_eq!(converted.timestamp(), 1112911993);
+        assert_eq!(converted.hour(), 0, "the time is in the original timezone");
+        assert_eq!(Time::try_from(converted)?, time);
+        Ok(())
+    }
+
+    #[cfg(feature = "git2")]
+    #[test]
+    fn conversion_to_and_from_the_git2_crate() -> Result<(), Box<dyn std::error::Error>> {
+        use std::convert::TryFrom;
+        let time = Time {
+            time: 1112911993,
+            offset: -5400,
+            sign: Sign::Minus,
+        };
+        let converted = git2::Time::from(time);
+        assert_eq!(converted.seconds(), 1112911993);
+        assert_eq!(converted.offset_minutes(), -90);
+        assert_eq!(converted.sign(), '-');
+        assert_eq!(Time::try_from(converted)?, time);
+        assert!(
+            Time::try_from(git2::Time::new(-1, 0)).is_err(),
+            "times before the epoch can't be represented"
+        );
+        Ok(())
+    }
 }
 
 mod signature {
+    #[cfg(feature = "git2")]
+    #[test]
+    fn conversion_to_and_from_the_git2_crate() -> Result<(), Box<dyn std::error::Error>> {
+        use bstr::ByteSlice;
+        use git_actor::{Sign, Signature, Time};
+        use std::convert::TryFrom;
+        let signature = Signature {
+            name: "Sebastian Thiel".into(),
+            email: "byronimo@gmail.com".into(),
+            time: Time {
+                time: 1112911993,
+                offset: 7200,
+                sign: Sign::Plus,
+            },
+        };
+        let converted = git2::Signature::try_from(&signature)?;
+        assert_eq!(converted.name(), Some("Sebastian Thiel"));
+        assert_eq!(converted.email(), Some("byronimo@gmail.com"));
+        assert_eq!(converted.when().offset_minutes(), 120);
+        assert_eq!(Signature::try_from(&converted)?, signature);
+
+        let invalid = Signature {
+            name: b"\xff".as_bstr().to_owned(),
+            ..signature
+        };
+        assert!(
+            git2::Signature::try_from(&invalid).is_err(),
+            "git2 only supports UTF-8 names and emails"
+        );
+        Ok(())
+    }
+
     mod write_to {
         mod invalid {
             use git_actor::{Sign, Signature, Time};
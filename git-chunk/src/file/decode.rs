@@ -0,0 +1,92 @@
+use crate::file::{index, Index};
+use std::convert::TryInto;
+
+/// The error returned by [`Index::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The table of contents for {num_chunks} chunks at offset {toc_offset} doesn't fit into the {data_len} bytes of the file")]
+    TocOutOfBounds {
+        toc_offset: usize,
+        num_chunks: u32,
+        data_len: usize,
+    },
+    #[error("The chunk '{}' starts at offset {offset} which is within the table of contents or before it", String::from_utf8_lossy(.kind))]
+    ChunkOverlapsToc { kind: crate::Id, offset: u64 },
+    #[error("The chunk '{}' ends at offset {end} which is before its start at offset {start}", String::from_utf8_lossy(.kind))]
+    NegativeSize { kind: crate::Id, start: u64, end: u64 },
+    #[error("The chunk '{}' ends at offset {end} which is beyond the end of the file at offset {data_len}", String::from_utf8_lossy(.kind))]
+    ChunkOutOfBounds { kind: crate::Id, end: u64, data_len: usize },
+    #[error("The chunk '{}' is listed more than once", String::from_utf8_lossy(.kind))]
+    DuplicateChunk { kind: crate::Id },
+    #[error("The table of contents is not terminated by the sentinel but by '{}'", String::from_utf8_lossy(.actual))]
+    MissingSentinel { actual: crate::Id },
+}
+
+/// Decoding
+impl Index {
+    /// Read the table of contents with `num_chunks` entries plus sentinel starting at `toc_offset` within `data`, the
+    /// entire chunk file.
+    ///
+    /// All chunks are validated to start after the table of contents, to be listed in ascending order of their offsets
+    /// and to end within `data`, so that accessing them by their [range][index::Entry::offset] can't panic.
+    pub fn from_bytes(data: &[u8], toc_offset: usize, num_chunks: u32) -> Result<Self, Error> {
+        let toc_end = Index::size_for_entries(num_chunks as usize)
+            .checked_add(toc_offset)
+            .filter(|end| *end <= data.len())
+            .ok_or(Error::TocOutOfBounds {
+                toc_offset,
+                num_chunks,
+                data_len: data.len(),
+            })?;
+        let toc = &data[toc_offset..toc_end];
+
+        let mut chunks = Vec::<index::Entry>::with_capacity(num_chunks as usize);
+        let mut entries = toc.chunks(Index::ENTRY_SIZE).map(|entry| {
+            let (kind, offset) = entry.split_at(4);
+            (
+                kind.try_into().expect("4 bytes of the id"),
+                u64::from_be_bytes(offset.try_into().expect("8 bytes of the offset")),
+            )
+        });
+        let (mut kind, mut start) = entries.next().expect("there is at least the sentinel");
+        for (next_kind, end) in entries {
+            if start < toc_end as u64 {
+                return Err(Error::ChunkOverlapsToc { kind, offset: start });
+            }
+            if end < start {
+                return Err(Error::NegativeSize { kind, start, end });
+            }
+            if end > data.len() as u64 {
+                return Err(Error::ChunkOutOfBounds {
+                    kind,
+                    end,
+                    data_len: data.len(),
+                });
+            }
+            if chunks.iter().any(|chunk| chunk.kind == kind) {
+                return Err(Error::DuplicateChunk { kind });
+            }
+            chunks.push(index::Entry {
+                kind,
+                offset: start as usize..end as usize,
+            });
+            kind = next_kind;
+            start = end;
+        }
+        if kind != crate::SENTINEL {
+            return Err(Error::MissingSentinel { actual: kind });
+        }
+        Ok(Index {
+            will_write: false,
+            chunks,
+        })
+    }
+
+    /// The offset at which the last chunk ends, which is where format specific trailers are located.
+    ///
+    /// If there is no chunk, `None` is returned.
+    pub fn end_of_chunks(&self) -> Option<usize> {
+        self.chunks.last().map(|chunk| chunk.offset.end)
+    }
+}
@@ -0,0 +1,21 @@
+use std::ops::Range;
+
+/// An entry of the table of contents of a chunk file.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Entry {
+    /// The kind of the chunk.
+    pub kind: crate::Id,
+    /// The range of bytes the chunk occupies in the chunk file.
+    pub offset: Range<usize>,
+}
+
+///
+pub mod offset_by_id {
+    /// The error returned by [`Index::offset_by_id()`][crate::file::Index::offset_by_id()].
+    #[derive(Debug, thiserror::Error)]
+    #[error("The chunk '{}' was not found in the table of contents", String::from_utf8_lossy(.kind))]
+    pub struct Error {
+        /// The kind of the chunk that wasn't found.
+        pub kind: crate::Id,
+    }
+}
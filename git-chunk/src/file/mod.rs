@@ -0,0 +1,44 @@
+use std::ops::Range;
+
+///
+pub mod decode;
+///
+pub mod index;
+///
+pub mod write;
+
+/// The table of contents of a chunk file, listing the location of each chunk.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Index {
+    /// If true, we use `chunks` in a way that facilitates writing them.
+    pub(crate) will_write: bool,
+    /// All chunks in the order they are stored in the file, which is also the order of the table of contents.
+    pub chunks: Vec<index::Entry>,
+}
+
+/// Access
+impl Index {
+    /// The size of a single entry in the table of contents in bytes, consisting of the [`Id`][crate::Id] and
+    /// a big-endian 64 bit offset.
+    pub const ENTRY_SIZE: usize = std::mem::size_of::<u32>() + std::mem::size_of::<u64>();
+    /// The size of the table of contents of a chunk file without any chunk, which still holds the sentinel entry.
+    pub const EMPTY_SIZE: usize = Index::ENTRY_SIZE;
+
+    /// The size of the table of contents in bytes for a chunk file with `num_entries` chunks, including the sentinel entry.
+    pub const fn size_for_entries(num_entries: usize) -> usize {
+        Self::ENTRY_SIZE * (num_entries + 1/* sentinel */)
+    }
+
+    /// Return the range of bytes in the chunk file occupied by the chunk with `kind`.
+    pub fn offset_by_id(&self, kind: crate::Id) -> Result<Range<usize>, index::offset_by_id::Error> {
+        self.chunks
+            .iter()
+            .find_map(|c| (c.kind == kind).then(|| c.offset.clone()))
+            .ok_or(index::offset_by_id::Error { kind })
+    }
+
+    /// Return the bytes of the chunk with `kind` within `data`, the entire chunk file this index was read from.
+    pub fn data_by_id<'a>(&self, data: &'a [u8], kind: crate::Id) -> Result<&'a [u8], index::offset_by_id::Error> {
+        self.offset_by_id(kind).map(|offset| &data[offset])
+    }
+}
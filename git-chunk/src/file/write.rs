@@ -0,0 +1,138 @@
+use crate::file::{index, Index};
+use std::io;
+
+/// A [`Write`][io::Write] implementation to write the data of all chunks planned with an [`Index`] in order, created
+/// by [`Index::into_write()`].
+pub struct Chunk<W> {
+    chunks_to_write: Vec<index::Entry>,
+    current_chunk: Option<index::Entry>,
+    written_bytes: usize,
+    out: W,
+}
+
+impl<W> Chunk<W>
+where
+    W: io::Write,
+{
+    /// Finish writing the current chunk, if any, and return the [`Id`][crate::Id] of the next chunk whose data is to be
+    /// written, or `None` if there is no chunk left.
+    ///
+    /// # Panics
+    ///
+    /// If the amount of bytes written for the current chunk doesn't match its planned size.
+    pub fn next_chunk(&mut self) -> Option<crate::Id> {
+        if let Some(entry) = self.current_chunk.take() {
+            assert_eq!(
+                entry.offset.end - entry.offset.start,
+                self.written_bytes,
+                "The chunk '{}' must have exactly the planned size",
+                String::from_utf8_lossy(&entry.kind)
+            );
+        }
+        self.written_bytes = 0;
+        self.current_chunk = self.chunks_to_write.pop();
+        self.current_chunk.as_ref().map(|entry| entry.kind)
+    }
+
+    /// Return the underlying writer once all chunks were written.
+    ///
+    /// # Panics
+    ///
+    /// If not all chunks were written, which is the case unless [`next_chunk()`][Chunk::next_chunk()] returned `None`.
+    pub fn into_inner(self) -> W {
+        assert!(
+            self.current_chunk.is_none() && self.chunks_to_write.is_empty(),
+            "All chunks must be written before obtaining the inner writer"
+        );
+        self.out
+    }
+}
+
+impl<W> io::Write for Chunk<W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.out.write(buf)?;
+        self.written_bytes += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Writing
+impl Index {
+    /// Create a new index to [plan chunks][Index::plan_chunk()] with before [writing them][Index::into_write()].
+    pub fn for_writing() -> Self {
+        Index {
+            will_write: true,
+            chunks: Vec::new(),
+        }
+    }
+
+    /// Plan to write a chunk of `kind` with exactly `size` bytes, after all previously planned chunks.
+    ///
+    /// # Panics
+    ///
+    /// If this index wasn't created [for writing][Index::for_writing()], if `kind` is the [sentinel][crate::SENTINEL]
+    /// or if a chunk of `kind` was already planned.
+    pub fn plan_chunk(&mut self, kind: crate::Id, size: usize) {
+        assert!(self.will_write, "BUG: create the index with `for_writing()`");
+        assert_ne!(kind, crate::SENTINEL, "BUG: the sentinel can't be used as chunk");
+        assert!(
+            !self.chunks.iter().any(|chunk| chunk.kind == kind),
+            "BUG: the chunk '{}' was already planned",
+            String::from_utf8_lossy(&kind)
+        );
+        self.chunks.push(index::Entry { kind, offset: 0..size });
+    }
+
+    /// The amount of bytes needed to store the table of contents and all planned chunks.
+    pub fn planned_storage_size(&self) -> u64 {
+        assert!(self.will_write, "BUG: create the index with `for_writing()`");
+        Index::size_for_entries(self.chunks.len()) as u64
+            + self
+                .chunks
+                .iter()
+                .map(|chunk| (chunk.offset.end - chunk.offset.start) as u64)
+                .sum::<u64>()
+    }
+
+    /// The amount of chunks planned so far, which is what file formats typically store in their header.
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Write the table of contents to `out`, which is positioned at `current_offset` within the chunk file, and return
+    /// a writer for the data of all planned chunks.
+    ///
+    /// Call [`Chunk::next_chunk()`] to learn which chunk to write next and write exactly its planned size.
+    pub fn into_write<W>(self, mut out: W, current_offset: usize) -> io::Result<Chunk<W>>
+    where
+        W: io::Write,
+    {
+        assert!(self.will_write, "BUG: create the index with `for_writing()`");
+        let mut chunks = self.chunks;
+        let mut offset = current_offset + Index::size_for_entries(chunks.len());
+        for chunk in chunks.iter_mut() {
+            let size = chunk.offset.end - chunk.offset.start;
+            chunk.offset = offset..offset + size;
+            out.write_all(&chunk.kind)?;
+            out.write_all(&(offset as u64).to_be_bytes())?;
+            offset += size;
+        }
+        out.write_all(&crate::SENTINEL)?;
+        out.write_all(&(offset as u64).to_be_bytes())?;
+
+        chunks.reverse();
+        Ok(Chunk {
+            chunks_to_write: chunks,
+            current_chunk: None,
+            written_bytes: 0,
+            out,
+        })
+    }
+}
@@ -0,0 +1,20 @@
+//! Low-level access to reading and writing chunk files, the format underlying commit-graph and multi-pack-index files.
+//!
+//! A chunk file starts with a format specific header, followed by a table of contents which lists the [`Id`] and the offset
+//! of each chunk, terminated by a [sentinel entry][SENTINEL] whose offset marks the end of the last chunk.
+//! The chunk data follows, typically along with a format specific trailer like a checksum.
+//!
+//! Formats are expected to read the table of contents with [`file::Index::from_bytes()`] and to obtain their chunks by
+//! [`Id`] without doing any offset arithmetic themselves, or to [plan their chunks][file::Index::plan_chunk()] and
+//! [write them][file::Index::into_write()] in order.
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms, missing_docs)]
+
+/// An identifier of the kind of a chunk, unique within a chunk file and typically made of 4 ASCII characters like `OIDF`.
+pub type Id = [u8; 4];
+
+/// The identifier of the last entry in the table of contents, which marks the end of the last chunk.
+pub const SENTINEL: Id = [0u8; 4];
+
+///
+pub mod file;
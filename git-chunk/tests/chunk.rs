@@ -0,0 +1,179 @@
+use git_chunk::file::{decode, Index};
+use std::io::Write;
+
+const HEADER: &[u8] = b"HEAD";
+
+/// Write a chunk file with `chunks` after a 4 byte header, along with a 2 byte trailer.
+fn chunk_file(chunks: &[(git_chunk::Id, &[u8])]) -> Vec<u8> {
+    let mut index = Index::for_writing();
+    for (kind, data) in chunks {
+        index.plan_chunk(*kind, data.len());
+    }
+    assert_eq!(index.num_chunks(), chunks.len());
+    let planned_size = index.planned_storage_size();
+
+    let mut out = Vec::from(HEADER);
+    let mut chunk = index.into_write(out, HEADER.len()).expect("writes to vec succeed");
+    for (kind, data) in chunks {
+        assert_eq!(chunk.next_chunk(), Some(*kind), "chunks are written in planned order");
+        chunk.write_all(data).expect("writes to vec succeed");
+    }
+    assert_eq!(chunk.next_chunk(), None);
+    out = chunk.into_inner();
+    assert_eq!(out.len() as u64, HEADER.len() as u64 + planned_size);
+    out.extend_from_slice(b"TT");
+    out
+}
+
+fn toc_entry_offset(entry: usize) -> usize {
+    HEADER.len() + entry * Index::ENTRY_SIZE
+}
+
+fn set_offset_of_entry(data: &mut [u8], entry: usize, offset: u64) {
+    let start = toc_entry_offset(entry) + 4;
+    data[start..start + 8].copy_from_slice(&offset.to_be_bytes());
+}
+
+mod write_and_read {
+    use crate::{chunk_file, HEADER};
+    use git_chunk::file::Index;
+
+    #[test]
+    fn chunks_are_found_by_id_and_can_be_empty() {
+        let file = chunk_file(&[(*b"FIRS", b"one"), (*b"EMPT", b""), (*b"SECO", b"second")]);
+        let index = Index::from_bytes(&file, HEADER.len(), 3).expect("valid chunk file");
+
+        assert_eq!(index.chunks.len(), 3);
+        assert_eq!(index.data_by_id(&file, *b"FIRS").expect("present"), b"one");
+        assert_eq!(index.data_by_id(&file, *b"EMPT").expect("present"), b"");
+        assert_eq!(index.data_by_id(&file, *b"SECO").expect("present"), b"second");
+        assert_eq!(
+            index.offset_by_id(*b"FIRS").expect("present"),
+            HEADER.len() + Index::size_for_entries(3)..HEADER.len() + Index::size_for_entries(3) + 3
+        );
+        assert_eq!(
+            &file[index.end_of_chunks().expect("there are chunks")..],
+            b"TT",
+            "the trailer follows the last chunk"
+        );
+        assert_eq!(
+            index.offset_by_id(*b"MISS").unwrap_err().to_string(),
+            "The chunk 'MISS' was not found in the table of contents"
+        );
+    }
+
+    #[test]
+    fn files_without_chunks_only_have_the_sentinel() {
+        let file = chunk_file(&[]);
+        assert_eq!(file.len(), HEADER.len() + Index::EMPTY_SIZE + 2);
+        let index = Index::from_bytes(&file, HEADER.len(), 0).expect("valid chunk file");
+        assert!(index.chunks.is_empty());
+        assert_eq!(index.end_of_chunks(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn writing_less_than_planned_panics() {
+        let mut index = Index::for_writing();
+        index.plan_chunk(*b"FIRS", 3);
+        index.plan_chunk(*b"SECO", 3);
+        let mut chunk = index.into_write(Vec::new(), 0).expect("writes to vec succeed");
+        chunk.next_chunk();
+        std::io::Write::write_all(&mut chunk, b"on").expect("writes to vec succeed");
+        chunk.next_chunk();
+    }
+
+    #[test]
+    #[should_panic]
+    fn planning_the_same_chunk_twice_panics() {
+        let mut index = Index::for_writing();
+        index.plan_chunk(*b"FIRS", 3);
+        index.plan_chunk(*b"FIRS", 3);
+    }
+}
+
+mod corrupt {
+    use crate::{chunk_file, set_offset_of_entry, toc_entry_offset, HEADER};
+    use git_chunk::file::{decode, Index};
+
+    fn two_chunks() -> Vec<u8> {
+        chunk_file(&[(*b"FIRS", b"one"), (*b"SECO", b"second")])
+    }
+
+    #[test]
+    fn table_of_contents_out_of_bounds() {
+        let file = two_chunks();
+        assert!(matches!(
+            Index::from_bytes(&file[..HEADER.len() + Index::ENTRY_SIZE], HEADER.len(), 2),
+            Err(decode::Error::TocOutOfBounds { num_chunks: 2, .. })
+        ));
+        assert!(matches!(
+            Index::from_bytes(&file, HEADER.len(), u32::MAX),
+            Err(decode::Error::TocOutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn chunk_overlapping_the_table_of_contents() {
+        let mut file = two_chunks();
+        set_offset_of_entry(&mut file, 0, (HEADER.len() + Index::ENTRY_SIZE) as u64);
+        assert!(matches!(
+            Index::from_bytes(&file, HEADER.len(), 2),
+            Err(decode::Error::ChunkOverlapsToc { kind, .. }) if &kind == b"FIRS"
+        ));
+    }
+
+    #[test]
+    fn chunk_with_negative_size() {
+        let mut file = two_chunks();
+        let first_offset = (HEADER.len() + Index::size_for_entries(2)) as u64;
+        set_offset_of_entry(&mut file, 1, first_offset - 1);
+        assert!(matches!(
+            Index::from_bytes(&file, HEADER.len(), 2),
+            Err(decode::Error::NegativeSize { kind, .. }) if &kind == b"FIRS"
+        ));
+    }
+
+    #[test]
+    fn chunk_beyond_the_end_of_the_file() {
+        let mut file = two_chunks();
+        set_offset_of_entry(&mut file, 2, u64::MAX);
+        let err = Index::from_bytes(&file, HEADER.len(), 2).unwrap_err();
+        assert!(matches!(&err, decode::Error::ChunkOutOfBounds { kind, .. } if kind == b"SECO"));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "The chunk 'SECO' ends at offset {} which is beyond the end of the file at offset {}",
+                u64::MAX,
+                file.len()
+            )
+        );
+    }
+
+    #[test]
+    fn duplicate_chunks() {
+        let mut file = two_chunks();
+        let second_id = toc_entry_offset(1);
+        file[second_id..second_id + 4].copy_from_slice(b"FIRS");
+        assert!(matches!(
+            Index::from_bytes(&file, HEADER.len(), 2),
+            Err(decode::Error::DuplicateChunk { kind }) if &kind == b"FIRS"
+        ));
+    }
+
+    #[test]
+    fn missing_sentinel() {
+        let file = two_chunks();
+        assert!(matches!(
+            Index::from_bytes(&file, HEADER.len(), 1),
+            Err(decode::Error::MissingSentinel { actual }) if &actual == b"SECO"
+        ));
+    }
+}
+
+#[test]
+fn decode_errors_are_usable_as_trait_objects() {
+    let file = chunk_file(&[]);
+    let err: Box<dyn std::error::Error> = Index::from_bytes(&file, HEADER.len(), 1).unwrap_err().into();
+    assert!(err.downcast_ref::<decode::Error>().is_some());
+}
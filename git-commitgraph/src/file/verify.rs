@@ -3,7 +3,6 @@ use crate::{
     file::{self, File},
     GENERATION_NUMBER_INFINITY, GENERATION_NUMBER_MAX,
 };
-use bstr::ByteSlice;
 use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use std::{
     cmp::{max, min},
@@ -172,6 +171,6 @@ fn verify_split_chain_filename_hash(path: impl AsRef<Path>, expected: &git_hash:
         .and_then(|stem| stem.strip_prefix("graph-"))
         .map_or(Ok(()), |hex| match git_hash::ObjectId::from_hex(hex.as_bytes()) {
             Ok(actual) if actual == expected => Ok(()),
-            _ => Err(format!("graph-{}.graph", expected.to_sha1_hex().as_bstr())),
+            _ => Err(format!("graph-{}.graph", expected)),
         })
 }
@@ -68,6 +68,22 @@ pub struct Outcome {
     pub num_commits: u32,
     /// A mapping of `N -> number of commits with N parents`.
     pub parent_counts: BTreeMap<u32, u32>,
+    /// Information about each file the graph is composed of, starting with the base file of a split chain.
+    pub layers: Vec<Layer>,
+}
+
+/// Information about a single file of a commit graph, as part of the [`Outcome`] of [`Graph::verify_integrity()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(serde::Deserialize, serde::Serialize))]
+pub struct Layer {
+    /// The path to the file.
+    pub path: PathBuf,
+    /// The verified trailing checksum of the file, which also identifies it in a split chain.
+    pub checksum: git_hash::ObjectId,
+    /// The amount of files this one builds upon.
+    pub base_graph_count: u8,
+    /// Statistics about the commits stored in this file only.
+    pub statistics: file::verify::Outcome,
 }
 
 impl Graph {
@@ -90,6 +106,7 @@ impl Graph {
             longest_path_length: None,
             num_commits: 0,
             parent_counts: BTreeMap::new(),
+            layers: Vec::with_capacity(self.files.len()),
         };
         let mut max_generation = 0u32;
 
@@ -188,9 +205,15 @@ impl Graph {
 
             max_generation = max(max_generation, file_stats.max_generation);
             stats.num_commits += file_stats.num_commits;
-            for (key, value) in file_stats.parent_counts.into_iter() {
-                *stats.parent_counts.entry(key).or_insert(0) += value;
+            for (key, value) in file_stats.parent_counts.iter() {
+                *stats.parent_counts.entry(*key).or_insert(0) += value;
             }
+            stats.layers.push(Layer {
+                path: file.path().to_owned(),
+                checksum: file.checksum().to_owned(),
+                base_graph_count: file.base_graph_count(),
+                statistics: file_stats,
+            });
             file_start_pos = next_file_start_pos;
         }
 
@@ -0,0 +1,64 @@
+use crate::{inspect_refs, make_readonly_repo};
+use git_commitgraph::Graph;
+use std::convert::Infallible;
+
+fn verify(graph: &Graph) -> Result<git_commitgraph::graph::verify::Outcome, Box<dyn std::error::Error>> {
+    Ok(graph.verify_integrity(|_commit| Ok::<_, Infallible>(()))?)
+}
+
+#[test]
+fn split_chains_report_each_layer_starting_with_the_base() -> crate::Result {
+    let repo_dir = make_readonly_repo("split_chain.sh");
+    let refs = inspect_refs(&repo_dir, &["commit1", "commit2", "commit3"]);
+    let cg = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+    let outcome = verify(&cg)?;
+
+    assert_eq!(outcome.num_commits, 3);
+    assert_eq!(outcome.longest_path_length, Some(2));
+    assert_eq!(
+        outcome.parent_counts.into_iter().collect::<Vec<_>>(),
+        vec![(0, 1), (1, 2)]
+    );
+    assert_eq!(
+        outcome.layers.len(),
+        3,
+        "one layer per commit as each was written separately"
+    );
+    for (index, layer) in outcome.layers.iter().enumerate() {
+        assert_eq!(usize::from(layer.base_graph_count), index);
+        assert_eq!(layer.statistics.num_commits, 1);
+        let generation = index as u32 + 1;
+        assert_eq!(layer.statistics.min_generation, generation);
+        assert_eq!(layer.statistics.max_generation, generation);
+        assert_eq!(
+            layer.path.file_name().and_then(|name| name.to_str()),
+            Some(format!("graph-{}.graph", layer.checksum).as_str()),
+            "split chain files are named after their checksum"
+        );
+    }
+    assert_eq!(cg.commit_by_id(refs["commit3"].id()).map(|c| c.generation()), Some(3));
+    Ok(())
+}
+
+#[test]
+fn single_files_have_a_single_layer() -> crate::Result {
+    let repo_dir = make_readonly_repo("octopus_merges.sh");
+    let cg = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+    let outcome = verify(&cg)?;
+
+    assert_eq!(outcome.layers.len(), 1);
+    let layer = &outcome.layers[0];
+    assert_eq!(layer.base_graph_count, 0);
+    assert_eq!(layer.statistics.num_commits, outcome.num_commits);
+    assert_eq!(
+        layer.path.file_name().and_then(|name| name.to_str()),
+        Some("commit-graph")
+    );
+    assert_eq!(layer.checksum, cg_file_checksum(&layer.path)?);
+    Ok(())
+}
+
+fn cg_file_checksum(path: &std::path::Path) -> Result<git_hash::ObjectId, Box<dyn std::error::Error>> {
+    let data = std::fs::read(path)?;
+    Ok(git_hash::ObjectId::from_20_bytes(&data[data.len() - 20..]))
+}
@@ -0,0 +1,164 @@
+use crate::lines::{self, Hunk, Line};
+use git_object::{
+    bstr::{BStr, BString},
+    tree::EntryMode,
+};
+
+///
+pub mod parse;
+#[doc(inline)]
+pub use parse::parse;
+
+///
+pub mod tree;
+#[doc(inline)]
+pub use tree::tree;
+
+/// The patch of a single file as obtained by [`parse()`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct File<'a> {
+    /// The path of the file before the change, or `None` if the file is created.
+    pub previous_path: Option<BString>,
+    /// The path of the file after the change, or `None` if the file is deleted.
+    pub path: Option<BString>,
+    /// The mode of the file before the change, if it is mentioned in the patch.
+    pub previous_mode: Option<EntryMode>,
+    /// The mode of the file after the change, if it is mentioned in the patch.
+    pub mode: Option<EntryMode>,
+    /// True if the file at `path` is a copy of the one at `previous_path`, which is kept.
+    pub copy: bool,
+    /// True if the content of the file is binary, which can't be applied as it's not part of the patch.
+    pub binary: bool,
+    /// The changes to the content of the file, with zero-based line ranges.
+    pub hunks: Vec<Hunk<'a>>,
+}
+
+/// Options to configure how [hunks][hunks()] are applied.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The maximum amount of context lines at the beginning and the end of a hunk which may be ignored if the hunk doesn't
+    /// apply with all of its context. Defaults to 0, which like `git apply` requires all context to match.
+    pub max_fuzz: usize,
+}
+
+/// Information about where a hunk was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Applied {
+    /// The zero-based line at which the lines the hunk expects were found.
+    pub line: usize,
+    /// The amount of lines the hunk was found after the position stated in the hunk, or before it if negative.
+    pub offset: isize,
+    /// The amount of context lines at the beginning and the end of the hunk which were ignored to apply it.
+    pub fuzz: usize,
+}
+
+/// The error returned by [`hunks()`] if a hunk doesn't apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mismatch {
+    /// The zero-based index of the hunk that didn't apply.
+    pub hunk: usize,
+    /// The zero-based line at which the hunk was expected to apply.
+    pub line: usize,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Hunk {} does not apply as its context doesn't match at or near line {}",
+            self.hunk + 1,
+            self.line + 1
+        )
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// Apply `hunks` to `data` and write the result into `out`, replacing its previous content, while allowing for as much fuzz as
+/// configured in `options`. Return where each hunk was applied.
+///
+/// Like `git apply`, hunks are searched for near the line they state, adjusted by the offset of the previous hunk, and must
+/// not overlap. Hunks with context that start at the first line or end at the last line must match there.
+pub fn hunks(data: &[u8], hunks: &[Hunk<'_>], options: Options, out: &mut Vec<u8>) -> Result<Vec<Applied>, Mismatch> {
+    let lines = lines::split(data);
+    out.clear();
+    let mut applied = Vec::with_capacity(hunks.len());
+    let mut pos = 0;
+    let mut offset = 0isize;
+    for (index, hunk) in hunks.iter().enumerate() {
+        let expected = (hunk.before.start as isize + offset).max(0) as usize;
+        let (line, fuzz, range) = (0..=options.max_fuzz)
+            .find_map(|fuzz| locate(&lines, pos, hunk, fuzz, offset).map(|(line, range)| (line, fuzz, range)))
+            .ok_or(Mismatch {
+                hunk: index,
+                line: expected,
+            })?;
+        let hunk_lines = &hunk.lines[range.clone()];
+        let skipped_context = range.start;
+        out.extend(lines[pos..line].iter().flat_map(|line| line.iter()));
+        out.extend(
+            hunk_lines
+                .iter()
+                .filter(|line| !matches!(line, Line::Removed(_)))
+                .flat_map(|line| line.data().iter()),
+        );
+        pos = line + hunk_lines.iter().filter(|line| !matches!(line, Line::Added(_))).count();
+        offset = line as isize - (hunk.before.start + skipped_context) as isize;
+        applied.push(Applied { line, offset, fuzz });
+    }
+    out.extend(lines[pos..].iter().flat_map(|line| line.iter()));
+    Ok(applied)
+}
+
+/// Find the line at which `hunk` applies with `fuzz` context lines ignored at each end, not before line `min_line`, and
+/// return it along with the range of hunk lines to apply.
+fn locate(
+    lines: &[&BStr],
+    min_line: usize,
+    hunk: &Hunk<'_>,
+    fuzz: usize,
+    offset: isize,
+) -> Option<(usize, std::ops::Range<usize>)> {
+    let is_context = |line: &&Line<'_>| matches!(line, Line::Context(_));
+    let leading = hunk.lines.iter().take_while(is_context).count();
+    let trailing = hunk.lines[leading..].iter().rev().take_while(is_context).count();
+    let (skip_leading, skip_trailing) = (fuzz.min(leading), fuzz.min(trailing));
+    if fuzz > 0 && skip_leading < fuzz && skip_trailing < fuzz {
+        return None;
+    }
+    let range = skip_leading..hunk.lines.len() - skip_trailing;
+    let preimage: Vec<_> = hunk.lines[range.clone()]
+        .iter()
+        .filter(|line| !matches!(line, Line::Added(_)))
+        .map(|line| line.data())
+        .collect();
+    let max_line = lines.len().checked_sub(preimage.len())?;
+    if min_line > max_line {
+        return None;
+    }
+    let matches_at = |line: usize| lines[line..][..preimage.len()] == preimage[..];
+
+    let has_context = leading + trailing > 0;
+    let anchored_start = has_context && skip_leading == 0 && hunk.before.start == 0;
+    let anchored_end = has_context && trailing == 0;
+    let line = match (anchored_start, anchored_end) {
+        (true, true) => Some(0).filter(|_| max_line == 0 && matches_at(0)),
+        (true, false) => Some(0).filter(|_| min_line == 0 && matches_at(0)),
+        (false, true) => Some(max_line).filter(|line| matches_at(*line)),
+        (false, false) => {
+            let expected =
+                (hunk.before.start as isize + skip_leading as isize + offset).max(min_line as isize) as usize;
+            let expected = expected.min(max_line);
+            (0..=max_line - min_line)
+                .flat_map(|distance| {
+                    let after = expected.checked_add(distance).filter(|line| *line <= max_line);
+                    let before = expected
+                        .checked_sub(distance)
+                        .filter(|line| distance > 0 && *line >= min_line);
+                    after.into_iter().chain(before)
+                })
+                .find(|line| matches_at(*line))
+        }
+    };
+    line.map(|line| (line, range))
+}
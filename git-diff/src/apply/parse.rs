@@ -0,0 +1,308 @@
+use crate::{
+    apply::File,
+    lines::{Hunk, Line},
+};
+use git_object::{
+    bstr::{BStr, BString, ByteSlice},
+    tree::EntryMode,
+};
+use quick_error::quick_error;
+use std::convert::TryFrom;
+
+quick_error! {
+    /// The error returned by [`parse()`][crate::apply::parse()].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        InvalidHunkHeader { line: usize } {
+            display("The hunk header in line {} is malformed", line)
+        }
+        TruncatedHunk { line: usize } {
+            display("The hunk starting in line {} ended before all of its lines were seen", line)
+        }
+        InvalidPath { line: usize } {
+            display("The path in line {} could not be parsed", line)
+        }
+        MissingPath { line: usize } {
+            display("The patch of the file starting in line {} does not name the file", line)
+        }
+    }
+}
+
+/// Parse all patches of files in `patch`, which is the output of `git diff` or `diff -u`, skipping all lines which are not
+/// part of these patches like the message preceding the patch in an email.
+///
+/// The hunks of each file refer to the lines of `patch`, and line numbers in errors are one-based.
+pub fn parse(patch: &[u8]) -> Result<Vec<File<'_>>, Error> {
+    let lines = crate::lines::split(patch);
+    let mut files = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        if line.starts_with(b"diff --git ") {
+            index = git_file(&lines, index, &mut files)?;
+        } else if line.starts_with(b"--- ") && lines.get(index + 1).map_or(false, |next| next.starts_with(b"+++ ")) {
+            let start = index;
+            let mut file = File::default();
+            index = paths(&lines, index, &mut file)?;
+            index = hunks(&lines, index, &mut file.hunks)?;
+            if file.previous_path.is_none() && file.path.is_none() {
+                return Err(Error::MissingPath { line: start + 1 });
+            }
+            files.push(file);
+        } else {
+            index += 1;
+        }
+    }
+    Ok(files)
+}
+
+/// Parse the patch of a file starting with the `diff --git` line at `index` and return the index of the first line after it.
+fn git_file<'a>(lines: &[&'a BStr], mut index: usize, files: &mut Vec<File<'a>>) -> Result<usize, Error> {
+    let start = index;
+    let header_path = header_path(&lines[index]["diff --git ".len()..]);
+    let mut file = File {
+        previous_path: header_path.clone(),
+        path: header_path,
+        ..Default::default()
+    };
+    index += 1;
+    let mut created = false;
+    let mut deleted = false;
+    while let Some(line) = lines.get(index) {
+        let line = line.trim_end_with(|c| c == '\n' || c == '\r');
+        if let Some(mode) = line.strip_prefix(b"old mode ") {
+            file.previous_mode = entry_mode(mode);
+        } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+            file.mode = entry_mode(mode);
+        } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+            file.mode = entry_mode(mode);
+            created = true;
+        } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+            file.previous_mode = entry_mode(mode);
+            deleted = true;
+        } else if let Some(path) = line
+            .strip_prefix(b"rename from ")
+            .or_else(|| line.strip_prefix(b"copy from "))
+        {
+            file.previous_path = Some(unquote(path).ok_or(Error::InvalidPath { line: index + 1 })?);
+        } else if let Some(path) = line.strip_prefix(b"rename to ") {
+            file.path = Some(unquote(path).ok_or(Error::InvalidPath { line: index + 1 })?);
+        } else if let Some(path) = line.strip_prefix(b"copy to ") {
+            file.path = Some(unquote(path).ok_or(Error::InvalidPath { line: index + 1 })?);
+            file.copy = true;
+        } else if let Some(ids_and_mode) = line.strip_prefix(b"index ") {
+            if let Some(mode) = ids_and_mode.splitn_str(2, " ").nth(1) {
+                file.previous_mode = entry_mode(mode);
+                file.mode = file.previous_mode;
+            }
+        } else if line.starts_with(b"Binary files ") || line == b"GIT binary patch" {
+            file.binary = true;
+        } else if line.starts_with(b"similarity index ") || line.starts_with(b"dissimilarity index ") {
+            // not needed to apply the patch
+        } else {
+            break;
+        }
+        index += 1;
+    }
+    if lines.get(index).map_or(false, |line| line.starts_with(b"--- ")) {
+        index = paths(lines, index, &mut file)?;
+        index = hunks(lines, index, &mut file.hunks)?;
+    }
+    if created {
+        file.previous_path = None;
+    }
+    if deleted {
+        file.path = None;
+    }
+    if file.previous_path.is_none() && file.path.is_none() {
+        return Err(Error::MissingPath { line: start + 1 });
+    }
+    files.push(file);
+    Ok(index)
+}
+
+/// Parse the `---` and `+++` lines at `index` into `file` and return the index of the line following them.
+fn paths(lines: &[&BStr], index: usize, file: &mut File<'_>) -> Result<usize, Error> {
+    let path = |index: usize, prefix: &str| -> Result<Option<BString>, Error> {
+        let path = lines
+            .get(index)
+            .and_then(|line| line.strip_prefix(prefix.as_bytes()))
+            .ok_or(Error::InvalidPath { line: index + 1 })?;
+        let path = path.trim_end_with(|c| c == '\n' || c == '\r');
+        if path == b"/dev/null" {
+            return Ok(None);
+        }
+        let path = if path.starts_with(b"\"") {
+            unquote(path)
+        } else {
+            Some(path.splitn_str(2, "\t").next().unwrap_or_default().into())
+        };
+        path.and_then(strip_first_component)
+            .map(Some)
+            .ok_or(Error::InvalidPath { line: index + 1 })
+    };
+    file.previous_path = path(index, "--- ")?;
+    file.path = path(index + 1, "+++ ")?;
+    Ok(index + 2)
+}
+
+/// Parse all hunks starting at `index` into `out` and return the index of the first line after them.
+fn hunks<'a>(lines: &[&'a BStr], mut index: usize, out: &mut Vec<Hunk<'a>>) -> Result<usize, Error> {
+    while let Some(line) = lines.get(index) {
+        if !line.starts_with(b"@@ ") {
+            break;
+        }
+        let start = index;
+        let (before, after) = hunk_header(line).ok_or(Error::InvalidHunkHeader { line: index + 1 })?;
+        let (mut before_left, mut after_left) = (before.len(), after.len());
+        let mut hunk = Hunk {
+            before,
+            after,
+            lines: Vec::new(),
+        };
+        index += 1;
+        while before_left + after_left > 0 || lines.get(index).map_or(false, |line| line.starts_with(b"\\")) {
+            let line = lines.get(index).ok_or(Error::TruncatedHunk { line: start + 1 })?;
+            index += 1;
+            let (kind, data) = match line.split_first() {
+                Some((b'\\', _)) => {
+                    let data = match hunk.lines.last_mut() {
+                        Some(Line::Context(data)) | Some(Line::Removed(data)) | Some(Line::Added(data)) => data,
+                        None => return Err(Error::TruncatedHunk { line: start + 1 }),
+                    };
+                    if data.ends_with(b"\n") {
+                        *data = data[..data.len() - 1].as_bstr();
+                    }
+                    continue;
+                }
+                Some((b'\n', _)) => (b' ', line[..].as_bstr()),
+                Some((kind, data)) => (*kind, data.as_bstr()),
+                None => return Err(Error::TruncatedHunk { line: start + 1 }),
+            };
+            let (line, before_lines, after_lines) = match kind {
+                b' ' => (Line::Context(data), 1, 1),
+                b'-' => (Line::Removed(data), 1, 0),
+                b'+' => (Line::Added(data), 0, 1),
+                _ => return Err(Error::TruncatedHunk { line: start + 1 }),
+            };
+            if before_lines > before_left || after_lines > after_left {
+                return Err(Error::TruncatedHunk { line: start + 1 });
+            }
+            before_left -= before_lines;
+            after_left -= after_lines;
+            hunk.lines.push(line);
+        }
+        out.push(hunk);
+    }
+    Ok(index)
+}
+
+/// Parse `@@ -start,len +start,len @@` into zero-based ranges of lines.
+fn hunk_header(line: &[u8]) -> Option<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+    let mut tokens = line.strip_prefix(b"@@ ")?.splitn_str(3, " ");
+    let range = |token: &[u8], prefix: u8| {
+        let token = token.strip_prefix(&[prefix])?;
+        let mut parts = token.splitn_str(2, ",");
+        let start: usize = parts.next()?.to_str().ok()?.parse().ok()?;
+        let len: usize = match parts.next() {
+            Some(len) => len.to_str().ok()?.parse().ok()?,
+            None => 1,
+        };
+        let start = if len == 0 { start } else { start.checked_sub(1)? };
+        Some(start..start + len)
+    };
+    let before = range(tokens.next()?, b'-')?;
+    let after = range(tokens.next()?, b'+')?;
+    tokens.next().filter(|rest| rest.starts_with(b"@@"))?;
+    Some((before, after))
+}
+
+/// Obtain the path from the remainder of a `diff --git a/path b/path` line, which is only possible if the path is the same on
+/// both sides. Otherwise the path is expected to be provided by other header lines.
+fn header_path(names: &[u8]) -> Option<BString> {
+    let names = names.trim_end_with(|c| c == '\n' || c == '\r');
+    if names.starts_with(b"\"") {
+        let end = quoted_len(names)?;
+        let (before, after) = (unquote(&names[..end])?, unquote(names[end..].strip_prefix(b" ")?)?);
+        return same(strip_first_component(before)?, strip_first_component(after)?);
+    }
+    if names.len() % 2 == 0 {
+        return None;
+    }
+    let (before, after) = (&names[..names.len() / 2], &names[names.len() / 2 + 1..]);
+    same(
+        strip_first_component(before.into())?,
+        strip_first_component(after.into())?,
+    )
+}
+
+fn same(before: BString, after: BString) -> Option<BString> {
+    if before == after {
+        Some(before)
+    } else {
+        None
+    }
+}
+
+/// Remove the `a/` or `b/` prefix of paths in patches.
+fn strip_first_component(path: BString) -> Option<BString> {
+    let pos = path.find_byte(b'/')?;
+    Some(path[pos + 1..].into())
+}
+
+fn entry_mode(mode: &[u8]) -> Option<EntryMode> {
+    EntryMode::try_from(mode.trim_end_with(|c| c == '\n' || c == '\r')).ok()
+}
+
+/// Return the length of the quoted string at the beginning of `input`, including both quotes.
+fn quoted_len(input: &[u8]) -> Option<usize> {
+    let mut escaped = false;
+    for (pos, b) in input.iter().enumerate().skip(1) {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return Some(pos + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Remove the quotes around `path` and resolve its escapes, the way git quotes paths with special or non-ASCII bytes, or
+/// return `path` as is if it isn't quoted.
+fn unquote(path: &[u8]) -> Option<BString> {
+    let path = match path.strip_prefix(b"\"") {
+        Some(quoted) => quoted.strip_suffix(b"\"")?,
+        None => return Some(path.into()),
+    };
+    let mut out = Vec::with_capacity(path.len());
+    let mut bytes = path.iter().copied();
+    while let Some(b) = bytes.next() {
+        if b != b'\\' {
+            out.push(b);
+            continue;
+        }
+        out.push(match bytes.next()? {
+            b'a' => b'\x07',
+            b'b' => b'\x08',
+            b't' => b'\t',
+            b'n' => b'\n',
+            b'v' => b'\x0b',
+            b'f' => b'\x0c',
+            b'r' => b'\r',
+            digit @ b'0'..=b'3' => {
+                let mut value = digit - b'0';
+                for _ in 0..2 {
+                    match bytes.next()? {
+                        digit @ b'0'..=b'7' => value = value * 8 + (digit - b'0'),
+                        _ => return None,
+                    }
+                }
+                value
+            }
+            other => other,
+        });
+    }
+    Some(out.into())
+}
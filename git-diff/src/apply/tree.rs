@@ -0,0 +1,187 @@
+use crate::apply::{self, Applied, File};
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::BString,
+    immutable::{self, tree::lookup},
+    mutable,
+    tree::EntryMode,
+};
+use quick_error::quick_error;
+use std::collections::HashMap;
+
+quick_error! {
+    /// The error returned by [`tree()`][crate::apply::tree()].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        LoadTree(err: immutable::tree::lookup::Error) {
+            display("A tree could not be loaded")
+            from()
+            source(err)
+        }
+        FindBlob { path: BString, id: ObjectId } {
+            display("The blob {} of '{}' could not be found", id, path)
+        }
+        NotFound { path: BString } {
+            display("The file '{}' to patch does not exist", path)
+        }
+        AlreadyExists { path: BString } {
+            display("The file '{}' to create already exists", path)
+        }
+        Binary { path: BString } {
+            display("The binary file '{}' cannot be patched as the patch doesn't contain its content", path)
+        }
+        Mismatch { path: BString, err: apply::Mismatch } {
+            display("The patch of '{}' does not apply", path)
+            source(err)
+        }
+        DeletionMismatch { path: BString } {
+            display("The file '{}' to delete has content the patch does not remove", path)
+        }
+        Edit(err: mutable::tree::editor::Error) {
+            display("The tree could not be edited")
+            from()
+            source(err)
+        }
+        Write(err: Box<dyn std::error::Error + Send + Sync>) {
+            display("An object could not be written")
+            source(&**err)
+        }
+    }
+}
+
+/// The result of [`tree()`][crate::apply::tree()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The id of the tree with all patches applied.
+    pub id: ObjectId,
+    /// Where the hunks of each file were applied, in the order of the files.
+    pub applied: Vec<Vec<Applied>>,
+}
+
+/// Apply the patches of all `files` to the tree with id `root` and return the id of the resulting tree, along with where
+/// each hunk was applied according to `options`.
+///
+/// * `find_tree(id, buf)` and `find_blob(id, buf)` return the trees and blobs of the repository.
+/// * `write_blob(data)` and `write_tree(tree)` store new objects and return their id.
+///
+/// Files are created, deleted, renamed, copied and have their mode changed as stated in the patch, and files patched more
+/// than once see the result of the previous patches. Modes not mentioned in the patch are kept, and new files default to
+/// being non-executable.
+pub fn tree<FindTree, FindBlob, WriteBlob, WriteTree, E>(
+    root: &oid,
+    files: &[File<'_>],
+    options: apply::Options,
+    mut find_tree: FindTree,
+    mut find_blob: FindBlob,
+    mut write_blob: WriteBlob,
+    mut write_tree: WriteTree,
+) -> Result<Outcome, Error>
+where
+    FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+    FindBlob: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<&'a [u8]>,
+    WriteBlob: FnMut(&[u8]) -> Result<ObjectId, E>,
+    WriteTree: FnMut(&mutable::Tree) -> Result<ObjectId, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut root_buf = Vec::new();
+    let root_iter = find_tree(root, &mut root_buf).ok_or_else(|| lookup::Error::FindTree {
+        path: BString::default(),
+        id: root.to_owned(),
+    })?;
+    let root_tree = mutable::Tree {
+        entries: root_iter
+            .clone()
+            .map(|entry| entry.map(Into::into))
+            .collect::<Result<_, _>>()
+            .map_err(|err| lookup::Error::DecodeTree {
+                path: BString::default(),
+                err,
+            })?,
+    };
+
+    let mut buf = Vec::new();
+    let mut entries = HashMap::<BString, Option<(EntryMode, ObjectId)>>::new();
+    for path in files
+        .iter()
+        .flat_map(|file| file.previous_path.iter().chain(file.path.iter()))
+    {
+        if !entries.contains_key(path) {
+            let entry = root_iter
+                .clone()
+                .lookup_entry(path, &mut buf, &mut find_tree)?
+                .map(|entry| (entry.mode, entry.oid));
+            entries.insert(path.clone(), entry);
+        }
+    }
+
+    let mut editor = mutable::tree::Editor::new(root_tree, &mut find_tree);
+    let mut applied = Vec::with_capacity(files.len());
+    let mut data = Vec::new();
+    for file in files {
+        let path = file
+            .path
+            .as_ref()
+            .or(file.previous_path.as_ref())
+            .expect("parsed patches have a path");
+        let previous = match &file.previous_path {
+            Some(previous_path) => Some(
+                entries[previous_path]
+                    .filter(|(mode, _)| mode.is_no_tree())
+                    .ok_or_else(|| Error::NotFound {
+                        path: previous_path.clone(),
+                    })?,
+            ),
+            None => {
+                if entries[path].is_some() {
+                    return Err(Error::AlreadyExists { path: path.clone() });
+                }
+                None
+            }
+        };
+        if file.binary {
+            return Err(Error::Binary { path: path.clone() });
+        }
+
+        let previous_data = match previous {
+            Some((_, id)) => find_blob(&id, &mut buf).ok_or_else(|| Error::FindBlob { path: path.clone(), id })?,
+            None => &[],
+        };
+        let hunks_applied =
+            apply::hunks(previous_data, &file.hunks, options, &mut data).map_err(|err| Error::Mismatch {
+                path: path.clone(),
+                err,
+            })?;
+        applied.push(hunks_applied);
+
+        if !file.copy {
+            if let Some(previous_path) = &file.previous_path {
+                editor.remove(previous_path)?;
+                entries.insert(previous_path.clone(), None);
+            }
+        }
+        match &file.path {
+            Some(path) => {
+                let id = match previous {
+                    Some((_, id)) if file.hunks.is_empty() => id,
+                    _ => write_blob(&data).map_err(|err| Error::Write(Box::new(err)))?,
+                };
+                let mode = file
+                    .mode
+                    .or_else(|| previous.map(|(mode, _)| mode))
+                    .unwrap_or(EntryMode::Blob);
+                editor.upsert(path, mode, id)?;
+                entries.insert(path.clone(), Some((mode, id)));
+            }
+            None => {
+                if !data.is_empty() {
+                    return Err(Error::DeletionMismatch { path: path.clone() });
+                }
+            }
+        }
+    }
+    let id = editor
+        .write(&mut write_tree)
+        .map_err(|err| Error::Write(Box::new(err)))?;
+    Ok(Outcome { id, applied })
+}
@@ -0,0 +1,34 @@
+use git_features::parallel;
+use std::marker::PhantomData;
+
+/// Reorders the items produced for indexed chunks of inputs processed in parallel to match the order of the inputs.
+pub(crate) struct Chunks<T, E> {
+    chunks: Vec<(usize, Vec<T>)>,
+    _error: PhantomData<E>,
+}
+
+impl<T, E> Default for Chunks<T, E> {
+    fn default() -> Self {
+        Chunks {
+            chunks: Vec::new(),
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<T, E> parallel::Reduce for Chunks<T, E> {
+    type Input = Result<(usize, Vec<T>), E>;
+    type FeedProduce = ();
+    type Output = Vec<T>;
+    type Error = E;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.chunks.push(item?);
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Self::Output, Self::Error> {
+        self.chunks.sort_by_key(|(chunk_index, _)| *chunk_index);
+        Ok(self.chunks.into_iter().flat_map(|(_, items)| items).collect())
+    }
+}
@@ -0,0 +1,223 @@
+use quick_error::quick_error;
+use std::collections::HashMap;
+
+quick_error! {
+    /// The error returned by [`apply()`] and [`apply_instructions()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Truncated {
+            display("The delta ended unexpectedly")
+        }
+        ReservedInstruction {
+            display("The delta contains the reserved instruction 0")
+        }
+        BaseSizeMismatch { expected: u64, actual: usize } {
+            display("The delta expects a base of {} bytes, but it has {} bytes", expected, actual)
+        }
+        CopyOutOfBounds { offset: usize, size: usize, base_size: usize } {
+            display("Cannot copy {} bytes at offset {} from a base of {} bytes", size, offset, base_size)
+        }
+        ResultSizeMismatch { expected: u64 } {
+            display("The delta doesn't produce the {} bytes it claims to", expected)
+        }
+    }
+}
+
+/// The amount of bytes that have to be the same in base and target for them to be found by [`encode()`].
+const BLOCK_SIZE: usize = 16;
+/// The maximum amount of positions with the same block in the base to try when looking for the longest match.
+const MAX_CANDIDATES: usize = 64;
+/// The largest amount of bytes a single copy instruction can copy.
+const MAX_COPY_SIZE: usize = 0x10000;
+/// The largest amount of bytes a single insert instruction can hold.
+const MAX_INSERT_SIZE: usize = 0x7f;
+
+/// Given the decompressed pack delta `d`, decode a size in bytes (either the base object size or the result object size)
+/// Equivalent to [this canonical git function](https://github.com/git/git/blob/311531c9de557d25ac087c1637818bd2aad6eb3a/delta.h#L89)
+pub fn decode_header_size(d: &[u8]) -> (u64, usize) {
+    let mut i = 0;
+    let mut size = 0u64;
+    let mut consumed = 0;
+    for cmd in d.iter() {
+        consumed += 1;
+        size |= (*cmd as u64 & 0x7f).checked_shl(i).unwrap_or(0);
+        i += 7;
+        if *cmd & 0x80 == 0 {
+            break;
+        }
+    }
+    (size, consumed)
+}
+
+/// Append `size` to `out` in the variable length encoding used for the sizes at the beginning of a delta.
+pub fn encode_header_size(mut size: u64, out: &mut Vec<u8>) {
+    while size >= 0x80 {
+        out.push(size as u8 | 0x80);
+        size >>= 7;
+    }
+    out.push(size as u8);
+}
+
+/// Apply the complete `delta`, including the base and result sizes it starts with, to `base` and write the result
+/// into `out`, replacing its previous content.
+///
+/// This is the format of deltified objects in packs as well as of the hunks of `GIT binary patch`es.
+pub fn apply(base: &[u8], delta: &[u8], out: &mut Vec<u8>) -> Result<(), Error> {
+    let (base_size, consumed) = decode_size(delta)?;
+    if base_size != base.len() as u64 {
+        return Err(Error::BaseSizeMismatch {
+            expected: base_size,
+            actual: base.len(),
+        });
+    }
+    let delta = &delta[consumed..];
+    let (result_size, consumed) = decode_size(delta)?;
+    out.clear();
+    out.resize(result_size as usize, 0);
+    apply_instructions(base, out, &delta[consumed..])
+}
+
+/// Apply the delta `instructions`, without the sizes preceding them, to `base` and write the result into `target`,
+/// which must have exactly the size of the result.
+pub fn apply_instructions(base: &[u8], target: &mut [u8], instructions: &[u8]) -> Result<(), Error> {
+    let expected = target.len() as u64;
+    let mut target = target;
+    let mut data = instructions;
+    while let Some((&cmd, rest)) = data.split_first() {
+        data = rest;
+        let (source, size) = if cmd & 0b1000_0000 != 0 {
+            let (mut offset, mut size) = (0usize, 0usize);
+            for (bit, shift) in (0..7).zip([0, 8, 16, 24, 0, 8, 16].iter()) {
+                if cmd & (1 << bit) == 0 {
+                    continue;
+                }
+                let (&byte, rest) = data.split_first().ok_or(Error::Truncated)?;
+                data = rest;
+                if bit < 4 {
+                    offset |= (byte as usize) << shift;
+                } else {
+                    size |= (byte as usize) << shift;
+                }
+            }
+            if size == 0 {
+                size = MAX_COPY_SIZE;
+            }
+            let source =
+                offset
+                    .checked_add(size)
+                    .and_then(|end| base.get(offset..end))
+                    .ok_or(Error::CopyOutOfBounds {
+                        offset,
+                        size,
+                        base_size: base.len(),
+                    })?;
+            (source, size)
+        } else if cmd == 0 {
+            return Err(Error::ReservedInstruction);
+        } else {
+            let size = cmd as usize;
+            let source = data.get(..size).ok_or(Error::Truncated)?;
+            data = &data[size..];
+            (source, size)
+        };
+        if size > target.len() {
+            return Err(Error::ResultSizeMismatch { expected });
+        }
+        let (head, tail) = target.split_at_mut(size);
+        head.copy_from_slice(source);
+        target = tail;
+    }
+    if !target.is_empty() {
+        return Err(Error::ResultSizeMismatch { expected });
+    }
+    Ok(())
+}
+
+/// Append a delta to `out` which turns `base` into `target` when [applied][apply()], including the sizes of both.
+///
+/// Like git, blocks of the `target` which are also in `base` are found by their content and copied from it, while
+/// everything else is inserted literally. Bytes beyond the first 4GiB of `base` are never copied.
+pub fn encode(base: &[u8], target: &[u8], out: &mut Vec<u8>) {
+    encode_header_size(base.len() as u64, out);
+    encode_header_size(target.len() as u64, out);
+
+    let indexed_len = base.len().min(u32::MAX as usize);
+    let mut blocks = HashMap::<&[u8], Vec<usize>>::new();
+    for offset in (0..indexed_len.saturating_sub(BLOCK_SIZE - 1)).step_by(BLOCK_SIZE) {
+        let candidates = blocks.entry(&base[offset..][..BLOCK_SIZE]).or_default();
+        if candidates.len() < MAX_CANDIDATES {
+            candidates.push(offset);
+        }
+    }
+
+    let mut literal_start = 0;
+    let mut pos = 0;
+    while pos + BLOCK_SIZE <= target.len() {
+        let best = blocks.get(&target[pos..][..BLOCK_SIZE]).and_then(|candidates| {
+            candidates
+                .iter()
+                .map(|&offset| {
+                    let len = base[offset..indexed_len]
+                        .iter()
+                        .zip(&target[pos..])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    (offset, len)
+                })
+                .max_by_key(|&(_, len)| len)
+        });
+        let (mut offset, mut len) = match best {
+            Some(best) => best,
+            None => {
+                pos += 1;
+                continue;
+            }
+        };
+        while pos > literal_start && offset > 0 && base[offset - 1] == target[pos - 1] {
+            pos -= 1;
+            offset -= 1;
+            len += 1;
+        }
+        insert(&target[literal_start..pos], out);
+        copy(offset, len, out);
+        pos += len;
+        literal_start = pos;
+    }
+    insert(&target[literal_start..], out);
+}
+
+fn decode_size(delta: &[u8]) -> Result<(u64, usize), Error> {
+    let (size, consumed) = decode_header_size(delta);
+    match delta[..consumed].last() {
+        Some(last) if last & 0x80 == 0 => Ok((size, consumed)),
+        _ => Err(Error::Truncated),
+    }
+}
+
+fn insert(data: &[u8], out: &mut Vec<u8>) {
+    for chunk in data.chunks(MAX_INSERT_SIZE) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+fn copy(mut offset: usize, mut len: usize, out: &mut Vec<u8>) {
+    while len > 0 {
+        let size = len.min(MAX_COPY_SIZE);
+        let cmd_pos = out.len();
+        out.push(0b1000_0000);
+        let encoded_size = if size == MAX_COPY_SIZE { 0 } else { size };
+        let fields = (0..4)
+            .map(|byte| (offset >> (byte * 8)) as u8)
+            .chain((0..3).map(|byte| (encoded_size >> (byte * 8)) as u8));
+        for (bit, byte) in fields.enumerate() {
+            if byte != 0 {
+                out[cmd_pos] |= 1 << bit;
+                out.push(byte);
+            }
+        }
+        offset += size;
+        len -= size;
+    }
+}
@@ -0,0 +1,139 @@
+use crate::tree::recorder::Change;
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::{BString, ByteVec},
+    immutable,
+    tree::EntryMode,
+};
+use quick_error::quick_error;
+use std::cmp::Ordering;
+
+quick_error! {
+    /// The error returned by [`needed_to_obtain()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        NotFound { oid: ObjectId } {
+            display("The tree {} could not be found in the database", oid)
+        }
+        EntriesDecode(err: immutable::object::decode::Error) {
+            display("tree entries could not be decoded.")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// An entry of an in-memory index, holding only what's needed to compare it with a tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The mode of the file, which is never a tree as indices only contain files, symbolic links and submodules.
+    pub mode: EntryMode,
+    /// The id of the file's content, or the commit of a submodule.
+    pub oid: ObjectId,
+    /// The path of the file relative to the root of the repository, using slashes as separators.
+    pub path: BString,
+}
+
+/// Calculate the changes that would need to be applied to `tree` to obtain the files in the `index`, which is what
+/// `git diff --cached` shows as the changes to be committed.
+///
+/// Entries in the `index` must be sorted by path like they are in index files, and should only contain entries
+/// of merged paths, i.e. those at stage 0. No `tree` is treated like the empty tree.
+/// `find(tree_id, &mut buffer)` returns a tree backed by `buffer` to look up the subtrees of `tree`.
+///
+/// As the index has no notion of directories, changes to trees aren't reported and a tree changing into a file
+/// or vice versa is represented as deletion and addition of all files involved.
+pub fn needed_to_obtain<'a, 'tree, Find>(
+    tree: impl Into<Option<immutable::TreeIter<'tree>>>,
+    index: impl IntoIterator<Item = &'a Entry>,
+    mut find: Find,
+) -> Result<Vec<Change>, Error>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<immutable::TreeIter<'b>>,
+{
+    let mut files = Vec::new();
+    if let Some(tree) = tree.into() {
+        flatten(tree, &mut BString::default(), &mut find, &mut files)?;
+    }
+
+    let mut out = Vec::new();
+    let mut files = files.into_iter().peekable();
+    let mut index = index.into_iter().peekable();
+    loop {
+        let ordering = match (files.peek(), index.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(file), Some(entry)) => file.path.cmp(&entry.path),
+        };
+        match ordering {
+            Ordering::Less => {
+                let file = files.next().expect("peeked");
+                out.push(Change::Deletion {
+                    entry_mode: file.mode,
+                    oid: file.oid,
+                    path: file.path,
+                });
+            }
+            Ordering::Greater => {
+                let entry = index.next().expect("peeked");
+                out.push(Change::Addition {
+                    entry_mode: entry.mode,
+                    oid: entry.oid,
+                    path: entry.path.clone(),
+                });
+            }
+            Ordering::Equal => {
+                let file = files.next().expect("peeked");
+                let entry = index.next().expect("peeked");
+                if file.mode != entry.mode || file.oid != entry.oid {
+                    out.push(Change::Modification {
+                        previous_entry_mode: file.mode,
+                        previous_oid: file.oid,
+                        entry_mode: entry.mode,
+                        oid: entry.oid,
+                        path: file.path,
+                    });
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Collect all non-tree entries of `tree` recursively into `out`, in the order of their paths which is the order of
+/// the index as trees sort as if they had a trailing slash.
+fn flatten<Find>(
+    tree: immutable::TreeIter<'_>,
+    path: &mut BString,
+    find: &mut Find,
+    out: &mut Vec<Entry>,
+) -> Result<(), Error>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<immutable::TreeIter<'b>>,
+{
+    let entries = tree
+        .map(|entry| entry.map(|entry| (entry.mode, entry.oid.to_owned(), entry.filename.to_owned())))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut buf = Vec::new();
+    for (mode, oid, filename) in entries {
+        let path_len = path.len();
+        if !path.is_empty() {
+            path.push(b'/');
+        }
+        path.push_str(&filename);
+        if mode.is_tree() {
+            let tree = find(&oid, &mut buf).ok_or(Error::NotFound { oid })?;
+            flatten(tree, path, find, out)?;
+        } else {
+            out.push(Entry {
+                mode,
+                oid,
+                path: path.clone(),
+            });
+        }
+        path.truncate(path_len);
+    }
+    Ok(())
+}
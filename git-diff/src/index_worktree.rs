@@ -0,0 +1,254 @@
+use crate::chunks::Chunks;
+use git_features::{hash, parallel};
+use git_hash::ObjectId;
+use git_object::{
+    bstr::{BString, ByteSlice, ByteVec},
+    tree::EntryMode,
+};
+use quick_error::quick_error;
+use std::{
+    fs, io,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+quick_error! {
+    /// The error returned by [`changes()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Io { path: PathBuf, err: io::Error } {
+            display("Could not read the file at '{}'", path.display())
+            source(err)
+        }
+    }
+}
+
+pub use git_features::fs::{Policy, Stat, Time};
+
+/// An entry of an in-memory index along with the information needed to compare it with its file in the worktree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The mode of the entry, with submodules being ignored.
+    pub mode: EntryMode,
+    /// The id of the entry's content.
+    pub oid: ObjectId,
+    /// The path of the file relative to the root of the worktree, using slashes as separators.
+    pub path: BString,
+    /// The stat information of the file at the time it was added to the index, whose `mode` is ignored in favor of the
+    /// `mode` of the entry.
+    pub stat: Stat,
+}
+
+/// Configure how [`changes()`] compares files with index entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The time at which the index was last modified. Entries whose files were modified at that time or later are
+    /// _racily clean_ as they may have been changed after their stat information was taken without it showing, which is
+    /// why their content is hashed and compared.
+    ///
+    /// If `None`, the stat information of all entries is trusted.
+    pub index_mtime: Option<Time>,
+    /// Decides which stat information to compare, taking into account whether the file system supports executable bits
+    /// and symbolic links, like the configuration of the repository does.
+    pub policy: Policy,
+    /// The amount of threads to use at most for checking files, or all logical cores if `None`. Defaults to `None`.
+    ///
+    /// Note that threads are only used if the `parallel` feature of `git-features` is enabled.
+    pub thread_limit: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            index_mtime: None,
+            policy: Policy::default(),
+            thread_limit: None,
+        }
+    }
+}
+
+/// A difference between an index entry and its file in the worktree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// The file doesn't exist anymore, or a directory is in its place.
+    Deletion {
+        /// The mode of the entry in the index.
+        entry_mode: EntryMode,
+        /// The id of the entry in the index.
+        oid: ObjectId,
+        /// The path of the entry.
+        path: BString,
+    },
+    /// The file's content or type differs from the one in the index.
+    Modification {
+        /// The mode of the entry in the index.
+        previous_entry_mode: EntryMode,
+        /// The id of the entry in the index.
+        previous_oid: ObjectId,
+        /// The mode of the file in the worktree.
+        entry_mode: EntryMode,
+        /// The id of the file's content in the worktree if it had to be hashed to find out whether it changed, or `None`
+        /// if its type or size already showed that it did.
+        oid: Option<ObjectId>,
+        /// The path of the entry.
+        path: BString,
+    },
+}
+
+/// Compare all `entries` of an index with their files in the worktree at `root` and return all files that changed in the
+/// order of the `entries`, which is what `git status` shows as the changes not staged for commit.
+///
+/// Like git does, files whose stat information matches the one in their entry are considered unchanged without reading
+/// them, and files whose size changed are known to be modified. All other files are hashed to compare their content,
+/// which happens if only their timestamps changed or if they are racily clean as determined by `options`.
+///
+/// The files of the `entries` are checked in parallel, but the worktree itself isn't traversed, so files which aren't in
+/// the index are not reported. Submodules are ignored.
+///
+/// Files are hashed with the kind of hash of the id of their entry, and hashing fails for [`git_hash::Kind::Sha256`] as
+/// only Sha1 hashes can be computed so far.
+pub fn changes(root: impl AsRef<Path>, entries: &[Entry], options: Options) -> Result<Vec<Change>, Error> {
+    let root = root.as_ref();
+    let (chunk_size, thread_limit, _) =
+        parallel::optimize_chunk_size_and_thread_limit(100, Some(entries.len()), options.thread_limit, None);
+    parallel::in_parallel(
+        entries.chunks(chunk_size.max(1)).enumerate(),
+        thread_limit,
+        |_| Vec::new(),
+        |(chunk_index, entries), buf| {
+            let mut out = Vec::new();
+            for entry in entries {
+                if let Some(change) = check(root, entry, &options, buf)? {
+                    out.push(change);
+                }
+            }
+            Ok((chunk_index, out))
+        },
+        Chunks::default(),
+    )
+}
+
+fn check(root: &Path, entry: &Entry, options: &Options, buf: &mut Vec<u8>) -> Result<Option<Change>, Error> {
+    if entry.mode == EntryMode::Commit {
+        return Ok(None);
+    }
+    let path = root.join(entry.path.to_path_lossy());
+    let deletion = || Change::Deletion {
+        entry_mode: entry.mode,
+        oid: entry.oid,
+        path: entry.path.clone(),
+    };
+    let meta = match fs::symlink_metadata(&path) {
+        Ok(meta) => meta,
+        Err(err) if err.kind() == io::ErrorKind::NotFound || is_not_a_directory(&err) => return Ok(Some(deletion())),
+        Err(err) => return Err(Error::Io { path, err }),
+    };
+    let mode = match worktree_mode(&meta, entry.mode, options) {
+        Some(mode) => mode,
+        None => return Ok(Some(deletion())),
+    };
+    let modification = |oid| Change::Modification {
+        previous_entry_mode: entry.mode,
+        previous_oid: entry.oid,
+        entry_mode: mode,
+        oid,
+        path: entry.path.clone(),
+    };
+    if mode != entry.mode {
+        return Ok(Some(modification(None)));
+    }
+
+    let recorded = Stat {
+        mode: entry.mode as u32,
+        ..entry.stat
+    };
+    let current = Stat::from_metadata(&meta);
+    if recorded.size != current.size && recorded.size != 0 {
+        return Ok(Some(modification(None)));
+    }
+    let is_racy = options
+        .index_mtime
+        .map_or(false, |index_mtime| recorded.mtime >= index_mtime);
+    if !is_racy && recorded.matches(&current, &options.policy) {
+        return Ok(None);
+    }
+
+    let oid = hash_file(&path, &meta, entry.oid.kind(), buf).map_err(|err| Error::Io { path, err })?;
+    Ok((oid != entry.oid).then(|| modification(Some(oid))))
+}
+
+/// Return the mode of the file in the worktree, or `None` if it is a directory.
+fn worktree_mode(meta: &fs::Metadata, entry_mode: EntryMode, options: &Options) -> Option<EntryMode> {
+    let file_type = meta.file_type();
+    let capabilities = &options.policy.capabilities;
+    Some(if file_type.is_symlink() {
+        EntryMode::Link
+    } else if file_type.is_dir() {
+        return None;
+    } else if entry_mode == EntryMode::Link && !capabilities.symlink {
+        EntryMode::Link
+    } else if !capabilities.executable_bit || !cfg!(unix) {
+        match entry_mode {
+            EntryMode::Blob | EntryMode::BlobExecutable => entry_mode,
+            _ => EntryMode::Blob,
+        }
+    } else if is_executable(meta) {
+        EntryMode::BlobExecutable
+    } else {
+        EntryMode::Blob
+    })
+}
+
+/// Hash the file at `path` like git would hash it as blob with a hash of `kind`, using `buf` to hold its content.
+fn hash_file(path: &Path, meta: &fs::Metadata, kind: git_hash::Kind, buf: &mut Vec<u8>) -> io::Result<ObjectId> {
+    let mut hasher = match kind {
+        git_hash::Kind::Sha1 => hash::Sha1::default(),
+        git_hash::Kind::Sha256 => {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Sha256 hashes cannot be computed yet",
+            ))
+        }
+    };
+    buf.clear();
+    if meta.file_type().is_symlink() {
+        let target = Vec::from_path_buf(fs::read_link(path)?).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "symbolic link targets must be valid unicode on this platform",
+            )
+        })?;
+        buf.extend_from_slice(&target);
+    } else {
+        fs::File::open(path)?.read_to_end(buf)?;
+    }
+    let mut header = [0u8; git_object::loose::header::MAX_LEN];
+    let header_len = git_object::loose::header::encode(git_object::Kind::Blob, buf.len() as u64, &mut header[..])?;
+    hasher.update(&header[..header_len]);
+    hasher.update(buf);
+    Ok(ObjectId::new_sha1(hasher.digest()))
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &fs::Metadata) -> bool {
+    false
+}
+
+/// Return true if a leading component of the path turned into a file, which means the file at the path is gone.
+#[cfg(unix)]
+fn is_not_a_directory(err: &io::Error) -> bool {
+    const ENOTDIR: i32 = 20;
+    err.raw_os_error() == Some(ENOTDIR)
+}
+
+#[cfg(not(unix))]
+fn is_not_a_directory(_err: &io::Error) -> bool {
+    false
+}
@@ -4,3 +4,29 @@
 
 ///
 pub mod tree;
+
+///
+pub mod lines;
+
+///
+pub mod stats;
+
+///
+pub mod textconv;
+
+///
+pub mod patch;
+
+///
+pub mod index;
+
+///
+pub mod index_worktree;
+
+///
+pub mod delta;
+
+///
+pub mod apply;
+
+mod chunks;
@@ -0,0 +1,154 @@
+use crate::lines::{myers, Change, Marks};
+use std::{collections::HashMap, hash::Hash, ops::Range};
+
+/// Lines occurring more often than this are not used to find common regions, like in git.
+const MAX_OCCURRENCES: usize = 64;
+
+/// Compute the changes to turn `before` into `after` using the histogram algorithm as implemented by git, which
+/// prefers matching lines that occur rarely in `before` and thus tends to align moved blocks better than [`myers`].
+///
+/// Regions in which all common lines occur too often fall back to the Myers algorithm.
+pub fn diff<T: Hash + Eq>(before: &[T], after: &[T]) -> Vec<Change> {
+    let mut marks = Marks::new(before.len(), after.len());
+    histogram(before, 0..before.len(), after, 0..after.len(), &mut marks);
+    marks.into_changes()
+}
+
+/// The longest region of common lines found in a range, inclusive on both ends.
+#[derive(Clone, Copy)]
+struct Region {
+    before_start: usize,
+    before_end: usize,
+    after_start: usize,
+    after_end: usize,
+}
+
+fn histogram<T: Hash + Eq>(
+    before: &[T],
+    mut before_range: Range<usize>,
+    after: &[T],
+    mut after_range: Range<usize>,
+    marks: &mut Marks,
+) {
+    loop {
+        if before_range.is_empty() || after_range.is_empty() {
+            marks.change(before_range, after_range);
+            return;
+        }
+        match longest_common_region(before, before_range.clone(), after, after_range.clone()) {
+            Lcs::Found(region) => {
+                histogram(
+                    before,
+                    before_range.start..region.before_start,
+                    after,
+                    after_range.start..region.after_start,
+                    marks,
+                );
+                before_range.start = region.before_end + 1;
+                after_range.start = region.after_end + 1;
+            }
+            Lcs::NoCommonLines => {
+                marks.change(before_range, after_range);
+                return;
+            }
+            Lcs::TooManyOccurrences => {
+                for change in myers::diff(&before[before_range.clone()], &after[after_range.clone()]) {
+                    marks.change(
+                        before_range.start + change.before.start..before_range.start + change.before.end,
+                        after_range.start + change.after.start..after_range.start + change.after.end,
+                    );
+                }
+                return;
+            }
+        }
+    }
+}
+
+enum Lcs {
+    Found(Region),
+    NoCommonLines,
+    TooManyOccurrences,
+}
+
+/// Find the longest region of lines common to both ranges whose lines occur least often in `before`, like `find_lcs()`
+/// in git's `xhistogram.c`.
+fn longest_common_region<T: Hash + Eq>(
+    before: &[T],
+    before_range: Range<usize>,
+    after: &[T],
+    after_range: Range<usize>,
+) -> Lcs {
+    let mut occurrences = HashMap::<&T, Vec<usize>>::new();
+    for (pos, line) in before[before_range.clone()].iter().enumerate() {
+        occurrences.entry(line).or_default().push(before_range.start + pos);
+    }
+    let count = |pos: usize| occurrences[&before[pos]].len();
+
+    let mut lcs: Option<Region> = None;
+    let mut min_count = MAX_OCCURRENCES + 1;
+    let mut has_common = false;
+    let mut after_pos = after_range.start;
+    while after_pos < after_range.end {
+        let mut after_next = after_pos + 1;
+        if let Some(positions) = occurrences.get(&after[after_pos]) {
+            has_common = true;
+            if positions.len() <= min_count {
+                let mut index = 0;
+                loop {
+                    let (mut before_start, mut after_start) = (positions[index], after_pos);
+                    let (mut before_end, mut after_end) = (before_start, after_start);
+                    let mut region_count = positions.len();
+                    while before_range.start < before_start
+                        && after_range.start < after_start
+                        && before[before_start - 1] == after[after_start - 1]
+                    {
+                        before_start -= 1;
+                        after_start -= 1;
+                        if region_count > 1 {
+                            region_count = region_count.min(count(before_start));
+                        }
+                    }
+                    while before_end + 1 < before_range.end
+                        && after_end + 1 < after_range.end
+                        && before[before_end + 1] == after[after_end + 1]
+                    {
+                        before_end += 1;
+                        after_end += 1;
+                        if region_count > 1 {
+                            region_count = region_count.min(count(before_end));
+                        }
+                    }
+
+                    if after_next <= after_end {
+                        after_next = after_end + 1;
+                    }
+                    let lcs_len = lcs.map_or(0, |lcs| lcs.before_end - lcs.before_start);
+                    if lcs_len < before_end - before_start || region_count < min_count {
+                        lcs = Some(Region {
+                            before_start,
+                            before_end,
+                            after_start,
+                            after_end,
+                        });
+                        min_count = region_count;
+                    }
+
+                    index += 1;
+                    while index < positions.len() && positions[index] <= before_end {
+                        index += 1;
+                    }
+                    if index == positions.len() {
+                        break;
+                    }
+                }
+            }
+        }
+        after_pos = after_next;
+    }
+
+    match lcs {
+        _ if has_common && min_count > MAX_OCCURRENCES => Lcs::TooManyOccurrences,
+        Some(region) => Lcs::Found(region),
+        None => Lcs::NoCommonLines,
+    }
+}
@@ -0,0 +1,184 @@
+use crate::lines::{self, Hunk, Line};
+use git_object::bstr::{BStr, ByteSlice};
+use std::ops::Range;
+
+/// Split text into the tokens which are compared when refining changed lines.
+pub trait Tokenizer {
+    /// Append the byte ranges of all tokens in `text` to `out`, in order and without gaps so that they cover all of `text`.
+    fn tokenize(&self, text: &BStr, out: &mut Vec<Range<usize>>);
+}
+
+/// A tokenizer yielding words, runs of whitespace and single punctuation characters, similar to `git diff --word-diff`
+/// but keeping whitespace to allow showing changes to it.
+///
+/// Words consist of ASCII alphanumeric characters, `_` and all non-ASCII bytes, which keeps words of any script together.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Words;
+
+impl Tokenizer for Words {
+    fn tokenize(&self, text: &BStr, out: &mut Vec<Range<usize>>) {
+        #[derive(PartialEq)]
+        enum Class {
+            Word,
+            Whitespace,
+            Punctuation,
+        }
+        let class = |b: u8| {
+            if b.is_ascii_alphanumeric() || b == b'_' || !b.is_ascii() {
+                Class::Word
+            } else if b.is_ascii_whitespace() {
+                Class::Whitespace
+            } else {
+                Class::Punctuation
+            }
+        };
+        let mut start = 0;
+        while start < text.len() {
+            let start_class = class(text[start]);
+            let len = if start_class == Class::Punctuation {
+                1
+            } else {
+                text[start..].iter().take_while(|b| class(**b) == start_class).count()
+            };
+            out.push(start..start + len);
+            start += len;
+        }
+    }
+}
+
+/// A tokenizer yielding every character, with invalid UTF-8 being split into the smallest possible units.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chars;
+
+impl Tokenizer for Chars {
+    fn tokenize(&self, text: &BStr, out: &mut Vec<Range<usize>>) {
+        out.extend(text.char_indices().map(|(start, end, _)| start..end));
+    }
+}
+
+/// A tokenizer yielding every grapheme cluster, which are the characters as perceived by users.
+#[cfg(feature = "unicode")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Graphemes;
+
+#[cfg(feature = "unicode")]
+impl Tokenizer for Graphemes {
+    fn tokenize(&self, text: &BStr, out: &mut Vec<Range<usize>>) {
+        out.extend(text.grapheme_indices().map(|(start, end, _)| start..end));
+    }
+}
+
+/// A region of bytes that differs between the `before` and `after` version of a text, as produced by [`diff()`].
+///
+/// Empty ranges indicate a pure addition or removal, with the range start marking the position at which the bytes were
+/// added or removed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Change {
+    /// The range of bytes in the `before` version.
+    pub before: Range<usize>,
+    /// The range of bytes in the `after` version.
+    pub after: Range<usize>,
+}
+
+/// Diff the tokens of `before` and `after` as produced by `tokenizer` and return the changed byte ranges.
+pub fn diff(before: &[u8], after: &[u8], tokenizer: &impl Tokenizer) -> Vec<Change> {
+    let mut before_tokens = Vec::new();
+    tokenizer.tokenize(before.as_bstr(), &mut before_tokens);
+    let mut after_tokens = Vec::new();
+    tokenizer.tokenize(after.as_bstr(), &mut after_tokens);
+
+    let (before_ids, after_ids) = lines::intern(&slices(before, &before_tokens), &slices(after, &after_tokens));
+    let bytes = |tokens: &[Range<usize>], range: Range<usize>, len: usize| {
+        let start = tokens.get(range.start).map_or(len, |token| token.start);
+        let end = if range.is_empty() {
+            start
+        } else {
+            tokens[range.end - 1].end
+        };
+        start..end
+    };
+    lines::myers::diff(&before_ids, &after_ids)
+        .into_iter()
+        .map(|change| Change {
+            before: bytes(&before_tokens, change.before, before.len()),
+            after: bytes(&after_tokens, change.after, after.len()),
+        })
+        .collect()
+}
+
+/// A [line][Line] of a [hunk][Hunk] along with the byte ranges within it that changed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RefinedLine<'a> {
+    /// The line as it is in the hunk.
+    pub line: Line<'a>,
+    /// The non-empty, ordered ranges of bytes within the line which changed, which are always empty for context lines.
+    pub changes: Vec<Range<usize>>,
+}
+
+/// Refine all lines of `hunk` by diffing each run of removed lines with the run of added lines following it token by
+/// token, as produced by `tokenizer`, and return them along with the byte ranges within each line that changed.
+///
+/// Removed lines that aren't followed by added lines, and vice versa, are changed entirely.
+pub fn hunk<'a>(hunk: &Hunk<'a>, tokenizer: &impl Tokenizer) -> Vec<RefinedLine<'a>> {
+    let mut out: Vec<_> = hunk
+        .lines
+        .iter()
+        .map(|line| RefinedLine {
+            line: *line,
+            changes: Vec::new(),
+        })
+        .collect();
+    let mut start = 0;
+    while start < out.len() {
+        let removed = count(&out[start..], |line| matches!(line, Line::Removed(_)));
+        let added = count(&out[start + removed..], |line| matches!(line, Line::Added(_)));
+        if removed + added == 0 {
+            start += 1;
+            continue;
+        }
+        let (before, before_lines) = concat(&out[start..][..removed]);
+        let (after, after_lines) = concat(&out[start + removed..][..added]);
+        for change in diff(&before, &after, tokenizer) {
+            distribute(change.before, &before_lines, &mut out[start..][..removed]);
+            distribute(change.after, &after_lines, &mut out[start + removed..][..added]);
+        }
+        start += removed + added;
+    }
+    out
+}
+
+fn slices<'a>(text: &'a [u8], tokens: &[Range<usize>]) -> Vec<&'a BStr> {
+    tokens.iter().map(|token| text[token.clone()].as_bstr()).collect()
+}
+
+fn count(lines: &[RefinedLine<'_>], predicate: impl Fn(&Line<'_>) -> bool) -> usize {
+    lines.iter().take_while(|refined| predicate(&refined.line)).count()
+}
+
+/// Return the data of all `lines` as a single text along with the range each line takes in it.
+fn concat(lines: &[RefinedLine<'_>]) -> (Vec<u8>, Vec<Range<usize>>) {
+    let mut text = Vec::new();
+    let mut ranges = Vec::with_capacity(lines.len());
+    for refined in lines {
+        let start = text.len();
+        text.extend_from_slice(refined.line.data());
+        ranges.push(start..text.len());
+    }
+    (text, ranges)
+}
+
+/// Add the parts of the `changed` range of bytes within the concatenation of `lines` to the lines they belong to.
+fn distribute(changed: Range<usize>, line_ranges: &[Range<usize>], lines: &mut [RefinedLine<'_>]) {
+    for (range, refined) in line_ranges.iter().zip(lines) {
+        let start = changed.start.max(range.start);
+        let end = changed.end.min(range.end);
+        if start >= end {
+            continue;
+        }
+        let change = start - range.start..end - range.start;
+        match refined.changes.last_mut() {
+            Some(last) if last.end == change.start => last.end = change.end,
+            _ => refined.changes.push(change),
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use git_object::bstr::{BStr, ByteSlice};
+use std::{borrow::Cow, collections::HashMap, hash::Hash, ops::Range};
+
+///
+pub mod myers;
+
+///
+pub mod histogram;
+
+///
+pub mod patience;
+
+///
+pub mod intraline;
+
+/// A region of lines that differs between the `before` and `after` version of a text, as produced by [`Algorithm::diff()`].
+///
+/// Empty ranges indicate a pure addition or removal of lines, with the range start marking the position at which
+/// the lines were added or removed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Change {
+    /// The range of lines in the `before` version.
+    pub before: Range<usize>,
+    /// The range of lines in the `after` version.
+    pub after: Range<usize>,
+}
+
+/// A single line within a [`Hunk`], including its line terminator if there was one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Line<'a> {
+    /// A line that is the same in both versions, as it is in the `after` version.
+    Context(&'a BStr),
+    /// A line that only exists in the `before` version.
+    Removed(&'a BStr),
+    /// A line that only exists in the `after` version.
+    Added(&'a BStr),
+}
+
+impl<'a> Line<'a> {
+    /// Return the line's content, including its line terminator if there was one.
+    pub fn data(&self) -> &'a BStr {
+        match self {
+            Line::Context(data) | Line::Removed(data) | Line::Added(data) => data,
+        }
+    }
+}
+
+/// A group of nearby [changes][Change] along with the lines surrounding them for context, like the sections of a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hunk<'a> {
+    /// The range of lines covered by this hunk in the `before` version, zero-based.
+    pub before: Range<usize>,
+    /// The range of lines covered by this hunk in the `after` version, zero-based.
+    pub after: Range<usize>,
+    /// All lines of the hunk in order, with removed lines preceding the added ones in each change.
+    pub lines: Vec<Line<'a>>,
+}
+
+/// The algorithm used to find the changes between two versions of a text, matching the ones git offers with
+/// `--diff-algorithm`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// The Myers algorithm, producing a minimal amount of changes. This is git's default.
+    Myers,
+    /// The histogram algorithm, which aligns lines occurring rarely first and often produces more readable diffs for
+    /// moved code.
+    Histogram,
+    /// The patience algorithm, which aligns lines that are unique in both versions first.
+    Patience,
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::Myers
+    }
+}
+
+impl Algorithm {
+    /// Compute the changes to turn `before` into `after` with this algorithm, ordered by their position.
+    pub fn diff<T: Hash + Eq>(&self, before: &[T], after: &[T]) -> Vec<Change> {
+        match self {
+            Algorithm::Myers => myers::diff(before, after),
+            Algorithm::Histogram => histogram::diff(before, after),
+            Algorithm::Patience => patience::diff(before, after),
+        }
+    }
+}
+
+/// Options to ignore changes to whitespace like the respective flags of `git diff`, all of which are disabled by default.
+///
+/// Lines are compared after [normalizing][Whitespace::normalize()] them, but hunks still show them as they are.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Whitespace {
+    /// Ignore all whitespace when comparing lines, like `-w`. This takes precedence over all other normalizations.
+    pub ignore_all_space: bool,
+    /// Ignore changes in the amount of whitespace and whitespace at the end of lines, like `-b`.
+    pub ignore_space_change: bool,
+    /// Ignore a carriage return at the end of lines, like `--ignore-cr-at-eol`.
+    pub ignore_cr_at_eol: bool,
+    /// Ignore changes whose lines are all blank unless they are within the context of other changes, like
+    /// `--ignore-blank-lines`.
+    pub ignore_blank_lines: bool,
+}
+
+impl Whitespace {
+    /// Return `line` in the form it is compared in, which is `line` itself if no normalization is enabled.
+    pub fn normalize<'a>(&self, line: &'a BStr) -> Cow<'a, BStr> {
+        if self.ignore_all_space {
+            Cow::Owned(
+                line.iter()
+                    .copied()
+                    .filter(|b| !is_space(*b))
+                    .collect::<Vec<_>>()
+                    .into(),
+            )
+        } else if self.ignore_space_change {
+            let mut out = Vec::with_capacity(line.len());
+            let mut bytes = line.iter().copied().peekable();
+            while let Some(b) = bytes.next() {
+                if !is_space(b) {
+                    out.push(b);
+                    continue;
+                }
+                while bytes.peek().map_or(false, |b| is_space(*b)) {
+                    bytes.next();
+                }
+                if bytes.peek().is_some() {
+                    out.push(b' ');
+                }
+            }
+            Cow::Owned(out.into())
+        } else if self.ignore_cr_at_eol {
+            let line = line.strip_suffix(b"\n").unwrap_or(line);
+            Cow::Borrowed(line.strip_suffix(b"\r").unwrap_or(line).as_bstr())
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+
+    /// Return true if `line` is considered blank, which means it consists of whitespace only if any whitespace is
+    /// ignored, or is empty apart from its line terminator otherwise.
+    pub fn is_blank(&self, line: &BStr) -> bool {
+        if self.normalizes() {
+            line.iter().copied().all(is_space)
+        } else {
+            line.is_empty() || line == "\n"
+        }
+    }
+
+    fn normalizes(&self) -> bool {
+        self.ignore_all_space || self.ignore_space_change || self.ignore_cr_at_eol
+    }
+}
+
+/// Whitespace as understood by git, which unlike [`u8::is_ascii_whitespace()`] includes the vertical tab.
+fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c)
+}
+
+/// Options to configure how [`diff()`] is performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The amount of unchanged lines to show before and after each change, with changes closer to each other than twice
+    /// that amount being merged into the same hunk.
+    pub context_lines: usize,
+    /// The algorithm to use for finding changed lines.
+    pub algorithm: Algorithm,
+    /// The changes to whitespace to ignore.
+    pub whitespace: Whitespace,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            context_lines: 3,
+            algorithm: Algorithm::default(),
+            whitespace: Whitespace::default(),
+        }
+    }
+}
+
+/// Split `data` into lines while keeping their line terminators, like git does when diffing.
+///
+/// This way the last line is different from the same line with a trailing newline.
+pub fn split(data: &[u8]) -> Vec<&BStr> {
+    data.lines_with_terminator().map(|line| line.as_bstr()).collect()
+}
+
+/// Map each of the given lines to an integer which is the same for equal lines, to allow them to be compared cheaply.
+///
+/// The returned tokens for `before` and `after` are suitable for use with [`Algorithm::diff()`].
+pub fn intern<'a>(before: &[&'a BStr], after: &[&'a BStr]) -> (Vec<u32>, Vec<u32>) {
+    let mut ids = HashMap::<&'a BStr, u32>::with_capacity(before.len() + after.len());
+    let mut tokens = |lines: &[&'a BStr]| {
+        lines
+            .iter()
+            .map(|line| {
+                let next_id = ids.len() as u32;
+                *ids.entry(line).or_insert(next_id)
+            })
+            .collect::<Vec<_>>()
+    };
+    let before = tokens(before);
+    let after = tokens(after);
+    (before, after)
+}
+
+/// Like [`intern()`], but map lines which are equal after [normalizing][Whitespace::normalize()] them according to
+/// `whitespace` to the same integer.
+pub fn intern_normalized<'a>(before: &[&'a BStr], after: &[&'a BStr], whitespace: &Whitespace) -> (Vec<u32>, Vec<u32>) {
+    if !whitespace.normalizes() {
+        return intern(before, after);
+    }
+    let mut ids = HashMap::<Cow<'a, BStr>, u32>::with_capacity(before.len() + after.len());
+    let mut tokens = |lines: &[&'a BStr]| {
+        lines
+            .iter()
+            .map(|line| {
+                let next_id = ids.len() as u32;
+                *ids.entry(whitespace.normalize(line)).or_insert(next_id)
+            })
+            .collect::<Vec<_>>()
+    };
+    let before = tokens(before);
+    let after = tokens(after);
+    (before, after)
+}
+
+/// Compute the changes between the `before` and `after` lines using the algorithm and whitespace handling of `options`.
+pub fn changes(before: &[&BStr], after: &[&BStr], options: Options) -> Vec<Change> {
+    let (before_tokens, after_tokens) = intern_normalized(before, after, &options.whitespace);
+    let changes = options.algorithm.diff(&before_tokens, &after_tokens);
+    if !options.whitespace.ignore_blank_lines {
+        return changes;
+    }
+
+    let blank = |change: &Change| {
+        let is_blank = |line: &&BStr| options.whitespace.is_blank(line);
+        before[change.before.clone()].iter().all(is_blank) && after[change.after.clone()].iter().all(is_blank)
+    };
+    let ignorable: Vec<_> = changes.iter().map(blank).collect();
+    let near = |previous: &Change, next: &Change| next.before.start - previous.before.end < options.context_lines;
+    let mut keep: Vec<_> = ignorable.iter().map(|ignorable| !ignorable).collect();
+    for index in 1..changes.len() {
+        if ignorable[index] && keep[index - 1] && near(&changes[index - 1], &changes[index]) {
+            keep[index] = true;
+        }
+    }
+    for index in (1..changes.len()).rev() {
+        if ignorable[index - 1] && keep[index] && near(&changes[index - 1], &changes[index]) {
+            keep[index - 1] = true;
+        }
+    }
+    changes
+        .into_iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|(change, _)| change)
+        .collect()
+}
+
+/// Diff the lines of the `before` and `after` version of a text and return the [hunks][Hunk] describing the
+/// differences according to `options`.
+///
+/// No hunks are returned if both versions are the same.
+pub fn diff<'a>(before: &'a [u8], after: &'a [u8], options: Options) -> Vec<Hunk<'a>> {
+    let before = split(before);
+    let after = split(after);
+    let changes = changes(&before, &after, options);
+    hunks(&before, &after, &changes, options.context_lines)
+}
+
+/// Group the given `changes` between the `before` and `after` lines into hunks, with `context_lines` of unchanged lines
+/// around each change.
+///
+/// Like in git, context lines are taken from `after` in case they differ in ignored whitespace.
+pub fn hunks<'a>(before: &[&'a BStr], after: &[&'a BStr], changes: &[Change], context_lines: usize) -> Vec<Hunk<'a>> {
+    let mut out = Vec::new();
+    let mut changes = changes.iter().peekable();
+    while let Some(first) = changes.next() {
+        let context = first.before.start.min(context_lines);
+        let mut hunk = Hunk {
+            before: first.before.start - context..first.before.start - context,
+            after: first.after.start - context..first.after.start - context,
+            lines: Vec::new(),
+        };
+        let mut change = first;
+        loop {
+            hunk.lines.extend(
+                after[hunk.after.end..change.after.start]
+                    .iter()
+                    .copied()
+                    .map(Line::Context),
+            );
+            hunk.lines
+                .extend(before[change.before.clone()].iter().copied().map(Line::Removed));
+            hunk.lines
+                .extend(after[change.after.clone()].iter().copied().map(Line::Added));
+            hunk.before.end = change.before.end;
+            hunk.after.end = change.after.end;
+
+            match changes.peek() {
+                Some(next) if next.before.start - change.before.end <= 2 * context_lines => {
+                    change = changes.next().expect("peeked");
+                }
+                _ => break,
+            }
+        }
+        let end = (hunk.before.end + context_lines).min(before.len());
+        let after_end = hunk.after.end + end - hunk.before.end;
+        hunk.lines
+            .extend(after[hunk.after.end..after_end].iter().copied().map(Line::Context));
+        hunk.after.end = after_end;
+        hunk.before.end = end;
+        out.push(hunk);
+    }
+    out
+}
+
+/// The changed lines of both versions of a text, for algorithms that determine them piecewise.
+pub(crate) struct Marks {
+    before: Vec<bool>,
+    after: Vec<bool>,
+}
+
+impl Marks {
+    pub(crate) fn new(before_len: usize, after_len: usize) -> Self {
+        Marks {
+            before: vec![false; before_len],
+            after: vec![false; after_len],
+        }
+    }
+
+    pub(crate) fn change(&mut self, before: Range<usize>, after: Range<usize>) {
+        self.before[before].iter_mut().for_each(|changed| *changed = true);
+        self.after[after].iter_mut().for_each(|changed| *changed = true);
+    }
+
+    /// Turn the marks into changes, pairing up unchanged lines of both versions in order.
+    pub(crate) fn into_changes(self) -> Vec<Change> {
+        let mut out = Vec::new();
+        let (mut before_pos, mut after_pos) = (0, 0);
+        loop {
+            let before_end = before_pos + self.before[before_pos..].iter().take_while(|changed| **changed).count();
+            let after_end = after_pos + self.after[after_pos..].iter().take_while(|changed| **changed).count();
+            if before_end > before_pos || after_end > after_pos {
+                out.push(Change {
+                    before: before_pos..before_end,
+                    after: after_pos..after_end,
+                });
+            }
+            if before_end == self.before.len() || after_end == self.after.len() {
+                debug_assert!(before_end == self.before.len() && after_end == self.after.len());
+                break;
+            }
+            before_pos = before_end + 1;
+            after_pos = after_end + 1;
+        }
+        out
+    }
+}
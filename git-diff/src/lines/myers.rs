@@ -0,0 +1,189 @@
+use crate::lines::Change;
+use std::ops::{Index, IndexMut, Range};
+
+/// Compute the shortest edit script to turn `before` into `after` using the linear-space variant of the
+/// [Myers algorithm](http://www.xmailserver.org/diff2.pdf) and return it as ordered list of [changes][Change].
+///
+/// Tokens are compared by equality only, which is why it's beneficial to intern lines as integers beforehand.
+pub fn diff<T: PartialEq>(before: &[T], after: &[T]) -> Vec<Change> {
+    let max_d = (before.len() + after.len() + 1) / 2 + 1;
+    let mut forward = V::new(max_d);
+    let mut backward = V::new(max_d);
+    let mut out = Vec::new();
+    conquer(
+        before,
+        0..before.len(),
+        after,
+        0..after.len(),
+        &mut forward,
+        &mut backward,
+        &mut out,
+    );
+    out
+}
+
+/// Diagonals `k` of the edit graph, mapped to the furthest reaching `x` coordinate on them.
+struct V {
+    offset: isize,
+    v: Vec<usize>,
+}
+
+impl V {
+    fn new(max_d: usize) -> Self {
+        V {
+            offset: max_d as isize,
+            v: vec![0; 2 * max_d + 1],
+        }
+    }
+}
+
+impl Index<isize> for V {
+    type Output = usize;
+
+    fn index(&self, k: isize) -> &Self::Output {
+        &self.v[(k + self.offset) as usize]
+    }
+}
+
+impl IndexMut<isize> for V {
+    fn index_mut(&mut self, k: isize) -> &mut Self::Output {
+        &mut self.v[(k + self.offset) as usize]
+    }
+}
+
+fn common_prefix_len<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+fn common_suffix_len<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter().rev().zip(b.iter().rev()).take_while(|(a, b)| a == b).count()
+}
+
+/// Find the middle snake of an optimal path through the edit graph of the given, non-empty ranges and return the position
+/// at which to split the problem into two smaller ones.
+fn middle_snake<T: PartialEq>(
+    before: &[T],
+    before_range: Range<usize>,
+    after: &[T],
+    after_range: Range<usize>,
+    forward: &mut V,
+    backward: &mut V,
+) -> Option<(usize, usize)> {
+    let n = before_range.len();
+    let m = after_range.len();
+    let delta = n as isize - m as isize;
+    let odd = delta & 1 == 1;
+    forward[1] = 0;
+    backward[1] = 0;
+    let max_d = ((n + m + 1) / 2 + 1) as isize;
+    for d in 0..max_d {
+        let mut k = d;
+        while k >= -d {
+            let mut x = if k == -d || (k != d && forward[k - 1] < forward[k + 1]) {
+                forward[k + 1]
+            } else {
+                forward[k - 1] + 1
+            };
+            let y = (x as isize - k) as usize;
+            let (x0, y0) = (x, y);
+            if x < n && y < m {
+                x += common_prefix_len(
+                    &before[before_range.start + x..before_range.end],
+                    &after[after_range.start + y..after_range.end],
+                );
+            }
+            forward[k] = x;
+            if odd && (k - delta).abs() < d && forward[k] + backward[delta - k] >= n {
+                return Some((before_range.start + x0, after_range.start + y0));
+            }
+            k -= 2;
+        }
+
+        let mut k = d;
+        while k >= -d {
+            let mut x = if k == -d || (k != d && backward[k - 1] < backward[k + 1]) {
+                backward[k + 1]
+            } else {
+                backward[k - 1] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            if x < n && y < m {
+                let advance = common_suffix_len(
+                    &before[before_range.start..before_range.start + n - x],
+                    &after[after_range.start..after_range.start + m - y],
+                );
+                x += advance;
+                y += advance;
+            }
+            backward[k] = x;
+            if !odd && (k - delta).abs() <= d && backward[k] + forward[delta - k] >= n {
+                return Some((before_range.start + n - x, after_range.start + m - y));
+            }
+            k -= 2;
+        }
+    }
+    None
+}
+
+fn conquer<T: PartialEq>(
+    before: &[T],
+    mut before_range: Range<usize>,
+    after: &[T],
+    mut after_range: Range<usize>,
+    forward: &mut V,
+    backward: &mut V,
+    out: &mut Vec<Change>,
+) {
+    let prefix = common_prefix_len(&before[before_range.clone()], &after[after_range.clone()]);
+    before_range.start += prefix;
+    after_range.start += prefix;
+    let suffix = common_suffix_len(&before[before_range.clone()], &after[after_range.clone()]);
+    before_range.end -= suffix;
+    after_range.end -= suffix;
+
+    if before_range.is_empty() && after_range.is_empty() {
+        return;
+    }
+    if !before_range.is_empty() && !after_range.is_empty() {
+        if let Some((x, y)) = middle_snake(
+            before,
+            before_range.clone(),
+            after,
+            after_range.clone(),
+            forward,
+            backward,
+        ) {
+            conquer(
+                before,
+                before_range.start..x,
+                after,
+                after_range.start..y,
+                forward,
+                backward,
+                out,
+            );
+            conquer(
+                before,
+                x..before_range.end,
+                after,
+                y..after_range.end,
+                forward,
+                backward,
+                out,
+            );
+            return;
+        }
+    }
+    push(out, before_range, after_range);
+}
+
+/// Add the given change, merging it with the previous one if they are adjacent.
+fn push(out: &mut Vec<Change>, before: Range<usize>, after: Range<usize>) {
+    match out.last_mut() {
+        Some(last) if last.before.end == before.start && last.after.end == after.start => {
+            last.before.end = before.end;
+            last.after.end = after.end;
+        }
+        _ => out.push(Change { before, after }),
+    }
+}
@@ -0,0 +1,155 @@
+use crate::lines::{myers, Change, Marks};
+use std::{collections::HashMap, hash::Hash, ops::Range};
+
+/// Compute the changes to turn `before` into `after` using the patience algorithm as implemented by git, which aligns
+/// lines that are unique in both versions first and diffs the regions between them recursively.
+///
+/// Regions without unique common lines fall back to the Myers algorithm.
+pub fn diff<T: Hash + Eq>(before: &[T], after: &[T]) -> Vec<Change> {
+    let mut marks = Marks::new(before.len(), after.len());
+    patience(before, 0..before.len(), after, 0..after.len(), &mut marks);
+    marks.into_changes()
+}
+
+/// A distinct line of the `before` range along with the position of the same line in the `after` range.
+struct Entry {
+    /// The position of the first occurrence in `before`.
+    before: usize,
+    /// The position of the first occurrence in `after`, if any.
+    after: Option<usize>,
+    /// False if the line occurs more than once in either range.
+    unique: bool,
+}
+
+impl Entry {
+    fn unique_position(&self) -> Option<(usize, usize)> {
+        self.after.filter(|_| self.unique).map(|after| (self.before, after))
+    }
+}
+
+fn patience<T: Hash + Eq>(
+    before: &[T],
+    before_range: Range<usize>,
+    after: &[T],
+    after_range: Range<usize>,
+    marks: &mut Marks,
+) {
+    if before_range.is_empty() || after_range.is_empty() {
+        marks.change(before_range, after_range);
+        return;
+    }
+
+    let mut index = HashMap::<&T, usize>::new();
+    let mut entries = Vec::<Entry>::new();
+    for pos in before_range.clone() {
+        match index.get(&before[pos]) {
+            Some(&entry) => entries[entry].unique = false,
+            None => {
+                index.insert(&before[pos], entries.len());
+                entries.push(Entry {
+                    before: pos,
+                    after: None,
+                    unique: true,
+                });
+            }
+        }
+    }
+    let mut has_matches = false;
+    for pos in after_range.clone() {
+        if let Some(&entry) = index.get(&after[pos]) {
+            has_matches = true;
+            let entry = &mut entries[entry];
+            match entry.after {
+                Some(_) => entry.unique = false,
+                None => entry.after = Some(pos),
+            }
+        }
+    }
+    if !has_matches {
+        marks.change(before_range, after_range);
+        return;
+    }
+
+    let anchors = longest_increasing_sequence(&entries);
+    if anchors.is_empty() {
+        for change in myers::diff(&before[before_range.clone()], &after[after_range.clone()]) {
+            marks.change(
+                before_range.start + change.before.start..before_range.start + change.before.end,
+                after_range.start + change.after.start..after_range.start + change.after.end,
+            );
+        }
+        return;
+    }
+
+    let (mut before_pos, mut after_pos) = (before_range.start, after_range.start);
+    let mut anchors = anchors.into_iter().peekable();
+    loop {
+        let (before_next, after_next) = match anchors.peek() {
+            Some(&(before_anchor, after_anchor)) => {
+                let (mut before_next, mut after_next) = (before_anchor, after_anchor);
+                while before_next > before_pos
+                    && after_next > after_pos
+                    && before[before_next - 1] == after[after_next - 1]
+                {
+                    before_next -= 1;
+                    after_next -= 1;
+                }
+                (before_next, after_next)
+            }
+            None => (before_range.end, after_range.end),
+        };
+        while before_pos < before_next && after_pos < after_next && before[before_pos] == after[after_pos] {
+            before_pos += 1;
+            after_pos += 1;
+        }
+        if before_next > before_pos || after_next > after_pos {
+            patience(before, before_pos..before_next, after, after_pos..after_next, marks);
+        }
+
+        let (mut before_anchor, mut after_anchor) = match anchors.next() {
+            Some(anchor) => anchor,
+            None => return,
+        };
+        while let Some(&(before_following, after_following)) = anchors.peek() {
+            if before_following != before_anchor + 1 || after_following != after_anchor + 1 {
+                break;
+            }
+            before_anchor = before_following;
+            after_anchor = after_following;
+            anchors.next();
+        }
+        before_pos = before_anchor + 1;
+        after_pos = after_anchor + 1;
+    }
+}
+
+/// Return the longest sequence of lines unique to both ranges whose positions increase in both, in order, using patience
+/// sorting like `find_longest_common_sequence()` in git's `xpatience.c`.
+fn longest_increasing_sequence(entries: &[Entry]) -> Vec<(usize, usize)> {
+    let mut tops = Vec::<usize>::new();
+    let mut previous = vec![None; entries.len()];
+    for (index, entry) in entries.iter().enumerate() {
+        let after = match entry.unique_position() {
+            Some((_, after)) => after,
+            None => continue,
+        };
+        let pile = tops
+            .binary_search_by_key(&after, |&top| entries[top].after.expect("only unique lines"))
+            .unwrap_or_else(|pile| pile);
+        previous[index] = pile.checked_sub(1).map(|pile| tops[pile]);
+        if pile == tops.len() {
+            tops.push(index);
+        } else {
+            tops[pile] = index;
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut next = tops.last().copied();
+    while let Some(index) = next {
+        out.push(entries[index].unique_position().expect("only unique lines"));
+        next = previous[index];
+    }
+    out.reverse();
+    out
+}
@@ -0,0 +1,309 @@
+use crate::{
+    lines,
+    stats::{self, Side},
+    textconv,
+    tree::recorder::Change,
+};
+use git_hash::{oid, ObjectId};
+use git_object::bstr::{BStr, ByteSlice};
+use quick_error::quick_error;
+use std::io;
+
+quick_error! {
+    /// The error returned by [`write()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        NotFound { oid: ObjectId } {
+            display("The blob {} could not be found", oid)
+        }
+        Io(err: io::Error) {
+            display("The patch could not be written")
+            from()
+            source(err)
+        }
+        Textconv(err: textconv::Error) {
+            display("A file could not be converted to text")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// Configure how [`write()`] formats patches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The amount of unchanged lines around each change. Defaults to 3, like git.
+    pub context_lines: usize,
+    /// The amount of hexadecimal characters of object ids shown in `index` lines. Defaults to 7.
+    pub abbrev: usize,
+    /// The algorithm to find changed lines with. Defaults to Myers, like git.
+    pub algorithm: lines::Algorithm,
+    /// The changes to whitespace to ignore, with files whose changes are all ignored being omitted entirely like in git.
+    /// Defaults to ignoring nothing.
+    pub whitespace: lines::Whitespace,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            context_lines: 3,
+            abbrev: 7,
+            algorithm: lines::Algorithm::default(),
+            whitespace: lines::Whitespace::default(),
+        }
+    }
+}
+
+/// Write all `changes` as unified diff with git's extended headers into `out`, as configured by `options`, to produce
+/// the same output as `git diff` which can be applied with `git apply`.
+///
+/// `find(object_id, &mut buffer)` returns the data of a blob backed by `buffer`. Changes to directories are skipped
+/// as the changes of the files they contain are expected to be present, and binary files are only mentioned to differ.
+pub fn write<'a, Find>(
+    out: impl io::Write,
+    changes: impl IntoIterator<Item = &'a Change>,
+    options: Options,
+    find: Find,
+) -> Result<(), Error>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<&'b [u8]>,
+{
+    write_with_drivers(out, changes, options, &mut textconv::Drivers::default(), find)
+}
+
+/// Like [`write()`], but convert files with the matching driver in `drivers` before diffing them, like git does with
+/// `textconv` drivers.
+///
+/// Converted files are always considered text, and the hunks show their converted lines which is why the patch can't be
+/// applied anymore.
+pub fn write_with_drivers<'a, Find>(
+    mut out: impl io::Write,
+    changes: impl IntoIterator<Item = &'a Change>,
+    options: Options,
+    drivers: &mut textconv::Drivers,
+    mut find: Find,
+) -> Result<(), Error>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<&'b [u8]>,
+{
+    let mut before_buf = Vec::new();
+    let mut after_buf = Vec::new();
+    let mut before_converted = Vec::new();
+    let mut after_converted = Vec::new();
+    for change in changes {
+        let (before, after, path, source_path) = stats::sides(change);
+        let before = before.filter(|(mode, _)| mode.is_no_tree());
+        let after = after.filter(|(mode, _)| mode.is_no_tree());
+        if before.is_none() && after.is_none() {
+            continue;
+        }
+        let source_path = source_path.unwrap_or(path).as_bstr();
+        let path = path.as_bstr();
+        let id = |side: Side<'_>| side.map_or_else(ObjectId::null_sha1, |(_, id)| *id);
+        let (before_id, after_id) = (id(before), id(after));
+
+        before_buf.clear();
+        after_buf.clear();
+        let content = if before_id == after_id {
+            None
+        } else {
+            let before_data =
+                stats::load(before, &mut before_buf, &mut find).ok_or(Error::NotFound { oid: before_id })?;
+            let after_data = stats::load(after, &mut after_buf, &mut find).ok_or(Error::NotFound { oid: after_id })?;
+            let (before_data, before_is_text) =
+                stats::convert(before, source_path, before_data, &mut before_converted, drivers)?;
+            let (after_data, after_is_text) = stats::convert(after, path, after_data, &mut after_converted, drivers)?;
+            if (!before_is_text && git_object::blob::is_binary(before_data))
+                || (!after_is_text && git_object::blob::is_binary(after_data))
+            {
+                Some(Content::Binary)
+            } else {
+                let before_lines = lines::split(before_data);
+                let after_lines = lines::split(after_data);
+                let changes = lines::changes(
+                    &before_lines,
+                    &after_lines,
+                    lines::Options {
+                        context_lines: options.context_lines,
+                        algorithm: options.algorithm,
+                        whitespace: options.whitespace,
+                    },
+                );
+                let hunks = lines::hunks(&before_lines, &after_lines, &changes, options.context_lines);
+                Some(Content::Text { before_lines, hunks })
+            }
+        };
+        let only_ignored_changes = matches!(change, Change::Modification { previous_entry_mode, entry_mode, .. } if previous_entry_mode == entry_mode)
+            && matches!(&content, Some(Content::Text { hunks, .. }) if hunks.is_empty());
+        if only_ignored_changes {
+            continue;
+        }
+
+        out.write_all(b"diff --git ")?;
+        write_path(&mut out, "a/", source_path)?;
+        out.write_all(b" ")?;
+        write_path(&mut out, "b/", path)?;
+        out.write_all(b"\n")?;
+        match (before, after) {
+            (None, Some((mode, _))) => writeln!(out, "new file mode {:06o}", mode as u16)?,
+            (Some((mode, _)), None) => writeln!(out, "deleted file mode {:06o}", mode as u16)?,
+            (Some((previous_mode, _)), Some((mode, _))) if previous_mode != mode => {
+                writeln!(out, "old mode {:06o}", previous_mode as u16)?;
+                writeln!(out, "new mode {:06o}", mode as u16)?;
+            }
+            _ => {}
+        }
+        match change {
+            Change::Rename { similarity, .. } | Change::Copy { similarity, .. } => {
+                let kind = if matches!(change, Change::Rename { .. }) {
+                    "rename"
+                } else {
+                    "copy"
+                };
+                writeln!(out, "similarity index {}%", similarity)?;
+                write!(out, "{} from ", kind)?;
+                write_path(&mut out, "", source_path)?;
+                write!(out, "\n{} to ", kind)?;
+                write_path(&mut out, "", path)?;
+                out.write_all(b"\n")?;
+            }
+            _ => {}
+        }
+        let content = match content {
+            Some(content) => content,
+            None => continue,
+        };
+        write!(
+            out,
+            "index {}..{}",
+            abbreviate(&before_id, options.abbrev),
+            abbreviate(&after_id, options.abbrev)
+        )?;
+        match (before, after) {
+            (Some((previous_mode, _)), Some((mode, _))) if previous_mode == mode => {
+                writeln!(out, " {:06o}", mode as u16)?
+            }
+            _ => out.write_all(b"\n")?,
+        }
+
+        let before_path = before.map(|_| ("a/", source_path));
+        let after_path = after.map(|_| ("b/", path));
+        let (before_lines, hunks) = match content {
+            Content::Text { before_lines, hunks } => (before_lines, hunks),
+            Content::Binary => {
+                out.write_all(b"Binary files ")?;
+                write_optional_path(&mut out, before_path)?;
+                out.write_all(b" and ")?;
+                write_optional_path(&mut out, after_path)?;
+                out.write_all(b" differ\n")?;
+                continue;
+            }
+        };
+        if hunks.is_empty() {
+            continue;
+        }
+        out.write_all(b"--- ")?;
+        write_optional_path(&mut out, before_path)?;
+        out.write_all(b"\n+++ ")?;
+        write_optional_path(&mut out, after_path)?;
+        out.write_all(b"\n")?;
+        for hunk in hunks {
+            write!(out, "@@ -{} +{} @@", Range(&hunk.before), Range(&hunk.after))?;
+            if let Some(function) = function_name(&before_lines[..hunk.before.start]) {
+                out.write_all(b" ")?;
+                out.write_all(function)?;
+            }
+            out.write_all(b"\n")?;
+            for line in hunk.lines {
+                let (prefix, data) = match line {
+                    lines::Line::Context(data) => (b' ', data),
+                    lines::Line::Removed(data) => (b'-', data),
+                    lines::Line::Added(data) => (b'+', data),
+                };
+                out.write_all(&[prefix])?;
+                out.write_all(data)?;
+                if !data.ends_with(b"\n") {
+                    out.write_all(b"\n\\ No newline at end of file\n")?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The differences between the content of both sides of a change.
+enum Content<'a> {
+    Binary,
+    Text {
+        before_lines: Vec<&'a BStr>,
+        hunks: Vec<lines::Hunk<'a>>,
+    },
+}
+
+/// A range of lines formatted like in hunk headers, using one-based line numbers.
+struct Range<'a>(&'a std::ops::Range<usize>);
+
+impl std::fmt::Display for Range<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.len() {
+            0 => write!(f, "{},0", self.0.start),
+            1 => write!(f, "{}", self.0.start + 1),
+            len => write!(f, "{},{}", self.0.start + 1, len),
+        }
+    }
+}
+
+/// Find the last line that looks like the beginning of a function like git does by default, which is any line starting with
+/// a letter, `_` or `$`, truncated to 80 bytes and without trailing whitespace.
+fn function_name<'a>(preceding_lines: &[&'a BStr]) -> Option<&'a [u8]> {
+    const MAX_LEN: usize = 80;
+    preceding_lines
+        .iter()
+        .rev()
+        .find(|line| matches!(line.first(), Some(b) if b.is_ascii_alphabetic() || *b == b'_' || *b == b'$'))
+        .map(|line| line[..line.len().min(MAX_LEN)].trim_end_with(|c| c.is_ascii_whitespace()))
+}
+
+fn abbreviate(id: &ObjectId, len: usize) -> String {
+    let mut hex = id.to_string();
+    hex.truncate(len);
+    hex
+}
+
+fn write_optional_path(out: impl io::Write, path: Option<(&str, &BStr)>) -> io::Result<()> {
+    match path {
+        Some((prefix, path)) => write_path(out, prefix, path),
+        None => {
+            let mut out = out;
+            out.write_all(b"/dev/null")
+        }
+    }
+}
+
+/// Write `prefix` and `path`, quoting both like git does if `path` contains special or non-ASCII bytes.
+fn write_path(mut out: impl io::Write, prefix: &str, path: &BStr) -> io::Result<()> {
+    let needs_quoting = |b: u8| !(0x20..0x7f).contains(&b) || b == b'"' || b == b'\\';
+    if !path.iter().copied().any(needs_quoting) {
+        out.write_all(prefix.as_bytes())?;
+        return out.write_all(path);
+    }
+    out.write_all(b"\"")?;
+    out.write_all(prefix.as_bytes())?;
+    for &b in path.iter() {
+        match b {
+            b'\x07' => out.write_all(b"\\a")?,
+            b'\x08' => out.write_all(b"\\b")?,
+            b'\t' => out.write_all(b"\\t")?,
+            b'\n' => out.write_all(b"\\n")?,
+            b'\x0b' => out.write_all(b"\\v")?,
+            b'\x0c' => out.write_all(b"\\f")?,
+            b'\r' => out.write_all(b"\\r")?,
+            b'"' => out.write_all(b"\\\"")?,
+            b'\\' => out.write_all(b"\\\\")?,
+            b if needs_quoting(b) => write!(out, "\\{:03o}", b)?,
+            b => out.write_all(&[b])?,
+        }
+    }
+    out.write_all(b"\"")
+}
@@ -0,0 +1,229 @@
+use crate::{lines, textconv, tree::recorder::Change};
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::{BStr, BString},
+    tree::EntryMode,
+};
+use quick_error::quick_error;
+
+quick_error! {
+    /// The error returned by [`Statistics::record()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        NotFound { oid: ObjectId } {
+            display("The blob {} could not be found", oid)
+        }
+        Textconv(err: textconv::Error) {
+            display("A file could not be converted to text")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// The amount of lines added and removed in a text file.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Lines {
+    /// The amount of added lines.
+    pub insertions: usize,
+    /// The amount of removed lines.
+    pub deletions: usize,
+}
+
+/// The statistics of a single changed file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct File {
+    /// The path of the file after the change.
+    pub path: BString,
+    /// The path of the file before it was renamed or copied, if it was.
+    pub source_path: Option<BString>,
+    /// The amount of changed lines, or `None` if the file is binary before or after the change, like git decides it.
+    pub lines: Option<Lines>,
+}
+
+/// An aggregator of changed lines per file as shown by `git diff --numstat`, along with totals as shown by `git diff --stat`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Statistics {
+    /// The changed files in order of them being recorded.
+    pub files: Vec<File>,
+}
+
+impl Statistics {
+    /// Compute the statistics of all `changes`, obtaining the blobs to diff with `find`.
+    pub fn from_changes<'a, Find>(changes: impl IntoIterator<Item = &'a Change>, mut find: Find) -> Result<Self, Error>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<&'b [u8]>,
+    {
+        let mut stats = Statistics::default();
+        for change in changes {
+            stats.record(change, &mut find)?;
+        }
+        Ok(stats)
+    }
+
+    /// Diff the blobs of `change` line by line and add its statistics, using `find(object_id, &mut buffer)` to obtain
+    /// the blob data backed by `buffer`.
+    ///
+    /// Changes to directories are ignored as their files are expected to be recorded individually, and submodules are
+    /// represented by the commit they point to, like git does.
+    pub fn record<Find>(&mut self, change: &Change, find: Find) -> Result<(), Error>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<&'b [u8]>,
+    {
+        self.record_with_drivers(change, &mut textconv::Drivers::default(), find)
+    }
+
+    /// Like [`record()`][Statistics::record()], but convert files with the matching driver in `drivers` before
+    /// counting their lines, which makes them text files.
+    pub fn record_with_drivers<Find>(
+        &mut self,
+        change: &Change,
+        drivers: &mut textconv::Drivers,
+        mut find: Find,
+    ) -> Result<(), Error>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<&'b [u8]>,
+    {
+        let (before_side, after_side, path, source_path) = sides(change);
+        let before_side = before_side.filter(|(mode, _)| mode.is_no_tree());
+        let after_side = after_side.filter(|(mode, _)| mode.is_no_tree());
+        if before_side.is_none() && after_side.is_none() {
+            return Ok(());
+        }
+
+        let mut before_buf = Vec::new();
+        let mut after_buf = Vec::new();
+        let mut before_converted = Vec::new();
+        let mut after_converted = Vec::new();
+        let before = load(before_side, &mut before_buf, &mut find).ok_or_else(|| not_found(before_side))?;
+        let after = load(after_side, &mut after_buf, &mut find).ok_or_else(|| not_found(after_side))?;
+        let source_or_path = source_path.unwrap_or(path).as_ref();
+        let (before, before_is_text) = convert(before_side, source_or_path, before, &mut before_converted, drivers)?;
+        let (after, after_is_text) = convert(after_side, path.as_ref(), after, &mut after_converted, drivers)?;
+        let lines = if (!before_is_text && git_object::blob::is_binary(before))
+            || (!after_is_text && git_object::blob::is_binary(after))
+        {
+            None
+        } else {
+            Some(count(before, after))
+        };
+        self.files.push(File {
+            path: path.to_owned(),
+            source_path: source_path.cloned(),
+            lines,
+        });
+        Ok(())
+    }
+
+    /// The total amount of added lines in text files.
+    pub fn insertions(&self) -> usize {
+        self.files.iter().filter_map(|f| f.lines).map(|l| l.insertions).sum()
+    }
+
+    /// The total amount of removed lines in text files.
+    pub fn deletions(&self) -> usize {
+        self.files.iter().filter_map(|f| f.lines).map(|l| l.deletions).sum()
+    }
+}
+
+/// The mode and id of an entry on one side of a change, or `None` if it doesn't exist there.
+pub(crate) type Side<'a> = Option<(EntryMode, &'a ObjectId)>;
+
+/// The entry before and after `change` along with its path and the path of the source of renames and copies.
+pub(crate) fn sides(change: &Change) -> (Side<'_>, Side<'_>, &BString, Option<&BString>) {
+    match change {
+        Change::Addition { entry_mode, oid, path } => (None, Some((*entry_mode, oid)), path, None),
+        Change::Deletion { entry_mode, oid, path } => (Some((*entry_mode, oid)), None, path, None),
+        Change::Modification {
+            previous_entry_mode,
+            previous_oid,
+            entry_mode,
+            oid,
+            path,
+        } => (
+            Some((*previous_entry_mode, previous_oid)),
+            Some((*entry_mode, oid)),
+            path,
+            None,
+        ),
+        Change::Rename {
+            previous_entry_mode,
+            previous_oid,
+            previous_path,
+            entry_mode,
+            oid,
+            path,
+            ..
+        } => (
+            Some((*previous_entry_mode, previous_oid)),
+            Some((*entry_mode, oid)),
+            path,
+            Some(previous_path),
+        ),
+        Change::Copy {
+            source_entry_mode,
+            source_oid,
+            source_path,
+            entry_mode,
+            oid,
+            path,
+            ..
+        } => (
+            Some((*source_entry_mode, source_oid)),
+            Some((*entry_mode, oid)),
+            path,
+            Some(source_path),
+        ),
+    }
+}
+
+/// Place the data of `entry` into `buf` and return it, with no entry being represented by no data, or `None` if the
+/// blob could not be found.
+pub(crate) fn load<'b, Find>(entry: Side<'_>, buf: &'b mut Vec<u8>, mut find: Find) -> Option<&'b [u8]>
+where
+    Find: for<'c> FnMut(&oid, &'c mut Vec<u8>) -> Option<&'c [u8]>,
+{
+    match entry {
+        None => Some(&buf[..0]),
+        Some((EntryMode::Commit, id)) => {
+            buf.extend_from_slice(format!("Subproject commit {}\n", id).as_bytes());
+            Some(buf.as_slice())
+        }
+        Some((_, id)) => find(id, buf),
+    }
+}
+
+fn not_found(entry: Side<'_>) -> Error {
+    Error::NotFound {
+        oid: *entry.expect("only existing entries can be missing").1,
+    }
+}
+
+fn count(before: &[u8], after: &[u8]) -> Lines {
+    let before = lines::split(before);
+    let after = lines::split(after);
+    let (before, after) = lines::intern(&before, &after);
+    lines::myers::diff(&before, &after)
+        .into_iter()
+        .fold(Lines::default(), |mut lines, change| {
+            lines.insertions += change.after.len();
+            lines.deletions += change.before.len();
+            lines
+        })
+}
+
+/// Convert the `data` of `entry` at `path` with the matching driver in `drivers` if the entry is a file, and return the
+/// data to diff along with `true` if it was converted, and is thus text.
+pub(crate) fn convert<'b>(
+    entry: Side<'_>,
+    path: &BStr,
+    data: &'b [u8],
+    out: &'b mut Vec<u8>,
+    drivers: &mut textconv::Drivers,
+) -> Result<(&'b [u8], bool), textconv::Error> {
+    match entry {
+        Some((mode, _)) if mode != EntryMode::Commit && !drivers.is_empty() => drivers.convert(path, data, out),
+        _ => Ok((data, false)),
+    }
+}
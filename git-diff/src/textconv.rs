@@ -0,0 +1,276 @@
+use git_object::bstr::{BStr, BString, ByteSlice};
+use quick_error::quick_error;
+
+quick_error! {
+    /// The error returned by [`Drivers::convert()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Convert { path: BString, err: Box<dyn std::error::Error + Send + Sync> } {
+            display("The content of '{}' could not be converted to text", path)
+            source(&**err)
+        }
+    }
+}
+
+/// A converter of the content of files into text before it is diffed line by line, like the `textconv` setting of diff
+/// drivers in git.
+///
+/// It's implemented for closures taking the same arguments as [`convert()`][Driver::convert()].
+pub trait Driver {
+    /// Convert `data` of the file at `path` into text and write it into the empty `out`.
+    fn convert(
+        &mut self,
+        path: &BStr,
+        data: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl<F> Driver for F
+where
+    F: FnMut(&BStr, &[u8], &mut Vec<u8>) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+{
+    fn convert(
+        &mut self,
+        path: &BStr,
+        data: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self(path, data, out)
+    }
+}
+
+/// Drivers registered for patterns of paths, used by [`patch::write_with_drivers()`][crate::patch::write_with_drivers()]
+/// and [`Statistics::record_with_drivers()`][crate::stats::Statistics::record_with_drivers()] to convert files before
+/// diffing them.
+///
+/// Patterns are matched like in `.gitattributes`: patterns without a slash match the file name in any directory, and
+/// all others match the whole path relative to the root of the repository. `*` matches anything but a slash, `**/` any
+/// amount of directories, a trailing `/**` everything inside of a directory and `?` a single byte other than a slash.
+/// Classes like `[a-z]`, `[!0-9]` or `[[:upper:]]` match a single byte other than a slash, and a backslash makes the
+/// byte following it match literally. If more than one pattern matches, the last one wins.
+#[derive(Default)]
+pub struct Drivers {
+    drivers: Vec<(Pattern, Box<dyn Driver>)>,
+}
+
+impl Drivers {
+    /// Convert files matching `pattern` with `driver`, taking precedence over all previously registered drivers.
+    pub fn register(&mut self, pattern: impl Into<BString>, driver: impl Driver + 'static) -> &mut Self {
+        self.drivers.push((Pattern::new(&pattern.into()), Box::new(driver)));
+        self
+    }
+
+    /// Return true if no driver is registered.
+    pub fn is_empty(&self) -> bool {
+        self.drivers.is_empty()
+    }
+
+    /// Return the driver registered last for a pattern matching `path`, if there is one.
+    pub fn find(&mut self, path: &BStr) -> Option<&mut (dyn Driver + 'static)> {
+        self.drivers
+            .iter_mut()
+            .rev()
+            .find(|(pattern, _)| pattern.matches(path))
+            .map(|(_, driver)| &mut **driver)
+    }
+
+    /// Convert `data` of the file at `path` into `out` if a driver is registered for it, and return the converted data along
+    /// with `true`. Otherwise return `data` as is along with `false`.
+    pub fn convert<'a>(
+        &mut self,
+        path: &BStr,
+        data: &'a [u8],
+        out: &'a mut Vec<u8>,
+    ) -> Result<(&'a [u8], bool), Error> {
+        match self.find(path) {
+            Some(driver) => {
+                out.clear();
+                driver.convert(path, data, out).map_err(|err| Error::Convert {
+                    path: path.to_owned(),
+                    err,
+                })?;
+                Ok((out.as_slice(), true))
+            }
+            None => Ok((data, false)),
+        }
+    }
+}
+
+/// A pattern of paths as used in `.gitattributes`, compiled for matching.
+struct Pattern {
+    /// The tokens of the pattern, or `None` if it is malformed and never matches, like git does for unterminated classes.
+    tokens: Option<Vec<Token>>,
+    /// If true, the pattern contains no slash and is matched against the file name only.
+    match_file_name: bool,
+}
+
+enum Token {
+    /// A byte matching itself.
+    Byte(u8),
+    /// `?`, matching any byte but a slash.
+    AnyByte,
+    /// `[...]`, matching any byte but a slash that is in the class, or isn't in it if negated.
+    Class { negated: bool, items: Vec<ClassItem> },
+    /// `*`, matching any amount of bytes but slashes.
+    Star,
+    /// `**/` at the beginning of the pattern or after a slash, matching any amount of leading directories.
+    Directories,
+    /// `**` at the end of the pattern after a slash or on its own, matching everything.
+    Everything,
+}
+
+enum ClassItem {
+    Range(u8, u8),
+    Named(fn(&u8) -> bool),
+}
+
+impl Pattern {
+    fn new(pattern: &[u8]) -> Self {
+        let (pattern, match_file_name) = match pattern.strip_prefix(b"/") {
+            Some(anchored) => (anchored, false),
+            None => (pattern, !pattern.contains(&b'/')),
+        };
+        Pattern {
+            tokens: tokenize(pattern),
+            match_file_name,
+        }
+    }
+
+    /// Return true if this pattern matches `path`, with patterns without a slash matching the file name only.
+    fn matches(&self, path: &BStr) -> bool {
+        let text = if self.match_file_name {
+            path.rsplit_str("/").next().unwrap_or(path)
+        } else {
+            path
+        };
+        self.tokens.as_ref().map_or(false, |tokens| wildmatch(tokens, text))
+    }
+}
+
+fn tokenize(pattern: &[u8]) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while let Some(&byte) = pattern.get(pos) {
+        pos += 1;
+        tokens.push(match byte {
+            b'\\' => {
+                pos += 1;
+                Token::Byte(*pattern.get(pos - 1)?)
+            }
+            b'?' => Token::AnyByte,
+            b'[' => {
+                let (class, len) = parse_class(&pattern[pos..])?;
+                pos += len;
+                class
+            }
+            b'*' => {
+                let stars = pattern[pos..].iter().take_while(|b| **b == b'*').count() + 1;
+                pos += stars - 1;
+                let is_segment_start = pos == stars || pattern[pos - stars - 1] == b'/';
+                match pattern.get(pos) {
+                    Some(b'/') if stars > 1 && is_segment_start => {
+                        pos += 1;
+                        Token::Directories
+                    }
+                    None if stars > 1 && is_segment_start => Token::Everything,
+                    _ => Token::Star,
+                }
+            }
+            _ => Token::Byte(byte),
+        });
+    }
+    Some(tokens)
+}
+
+/// Parse the class in `pattern` following its opening bracket, and return it along with the amount of bytes it took
+/// including the closing bracket, or `None` if it isn't terminated.
+fn parse_class(pattern: &[u8]) -> Option<(Token, usize)> {
+    let negated = matches!(pattern.first(), Some(b'!') | Some(b'^'));
+    let mut pos = usize::from(negated);
+    let mut items = Vec::new();
+    loop {
+        let byte = *pattern.get(pos)?;
+        pos += 1;
+        let start = match byte {
+            b']' if pos > usize::from(negated) + 1 => return Some((Token::Class { negated, items }, pos)),
+            b'[' if pattern.get(pos) == Some(&b':') => {
+                let name_len = pattern[pos + 1..].find(":]")?;
+                let name = &pattern[pos + 1..][..name_len];
+                pos += 1 + name_len + 2;
+                items.push(ClassItem::Named(named_class(name)?));
+                continue;
+            }
+            b'\\' => {
+                pos += 1;
+                *pattern.get(pos - 1)?
+            }
+            _ => byte,
+        };
+        let end = match (pattern.get(pos), pattern.get(pos + 1)) {
+            (Some(b'-'), Some(&end)) if end != b']' => {
+                pos += 2;
+                if end == b'\\' {
+                    pos += 1;
+                    *pattern.get(pos - 1)?
+                } else {
+                    end
+                }
+            }
+            _ => start,
+        };
+        items.push(ClassItem::Range(start, end));
+    }
+}
+
+fn named_class(name: &[u8]) -> Option<fn(&u8) -> bool> {
+    Some(match name {
+        b"alnum" => u8::is_ascii_alphanumeric,
+        b"alpha" => u8::is_ascii_alphabetic,
+        b"blank" => |b: &u8| *b == b' ' || *b == b'\t',
+        b"cntrl" => u8::is_ascii_control,
+        b"digit" => u8::is_ascii_digit,
+        b"graph" => u8::is_ascii_graphic,
+        b"lower" => u8::is_ascii_lowercase,
+        b"print" => |b: &u8| b.is_ascii_graphic() || *b == b' ',
+        b"punct" => u8::is_ascii_punctuation,
+        b"space" => u8::is_ascii_whitespace,
+        b"upper" => u8::is_ascii_uppercase,
+        b"xdigit" => u8::is_ascii_hexdigit,
+        _ => return None,
+    })
+}
+
+/// Return true if `tokens` match all of `text`.
+///
+/// Instead of backtracking, which takes exponential time for patterns with many stars, all positions in `text` reachable
+/// after each token are tracked, which takes time proportional to the amount of tokens times the length of `text`.
+fn wildmatch(tokens: &[Token], text: &[u8]) -> bool {
+    let mut reachable = vec![false; text.len() + 1];
+    reachable[0] = true;
+    let mut next = vec![false; text.len() + 1];
+    for token in tokens {
+        let mut any_reachable_before = false;
+        for pos in 0..=text.len() {
+            let previous = pos.checked_sub(1).map(|previous| (reachable[previous], text[previous]));
+            next[pos] = match token {
+                Token::Byte(expected) => matches!(previous, Some((true, byte)) if byte == *expected),
+                Token::AnyByte => matches!(previous, Some((true, byte)) if byte != b'/'),
+                Token::Class { negated, items } => matches!(previous, Some((true, byte)) if byte != b'/'
+                    && items.iter().any(|item| match item {
+                        ClassItem::Range(start, end) => (*start..=*end).contains(&byte),
+                        ClassItem::Named(is_member) => is_member(&byte),
+                    }) != *negated),
+                Token::Star => reachable[pos] || (pos > 0 && next[pos - 1] && text[pos - 1] != b'/'),
+                Token::Everything => reachable[pos] || (pos > 0 && next[pos - 1]),
+                Token::Directories => {
+                    reachable[pos] || (any_reachable_before && matches!(previous, Some((_, byte)) if byte == b'/'))
+                }
+            };
+            any_reachable_before |= reachable[pos];
+        }
+        std::mem::swap(&mut reachable, &mut next);
+    }
+    reachable[text.len()]
+}
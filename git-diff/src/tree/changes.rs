@@ -3,12 +3,16 @@ use crate::{
     tree::{visit::Change, TreeInfoPair},
 };
 use git_hash::{oid, ObjectId};
-use git_object::immutable;
+use git_object::{
+    bstr::{BStr, BString},
+    immutable,
+    pathspec::Pathspecs,
+};
 use quick_error::quick_error;
 use std::{borrow::BorrowMut, collections::VecDeque};
 
 quick_error! {
-    /// The error returned by [tree::Changes::needed_to_obtain()].
+    /// The error returned by [tree::Changes::needed_to_obtain()] and [tree::changes_exist()].
     #[derive(Debug)]
     #[allow(missing_docs)]
     pub enum Error {
@@ -27,6 +31,17 @@ quick_error! {
 }
 
 impl<'a> tree::Changes<'a> {
+    /// Only report changes of entries at or below one of the given `pathspecs`, like `src` or `src/lib.rs`, and don't
+    /// descend into trees which can't contain them. This makes diffing a few paths of large trees very fast.
+    ///
+    /// Pathspecs are paths relative to the root of the tree using slashes as separators and are matched literally,
+    /// with trailing slashes being ignored. Trees leading up to a pathspec are reported as well, if they changed.
+    /// Without pathspecs, which is the default, all changes are reported.
+    pub fn with_pathspecs(mut self, pathspecs: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.pathspecs = Pathspecs::new(pathspecs);
+        self
+    }
+
     /// Calculate the changes that would need to be applied to `self` to get `other`.
     ///
     /// * The `state` maybe owned or mutably borrowed to allow reuses allocated data structures through multiple runs.
@@ -45,6 +60,8 @@ impl<'a> tree::Changes<'a> {
     ///   by the delegate implementation which should be as specific as possible. Rename tracking can be computed on top of the changes
     ///   received by the `delegate`.
     /// * cycle checking is not performed, but can be performed in the delegate which can return [`tree::visit::Action::Cancel`] to stop the traversal.
+    /// * the `delegate` can return [`tree::visit::Action::Stop`] to end the traversal early without error once it has seen enough changes,
+    ///   which avoids diffing entire trees just to learn whether there are changes at all.
     /// * [std::mem::ManuallyDrop] is used because `Peekable` is needed. When using it as wrapper around our no-drop iterators, all of the sudden
     ///   borrowcheck complains as Drop is present (even though it's not)
     ///
@@ -54,7 +71,7 @@ impl<'a> tree::Changes<'a> {
         mut self,
         other: immutable::TreeIter<'_>,
         mut state: StateMut,
-        mut find: FindFn,
+        find: FindFn,
         delegate: &mut R,
     ) -> Result<(), Error>
     where
@@ -64,101 +81,217 @@ impl<'a> tree::Changes<'a> {
     {
         let state = state.borrow_mut();
         state.clear();
-        let mut lhs_entries = peekable(self.0.take().unwrap_or_default());
-        let mut rhs_entries = peekable(other);
-        let mut pop_path = false;
+        match changes(
+            self.tree.take().unwrap_or_default(),
+            other,
+            &mut self.pathspecs,
+            state,
+            find,
+            delegate,
+        ) {
+            Ok(()) | Err(Interrupt::Stop) => Ok(()),
+            Err(Interrupt::Error(err)) => Err(err),
+        }
+    }
+}
 
-        loop {
-            if pop_path {
-                delegate.pop_path_component();
+/// Return true if there are any changes between the `lhs` and `rhs` trees, which is faster than computing them with
+/// [`Changes::needed_to_obtain()`][tree::Changes::needed_to_obtain()] if only a boolean answer is needed, for example to
+/// implement status-like checks.
+///
+/// The trees are compared level by level without tracking paths, and the comparison stops at the first entry which differs
+/// in name, mode or id. Sub-trees are only descended into if their ids differ.
+///
+/// * The `state` maybe owned or mutably borrowed to allow reuses allocated data structures through multiple runs.
+/// * `find` is a function `f(object_id, &mut buffer) -> Option<TreeIter>` to return a `TreeIter` for the given object id
+///   just like in [`Changes::needed_to_obtain()`][tree::Changes::needed_to_obtain()].
+pub fn changes_exist<FindFn, StateMut>(
+    lhs: immutable::TreeIter<'_>,
+    rhs: immutable::TreeIter<'_>,
+    mut state: StateMut,
+    mut find: FindFn,
+) -> Result<bool, Error>
+where
+    FindFn: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<immutable::tree::TreeIter<'b>>,
+    StateMut: BorrowMut<tree::State>,
+{
+    let state = state.borrow_mut();
+    state.clear();
+    let mut lhs_entries = lhs;
+    let mut rhs_entries = rhs;
+    loop {
+        if entries_differ(lhs_entries, rhs_entries, &mut state.trees)? {
+            return Ok(true);
+        }
+        match state.trees.pop_front() {
+            Some((Some(lhs), Some(rhs))) => {
+                lhs_entries = find(&lhs, &mut state.buf1).ok_or(Error::NotFound { oid: lhs })?;
+                rhs_entries = find(&rhs, &mut state.buf2).ok_or(Error::NotFound { oid: rhs })?;
             }
-            pop_path = true;
+            Some(_) => unreachable!("BUG: only pairs of trees are scheduled for comparison"),
+            None => return Ok(false),
+        }
+    }
+}
 
-            match (lhs_entries.next(), rhs_entries.next()) {
-                (None, None) => {
-                    match state.trees.pop_front() {
-                        Some((None, Some(rhs))) => {
-                            delegate.pop_front_tracked_path_and_set_current();
-                            rhs_entries = peekable(find(&rhs, &mut state.buf2).ok_or(Error::NotFound { oid: rhs })?);
-                        }
-                        Some((Some(lhs), Some(rhs))) => {
-                            delegate.pop_front_tracked_path_and_set_current();
-                            lhs_entries = peekable(find(&lhs, &mut state.buf1).ok_or(Error::NotFound { oid: lhs })?);
-                            rhs_entries = peekable(find(&rhs, &mut state.buf2).ok_or(Error::NotFound { oid: rhs })?);
-                        }
-                        Some((Some(lhs), None)) => {
-                            delegate.pop_front_tracked_path_and_set_current();
-                            lhs_entries = peekable(find(&lhs, &mut state.buf1).ok_or(Error::NotFound { oid: lhs })?);
-                        }
-                        Some((None, None)) => unreachable!("BUG: it makes no sense to fill the stack with empties"),
-                        None => return Ok(()),
-                    };
-                    pop_path = false;
+/// Return true if the entries of `lhs` and `rhs` differ, or schedule pairs of sub-trees with different ids for comparison
+/// in `queue`.
+fn entries_differ(
+    mut lhs: immutable::TreeIter<'_>,
+    mut rhs: immutable::TreeIter<'_>,
+    queue: &mut VecDeque<TreeInfoPair>,
+) -> Result<bool, Error> {
+    loop {
+        match (lhs.next().transpose()?, rhs.next().transpose()?) {
+            (None, None) => return Ok(false),
+            (Some(lhs), Some(rhs)) => {
+                if lhs.filename != rhs.filename || lhs.mode != rhs.mode {
+                    return Ok(true);
                 }
-                (Some(lhs), Some(rhs)) => {
-                    use std::cmp::Ordering::*;
-                    let (lhs, rhs) = (lhs?, rhs?);
-                    match lhs.filename.cmp(rhs.filename) {
-                        Equal => handle_lhs_and_rhs_with_equal_filenames(lhs, rhs, &mut state.trees, delegate)?,
-                        Less => catchup_lhs_with_rhs(&mut lhs_entries, lhs, rhs, &mut state.trees, delegate)?,
-                        Greater => catchup_rhs_with_lhs(&mut rhs_entries, lhs, rhs, &mut state.trees, delegate)?,
+                if lhs.oid != rhs.oid {
+                    if !lhs.mode.is_tree() {
+                        return Ok(true);
                     }
+                    queue.push_back((Some(lhs.oid.to_owned()), Some(rhs.oid.to_owned())));
                 }
-                (Some(lhs), None) => {
-                    let lhs = lhs?;
-                    delete_entry_schedule_recursion(lhs, &mut state.trees, delegate)?;
-                }
-                (None, Some(rhs)) => {
-                    let rhs = rhs?;
-                    add_entry_schedule_recursion(rhs, &mut state.trees, delegate)?;
+            }
+            (Some(_), None) | (None, Some(_)) => return Ok(true),
+        }
+    }
+}
+
+/// The reason for stopping the traversal early, either on request of the delegate or due to an error.
+enum Interrupt {
+    Stop,
+    Error(Error),
+}
+
+impl From<Error> for Interrupt {
+    fn from(err: Error) -> Self {
+        Interrupt::Error(err)
+    }
+}
+
+impl From<immutable::object::decode::Error> for Interrupt {
+    fn from(err: immutable::object::decode::Error) -> Self {
+        Interrupt::Error(err.into())
+    }
+}
+
+fn handle_action(action: tree::visit::Action) -> Result<(), Interrupt> {
+    match action {
+        tree::visit::Action::Continue => Ok(()),
+        tree::visit::Action::Stop => Err(Interrupt::Stop),
+        tree::visit::Action::Cancel => Err(Error::Cancelled.into()),
+    }
+}
+
+fn changes<FindFn, R>(
+    lhs: immutable::TreeIter<'_>,
+    rhs: immutable::TreeIter<'_>,
+    pathspecs: &mut Pathspecs,
+    state: &mut tree::State,
+    mut find: FindFn,
+    delegate: &mut R,
+) -> Result<(), Interrupt>
+where
+    FindFn: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<immutable::tree::TreeIter<'b>>,
+    R: tree::Visit,
+{
+    let mut queue = Queue {
+        trees: &mut state.trees,
+        pathspecs,
+    };
+    let mut lhs_entries = peekable(lhs);
+    let mut rhs_entries = peekable(rhs);
+    let mut pop_path = false;
+
+    loop {
+        if pop_path {
+            delegate.pop_path_component();
+        }
+        pop_path = true;
+
+        match (lhs_entries.next(), rhs_entries.next()) {
+            (None, None) => {
+                match queue.pop_front() {
+                    Some((None, Some(rhs))) => {
+                        delegate.pop_front_tracked_path_and_set_current();
+                        rhs_entries = peekable(find(&rhs, &mut state.buf2).ok_or(Error::NotFound { oid: rhs })?);
+                    }
+                    Some((Some(lhs), Some(rhs))) => {
+                        delegate.pop_front_tracked_path_and_set_current();
+                        lhs_entries = peekable(find(&lhs, &mut state.buf1).ok_or(Error::NotFound { oid: lhs })?);
+                        rhs_entries = peekable(find(&rhs, &mut state.buf2).ok_or(Error::NotFound { oid: rhs })?);
+                    }
+                    Some((Some(lhs), None)) => {
+                        delegate.pop_front_tracked_path_and_set_current();
+                        lhs_entries = peekable(find(&lhs, &mut state.buf1).ok_or(Error::NotFound { oid: lhs })?);
+                    }
+                    Some((None, None)) => unreachable!("BUG: it makes no sense to fill the stack with empties"),
+                    None => return Ok(()),
+                };
+                pop_path = false;
+            }
+            (Some(lhs), Some(rhs)) => {
+                use std::cmp::Ordering::*;
+                let (lhs, rhs) = (lhs?, rhs?);
+                match lhs.filename.cmp(rhs.filename) {
+                    Equal => handle_lhs_and_rhs_with_equal_filenames(lhs, rhs, &mut queue, delegate)?,
+                    Less => catchup_lhs_with_rhs(&mut lhs_entries, lhs, rhs, &mut queue, delegate)?,
+                    Greater => catchup_rhs_with_lhs(&mut rhs_entries, lhs, rhs, &mut queue, delegate)?,
                 }
             }
+            (Some(lhs), None) => {
+                let lhs = lhs?;
+                delete_entry_schedule_recursion(lhs, &mut queue, delegate)?;
+            }
+            (None, Some(rhs)) => {
+                let rhs = rhs?;
+                add_entry_schedule_recursion(rhs, &mut queue, delegate)?;
+            }
         }
     }
 }
 
 fn delete_entry_schedule_recursion<R: tree::Visit>(
     entry: immutable::tree::Entry<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue<'_>,
     delegate: &mut R,
-) -> Result<(), Error> {
+) -> Result<(), Interrupt> {
     delegate.push_path_component(entry.filename);
-    if delegate
-        .visit(Change::Deletion {
-            entry_mode: entry.mode,
-            oid: entry.oid.to_owned(),
-        })
-        .cancelled()
-    {
-        return Err(Error::Cancelled);
+    if !queue.is_relevant(entry.filename, entry.mode.is_tree()) {
+        return Ok(());
     }
+    handle_action(delegate.visit(Change::Deletion {
+        entry_mode: entry.mode,
+        oid: entry.oid.to_owned(),
+    }))?;
     if entry.mode.is_tree() {
         delegate.pop_path_component();
         delegate.push_back_tracked_path_component(entry.filename);
-        queue.push_back((Some(entry.oid.to_owned()), None));
+        queue.push_back((Some(entry.oid.to_owned()), None), entry.filename);
     }
     Ok(())
 }
 
 fn add_entry_schedule_recursion<R: tree::Visit>(
     entry: immutable::tree::Entry<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue<'_>,
     delegate: &mut R,
-) -> Result<(), Error> {
+) -> Result<(), Interrupt> {
     delegate.push_path_component(entry.filename);
-    if delegate
-        .visit(Change::Addition {
-            entry_mode: entry.mode,
-            oid: entry.oid.to_owned(),
-        })
-        .cancelled()
-    {
-        return Err(Error::Cancelled);
+    if !queue.is_relevant(entry.filename, entry.mode.is_tree()) {
+        return Ok(());
     }
+    handle_action(delegate.visit(Change::Addition {
+        entry_mode: entry.mode,
+        oid: entry.oid.to_owned(),
+    }))?;
     if entry.mode.is_tree() {
         delegate.pop_path_component();
         delegate.push_back_tracked_path_component(entry.filename);
-        queue.push_back((None, Some(entry.oid.to_owned())))
+        queue.push_back((None, Some(entry.oid.to_owned())), entry.filename);
     }
     Ok(())
 }
@@ -166,9 +299,9 @@ fn catchup_rhs_with_lhs<R: tree::Visit>(
     rhs_entries: &mut IteratorType<immutable::TreeIter<'_>>,
     lhs: immutable::tree::Entry<'_>,
     rhs: immutable::tree::Entry<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue<'_>,
     delegate: &mut R,
-) -> Result<(), Error> {
+) -> Result<(), Interrupt> {
     use std::cmp::Ordering::*;
     add_entry_schedule_recursion(rhs, queue, delegate)?;
     loop {
@@ -191,7 +324,7 @@ fn catchup_rhs_with_lhs<R: tree::Visit>(
                     break;
                 }
             },
-            Some(Err(err)) => return Err(Error::EntriesDecode(err.to_owned())),
+            Some(Err(err)) => return Err(Error::EntriesDecode(err.to_owned()).into()),
             None => {
                 delegate.pop_path_component();
                 delete_entry_schedule_recursion(lhs, queue, delegate)?;
@@ -206,9 +339,9 @@ fn catchup_lhs_with_rhs<R: tree::Visit>(
     lhs_entries: &mut IteratorType<immutable::TreeIter<'_>>,
     lhs: immutable::tree::Entry<'_>,
     rhs: immutable::tree::Entry<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue<'_>,
     delegate: &mut R,
-) -> Result<(), Error> {
+) -> Result<(), Interrupt> {
     use std::cmp::Ordering::*;
     delete_entry_schedule_recursion(lhs, queue, delegate)?;
     loop {
@@ -231,7 +364,7 @@ fn catchup_lhs_with_rhs<R: tree::Visit>(
                     break;
                 }
             },
-            Some(Err(err)) => return Err(Error::EntriesDecode(err.to_owned())),
+            Some(Err(err)) => return Err(Error::EntriesDecode(err.to_owned()).into()),
             None => {
                 delegate.pop_path_component();
                 add_entry_schedule_recursion(rhs, queue, delegate)?;
@@ -245,91 +378,98 @@ fn catchup_lhs_with_rhs<R: tree::Visit>(
 fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
     lhs: immutable::tree::Entry<'_>,
     rhs: immutable::tree::Entry<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue<'_>,
     delegate: &mut R,
-) -> Result<(), Error> {
+) -> Result<(), Interrupt> {
     use git_object::tree::EntryMode::*;
+    if !queue.is_relevant(lhs.filename, lhs.mode.is_tree() || rhs.mode.is_tree()) {
+        delegate.push_path_component(lhs.filename);
+        return Ok(());
+    }
     match (lhs.mode, rhs.mode) {
         (Tree, Tree) => {
             delegate.push_back_tracked_path_component(lhs.filename);
-            if lhs.oid != rhs.oid
-                && delegate
-                    .visit(Change::Modification {
-                        previous_entry_mode: lhs.mode,
-                        previous_oid: lhs.oid.to_owned(),
-                        entry_mode: rhs.mode,
-                        oid: rhs.oid.to_owned(),
-                    })
-                    .cancelled()
-            {
-                return Err(Error::Cancelled);
+            if lhs.oid != rhs.oid {
+                handle_action(delegate.visit(Change::Modification {
+                    previous_entry_mode: lhs.mode,
+                    previous_oid: lhs.oid.to_owned(),
+                    entry_mode: rhs.mode,
+                    oid: rhs.oid.to_owned(),
+                }))?;
             }
-            queue.push_back((Some(lhs.oid.to_owned()), Some(rhs.oid.to_owned())));
+            queue.push_back((Some(lhs.oid.to_owned()), Some(rhs.oid.to_owned())), lhs.filename);
         }
         (lhs_mode, Tree) if lhs_mode.is_no_tree() => {
+            let is_lhs_relevant = queue.is_relevant(lhs.filename, false);
             delegate.push_back_tracked_path_component(lhs.filename);
-            if delegate
-                .visit(Change::Deletion {
+            if is_lhs_relevant {
+                handle_action(delegate.visit(Change::Deletion {
                     entry_mode: lhs.mode,
                     oid: lhs.oid.to_owned(),
-                })
-                .cancelled()
-            {
-                return Err(Error::Cancelled);
-            };
-            if delegate
-                .visit(Change::Addition {
-                    entry_mode: rhs.mode,
-                    oid: rhs.oid.to_owned(),
-                })
-                .cancelled()
-            {
-                return Err(Error::Cancelled);
-            };
-            queue.push_back((None, Some(rhs.oid.to_owned())));
+                }))?;
+            }
+            handle_action(delegate.visit(Change::Addition {
+                entry_mode: rhs.mode,
+                oid: rhs.oid.to_owned(),
+            }))?;
+            queue.push_back((None, Some(rhs.oid.to_owned())), lhs.filename);
         }
         (Tree, rhs_mode) if rhs_mode.is_no_tree() => {
+            let is_rhs_relevant = queue.is_relevant(lhs.filename, false);
             delegate.push_back_tracked_path_component(lhs.filename);
-            if delegate
-                .visit(Change::Deletion {
-                    entry_mode: lhs.mode,
-                    oid: lhs.oid.to_owned(),
-                })
-                .cancelled()
-            {
-                return Err(Error::Cancelled);
-            }
-            if delegate
-                .visit(Change::Addition {
+            handle_action(delegate.visit(Change::Deletion {
+                entry_mode: lhs.mode,
+                oid: lhs.oid.to_owned(),
+            }))?;
+            if is_rhs_relevant {
+                handle_action(delegate.visit(Change::Addition {
                     entry_mode: rhs.mode,
                     oid: rhs.oid.to_owned(),
-                })
-                .cancelled()
-            {
-                return Err(Error::Cancelled);
-            };
-            queue.push_back((Some(lhs.oid.to_owned()), None));
+                }))?;
+            }
+            queue.push_back((Some(lhs.oid.to_owned()), None), lhs.filename);
         }
         (lhs_non_tree, rhs_non_tree) => {
             delegate.push_path_component(lhs.filename);
             debug_assert!(lhs_non_tree.is_no_tree() && rhs_non_tree.is_no_tree());
-            if lhs.oid != rhs.oid
-                && delegate
-                    .visit(Change::Modification {
-                        previous_entry_mode: lhs.mode,
-                        previous_oid: lhs.oid.to_owned(),
-                        entry_mode: rhs.mode,
-                        oid: rhs.oid.to_owned(),
-                    })
-                    .cancelled()
-            {
-                return Err(Error::Cancelled);
+            if lhs.oid != rhs.oid || lhs.mode != rhs.mode {
+                handle_action(delegate.visit(Change::Modification {
+                    previous_entry_mode: lhs.mode,
+                    previous_oid: lhs.oid.to_owned(),
+                    entry_mode: rhs.mode,
+                    oid: rhs.oid.to_owned(),
+                }))?;
             }
         }
     };
     Ok(())
 }
 
+/// The trees scheduled for comparison, along with their paths if only some paths are of interest.
+struct Queue<'a> {
+    trees: &'a mut VecDeque<TreeInfoPair>,
+    pathspecs: &'a mut Pathspecs,
+}
+
+impl Queue<'_> {
+    fn push_back(&mut self, trees: TreeInfoPair, name: &BStr) {
+        self.pathspecs.push_tree(name);
+        self.trees.push_back(trees);
+    }
+
+    fn pop_front(&mut self) -> Option<TreeInfoPair> {
+        let trees = self.trees.pop_front()?;
+        self.pathspecs.pop_tree();
+        Some(trees)
+    }
+
+    /// Return true if the entry `name` of the current trees matches a pathspec, or may contain matching entries if it `is_tree`.
+    /// Trees leading up to a pathspec are relevant to report their changes and to compare their entries.
+    fn is_relevant(&mut self, name: &BStr, is_tree: bool) -> bool {
+        self.pathspecs.relevance(name, is_tree).is_relevant()
+    }
+}
+
 type IteratorType<I> = std::mem::ManuallyDrop<std::iter::Peekable<I>>;
 
 fn peekable<I: Iterator>(iter: I) -> IteratorType<I> {
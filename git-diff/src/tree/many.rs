@@ -0,0 +1,89 @@
+use crate::{
+    chunks::Chunks,
+    tree::{self, changes::Error, recorder, Recorder},
+};
+use git_features::parallel;
+use git_hash::{oid, ObjectId};
+use git_object::immutable;
+
+/// A pair of trees to diff, with `None` standing for an empty tree, for example to diff a root commit.
+pub type Pair = (Option<ObjectId>, Option<ObjectId>);
+
+/// Diff all `pairs` of trees using up to `thread_limit` threads and return the changes needed to turn the first tree of
+/// each pair into the second one, in the order of `pairs`.
+///
+/// `new_find()` is called once per thread to create the function to find trees with, which allows each thread to use its
+/// own object cache. Each thread also reuses its [`State`][tree::State] and buffers for all pairs it diffs.
+///
+/// Without the `parallel` feature of `git-features`, all pairs are diffed on the current thread.
+pub fn changes<Find, NewFindFn>(
+    pairs: impl IntoIterator<Item = Pair>,
+    thread_limit: Option<usize>,
+    new_find: NewFindFn,
+) -> Result<Vec<Vec<recorder::Change>>, Error>
+where
+    NewFindFn: Fn() -> Find + Send + Sync,
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<immutable::TreeIter<'b>>,
+{
+    let pairs: Vec<_> = pairs.into_iter().collect();
+    let (chunk_size, thread_limit, _) =
+        parallel::optimize_chunk_size_and_thread_limit(10, Some(pairs.len()), thread_limit, None);
+    parallel::in_parallel(
+        pairs.chunks(chunk_size.max(1)).enumerate(),
+        thread_limit,
+        |_| Thread {
+            state: tree::State::default(),
+            lhs: Vec::new(),
+            rhs: Vec::new(),
+            find: new_find(),
+        },
+        |(chunk_index, pairs), thread| {
+            let changes = pairs
+                .iter()
+                .map(|(lhs, rhs)| thread.changes(lhs.as_ref(), rhs.as_ref()))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((chunk_index, changes))
+        },
+        Chunks::default(),
+    )
+}
+
+/// The state each thread keeps for all pairs of trees it diffs.
+struct Thread<Find> {
+    state: tree::State,
+    lhs: Vec<u8>,
+    rhs: Vec<u8>,
+    find: Find,
+}
+
+impl<Find> Thread<Find>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<immutable::TreeIter<'b>>,
+{
+    fn changes(&mut self, lhs: Option<&ObjectId>, rhs: Option<&ObjectId>) -> Result<Vec<recorder::Change>, Error> {
+        let lhs = find_tree(lhs, &mut self.lhs, &mut self.find)?;
+        let rhs = find_tree(rhs, &mut self.rhs, &mut self.find)?;
+        let mut recorder = Recorder::default();
+        tree::Changes::from(lhs).needed_to_obtain(
+            rhs.unwrap_or_default(),
+            &mut self.state,
+            &mut self.find,
+            &mut recorder,
+        )?;
+        Ok(recorder.records)
+    }
+}
+
+fn find_tree<'b, Find>(
+    id: Option<&ObjectId>,
+    buf: &'b mut Vec<u8>,
+    find: &mut Find,
+) -> Result<Option<immutable::TreeIter<'b>>, Error>
+where
+    Find: for<'c> FnMut(&oid, &'c mut Vec<u8>) -> Option<immutable::TreeIter<'c>>,
+{
+    match id {
+        Some(id) => find(id, buf).map(Some).ok_or(Error::NotFound { oid: *id }),
+        None => Ok(None),
+    }
+}
@@ -1,5 +1,5 @@
 use git_hash::ObjectId;
-use git_object::immutable;
+use git_object::{immutable, pathspec::Pathspecs};
 use std::collections::VecDeque;
 
 /// The state required to visit [Changes] to be instantiated with `State::default()`.
@@ -21,19 +21,27 @@ impl State {
 }
 
 /// An iterator over changes of a tree, instantiated using `Changes::from(…)`.
-pub struct Changes<'a>(Option<immutable::TreeIter<'a>>);
+pub struct Changes<'a> {
+    tree: Option<immutable::TreeIter<'a>>,
+    pathspecs: Pathspecs,
+}
 
 impl<'a, T> From<T> for Changes<'a>
 where
     T: Into<Option<immutable::TreeIter<'a>>>,
 {
     fn from(v: T) -> Self {
-        Changes(v.into())
+        Changes {
+            tree: v.into(),
+            pathspecs: Pathspecs::default(),
+        }
     }
 }
 
 ///
 pub mod changes;
+#[doc(inline)]
+pub use changes::changes_exist;
 
 ///
 pub mod visit;
@@ -44,3 +52,9 @@ pub use visit::Visit;
 pub mod recorder;
 #[doc(inline)]
 pub use recorder::Recorder;
+
+///
+pub mod rewrites;
+
+///
+pub mod many;
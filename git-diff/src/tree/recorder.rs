@@ -30,6 +30,34 @@ pub enum Change {
 
         path: BString,
     },
+    /// A deletion and an addition paired by [rename tracking][crate::tree::rewrites::detect()], which is never
+    /// produced by the `Recorder` itself.
+    Rename {
+        previous_entry_mode: tree::EntryMode,
+        previous_oid: ObjectId,
+        previous_path: BString,
+
+        entry_mode: tree::EntryMode,
+        oid: ObjectId,
+        path: BString,
+
+        /// How similar the content before and after the rename is in percent, with 100 meaning they are the same.
+        similarity: u8,
+    },
+    /// An addition whose content was copied from the file at `source_path` as found by [copy tracking][crate::tree::rewrites::Copies],
+    /// which is never produced by the `Recorder` itself.
+    Copy {
+        source_entry_mode: tree::EntryMode,
+        source_oid: ObjectId,
+        source_path: BString,
+
+        entry_mode: tree::EntryMode,
+        oid: ObjectId,
+        path: BString,
+
+        /// How similar the content of the source and the copy is in percent, with 100 meaning they are the same.
+        similarity: u8,
+    },
 }
 
 /// A [Visit][visit::Visit] implementation to record every observed change and keep track of the changed paths.
@@ -39,11 +67,22 @@ pub struct Recorder {
     path_map: BTreeMap<usize, BString>,
     path_deque: VecDeque<BString>,
     path: BString,
+    limit: Option<usize>,
     /// The observed changes.
     pub records: Vec<Change>,
 }
 
 impl Recorder {
+    /// Create a new instance which stops the traversal successfully once `max_changes` changes were recorded.
+    ///
+    /// With a limit of 1, this is an efficient way to learn whether two trees differ at all.
+    pub fn with_limit(max_changes: usize) -> Self {
+        Recorder {
+            limit: Some(max_changes),
+            ..Default::default()
+        }
+    }
+
     fn pop_element(&mut self) {
         if let Some(pos) = self.path.rfind_byte(b'/') {
             self.path.resize(pos, 0);
@@ -108,6 +147,27 @@ impl visit::Visit for Recorder {
                 path: self.path_clone(),
             },
         });
-        visit::Action::Continue
+        match self.limit {
+            Some(limit) if self.records.len() >= limit => visit::Action::Stop,
+            _ => visit::Action::Continue,
+        }
+    }
+}
+
+impl IntoIterator for Recorder {
+    type Item = Change;
+    type IntoIter = std::vec::IntoIter<Change>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Recorder {
+    type Item = &'a Change;
+    type IntoIter = std::slice::Iter<'a, Change>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.iter()
     }
 }
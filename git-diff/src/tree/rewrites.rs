@@ -0,0 +1,342 @@
+use crate::{lines, tree::recorder::Change};
+use git_hash::{oid, ObjectId};
+use git_object::{bstr::BString, tree::EntryMode};
+use std::{cmp::Reverse, collections::HashMap};
+
+/// Configure how [`detect()`] pairs deletions with additions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The minimal similarity of the content of a deleted and an added file in percent for them to be considered a rename.
+    ///
+    /// 100 only detects exact renames, which is fast as no content has to be compared. Defaults to 50, like git.
+    pub percentage: u8,
+    /// If the amount of deleted files times the amount of added files left after detecting exact renames exceeds the square
+    /// of this number, their content isn't compared and only exact renames are detected. 0 disables the limit.
+    ///
+    /// The same limit applies to the amount of copy sources times the amount of added files when detecting copies.
+    /// Defaults to 1000, like `diff.renameLimit` in git.
+    pub limit: usize,
+    /// If set, additions which aren't renames are checked for being copies of other files, using the same `percentage`.
+    ///
+    /// Defaults to `None`.
+    pub copies: Option<Copies>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            percentage: 50,
+            limit: 1000,
+            copies: None,
+        }
+    }
+}
+
+/// The files to consider as sources of [copies][Change::Copy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Copies {
+    /// Consider deleted and modified files, like `git diff --find-copies` does.
+    FromModifiedFiles,
+    /// Consider the unmodified files passed to [`detect_with_unmodified()`] as well, like `git diff --find-copies-harder` does.
+    ///
+    /// This is expensive as it typically involves all files of the source tree.
+    FromAllFiles,
+}
+
+/// A file that didn't change, to be considered as source for [copies][Copies::FromAllFiles].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Unmodified {
+    /// The mode of the file.
+    pub entry_mode: EntryMode,
+    /// The id of the file's content.
+    pub oid: ObjectId,
+    /// The path of the file relative to the root of the tree.
+    pub path: BString,
+}
+
+/// Turn pairs of [deletions][Change::Deletion] and [additions][Change::Addition] of files in `changes` into
+/// [renames][Change::Rename], and additions into [copies][Change::Copy] if enabled, as configured by `options`.
+/// Return all changes in their original order.
+///
+/// Files with the same content are paired first, in order of their appearance. Then the content of the remaining
+/// files is compared line by line, obtained with `find(object_id, &mut buffer)` which returns the data of the blob
+/// backed by `buffer`, and each addition is paired with the most similar deletion that wasn't paired yet.
+/// Blobs which cannot be found are only considered for exact renames.
+///
+/// Regular files and symbolic links are never paired with each other, and neither are directories or submodules considered.
+pub fn detect<Find>(changes: Vec<Change>, options: Options, find: Find) -> Vec<Change>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<&'b [u8]>,
+{
+    detect_with_unmodified(changes, std::iter::empty(), options, find)
+}
+
+/// Like [`detect()`], but with `unmodified` files of the source tree to be considered as copy sources if
+/// [`Copies::FromAllFiles`] is set in `options`.
+///
+/// Copies are detected after renames in the same way, but each source may be copied any number of times and deleted
+/// files remain copy sources even if they were renamed.
+pub fn detect_with_unmodified<Find>(
+    changes: Vec<Change>,
+    unmodified: impl IntoIterator<Item = Unmodified>,
+    options: Options,
+    find: Find,
+) -> Vec<Change>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<&'b [u8]>,
+{
+    let mut blobs = Blobs {
+        find,
+        data: HashMap::new(),
+    };
+    let mut deletions = Vec::new();
+    let mut additions = Vec::new();
+    for (index, change) in changes.iter().enumerate() {
+        match change {
+            Change::Deletion { entry_mode, oid, .. } if is_file(*entry_mode) => {
+                deletions.push((index, *entry_mode, *oid))
+            }
+            Change::Addition { entry_mode, oid, .. } if is_file(*entry_mode) => {
+                additions.push((index, *entry_mode, *oid))
+            }
+            _ => {}
+        }
+    }
+
+    let mut rename_sources = HashMap::<usize, (usize, u8)>::new();
+    for (&(addition, _, _), source) in additions
+        .iter()
+        .zip(pair(&deletions, &additions, true, options, &mut blobs))
+    {
+        if let Some((source, similarity)) = source {
+            rename_sources.insert(addition, (deletions[source].0, similarity));
+        }
+    }
+
+    let mut copy_sources = HashMap::<usize, (Unmodified, u8)>::new();
+    if let Some(copies) = options.copies {
+        additions.retain(|(index, _, _)| !rename_sources.contains_key(index));
+        let mut sources: Vec<_> = changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Deletion { entry_mode, oid, path } => Some((*entry_mode, *oid, path)),
+                Change::Modification {
+                    previous_entry_mode,
+                    previous_oid,
+                    path,
+                    ..
+                } => Some((*previous_entry_mode, *previous_oid, path)),
+                _ => None,
+            })
+            .map(|(entry_mode, oid, path)| Unmodified {
+                entry_mode,
+                oid,
+                path: path.clone(),
+            })
+            .collect();
+        if copies == Copies::FromAllFiles {
+            sources.extend(unmodified);
+        }
+        sources.retain(|file| is_file(file.entry_mode));
+        let candidates: Vec<_> = sources
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (index, file.entry_mode, file.oid))
+            .collect();
+        for (&(addition, _, _), source) in
+            additions
+                .iter()
+                .zip(pair(&candidates, &additions, false, options, &mut blobs))
+        {
+            if let Some((source, similarity)) = source {
+                copy_sources.insert(addition, (sources[source].clone(), similarity));
+            }
+        }
+    }
+
+    if rename_sources.is_empty() && copy_sources.is_empty() {
+        return changes;
+    }
+    let mut renamed = HashMap::new();
+    for (source, _) in rename_sources.values() {
+        renamed.insert(*source, None);
+    }
+    let mut out = Vec::with_capacity(changes.len() - rename_sources.len());
+    for (index, change) in changes.into_iter().enumerate() {
+        match change {
+            Change::Deletion { entry_mode, oid, path } if renamed.contains_key(&index) => {
+                renamed.insert(index, Some((entry_mode, oid, path)));
+            }
+            change => out.push((index, change)),
+        }
+    }
+    out.into_iter()
+        .map(|(index, change)| match change {
+            Change::Addition { entry_mode, oid, path } if rename_sources.contains_key(&index) => {
+                let (deletion, similarity) = rename_sources[&index];
+                let (previous_entry_mode, previous_oid, previous_path) = renamed
+                    .remove(&deletion)
+                    .flatten()
+                    .expect("each deletion is paired once");
+                Change::Rename {
+                    previous_entry_mode,
+                    previous_oid,
+                    previous_path,
+                    entry_mode,
+                    oid,
+                    path,
+                    similarity,
+                }
+            }
+            Change::Addition { entry_mode, oid, path } if copy_sources.contains_key(&index) => {
+                let (source, similarity) = copy_sources.remove(&index).expect("present");
+                Change::Copy {
+                    source_entry_mode: source.entry_mode,
+                    source_oid: source.oid,
+                    source_path: source.path,
+                    entry_mode,
+                    oid,
+                    path,
+                    similarity,
+                }
+            }
+            change => change,
+        })
+        .collect()
+}
+
+/// The content of blobs by id, obtained with `find` once.
+struct Blobs<Find> {
+    find: Find,
+    data: HashMap<ObjectId, Option<Vec<u8>>>,
+}
+
+impl<Find> Blobs<Find>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<&'b [u8]>,
+{
+    fn load(&mut self, id: ObjectId) {
+        let find = &mut self.find;
+        self.data.entry(id).or_insert_with(|| {
+            let mut buf = Vec::new();
+            find(&id, &mut buf).map(ToOwned::to_owned)
+        });
+    }
+
+    fn get(&self, id: &ObjectId) -> Option<&[u8]> {
+        self.data.get(id).and_then(|data| data.as_deref())
+    }
+}
+
+/// For each of the `targets`, find the index of the most similar of the `sources` along with its similarity as configured
+/// by `options`, with each source being used only once if `exclusive` is true.
+///
+/// Items are tuples of an arbitrary index, the entry mode and the object id of a file.
+fn pair<Find>(
+    sources: &[(usize, EntryMode, ObjectId)],
+    targets: &[(usize, EntryMode, ObjectId)],
+    exclusive: bool,
+    options: Options,
+    blobs: &mut Blobs<Find>,
+) -> Vec<Option<(usize, u8)>>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<&'b [u8]>,
+{
+    let mut out = vec![None; targets.len()];
+    let mut is_used = vec![false; sources.len()];
+    let mut sources_by_id = HashMap::<ObjectId, Vec<usize>>::new();
+    for (source, (_, _, id)) in sources.iter().enumerate() {
+        sources_by_id.entry(*id).or_default().push(source);
+    }
+    for ((_, mode, id), out) in targets.iter().zip(out.iter_mut()) {
+        let source = sources_by_id.get(id).and_then(|candidates| {
+            candidates
+                .iter()
+                .copied()
+                .find(|&source| !is_used[source] && is_compatible(sources[source].1, *mode))
+        });
+        if let Some(source) = source {
+            is_used[source] = exclusive;
+            *out = Some((source, 100));
+        }
+    }
+
+    let remaining_sources: Vec<_> = (0..sources.len()).filter(|&source| !is_used[source]).collect();
+    let remaining_targets: Vec<_> = (0..targets.len()).filter(|&target| out[target].is_none()).collect();
+    let within_limit = options.limit == 0
+        || remaining_sources.len().saturating_mul(remaining_targets.len())
+            <= options.limit.saturating_mul(options.limit);
+    if options.percentage >= 100 || remaining_sources.is_empty() || remaining_targets.is_empty() || !within_limit {
+        return out;
+    }
+
+    for &source in &remaining_sources {
+        blobs.load(sources[source].2);
+    }
+    for &target in &remaining_targets {
+        blobs.load(targets[target].2);
+    }
+    let mut candidates = Vec::new();
+    for &target in &remaining_targets {
+        let (_, target_mode, target_id) = &targets[target];
+        let target_data = match blobs.get(target_id) {
+            Some(data) => data,
+            None => continue,
+        };
+        for &source in &remaining_sources {
+            let (_, source_mode, source_id) = &sources[source];
+            let source_data = match blobs.get(source_id) {
+                Some(data) => data,
+                None => continue,
+            };
+            if !is_compatible(*source_mode, *target_mode) {
+                continue;
+            }
+            let (min, max) = (
+                source_data.len().min(target_data.len()),
+                source_data.len().max(target_data.len()),
+            );
+            if max != 0 && min * 100 / max < options.percentage as usize {
+                continue;
+            }
+            let score = similarity(source_data, target_data);
+            if score >= options.percentage {
+                candidates.push((score, target, source));
+            }
+        }
+    }
+    candidates.sort_by_key(|(score, _, _)| Reverse(*score));
+    for (score, target, source) in candidates {
+        if out[target].is_some() || is_used[source] {
+            continue;
+        }
+        is_used[source] = exclusive;
+        out[target] = Some((source, score));
+    }
+    out
+}
+
+/// Return how similar `before` and `after` are in percent, based on the amount of bytes in lines they have in common
+/// relative to the size of the larger one.
+fn similarity(before: &[u8], after: &[u8]) -> u8 {
+    let max_len = before.len().max(after.len());
+    if max_len == 0 {
+        return 100;
+    }
+    let before_lines = lines::split(before);
+    let after_lines = lines::split(after);
+    let (before_tokens, after_tokens) = lines::intern(&before_lines, &after_lines);
+    let removed: usize = lines::myers::diff(&before_tokens, &after_tokens)
+        .into_iter()
+        .flat_map(|change| before_lines[change.before].iter().map(|line| line.len()))
+        .sum();
+    ((before.len() - removed) * 100 / max_len) as u8
+}
+
+fn is_file(mode: EntryMode) -> bool {
+    matches!(mode, EntryMode::Blob | EntryMode::BlobExecutable | EntryMode::Link)
+}
+
+fn is_compatible(lhs: EntryMode, rhs: EntryMode) -> bool {
+    (lhs == EntryMode::Link) == (rhs == EntryMode::Link)
+}
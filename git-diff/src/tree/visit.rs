@@ -38,11 +38,18 @@ pub enum Action {
     /// Continue the traversal of changes.
     Continue,
     /// Stop the traversal of changes, making this te last call to [visit(…)][Visit::visit()].
+    ///
+    /// The traversal fails with [`Error::Cancelled`][crate::tree::changes::Error::Cancelled].
     Cancel,
+    /// Stop the traversal of changes successfully, making this the last call to [visit(…)][Visit::visit()].
+    ///
+    /// Use it if the changes seen so far are all that is needed, for example to learn if there are any changes at all
+    /// or to obtain only the first few of them.
+    Stop,
 }
 
 impl Action {
-    /// Returns true if this action means to stop the traversal.
+    /// Returns true if this action means to stop the traversal with an error.
     pub fn cancelled(&self) -> bool {
         matches!(self, Action::Cancel)
     }
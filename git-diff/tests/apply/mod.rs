@@ -0,0 +1,471 @@
+use git_diff::{
+    apply,
+    lines::{Hunk, Line},
+};
+use git_object::bstr::ByteSlice;
+
+mod parse {
+    use git_diff::{
+        apply::{self, File},
+        lines::{Hunk, Line},
+    };
+    use git_object::{bstr::ByteSlice, tree::EntryMode};
+
+    #[test]
+    fn git_patches_with_extended_headers() -> crate::Result {
+        let patch = br#"From: Someone
+Subject: [PATCH] all kinds of changes
+
+---
+diff --git a/modified b/modified
+index 89ab80d..55ea8f1 100644
+--- a/modified
++++ b/modified
+@@ -1,2 +1,2 @@ fn main() {
+ same
+-before
+\ No newline at end of file
++after
+diff --git a/script b/script
+old mode 100644
+new mode 100755
+diff --git a/new b/new
+new file mode 100644
+index 0000000..3e75765
+--- /dev/null
++++ b/new
+@@ -0,0 +1 @@
++new
+diff --git a/gone b/gone
+deleted file mode 100755
+index 286c5f5..0000000
+--- a/gone
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/empty b/empty
+new file mode 100644
+index 0000000..e69de29
+diff --git "a/t\303\244b\tl\"e" "b/t\303\244b\tl\"e2"
+similarity index 90%
+rename from "t\303\244b\tl\"e"
+rename to "t\303\244b\tl\"e2"
+diff --git a/source b/copy
+similarity index 100%
+copy from source
+copy to copy
+diff --git a/binary b/binary
+index 87ae6b6..481be20 100644
+Binary files a/binary and b/binary differ
+-- 
+2.31.1
+"#;
+        let files = apply::parse(patch)?;
+        assert_eq!(
+            files[0],
+            File {
+                previous_path: Some("modified".into()),
+                path: Some("modified".into()),
+                previous_mode: Some(EntryMode::Blob),
+                mode: Some(EntryMode::Blob),
+                hunks: vec![Hunk {
+                    before: 0..2,
+                    after: 0..2,
+                    lines: vec![
+                        Line::Context(b"same\n".as_bstr()),
+                        Line::Removed(b"before".as_bstr()),
+                        Line::Added(b"after\n".as_bstr())
+                    ]
+                }],
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            files[1],
+            File {
+                previous_path: Some("script".into()),
+                path: Some("script".into()),
+                previous_mode: Some(EntryMode::Blob),
+                mode: Some(EntryMode::BlobExecutable),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            files[2],
+            File {
+                path: Some("new".into()),
+                mode: Some(EntryMode::Blob),
+                hunks: vec![Hunk {
+                    before: 0..0,
+                    after: 0..1,
+                    lines: vec![Line::Added(b"new\n".as_bstr())]
+                }],
+                ..Default::default()
+            }
+        );
+        assert_eq!(files[3].previous_path, Some("gone".into()));
+        assert_eq!(files[3].path, None);
+        assert_eq!(files[3].previous_mode, Some(EntryMode::BlobExecutable));
+        assert_eq!(files[3].hunks[0].before, 0..1);
+        assert_eq!(
+            files[4],
+            File {
+                path: Some("empty".into()),
+                mode: Some(EntryMode::Blob),
+                ..Default::default()
+            }
+        );
+        assert_eq!(files[5].previous_path, Some("t\u{e4}b\tl\"e".into()));
+        assert_eq!(files[5].path, Some("t\u{e4}b\tl\"e2".into()));
+        assert!(!files[5].copy);
+        assert_eq!(files[6].previous_path, Some("source".into()));
+        assert_eq!(files[6].path, Some("copy".into()));
+        assert!(files[6].copy);
+        assert!(files[7].binary);
+        assert_eq!(files.len(), 8);
+        Ok(())
+    }
+
+    #[test]
+    fn plain_unified_diffs() -> crate::Result {
+        let files = apply::parse(
+            b"--- a/file\t2021-01-01 00:00:00\n+++ b/file\t2021-01-02 00:00:00\n@@ -1 +1,2 @@\n a\n+b\n\n",
+        )?;
+        assert_eq!(
+            files,
+            vec![File {
+                previous_path: Some("file".into()),
+                path: Some("file".into()),
+                hunks: vec![Hunk {
+                    before: 0..1,
+                    after: 0..2,
+                    lines: vec![Line::Context(b"a\n".as_bstr()), Line::Added(b"b\n".as_bstr())]
+                }],
+                ..Default::default()
+            }],
+            "the empty line after the hunk isn't part of it as all lines were seen"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_patches_are_errors() {
+        for (patch, expected) in &[
+            (
+                &b"--- a/f\n+++ b/f\n@@ -1 +1 @@\n"[..],
+                "The hunk starting in line 3 ended before all of its lines were seen",
+            ),
+            (
+                &b"--- a/f\n+++ b/f\n@@ -x +1 @@\n"[..],
+                "The hunk header in line 3 is malformed",
+            ),
+            (
+                &b"--- /dev/null\n+++ /dev/null\n"[..],
+                "The patch of the file starting in line 1 does not name the file",
+            ),
+            (
+                &b"diff --git a/x b/y\nindex 1..2\n"[..],
+                "The patch of the file starting in line 1 does not name the file",
+            ),
+        ] {
+            assert_eq!(apply::parse(patch).expect_err("invalid").to_string(), *expected);
+        }
+    }
+}
+
+fn hunk(before_start: usize, lines: &[(char, &'static str)]) -> Hunk<'static> {
+    let before_len = lines.iter().filter(|(kind, _)| *kind != '+').count();
+    let after_len = lines.iter().filter(|(kind, _)| *kind != '-').count();
+    Hunk {
+        before: before_start..before_start + before_len,
+        after: before_start..before_start + after_len,
+        lines: lines
+            .iter()
+            .map(|(kind, line)| match kind {
+                ' ' => Line::Context(line.as_bytes().as_bstr()),
+                '-' => Line::Removed(line.as_bytes().as_bstr()),
+                _ => Line::Added(line.as_bytes().as_bstr()),
+            })
+            .collect(),
+    }
+}
+
+fn numbered(range: std::ops::Range<usize>) -> String {
+    range.map(|n| format!("{}\n", n)).collect()
+}
+
+#[test]
+fn hunks_apply_at_their_position_or_with_an_offset() -> crate::Result {
+    let change = hunk(
+        3,
+        &[(' ', "3\n"), (' ', "4\n"), ('-', "5\n"), ('+', "five\n"), (' ', "6\n")],
+    );
+    let mut out = Vec::new();
+    let applied = apply::hunks(
+        numbered(0..10).as_bytes(),
+        &[change.clone()],
+        Default::default(),
+        &mut out,
+    )?;
+    assert_eq!(out.as_bstr(), numbered(0..10).replace("5\n", "five\n"));
+    assert_eq!(
+        applied,
+        vec![apply::Applied {
+            line: 3,
+            offset: 0,
+            fuzz: 0
+        }]
+    );
+
+    let applied = apply::hunks(
+        numbered(2..10).as_bytes(),
+        &[change.clone()],
+        Default::default(),
+        &mut out,
+    )?;
+    assert_eq!(out.as_bstr(), numbered(2..10).replace("5\n", "five\n"));
+    assert_eq!(applied[0].offset, -2, "found two lines earlier");
+
+    let second = hunk(7, &[(' ', "7\n"), ('+', "seven and a half\n"), (' ', "8\n")]);
+    let applied = apply::hunks(
+        format!("new\n{}", numbered(0..10)).as_bytes(),
+        &[change, second],
+        Default::default(),
+        &mut out,
+    )?;
+    assert_eq!(
+        out.as_bstr(),
+        format!("new\n{}", numbered(0..10))
+            .replace("5\n", "five\n")
+            .replace("8\n", "seven and a half\n8\n")
+    );
+    assert_eq!(
+        applied.iter().map(|a| (a.line, a.offset)).collect::<Vec<_>>(),
+        vec![(4, 1), (8, 1)]
+    );
+    Ok(())
+}
+
+#[test]
+fn context_mismatches_need_fuzz() -> crate::Result {
+    let change = hunk(
+        3,
+        &[(' ', "3\n"), (' ', "4\n"), ('-', "5\n"), ('+', "five\n"), (' ', "6\n")],
+    );
+    let data = numbered(0..10).replace("3\n", "three\n");
+    let mut out = Vec::new();
+    let err = apply::hunks(data.as_bytes(), &[change.clone()], Default::default(), &mut out).expect_err("mismatch");
+    assert_eq!(err, apply::Mismatch { hunk: 0, line: 3 });
+    assert_eq!(
+        err.to_string(),
+        "Hunk 1 does not apply as its context doesn't match at or near line 4"
+    );
+
+    let applied = apply::hunks(data.as_bytes(), &[change], apply::Options { max_fuzz: 1 }, &mut out)?;
+    assert_eq!(out.as_bstr(), data.replace("5\n", "five\n"));
+    assert_eq!(
+        applied,
+        vec![apply::Applied {
+            line: 4,
+            offset: 0,
+            fuzz: 1
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn hunks_at_the_beginning_or_end_must_match_there() -> crate::Result {
+    let at_end = hunk(8, &[(' ', "8\n"), (' ', "9\n"), ('+', "10\n")]);
+    let mut out = Vec::new();
+    apply::hunks(
+        numbered(0..10).as_bytes(),
+        &[at_end.clone()],
+        Default::default(),
+        &mut out,
+    )?;
+    assert_eq!(out.as_bstr(), numbered(0..11));
+    assert!(
+        apply::hunks(numbered(0..11).as_bytes(), &[at_end], Default::default(), &mut out).is_err(),
+        "the context must be at the end of the file"
+    );
+
+    let at_beginning = hunk(0, &[('+', "start\n"), (' ', "0\n")]);
+    assert!(apply::hunks(
+        format!("x\n{}", numbered(0..3)).as_bytes(),
+        &[at_beginning.clone()],
+        Default::default(),
+        &mut out
+    )
+    .is_err());
+    apply::hunks(numbered(0..3).as_bytes(), &[at_beginning], Default::default(), &mut out)?;
+    assert_eq!(out.as_bstr(), format!("start\n{}", numbered(0..3)));
+    Ok(())
+}
+
+mod tree {
+    use git_diff::{apply, patch, tree};
+    use git_hash::ObjectId;
+    use git_object::{immutable, mutable, tree::EntryMode, Kind};
+    use std::{cell::RefCell, collections::HashMap};
+
+    #[derive(Default)]
+    struct Odb(RefCell<HashMap<ObjectId, Vec<u8>>>);
+
+    impl Odb {
+        fn write(&self, kind: Kind, data: &[u8]) -> ObjectId {
+            let mut header = [0u8; 32];
+            let header_len = git_object::loose::header::encode(kind, data.len() as u64, &mut header[..]).expect("fits");
+            let mut hasher = git_features::hash::Sha1::default();
+            hasher.update(&header[..header_len]);
+            hasher.update(data);
+            let id = ObjectId::new_sha1(hasher.digest());
+            self.0.borrow_mut().insert(id, data.to_owned());
+            id
+        }
+
+        fn blob(&self, data: &str) -> ObjectId {
+            self.write(Kind::Blob, data.as_bytes())
+        }
+
+        fn tree(&self, entries: &[(&str, EntryMode, ObjectId)]) -> ObjectId {
+            let mut tree = mutable::Tree {
+                entries: entries
+                    .iter()
+                    .map(|(filename, mode, oid)| mutable::tree::Entry {
+                        mode: *mode,
+                        filename: (*filename).into(),
+                        oid: *oid,
+                    })
+                    .collect(),
+            };
+            tree.entries.sort();
+            self.write_tree(&tree)
+        }
+
+        fn write_tree(&self, tree: &mutable::Tree) -> ObjectId {
+            let mut buf = Vec::new();
+            tree.write_to(&mut buf).expect("valid tree");
+            self.write(Kind::Tree, &buf)
+        }
+
+        fn find<'b>(&self, id: &git_hash::oid, buf: &'b mut Vec<u8>) -> Option<&'b [u8]> {
+            buf.clear();
+            buf.extend_from_slice(self.0.borrow().get(&id.to_owned())?);
+            Some(buf)
+        }
+
+        fn diff(&self, lhs: ObjectId, rhs: ObjectId) -> Vec<u8> {
+            let lhs_data = self.0.borrow()[&lhs].clone();
+            let rhs_data = self.0.borrow()[&rhs].clone();
+            let mut recorder = tree::Recorder::default();
+            tree::Changes::from(immutable::TreeIter::from_bytes(&lhs_data))
+                .needed_to_obtain(
+                    immutable::TreeIter::from_bytes(&rhs_data),
+                    tree::State::default(),
+                    |id, buf| self.find(id, buf).map(immutable::TreeIter::from_bytes),
+                    &mut recorder,
+                )
+                .expect("trees present");
+            let changes = tree::rewrites::detect(recorder.records, Default::default(), |id, buf| self.find(id, buf));
+            let mut out = Vec::new();
+            patch::write(&mut out, &changes, Default::default(), |id, buf| self.find(id, buf)).expect("blobs present");
+            out
+        }
+
+        fn apply(&self, root: ObjectId, patch: &[u8]) -> Result<apply::tree::Outcome, apply::tree::Error> {
+            apply::tree(
+                &root,
+                &apply::parse(patch).expect("valid patch"),
+                Default::default(),
+                |id, buf| self.find(id, buf).map(immutable::TreeIter::from_bytes),
+                |id, buf| self.find(id, buf),
+                |data| Ok::<_, std::io::Error>(self.write(Kind::Blob, data)),
+                |tree| Ok::<_, std::io::Error>(self.write_tree(tree)),
+            )
+        }
+    }
+
+    #[test]
+    fn patches_produced_by_diffing_trees_turn_one_tree_into_the_other() {
+        let odb = Odb::default();
+        let text: String = (0..20).map(|n| format!("line {}\n", n)).collect();
+        let before = odb.tree(&[
+            ("modified", EntryMode::Blob, odb.blob("a\nb\nc\n")),
+            ("deleted", EntryMode::Blob, odb.blob("gone\n")),
+            ("script", EntryMode::Blob, odb.blob("#!/bin/sh\n")),
+            (
+                "dir",
+                EntryMode::Tree,
+                odb.tree(&[("renamed", EntryMode::Blob, odb.blob(&text))]),
+            ),
+        ]);
+        let after = odb.tree(&[
+            ("modified", EntryMode::Blob, odb.blob("a\nB\nc")),
+            ("added", EntryMode::Blob, odb.blob("new\n")),
+            ("script", EntryMode::BlobExecutable, odb.blob("#!/bin/sh\nexit 0\n")),
+            (
+                "other",
+                EntryMode::Tree,
+                odb.tree(&[(
+                    "renamed",
+                    EntryMode::Blob,
+                    odb.blob(&text.replace("line 10\n", "line ten\n")),
+                )]),
+            ),
+        ]);
+
+        let patch = odb.diff(before, after);
+        assert!(
+            patch.windows(b"rename from".len()).any(|w| w == b"rename from"),
+            "the patch contains a rename"
+        );
+        let outcome = odb.apply(before, &patch).expect("patch applies");
+        assert_eq!(outcome.id, after);
+        assert_eq!(outcome.applied.len(), 5);
+        assert!(patch.windows(b"new mode".len()).any(|w| w == b"new mode"));
+
+        let err = odb.apply(after, &patch).expect_err("already applied");
+        assert_eq!(err.to_string(), "The file 'added' to create already exists");
+    }
+
+    #[test]
+    fn files_are_checked_before_patching() {
+        let odb = Odb::default();
+        let root = odb.tree(&[("file", EntryMode::Blob, odb.blob("content\n"))]);
+        for (patch, expected) in &[
+            (
+                &b"diff --git a/file b/file\nnew file mode 100644\n"[..],
+                "The file 'file' to create already exists",
+            ),
+            (
+                &b"diff --git a/missing b/missing\nold mode 100644\nnew mode 100755\n"[..],
+                "The file 'missing' to patch does not exist",
+            ),
+            (
+                &b"diff --git a/file b/file\ndeleted file mode 100644\n"[..],
+                "The file 'file' to delete has content the patch does not remove",
+            ),
+            (
+                &b"diff --git a/file b/file\nindex 1..2 100644\nBinary files a/file and b/file differ\n"[..],
+                "The binary file 'file' cannot be patched as the patch doesn't contain its content",
+            ),
+        ] {
+            assert_eq!(odb.apply(root, patch).expect_err("invalid").to_string(), *expected);
+        }
+
+        let missing_tree = odb.tree(&[("file", EntryMode::Blob, odb.blob("content\n"))]);
+        let root = odb.tree(&[("dir", EntryMode::Tree, missing_tree)]);
+        odb.0.borrow_mut().remove(&missing_tree);
+        let err = odb
+            .apply(
+                root,
+                b"diff --git a/dir/file b/dir/file\nold mode 100644\nnew mode 100755\n",
+            )
+            .expect_err("the tree of 'dir' is missing");
+        assert!(matches!(
+            err,
+            apply::tree::Error::LoadTree(immutable::tree::lookup::Error::FindTree { .. })
+        ));
+    }
+}
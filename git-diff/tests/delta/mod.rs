@@ -0,0 +1,119 @@
+use git_diff::delta;
+
+fn roundtrip(base: &[u8], target: &[u8]) -> usize {
+    let mut encoded = Vec::new();
+    delta::encode(base, target, &mut encoded);
+    let mut out = Vec::new();
+    delta::apply(base, &encoded, &mut out).expect("valid delta");
+    assert_eq!(out, target);
+    encoded.len()
+}
+
+/// Pseudo-random bytes, as the content of real binary files compresses badly.
+fn noise(seed: u32, len: usize) -> Vec<u8> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn apply_copies_and_inserts() -> crate::Result {
+    let base = b"hello world";
+    let delta = [
+        11,          // base size
+        10,          // result size
+        0b1001_0001, // copy with one byte of offset and size
+        6,
+        5,
+        5, // insert 5 bytes
+        b',',
+        b' ',
+        b'a',
+        b'l',
+        b'l',
+    ];
+    let mut out = Vec::new();
+    delta::apply(base, &delta, &mut out)?;
+    assert_eq!(out, b"world, all");
+    Ok(())
+}
+
+#[test]
+fn header_sizes_roundtrip() {
+    for &size in &[0u64, 1, 0x7f, 0x80, 0x3fff, 0x4000, u32::MAX as u64, u64::MAX] {
+        let mut buf = Vec::new();
+        delta::encode_header_size(size, &mut buf);
+        assert_eq!(delta::decode_header_size(&buf), (size, buf.len()));
+    }
+}
+
+#[test]
+fn encode_copies_what_is_in_the_base() {
+    let base = noise(1, 100_000);
+    let mut target = base.clone();
+    target[50_000] ^= 0xff;
+    target.splice(20_000..20_000, b"inserted".iter().copied());
+    target.drain(80_000..80_100);
+    target.extend_from_slice(&base[..1000]);
+    let len = roundtrip(&base, &target);
+    assert!(len < 100, "mostly copies, got {} bytes", len);
+
+    let len = roundtrip(&base, &base);
+    assert!(len < 20, "copies are split at 64KiB, got {} bytes", len);
+}
+
+#[test]
+fn encode_inserts_what_is_not_in_the_base() {
+    let target = noise(2, 1000);
+    let len = roundtrip(&noise(3, 1000), &target);
+    assert!(
+        len > 1000 && len < 1020,
+        "all inserted with a small overhead, got {}",
+        len
+    );
+    roundtrip(b"", &target);
+    roundtrip(&target, b"");
+    roundtrip(b"", b"");
+    roundtrip(b"short", b"shorter");
+}
+
+#[test]
+fn encode_works_with_repetitive_data() {
+    let base = vec![0u8; 200_000];
+    let mut target = vec![0u8; 300_000];
+    target[150_000] = 1;
+    let len = roundtrip(&base, &target);
+    assert!(len < 50, "got {} bytes", len);
+}
+
+#[test]
+fn invalid_deltas_are_errors() {
+    let mut out = Vec::new();
+    let base = b"base";
+    for (delta, expected) in &[
+        (&[4u8, 0x80][..], "The delta ended unexpectedly"),
+        (&[5, 0][..], "The delta expects a base of 5 bytes, but it has 4 bytes"),
+        (&[4, 2, 0][..], "The delta contains the reserved instruction 0"),
+        (&[4, 2, 3, b'a'][..], "The delta ended unexpectedly"),
+        (
+            &[4, 2, 0b1001_0001, 3, 2][..],
+            "Cannot copy 2 bytes at offset 3 from a base of 4 bytes",
+        ),
+        (&[4, 2, 0b1001_0001][..], "The delta ended unexpectedly"),
+        (
+            &[4, 2, 1, b'a'][..],
+            "The delta doesn't produce the 2 bytes it claims to",
+        ),
+        (
+            &[4, 2, 3, b'a', b'b', b'c'][..],
+            "The delta doesn't produce the 2 bytes it claims to",
+        ),
+    ] {
+        let err = delta::apply(base, delta, &mut out).expect_err("invalid");
+        assert_eq!(err.to_string(), *expected, "{:?}", delta);
+    }
+}
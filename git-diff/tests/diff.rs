@@ -1,5 +1,76 @@
+use git_hash::{oid, ObjectId};
+use git_object::{immutable, mutable, tree::EntryMode};
+use std::collections::HashMap;
+
 pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 pub use git_testtools::hex_to_id;
 
+mod apply;
+mod delta;
+mod index;
+mod index_worktree;
+mod lines;
+mod many;
+mod patch;
+mod rewrites;
+mod stats;
+mod textconv;
 mod visit;
+
+/// Blobs kept in memory to serve as object database.
+#[derive(Default)]
+pub struct Blobs(HashMap<ObjectId, Vec<u8>>);
+
+impl Blobs {
+    /// Add a blob with `data` and return its made-up id.
+    pub fn add(&mut self, data: impl AsRef<[u8]>) -> ObjectId {
+        self.insert(ObjectId::from_20_bytes(&[self.0.len() as u8 + 1; 20]), data)
+    }
+
+    /// Add a blob with `data` under the given `id`, for when the id is visible in the output, and return `id`.
+    pub fn insert(&mut self, id: ObjectId, data: impl AsRef<[u8]>) -> ObjectId {
+        self.0.insert(id, data.as_ref().into());
+        id
+    }
+
+    /// Load the blob with `id` into `buf`.
+    pub fn find<'b>(&self, id: &oid, buf: &'b mut Vec<u8>) -> Option<&'b [u8]> {
+        buf.clear();
+        buf.extend_from_slice(self.0.get(&id.to_owned())?);
+        Some(buf.as_slice())
+    }
+}
+
+/// Trees kept in memory to serve as object database.
+#[derive(Default)]
+pub struct Trees(HashMap<ObjectId, Vec<u8>>);
+
+impl Trees {
+    /// Add a tree with `entries` in any order and return its made-up id.
+    pub fn add(&mut self, entries: &[(&str, EntryMode, ObjectId)]) -> ObjectId {
+        let mut tree = mutable::Tree {
+            entries: entries
+                .iter()
+                .map(|(filename, mode, oid)| mutable::tree::Entry {
+                    mode: *mode,
+                    filename: (*filename).into(),
+                    oid: *oid,
+                })
+                .collect(),
+        };
+        tree.entries.sort();
+        let mut buf = Vec::new();
+        tree.write_to(&mut buf).expect("valid tree");
+        let id = ObjectId::from_20_bytes(&[0xf0 - self.0.len() as u8; 20]);
+        self.0.insert(id, buf);
+        id
+    }
+
+    /// Load the tree with `id` into `buf`.
+    pub fn find<'b>(&self, id: &oid, buf: &'b mut Vec<u8>) -> Option<immutable::TreeIter<'b>> {
+        buf.clear();
+        buf.extend_from_slice(self.0.get(&id.to_owned())?);
+        Some(immutable::TreeIter::from_bytes(buf))
+    }
+}
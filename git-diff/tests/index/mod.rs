@@ -0,0 +1,131 @@
+use crate::Trees;
+use git_diff::{index, tree::recorder::Change};
+use git_hash::ObjectId;
+use git_object::{immutable, tree::EntryMode};
+
+impl Trees {
+    fn changes(&self, tree: Option<ObjectId>, index: &[index::Entry]) -> Vec<Change> {
+        index::needed_to_obtain(
+            tree.map(|id| immutable::TreeIter::from_bytes(&self.0[&id])),
+            index,
+            |id, buf| self.find(id, buf),
+        )
+        .expect("all trees present")
+    }
+}
+
+fn blob(n: u8) -> ObjectId {
+    ObjectId::from_20_bytes(&[n; 20])
+}
+
+fn entry(path: &str, mode: EntryMode, oid: ObjectId) -> index::Entry {
+    index::Entry {
+        mode,
+        oid,
+        path: path.into(),
+    }
+}
+
+#[test]
+fn without_tree_all_entries_are_additions() {
+    let index = vec![
+        entry("a", EntryMode::Blob, blob(1)),
+        entry("d/b", EntryMode::BlobExecutable, blob(2)),
+    ];
+    assert_eq!(
+        Trees::default().changes(None, &index),
+        vec![
+            Change::Addition {
+                entry_mode: EntryMode::Blob,
+                oid: blob(1),
+                path: "a".into()
+            },
+            Change::Addition {
+                entry_mode: EntryMode::BlobExecutable,
+                oid: blob(2),
+                path: "d/b".into()
+            }
+        ]
+    );
+}
+
+#[test]
+fn an_index_matching_the_tree_has_no_changes() {
+    let mut trees = Trees::default();
+    let sub = trees.add(&[("b", EntryMode::Blob, blob(2)), ("c", EntryMode::Link, blob(3))]);
+    let root = trees.add(&[
+        ("a.rs", EntryMode::Blob, blob(1)),
+        ("a", EntryMode::Tree, sub),
+        ("a0", EntryMode::Commit, blob(4)),
+    ]);
+    let index = vec![
+        entry("a.rs", EntryMode::Blob, blob(1)),
+        entry("a/b", EntryMode::Blob, blob(2)),
+        entry("a/c", EntryMode::Link, blob(3)),
+        entry("a0", EntryMode::Commit, blob(4)),
+    ];
+    assert_eq!(trees.changes(Some(root), &index), vec![]);
+    assert_eq!(
+        trees.changes(Some(root), &[]).len(),
+        4,
+        "all files of the tree are deleted in an empty index"
+    );
+}
+
+#[test]
+fn modifications_additions_and_deletions_of_files_in_nested_trees() {
+    let mut trees = Trees::default();
+    let deep = trees.add(&[("c", EntryMode::Blob, blob(3))]);
+    let sub = trees.add(&[
+        ("a", EntryMode::Blob, blob(1)),
+        ("b", EntryMode::Tree, deep),
+        ("x", EntryMode::Blob, blob(5)),
+    ]);
+    let root = trees.add(&[("d", EntryMode::Tree, sub), ("f", EntryMode::Blob, blob(4))]);
+    let index = vec![
+        entry("d/a", EntryMode::BlobExecutable, blob(1)),
+        entry("d/b/c", EntryMode::Blob, blob(6)),
+        entry("d/b/new", EntryMode::Blob, blob(7)),
+        entry("f/g", EntryMode::Blob, blob(4)),
+    ];
+    assert_eq!(
+        trees.changes(Some(root), &index),
+        vec![
+            Change::Modification {
+                previous_entry_mode: EntryMode::Blob,
+                previous_oid: blob(1),
+                entry_mode: EntryMode::BlobExecutable,
+                oid: blob(1),
+                path: "d/a".into()
+            },
+            Change::Modification {
+                previous_entry_mode: EntryMode::Blob,
+                previous_oid: blob(3),
+                entry_mode: EntryMode::Blob,
+                oid: blob(6),
+                path: "d/b/c".into()
+            },
+            Change::Addition {
+                entry_mode: EntryMode::Blob,
+                oid: blob(7),
+                path: "d/b/new".into()
+            },
+            Change::Deletion {
+                entry_mode: EntryMode::Blob,
+                oid: blob(5),
+                path: "d/x".into()
+            },
+            Change::Deletion {
+                entry_mode: EntryMode::Blob,
+                oid: blob(4),
+                path: "f".into()
+            },
+            Change::Addition {
+                entry_mode: EntryMode::Blob,
+                oid: blob(4),
+                path: "f/g".into()
+            },
+        ],
+        "a file turning into a directory is a deletion followed by additions"
+    );
+}
@@ -0,0 +1,289 @@
+use crate::hex_to_id;
+use git_diff::index_worktree::{self, Change, Entry, Options, Stat, Time};
+use git_hash::ObjectId;
+use git_object::tree::EntryMode;
+use std::{fs, path::Path};
+
+fn hello() -> ObjectId {
+    hex_to_id("ce013625030ba8dba906f756967f9e9ca394464a")
+}
+
+/// An entry for the file at `path` as it would be written into the index right now.
+fn entry(root: &Path, path: &str, mode: EntryMode, oid: ObjectId) -> Entry {
+    let meta = fs::symlink_metadata(root.join(path)).expect("file exists");
+    Entry {
+        mode,
+        oid,
+        path: path.into(),
+        stat: Stat::from_metadata(&meta),
+    }
+}
+
+fn changes(root: &Path, entries: &[Entry], options: Options) -> Vec<Change> {
+    index_worktree::changes(root, entries, options).expect("no IO error")
+}
+
+fn modification(path: &str, previous_entry_mode: EntryMode, entry_mode: EntryMode, oid: Option<ObjectId>) -> Change {
+    Change::Modification {
+        previous_entry_mode,
+        previous_oid: hello(),
+        entry_mode,
+        oid,
+        path: path.into(),
+    }
+}
+
+#[test]
+fn files_with_unchanged_stat_information_are_not_read_unless_racily_clean() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a"), "hello\n")?;
+    let mut a = entry(dir.path(), "a", EntryMode::Blob, hello());
+    assert_eq!(changes(dir.path(), &[a.clone()], Options::default()), vec![]);
+
+    let index_mtime = a.stat.mtime;
+    fs::write(dir.path().join("a"), "world\n")?;
+    a.stat = entry(dir.path(), "a", EntryMode::Blob, hello()).stat;
+    assert_eq!(
+        changes(dir.path(), &[a.clone()], Options::default()),
+        vec![],
+        "the stat information doesn't show the change, so the file isn't looked at"
+    );
+    assert_eq!(
+        changes(
+            dir.path(),
+            &[a.clone()],
+            Options {
+                index_mtime: Some(index_mtime),
+                ..Default::default()
+            }
+        ),
+        vec![modification(
+            "a",
+            EntryMode::Blob,
+            EntryMode::Blob,
+            Some(hex_to_id("cc628ccd10742baea8241c5924df992b5c019f71"))
+        )],
+        "the file was modified when the index was written, so it's hashed to see if it changed"
+    );
+    Ok(())
+}
+
+#[test]
+fn files_are_hashed_if_only_their_timestamps_changed() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("same"), "hello\n")?;
+    fs::write(dir.path().join("changed"), "world\n")?;
+    let mut entries = vec![
+        entry(dir.path(), "changed", EntryMode::Blob, hello()),
+        entry(dir.path(), "same", EntryMode::Blob, hello()),
+    ];
+    for entry in &mut entries {
+        entry.stat.mtime = Time::default();
+    }
+    assert_eq!(
+        changes(dir.path(), &entries, Options::default()),
+        vec![modification(
+            "changed",
+            EntryMode::Blob,
+            EntryMode::Blob,
+            Some(hex_to_id("cc628ccd10742baea8241c5924df992b5c019f71"))
+        )]
+    );
+    Ok(())
+}
+
+#[test]
+fn files_are_hashed_with_the_hash_kind_of_their_entry() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a"), "hello\n")?;
+    let mut a = entry(
+        dir.path(),
+        "a",
+        EntryMode::Blob,
+        ObjectId::null_sha(git_hash::Kind::Sha256),
+    );
+    assert_eq!(
+        changes(dir.path(), &[a.clone()], Options::default()),
+        vec![],
+        "files aren't hashed if their stat information matches"
+    );
+
+    a.stat.mtime = Time::default();
+    let err = index_worktree::changes(dir.path(), &[a], Options::default()).expect_err("Sha256 isn't supported");
+    assert_eq!(
+        std::error::Error::source(&err).expect("IO error").to_string(),
+        "Sha256 hashes cannot be computed yet"
+    );
+    Ok(())
+}
+
+#[test]
+fn files_with_a_different_size_are_modified_without_hashing() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a"), "hello\n")?;
+    let a = entry(dir.path(), "a", EntryMode::Blob, hello());
+    fs::write(dir.path().join("a"), "hello world\n")?;
+    assert_eq!(
+        changes(dir.path(), &[a], Options::default()),
+        vec![modification("a", EntryMode::Blob, EntryMode::Blob, None)]
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_files_and_files_replaced_by_directories_are_deletions() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    for path in &["dir", "gone", "parent"] {
+        fs::write(dir.path().join(path), "hello\n")?;
+    }
+    let mut entries = vec![
+        entry(dir.path(), "dir", EntryMode::Blob, hello()),
+        entry(dir.path(), "gone", EntryMode::Blob, hello()),
+        entry(dir.path(), "parent", EntryMode::Blob, hello()),
+    ];
+    entries.push(Entry {
+        path: "parent/file".into(),
+        ..entries[2].clone()
+    });
+    fs::remove_file(dir.path().join("dir"))?;
+    fs::create_dir(dir.path().join("dir"))?;
+    fs::remove_file(dir.path().join("gone"))?;
+
+    let deletion = |path: &str| Change::Deletion {
+        entry_mode: EntryMode::Blob,
+        oid: hello(),
+        path: path.into(),
+    };
+    assert_eq!(
+        changes(dir.path(), &entries, Options::default()),
+        vec![deletion("dir"), deletion("gone"), deletion("parent/file")]
+    );
+    Ok(())
+}
+
+#[test]
+fn submodules_are_ignored() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let submodule = Entry {
+        mode: EntryMode::Commit,
+        oid: hello(),
+        path: "missing".into(),
+        stat: Stat::default(),
+    };
+    assert_eq!(changes(dir.path(), &[submodule], Options::default()), vec![]);
+    Ok(())
+}
+
+#[test]
+fn changes_are_in_order_of_entries_when_checked_in_parallel() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let entries: Vec<_> = (0..1000)
+        .map(|n| Entry {
+            mode: EntryMode::Blob,
+            oid: hello(),
+            path: format!("{:04}", n).into(),
+            stat: Stat::default(),
+        })
+        .collect();
+    let paths: Vec<_> = changes(
+        dir.path(),
+        &entries,
+        Options {
+            thread_limit: Some(4),
+            ..Default::default()
+        },
+    )
+    .into_iter()
+    .map(|change| match change {
+        Change::Deletion { path, .. } => path,
+        Change::Modification { .. } => unreachable!("all files are missing"),
+    })
+    .collect();
+    assert_eq!(paths, entries.into_iter().map(|e| e.path).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::{changes, entry, hello, modification};
+    use git_diff::index_worktree::{Options, Policy};
+    use git_features::fs::Capabilities;
+    use git_object::tree::EntryMode;
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    #[test]
+    fn the_executable_bit_is_compared_if_enabled() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("a");
+        fs::write(&path, "hello\n")?;
+        let a = entry(dir.path(), "a", EntryMode::Blob, hello());
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+
+        assert_eq!(
+            changes(dir.path(), &[a.clone()], Options::default()),
+            vec![modification("a", EntryMode::Blob, EntryMode::BlobExecutable, None)]
+        );
+        assert_eq!(
+            changes(
+                dir.path(),
+                &[a],
+                Options {
+                    policy: Policy {
+                        capabilities: Capabilities {
+                            executable_bit: false,
+                            ..Capabilities::default()
+                        },
+                        trust_ctime: false,
+                        ..Policy::default()
+                    },
+                    ..Default::default()
+                }
+            ),
+            vec![],
+            "like core.fileMode=false, with the permission change also affecting the ctime"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_links_are_hashed_by_their_target() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        std::os::unix::fs::symlink("hello\n", dir.path().join("link"))?;
+        let mut link = entry(dir.path(), "link", EntryMode::Link, hello());
+        link.stat.mtime = Default::default();
+        assert_eq!(changes(dir.path(), &[link.clone()], Options::default()), vec![]);
+
+        let file = super::Entry {
+            mode: EntryMode::Blob,
+            ..link
+        };
+        assert_eq!(
+            changes(dir.path(), &[file], Options::default()),
+            vec![modification("link", EntryMode::Blob, EntryMode::Link, None)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_links_checked_out_as_files_are_unchanged_if_symlinks_are_not_supported() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("link"), "hello\n")?;
+        let link = entry(dir.path(), "link", EntryMode::Link, hello());
+        let without_symlinks = Options {
+            policy: Policy {
+                capabilities: Capabilities {
+                    symlink: false,
+                    ..Capabilities::default()
+                },
+                ..Policy::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(changes(dir.path(), &[link.clone()], without_symlinks), vec![]);
+        assert_eq!(
+            changes(dir.path(), &[link], Options::default()),
+            vec![modification("link", EntryMode::Link, EntryMode::Blob, None)]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,545 @@
+use git_diff::lines::{self, Hunk, Line::*, Options};
+use git_object::bstr::ByteSlice;
+
+fn numbered(count: usize) -> String {
+    (1..=count).map(|n| format!("{}\n", n)).collect()
+}
+
+/// Replace the line `from` with `to` in `lines`, or remove it if `to` is empty.
+fn edit(lines: &str, from: &str, to: &str) -> String {
+    lines
+        .lines()
+        .filter_map(|line| match line {
+            line if line == from && to.is_empty() => None,
+            line if line == from => Some(format!("{}\n", to)),
+            line => Some(format!("{}\n", line)),
+        })
+        .collect()
+}
+
+#[test]
+fn same_input_yields_no_hunks() {
+    assert!(lines::diff(b"a\nb\n", b"a\nb\n", Options::default()).is_empty());
+    assert!(lines::diff(b"", b"", Options::default()).is_empty());
+}
+
+#[test]
+fn modification_with_context() {
+    let before = numbered(10);
+    let after = edit(&before, "5", "five");
+    assert_eq!(
+        lines::diff(before.as_bytes(), after.as_bytes(), Options::default()),
+        vec![Hunk {
+            before: 1..8,
+            after: 1..8,
+            lines: vec![
+                Context(b"2\n".as_bstr()),
+                Context(b"3\n".as_bstr()),
+                Context(b"4\n".as_bstr()),
+                Removed(b"5\n".as_bstr()),
+                Added(b"five\n".as_bstr()),
+                Context(b"6\n".as_bstr()),
+                Context(b"7\n".as_bstr()),
+                Context(b"8\n".as_bstr()),
+            ]
+        }]
+    );
+}
+
+#[test]
+fn additions_and_removals_at_the_boundaries() {
+    assert_eq!(
+        lines::diff(b"", b"a\nb", Options::default()),
+        vec![Hunk {
+            before: 0..0,
+            after: 0..2,
+            lines: vec![Added(b"a\n".as_bstr()), Added(b"b".as_bstr())]
+        }]
+    );
+    assert_eq!(
+        lines::diff(
+            b"a\nb\n",
+            b"b\n",
+            Options {
+                context_lines: 1,
+                ..Default::default()
+            }
+        ),
+        vec![Hunk {
+            before: 0..2,
+            after: 0..1,
+            lines: vec![Removed(b"a\n".as_bstr()), Context(b"b\n".as_bstr())]
+        }]
+    );
+}
+
+#[test]
+fn a_missing_newline_at_the_end_is_a_change() {
+    assert_eq!(
+        lines::diff(b"a\nb\n", b"a\nb", Options::default()),
+        vec![Hunk {
+            before: 0..2,
+            after: 0..2,
+            lines: vec![
+                Context(b"a\n".as_bstr()),
+                Removed(b"b\n".as_bstr()),
+                Added(b"b".as_bstr())
+            ]
+        }]
+    );
+}
+
+#[test]
+fn nearby_changes_share_a_hunk_and_distant_ones_do_not() {
+    let before = numbered(20);
+    let after = edit(&edit(&before, "4", ""), "12", "twelve");
+    let hunks = lines::diff(before.as_bytes(), after.as_bytes(), Options::default());
+    assert_eq!(
+        hunks
+            .iter()
+            .map(|h| (h.before.clone(), h.after.clone()))
+            .collect::<Vec<_>>(),
+        vec![(0..7, 0..6), (8..15, 7..14)],
+        "7 lines between changes are more than twice the context"
+    );
+
+    let after = edit(&edit(&before, "4", ""), "10", "ten");
+    let hunks = lines::diff(before.as_bytes(), after.as_bytes(), Options::default());
+    assert_eq!(
+        hunks
+            .iter()
+            .map(|h| (h.before.clone(), h.after.clone()))
+            .collect::<Vec<_>>(),
+        vec![(0..13, 0..12)],
+        "up to 6 lines between changes are covered by the context of both"
+    );
+    assert_eq!(hunks[0].lines.len(), 14, "13 lines before, one of which was modified");
+
+    let hunks = lines::diff(
+        before.as_bytes(),
+        after.as_bytes(),
+        Options {
+            context_lines: 0,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        hunks
+            .iter()
+            .map(|h| (h.before.clone(), h.after.clone()))
+            .collect::<Vec<_>>(),
+        vec![(3..4, 3..3), (9..10, 8..9)],
+    );
+}
+
+mod myers {
+    use git_diff::lines::myers;
+
+    /// A simple linear congruential generator, good enough to produce a variety of inputs deterministically.
+    pub fn sequence(seed: &mut u64, len: usize, alphabet: u64) -> Vec<u64> {
+        (0..len)
+            .map(|_| {
+                *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (*seed >> 33) % alphabet
+            })
+            .collect()
+    }
+
+    fn lcs_len(a: &[u64], b: &[u64]) -> usize {
+        let mut row = vec![0; b.len() + 1];
+        for x in a {
+            let mut diagonal = 0;
+            for (j, y) in b.iter().enumerate() {
+                let above = row[j + 1];
+                row[j + 1] = if x == y { diagonal + 1 } else { above.max(row[j]) };
+                diagonal = above;
+            }
+        }
+        row[b.len()]
+    }
+
+    #[test]
+    fn edit_scripts_are_correct_and_minimal() {
+        let mut seed = 42;
+        for round in 0..500 {
+            let (len_a, len_b) = (round % 23, (round * 7) % 19);
+            let alphabet = 1 + round as u64 % 5;
+            let a = sequence(&mut seed, len_a, alphabet);
+            let b = sequence(&mut seed, len_b, alphabet);
+            let changes = myers::diff(&a, &b);
+
+            let mut reconstructed = Vec::new();
+            let mut pos = 0;
+            for change in &changes {
+                assert!(!change.before.is_empty() || !change.after.is_empty());
+                assert!(change.before.start >= pos, "changes are ordered and don't overlap");
+                reconstructed.extend_from_slice(&a[pos..change.before.start]);
+                reconstructed.extend_from_slice(&b[change.after.clone()]);
+                pos = change.before.end;
+            }
+            reconstructed.extend_from_slice(&a[pos..]);
+            assert_eq!(reconstructed, b, "applying the changes to {:?} yields {:?}", a, b);
+
+            let edits: usize = changes.iter().map(|c| c.before.len() + c.after.len()).sum();
+            assert_eq!(edits, a.len() + b.len() - 2 * lcs_len(&a, &b), "{:?} -> {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn adjacent_changes_are_merged() {
+        assert_eq!(
+            myers::diff(&[1, 2, 3], &[4, 5]),
+            vec![git_diff::lines::Change {
+                before: 0..3,
+                after: 0..2
+            }]
+        );
+    }
+}
+
+mod algorithm {
+    use git_diff::lines::{self, Algorithm, Change};
+
+    fn changes(algorithm: Algorithm, before: &str, after: &str) -> Vec<Change> {
+        let (before, after) = (lines::split(before.as_bytes()), lines::split(after.as_bytes()));
+        let (before, after) = lines::intern(&before, &after);
+        algorithm.diff(&before, &after)
+    }
+
+    #[test]
+    fn edit_scripts_are_correct() {
+        let mut seed = 7;
+        for round in 0..500 {
+            let (len_a, len_b) = (round % 31, (round * 7) % 29);
+            let alphabet = 1 + round as u64 % 9;
+            let a = super::myers::sequence(&mut seed, len_a, alphabet);
+            let b = super::myers::sequence(&mut seed, len_b, alphabet);
+            for algorithm in &[Algorithm::Histogram, Algorithm::Patience] {
+                let mut reconstructed = Vec::new();
+                let mut pos = 0;
+                for change in algorithm.diff(&a, &b) {
+                    assert!(!change.before.is_empty() || !change.after.is_empty());
+                    assert!(change.before.start >= pos, "changes are ordered and don't overlap");
+                    reconstructed.extend_from_slice(&a[pos..change.before.start]);
+                    reconstructed.extend_from_slice(&b[change.after.clone()]);
+                    pos = change.before.end;
+                }
+                reconstructed.extend_from_slice(&a[pos..]);
+                assert_eq!(reconstructed, b, "{:?}: {:?} -> {:?}", algorithm, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn moved_blocks_are_kept_together_like_git_does() {
+        let before = ".foo1 {\n    margin: 0;\n}\n\n.bar {\n    margin: 0;\n}\n";
+        let after = ".bar {\n    margin: 0;\n}\n\n.foo1 {\n    margin: 0;\n    color: green;\n}\n";
+        assert_eq!(
+            changes(Algorithm::Myers, before, after),
+            vec![
+                Change {
+                    before: 0..1,
+                    after: 0..1
+                },
+                Change {
+                    before: 4..5,
+                    after: 4..5
+                },
+                Change {
+                    before: 6..6,
+                    after: 6..7
+                }
+            ],
+            "minimal, but hard to read"
+        );
+        for algorithm in &[Algorithm::Histogram, Algorithm::Patience] {
+            assert_eq!(
+                changes(*algorithm, before, after),
+                vec![
+                    Change {
+                        before: 0..4,
+                        after: 0..0
+                    },
+                    Change {
+                        before: 7..7,
+                        after: 3..8
+                    }
+                ],
+                "{:?} matches git --diff-algorithm",
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn empty_and_equal_inputs() {
+        for algorithm in &[Algorithm::Myers, Algorithm::Histogram, Algorithm::Patience] {
+            assert_eq!(changes(*algorithm, "a\nb\n", "a\nb\n"), vec![]);
+            assert_eq!(changes(*algorithm, "", ""), vec![]);
+            assert_eq!(
+                changes(*algorithm, "", "a\n"),
+                vec![Change {
+                    before: 0..0,
+                    after: 0..1
+                }]
+            );
+            assert_eq!(
+                changes(*algorithm, "a\nb\n", "c\n"),
+                vec![Change {
+                    before: 0..2,
+                    after: 0..1
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn lines_occurring_too_often_fall_back_to_myers() {
+        let before: String = (0..100).map(|n| if n == 50 { "x\n" } else { "a\n" }).collect();
+        let after: String = (0..100).map(|n| if n == 60 { "y\n" } else { "a\n" }).collect();
+        assert_eq!(
+            changes(Algorithm::Histogram, &before, &after),
+            changes(Algorithm::Myers, &before, &after)
+        );
+    }
+}
+
+mod whitespace {
+    use git_diff::lines::{self, Change, Options, Whitespace};
+
+    fn changes(before: &str, after: &str, whitespace: Whitespace, context_lines: usize) -> Vec<Change> {
+        let (before, after) = (lines::split(before.as_bytes()), lines::split(after.as_bytes()));
+        lines::changes(
+            &before,
+            &after,
+            Options {
+                context_lines,
+                whitespace,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn change(before: std::ops::Range<usize>, after: std::ops::Range<usize>) -> Change {
+        Change { before, after }
+    }
+
+    #[test]
+    fn normalization() {
+        let all = Whitespace {
+            ignore_all_space: true,
+            ..Default::default()
+        };
+        let amount = Whitespace {
+            ignore_space_change: true,
+            ..Default::default()
+        };
+        let cr = Whitespace {
+            ignore_cr_at_eol: true,
+            ..Default::default()
+        };
+        let normalize = |whitespace: Whitespace, line: &str| whitespace.normalize(line.into()).into_owned();
+        assert_eq!(normalize(all, " a \t b\r\n"), "ab");
+        assert_eq!(normalize(amount, " a \t b\r\n"), " a b");
+        assert_eq!(normalize(cr, " a \t b\r\n"), " a \t b");
+        assert_eq!(normalize(cr, "a\r"), "a");
+        assert_eq!(normalize(Whitespace::default(), " a\r\n"), " a\r\n");
+    }
+
+    #[test]
+    fn ignored_whitespace_is_not_a_change_like_in_git() {
+        let (before, after) = (" a  b\nc\t\n", " a b\n c\n");
+        assert_eq!(
+            changes(before, after, Whitespace::default(), 3),
+            vec![change(0..2, 0..2)]
+        );
+        assert_eq!(
+            changes(
+                before,
+                after,
+                Whitespace {
+                    ignore_space_change: true,
+                    ..Default::default()
+                },
+                3
+            ),
+            vec![change(1..2, 1..2)],
+            "leading whitespace is still significant"
+        );
+        assert_eq!(
+            changes(
+                before,
+                after,
+                Whitespace {
+                    ignore_all_space: true,
+                    ..Default::default()
+                },
+                3
+            ),
+            vec![]
+        );
+        assert_eq!(
+            changes(
+                "x\r\ny\r\nz\n",
+                "x\ny\r\nZ\n",
+                Whitespace {
+                    ignore_cr_at_eol: true,
+                    ..Default::default()
+                },
+                3
+            ),
+            vec![change(2..3, 2..3)]
+        );
+    }
+
+    #[test]
+    fn blank_lines_are_ignored_unless_they_are_close_to_other_changes() {
+        let before = "a\n\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let after = "a\nb\nc\n\nd\nE\nf\ng\nh\n\ni\n";
+        let ignore_blank_lines = Whitespace {
+            ignore_blank_lines: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            changes(before, after, Whitespace::default(), 3).len(),
+            4,
+            "without the option, all blank lines are changes"
+        );
+        assert_eq!(
+            changes(before, after, ignore_blank_lines, 3),
+            vec![change(1..2, 1..1), change(4..4, 3..4), change(5..6, 5..6)],
+            "blank lines less than 3 lines away from other changes are kept, like git does"
+        );
+        assert_eq!(changes(before, after, ignore_blank_lines, 1), vec![change(5..6, 5..6)]);
+        assert_eq!(changes("a\n", "a\n\n", ignore_blank_lines, 3), vec![]);
+    }
+
+    #[test]
+    fn hunks_show_context_lines_as_they_are_after_the_change() {
+        let hunks = lines::diff(
+            b"a\nb \nc\n",
+            b"a\nb\nC\n",
+            Options {
+                whitespace: Whitespace {
+                    ignore_space_change: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(
+            hunks[0].lines,
+            vec![
+                lines::Line::Context("a\n".into()),
+                lines::Line::Context("b\n".into()),
+                lines::Line::Removed("c\n".into()),
+                lines::Line::Added("C\n".into())
+            ]
+        );
+    }
+}
+
+mod intraline {
+    use git_diff::lines::{
+        self,
+        intraline::{self, Change, Chars, RefinedLine, Tokenizer, Words},
+        Line::*,
+        Options,
+    };
+    use git_object::bstr::ByteSlice;
+    use std::ops::Range;
+
+    fn tokens(tokenizer: impl Tokenizer, text: &str) -> Vec<&str> {
+        let mut out = Vec::new();
+        tokenizer.tokenize(text.as_bytes().as_bstr(), &mut out);
+        out.into_iter().map(|range| &text[range]).collect()
+    }
+
+    fn changed<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<&'a str> {
+        ranges.iter().map(|range| &text[range.clone()]).collect()
+    }
+
+    #[test]
+    fn words_keep_whitespace_and_split_punctuation() {
+        assert_eq!(
+            tokens(Words, "let  größe = a.b_c(1);\n"),
+            vec!["let", "  ", "größe", " ", "=", " ", "a", ".", "b_c", "(", "1", ")", ";", "\n"]
+        );
+        assert_eq!(tokens(Words, ""), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn chars_split_valid_and_invalid_utf8() {
+        assert_eq!(tokens(Chars, "aé\n"), vec!["a", "é", "\n"]);
+        let mut out = Vec::new();
+        Chars.tokenize(b"a\xffb".as_bstr(), &mut out);
+        assert_eq!(out, vec![0..1, 1..2, 2..3]);
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn graphemes_keep_combining_characters() {
+        assert_eq!(tokens(intraline::Graphemes, "e\u{301}x"), vec!["e\u{301}", "x"]);
+        assert_eq!(tokens(Chars, "e\u{301}x"), vec!["e", "\u{301}", "x"]);
+    }
+
+    #[test]
+    fn diff_yields_changed_byte_ranges() {
+        assert_eq!(
+            intraline::diff(b"the quick fox", b"the slow brown fox", &Words),
+            vec![Change {
+                before: 4..9,
+                after: 4..14
+            }]
+        );
+        assert_eq!(
+            intraline::diff(b"a b", b"a b c", &Words),
+            vec![Change {
+                before: 3..3,
+                after: 3..5
+            }],
+            "additions at the end are positioned at the end of the previous version"
+        );
+        assert_eq!(
+            intraline::diff(b"abc", b"abd", &Chars),
+            vec![Change {
+                before: 2..3,
+                after: 2..3
+            }]
+        );
+        assert_eq!(intraline::diff(b"same", b"same", &Words), vec![]);
+    }
+
+    #[test]
+    fn hunks_are_refined_per_run_of_removed_and_added_lines() {
+        let before = "fn a() {\n    let x = 1;\n    let y = 2;\n}\nold\n";
+        let after = "fn a() {\n    let x = 10;\n    let why = 2;\n}\n";
+        let hunks = lines::diff(before.as_bytes(), after.as_bytes(), Options::default());
+        assert_eq!(hunks.len(), 1);
+        let refined = intraline::hunk(&hunks[0], &Words);
+        let summary: Vec<_> = refined
+            .iter()
+            .map(|RefinedLine { line, changes }| {
+                let kind = match line {
+                    Context(_) => ' ',
+                    Removed(_) => '-',
+                    Added(_) => '+',
+                };
+                (kind, changed(line.data().to_str().unwrap(), changes))
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (' ', vec![]),
+                ('-', vec!["1"]),
+                ('-', vec!["y"]),
+                ('+', vec!["10"]),
+                ('+', vec!["why"]),
+                (' ', vec![]),
+                ('-', vec!["old\n"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use crate::Trees;
+use git_diff::tree::{self, recorder::Change};
+use git_hash::ObjectId;
+use git_object::{immutable, tree::EntryMode};
+
+fn blob(n: u8) -> ObjectId {
+    ObjectId::from_20_bytes(&[n; 20])
+}
+
+#[test]
+fn changes_of_all_pairs_are_returned_in_order() -> crate::Result {
+    let mut trees = Trees::default();
+    let (mut ids, mut subtrees) = (vec![None], vec![None]);
+    for n in 1..40u8 {
+        let sub = trees.add(&[("file", EntryMode::Blob, blob(n % 3))]);
+        subtrees.push(Some(sub));
+        ids.push(Some(
+            trees.add(&[("a", EntryMode::Blob, blob(n)), ("dir", EntryMode::Tree, sub)]),
+        ));
+    }
+    let pairs: Vec<_> = ids.windows(2).map(|pair| (pair[0], pair[1])).collect();
+
+    let changes = tree::many::changes(pairs.iter().copied(), Some(4), || {
+        let trees = &trees;
+        move |id: &git_hash::oid, buf: &mut Vec<u8>| trees.find(id, buf)
+    })?;
+    assert_eq!(changes.len(), pairs.len());
+    for ((lhs, rhs), changes) in pairs.iter().zip(&changes) {
+        let lhs_buf = lhs.map(|id| trees.0[&id].clone());
+        let mut recorder = tree::Recorder::default();
+        tree::Changes::from(lhs_buf.as_deref().map(immutable::TreeIter::from_bytes)).needed_to_obtain(
+            immutable::TreeIter::from_bytes(&trees.0[&rhs.expect("always set")]),
+            tree::State::default(),
+            |id, buf| trees.find(id, buf),
+            &mut recorder,
+        )?;
+        assert_eq!(*changes, recorder.records, "same as diffing each pair on its own");
+    }
+    assert_eq!(changes[0].len(), 3, "the first tree is diffed against the empty tree");
+    assert_eq!(
+        changes[1],
+        vec![
+            Change::Modification {
+                previous_entry_mode: EntryMode::Blob,
+                previous_oid: blob(1),
+                entry_mode: EntryMode::Blob,
+                oid: blob(2),
+                path: "a".into()
+            },
+            Change::Modification {
+                previous_entry_mode: EntryMode::Tree,
+                previous_oid: subtrees[1].expect("set"),
+                entry_mode: EntryMode::Tree,
+                oid: subtrees[2].expect("set"),
+                path: "dir".into()
+            },
+            Change::Modification {
+                previous_entry_mode: EntryMode::Blob,
+                previous_oid: blob(1),
+                entry_mode: EntryMode::Blob,
+                oid: blob(2),
+                path: "dir/file".into()
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_trees_are_an_error() {
+    let trees = Trees::default();
+    let err = tree::many::changes(vec![(None, Some(blob(1)))], None, || {
+        let trees = &trees;
+        move |id: &git_hash::oid, buf: &mut Vec<u8>| trees.find(id, buf)
+    })
+    .expect_err("tree is missing");
+    assert!(matches!(err, tree::changes::Error::NotFound { oid } if oid == blob(1)));
+}
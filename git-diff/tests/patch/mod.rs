@@ -0,0 +1,249 @@
+use git_diff::{patch, tree::recorder::Change};
+use git_hash::ObjectId;
+use git_object::{bstr::ByteSlice, tree::EntryMode};
+
+use crate::{hex_to_id, Blobs};
+
+impl Blobs {
+    fn patch(&self, changes: &[Change], options: patch::Options) -> Result<String, patch::Error> {
+        let mut out = Vec::new();
+        patch::write(&mut out, changes, options, |id, buf| self.find(id, buf))?;
+        Ok(out.to_str().expect("valid UTF-8").to_owned())
+    }
+}
+
+fn modification(path: &str, previous: (EntryMode, ObjectId), current: (EntryMode, ObjectId)) -> Change {
+    Change::Modification {
+        previous_entry_mode: previous.0,
+        previous_oid: previous.1,
+        entry_mode: current.0,
+        oid: current.1,
+        path: path.into(),
+    }
+}
+
+#[test]
+fn changes_are_formatted_like_git_diff_does() -> crate::Result {
+    let mut blobs = Blobs::default();
+    let code_before = blobs.insert(
+        hex_to_id("92a3c564a2395abbe11d286c01a93276684af573"),
+        b"fn main() {\n    one();\n    two();\n    three();\n    four();\n    five();\n}\n",
+    );
+    let code_after = blobs.insert(
+        hex_to_id("0aba98ca131e9002bc925702e54160f64108c18b"),
+        b"fn main() {\n    one();\n    two();\n    THREE();\n    four();\n    five();\n}\n",
+    );
+    let added = blobs.insert(hex_to_id("3e757656cf36eca53338e520d134963a44f793f8"), b"new\n");
+    let binary_before = blobs.insert(hex_to_id("87ae6b695deceaf160611414f7dcd5c7366b2e79"), b"bin\0ary");
+    let binary_after = blobs.insert(hex_to_id("481be20e06ffdd5831f393178fde23f11b4a6726"), b"bin\0arY");
+    let deleted = blobs.insert(hex_to_id("286c5f5776916d7d7d5849988ca9d83e722cf9c2"), b"gone\n");
+    let empty = blobs.insert(hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"), b"");
+    let script = blobs.insert(hex_to_id("2fa992c0b8b5c6acd2bdd4fa31de29d29799bdd5"), b"keep\n");
+    let no_newline_before = blobs.insert(hex_to_id("c1b0730e0133447badcfd47fd144e254807b06e1"), b"x");
+    let no_newline_after = blobs.insert(hex_to_id("e25f1814e51579d5f55c0f1fe0135ddb28a47f4a"), b"y");
+    let renamed_before = blobs.insert(
+        hex_to_id("f00c965d8307308469e537302baa73048488f162"),
+        b"1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n",
+    );
+    let renamed_after = blobs.insert(
+        hex_to_id("088bd5d92c2a8e0203ca8e7e4c2a5c692f6ae3f7"),
+        b"1\n2\n3\n4\n5\n6\n7\n8\n9\nten\n",
+    );
+
+    let changes = vec![
+        modification("a.rs", (EntryMode::Blob, code_before), (EntryMode::Blob, code_after)),
+        Change::Addition {
+            entry_mode: EntryMode::Blob,
+            oid: added,
+            path: "added".into(),
+        },
+        modification("bin", (EntryMode::Blob, binary_before), (EntryMode::Blob, binary_after)),
+        Change::Deletion {
+            entry_mode: EntryMode::Blob,
+            oid: deleted,
+            path: "deleted".into(),
+        },
+        Change::Addition {
+            entry_mode: EntryMode::Tree,
+            oid: empty,
+            path: "directories-are-skipped".into(),
+        },
+        Change::Addition {
+            entry_mode: EntryMode::Blob,
+            oid: empty,
+            path: "empty".into(),
+        },
+        modification(
+            "mode.sh",
+            (EntryMode::Blob, script),
+            (EntryMode::BlobExecutable, script),
+        ),
+        modification(
+            "nonl",
+            (EntryMode::Blob, no_newline_before),
+            (EntryMode::Blob, no_newline_after),
+        ),
+        Change::Rename {
+            previous_entry_mode: EntryMode::Blob,
+            previous_oid: renamed_before,
+            previous_path: "renamed-from".into(),
+            entry_mode: EntryMode::Blob,
+            oid: renamed_after,
+            path: "renamed-to".into(),
+            similarity: 81,
+        },
+    ];
+    assert_eq!(
+        blobs.patch(&changes, patch::Options::default())?,
+        r#"diff --git a/a.rs b/a.rs
+index 92a3c56..0aba98c 100644
+--- a/a.rs
++++ b/a.rs
+@@ -1,7 +1,7 @@
+ fn main() {
+     one();
+     two();
+-    three();
++    THREE();
+     four();
+     five();
+ }
+diff --git a/added b/added
+new file mode 100644
+index 0000000..3e75765
+--- /dev/null
++++ b/added
+@@ -0,0 +1 @@
++new
+diff --git a/bin b/bin
+index 87ae6b6..481be20 100644
+Binary files a/bin and b/bin differ
+diff --git a/deleted b/deleted
+deleted file mode 100644
+index 286c5f5..0000000
+--- a/deleted
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/empty b/empty
+new file mode 100644
+index 0000000..e69de29
+diff --git a/mode.sh b/mode.sh
+old mode 100644
+new mode 100755
+diff --git a/nonl b/nonl
+index c1b0730..e25f181 100644
+--- a/nonl
++++ b/nonl
+@@ -1 +1 @@
+-x
+\ No newline at end of file
++y
+\ No newline at end of file
+diff --git a/renamed-from b/renamed-to
+similarity index 81%
+rename from renamed-from
+rename to renamed-to
+index f00c965..088bd5d 100644
+--- a/renamed-from
++++ b/renamed-to
+@@ -7,4 +7,4 @@
+ 7
+ 8
+ 9
+-10
++ten
+"#
+    );
+    Ok(())
+}
+
+#[test]
+fn hunk_headers_show_the_enclosing_function_and_special_paths_are_quoted() -> crate::Result {
+    let mut blobs = Blobs::default();
+    let before = blobs.insert(
+        hex_to_id("89ab80d0298f19178538ea4f76088653d3c50d4f"),
+        b"struct S;\n\nfn main() {\n    one();\n    two();\n    three();\n    four();\n    five();\n}\n",
+    );
+    let after = blobs.insert(
+        hex_to_id("55ea8f1f5dc4c5f62a8e9e27d8a9758b4e7e186d"),
+        b"struct S;\n\nfn main() {\n    one();\n    two();\n    three();\n    four();\n    FIVE();\n}\n",
+    );
+    let changes = vec![modification(
+        "t\u{e4}b\tl\"e.rs",
+        (EntryMode::Blob, before),
+        (EntryMode::Blob, after),
+    )];
+    assert_eq!(
+        blobs.patch(
+            &changes,
+            patch::Options {
+                abbrev: 10,
+                ..Default::default()
+            }
+        )?,
+        r#"diff --git "a/t\303\244b\tl\"e.rs" "b/t\303\244b\tl\"e.rs"
+index 89ab80d029..55ea8f1f5d 100644
+--- "a/t\303\244b\tl\"e.rs"
++++ "b/t\303\244b\tl\"e.rs"
+@@ -5,5 +5,5 @@ fn main() {
+     two();
+     three();
+     four();
+-    five();
++    FIVE();
+ }
+"#
+    );
+    Ok(())
+}
+
+#[test]
+fn ignored_whitespace_changes_omit_files_and_show_context_as_it_is_now() -> crate::Result {
+    let mut blobs = Blobs::default();
+    let reindented_before = blobs.insert(
+        hex_to_id("692428d753beb1de110b3910c8bcc56b25210714"),
+        b"if x {\n    y();\n}\n",
+    );
+    let reindented_after = blobs.insert(
+        hex_to_id("a3386cb59a4c8aa48b80662af75f25e8766f61d3"),
+        b"if x  {\n\ty();\n}\n",
+    );
+    let modified_before = blobs.insert(hex_to_id("5a78edfb16fa0d2d8c7718a22f57baa96e401eb2"), b"a\nb \nc\n");
+    let modified_after = blobs.insert(hex_to_id("6dcce7d0cfdbcdb3076b2dca72674fb9d7d13ef8"), b"a\nb\nC\n");
+    let changes = vec![
+        modification(
+            "reindented",
+            (EntryMode::Blob, reindented_before),
+            (EntryMode::Blob, reindented_after),
+        ),
+        modification(
+            "modified",
+            (EntryMode::Blob, modified_before),
+            (EntryMode::Blob, modified_after),
+        ),
+    ];
+    assert_eq!(
+        blobs.patch(
+            &changes,
+            patch::Options {
+                whitespace: git_diff::lines::Whitespace {
+                    ignore_all_space: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        )?,
+        "diff --git a/modified b/modified
+index 5a78edf..6dcce7d 100644
+--- a/modified
++++ b/modified
+@@ -1,3 +1,3 @@
+ a
+ b
+-c
++C
+"
+    );
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use crate::Blobs;
+use git_diff::tree::{
+    recorder::Change,
+    rewrites::{self, Copies},
+};
+use git_hash::ObjectId;
+use git_object::{bstr::BString, tree::EntryMode};
+
+impl Blobs {
+    fn detect(&self, changes: Vec<Change>, options: rewrites::Options) -> Vec<Change> {
+        self.detect_with_unmodified(changes, None, options)
+    }
+
+    fn detect_with_unmodified(
+        &self,
+        changes: Vec<Change>,
+        unmodified: impl IntoIterator<Item = rewrites::Unmodified>,
+        options: rewrites::Options,
+    ) -> Vec<Change> {
+        rewrites::detect_with_unmodified(changes, unmodified, options, |id, buf| self.find(id, buf))
+    }
+}
+
+fn addition(path: &str, entry_mode: EntryMode, oid: ObjectId) -> Change {
+    Change::Addition {
+        entry_mode,
+        oid,
+        path: path.into(),
+    }
+}
+
+fn deletion(path: &str, entry_mode: EntryMode, oid: ObjectId) -> Change {
+    Change::Deletion {
+        entry_mode,
+        oid,
+        path: path.into(),
+    }
+}
+
+fn renames(changes: &[Change]) -> Vec<(BString, BString, u8)> {
+    changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::Rename {
+                previous_path,
+                path,
+                similarity,
+                ..
+            } => Some((previous_path.clone(), path.clone(), *similarity)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn numbered(count: usize) -> String {
+    (1..=count).map(|n| format!("line {}\n", n)).collect()
+}
+
+#[test]
+fn exact_renames_are_paired_in_order_and_keep_the_position_of_the_addition() {
+    let mut blobs = Blobs::default();
+    let content = blobs.add("content\n");
+    let other = blobs.add("other\n");
+    let changes = vec![
+        deletion("a", EntryMode::Blob, content),
+        deletion("b", EntryMode::Blob, content),
+        addition("c", EntryMode::Blob, other),
+        addition("d", EntryMode::BlobExecutable, content),
+    ];
+    let out = blobs.detect(changes, rewrites::Options::default());
+    assert_eq!(
+        out,
+        vec![
+            deletion("b", EntryMode::Blob, content),
+            addition("c", EntryMode::Blob, other),
+            Change::Rename {
+                previous_entry_mode: EntryMode::Blob,
+                previous_oid: content,
+                previous_path: "a".into(),
+                entry_mode: EntryMode::BlobExecutable,
+                oid: content,
+                path: "d".into(),
+                similarity: 100
+            }
+        ],
+        "mode changes of files don't prevent renames"
+    );
+}
+
+#[test]
+fn similar_files_are_paired_with_the_most_similar_deletion() {
+    let mut blobs = Blobs::default();
+    let original = numbered(10);
+    let slightly_changed = blobs.add(&original.replace("line 10\n", "line ten\n"));
+    let more_changed = blobs.add(
+        &original
+            .replace("line 1\n", "line one\n")
+            .replace("line 2\n", "line two\n"),
+    );
+    let original = blobs.add(&original);
+    let unrelated = blobs.add("something else entirely\n");
+
+    let changes = vec![
+        deletion("more", EntryMode::Blob, more_changed),
+        deletion("unrelated", EntryMode::Blob, unrelated),
+        addition("new", EntryMode::Blob, original),
+        deletion("slightly", EntryMode::Blob, slightly_changed),
+    ];
+    let out = blobs.detect(changes.clone(), rewrites::Options::default());
+    assert_eq!(renames(&out), vec![("slightly".into(), "new".into(), 87)]);
+    assert_eq!(out.len(), 3, "the other deletions are retained");
+
+    let out = blobs.detect(
+        changes.clone(),
+        rewrites::Options {
+            percentage: 95,
+            ..Default::default()
+        },
+    );
+    assert_eq!(out, changes, "nothing is similar enough");
+
+    let out = blobs.detect(
+        changes.clone(),
+        rewrites::Options {
+            limit: 1,
+            ..Default::default()
+        },
+    );
+    assert_eq!(out, changes, "3 deletions exceed the limit for inexact renames");
+}
+
+#[test]
+fn links_and_files_and_directories_are_not_paired() {
+    let mut blobs = Blobs::default();
+    let target = blobs.add("target");
+    let tree = blobs.add("");
+    let changes = vec![
+        deletion("link", EntryMode::Link, target),
+        addition("file", EntryMode::Blob, target),
+        deletion("dir", EntryMode::Tree, tree),
+        addition("other-dir", EntryMode::Tree, tree),
+    ];
+    assert_eq!(blobs.detect(changes.clone(), rewrites::Options::default()), changes);
+}
+
+fn copies(changes: &[Change]) -> Vec<(BString, BString, u8)> {
+    changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::Copy {
+                source_path,
+                path,
+                similarity,
+                ..
+            } => Some((source_path.clone(), path.clone(), *similarity)),
+            _ => None,
+        })
+        .collect()
+}
+
+mod copies {
+    use super::*;
+
+    fn with_copies(copies: Copies) -> rewrites::Options {
+        rewrites::Options {
+            copies: Some(copies),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn modified_and_renamed_files_are_sources() {
+        let mut blobs = Blobs::default();
+        let original = numbered(10);
+        let modified = blobs.add(&original.replace("line 5\n", ""));
+        let similar = blobs.add(&original.replace("line 1\n", "line one\n"));
+        let original = blobs.add(&original);
+        let deleted = blobs.add("deleted\n");
+
+        let changes = vec![
+            Change::Modification {
+                previous_entry_mode: EntryMode::Blob,
+                previous_oid: original,
+                entry_mode: EntryMode::Blob,
+                oid: modified,
+                path: "modified".into(),
+            },
+            deletion("deleted", EntryMode::Blob, deleted),
+            addition("renamed", EntryMode::Blob, deleted),
+            addition("copy-of-deleted", EntryMode::Blob, deleted),
+            addition("copy-of-original", EntryMode::Blob, similar),
+        ];
+        let out = blobs.detect(changes.clone(), with_copies(Copies::FromModifiedFiles));
+        assert_eq!(renames(&out), vec![("deleted".into(), "renamed".into(), 100)]);
+        assert_eq!(
+            copies(&out),
+            vec![
+                ("deleted".into(), "copy-of-deleted".into(), 100),
+                ("modified".into(), "copy-of-original".into(), 87)
+            ],
+            "the content before the modification is the source"
+        );
+        assert_eq!(out.len(), 4, "the deletion is turned into a rename");
+
+        let out = blobs.detect(changes, rewrites::Options::default());
+        assert!(copies(&out).is_empty(), "copies are only detected on request");
+    }
+
+    #[test]
+    fn unmodified_files_are_only_sources_if_all_files_are_considered() {
+        let mut blobs = Blobs::default();
+        let unchanged = blobs.add(&numbered(4));
+        let similar = blobs.add(&numbered(5));
+        let changes = vec![addition("copy", EntryMode::Blob, similar)];
+        let unmodified = || {
+            vec![rewrites::Unmodified {
+                entry_mode: EntryMode::Blob,
+                oid: unchanged,
+                path: "unchanged".into(),
+            }]
+        };
+
+        let out = blobs.detect_with_unmodified(changes.clone(), unmodified(), with_copies(Copies::FromModifiedFiles));
+        assert_eq!(out, changes);
+
+        let out = blobs.detect_with_unmodified(changes.clone(), unmodified(), with_copies(Copies::FromAllFiles));
+        assert_eq!(copies(&out), vec![("unchanged".into(), "copy".into(), 80)]);
+
+        let out = blobs.detect_with_unmodified(
+            changes.clone(),
+            unmodified(),
+            rewrites::Options {
+                limit: 0,
+                percentage: 81,
+                copies: Some(Copies::FromAllFiles),
+            },
+        );
+        assert_eq!(out, changes, "the copy isn't similar enough");
+    }
+}
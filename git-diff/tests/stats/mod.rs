@@ -0,0 +1,110 @@
+use crate::Blobs;
+use git_diff::{
+    stats::{self, Lines, Statistics},
+    tree::recorder::Change,
+};
+use git_hash::ObjectId;
+use git_object::tree::EntryMode;
+
+impl Blobs {
+    fn stats(&self, changes: &[Change]) -> Result<Statistics, stats::Error> {
+        Statistics::from_changes(changes, |id, buf| self.find(id, buf))
+    }
+}
+
+fn lines(insertions: usize, deletions: usize) -> Option<Lines> {
+    Some(Lines { insertions, deletions })
+}
+
+#[test]
+fn lines_of_text_files_are_counted_like_git_numstat_does() -> crate::Result {
+    let mut blobs = Blobs::default();
+    let before = blobs.add(b"1\n2\n3\n4\n");
+    let after = blobs.add(b"1\ntwo\n3\n4\n5");
+    let binary = blobs.add(b"binary\0data");
+    let changes = vec![
+        Change::Modification {
+            previous_entry_mode: EntryMode::Blob,
+            previous_oid: before,
+            entry_mode: EntryMode::BlobExecutable,
+            oid: after,
+            path: "modified".into(),
+        },
+        Change::Addition {
+            entry_mode: EntryMode::Blob,
+            oid: after,
+            path: "added".into(),
+        },
+        Change::Deletion {
+            entry_mode: EntryMode::Blob,
+            oid: before,
+            path: "deleted".into(),
+        },
+        Change::Modification {
+            previous_entry_mode: EntryMode::Blob,
+            previous_oid: before,
+            entry_mode: EntryMode::Blob,
+            oid: binary,
+            path: "now-binary".into(),
+        },
+        Change::Rename {
+            previous_entry_mode: EntryMode::Blob,
+            previous_oid: before,
+            previous_path: "old".into(),
+            entry_mode: EntryMode::Blob,
+            oid: before,
+            path: "new".into(),
+            similarity: 100,
+        },
+        Change::Addition {
+            entry_mode: EntryMode::Tree,
+            oid: before,
+            path: "directory".into(),
+        },
+        Change::Modification {
+            previous_entry_mode: EntryMode::Commit,
+            previous_oid: before,
+            entry_mode: EntryMode::Commit,
+            oid: after,
+            path: "submodule".into(),
+        },
+    ];
+    let stats = blobs.stats(&changes)?;
+    assert_eq!(
+        stats
+            .files
+            .iter()
+            .map(|f| (
+                f.path.to_string(),
+                f.source_path.as_ref().map(|p| p.to_string()),
+                f.lines
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("modified".into(), None, lines(2, 1)),
+            ("added".into(), None, lines(5, 0)),
+            ("deleted".into(), None, lines(0, 4)),
+            ("now-binary".into(), None, None),
+            ("new".into(), Some("old".into()), lines(0, 0)),
+            ("submodule".into(), None, lines(1, 1)),
+        ],
+        "directories are skipped and binary files have no line counts"
+    );
+    assert_eq!(stats.insertions(), 8);
+    assert_eq!(stats.deletions(), 6);
+    Ok(())
+}
+
+#[test]
+fn missing_blobs_are_an_error() {
+    let blobs = Blobs::default();
+    let missing = ObjectId::null_sha1();
+    let err = blobs
+        .stats(&[Change::Addition {
+            entry_mode: EntryMode::Blob,
+            oid: missing,
+            path: "file".into(),
+        }])
+        .unwrap_err();
+    assert!(matches!(err, stats::Error::NotFound { oid } if oid == missing));
+}
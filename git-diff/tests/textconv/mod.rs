@@ -0,0 +1,208 @@
+use crate::Blobs;
+use git_diff::{
+    patch,
+    stats::{Lines, Statistics},
+    textconv::Drivers,
+    tree::recorder::Change,
+};
+use git_object::{
+    bstr::{BStr, ByteSlice},
+    tree::EntryMode,
+};
+
+type ConvertResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+impl Blobs {
+    fn modification(&mut self, path: &str, before: &[u8], after: &[u8]) -> Change {
+        Change::Modification {
+            previous_entry_mode: EntryMode::Blob,
+            previous_oid: self.add(before),
+            entry_mode: EntryMode::Blob,
+            oid: self.add(after),
+            path: path.into(),
+        }
+    }
+
+    fn patch_with_drivers(&self, changes: &[Change], drivers: &mut Drivers) -> Result<String, patch::Error> {
+        let mut out = Vec::new();
+        patch::write_with_drivers(&mut out, changes, Default::default(), drivers, |id, buf| {
+            self.find(id, buf)
+        })?;
+        Ok(out.to_str().expect("valid UTF-8").to_owned())
+    }
+}
+
+/// Render the null-separated words of a binary 'document' as one line each.
+fn words(_path: &BStr, data: &[u8], out: &mut Vec<u8>) -> ConvertResult {
+    for word in data.split_str("\0").filter(|word| !word.is_empty()) {
+        out.extend_from_slice(word);
+        out.push(b'\n');
+    }
+    Ok(())
+}
+
+#[test]
+fn files_matching_a_pattern_are_diffed_as_converted_text() -> crate::Result {
+    let mut blobs = Blobs::default();
+    let changes = vec![
+        blobs.modification("docs/report.docx", b"one\0two\0three", b"one\0TWO\0three"),
+        blobs.modification("other.bin", b"one\0two", b"one\0TWO"),
+        blobs.modification("docs/reformatted.docx", b"same\0words", b"same\0\0words"),
+    ];
+    let mut drivers = Drivers::default();
+    drivers.register("*.docx", words);
+
+    assert_eq!(
+        blobs.patch_with_drivers(&changes, &mut drivers)?,
+        "diff --git a/docs/report.docx b/docs/report.docx
+index 0101010..0202020 100644
+--- a/docs/report.docx
++++ b/docs/report.docx
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+diff --git a/other.bin b/other.bin
+index 0303030..0404040 100644
+Binary files a/other.bin and b/other.bin differ
+",
+        "binary files are converted, and those without changes after conversion are omitted like git does"
+    );
+
+    let mut stats = Statistics::default();
+    for change in &changes {
+        stats.record_with_drivers(change, &mut drivers, |id, buf| blobs.find(id, buf))?;
+    }
+    assert_eq!(
+        stats.files.iter().map(|file| file.lines).collect::<Vec<_>>(),
+        vec![
+            Some(Lines {
+                insertions: 1,
+                deletions: 1
+            }),
+            None,
+            Some(Lines::default())
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn the_last_matching_pattern_wins() {
+    let mut drivers = Drivers::default();
+    drivers
+        .register("*.ipynb", |_: &BStr, _: &[u8], out: &mut Vec<u8>| -> ConvertResult {
+            out.extend_from_slice(b"any");
+            Ok(())
+        })
+        .register(
+            "/notebooks/**/*.ipynb",
+            |_: &BStr, _: &[u8], out: &mut Vec<u8>| -> ConvertResult {
+                out.extend_from_slice(b"notebooks");
+                Ok(())
+            },
+        )
+        .register("data?/*", |_: &BStr, _: &[u8], out: &mut Vec<u8>| -> ConvertResult {
+            out.extend_from_slice(b"data");
+            Ok(())
+        });
+
+    let mut out = Vec::new();
+    for (path, expected) in &[
+        ("a.ipynb", Some("any")),
+        ("deep/down/a.ipynb", Some("any")),
+        ("notebooks/a.ipynb", Some("notebooks")),
+        ("notebooks/deep/down/a.ipynb", Some("notebooks")),
+        ("other/notebooks/a.ipynb", Some("any")),
+        ("data1/file", Some("data")),
+        ("data1/sub/file", None),
+        ("data/file", None),
+        ("a.ipynb.txt", None),
+    ] {
+        let (data, converted) = drivers
+            .convert(path.as_bytes().as_bstr(), b"original", &mut out)
+            .expect("no error");
+        assert_eq!(
+            converted.then(|| data.to_str().expect("valid UTF-8")),
+            *expected,
+            "{}",
+            path
+        );
+    }
+}
+
+#[test]
+fn patterns_support_classes_escapes_and_directory_wildcards_like_gitattributes() {
+    let is_converted = |pattern: &str, path: &str| {
+        let mut drivers = Drivers::default();
+        drivers.register(pattern, |_: &BStr, _: &[u8], _: &mut Vec<u8>| -> ConvertResult {
+            Ok(())
+        });
+        drivers
+            .convert(path.as_bytes().as_bstr(), b"original", &mut Vec::new())
+            .expect("no error")
+            .1
+    };
+    for (pattern, path, expected) in &[
+        ("[a-c].txt", "dir/b.txt", true),
+        ("[a-c].txt", "d.txt", false),
+        ("[!a-c].txt", "d.txt", true),
+        ("[^a-c].txt", "a.txt", false),
+        ("[[:digit:]x].txt", "7.txt", true),
+        ("[[:digit:]x].txt", "x.txt", true),
+        ("[[:digit:]x].txt", "y.txt", false),
+        ("[]].txt", "].txt", true),
+        ("[a-].txt", "-.txt", true),
+        ("[\\]a]", "]", true),
+        ("dir[/]a", "dir/a", false),
+        ("\\*.txt", "*.txt", true),
+        ("\\*.txt", "a.txt", false),
+        ("[a", "[a", false),
+        ("[[:unknown:]]", "a", false),
+        ("trailing\\", "trailing\\", false),
+        ("dir/?", "dir/a", true),
+        ("dir?a", "dir/a", false),
+        ("dir/**", "dir/a/b", true),
+        ("dir/**", "other/a", false),
+        ("**/dir/*.txt", "dir/a.txt", true),
+        ("**/dir/*.txt", "x/y/dir/a.txt", true),
+        ("**/dir/*.txt", "x/dir/y/a.txt", false),
+        ("a/**/b", "a/b", true),
+        ("a/**/b", "a/x/y/b", true),
+        ("a/**b", "a/xb", true),
+        ("a/**b", "a/x/b", false),
+    ] {
+        assert_eq!(is_converted(pattern, path), *expected, "{} {}", pattern, path);
+    }
+
+    let path = "a".repeat(100);
+    assert!(
+        !is_converted("*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*b", &path),
+        "many stars don't cause exponential backtracking"
+    );
+    assert!(is_converted("*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*", &path));
+}
+
+#[test]
+fn conversion_errors_abort_the_patch() {
+    let mut blobs = Blobs::default();
+    let changes = vec![blobs.modification("a.docx", b"a", b"b")];
+    let mut drivers = Drivers::default();
+    drivers.register("a.docx", |_: &BStr, _: &[u8], _: &mut Vec<u8>| -> ConvertResult {
+        Err("invalid document".into())
+    });
+    let err = blobs
+        .patch_with_drivers(&changes, &mut drivers)
+        .expect_err("conversion fails");
+    assert_eq!(err.to_string(), "A file could not be converted to text");
+    let source = std::error::Error::source(&err).expect("textconv error");
+    assert_eq!(
+        source.to_string(),
+        "The content of 'a.docx' could not be converted to text"
+    );
+    assert_eq!(
+        std::error::Error::source(source).expect("driver error").to_string(),
+        "invalid document"
+    );
+}
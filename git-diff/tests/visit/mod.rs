@@ -40,25 +40,45 @@ mod changes {
         }
 
         fn diff_commits(db: &linked::Store, lhs: impl Into<Option<ObjectId>>, rhs: &oid) -> crate::Result<Changes> {
+            diff_commits_with(db, lhs, rhs, git_diff::tree::Recorder::default())
+        }
+
+        fn diff_commits_with(
+            db: &linked::Store,
+            lhs: impl Into<Option<ObjectId>>,
+            rhs: &oid,
+            recorder: git_diff::tree::Recorder,
+        ) -> crate::Result<Changes> {
+            diff_commits_with_pathspecs(db, lhs, rhs, recorder, None::<&str>)
+        }
+
+        fn diff_commits_with_pathspecs(
+            db: &linked::Store,
+            lhs: impl Into<Option<ObjectId>>,
+            rhs: &oid,
+            mut recorder: git_diff::tree::Recorder,
+            pathspecs: impl IntoIterator<Item = impl Into<git_object::bstr::BString>>,
+        ) -> crate::Result<Changes> {
             let mut buf = Vec::new();
             let lhs_tree = lhs
                 .into()
                 .and_then(|lhs| locate_tree_by_commit(db, &lhs, &mut buf).ok());
             let mut buf2 = Vec::new();
             let rhs_tree = locate_tree_by_commit(db, rhs, &mut buf2)?;
-            let mut recorder = git_diff::tree::Recorder::default();
-            git_diff::tree::Changes::from(lhs_tree).needed_to_obtain(
-                rhs_tree,
-                git_diff::tree::State::default(),
-                |oid, buf| {
-                    db.find(oid, buf, &mut pack::cache::Never)
-                        .ok()
-                        .flatten()
-                        .and_then(|obj| obj.into_tree_iter())
-                },
-                &mut recorder,
-            )?;
-            Ok(recorder.records)
+            git_diff::tree::Changes::from(lhs_tree)
+                .with_pathspecs(pathspecs)
+                .needed_to_obtain(
+                    rhs_tree,
+                    git_diff::tree::State::default(),
+                    |oid, buf| {
+                        db.find(oid, buf, &mut pack::cache::Never)
+                            .ok()
+                            .flatten()
+                            .and_then(|obj| obj.into_tree_iter())
+                    },
+                    &mut recorder,
+                )?;
+            Ok(recorder.into_iter().collect())
         }
 
         fn diff_with_previous_commit_from(db: &linked::Store, commit_id: &oid) -> crate::Result<Changes> {
@@ -554,6 +574,151 @@ mod changes {
             Ok(())
         }
 
+        #[test]
+        fn changes_exist_if_and_only_if_changes_are_recorded() -> crate::Result {
+            for args in &[None, Some("a")] {
+                let db = db(args.iter().copied())?;
+                let all_commits = all_commits(&db);
+                let mut state = git_diff::tree::State::default();
+                let pairs = all_commits
+                    .iter()
+                    .zip(all_commits.iter().skip(1))
+                    .chain(std::iter::once((
+                        &all_commits[0],
+                        all_commits.last().expect("many commits"),
+                    )));
+                for (lhs, rhs) in pairs.chain(all_commits.iter().map(|id| (id, id))) {
+                    let (mut buf1, mut buf2) = (Vec::new(), Vec::new());
+                    let changes_exist = git_diff::tree::changes_exist(
+                        locate_tree_by_commit(&db, lhs, &mut buf1)?,
+                        locate_tree_by_commit(&db, rhs, &mut buf2)?,
+                        &mut state,
+                        |oid, buf| {
+                            db.find(oid, buf, &mut pack::cache::Never)
+                                .ok()
+                                .flatten()
+                                .and_then(|obj| obj.into_tree_iter())
+                        },
+                    )?;
+                    assert_eq!(
+                        changes_exist,
+                        !diff_commits(&db, lhs.to_owned(), rhs)?.is_empty(),
+                        "{} -> {}",
+                        lhs,
+                        rhs
+                    );
+                    assert_eq!(
+                        changes_exist,
+                        lhs != rhs,
+                        "each commit in the fixture changes something"
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn stopping_early_once_enough_changes_are_seen() -> crate::Result {
+            let db = db(None)?;
+            let all_commits = all_commits(&db);
+            let (first, last) = (all_commits[0], *all_commits.last().expect("we have many commits"));
+
+            assert_eq!(
+                diff_commits_with(&db, first, &last, git_diff::tree::Recorder::with_limit(1))?,
+                vec![Addition {
+                    entry_mode: EntryMode::Blob,
+                    oid: hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+                    path: "b".into()
+                }],
+                "the traversal ends successfully after the first change"
+            );
+            assert_eq!(
+                diff_commits_with(&db, first, &last, git_diff::tree::Recorder::with_limit(2))?.len(),
+                2
+            );
+            assert_eq!(
+                diff_commits_with(&db, first, &last, git_diff::tree::Recorder::with_limit(10))?.len(),
+                3,
+                "limits beyond the amount of changes have no effect"
+            );
+            assert!(
+                diff_commits_with(&db, first, &first, git_diff::tree::Recorder::with_limit(1))?.is_empty(),
+                "there are no changes between the same trees"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn pathspecs_limit_changes_to_matching_paths_and_the_trees_leading_to_them() -> crate::Result {
+            let db = db(["a"].iter().copied())?;
+            let all_commits = all_commits(&db);
+            let tip = &all_commits[all_commits.len() - 6];
+            let diff = |pathspecs: &[&str]| {
+                diff_commits_with_pathspecs(
+                    &db,
+                    None::<ObjectId>,
+                    tip,
+                    Default::default(),
+                    pathspecs.iter().copied(),
+                )
+                .map(|changes| changes.into_iter().map(|c| path_of(&c).to_string()).collect::<Vec<_>>())
+            };
+
+            assert_eq!(diff(&["a/g/"])?, vec!["a", "a/g", "a/g/a"]);
+            assert_eq!(diff(&["a/b", "a/g/a"])?, vec!["a", "a/b", "a/g", "a/g/a"]);
+            assert_eq!(
+                diff(&["a/g/a/nested"])?,
+                vec!["a", "a/g"],
+                "trees leading up to the pathspec are reported"
+            );
+            assert_eq!(diff(&["a/b/nested", "missing", "a/gg"])?, vec!["a"]);
+            assert_eq!(diff(&[""])?.len(), 8, "the empty pathspec matches everything");
+
+            for (lhs, rhs) in all_commits.iter().zip(all_commits.iter().skip(1)) {
+                let all_changes = diff_commits(&db, lhs.to_owned(), rhs)?;
+                for spec in &["a", "a/f", "a/f/f", "a/g"] {
+                    let expected: Vec<_> = all_changes
+                        .iter()
+                        .filter(|c| {
+                            let path = path_of(c);
+                            path == spec
+                                || path.starts_with(format!("{}/", spec).as_bytes())
+                                || (is_tree(c) && spec.as_bytes().starts_with(format!("{}/", path).as_bytes()))
+                        })
+                        .cloned()
+                        .collect();
+                    assert_eq!(
+                        diff_commits_with_pathspecs(&db, lhs.to_owned(), rhs, Default::default(), Some(*spec))?,
+                        expected,
+                        "{} -> {} limited to '{}'",
+                        lhs,
+                        rhs,
+                        spec
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        fn is_tree(change: &recorder::Change) -> bool {
+            match change {
+                Addition { entry_mode, .. } | Deletion { entry_mode, .. } | Modification { entry_mode, .. } => {
+                    entry_mode.is_tree()
+                }
+                Rename { .. } | Copy { .. } => unreachable!("rewrites aren't tracked"),
+            }
+        }
+
+        fn path_of(change: &recorder::Change) -> &git_object::bstr::BStr {
+            match change {
+                Addition { path, .. }
+                | Deletion { path, .. }
+                | Modification { path, .. }
+                | Rename { path, .. }
+                | Copy { path, .. } => path.as_ref(),
+            }
+        }
+
         #[test]
         fn maximal_difference_nested() -> crate::Result {
             let db = db(["a"].iter().copied())?;
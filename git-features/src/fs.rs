@@ -1,11 +1,17 @@
 //! Filesystem utilities
 //!
+//! File metadata can be compared the way git does with [`Stat`] and a [`Policy`] that takes into account what the file
+//! system is capable of.
+//!
 //! These are will be parallel if the `parallel` feature is enabled, at the expense of compiling additional dependencies
 //! along with runtime costs for maintaining a global [`rayon`](https://docs.rs/rayon) thread pool.
 //!
 //! For information on how to use the [`WalkDir`] type, have a look at
 //! * [`jwalk::WalkDir`](https://docs.rs/jwalk/0.5.1/jwalk/type.WalkDir.html) if `parallel` feature is enabled
 //! * [walkdir::WalkDir](https://docs.rs/walkdir/2.3.1/walkdir/struct.WalkDir.html) otherwise
+mod stat;
+pub use stat::{Capabilities, CheckStat, Policy, Stat, Time};
+
 #[cfg(feature = "parallel")]
 ///
 pub mod walkdir {
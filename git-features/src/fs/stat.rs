@@ -0,0 +1,251 @@
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const S_IFMT: u32 = 0o170000;
+const S_IFREG: u32 = 0o100000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFLNK: u32 = 0o120000;
+const S_IFGITLINK: u32 = 0o160000;
+const EXECUTABLE_BIT: u32 = 0o100;
+
+/// What a file system is capable of, which corresponds to the `core.fileMode`, `core.symlinks` and `core.ignoreCase` configuration.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Capabilities {
+    /// If true, the executable bit of files is reliable and changes to it are changes to the file.
+    pub executable_bit: bool,
+    /// If true, symbolic links can be created. Otherwise they are checked out as plain files containing the link target.
+    pub symlink: bool,
+    /// If true, file names differing only in case refer to the same file.
+    pub ignore_case: bool,
+}
+
+impl Default for Capabilities {
+    /// The capabilities git assumes for the file systems of the current platform if nothing is configured.
+    fn default() -> Self {
+        Capabilities {
+            executable_bit: !cfg!(windows),
+            symlink: !cfg!(windows),
+            ignore_case: cfg!(any(windows, target_os = "macos")),
+        }
+    }
+}
+
+impl Capabilities {
+    /// Determine the capabilities of the file system `directory` is on by creating and removing files in it, similar to what
+    /// `git init` does to decide on the initial configuration of a repository.
+    ///
+    /// Capabilities that can't be probed, for example because `directory` isn't writable, are assumed to be missing.
+    pub fn probe(directory: &Path) -> Self {
+        let file = directory.join(format!("_gitoxide_Probe_{}", std::process::id()));
+        if std::fs::write(&file, b"").is_err() {
+            return Capabilities {
+                executable_bit: false,
+                symlink: false,
+                ignore_case: false,
+            };
+        }
+        let ignore_case = file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| directory.join(name.to_lowercase()).exists());
+        let out = Capabilities {
+            executable_bit: probe_executable_bit(&file),
+            symlink: probe_symlink(&file),
+            ignore_case,
+        };
+        std::fs::remove_file(&file).ok();
+        out
+    }
+}
+
+#[cfg(unix)]
+fn probe_executable_bit(file: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    let is_executable = |file: &Path| {
+        std::fs::metadata(file).map_or(false, |metadata| metadata.permissions().mode() & EXECUTABLE_BIT != 0)
+    };
+    if is_executable(file) {
+        return false;
+    }
+    std::fs::set_permissions(file, std::fs::Permissions::from_mode(0o755)).is_ok() && is_executable(file)
+}
+
+#[cfg(not(unix))]
+fn probe_executable_bit(_file: &Path) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn probe_symlink(file: &Path) -> bool {
+    let link = file.with_extension("link");
+    let is_symlink = std::os::unix::fs::symlink(file, &link).is_ok()
+        && std::fs::symlink_metadata(&link).map_or(false, |metadata| metadata.file_type().is_symlink());
+    std::fs::remove_file(&link).ok();
+    is_symlink
+}
+
+#[cfg(not(unix))]
+fn probe_symlink(_file: &Path) -> bool {
+    false
+}
+
+/// How much of the [`Stat`] information to compare, as configured by `core.checkStat`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum CheckStat {
+    /// Compare all fields that are known to be reliable.
+    Default,
+    /// Only compare the modification time in whole seconds, the size and the mode, for file systems with unstable inode numbers
+    /// or ownership, as seen on some network file systems.
+    Minimal,
+}
+
+impl Default for CheckStat {
+    fn default() -> Self {
+        CheckStat::Default
+    }
+}
+
+/// Decides whether a file changed by comparing its current [`Stat`] to the one recorded previously, for example in the index.
+///
+/// Repositories provide one as configured by `core.fileMode`, `core.symlinks`, `core.ignoreCase`, `core.trustctime` and
+/// `core.checkStat`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Policy {
+    /// What the file system is capable of, either as configured or as probed.
+    pub capabilities: Capabilities,
+    /// If false, the time of the last status change of a file is ignored, which can change without the file being modified,
+    /// for example when it is backed up or indexed. This is `core.trustctime`.
+    pub trust_ctime: bool,
+    /// How much information to compare, see [`CheckStat`].
+    pub check_stat: CheckStat,
+    /// If true, the sub-second part of timestamps is compared as well, which git only does if compiled with `USE_NSEC`.
+    pub use_nsec: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            capabilities: Capabilities::default(),
+            trust_ctime: true,
+            check_stat: CheckStat::default(),
+            use_nsec: false,
+        }
+    }
+}
+
+/// A point in time as recorded in a [`Stat`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+pub struct Time {
+    /// The amount of seconds since the unix epoch.
+    pub secs: u32,
+    /// The amount of nanoseconds within the second.
+    pub nsecs: u32,
+}
+
+impl From<SystemTime> for Time {
+    fn from(time: SystemTime) -> Self {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        Time {
+            secs: since_epoch.as_secs() as u32,
+            nsecs: since_epoch.subsec_nanos(),
+        }
+    }
+}
+
+/// The file system metadata git records about each file in the index to quickly determine if it changed.
+///
+/// Fields are truncated to 32 bits like in the index.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+pub struct Stat {
+    /// The time of the last modification of the file's content.
+    pub mtime: Time,
+    /// The time of the last change to the file's content or metadata.
+    pub ctime: Time,
+    /// The device the file is on.
+    pub dev: u32,
+    /// The inode number of the file.
+    pub ino: u32,
+    /// The mode of the file like in `st_mode`, including its type.
+    pub mode: u32,
+    /// The id of the user owning the file.
+    pub uid: u32,
+    /// The id of the group owning the file.
+    pub gid: u32,
+    /// The size of the file in bytes.
+    pub size: u32,
+}
+
+impl Stat {
+    /// Obtain all values from `metadata`, which should have been obtained without following symbolic links.
+    ///
+    /// On platforms other than unix, the mode is inferred from the file type and all values not available are zero.
+    pub fn from_metadata(metadata: &std::fs::Metadata) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            Stat {
+                mtime: Time {
+                    secs: metadata.mtime() as u32,
+                    nsecs: metadata.mtime_nsec() as u32,
+                },
+                ctime: Time {
+                    secs: metadata.ctime() as u32,
+                    nsecs: metadata.ctime_nsec() as u32,
+                },
+                dev: metadata.dev() as u32,
+                ino: metadata.ino() as u32,
+                mode: metadata.mode(),
+                uid: metadata.uid(),
+                gid: metadata.gid(),
+                size: metadata.len() as u32,
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let file_type = metadata.file_type();
+            Stat {
+                mtime: metadata.modified().map(Into::into).unwrap_or_default(),
+                ctime: metadata.created().map(Into::into).unwrap_or_default(),
+                mode: if file_type.is_dir() {
+                    S_IFDIR | 0o755
+                } else if file_type.is_symlink() {
+                    S_IFLNK | 0o777
+                } else {
+                    S_IFREG | 0o644
+                },
+                size: metadata.len() as u32,
+                ..Default::default()
+            }
+        }
+    }
+
+    /// Return true if the file described by `current` is unchanged compared to when `self` was recorded, according to `policy`.
+    ///
+    /// `self` may have a mode as stored in the index, like `0o100755` for executables or `0o160000` for submodules.
+    pub fn matches(&self, current: &Stat, policy: &Policy) -> bool {
+        let check_all = policy.check_stat == CheckStat::Default;
+        self.mode_matches(current.mode, &policy.capabilities)
+            && self.size == current.size
+            && self.mtime.secs == current.mtime.secs
+            && (!(policy.use_nsec && check_all) || self.mtime.nsecs == current.mtime.nsecs)
+            && (!(policy.trust_ctime && check_all)
+                || (self.ctime.secs == current.ctime.secs
+                    && (!policy.use_nsec || self.ctime.nsecs == current.ctime.nsecs)))
+            && (!check_all || (self.ino == current.ino && self.uid == current.uid && self.gid == current.gid))
+    }
+
+    fn mode_matches(&self, current_mode: u32, capabilities: &Capabilities) -> bool {
+        let current_type = current_mode & S_IFMT;
+        match self.mode & S_IFMT {
+            S_IFREG => {
+                current_type == S_IFREG
+                    && (!capabilities.executable_bit || (self.mode & EXECUTABLE_BIT) == (current_mode & EXECUTABLE_BIT))
+            }
+            S_IFLNK => current_type == S_IFLNK || (!capabilities.symlink && current_type == S_IFREG),
+            S_IFGITLINK | S_IFDIR => current_type == S_IFDIR,
+            _ => false,
+        }
+    }
+}
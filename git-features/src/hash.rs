@@ -82,6 +82,7 @@ pub fn crc32(bytes: &[u8]) -> u32 {
 /// * Only available with the `git-object` feature enabled due to usage of the [`git_hash::Kind`] enum and the
 ///   [`git_hash::ObjectId`] return value.
 /// * [Interrupts][crate::interrupt] are supported.
+/// * Only Sha1 hashes can be computed so far, using [`git_hash::Kind::Sha256`] results in an error.
 #[cfg(all(feature = "progress", any(feature = "sha1", feature = "fast-sha1")))]
 pub fn bytes_of_file(
     path: impl AsRef<std::path::Path>,
@@ -92,6 +93,12 @@ pub fn bytes_of_file(
 ) -> std::io::Result<git_hash::ObjectId> {
     let mut hasher = match kind {
         git_hash::Kind::Sha1 => crate::hash::Sha1::default(),
+        git_hash::Kind::Sha256 => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Sha256 hashes cannot be computed yet",
+            ))
+        }
     };
     let start = std::time::Instant::now();
     // init progress before the possibility for failure, as convenience in case people want to recover
@@ -151,12 +158,18 @@ mod write {
         T: std::io::Write,
     {
         /// Create a new hash writer which hashes all bytes written to `inner` with a hash of `kind`.
-        pub fn new(inner: T, kind: git_hash::Kind) -> Self {
+        ///
+        /// Fails if `kind` is [`git_hash::Kind::Sha256`] as only Sha1 hashes can be computed so far.
+        pub fn new(inner: T, kind: git_hash::Kind) -> std::io::Result<Self> {
             match kind {
-                git_hash::Kind::Sha1 => Write {
+                git_hash::Kind::Sha1 => Ok(Write {
                     inner,
                     hash: Sha1::default(),
-                },
+                }),
+                git_hash::Kind::Sha256 => Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "Sha256 hashes cannot be computed yet",
+                )),
             }
         }
     }
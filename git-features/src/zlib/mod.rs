@@ -1,5 +1,73 @@
 pub use flate2::{Decompress, Status};
 
+/// The zlib implementation doing the actual work, selected at compile time using the `zlib-ng-compat` and `zlib-rust-backend`
+/// cargo features of this crate.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Backend {
+    /// The C implementation `zlib-ng`, usually faster at the cost of requiring a C toolchain.
+    ZlibNg,
+    /// The pure rust implementation `miniz_oxide`.
+    RustMiniz,
+}
+
+impl Backend {
+    /// The backend compiled into this crate.
+    pub fn current() -> Self {
+        if cfg!(feature = "zlib-ng-compat") {
+            Backend::ZlibNg
+        } else {
+            Backend::RustMiniz
+        }
+    }
+}
+
+/// Settings to control how data is compressed, trading CPU time for size of the output.
+///
+/// All writers producing zlib streams accept these to allow configuring them. The [`Backend`] doing the work is
+/// selected at compile time and applies to all of them.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compression {
+    level: u32,
+}
+
+impl Default for Compression {
+    /// Favor speed over size, which is what git uses for loose objects and pack entries by default.
+    fn default() -> Self {
+        Compression::fast()
+    }
+}
+
+impl Compression {
+    /// Create a new instance with the given `level`, clamped to 9 at most.
+    pub fn new(level: u32) -> Self {
+        Compression { level: level.min(9) }
+    }
+    /// Don't compress at all and only wrap the data into a zlib stream.
+    pub fn none() -> Self {
+        Compression { level: 0 }
+    }
+    /// Compress as fast as possible.
+    pub fn fast() -> Self {
+        Compression { level: 1 }
+    }
+    /// Produce the smallest output possible at the cost of speed.
+    pub fn best() -> Self {
+        Compression { level: 9 }
+    }
+    /// The compression level from 0 (no compression) to 9 (best compression).
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+}
+
+impl From<Compression> for flate2::Compression {
+    fn from(v: Compression) -> Self {
+        flate2::Compression::new(v.level)
+    }
+}
+
 /// non-streaming interfaces for decompression
 pub mod inflate {
     use quick_error::quick_error;
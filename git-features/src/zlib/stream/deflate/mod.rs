@@ -13,17 +13,23 @@ pub struct Write<W> {
 
 mod impls {
     use crate::zlib::stream::deflate;
-    use flate2::{Compress, Compression, FlushCompress, Status};
+    use crate::zlib::Compression;
+    use flate2::{Compress, FlushCompress, Status};
     use std::io;
 
     impl<W> deflate::Write<W>
     where
         W: io::Write,
     {
-        /// Create a new instance writing compressed bytes to `inner`.
+        /// Create a new instance writing compressed bytes to `inner` using the [default compression][Compression::default()].
         pub fn new(inner: W) -> deflate::Write<W> {
+            Self::with_compression(inner, Compression::default())
+        }
+
+        /// Create a new instance writing bytes to `inner`, compressed according to `compression`.
+        pub fn with_compression(inner: W, compression: Compression) -> deflate::Write<W> {
             deflate::Write {
-                compressor: Compress::new(Compression::fast(), true),
+                compressor: Compress::new(compression.into(), true),
                 inner,
                 buf: [0; deflate::BUF_SIZE],
             }
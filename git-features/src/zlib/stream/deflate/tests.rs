@@ -1,5 +1,5 @@
 mod deflate_stream {
-    use crate::zlib::stream::deflate;
+    use crate::zlib::{stream::deflate, Compression};
     use bstr::ByteSlice;
     use flate2::Decompress;
     use std::{
@@ -96,4 +96,31 @@ mod deflate_stream {
 
         assert_deflate_buffer(w.inner, bytes)
     }
+
+    #[test]
+    fn higher_compression_levels_produce_smaller_output() -> Result<(), Box<dyn std::error::Error>> {
+        let bytes = &b"hello world, hello compression ".repeat(1000);
+        let mut compressed_sizes = Vec::new();
+        for compression in &[Compression::none(), Compression::fast(), Compression::best()] {
+            let mut w = deflate::Write::with_compression(Vec::new(), *compression);
+            w.write_all(bytes)?;
+            w.flush()?;
+            compressed_sizes.push(w.inner.len());
+            assert_deflate_buffer(w.inner, bytes)?;
+        }
+        assert!(
+            compressed_sizes[0] > bytes.len(),
+            "no compression only adds the zlib framing"
+        );
+        assert!(compressed_sizes[1] < bytes.len());
+        assert!(compressed_sizes[1] >= compressed_sizes[2]);
+        Ok(())
+    }
+
+    #[test]
+    fn compression_levels_are_clamped_to_the_best_one() {
+        assert_eq!(Compression::new(42), Compression::best());
+        assert_eq!(Compression::new(5).level(), 5);
+        assert_eq!(Compression::default().level(), Compression::fast().level());
+    }
 }
@@ -0,0 +1,121 @@
+use git_features::fs::{Capabilities, CheckStat, Policy, Stat};
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+#[cfg(unix)]
+fn capabilities_are_probed_by_creating_files() -> Result {
+    let dir = tempfile::tempdir()?;
+    let capabilities = Capabilities::probe(dir.path());
+    assert!(capabilities.executable_bit);
+    assert!(capabilities.symlink);
+    assert_eq!(
+        std::fs::read_dir(dir.path())?.count(),
+        0,
+        "all probe files are removed afterwards"
+    );
+    Ok(())
+}
+
+fn file_stat() -> Result<Stat> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("file");
+    std::fs::write(&path, b"content")?;
+    Ok(Stat::from_metadata(&std::fs::symlink_metadata(path)?))
+}
+
+#[test]
+fn unchanged_files_match() -> Result {
+    let stat = file_stat()?;
+    assert_eq!(stat.size, 7);
+    assert!(stat.matches(&stat, &Policy::default()));
+    Ok(())
+}
+
+#[test]
+fn changes_to_size_or_modification_time_are_always_detected() -> Result {
+    let recorded = file_stat()?;
+    let minimal = Policy {
+        check_stat: CheckStat::Minimal,
+        ..Policy::default()
+    };
+    let mut current = recorded;
+    current.size += 1;
+    assert!(!recorded.matches(&current, &minimal));
+
+    let mut current = recorded;
+    current.mtime.secs += 1;
+    assert!(!recorded.matches(&current, &minimal));
+    Ok(())
+}
+
+#[test]
+fn ctime_and_inode_are_ignored_if_configured() -> Result {
+    let recorded = file_stat()?;
+    let mut current = recorded;
+    current.ctime.secs += 1;
+    assert!(!recorded.matches(&current, &Policy::default()));
+    assert!(recorded.matches(
+        &current,
+        &Policy {
+            trust_ctime: false,
+            ..Policy::default()
+        }
+    ));
+
+    let mut current = recorded;
+    current.ino += 1;
+    assert!(!recorded.matches(&current, &Policy::default()));
+    assert!(recorded.matches(
+        &current,
+        &Policy {
+            check_stat: CheckStat::Minimal,
+            ..Policy::default()
+        }
+    ));
+    Ok(())
+}
+
+#[test]
+fn the_executable_bit_is_ignored_if_the_file_system_does_not_support_it() -> Result {
+    let recorded = Stat {
+        mode: 0o100644,
+        ..file_stat()?
+    };
+    let current = Stat {
+        mode: 0o100755,
+        ..recorded
+    };
+    let without_executable_bit = Policy {
+        capabilities: Capabilities {
+            executable_bit: false,
+            ..Capabilities::default()
+        },
+        ..Policy::default()
+    };
+    assert!(!recorded.matches(&current, &Policy::default()));
+    assert!(recorded.matches(&current, &without_executable_bit));
+    Ok(())
+}
+
+#[test]
+fn symlinks_checked_out_as_files_match_if_the_file_system_does_not_support_them() -> Result {
+    let recorded = Stat {
+        mode: 0o120000,
+        ..file_stat()?
+    };
+    let current = Stat {
+        mode: 0o100644,
+        ..recorded
+    };
+    let without_symlinks = Policy {
+        capabilities: Capabilities {
+            symlink: false,
+            ..Capabilities::default()
+        },
+        ..Policy::default()
+    };
+    assert!(!recorded.matches(&current, &Policy::default()));
+    assert!(recorded.matches(&current, &without_symlinks));
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{ObjectId, SIZE_OF_SHA1_DIGEST};
+use crate::{ObjectId, SIZE_OF_SHA1_DIGEST, SIZE_OF_SHA256_DIGEST};
 use std::{convert::TryInto, fmt};
 
 /// A borrowed reference to a hash identifying objects.
@@ -25,6 +25,7 @@ impl std::fmt::Debug for oid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.kind() {
             crate::Kind::Sha1 => f.write_str("Sha1(")?,
+            crate::Kind::Sha256 => f.write_str("Sha256(")?,
         }
         for b in self.as_bytes() {
             write!(f, "{:02x}", b)?;
@@ -49,7 +50,7 @@ impl oid {
     /// Try to create a shared object id from a slice of bytes representing a hash `digest`
     pub fn try_from(digest: &[u8]) -> Result<&Self, Error> {
         match digest.len() {
-            20 => Ok(
+            SIZE_OF_SHA1_DIGEST | SIZE_OF_SHA256_DIGEST => Ok(
                 #[allow(unsafe_code)]
                 unsafe {
                     &*(digest as *const [u8] as *const oid)
@@ -72,10 +73,8 @@ impl oid {
 impl oid {
     /// The kind of hash used for this Digest
     pub fn kind(&self) -> crate::Kind {
-        match self.bytes.len() {
-            20 => crate::Kind::Sha1,
-            _ => unreachable!("creating this instance is checked and fails on unknown lengths"),
-        }
+        crate::Kind::from_len_in_bytes(self.bytes.len())
+            .expect("creating this instance is checked and fails on unknown lengths")
     }
 
     /// The first byte of the hash, commonly used to partition a set of `Id`s
@@ -92,20 +91,19 @@ impl oid {
 
 /// Sha1 specific methods
 impl oid {
-    /// Returns an array with a hexadecimal encoded version of the Sha1 hash this `Id` represents.
-    ///
-    /// **Panics** if this is not a Sha1 hash, as identifiable by [`ObjectId::kind()`].
-    pub fn to_sha1_hex(&self) -> [u8; SIZE_OF_SHA1_DIGEST * 2] {
-        let mut buf = [0u8; SIZE_OF_SHA1_DIGEST * 2];
-        hex::encode_to_slice(&self.bytes, &mut buf).expect("to count correctly");
-        buf
+    /// Returns an array with a hexadecimal encoded version of the Sha1 hash this `Id` represents, or `None` if this is not
+    /// a Sha1 hash, as identifiable by [`oid::kind()`].
+    pub fn to_sha1_hex(&self) -> Option<[u8; SIZE_OF_SHA1_DIGEST * 2]> {
+        self.sha1().map(|b| {
+            let mut buf = [0u8; SIZE_OF_SHA1_DIGEST * 2];
+            hex::encode_to_slice(b, &mut buf).expect("to count correctly");
+            buf
+        })
     }
 
-    /// Returns the bytes making up the Sha1.
-    ///
-    /// **Panics** if this is not a Sha1 hash, as identifiable by [`ObjectId::kind()`].
-    pub fn sha1(&self) -> &[u8; SIZE_OF_SHA1_DIGEST] {
-        self.bytes.try_into().expect("correctly sized slice")
+    /// Returns the bytes making up the Sha1, or `None` if this is not a Sha1 hash, as identifiable by [`oid::kind()`].
+    pub fn sha1(&self) -> Option<&[u8; SIZE_OF_SHA1_DIGEST]> {
+        self.bytes.try_into().ok()
     }
 
     /// Returns a Sha1 digest with all bytes being initialized to zero.
@@ -114,6 +112,14 @@ impl oid {
     }
 }
 
+/// Hash independent methods
+impl oid {
+    /// Returns a digest of the given `kind` with all bytes being initialized to zero.
+    pub fn null(kind: crate::Kind) -> &'static Self {
+        oid::from(&[0u8; SIZE_OF_SHA256_DIGEST][..kind.len_in_bytes()])
+    }
+}
+
 impl AsRef<oid> for &oid {
     fn as_ref(&self) -> &oid {
         self
@@ -126,6 +132,7 @@ impl ToOwned for oid {
     fn to_owned(&self) -> Self::Owned {
         match self.kind() {
             crate::Kind::Sha1 => crate::ObjectId::Sha1(self.bytes.try_into().expect("no bug in hash detection")),
+            crate::Kind::Sha256 => crate::ObjectId::Sha256(self.bytes.try_into().expect("no bug in hash detection")),
         }
     }
 }
@@ -136,6 +143,12 @@ impl<'a> From<&'a [u8; SIZE_OF_SHA1_DIGEST]> for &'a oid {
     }
 }
 
+impl<'a> From<&'a [u8; SIZE_OF_SHA256_DIGEST]> for &'a oid {
+    fn from(v: &'a [u8; SIZE_OF_SHA256_DIGEST]) -> Self {
+        oid::from(v.as_ref())
+    }
+}
+
 impl fmt::Display for &oid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for b in self.as_bytes() {
@@ -30,7 +30,7 @@ pub mod decode {
 
     /// Hash decoding
     impl ObjectId {
-        /// Create an instance from a `buffer` of 40 bytes encoded with hexadecimal notation.
+        /// Create an instance from a `buffer` of 40 or 64 bytes encoded with hexadecimal notation, for Sha1 or Sha256 respectively.
         ///
         /// Such a buffer can be obtained using [`write_hex_to(buffer)`][ObjectId::write_hex_to()]
         pub fn from_hex(buffer: &[u8]) -> Result<ObjectId, Error> {
@@ -39,6 +39,9 @@ pub mod decode {
                 40 => Ok(ObjectId::Sha1(
                     <[u8; 20]>::from_hex(buffer).expect("our length check is correct thus we can decode hex"),
                 )),
+                64 => Ok(ObjectId::Sha256(
+                    <[u8; 32]>::from_hex(buffer).expect("our length check is correct thus we can decode hex"),
+                )),
                 len => Err(Error::InvalidHexEncodingLength(len)),
             }
         }
@@ -48,19 +51,15 @@ pub mod decode {
         type Err = Error;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
-            use hex::FromHex;
-            match s.len() {
-                40 => Ok(ObjectId::Sha1(
-                    <[u8; 20]>::from_hex(s).expect("our length check is correct thus we can decode hex"),
-                )),
-                len => Err(Error::InvalidHexEncodingLength(len)),
-            }
+            Self::from_hex(s.as_bytes())
         }
     }
 }
 
 /// The size of a SHA1 hash digest in bytes
 pub const SIZE_OF_SHA1_DIGEST: usize = 20;
+/// The size of a SHA256 hash digest in bytes
+pub const SIZE_OF_SHA256_DIGEST: usize = 32;
 
 /// Denotes the kind of function to produce a `Id`
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -68,6 +67,8 @@ pub const SIZE_OF_SHA1_DIGEST: usize = 20;
 pub enum Kind {
     /// The Sha1 hash with 160 bits.
     Sha1,
+    /// The Sha256 hash with 256 bits, as used by repositories with `extensions.objectFormat = sha256`.
+    Sha256,
 }
 
 impl Default for Kind {
@@ -84,13 +85,83 @@ impl Kind {
 
     /// Returns the longest hash we support
     pub const fn longest() -> Self {
-        Self::Sha1
+        Self::Sha256
     }
 
     /// Returns the amount of ascii-characters needed to encode this has in hex
     pub const fn len_in_hex(&self) -> usize {
         match self {
             Kind::Sha1 => 40,
+            Kind::Sha256 => 64,
+        }
+    }
+
+    /// Returns the amount of bytes taken up by the hash of this kind
+    pub const fn len_in_bytes(&self) -> usize {
+        match self {
+            Kind::Sha1 => SIZE_OF_SHA1_DIGEST,
+            Kind::Sha256 => SIZE_OF_SHA256_DIGEST,
+        }
+    }
+
+    /// Returns the kind of hash whose digest is `len` bytes long, or `None` if there is no such hash.
+    pub const fn from_len_in_bytes(len: usize) -> Option<Self> {
+        match len {
+            SIZE_OF_SHA1_DIGEST => Some(Kind::Sha1),
+            SIZE_OF_SHA256_DIGEST => Some(Kind::Sha256),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of hash whose hexadecimal representation is `len` characters long, or `None` if there is no such hash.
+    pub const fn from_hex_len(len: usize) -> Option<Self> {
+        match len {
+            40 => Some(Kind::Sha1),
+            64 => Some(Kind::Sha256),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Kind {
+    type Err = String;
+
+    /// Parse the values of `extensions.objectFormat`, `sha1` and `sha256`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sha1" | "SHA1" => Ok(Kind::Sha1),
+            "sha256" | "SHA256" => Ok(Kind::Sha256),
+            other => Err(other.into()),
+        }
+    }
+}
+
+#[cfg(feature = "git2")]
+mod git2_crate {
+    use crate::{oid, ObjectId};
+    use std::convert::TryFrom;
+
+    impl From<git2::Oid> for ObjectId {
+        fn from(id: git2::Oid) -> Self {
+            ObjectId::from_20_bytes(id.as_bytes())
+        }
+    }
+
+    /// Fails for all but SHA1 ids, as `git2` doesn't support other hashes.
+    impl TryFrom<&oid> for git2::Oid {
+        type Error = git2::Error;
+
+        fn try_from(id: &oid) -> Result<Self, Self::Error> {
+            git2::Oid::from_bytes(id.as_bytes())
+        }
+    }
+
+    /// Fails for all but SHA1 ids, as `git2` doesn't support other hashes.
+    impl TryFrom<ObjectId> for git2::Oid {
+        type Error = git2::Error;
+
+        fn try_from(id: ObjectId) -> Result<Self, Self::Error> {
+            git2::Oid::from_bytes(id.as_slice())
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::{borrowed::oid, SIZE_OF_SHA1_DIGEST};
+use crate::{borrowed::oid, SIZE_OF_SHA1_DIGEST, SIZE_OF_SHA256_DIGEST};
 use std::{borrow::Borrow, fmt, io, ops::Deref};
 
 /// An owned hash identifying objects, most commonly Sha1
@@ -6,12 +6,14 @@ use std::{borrow::Borrow, fmt, io, ops::Deref};
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObjectId {
     Sha1([u8; SIZE_OF_SHA1_DIGEST]),
+    Sha256([u8; SIZE_OF_SHA256_DIGEST]),
 }
 
 impl std::fmt::Debug for ObjectId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ObjectId::Sha1(_hash) => f.write_str("Sha1(")?,
+            ObjectId::Sha256(_hash) => f.write_str("Sha256(")?,
         }
         for b in self.as_bytes() {
             write!(f, "{:02x}", b)?;
@@ -24,75 +26,116 @@ impl std::fmt::Debug for ObjectId {
 impl ObjectId {
     /// Returns the kind of hash used in this `Id`
     pub fn kind(&self) -> crate::Kind {
-        crate::Kind::Sha1
+        match self {
+            Self::Sha1(_) => crate::Kind::Sha1,
+            Self::Sha256(_) => crate::Kind::Sha256,
+        }
     }
     /// Return the raw byte slice representing this hash
     pub fn as_slice(&self) -> &[u8] {
         match self {
             Self::Sha1(b) => b.as_ref(),
+            Self::Sha256(b) => b.as_ref(),
         }
     }
     /// Return the raw mutable byte slice representing this hash
     pub fn as_mut_slice(&mut self) -> &mut [u8] {
         match self {
             Self::Sha1(b) => b.as_mut(),
+            Self::Sha256(b) => b.as_mut(),
         }
     }
 
     /// Write ourselves to `out` in hexadecimal notation
     pub fn write_hex_to(&self, mut out: impl io::Write) -> io::Result<()> {
-        out.write_all(&self.to_sha1_hex())
+        let mut hex_buf = [0u8; SIZE_OF_SHA256_DIGEST * 2];
+        let hex_len = self.kind().len_in_hex();
+        hex::encode_to_slice(self.as_slice(), &mut hex_buf[..hex_len]).expect("we can count");
+        out.write_all(&hex_buf[..hex_len])
+    }
+
+    /// The id of a tree without any entries, using the hash of the given `kind`.
+    ///
+    /// Like in git, this object is always considered present in object databases even if it is not stored there.
+    pub const fn empty_tree(kind: crate::Kind) -> ObjectId {
+        match kind {
+            crate::Kind::Sha1 => {
+                ObjectId::Sha1(*b"\x4b\x82\x5d\xc6\x42\xcb\x6e\xb9\xa0\x60\xe5\x4b\xf8\xd6\x92\x88\xfb\xee\x49\x04")
+            }
+            crate::Kind::Sha256 => ObjectId::Sha256(
+                *b"\x6e\xf1\x9b\x41\x22\x5c\x53\x69\xf1\xc1\x04\xd4\x5d\x8d\x85\xef\xa9\xb0\x57\xb5\x3b\x14\xb4\xb9\xb9\x39\xdd\x74\xde\xcc\x53\x21",
+            ),
+        }
+    }
+
+    /// The id of a blob without any content, using the hash of the given `kind`.
+    pub const fn empty_blob(kind: crate::Kind) -> ObjectId {
+        match kind {
+            crate::Kind::Sha1 => {
+                ObjectId::Sha1(*b"\xe6\x9d\xe2\x9b\xb2\xd1\xd6\x43\x4b\x8b\x29\xae\x77\x5a\xd8\xc2\xe4\x8c\x53\x91")
+            }
+            crate::Kind::Sha256 => ObjectId::Sha256(
+                *b"\x47\x3a\x0f\x4c\x3b\xe8\xa9\x36\x81\xa2\x67\xe3\xb1\xe9\xa7\xdc\xda\x11\x85\x43\x6f\xe1\x41\xf7\x74\x91\x20\xa3\x03\x72\x18\x13",
+            ),
+        }
+    }
+
+    /// Returns true if this is the id of the [empty tree][ObjectId::empty_tree()] of its kind.
+    pub fn is_empty_tree(&self) -> bool {
+        *self == Self::empty_tree(self.kind())
     }
 
-    pub const fn empty_tree() -> ObjectId {
-        ObjectId::Sha1(*b"\x4b\x82\x5d\xc6\x42\xcb\x6e\xb9\xa0\x60\xe5\x4b\xf8\xd6\x92\x88\xfb\xee\x49\x04")
+    /// Returns true if this is the id of the [empty blob][ObjectId::empty_blob()] of its kind.
+    pub fn is_empty_blob(&self) -> bool {
+        *self == Self::empty_blob(self.kind())
     }
 
     /// Returns true if this hash consists of all null bytes
     pub fn is_null(&self) -> bool {
-        match self {
-            ObjectId::Sha1(digest) => &digest[..] == Self::null_sha1().as_bytes(),
-        }
+        self.as_slice().iter().all(|b| *b == 0)
     }
 
     /// Returns an Digest representing a hash with whose memory is zeroed.
     pub const fn null_sha(kind: crate::Kind) -> ObjectId {
         match kind {
             crate::Kind::Sha1 => Self::null_sha1(),
+            crate::Kind::Sha256 => ObjectId::Sha256([0u8; SIZE_OF_SHA256_DIGEST]),
         }
     }
+
+    /// Instantiate an Digest from a slice of bytes of a Sha1 or Sha256 digest, with the kind determined by its length.
+    ///
+    /// Panics if the slice isn't 20 or 32 bytes long.
+    pub fn from_bytes(b: &[u8]) -> ObjectId {
+        oid::try_from(b).expect("a slice of a valid hash length").to_owned()
+    }
 }
 
 /// Sha1 hash specific methods
 impl ObjectId {
-    /// Returns ourselves as slice of 20 bytes.
-    ///
-    /// Panics if this instance is not a sha1 hash.
-    pub fn sha1(&self) -> &[u8; SIZE_OF_SHA1_DIGEST] {
+    /// Returns ourselves as slice of 20 bytes, or `None` if this instance is not a sha1 hash.
+    pub fn sha1(&self) -> Option<&[u8; SIZE_OF_SHA1_DIGEST]> {
         match self {
-            Self::Sha1(b) => &b,
+            Self::Sha1(b) => Some(b),
+            Self::Sha256(_) => None,
         }
     }
 
-    /// Return ourselves as array of 40 hexadecimal bytes.
+    /// Return ourselves as array of 40 hexadecimal bytes, or `None` if this instance is not a sha1 hash.
     ///
-    /// Panics if this instance is not a sha1 hash.
-    pub fn to_sha1_hex(self) -> [u8; SIZE_OF_SHA1_DIGEST * 2] {
-        match self {
-            Self::Sha1(b) => {
-                let mut hex_buf = [0u8; 40];
-                hex::encode_to_slice(b, &mut hex_buf).expect("we can count");
-                hex_buf
-            }
-        }
+    /// Use the [`Display`][std::fmt::Display] implementation for the hexadecimal representation of hashes of any kind.
+    pub fn to_sha1_hex(self) -> Option<[u8; SIZE_OF_SHA1_DIGEST * 2]> {
+        self.sha1().map(|b| {
+            let mut hex_buf = [0u8; 40];
+            hex::encode_to_slice(b, &mut hex_buf).expect("we can count");
+            hex_buf
+        })
     }
 
-    /// Return ourselves as hexadecimal string with a length of 40 bytes.
-    ///
-    /// Panics if this instance is not a sha1 hash.
-    pub fn to_sha1_hex_string(self) -> String {
-        let buf = self.to_sha1_hex();
-        std::str::from_utf8(&buf).expect("hex is valid UTF-8").to_string()
+    /// Return ourselves as hexadecimal string with a length of 40 bytes, or `None` if this instance is not a sha1 hash.
+    pub fn to_sha1_hex_string(self) -> Option<String> {
+        self.to_sha1_hex()
+            .map(|buf| std::str::from_utf8(&buf).expect("hex is valid UTF-8").to_string())
     }
 
     /// Instantiate an Digest from 20 bytes of a Sha1 digest.
@@ -129,11 +172,15 @@ impl From<[u8; SIZE_OF_SHA1_DIGEST]> for ObjectId {
     }
 }
 
+impl From<[u8; SIZE_OF_SHA256_DIGEST]> for ObjectId {
+    fn from(v: [u8; 32]) -> Self {
+        ObjectId::Sha256(v)
+    }
+}
+
 impl From<&crate::oid> for ObjectId {
     fn from(v: &oid) -> Self {
-        match v.kind() {
-            crate::Kind::Sha1 => ObjectId::from_20_bytes(v.as_bytes()),
-        }
+        v.to_owned()
     }
 }
 
@@ -0,0 +1,67 @@
+//! Tell text from binary data, count lines and detect the encoding of text like git does, for use with
+//! [borrowed][crate::immutable::Blob] and [owned][crate::mutable::Blob] blobs.
+//!
+//! These are the rules git applies in its diff and merge machinery, and using them assures that all layers agree on
+//! what is considered binary.
+
+/// The amount of bytes at the beginning of data to search for null bytes to determine if it is binary, like git does.
+pub const BINARY_DETECTION_LEN: usize = 8000;
+
+/// The encodings of text that can be detected by their byte order mark.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Encoding {
+    Utf8,
+    Utf16LE,
+    Utf16BE,
+    Utf32LE,
+    Utf32BE,
+}
+
+impl Encoding {
+    /// Return the byte order mark of this encoding.
+    pub fn bom(&self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => b"\xef\xbb\xbf",
+            Encoding::Utf16LE => b"\xff\xfe",
+            Encoding::Utf16BE => b"\xfe\xff",
+            Encoding::Utf32LE => b"\xff\xfe\0\0",
+            Encoding::Utf32BE => b"\0\0\xfe\xff",
+        }
+    }
+}
+
+/// Return true if `data` is considered binary, which is the case if there is a null byte within its first
+/// [`BINARY_DETECTION_LEN`] bytes.
+///
+/// Note that this is also true for text encoded in UTF-16 or UTF-32, just like it is in git.
+pub fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(BINARY_DETECTION_LEN)].contains(&0)
+}
+
+/// Return the amount of lines in `data`, counting a last line without trailing newline as well.
+pub fn count_lines(data: &[u8]) -> usize {
+    let newlines = data.iter().filter(|b| **b == b'\n').count();
+    match data.last() {
+        Some(b'\n') | None => newlines,
+        Some(_) => newlines + 1,
+    }
+}
+
+/// Return the encoding of `data` as indicated by the byte order mark it starts with, or `None` if there is none.
+///
+/// Skip the amount of bytes of the [byte order mark][Encoding::bom()] to obtain the text.
+pub fn encoding_from_bom(data: &[u8]) -> Option<Encoding> {
+    // UTF-32 LE is checked first as its byte order mark starts with the one of UTF-16 LE.
+    [
+        Encoding::Utf8,
+        Encoding::Utf32LE,
+        Encoding::Utf32BE,
+        Encoding::Utf16LE,
+        Encoding::Utf16BE,
+    ]
+    .iter()
+    .copied()
+    .find(|encoding| data.starts_with(encoding.bom()))
+}
@@ -1,12 +1,110 @@
-use bstr::{BStr, ByteSlice};
+use bstr::{BStr, BString, ByteSlice};
+use std::borrow::Cow;
 
 use crate::immutable;
 
+/// A commit message split into its title and body following the rules of git, obtained with
+/// [`immutable::Commit::message()`] or [`MessageRef::from_bytes()`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessageRef<'a> {
+    #[cfg_attr(feature = "serde1", serde(borrow))]
+    title: &'a BStr,
+    body: Option<&'a BStr>,
+}
+
+impl<'a> MessageRef<'a> {
+    /// Split the commit `message` into its title, the first paragraph after skipping leading empty lines, and its body,
+    /// which is everything after the title with leading empty lines skipped as well.
+    ///
+    /// Lines consisting only of whitespace count as empty, and both `\n` and `\r\n` line endings are supported.
+    pub fn from_bytes(message: &'a [u8]) -> Self {
+        let message = skip_empty_lines(message);
+        let mut title_len = 0;
+        let mut rest = message;
+        while !rest.is_empty() {
+            let line_len = line_len(rest);
+            if is_empty_line(&rest[..line_len]) {
+                break;
+            }
+            title_len += line_len;
+            rest = &rest[line_len..];
+        }
+        let body = skip_empty_lines(rest);
+        MessageRef {
+            title: message[..title_len].as_bstr(),
+            body: if body.is_empty() { None } else { Some(body.as_bstr()) },
+        }
+    }
+
+    /// Return the summary of the message like `git log --format=%s` does, which are all lines of the title joined with
+    /// a space and without trailing whitespace.
+    ///
+    /// Only titles spanning multiple lines have to be allocated.
+    pub fn summary(&self) -> Cow<'a, BStr> {
+        let mut lines = self
+            .title
+            .lines()
+            .map(|line| line.trim_end_with(|c| c.is_ascii_whitespace()));
+        let first = lines.next().unwrap_or_default();
+        match lines.next() {
+            None => Cow::Borrowed(first.as_bstr()),
+            Some(second) => {
+                let mut summary = BString::from(first);
+                for line in std::iter::once(second).chain(lines) {
+                    summary.push(b' ');
+                    summary.extend_from_slice(line);
+                }
+                Cow::Owned(summary)
+            }
+        }
+    }
+
+    /// Return the body of the message like `git log --format=%b` does, which is everything after the title and the
+    /// empty lines following it, or `None` if there is nothing after the title.
+    pub fn body(&self) -> Option<&'a BStr> {
+        self.body
+    }
+}
+
+/// Return the length of the first line in `data` including its line feed, if present.
+fn line_len(data: &[u8]) -> usize {
+    data.find_byte(b'\n').map_or(data.len(), |pos| pos + 1)
+}
+
+fn is_empty_line(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+fn skip_empty_lines(mut data: &[u8]) -> &[u8] {
+    while !data.is_empty() {
+        let line_len = line_len(data);
+        if !is_empty_line(&data[..line_len]) {
+            break;
+        }
+        data = &data[line_len..];
+    }
+    data
+}
+
 /// An iterator over extra headers in [owned][crate::mutable::Commit] and [borrowed][immutable::Commit] commits.
+///
+/// It yields all (name, value) pairs in order of them being encountered, and offers typed access to well-known headers.
 pub struct ExtraHeaders<I> {
     inner: I,
 }
 
+impl<'a, I> Iterator for ExtraHeaders<I>
+where
+    I: Iterator<Item = (&'a BStr, &'a BStr)>,
+{
+    type Item = (&'a BStr, &'a BStr);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
 /// Instantiation and convenience.
 impl<'a, I> ExtraHeaders<I>
 where
@@ -34,6 +132,23 @@ where
         self.find_all("mergetag").map(|b| immutable::Tag::from_bytes(b))
     }
 
+    /// Return an iterator over the data of all git mergetags, which is the tag object exactly as it was when it was merged.
+    ///
+    /// Use it to [verify][crate::signed::verify()] the signatures of merged tags like `git log --show-signature` does,
+    /// or to compute the ids of the merged tags.
+    /// Note that the data has a trailing newline which isn't part of the header value.
+    pub fn mergetags_data(self) -> impl Iterator<Item = BString> + 'a
+    where
+        I: 'a,
+    {
+        self.find_all("mergetag").map(|b| {
+            let mut data = BString::from(Vec::with_capacity(b.len() + 1));
+            data.extend_from_slice(b);
+            data.push(b'\n');
+            data
+        })
+    }
+
     /// Return the cryptographic signature provided by gpg/pgp verbatim.
     pub fn pgp_signature(self) -> Option<&'a BStr> {
         self.find("gpgsig")
@@ -13,4 +13,20 @@ impl<'a> Blob<'a> {
     pub fn from_bytes(data: &[u8]) -> Result<Blob<'_>, Infallible> {
         Ok(Blob { data })
     }
+
+    /// Return true if the data is binary, see [`blob::is_binary()`][crate::blob::is_binary()].
+    pub fn is_binary(&self) -> bool {
+        crate::blob::is_binary(self.data)
+    }
+
+    /// Return the amount of lines in the data, see [`blob::count_lines()`][crate::blob::count_lines()].
+    pub fn count_lines(&self) -> usize {
+        crate::blob::count_lines(self.data)
+    }
+
+    /// Return the encoding indicated by the byte order mark the data starts with, if there is one, see
+    /// [`blob::encoding_from_bom()`][crate::blob::encoding_from_bom()].
+    pub fn encoding_from_bom(&self) -> Option<crate::blob::Encoding> {
+        crate::blob::encoding_from_bom(self.data)
+    }
 }
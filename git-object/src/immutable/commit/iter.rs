@@ -37,6 +37,8 @@ impl Default for State {
 /// It's particularly useful to traverse the commit graph without ever allocating arrays for parents.
 pub struct Iter<'a> {
     data: &'a [u8],
+    /// The length of the data we were created with, to compute offsets in errors.
+    data_len: usize,
     state: State,
 }
 
@@ -45,6 +47,7 @@ impl<'a> Iter<'a> {
     pub fn from_bytes(data: &'a [u8]) -> Iter<'a> {
         Iter {
             data,
+            data_len: data.len(),
             state: State::default(),
         }
     }
@@ -73,10 +76,25 @@ impl<'a> Iter<'a> {
                 _ => None,
             })
     }
+
+    /// Returns the message of the commit as slice of the commit data, skipping all other fields, or `None` if there was a
+    /// decoding error.
+    ///
+    /// The message isn't copied, making this suitable for commits with huge messages, and it can be split into its
+    /// summary and body with [`MessageRef::from_bytes()`][crate::commit::MessageRef::from_bytes()].
+    pub fn message(self) -> Option<&'a BStr> {
+        self.filter_map(Result::ok).find_map(|t| match t {
+            Token::Message(message) => Some(message),
+            _ => None,
+        })
+    }
 }
 
 impl<'a> Iter<'a> {
-    fn next_inner(i: &'a [u8], state: &mut State) -> Result<(&'a [u8], Token<'a>), object::decode::Error> {
+    fn next_inner(
+        i: &'a [u8],
+        state: &mut State,
+    ) -> Result<(&'a [u8], Token<'a>), nom::Err<object::decode::ParseError<'a>>> {
         use State::*;
         Ok(match state {
             Tree => {
@@ -188,7 +206,7 @@ impl<'a> Iterator for Iter<'a> {
             }
             Err(err) => {
                 self.data = &[];
-                Some(Err(err))
+                Some(Err(object::decode::Error::new(self.data_len, err)))
             }
         }
     }
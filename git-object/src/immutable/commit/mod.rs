@@ -42,7 +42,7 @@ impl<'a> Commit<'a> {
     pub fn from_bytes(data: &'a [u8]) -> Result<Commit<'a>, object::decode::Error> {
         decode::commit(data)
             .map(|(_, t)| t)
-            .map_err(object::decode::Error::from)
+            .map_err(|err| object::decode::Error::new(data.len(), err))
     }
     /// Return the `tree` fields hash digest.
     pub fn tree(&self) -> git_hash::ObjectId {
@@ -56,6 +56,12 @@ impl<'a> Commit<'a> {
             .map(|hex_hash| git_hash::ObjectId::from_hex(hex_hash).expect("prior validation of hashes during parsing"))
     }
 
+    /// Returns the `message` split into its title and body, providing its [summary][crate::commit::MessageRef::summary()]
+    /// and [body][crate::commit::MessageRef::body()].
+    pub fn message(&self) -> crate::commit::MessageRef<'a> {
+        crate::commit::MessageRef::from_bytes(self.message)
+    }
+
     /// Returns a convenient iterator over all extra headers.
     pub fn extra_headers(&self) -> crate::commit::ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         crate::commit::ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (*k, v.as_ref())))
@@ -186,76 +186,88 @@ mod convert {
 }
 
 ///
-#[cfg(feature = "verbose-object-parsing-errors")]
 pub mod decode {
     use crate::bstr::{BString, ByteSlice};
 
     /// The type to be used for parse errors.
+    #[cfg(feature = "verbose-object-parsing-errors")]
     pub type ParseError<'a> = nom::error::VerboseError<&'a [u8]>;
     /// The owned type to be used for parse errors.
+    #[cfg(feature = "verbose-object-parsing-errors")]
     pub type ParseErrorOwned = nom::error::VerboseError<BString>;
 
+    /// The type to be used for parse errors, which only keeps the input at which the error occurred without allocating.
+    #[cfg(not(feature = "verbose-object-parsing-errors"))]
+    pub type ParseError<'a> = (&'a [u8], nom::error::ErrorKind);
+    /// The owned type to be used for parse errors, discards everything and is zero size
+    #[cfg(not(feature = "verbose-object-parsing-errors"))]
+    pub type ParseErrorOwned = ();
+
+    /// The maximum amount of bytes of the offending input to keep in an [`Error`].
+    const SNIPPET_LEN: usize = 32;
+
     /// A type to indicate errors during parsing and to abstract away details related to `nom`.
     #[derive(Debug, Clone)]
     pub struct Error {
         /// The actual error
         pub inner: ParseErrorOwned,
+        /// The offset in bytes into the object data at which the error occurred.
+        pub offset: usize,
+        /// Up to 32 bytes of the object data starting at `offset`, to show what couldn't be decoded.
+        pub snippet: BString,
     }
 
-    impl<'a> From<nom::Err<ParseError<'a>>> for Error {
-        fn from(v: nom::Err<ParseError<'a>>) -> Self {
+    impl Error {
+        /// Create a new instance from `err` which was produced when parsing object data of `data_len` bytes,
+        /// or a suffix of it.
+        pub(crate) fn new(data_len: usize, err: nom::Err<ParseError<'_>>) -> Self {
+            let err = match err {
+                nom::Err::Error(err) | nom::Err::Failure(err) => err,
+                nom::Err::Incomplete(_) => unreachable!("we don't have streaming parsers"),
+            };
+            let remaining = remaining_input(&err);
             Error {
-                inner: match v {
-                    nom::Err::Error(err) | nom::Err::Failure(err) => nom::error::VerboseError {
-                        errors: err
-                            .errors
-                            .into_iter()
-                            .map(|(i, v)| (i.as_bstr().to_owned(), v))
-                            .collect(),
-                    },
-                    nom::Err::Incomplete(_) => unreachable!("we don't have streaming parsers"),
-                },
+                offset: data_len.saturating_sub(remaining.len()),
+                snippet: remaining[..remaining.len().min(SNIPPET_LEN)].as_bstr().to_owned(),
+                inner: into_owned(err),
             }
         }
     }
 
-    impl std::fmt::Display for Error {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            self.inner.fmt(f)
-        }
+    #[cfg(feature = "verbose-object-parsing-errors")]
+    fn remaining_input<'a>(err: &ParseError<'a>) -> &'a [u8] {
+        // The first error is the innermost, and thus the most precise one.
+        err.errors.first().map(|(input, _)| *input).unwrap_or_default()
     }
 
-    impl std::error::Error for Error {}
-}
-
-///
-#[cfg(not(feature = "verbose-object-parsing-errors"))]
-pub mod decode {
-    /// The type to be used for parse errors, discards everything and is zero size
-    pub type ParseError<'a> = ();
-    /// The owned type to be used for parse errors, discards everything and is zero size
-    pub type ParseErrorOwned = ();
-
-    /// A type to indicate errors during parsing and to abstract away details related to `nom`.
-    #[derive(Debug, Clone)]
-    pub struct Error {
-        /// The actual error
-        pub inner: ParseErrorOwned,
+    #[cfg(feature = "verbose-object-parsing-errors")]
+    fn into_owned(err: ParseError<'_>) -> ParseErrorOwned {
+        nom::error::VerboseError {
+            errors: err
+                .errors
+                .into_iter()
+                .map(|(i, v)| (i.as_bstr().to_owned(), v))
+                .collect(),
+        }
     }
 
-    impl<'a> From<nom::Err<ParseError<'a>>> for Error {
-        fn from(v: nom::Err<ParseError<'a>>) -> Self {
-            Error {
-                inner: match v {
-                    nom::Err::Error(err) | nom::Err::Failure(err) => err,
-                    nom::Err::Incomplete(_) => unreachable!("we don't have streaming parsers"),
-                },
-            }
-        }
+    #[cfg(not(feature = "verbose-object-parsing-errors"))]
+    fn remaining_input<'a>(err: &ParseError<'a>) -> &'a [u8] {
+        err.0
     }
 
+    #[cfg(not(feature = "verbose-object-parsing-errors"))]
+    fn into_owned(_err: ParseError<'_>) -> ParseErrorOwned {}
+
     impl std::fmt::Display for Error {
-        fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f,
+                "Object data could not be decoded at byte {}, starting with {:?}",
+                self.offset, self.snippet
+            )?;
+            #[cfg(feature = "verbose-object-parsing-errors")]
+            write!(f, ": {}", self.inner)?;
             Ok(())
         }
     }
@@ -1,7 +1,7 @@
 use bstr::{BStr, BString, ByteVec};
 use nom::{
     bytes::complete::{is_not, tag, take_until, take_while_m_n},
-    combinator::{peek, recognize},
+    combinator::{peek, recognize, verify},
     error::{context, ContextError, ParseError},
     multi::many1_count,
     sequence::{preceded, terminated, tuple},
@@ -34,7 +34,8 @@ pub(crate) fn any_header_field_multi_line<'a, E: ParseError<&'a [u8]> + ContextE
 
     let bytes = o[..o.len() - 1].as_bstr();
     let mut out = BString::from(Vec::with_capacity(bytes.len()));
-    let mut lines = bytes.lines();
+    // Split on newlines only to retain carriage returns, which are part of the value.
+    let mut lines = bytes.split_str(NL);
     out.push_str(lines.next().expect("first line"));
     for line in lines {
         out.push(b'\n');
@@ -66,10 +67,13 @@ fn is_hex_digit_lc(b: u8) -> bool {
 }
 
 pub fn hex_hash<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], &'a BStr, E> {
-    take_while_m_n(
-        git_hash::Kind::shortest().len_in_hex(),
-        git_hash::Kind::longest().len_in_hex(),
-        is_hex_digit_lc,
+    verify(
+        take_while_m_n(
+            git_hash::Kind::shortest().len_in_hex(),
+            git_hash::Kind::longest().len_in_hex(),
+            is_hex_digit_lc,
+        ),
+        |hex: &[u8]| git_hash::Kind::from_hex_len(hex.len()).is_some(),
     )(i)
     .map(|(i, hex)| (i, hex.as_bstr()))
 }
@@ -13,8 +13,9 @@ pub struct Tag<'a> {
     pub name: &'a BStr,
     /// The author of the tag.
     pub tagger: Option<git_actor::immutable::Signature<'a>>,
-    /// The message describing this release.
-    pub message: &'a BStr,
+    /// The message describing this release, or `None` if the headers aren't followed by the blank line separating them from
+    /// the message, which git always writes even if the message is empty.
+    pub message: Option<&'a BStr>,
     /// A cryptographic signature over the entire content of the serialized tag object thus far.
     pub pgp_signature: Option<&'a BStr>,
 }
@@ -24,7 +25,7 @@ impl<'a> Tag<'a> {
     pub fn from_bytes(data: &'a [u8]) -> Result<Tag<'a>, object::decode::Error> {
         decode::git_tag(data)
             .map(|(_, t)| t)
-            .map_err(object::decode::Error::from)
+            .map_err(|err| object::decode::Error::new(data.len(), err))
     }
     /// The object this tag points to as `Id`.
     pub fn target(&self) -> git_hash::ObjectId {
@@ -38,7 +39,7 @@ mod decode {
         branch::alt,
         bytes::complete::{tag, take_until, take_while1},
         character::is_alphabetic,
-        combinator::{all_consuming, opt, recognize},
+        combinator::{all_consuming, map, opt, recognize},
         error::context,
         sequence::{preceded, tuple},
         IResult,
@@ -56,11 +57,12 @@ mod decode {
             parse::header_field(i, b"object", parse::hex_hash)
         })(i)?;
 
+        let kind_field = i;
         let (i, kind) = context("type <object kind>", |i| {
             parse::header_field(i, b"type", take_while1(is_alphabetic))
         })(i)?;
         let kind = crate::Kind::from_bytes(kind)
-            .map_err(|_| nom::Err::Error(E::from_error_kind(i, nom::error::ErrorKind::MapRes)))?;
+            .map_err(|_| nom::Err::Error(E::from_error_kind(kind_field, nom::error::ErrorKind::MapRes)))?;
 
         let (i, tag_version) = context("tag <version>", |i| {
             parse::header_field(i, b"tag", take_while1(|b| b != NL[0]))
@@ -70,7 +72,13 @@ mod decode {
             "tagger <signature>",
             opt(|i| parse::header_field(i, b"tagger", parse::signature)),
         )(i)?;
-        let (i, (message, pgp_signature)) = all_consuming(message)(i)?;
+        let (i, (message, pgp_signature)) = if i.is_empty() {
+            (i, (None, None))
+        } else {
+            map(all_consuming(message), |(message, pgp_signature)| {
+                (Some(message), pgp_signature)
+            })(i)?
+        };
         Ok((
             i,
             Tag {
@@ -93,11 +101,9 @@ mod decode {
         }
         let (i, _) = tag(NL)(i)?;
         fn all_to_end<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], (&'a [u8], &'a [u8]), E> {
-            if i.is_empty() {
-                return Err(nom::Err::Error(E::from_error_kind(i, nom::error::ErrorKind::Eof)));
-            }
             // an empty signature message signals that there is none - the function signature is needed
-            // to work with 'alt(…)'. PGP signatures are never empty
+            // to work with 'alt(…)'. PGP signatures are never empty, but messages are when git separates them
+            // from the headers even though there is nothing to say.
             Ok((&[], (&i, &[])))
         }
         let (i, (message, signature)) = alt((
@@ -165,6 +171,8 @@ pub mod iter {
     /// It's particularly useful to dereference only the target chain.
     pub struct Iter<'a> {
         data: &'a [u8],
+        /// The length of the data we were created with, to compute offsets in errors.
+        data_len: usize,
         state: State,
     }
 
@@ -173,6 +181,7 @@ pub mod iter {
         pub fn from_bytes(data: &'a [u8]) -> Iter<'a> {
             Iter {
                 data,
+                data_len: data.len(),
                 state: State::default(),
             }
         }
@@ -187,10 +196,24 @@ pub mod iter {
         pub fn target_id(&mut self) -> Option<ObjectId> {
             self.next().and_then(Result::ok).and_then(Token::into_id)
         }
+
+        /// Returns the message of the tag as slice of the tag data along with its pgp signature, if present, skipping all
+        /// other fields, or `None` if there was a decoding error.
+        ///
+        /// Neither the message nor the signature are copied, making this suitable for tags with huge messages.
+        pub fn message(self) -> Option<(&'a BStr, Option<&'a BStr>)> {
+            self.filter_map(Result::ok).find_map(|t| match t {
+                Token::Body { message, pgp_signature } => Some((message, pgp_signature)),
+                _ => None,
+            })
+        }
     }
 
     impl<'a> Iter<'a> {
-        fn next_inner(i: &'a [u8], state: &mut State) -> Result<(&'a [u8], Token<'a>), object::decode::Error> {
+        fn next_inner(
+            i: &'a [u8],
+            state: &mut State,
+        ) -> Result<(&'a [u8], Token<'a>), nom::Err<object::decode::ParseError<'a>>> {
             use State::*;
             Ok(match state {
                 Target => {
@@ -206,11 +229,13 @@ pub mod iter {
                     )
                 }
                 TargetKind => {
+                    let kind_field = i;
                     let (i, kind) = context("type <object kind>", |i| {
                         parse::header_field(i, b"type", take_while1(is_alphabetic))
                     })(i)?;
                     let kind = crate::Kind::from_bytes(kind).map_err(|_| {
-                        let err = object::decode::ParseError::from_error_kind(i, nom::error::ErrorKind::MapRes);
+                        let err =
+                            object::decode::ParseError::from_error_kind(kind_field, nom::error::ErrorKind::MapRes);
                         nom::Err::Error(err)
                     })?;
                     *state = State::Name;
@@ -257,7 +282,7 @@ pub mod iter {
                 }
                 Err(err) => {
                     self.data = &[];
-                    Some(Err(err))
+                    Some(Err(object::decode::Error::new(self.data_len, err)))
                 }
             }
         }
@@ -1,7 +1,8 @@
 use std::convert::TryFrom;
 
-use crate::{immutable::object, tree};
+use crate::{immutable::object, mutable, tree};
 use bstr::BStr;
+use git_hash::oid;
 
 /// A directory snapshot containing files (blobs), directories (trees) and submodules (commits).
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
@@ -19,12 +20,37 @@ pub struct TreeIter<'a> {
     /// The directories and files contained in this tree.
     #[cfg_attr(feature = "serde1", serde(borrow))]
     data: &'a [u8],
+    /// The length of the tree data we were created with, to compute offsets in errors.
+    data_len: usize,
+    /// The data starting at the entry which failed to decode, to be able to skip it.
+    malformed: &'a [u8],
 }
 
 impl<'a> TreeIter<'a> {
     /// Instantiate an iterator from the given tree data.
     pub fn from_bytes(data: &'a [u8]) -> TreeIter<'a> {
-        TreeIter { data }
+        TreeIter {
+            data,
+            data_len: data.len(),
+            malformed: &[],
+        }
+    }
+
+    /// Skip the entry which failed to decode with the last returned error and return true if iteration can
+    /// resume with the entry after it, or false if there is nothing more to decode.
+    ///
+    /// As entries are terminated only by the 20 bytes object id following the null byte after their filename,
+    /// the malformed entry is assumed to at least have a null byte followed by 20 bytes.
+    /// This allows tooling to report all corrupt entries of a tree instead of just the first one.
+    pub fn skip_malformed_entry(&mut self) -> bool {
+        let malformed = std::mem::take(&mut self.malformed);
+        match malformed.iter().position(|b| *b == 0) {
+            Some(null_pos) if null_pos + 1 + 20 < malformed.len() => {
+                self.data = &malformed[null_pos + 1 + 20..];
+                true
+            }
+            _ => false,
+        }
     }
 }
 
@@ -44,7 +70,9 @@ pub struct Entry<'a> {
 impl<'a> Tree<'a> {
     /// Deserialize a Tree from `data`.
     pub fn from_bytes(data: &'a [u8]) -> Result<Tree<'a>, object::decode::Error> {
-        decode::tree(data).map(|(_, t)| t).map_err(object::decode::Error::from)
+        decode::tree(data)
+            .map(|(_, t)| t)
+            .map_err(|err| object::decode::Error::new(data.len(), err))
     }
 
     /// Create an instance of the empty tree.
@@ -62,9 +90,144 @@ impl<'a> TreeIter<'a> {
     }
 }
 
+/// Lookup
+impl<'a> Tree<'a> {
+    /// Return the entry at the slash-separated `path`, like `src/lib.rs`, or `None` if there is no such entry.
+    ///
+    /// `find` is used to load the subtrees along `path` into `buf`.
+    pub fn lookup_entry<Find>(
+        &self,
+        path: impl AsRef<BStr>,
+        buf: &mut Vec<u8>,
+        find: Find,
+    ) -> Result<Option<mutable::tree::Entry>, lookup::Error>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<TreeIter<'b>>,
+    {
+        lookup::entry(self.entries.iter().cloned().map(Ok), path.as_ref(), buf, find)
+    }
+}
+
+/// Lookup
+impl<'a> TreeIter<'a> {
+    /// Return the entry at the slash-separated `path`, like `src/lib.rs`, or `None` if there is no such entry.
+    ///
+    /// `find` is used to load the subtrees along `path` into `buf`, and only the entries up to the ones on `path`
+    /// are decoded.
+    pub fn lookup_entry<Find>(
+        self,
+        path: impl AsRef<BStr>,
+        buf: &mut Vec<u8>,
+        find: Find,
+    ) -> Result<Option<mutable::tree::Entry>, lookup::Error>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<TreeIter<'b>>,
+    {
+        lookup::entry(self, path.as_ref(), buf, find)
+    }
+}
+
+///
+pub mod lookup {
+    use super::Entry;
+    use crate::{immutable::object, mutable};
+    use bstr::{BStr, BString, ByteSlice};
+    use git_hash::{oid, ObjectId};
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`Tree::lookup_entry()`][super::Tree::lookup_entry()] and
+        /// [`TreeIter::lookup_entry()`][super::TreeIter::lookup_entry()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            FindTree { path: BString, id: ObjectId } {
+                display("The tree at '{}' with id {} could not be found", path, id)
+            }
+            DecodeTree { path: BString, err: object::decode::Error } {
+                display("The tree at '{}' could not be decoded", path)
+                source(err)
+            }
+        }
+    }
+
+    pub(crate) fn entry<'a, Find>(
+        root: impl Iterator<Item = Result<Entry<'a>, object::decode::Error>>,
+        path: &BStr,
+        buf: &mut Vec<u8>,
+        mut find: Find,
+    ) -> Result<Option<mutable::tree::Entry>, Error>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<super::TreeIter<'b>>,
+    {
+        let mut components = path.split(|b| *b == b'/');
+        let first = components.next().expect("splitting yields at least one component");
+        let mut entry = match entry_named(root, first, "".into())? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let mut tree_path_len = first.len();
+        for component in components {
+            if !entry.mode.is_tree() {
+                return Ok(None);
+            }
+            let tree_path = path[..tree_path_len].as_bstr();
+            let tree = find_tree(&mut find, buf, tree_path, entry.oid)?;
+            entry = match entry_named(tree, component, tree_path)? {
+                Some(entry) => entry,
+                None => return Ok(None),
+            };
+            tree_path_len += 1 + component.len();
+        }
+        Ok(Some(entry))
+    }
+
+    /// Load the tree with `id` at `path` into `buf` using `find`, or fail if it can't be found.
+    pub(crate) fn find_tree<'b, Find>(
+        find: &mut Find,
+        buf: &'b mut Vec<u8>,
+        path: &BStr,
+        id: ObjectId,
+    ) -> Result<super::TreeIter<'b>, Error>
+    where
+        Find: for<'c> FnMut(&oid, &'c mut Vec<u8>) -> Option<super::TreeIter<'c>>,
+    {
+        find(&id, buf).ok_or_else(|| Error::FindTree {
+            path: path.to_owned(),
+            id,
+        })
+    }
+
+    /// Return the error to use if an entry of the tree at `path` could not be decoded.
+    pub(crate) fn decode_tree_error(path: &BStr) -> impl FnOnce(object::decode::Error) -> Error + '_ {
+        move |err| Error::DecodeTree {
+            path: path.to_owned(),
+            err,
+        }
+    }
+
+    fn entry_named<'a>(
+        entries: impl Iterator<Item = Result<Entry<'a>, object::decode::Error>>,
+        filename: &[u8],
+        tree_path: &BStr,
+    ) -> Result<Option<mutable::tree::Entry>, Error> {
+        for entry in entries {
+            let entry = entry.map_err(decode_tree_error(tree_path))?;
+            if entry.filename == filename {
+                return Ok(Some(entry.into()));
+            }
+        }
+        Ok(None)
+    }
+}
+
 impl<'a> Default for TreeIter<'a> {
     fn default() -> Self {
-        TreeIter { data: &[] }
+        TreeIter {
+            data: &[],
+            data_len: 0,
+            malformed: &[],
+        }
     }
 }
 
@@ -81,8 +244,8 @@ impl<'a> Iterator for TreeIter<'a> {
                 Some(Ok(entry))
             }
             Err(err) => {
-                self.data = &[];
-                Some(Err(err.into()))
+                self.malformed = std::mem::take(&mut self.data);
+                Some(Err(object::decode::Error::new(self.data_len, err)))
             }
         }
     }
@@ -125,10 +288,10 @@ mod decode {
 
     const NULL: &[u8] = b"\0";
 
-    pub fn entry<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&[u8], Entry<'_>, E> {
-        let (i, mode) = terminated(take_while_m_n(5, 6, is_digit), tag(SPACE))(i)?;
+    pub fn entry<'a, E: ParseError<&'a [u8]>>(entry: &'a [u8]) -> IResult<&[u8], Entry<'_>, E> {
+        let (i, mode) = terminated(take_while_m_n(5, 6, is_digit), tag(SPACE))(entry)?;
         let mode = tree::EntryMode::try_from(mode)
-            .map_err(|invalid| nom::Err::Error(E::from_error_kind(invalid, nom::error::ErrorKind::MapRes)))?;
+            .map_err(|_invalid| nom::Err::Error(E::from_error_kind(entry, nom::error::ErrorKind::MapRes)))?;
         let (i, filename) = terminated(take_while1(|b| b != NULL[0]), tag(NULL))(i)?;
         let (i, oid) = take(20u8)(i)?;
 
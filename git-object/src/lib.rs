@@ -14,5 +14,9 @@ pub mod mutable;
 mod types;
 pub use types::{tree, Error, Kind};
 
+pub mod blob;
 ///
 pub mod commit;
+pub mod loose;
+pub mod pathspec;
+pub mod signed;
@@ -0,0 +1,3 @@
+//! Functionality related to loose objects, which are stored zlib-compressed in their own file and prefixed with a header.
+///
+pub mod header;
@@ -0,0 +1,80 @@
+//! Encode and decode the header of loose objects, `<kind> <size>\0`, which precedes their data when they are stored or hashed.
+use crate::{BString, Kind};
+use quick_error::quick_error;
+use std::io;
+
+/// The maximum length of a header in bytes, as needed for commits of the largest possible size.
+///
+/// Buffers of this length can hold any valid header, and inputs at least this long must contain a complete header.
+pub const MAX_LEN: usize = b"commit ".len() + 20 /* digits of u64::MAX */ + 1;
+
+quick_error! {
+    /// The error returned by [`decode()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Incomplete {
+            display("The header isn't terminated by a null byte yet, more data is needed")
+        }
+        InvalidHeader(message: &'static str) {
+            display("{}", message)
+        }
+        InvalidKind(err: crate::Error) {
+            display("The object kind in the header is invalid")
+            from()
+            source(err)
+        }
+        InvalidSize { size: BString } {
+            display("The object size {:?} in the header is not a canonical decimal number that fits into 64 bits", size)
+        }
+    }
+}
+
+/// Decode a loose object header, being `<kind> <size>\0`, from the beginning of `input`, returning the object's
+/// [`Kind`], its `size` in bytes and the length of the header, which is where the object data starts.
+///
+/// `input` may contain just a part of the object or its header, which allows to decode headers while streaming:
+/// if no null byte is contained in it yet and it is shorter than [`MAX_LEN`], [`Error::Incomplete`] is returned,
+/// indicating that more data is needed.
+///
+/// Like git, the size must be written in decimal without leading zeroes.
+pub fn decode(input: &[u8]) -> Result<(Kind, u64, usize), Error> {
+    let header_end = match input.iter().take(MAX_LEN).position(|&b| b == 0) {
+        Some(pos) => pos,
+        None if input.len() < MAX_LEN => return Err(Error::Incomplete),
+        None => return Err(Error::InvalidHeader("Did not find 0 byte in header")),
+    };
+    let header = &input[..header_end];
+    let space = header
+        .iter()
+        .position(|&b| b == b' ')
+        .ok_or(Error::InvalidHeader("Expected '<type> <size>'"))?;
+    let (kind, size) = (&header[..space], &header[space + 1..]);
+    Ok((Kind::from_bytes(kind)?, parse_size(size)?, header_end + 1))
+}
+
+fn parse_size(size: &[u8]) -> Result<u64, Error> {
+    let invalid = || Error::InvalidSize { size: size.into() };
+    if size.is_empty() || (size.len() > 1 && size[0] == b'0') {
+        return Err(invalid());
+    }
+    size.iter().try_fold(0u64, |acc, b| {
+        if !b.is_ascii_digit() {
+            return Err(invalid());
+        }
+        acc.checked_mul(10)
+            .and_then(|acc| acc.checked_add(u64::from(b - b'0')))
+            .ok_or_else(invalid)
+    })
+}
+
+/// Encode the header of an object of `kind` with `size` bytes of data into `out`, returning the amount of bytes written,
+/// which is at most [`MAX_LEN`].
+pub fn encode(kind: Kind, size: u64, mut out: impl io::Write) -> io::Result<usize> {
+    let kind = kind.as_bytes();
+    out.write_all(kind)?;
+    out.write_all(b" ")?;
+    let size_len = itoa::write(&mut out, size)?;
+    out.write_all(b"\0")?;
+    Ok(kind.len() + 1 + size_len + 1)
+}
@@ -35,6 +35,11 @@ impl Commit {
     pub fn extra_headers(&self) -> commit::ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         commit::ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (k.as_bstr(), v.as_bstr())))
     }
+    /// Returns the `message` split into its title and body, providing its [summary][commit::MessageRef::summary()]
+    /// and [body][commit::MessageRef::body()].
+    pub fn message(&self) -> commit::MessageRef<'_> {
+        commit::MessageRef::from_bytes(&self.message)
+    }
     /// Serializes this instance to `out` in the git serialization format.
     pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
         encode::trusted_header_id(b"tree", &self.tree, &mut out)?;
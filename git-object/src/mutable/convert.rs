@@ -14,7 +14,7 @@ impl From<immutable::Tag<'_>> for mutable::Tag {
             target: git_hash::ObjectId::from_hex(&target).expect("40 bytes hex sha1"),
             name: name.to_owned(),
             target_kind,
-            message: message.to_owned(),
+            message: message.map(ToOwned::to_owned),
             signature: signature.map(Into::into),
             pgp_signature: pgp_signature.map(ToOwned::to_owned),
         }
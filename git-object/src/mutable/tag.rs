@@ -34,8 +34,9 @@ pub struct Tag {
     pub target_kind: crate::Kind,
     /// The name of the tag, e.g. "v1.0".
     pub name: BString,
-    /// The message describing the tag.
-    pub message: BString,
+    /// The message describing the tag, or `None` if there is no blank line separating it from the headers, which git always
+    /// writes even if the message is empty.
+    pub message: Option<BString>,
     /// The tags author.
     pub signature: Option<git_actor::Signature>,
     /// A pgp signature over all bytes of the encoded tag, excluding the pgp signature itself.
@@ -52,9 +53,11 @@ impl Tag {
             encode::trusted_header_signature(b"tagger", tagger, &mut out)?;
         }
 
-        if !self.message.is_empty() {
+        if self.message.is_some() || self.pgp_signature.is_some() {
             out.write_all(NL)?;
-            out.write_all(&self.message)?;
+        }
+        if let Some(message) = &self.message {
+            out.write_all(message)?;
         }
         if let Some(ref message) = self.pgp_signature {
             out.write_all(NL)?;
@@ -1,17 +1,29 @@
 use crate::{mutable::SPACE, tree::EntryMode};
-use bstr::{BString, ByteSlice};
+use bstr::{BStr, BString, ByteSlice};
 use quick_error::quick_error;
 use std::cmp::Ordering;
 use std::io;
 
 quick_error! {
-    /// The Error used in [`Tree::write_to()`].
+    /// The Error used in [`Tree::write_to()`] and [`Tree::insert()`].
     #[derive(Debug)]
     #[allow(missing_docs)]
     pub enum Error {
         NewlineInFilename(name: BString) {
             display("Newlines are invalid in file paths: {:?}", name)
         }
+        EmptyFilename {
+            display("Tree entries must have a filename")
+        }
+        InvalidFilename(name: BString) {
+            display("Filenames must not contain slashes or null bytes: {:?}", name)
+        }
+        DuplicateFilename(name: BString) {
+            display("The filename {:?} is used by more than one tree entry", name)
+        }
+        Unsorted { previous: BString, current: BString } {
+            display("The tree entry {:?} must be sorted before {:?}", current, previous)
+        }
     }
 }
 
@@ -21,11 +33,16 @@ impl From<Error> for io::Error {
     }
 }
 
+///
+pub mod editor;
+pub use editor::Editor;
+
 /// A mutable Tree, containing other trees, blobs or commits.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tree {
-    /// The directories and files contained in this tree. They must be and remain sorted by [`filename`][Entry::filename].
+    /// The directories and files contained in this tree. They must be and remain sorted like [`Entry`] orders them, which
+    /// is assured when using [`insert()`][Tree::insert()] or by calling `entries.sort()` after making changes.
     pub entries: Vec<Entry>,
 }
 
@@ -48,13 +65,41 @@ impl PartialOrd for Entry {
 }
 
 impl Ord for Entry {
-    /// Entries compare by the common portion of the filename. This is critical for proper functioning of algorithms working on trees.
+    /// Entries compare by their filename like git does, with the filenames of trees compared as if they had a trailing
+    /// slash. Hence `a.rs` sorts before the tree `a` (`a/`), which sorts before `a0`.
+    /// This is critical for proper functioning of algorithms working on trees.
     fn cmp(&self, other: &Self) -> Ordering {
-        let len = self.filename.len().min(other.filename.len());
-        self.filename[..len].cmp(&other.filename[..len])
+        compare_filenames(
+            self.filename.as_ref(),
+            self.mode.is_tree(),
+            other.filename.as_ref(),
+            other.mode.is_tree(),
+        )
     }
 }
 
+fn compare_filenames(lhs: &BStr, lhs_is_tree: bool, rhs: &BStr, rhs_is_tree: bool) -> Ordering {
+    let common = lhs.len().min(rhs.len());
+    lhs[..common].cmp(&rhs[..common]).then_with(|| {
+        let lhs = lhs.get(common).or_else(|| lhs_is_tree.then(|| &b'/'));
+        let rhs = rhs.get(common).or_else(|| rhs_is_tree.then(|| &b'/'));
+        lhs.cmp(&rhs)
+    })
+}
+
+fn validate_filename(filename: &BStr) -> Result<(), Error> {
+    if filename.is_empty() {
+        return Err(Error::EmptyFilename);
+    }
+    if filename.find_byteset(b"/\0").is_some() {
+        return Err(Error::InvalidFilename(filename.to_owned()));
+    }
+    if filename.find_byte(b'\n').is_some() {
+        return Err(Error::NewlineInFilename(filename.to_owned()));
+    }
+    Ok(())
+}
+
 /// Serialization
 impl EntryMode {
     /// Return the representation as used in the git internal format.
@@ -70,26 +115,94 @@ impl EntryMode {
     }
 }
 
+/// Initialization
+impl Tree {
+    /// Create a tree without any entry, which is the [empty tree][git_hash::ObjectId::empty_tree()] once serialized.
+    pub const fn empty() -> Self {
+        Tree { entries: Vec::new() }
+    }
+}
+
+/// Editing
+impl Tree {
+    /// Add an entry of `mode` named `filename` pointing to `oid` at the position git expects it to be,
+    /// returning the previous entry with the same `filename` if there was one.
+    ///
+    /// Note that the previous entry is replaced even if its mode differs, so a file can be replaced by a directory and
+    /// vice versa. `filename` must not be empty and must not contain slashes, null bytes or newlines.
+    pub fn insert(
+        &mut self,
+        mode: EntryMode,
+        filename: impl Into<BString>,
+        oid: git_hash::ObjectId,
+    ) -> Result<Option<Entry>, Error> {
+        let filename = filename.into();
+        validate_filename(filename.as_ref())?;
+        let previous = self.remove(filename.as_ref());
+        let entry = Entry { mode, filename, oid };
+        let index = self
+            .entries
+            .binary_search(&entry)
+            .expect_err("entries with the same filename were removed");
+        self.entries.insert(index, entry);
+        Ok(previous)
+    }
+
+    /// Remove the entry named `filename` and return it, or `None` if there is no such entry.
+    pub fn remove(&mut self, filename: &BStr) -> Option<Entry> {
+        [false, true]
+            .iter()
+            .find_map(|is_tree| {
+                self.entries
+                    .binary_search_by(|entry| {
+                        compare_filenames(entry.filename.as_ref(), entry.mode.is_tree(), filename, *is_tree)
+                    })
+                    .ok()
+            })
+            .map(|index| self.entries.remove(index))
+    }
+}
+
 /// Serialization
 impl Tree {
     /// Serialize this tree to `out` in the git internal format.
+    ///
+    /// Fails if the entries are not sorted, if a filename is used more than once or if filenames are invalid,
+    /// as git would reject the resulting tree.
     pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
-        debug_assert_eq!(
-            &{
-                let mut entries_sorted = self.entries.clone();
-                entries_sorted.sort();
-                entries_sorted
-            },
-            &self.entries,
-            "entries for serialization must be sorted by filename"
-        );
+        for (index, pair) in self.entries.windows(2).enumerate() {
+            let (previous, current) = (&pair[0], &pair[1]);
+            if previous.filename == current.filename {
+                return Err(Error::DuplicateFilename(current.filename.to_owned()).into());
+            }
+            if previous.cmp(current) != Ordering::Less {
+                return Err(Error::Unsorted {
+                    previous: previous.filename.to_owned(),
+                    current: current.filename.to_owned(),
+                }
+                .into());
+            }
+            // A non-tree entry with the same name sorts before a tree, but not necessarily right before it.
+            if current.mode.is_tree()
+                && self.entries[..=index]
+                    .binary_search_by(|entry| {
+                        compare_filenames(
+                            entry.filename.as_ref(),
+                            entry.mode.is_tree(),
+                            current.filename.as_ref(),
+                            false,
+                        )
+                    })
+                    .is_ok()
+            {
+                return Err(Error::DuplicateFilename(current.filename.to_owned()).into());
+            }
+        }
         for Entry { mode, filename, oid } in &self.entries {
             out.write_all(mode.as_bytes())?;
             out.write_all(SPACE)?;
 
-            if filename.find_byte(b'\n').is_some() {
-                return Err(Error::NewlineInFilename(filename.to_owned()).into());
-            }
+            validate_filename(filename.as_ref())?;
             out.write_all(&filename)?;
             out.write_all(&[b'\0'])?;
 
@@ -98,3 +211,31 @@ impl Tree {
         Ok(())
     }
 }
+
+#[cfg(feature = "git2")]
+mod git2_crate {
+    use super::Entry;
+    use crate::tree::EntryMode;
+    use std::convert::TryFrom;
+
+    /// Fails with the file mode of the entry if it isn't one git supports.
+    impl TryFrom<&git2::TreeEntry<'_>> for Entry {
+        type Error = i32;
+
+        fn try_from(entry: &git2::TreeEntry<'_>) -> Result<Self, Self::Error> {
+            let mode = match entry.filemode() {
+                0o040000 => EntryMode::Tree,
+                0o100644 => EntryMode::Blob,
+                0o100755 => EntryMode::BlobExecutable,
+                0o120000 => EntryMode::Link,
+                0o160000 => EntryMode::Commit,
+                mode => return Err(mode),
+            };
+            Ok(Entry {
+                mode,
+                filename: entry.name_bytes().into(),
+                oid: git_hash::ObjectId::from_20_bytes(entry.id().as_bytes()),
+            })
+        }
+    }
+}
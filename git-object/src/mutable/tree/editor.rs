@@ -0,0 +1,215 @@
+//! Edit trees recursively by path, see [`Editor`].
+use crate::{
+    immutable::{self, tree::lookup},
+    mutable::{tree::Entry, Tree},
+    tree::EntryMode,
+};
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use git_hash::{oid, ObjectId};
+use quick_error::quick_error;
+use std::collections::BTreeMap;
+
+quick_error! {
+    /// The error returned by [`Editor::upsert()`] and [`Editor::remove()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        EmptyPath {
+            display("Paths to edit must not be empty")
+        }
+        InvalidPath { path: BString, err: crate::mutable::tree::Error } {
+            display("The path '{}' contains a component that is no valid filename", path)
+            source(err)
+        }
+        LoadTree(err: immutable::tree::lookup::Error) {
+            display("A tree along the path could not be loaded")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// Change a tree by adding, replacing or removing entries at arbitrary paths, and write all changed trees once done.
+///
+/// Only the trees along the edited paths are loaded using the `Find` function, all other trees are left untouched.
+/// Trees which end up without entries are removed from their parent trees, like git does.
+pub struct Editor<Find> {
+    find: Find,
+    /// The trees loaded or created so far, keyed by their slash-separated path with the root tree having the empty path.
+    trees: BTreeMap<BString, Tree>,
+    buf: Vec<u8>,
+}
+
+/// Initialization
+impl<Find> Editor<Find>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+{
+    /// Create a new editor to change the `root` tree, using `find` to load all subtrees along the paths to edit.
+    ///
+    /// Pass an [empty tree][Tree::empty()] to create an entirely new tree.
+    pub fn new(root: Tree, find: Find) -> Self {
+        let mut trees = BTreeMap::new();
+        trees.insert(BString::default(), root);
+        Editor {
+            find,
+            trees,
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Editing
+impl<Find> Editor<Find>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+{
+    /// Add an entry of `mode` pointing to `id` at the slash-separated `path`, like `src/lib.rs`, or replace the entry
+    /// that is already present there.
+    ///
+    /// Missing trees along `path` are created, and entries which are in the way of these trees are replaced.
+    pub fn upsert(&mut self, path: impl AsRef<BStr>, mode: EntryMode, id: ObjectId) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        let (parent_path, filename) = split_path(path)?;
+        self.load_trees_to(parent_path, true)?;
+        self.trees
+            .get_mut(parent_path)
+            .expect("parent was created")
+            .insert(mode, filename, id)
+            .map_err(|err| Error::InvalidPath {
+                path: path.to_owned(),
+                err,
+            })?;
+        self.forget_trees_at(path);
+        Ok(self)
+    }
+
+    /// Remove the entry at the slash-separated `path`, along with all entries it contains if it is a tree.
+    ///
+    /// Removing an entry which doesn't exist does nothing.
+    pub fn remove(&mut self, path: impl AsRef<BStr>) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        let (parent_path, filename) = split_path(path)?;
+        if self.load_trees_to(parent_path, false)? {
+            self.trees
+                .get_mut(parent_path)
+                .expect("parent was loaded")
+                .remove(filename);
+            self.forget_trees_at(path);
+        }
+        Ok(self)
+    }
+
+    /// Make sure the tree at `path` and all trees leading to it are loaded. If `create` is true, missing trees are created
+    /// and entries in their way are replaced, otherwise false is returned if there is no tree at `path`.
+    fn load_trees_to(&mut self, path: &BStr, create: bool) -> Result<bool, Error> {
+        if path.is_empty() {
+            return Ok(true);
+        }
+        let mut tree_path = BString::default();
+        for filename in path.split_str("/") {
+            let parent_path_len = tree_path.len();
+            if parent_path_len != 0 {
+                tree_path.push_byte(b'/');
+            }
+            tree_path.push_str(filename);
+            if self.trees.contains_key(tree_path.as_bstr()) {
+                continue;
+            }
+
+            let parent = self
+                .trees
+                .get_mut(tree_path[..parent_path_len].as_bstr())
+                .expect("parents are loaded first");
+            let tree = match parent.entries.iter().find(|entry| entry.filename == filename) {
+                Some(Entry {
+                    mode: EntryMode::Tree,
+                    oid,
+                    ..
+                }) => load_tree(&mut self.find, &mut self.buf, tree_path.as_bstr(), *oid)?,
+                _ if create => {
+                    parent
+                        .insert(EntryMode::Tree, filename, ObjectId::null_sha1())
+                        .map_err(|err| Error::InvalidPath {
+                            path: path.to_owned(),
+                            err,
+                        })?;
+                    Tree::empty()
+                }
+                _ => return Ok(false),
+            };
+            self.trees.insert(tree_path.clone(), tree);
+        }
+        Ok(true)
+    }
+
+    /// Forget all loaded trees at and below `path` as their parent entry was replaced or removed.
+    fn forget_trees_at(&mut self, path: &BStr) {
+        let mut prefix = BString::from(path);
+        prefix.push_byte(b'/');
+        self.trees
+            .retain(|tree_path, _| tree_path != path && !tree_path.starts_with(prefix.as_slice()));
+    }
+}
+
+/// Writing
+impl<Find> Editor<Find> {
+    /// Write all trees that were changed bottom-up by calling `write` with each of them, and return the id of the new
+    /// root tree. `write` is expected to store each tree and return its id, or fail with an error that is returned as is.
+    pub fn write<E>(mut self, mut write: impl FnMut(&Tree) -> Result<ObjectId, E>) -> Result<ObjectId, E> {
+        let mut paths: Vec<_> = self.trees.keys().cloned().collect();
+        paths.sort_by_key(|path| {
+            std::cmp::Reverse(if path.is_empty() {
+                0
+            } else {
+                path.find_iter("/").count() + 1
+            })
+        });
+        for path in paths {
+            let tree = self.trees.remove(&path).expect("present");
+            if path.is_empty() {
+                return write(&tree);
+            }
+            let (parent_path, filename) = split_path(path.as_bstr()).expect("only the root has an empty path");
+            let parent = self
+                .trees
+                .get_mut(parent_path)
+                .expect("parents are loaded with their children");
+            if tree.entries.is_empty() {
+                parent.remove(filename);
+            } else {
+                let id = write(&tree)?;
+                parent
+                    .entries
+                    .iter_mut()
+                    .find(|entry| entry.filename == filename)
+                    .expect("entries of loaded trees are in their parents")
+                    .oid = id;
+            }
+        }
+        unreachable!("the root tree is always present and written last")
+    }
+}
+
+fn split_path(path: &BStr) -> Result<(&BStr, &BStr), Error> {
+    if path.is_empty() {
+        return Err(Error::EmptyPath);
+    }
+    Ok(match path.rfind_byte(b'/') {
+        Some(pos) => (path[..pos].as_bstr(), path[pos + 1..].as_bstr()),
+        None => ("".into(), path),
+    })
+}
+
+fn load_tree<Find>(find: &mut Find, buf: &mut Vec<u8>, path: &BStr, id: ObjectId) -> Result<Tree, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+{
+    let tree = lookup::find_tree(find, buf, path, id)?;
+    Ok(Tree {
+        entries: tree
+            .map(|entry| entry.map(Into::into))
+            .collect::<Result<_, _>>()
+            .map_err(lookup::decode_tree_error(path))?,
+    })
+}
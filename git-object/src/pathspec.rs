@@ -0,0 +1,126 @@
+//! Match literal pathspecs against the entries of trees traversed level by level, to skip the trees which can't contain any
+//! of them without looking them up.
+use std::collections::VecDeque;
+
+use crate::{BStr, BString};
+use bstr::ByteVec;
+
+/// Pathspecs along with the path of the tree whose entries are currently matched, and the paths of the trees scheduled for later.
+///
+/// Pathspecs are paths relative to the root tree using slashes as separators, like `src` or `src/lib.rs`, and are matched
+/// literally, with trailing slashes being ignored. Without pathspecs all entries match, and paths aren't tracked at all.
+///
+/// Trees are expected to be traversed breadth-first, so the paths of trees are tracked in the order in which they are
+/// [scheduled][Pathspecs::push_tree()] and [entered][Pathspecs::pop_tree()].
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct Pathspecs {
+    specs: Vec<BString>,
+    /// The path of the tree whose entries are currently matched, with a trailing slash unless it's empty.
+    current: BString,
+    /// The paths of the scheduled trees, in the same order.
+    scheduled: VecDeque<BString>,
+    /// The path of the entry to match.
+    buf: BString,
+}
+
+/// The relation of a tree entry to the [`Pathspecs`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Relevance {
+    /// The entry is at or below one of the pathspecs.
+    pub is_match: bool,
+    /// The entry is a tree leading up to one of the pathspecs, and thus contains matching entries if they exist.
+    pub contains_match: bool,
+}
+
+impl Relevance {
+    /// Return true if the entry matches or contains matching entries.
+    pub fn is_relevant(&self) -> bool {
+        self.is_match || self.contains_match
+    }
+}
+
+impl Pathspecs {
+    /// Create a new instance to match the given `specs`, which match all entries if empty.
+    pub fn new(specs: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        let mut pathspecs = Pathspecs::default();
+        pathspecs.reset(specs);
+        pathspecs
+    }
+
+    /// Match the given `specs` from the root tree on, like a [new instance][Pathspecs::new()] but reusing allocations.
+    pub fn reset(&mut self, specs: impl IntoIterator<Item = impl Into<BString>>) {
+        self.specs.clear();
+        self.specs.extend(specs.into_iter().map(|spec| {
+            let mut spec = spec.into();
+            while spec.last() == Some(&b'/') {
+                spec.pop();
+            }
+            spec
+        }));
+        self.current.clear();
+        self.scheduled.clear();
+        self.buf.clear();
+    }
+
+    /// Return true if there are no pathspecs, which makes all entries match.
+    pub fn is_empty(&self) -> bool {
+        self.specs.is_empty()
+    }
+
+    /// Return how the entry `name` of the current tree relates to the pathspecs, with trees which may contain matching
+    /// entries being indicated by `is_tree`.
+    pub fn relevance(&mut self, name: &BStr, is_tree: bool) -> Relevance {
+        if self.specs.is_empty() {
+            return Relevance {
+                is_match: true,
+                contains_match: false,
+            };
+        }
+        self.buf.clear();
+        self.buf.push_str(&self.current);
+        self.buf.push_str(name);
+        let path = self.buf.as_slice();
+        let is_match = self.specs.iter().any(|spec| {
+            spec.is_empty()
+                || path
+                    .strip_prefix(spec.as_slice())
+                    .map_or(false, |rest| rest.is_empty() || rest[0] == b'/')
+        });
+        let contains_match = is_tree
+            && self.specs.iter().any(|spec| {
+                spec.strip_prefix(path)
+                    .map_or(false, |rest| rest.first() == Some(&b'/'))
+            });
+        Relevance {
+            is_match,
+            contains_match,
+        }
+    }
+
+    /// Remember the path of the tree entry `name` of the current tree, which was scheduled to be traversed after all trees
+    /// scheduled before it.
+    pub fn push_tree(&mut self, name: &BStr) {
+        if self.specs.is_empty() {
+            return;
+        }
+        let mut path = self.current.clone();
+        path.push_str(name);
+        path.push(b'/');
+        self.scheduled.push_back(path);
+    }
+
+    /// Match the entries of the tree that was scheduled first from now on.
+    ///
+    /// # Panics
+    ///
+    /// If there are pathspecs but no tree was scheduled.
+    pub fn pop_tree(&mut self) {
+        if self.specs.is_empty() {
+            return;
+        }
+        self.current = self
+            .scheduled
+            .pop_front()
+            .expect("trees are scheduled before they are entered");
+    }
+}
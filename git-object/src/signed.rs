@@ -0,0 +1,158 @@
+//! Split signed commits and tags into the signed payload and their signature to verify them like
+//! `git verify-commit` and `git verify-tag` do, without depending on any particular cryptography library.
+use std::borrow::Cow;
+
+use crate::{BStr, ByteSlice, Kind};
+
+/// The names of commit headers containing signatures, along with the kind of hash of the repository they are made for.
+const COMMIT_SIGNATURE_HEADERS: &[(&[u8], git_hash::Kind)] = &[
+    (b"gpgsig", git_hash::Kind::Sha1),
+    (b"gpgsig-sha256", git_hash::Kind::Sha256),
+];
+
+/// The lines with which signatures of tags begin, for PGP, X509 and SSH signatures.
+const TAG_SIGNATURE_STARTS: &[&[u8]] = &[
+    b"-----BEGIN PGP SIGNATURE-----",
+    b"-----BEGIN PGP MESSAGE-----",
+    b"-----BEGIN SIGNED MESSAGE-----",
+    b"-----BEGIN SSH SIGNATURE-----",
+];
+
+/// A signed object split into the data that was signed and the signature over it.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parts<'a> {
+    /// The data that was signed, which is the object data without the signature.
+    pub payload: Cow<'a, BStr>,
+    /// The signature over the `payload`, for example an ASCII armored PGP signature.
+    pub signature: Cow<'a, BStr>,
+}
+
+/// Split the object `data` of `kind` into its signed payload and its signature, or return `None` if it isn't signed.
+///
+/// Only commits and tags can be signed, and `hash_kind` selects the commit signature to use as commits may be signed
+/// for SHA1 and SHA256 repositories at the same time. It is ignored for tags.
+pub fn split(kind: Kind, hash_kind: git_hash::Kind, data: &[u8]) -> Option<Parts<'_>> {
+    match kind {
+        Kind::Commit => split_commit(data, hash_kind),
+        Kind::Tag => split_tag(data),
+        Kind::Tree | Kind::Blob => None,
+    }
+}
+
+/// Split the commit `data` into its signed payload and the signature made for a repository using `hash_kind`, or return
+/// `None` if there is no such signature.
+///
+/// The signature is the unfolded value of the `gpgsig` header for SHA1 or the `gpgsig-sha256` header for SHA256, and
+/// the payload is the commit without any of these headers, just like `git verify-commit` sees it.
+pub fn split_commit(data: &[u8], hash_kind: git_hash::Kind) -> Option<Parts<'_>> {
+    let mut payload = Vec::with_capacity(data.len());
+    let mut signature = Vec::new();
+    let mut in_signature = None;
+    let mut rest = data;
+    while !rest.is_empty() {
+        let line_len = rest.find_byte(b'\n').map_or(rest.len(), |pos| pos + 1);
+        let (line, tail) = rest.split_at(line_len);
+        rest = tail;
+        if line == b"\n" {
+            // The headers end here and the message follows, which isn't searched for signatures.
+            payload.extend_from_slice(line);
+            payload.extend_from_slice(rest);
+            break;
+        }
+        if let Some(signature_kind) = in_signature.filter(|_| line.starts_with(b" ")) {
+            if signature_kind == hash_kind {
+                signature.extend_from_slice(&line[1..]);
+            }
+            continue;
+        }
+        in_signature = None;
+        match COMMIT_SIGNATURE_HEADERS.iter().find_map(|(name, signature_kind)| {
+            line.strip_prefix(*name)
+                .and_then(|value| value.strip_prefix(b" "))
+                .map(|value| (value, *signature_kind))
+        }) {
+            Some((value, signature_kind)) => {
+                in_signature = Some(signature_kind);
+                if signature_kind == hash_kind {
+                    signature.extend_from_slice(value);
+                }
+            }
+            None => payload.extend_from_slice(line),
+        }
+    }
+    if signature.is_empty() {
+        return None;
+    }
+    Some(Parts {
+        payload: Cow::Owned(payload.into()),
+        signature: Cow::Owned(signature.into()),
+    })
+}
+
+/// Split the tag `data` into its signed payload and the signature appended to its message, or return `None` if
+/// there is no signature.
+///
+/// The signature starts at the last line that begins a PGP, X509 or SSH signature, with the payload being everything before it.
+pub fn split_tag(data: &[u8]) -> Option<Parts<'_>> {
+    let mut signature_start = None;
+    let mut line_start = 0;
+    while line_start < data.len() {
+        let line = &data[line_start..];
+        if TAG_SIGNATURE_STARTS.iter().any(|start| line.starts_with(start)) {
+            signature_start = Some(line_start);
+        }
+        line_start += line.find_byte(b'\n').map_or(line.len(), |pos| pos + 1);
+    }
+    signature_start.map(|pos| Parts {
+        payload: Cow::Borrowed(data[..pos].as_bstr()),
+        signature: Cow::Borrowed(data[pos..].as_bstr()),
+    })
+}
+
+/// The error returned by [`verify()`].
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The object doesn't have a signature.
+    Unsigned,
+    /// The signature could not be verified.
+    Verify(E),
+}
+
+impl<E> std::fmt::Display for Error<E>
+where
+    E: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Unsigned => f.write_str("The object is not signed"),
+            Error::Verify(err) => write!(f, "The signature could not be verified: {}", err),
+        }
+    }
+}
+
+impl<E> std::error::Error for Error<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Unsigned => None,
+            Error::Verify(err) => Some(err),
+        }
+    }
+}
+
+/// Verify the signature of the object `data` of `kind` in a repository using `hash_kind` by calling `verify(payload, signature)`, which is expected to
+/// use a cryptography library of the callers choice to check that `signature` was made over `payload`.
+///
+/// The value returned by `verify` on success is passed on, and can be used to return the identity of the signer, for example.
+pub fn verify<T, E>(
+    kind: Kind,
+    hash_kind: git_hash::Kind,
+    data: &[u8],
+    verify: impl FnOnce(&BStr, &BStr) -> Result<T, E>,
+) -> Result<T, Error<E>> {
+    let parts = split(kind, hash_kind, data).ok_or(Error::Unsigned)?;
+    verify(parts.payload.as_ref(), parts.signature.as_ref()).map_err(Error::Verify)
+}
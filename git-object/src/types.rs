@@ -1,3 +1,4 @@
+use crate::immutable;
 use quick_error::quick_error;
 use std::fmt;
 
@@ -45,6 +46,43 @@ impl Kind {
     }
 }
 
+/// Sniffing and validation
+impl Kind {
+    /// Guess the kind of the object whose raw `data`, without loose object header, is given, without fully decoding it.
+    ///
+    /// Data starting with the first field of a commit or tag is considered to be a commit or tag respectively,
+    /// data consisting of well-formed tree entries is considered a tree and everything else is a blob.
+    /// Note that empty data is a blob, even though it is a valid empty tree as well.
+    pub fn sniff(data: &[u8]) -> Kind {
+        if data.is_empty() {
+            return Kind::Blob;
+        }
+        if let Some(Ok(_)) = immutable::CommitIter::from_bytes(data).next() {
+            return Kind::Commit;
+        }
+        if let Some(Ok(_)) = immutable::TagIter::from_bytes(data).next() {
+            return Kind::Tag;
+        }
+        if Kind::Tree.validate(data).is_ok() {
+            return Kind::Tree;
+        }
+        Kind::Blob
+    }
+
+    /// Check that the raw `data` of an object of this kind, without loose object header, is well-formed without building
+    /// the decoded object, returning the same error decoding would.
+    ///
+    /// This is cheaper than decoding objects and suitable to reject corrupt objects early, and blobs are always valid.
+    pub fn validate(&self, data: &[u8]) -> Result<(), immutable::object::decode::Error> {
+        match self {
+            Kind::Commit => immutable::CommitIter::from_bytes(data).try_for_each(|token| token.map(drop)),
+            Kind::Tag => immutable::TagIter::from_bytes(data).try_for_each(|token| token.map(drop)),
+            Kind::Tree => immutable::TreeIter::from_bytes(data).try_for_each(|entry| entry.map(drop)),
+            Kind::Blob => Ok(()),
+        }
+    }
+}
+
 impl fmt::Display for Kind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(std::str::from_utf8(self.as_bytes()).expect("Converting Kind name to utf8"))
@@ -79,4 +117,38 @@ pub mod tree {
             *self != EntryMode::Tree
         }
     }
+
+    #[cfg(feature = "git2")]
+    mod git2_crate {
+        use super::EntryMode;
+        use std::convert::TryFrom;
+
+        impl From<EntryMode> for git2::FileMode {
+            fn from(mode: EntryMode) -> Self {
+                match mode {
+                    EntryMode::Tree => git2::FileMode::Tree,
+                    EntryMode::Blob => git2::FileMode::Blob,
+                    EntryMode::BlobExecutable => git2::FileMode::BlobExecutable,
+                    EntryMode::Link => git2::FileMode::Link,
+                    EntryMode::Commit => git2::FileMode::Commit,
+                }
+            }
+        }
+
+        /// Fails with the given `mode` if it is [`Unreadable`][git2::FileMode::Unreadable].
+        impl TryFrom<git2::FileMode> for EntryMode {
+            type Error = git2::FileMode;
+
+            fn try_from(mode: git2::FileMode) -> Result<Self, Self::Error> {
+                Ok(match mode {
+                    git2::FileMode::Tree => EntryMode::Tree,
+                    git2::FileMode::Blob => EntryMode::Blob,
+                    git2::FileMode::BlobExecutable => EntryMode::BlobExecutable,
+                    git2::FileMode::Link => EntryMode::Link,
+                    git2::FileMode::Commit => EntryMode::Commit,
+                    git2::FileMode::Unreadable => return Err(mode),
+                })
+            }
+        }
+    }
 }
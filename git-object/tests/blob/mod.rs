@@ -0,0 +1,64 @@
+use git_object::{
+    blob::{self, Encoding},
+    immutable, mutable,
+};
+
+#[test]
+fn binary_detection_is_like_the_one_of_git() {
+    let mut data = vec![b'x'; blob::BINARY_DETECTION_LEN - 1];
+    data.push(0);
+    assert!(
+        blob::is_binary(&data),
+        "null bytes within the first 8000 bytes mean binary"
+    );
+    data.insert(0, b'x');
+    assert!(!blob::is_binary(&data), "later null bytes are ignored");
+
+    for (input, expected) in &[
+        (&b""[..], false),
+        (b"text\nwith lines\n", false),
+        (b"\xff\xfeh\0i\0", true),
+        (b"\xef\xbb\xbftext", false),
+    ] {
+        assert_eq!(blob::is_binary(input), *expected, "{:?}", input);
+        assert_eq!(immutable::Blob { data: input }.is_binary(), *expected);
+        assert_eq!(mutable::Blob { data: input.to_vec() }.is_binary(), *expected);
+    }
+}
+
+#[test]
+fn lines_are_counted_like_git_does_in_diffs() {
+    for (input, expected) in &[
+        (&b""[..], 0),
+        (b"a", 1),
+        (b"a\nb", 2),
+        (b"a\nb\n", 2),
+        (b"\n\n", 2),
+        (b"a\r\nb\r\n", 2),
+    ] {
+        assert_eq!(blob::count_lines(input), *expected, "{:?}", input);
+        assert_eq!(immutable::Blob { data: input }.count_lines(), *expected);
+        assert_eq!(mutable::Blob { data: input.to_vec() }.count_lines(), *expected);
+    }
+}
+
+#[test]
+fn encodings_are_detected_by_their_byte_order_mark() {
+    for (input, expected) in &[
+        (&b"\xef\xbb\xbftext"[..], Some(Encoding::Utf8)),
+        (b"\xff\xfet\0", Some(Encoding::Utf16LE)),
+        (b"\xfe\xff\0t", Some(Encoding::Utf16BE)),
+        (b"\xff\xfe\0\0t\0\0\0", Some(Encoding::Utf32LE)),
+        (b"\0\0\xfe\xff\0\0\0t", Some(Encoding::Utf32BE)),
+        (b"text", None),
+        (b"\xef\xbb", None),
+        (b"", None),
+    ] {
+        assert_eq!(blob::encoding_from_bom(input), *expected, "{:?}", input);
+        assert_eq!(immutable::Blob { data: input }.encoding_from_bom(), *expected);
+        assert_eq!(mutable::Blob { data: input.to_vec() }.encoding_from_bom(), *expected);
+        if let Some(encoding) = expected {
+            assert!(input.starts_with(encoding.bom()));
+        }
+    }
+}
@@ -0,0 +1,26 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+mkdir -p a/b c trees
+
+echo 1 > a/b/file
+echo 2 > a/x
+echo 3 > c/y
+echo 4 > top
+git add .
+base=$(git write-tree)
+echo "$base" > base-tree-id
+
+new=$(echo new | git hash-object -w --stdin)
+echo "$new" > new-blob-id
+
+git update-index --add --cacheinfo "100644,$new,a/b/new"
+git update-index --force-remove a/x c/y
+git update-index --add --cacheinfo "100755,$new,d/e/f"
+git update-index --cacheinfo "100644,$new,top"
+git write-tree > edited-tree-id
+
+for tree in $base $(git ls-tree -r -t "$base" | awk '$2 == "tree" { print $3 }'); do
+  git cat-file tree "$tree" > "trees/$tree"
+done
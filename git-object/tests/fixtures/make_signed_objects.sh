@@ -0,0 +1,60 @@
+#!/bin/bash
+set -eu -o pipefail
+
+# A stand-in for gpg which signs with a fake signature and records what git passes to it for verification.
+cat > fake-gpg <<'FAKE_GPG'
+#!/bin/bash
+set -eu -o pipefail
+if [[ " $* " == *" --verify "* ]]; then
+  args=("$@")
+  cp "${args[$# - 2]}" "$VERIFY_DIR/signature"
+  cat > "$VERIFY_DIR/payload"
+  echo "[GNUPG:] NEWSIG"
+  echo "[GNUPG:] GOODSIG 0000000000000000 fake"
+  echo "[GNUPG:] VALIDSIG 0000000000000000"
+  echo "[GNUPG:] TRUST_FULLY 0 pgp"
+else
+  echo "[GNUPG:] SIG_CREATED D 1 8 00 946771200 0000000000000000" >&2
+  printf -- "-----BEGIN PGP SIGNATURE-----\n\n%s\n-----END PGP SIGNATURE-----\n" "$(git hash-object --stdin)"
+fi
+FAKE_GPG
+chmod +x fake-gpg
+
+git init -q
+git config gpg.program "$PWD/fake-gpg"
+git config user.signingkey fake
+
+git commit -q --allow-empty -S -m "signed"
+git -c i18n.commitEncoding=ISO-8859-1 commit -q --allow-empty -S -m "signed with encoding" -m "and a body"
+git commit -q --allow-empty --no-gpg-sign -m "unsigned"
+git tag -s -m "signed tag" signed-tag HEAD~1
+git tag -a -m "unsigned tag" unsigned-tag
+
+mkdir objects verified
+for rev in HEAD~2 HEAD~1 HEAD; do
+  id=$(git rev-parse "$rev")
+  git cat-file commit "$id" > "objects/$id"
+  if git cat-file commit "$id" | grep -q '^gpgsig '; then
+    mkdir "verified/$id"
+    VERIFY_DIR="$PWD/verified/$id" git verify-commit "$id" 2>/dev/null
+  fi
+done
+for tag in signed-tag unsigned-tag; do
+  id=$(git rev-parse "$tag")
+  git cat-file tag "$id" > "objects/$id"
+done
+id=$(git rev-parse signed-tag)
+mkdir "verified/$id"
+VERIFY_DIR="$PWD/verified/$id" git verify-tag "$id" 2>/dev/null
+
+# Merging a signed tag embeds it into the 'mergetag' header of the merge commit.
+git checkout -q -b side
+git commit -q --allow-empty --no-gpg-sign -m "side"
+git tag -s -m "signed side tag" signed-side-tag
+git checkout -q -
+mkdir -p mergetag/verified
+VERIFY_DIR="$PWD/mergetag/verified" git merge -q --no-ff --no-edit --no-gpg-sign signed-side-tag
+git cat-file commit HEAD > mergetag/commit
+git rev-parse signed-side-tag > mergetag/tag-id
+git rev-parse side > mergetag/target-id
+VERIFY_DIR="$PWD/mergetag/verified" git log -1 --show-signature > /dev/null
@@ -0,0 +1,36 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config commit.gpgsign false
+git config tag.gpgsign false
+
+git commit -q --allow-empty -m "tagged"
+
+git tag -a -m "" empty-message
+git tag -a -m "subject" -m "body paragraph" multi-paragraph
+git tag -a --cleanup=verbatim -m "  verbatim without newline  " verbatim
+git -c advice.nestedTag=false tag -a -m "a nested tag" nested multi-paragraph
+git tag -a -m "a tree" tree HEAD^{tree}
+git tag -a -m "a blob" blob "$(echo content | git hash-object -w --stdin)"
+
+signed=$(git mktag <<MKTAG
+object $(git rev-parse HEAD)
+type commit
+tag signed
+tagger $GIT_COMMITTER_NAME <$GIT_COMMITTER_EMAIL> 946771200 +0000
+
+signed with a fake signature
+-----BEGIN PGP SIGNATURE-----
+
+iQEzBAABCAAdFiEEfake
+=fake
+-----END PGP SIGNATURE-----
+MKTAG
+)
+git update-ref refs/tags/signed "$signed"
+
+mkdir tags
+for tag in $(git tag); do
+  git cat-file tag "$tag" > "tags/$tag"
+done
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+mkdir trees
+
+blob=e69de29bb2d1d6434b8b29ae775ad8c2e48c5391
+tree=4b825dc642cb6eb9a060e54bf8d69288fbee4904
+
+# names which sort differently once trees are compared as if they had a trailing slash
+tricky=$(printf "100644 blob $blob\ta0\n040000 tree $tree\ta\n100644 blob $blob\ta.rs\n120000 blob $blob\ta-\n160000 commit $blob\tsub\n100755 blob $blob\tb\n040000 tree $tree\tab\n100644 blob $blob\taa\n040000 tree $tree\tsub.d\n" | git mktree --missing)
+git cat-file tree "$tricky" > trees/tricky
+
+empty=$(git mktree < /dev/null)
+git cat-file tree "$empty" > trees/empty
@@ -146,6 +146,56 @@ mod method {
     }
 }
 
+mod message {
+    use crate::immutable::fixture_bytes;
+    use git_object::{commit::MessageRef, immutable::Commit};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn summary_and_body_of_commits() -> crate::Result {
+        let fixture = fixture_bytes("commit", "mergetag.txt");
+        let message = Commit::from_bytes(&fixture)?.message();
+        assert_eq!(
+            message.summary().as_ref(),
+            "Merge tag 'thermal-v5.8-rc1' of git://git.kernel.org/pub/scm/linux/kernel/git/thermal/linux"
+        );
+        assert!(message
+            .body()
+            .expect("present")
+            .starts_with(b"Pull thermal updates from Daniel Lezcano:\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn summary_and_body_follow_the_rules_of_git() {
+        for (message, summary, body) in &[
+            ("", "", None),
+            ("title", "title", None),
+            ("  indented\n", "  indented", None),
+            ("title\r\n\r\nbody", "title", Some("body")),
+            (
+                "\n\n  \ntitle  \r\nsecond line\n\n\nbody\n",
+                "title second line",
+                Some("body\n"),
+            ),
+            (
+                "one\ntwo  \n \t\n\n  body\n\nmore\n",
+                "one two",
+                Some("  body\n\nmore\n"),
+            ),
+        ] {
+            let parsed = MessageRef::from_bytes(message.as_bytes());
+            assert_eq!(parsed.summary().as_ref(), *summary, "summary of {:?}", message);
+            assert_eq!(
+                parsed.body().map(|b| b.to_string()),
+                body.map(ToOwned::to_owned),
+                "body of {:?}",
+                message
+            );
+        }
+    }
+}
+
 mod iter {
     use crate::{
         hex_to_id,
@@ -154,7 +204,11 @@ mod iter {
             fixture_bytes, linus_signature, signature,
         },
     };
-    use git_object::{bstr::ByteSlice, immutable::commit::iter::Token, immutable::CommitIter};
+    use git_object::{
+        bstr::ByteSlice,
+        immutable::commit::iter::Token,
+        immutable::{Commit, CommitIter},
+    };
 
     #[test]
     fn newline_right_after_signature_multiline_header() -> crate::Result {
@@ -275,6 +329,42 @@ mod iter {
         Ok(())
     }
 
+    #[test]
+    fn hashes_of_unknown_length_are_errors() {
+        for data in &[
+            &b"tree 1b2dfb4ac5e42080b682fc676e9738c94ce6d54d0\n\nmessage"[..],
+            b"tree 1b2dfb4ac5e42080b682fc676e9738c94ce6d54d\nparent 1b2dfb4ac5e42080b682fc676e9738c94ce6d54d0\n\nmessage",
+        ] {
+            assert!(
+                CommitIter::from_bytes(data).any(|token| token.is_err()),
+                "{}",
+                data.as_bstr()
+            );
+            assert!(Commit::from_bytes(data).is_err(), "{}", data.as_bstr());
+        }
+    }
+
+    #[test]
+    fn errors_contain_the_offset_and_a_snippet_of_the_malformed_data() {
+        let data = b"tree 1b2dfb4ac5e42080b682fc676e9738c94ce6d54d\nauthor without signature\n\nmessage";
+        let err = CommitIter::from_bytes(data)
+            .find_map(Result::err)
+            .expect("author is malformed");
+        let author_offset = data.find("author").expect("present");
+        assert!(
+            err.offset >= author_offset,
+            "the error is at or within the author field"
+        );
+        assert!(!err.snippet.is_empty() && data[err.offset..].starts_with(&err.snippet));
+
+        let err = Commit::from_bytes(data).expect_err("author is malformed");
+        assert!(err.offset >= author_offset);
+        assert!(
+            err.to_string().contains(&format!("at byte {}", err.offset)),
+            "the offset is part of the message"
+        );
+    }
+
     #[test]
     fn mergetag() -> crate::Result {
         assert_eq!(
@@ -334,10 +424,24 @@ mod iter {
             );
             Ok(())
         }
+
+        #[test]
+        fn message() -> crate::Result {
+            let data = fixture_bytes("commit", "two-multiline-headers.txt");
+            let message = CommitIter::from_bytes(&data).message().expect("decodable");
+            assert_eq!(message, git_object::immutable::Commit::from_bytes(&data)?.message);
+            assert!(
+                data.as_ptr_range().contains(&message.as_ptr()),
+                "the message is a slice of the commit data"
+            );
+            assert_eq!(CommitIter::from_bytes(b"tree invalid").message(), None);
+            Ok(())
+        }
     }
 }
 
 mod from_bytes {
+    use crate::hex_to_id;
     use crate::immutable::{
         commit::{LONG_MESSAGE, MERGE_TAG, SIGNATURE},
         fixture_bytes, linus_signature, signature,
@@ -491,6 +595,31 @@ mod from_bytes {
         Ok(())
     }
 
+    #[test]
+    fn extra_headers_are_iterable_in_order_and_accessible_by_name() -> crate::Result {
+        let fixture = fixture_bytes("commit", "two-multiline-headers.txt");
+        let commit = Commit::from_bytes(&fixture)?;
+        assert_eq!(
+            commit.extra_headers().map(|(name, _value)| name).collect::<Vec<_>>(),
+            vec![b"mergetag".as_bstr(), b"gpgsig".as_bstr()]
+        );
+        let mergetags = commit.extra_headers().mergetags().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(mergetags.len(), 1);
+        assert_eq!(
+            mergetags[0].target(),
+            hex_to_id("8f3d9f354286745c751374f5f1fcafee6b3f3136")
+        );
+        let signature = commit.extra_headers().pgp_signature().expect("signed");
+        assert!(signature.starts_with(b"-----BEGIN PGP SIGNATURE-----\n"));
+        assert!(signature.ends_with(b"-----END PGP SIGNATURE-----"));
+        assert_eq!(
+            commit.extra_headers().find("encoding"),
+            None,
+            "encoding has its own field"
+        );
+        Ok(())
+    }
+
     const OTHER_SIGNATURE: &'static [u8; 455] = b"-----BEGIN PGP SIGNATURE-----
 
 wsBcBAABCAAQBQJeqxW4CRBK7hj4Ov3rIwAAdHIIAFD98qgN/k8ybukCLf6kpzvi
@@ -22,7 +22,7 @@ mod iter {
     };
     use git_object::{
         bstr::ByteSlice,
-        immutable::{tag::iter::Token, TagIter},
+        immutable::{tag::iter::Token, Tag, TagIter},
         Kind,
     };
 
@@ -109,6 +109,39 @@ KLMHist5yj0sw1E4hDTyQa0=
         );
         Ok(())
     }
+
+    #[test]
+    fn message() -> crate::Result {
+        let data = fixture_bytes("tag", "signed.txt");
+        let (message, pgp_signature) = TagIter::from_bytes(&data).message().expect("decodable");
+        let tag = Tag::from_bytes(&data)?;
+        assert_eq!(Some(message), tag.message);
+        assert_eq!(pgp_signature, tag.pgp_signature);
+        assert!(
+            data.as_ptr_range().contains(&message.as_ptr()),
+            "the message is a slice of the tag data"
+        );
+
+        let data = fixture_bytes("tag", "whitespace.txt");
+        let (message, pgp_signature) = TagIter::from_bytes(&data).message().expect("decodable");
+        assert_eq!(Some(message), Tag::from_bytes(&data)?.message);
+        assert_eq!(pgp_signature, None, "the tag isn't signed");
+        assert_eq!(TagIter::from_bytes(b"object invalid").message(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn errors_point_to_the_field_that_could_not_be_decoded() {
+        let data = b"object 01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc\ntype unknown\ntag name\n\nmessage\n";
+        let err = TagIter::from_bytes(data)
+            .find_map(Result::err)
+            .expect("the type is invalid");
+        assert_eq!(err.offset, 48, "the start of the type field");
+        assert_eq!(err.snippet, "type unknown\ntag name\n\nmessage\n");
+
+        let err = Tag::from_bytes(data).expect_err("the type is invalid");
+        assert_eq!(err.offset, 48);
+    }
 }
 
 mod from_bytes {
@@ -129,7 +162,7 @@ mod from_bytes {
                 target: b"01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc".as_bstr(),
                 name: b"empty".as_bstr(),
                 target_kind: Kind::Commit,
-                message: b"".as_bstr(),
+                message: None,
                 tagger: Some(signature(1592381636)),
                 pgp_signature: None
             }
@@ -145,7 +178,7 @@ mod from_bytes {
                 target: b"ebdf205038b66108c0331aa590388431427493b7".as_bstr(),
                 name: b"baz".as_bstr(),
                 target_kind: Kind::Commit,
-                message: b"hello\n\nworld".as_bstr(),
+                message: Some(b"hello\n\nworld".as_bstr()),
                 tagger: Some(signature(1592311808)),
                 pgp_signature: None
             }
@@ -161,13 +194,15 @@ mod from_bytes {
                 target: b"c39ae07f393806ccf406ef966e9a15afc43cc36a".as_bstr(),
                 name: b"v2.6.11-tree".as_bstr(),
                 target_kind: Kind::Tree,
-                message: b"This is the 2.6.11 tree object.
+                message: Some(
+                    b"This is the 2.6.11 tree object.
 
 NOTE! There's no commit for this, since it happened before I started with git.
 Eventually we'll import some sort of history, and that should tie this tree
 object up to a real commit. In the meantime, this acts as an anchor point for
 doing diffs etc under git."
-                    .as_bstr(),
+                        .as_bstr()
+                ),
                 tagger: None,
                 pgp_signature: Some(
                     b"-----BEGIN PGP SIGNATURE-----
@@ -193,7 +228,7 @@ KLMHist5yj0sw1E4hDTyQa0=
                 target: b"01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc".as_bstr(),
                 name: b"whitespace".as_bstr(),
                 target_kind: Kind::Commit,
-                message: b" \ttab\nnewline\n\nlast-with-trailer\n".as_bstr(),
+                message: Some(b" \ttab\nnewline\n\nlast-with-trailer\n".as_bstr()),
                 tagger: Some(signature(1592382888)),
                 pgp_signature: None
             }
@@ -207,7 +242,7 @@ fn tag_fixture(offset: i32) -> Tag<'static> {
         target: b"ffa700b4aca13b80cb6b98a078e7c96804f8e0ec".as_bstr(),
         name: b"1.0.0".as_bstr(),
         target_kind: Kind::Commit,
-        message: b"for the signature".as_bstr(),
+        message: Some(b"for the signature".as_bstr()),
         pgp_signature: Some(
             b"-----BEGIN PGP SIGNATURE-----
 Comment: GPGTools - https://gpgtools.org
@@ -22,6 +22,49 @@ mod iter {
         );
     }
 
+    #[test]
+    fn malformed_entries_can_be_skipped_to_resume_iteration() -> crate::Result {
+        let id = [1u8; 20];
+        let mut data = Vec::new();
+        for (mode, name) in &[("100644", "a"), ("12345", "b"), ("40000", "c")] {
+            data.extend_from_slice(format!("{} {}\0", mode, name).as_bytes());
+            data.extend_from_slice(&id);
+        }
+        let entry_len = "100644 a\0".len() + id.len();
+
+        let mut iter = TreeIter::from_bytes(&data);
+        assert_eq!(iter.next().transpose()?.map(|e| e.filename), Some(b"a".as_bstr()));
+        let err = iter.next().expect("an error").expect_err("invalid mode");
+        assert_eq!(
+            err.offset, entry_len,
+            "the offset points to the start of the malformed entry"
+        );
+        assert!(err.snippet.starts_with(b"12345 b\0"));
+        assert!(
+            iter.next().is_none(),
+            "iteration stops until the malformed entry is skipped"
+        );
+
+        assert!(iter.skip_malformed_entry());
+        assert_eq!(iter.next().transpose()?.map(|e| e.filename), Some(b"c".as_bstr()));
+        assert!(iter.next().is_none());
+        assert!(!iter.skip_malformed_entry(), "there is nothing to skip without error");
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_entries_cannot_be_skipped() {
+        let data = fixture_bytes("tree", "everything.tree");
+        let mut iter = TreeIter::from_bytes(&data[..data.len() - 1]);
+        let err = iter
+            .by_ref()
+            .find_map(Result::err)
+            .expect("the last entry lacks a byte of its id");
+        assert!(err.offset < data.len() - 1);
+        assert!(!iter.skip_malformed_entry());
+        assert!(iter.next().is_none());
+    }
+
     #[test]
     fn everything() -> crate::Result {
         assert_eq!(
@@ -135,3 +178,92 @@ mod from_bytes {
         Ok(())
     }
 }
+
+mod lookup_entry {
+    use crate::{find_tree, hex_to_id};
+    use git_hash::ObjectId;
+    use git_object::{
+        bstr::ByteSlice,
+        immutable::{Tree, TreeIter},
+        tree::EntryMode,
+    };
+    use std::collections::HashMap;
+
+    /// Return the base tree of the fixture along with all of its subtrees.
+    fn fixture() -> crate::Result<(ObjectId, HashMap<ObjectId, Vec<u8>>)> {
+        let (dir, trees) = crate::edited_trees()?;
+        let base = ObjectId::from_hex(std::fs::read(dir.join("base-tree-id"))?.trim())?;
+        Ok((base, trees))
+    }
+
+    #[test]
+    fn entries_are_found_in_nested_trees() -> crate::Result {
+        let (base, trees) = fixture()?;
+        let root = Tree::from_bytes(&trees[&base])?;
+        let mut buf = Vec::new();
+        let mut loaded = 0;
+        let entry = root
+            .lookup_entry("a/b/file", &mut buf, |id, buf| {
+                loaded += 1;
+                find_tree(&trees, id, buf)
+            })?
+            .expect("present");
+        assert_eq!(loaded, 2, "only the trees along the path are loaded");
+        assert_eq!(entry.mode, EntryMode::Blob);
+        assert_eq!(entry.filename, "file");
+        assert_eq!(entry.oid, hex_to_id("d00491fd7e5bb6fa28c517a0bb32b8b506539d4d"));
+
+        let entry = TreeIter::from_bytes(&trees[&base])
+            .lookup_entry("a/b", &mut buf, |id, buf| find_tree(&trees, id, buf))?
+            .expect("present");
+        assert_eq!(entry.mode, EntryMode::Tree, "trees can be looked up as well");
+        assert!(trees.contains_key(&entry.oid));
+        assert_eq!(
+            root.lookup_entry("top", &mut buf, |id, buf| find_tree(&trees, id, buf))?
+                .map(|entry| entry.oid),
+            Some(hex_to_id("b8626c4cff2849624fb67f87cd0ad72b163671ad"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_entries_yield_none() -> crate::Result {
+        let (base, trees) = fixture()?;
+        let root = Tree::from_bytes(&trees[&base])?;
+        let mut buf = Vec::new();
+        for path in &[
+            "",
+            "missing",
+            "a/missing",
+            "a/b/file/below-a-blob",
+            "top/below-a-blob",
+            "a//x",
+            "a/",
+        ] {
+            let entry = root.lookup_entry(*path, &mut buf, |id, buf| find_tree(&trees, id, buf))?;
+            assert_eq!(entry, None, "{:?} doesn't exist", path);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn trees_which_cannot_be_found_or_decoded_are_errors() -> crate::Result {
+        let (base, trees) = fixture()?;
+        let root = Tree::from_bytes(&trees[&base])?;
+        let mut buf = Vec::new();
+        let err = root
+            .lookup_entry("a/b/file", &mut buf, |_id, _buf| None)
+            .expect_err("the tree 'a' can't be found");
+        assert!(err.to_string().starts_with("The tree at 'a' with id "));
+
+        let err = root
+            .lookup_entry("a/b/file", &mut buf, |_id, buf| {
+                buf.clear();
+                buf.extend_from_slice(b"garbage");
+                Some(TreeIter::from_bytes(buf))
+            })
+            .expect_err("the tree 'a' is corrupt");
+        assert_eq!(err.to_string(), "The tree at 'a' could not be decoded");
+        Ok(())
+    }
+}
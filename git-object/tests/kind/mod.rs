@@ -0,0 +1,98 @@
+use git_object::Kind;
+
+use crate::fixture_bytes;
+
+mod sniff {
+    use git_object::Kind;
+
+    use crate::fixture_bytes;
+
+    #[test]
+    fn commits() {
+        for name in &[
+            "unsigned.txt",
+            "signed.txt",
+            "merge.txt",
+            "mergetag.txt",
+            "with-encoding.txt",
+        ] {
+            assert_eq!(
+                Kind::sniff(&fixture_bytes(&format!("commit/{}", name))),
+                Kind::Commit,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn tags() {
+        for name in &[
+            "empty.txt",
+            "no-tagger.txt",
+            "signed.txt",
+            "whitespace.txt",
+            "with-newlines.txt",
+        ] {
+            assert_eq!(
+                Kind::sniff(&fixture_bytes(&format!("tag/{}", name))),
+                Kind::Tag,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn trees() {
+        for name in &["everything.tree", "definitely-special.tree", "maybe-special.tree"] {
+            assert_eq!(
+                Kind::sniff(&fixture_bytes(&format!("tree/{}", name))),
+                Kind::Tree,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn everything_else_is_a_blob() {
+        for data in &[
+            &b""[..],
+            b"hello world\n",
+            b"tree not-a-hash\n",
+            b"100644 file\0too-short",
+        ] {
+            assert_eq!(Kind::sniff(data), Kind::Blob, "{:?}", data);
+        }
+    }
+}
+
+#[test]
+fn validate_accepts_well_formed_objects() {
+    assert!(Kind::Commit
+        .validate(&fixture_bytes("commit/signed-with-encoding.txt"))
+        .is_ok());
+    assert!(Kind::Tag.validate(&fixture_bytes("tag/signed.txt")).is_ok());
+    assert!(Kind::Tree.validate(&fixture_bytes("tree/everything.tree")).is_ok());
+    assert!(Kind::Tree.validate(b"").is_ok(), "the empty tree is valid");
+    assert!(Kind::Blob.validate(b"\0anything\xff").is_ok(), "blobs are always valid");
+}
+
+#[test]
+fn validate_rejects_corrupt_objects() {
+    let commit = fixture_bytes("commit/unsigned.txt");
+    let truncated_author = &commit[..commit.iter().position(|b| *b == b'<').expect("author email")];
+    assert!(Kind::Commit.validate(truncated_author).is_err());
+    assert!(Kind::Commit.validate(&fixture_bytes("tag/signed.txt")).is_err());
+
+    let tag = fixture_bytes("tag/signed.txt");
+    assert!(Kind::Tag.validate(&tag[..20]).is_err(), "the target hash is cut off");
+
+    let tree = fixture_bytes("tree/everything.tree");
+    assert!(
+        Kind::Tree.validate(&tree[..tree.len() - 1]).is_err(),
+        "the last hash is cut off"
+    );
+    assert!(Kind::Tree.validate(&commit).is_err());
+}
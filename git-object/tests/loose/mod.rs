@@ -0,0 +1,72 @@
+mod header {
+    use git_object::{bstr::ByteSlice, loose::header, Kind};
+
+    #[test]
+    fn encode_decode_round_trip() -> crate::Result {
+        let mut buf = [0; header::MAX_LEN];
+        for (kind, size, expected) in &[
+            (Kind::Tree, 1234, &b"tree 1234\0"[..]),
+            (Kind::Blob, 0, b"blob 0\0"),
+            (Kind::Commit, 24241, b"commit 24241\0"),
+            (Kind::Tag, 9999999999, b"tag 9999999999\0"),
+            (Kind::Commit, u64::MAX, b"commit 18446744073709551615\0"),
+        ] {
+            let written = header::encode(*kind, *size, &mut buf[..])?;
+            assert_eq!(buf[..written].as_bstr(), expected.as_bstr());
+            let (actual_kind, actual_size, actual_read) = header::decode(&buf[..written])?;
+            assert_eq!(actual_kind, *kind);
+            assert_eq!(actual_size, *size);
+            assert_eq!(actual_read, written);
+        }
+        assert_eq!(
+            header::encode(Kind::Commit, u64::MAX, std::io::sink())?,
+            header::MAX_LEN,
+            "no header is longer than that"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_returns_the_header_length_to_find_the_data() -> crate::Result {
+        assert_eq!(header::decode(b"blob 5\0hello")?, (Kind::Blob, 5, 7));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_asks_for_more_data_until_the_header_is_complete() -> crate::Result {
+        let input = b"commit 24241\0";
+        for len in 0..input.len() {
+            assert!(
+                matches!(header::decode(&input[..len]), Err(header::Error::Incomplete)),
+                "{:?} is incomplete",
+                input[..len].as_bstr()
+            );
+        }
+        assert!(header::decode(input).is_ok());
+        assert!(
+            matches!(
+                header::decode(&[b'x'; header::MAX_LEN]),
+                Err(header::Error::InvalidHeader(_))
+            ),
+            "headers can't be longer than MAX_LEN"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_rejects_headers_git_would_reject() {
+        for input in &[
+            &b"blob\0"[..],
+            b"blob \0",
+            b"blob 01\0",
+            b"blob +1\0",
+            b"blob -1\0",
+            b"blob 1 \0",
+            b"blob 18446744073709551616\0",
+            b"blobs 1\0",
+            b" 1\0",
+        ] {
+            assert!(header::decode(input).is_err(), "{:?} is invalid", input.as_bstr());
+        }
+    }
+}
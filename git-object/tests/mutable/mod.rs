@@ -28,6 +28,10 @@ macro_rules! round_trip {
 
 mod object;
 mod tag {
+    use crate::hex_to_id;
+    use bstr::ByteSlice;
+    use git_object::{immutable, mutable, Kind};
+
     round_trip!(
         mutable::Tag,
         immutable::Tag,
@@ -37,6 +41,93 @@ mod tag {
         "tag/with-newlines.txt",
         "tag/signed.txt"
     );
+
+    fn git_created_tags() -> crate::Result<Vec<(String, Vec<u8>)>> {
+        let tags_dir = git_testtools::scripted_fixture_repo_read_only("make_tags.sh")?.join("tags");
+        let mut tags = Vec::new();
+        for entry in std::fs::read_dir(tags_dir)? {
+            let entry = entry?;
+            tags.push((
+                entry.file_name().to_string_lossy().into_owned(),
+                std::fs::read(entry.path())?,
+            ));
+        }
+        tags.sort();
+        Ok(tags)
+    }
+
+    #[test]
+    fn round_trip_git_created_tags() -> crate::Result {
+        let tags = git_created_tags()?;
+        assert_eq!(tags.len(), 7, "all tags created by the fixture are seen");
+        for (name, input) in tags {
+            let tag: mutable::Tag = immutable::Tag::from_bytes(&input)?.into();
+            assert_eq!(tag.name, name.as_bytes().as_bstr());
+            let mut output = Vec::new();
+            tag.write_to(&mut output)?;
+            assert_eq!(
+                output.as_bstr(),
+                input.as_bstr(),
+                "tag '{}' is encoded like git does it",
+                name
+            );
+            assert!(
+                immutable::TagIter::from_bytes(&input).all(|token| token.is_ok()),
+                "tag '{}' can be iterated as well",
+                name
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn empty_messages_are_separated_from_the_headers_like_git_does() -> crate::Result {
+        let (_, input) = git_created_tags()?
+            .into_iter()
+            .find(|(name, _)| name == "empty-message")
+            .expect("present");
+        assert!(
+            input.ends_with(b"+0000\n\n"),
+            "git writes a blank line even if there is no message"
+        );
+        let tag = immutable::Tag::from_bytes(&input)?;
+        assert_eq!(tag.message, Some(b"".as_bstr()));
+        assert_eq!(
+            immutable::Tag::from_bytes(&input[..input.len() - 1])?.message,
+            None,
+            "without blank line there is no message at all"
+        );
+        assert_eq!(tag.pgp_signature, None);
+        Ok(())
+    }
+
+    #[test]
+    fn constructed_tags_are_identical_to_the_ones_created_by_git() -> crate::Result {
+        let tag = mutable::Tag {
+            target: hex_to_id("d95f3ad14dee633a758d2e331151e950dd13e4ed"),
+            target_kind: Kind::Blob,
+            name: "blob".into(),
+            message: Some("a blob\n".into()),
+            signature: Some(git_actor::Signature {
+                name: "committer".into(),
+                email: "committer@example.com".into(),
+                time: git_actor::Time {
+                    time: 946771200,
+                    offset: 0,
+                    sign: git_actor::Sign::Plus,
+                },
+            }),
+            pgp_signature: None,
+        };
+        let mut output = Vec::new();
+        tag.write_to(&mut output)?;
+        let (_, expected) = git_created_tags()?
+            .into_iter()
+            .find(|(name, _)| name == "blob")
+            .expect("present");
+        assert_eq!(output.as_bstr(), expected.as_bstr());
+        Ok(())
+    }
 }
 
 mod commit {
@@ -54,10 +145,372 @@ mod commit {
         "commit/whitespace.txt",
         "commit/with-encoding.txt"
     );
+
+    use crate::fixture_bytes;
+    use bstr::ByteSlice;
+    use git_object::{immutable, mutable};
+
+    #[test]
+    fn extra_headers_survive_conversion_to_mutable_commits() -> crate::Result {
+        let input = fixture_bytes("commit/two-multiline-headers.txt");
+        let commit = immutable::Commit::from_bytes(&input)?;
+        let owned: mutable::Commit = commit.clone().into();
+        assert!(
+            owned.extra_headers().eq(commit.extra_headers()),
+            "all headers are retained in order"
+        );
+        assert_eq!(
+            owned.extra_headers().pgp_signature(),
+            commit.extra_headers().pgp_signature()
+        );
+        assert_eq!(
+            owned.extra_headers().mergetags().collect::<Result<Vec<_>, _>>()?,
+            commit.extra_headers().mergetags().collect::<Result<Vec<_>, _>>()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn multi_line_headers_with_carriage_returns_and_empty_lines_round_trip() -> crate::Result {
+        let input = b"tree 1b2dfb4ac5e42080b682fc676e9738c94ce6d54d
+author Sebastian Thiel <sebastian.thiel@icloud.com> 1592437401 +0800
+committer Sebastian Thiel <sebastian.thiel@icloud.com> 1592437401 +0800
+x-custom first\r
+ \r
+ third\r
+ 
+gpgsig -----BEGIN PGP SIGNATURE-----
+ 
+ -----END PGP SIGNATURE-----
+ 
+
+message
+";
+        let commit = immutable::Commit::from_bytes(input)?;
+        assert_eq!(
+            commit.extra_headers().find("x-custom"),
+            Some(b"first\r\n\r\nthird\r\n".as_bstr())
+        );
+        assert_eq!(
+            commit.extra_headers().pgp_signature(),
+            Some(b"-----BEGIN PGP SIGNATURE-----\n\n-----END PGP SIGNATURE-----\n".as_bstr())
+        );
+
+        let owned: mutable::Commit = commit.into();
+        let mut output = Vec::new();
+        owned.write_to(&mut output)?;
+        assert_eq!(output.as_bstr(), input.as_bstr());
+        Ok(())
+    }
 }
 
 mod tree {
+    use bstr::ByteSlice;
+    use git_object::{immutable, mutable, tree::EntryMode};
+
     round_trip!(mutable::Tree, immutable::Tree, "tree/everything.tree");
+
+    fn git_created_tree(name: &str) -> crate::Result<Vec<u8>> {
+        Ok(std::fs::read(
+            git_testtools::scripted_fixture_repo_read_only("make_trees.sh")?
+                .join("trees")
+                .join(name),
+        )?)
+    }
+
+    fn blob() -> git_hash::ObjectId {
+        crate::hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")
+    }
+
+    fn filenames(tree: &mutable::Tree) -> Vec<&str> {
+        tree.entries
+            .iter()
+            .map(|entry| entry.filename.to_str().expect("valid UTF-8"))
+            .collect()
+    }
+
+    #[test]
+    fn insertion_in_any_order_yields_trees_identical_to_the_ones_created_by_git() -> crate::Result {
+        let expected = git_created_tree("tricky")?;
+        let entries = immutable::Tree::from_bytes(&expected)?.entries;
+        for entries in [entries.clone(), entries.into_iter().rev().collect()] {
+            let mut tree = mutable::Tree::empty();
+            for entry in entries {
+                assert!(tree.insert(entry.mode, entry.filename, entry.oid.to_owned())?.is_none());
+            }
+            assert_eq!(
+                filenames(&tree),
+                vec!["a-", "a.rs", "a", "a0", "aa", "ab", "b", "sub", "sub.d"],
+                "trees sort as if they had a trailing slash, unlike submodules"
+            );
+            let mut output = Vec::new();
+            tree.write_to(&mut output)?;
+            assert_eq!(output.as_bstr(), expected.as_bstr());
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "git2")]
+    #[test]
+    fn git2_tree_entries_convert_into_entries_writing_the_same_tree() -> crate::Result {
+        use std::convert::TryFrom;
+        let expected = git_created_tree("tricky")?;
+        let repo = git2::Repository::open(git_testtools::scripted_fixture_repo_read_only("make_trees.sh")?)?;
+        let tree = repo.find_tree(git2::Oid::hash_object(git2::ObjectType::Tree, &expected)?)?;
+        let mut entries = Vec::new();
+        for git2_entry in tree.iter() {
+            let entry = mutable::tree::Entry::try_from(&git2_entry).expect("valid mode");
+            assert_eq!(i32::from(git2::FileMode::from(entry.mode)), git2_entry.filemode());
+            assert_eq!(EntryMode::try_from(git2::FileMode::from(entry.mode)), Ok(entry.mode));
+            entries.push(entry);
+        }
+        let mut output = Vec::new();
+        mutable::Tree { entries }.write_to(&mut output)?;
+        assert_eq!(output.as_bstr(), expected.as_bstr());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_trees_are_written_like_git_does() -> crate::Result {
+        let mut output = Vec::new();
+        mutable::Tree::empty().write_to(&mut output)?;
+        assert_eq!(output.as_bstr(), git_created_tree("empty")?.as_bstr());
+        Ok(())
+    }
+
+    #[test]
+    fn insert_replaces_entries_with_the_same_name_even_if_their_mode_differs() -> crate::Result {
+        let mut tree = mutable::Tree::empty();
+        tree.insert(EntryMode::Blob, "a", blob())?;
+        tree.insert(EntryMode::Blob, "a-", blob())?;
+        let previous = tree.insert(EntryMode::Tree, "a", blob())?.expect("replaced");
+        assert_eq!(previous.mode, EntryMode::Blob);
+        assert_eq!(filenames(&tree), vec!["a-", "a"]);
+
+        let previous = tree.insert(EntryMode::Link, "a", blob())?.expect("replaced");
+        assert_eq!(previous.mode, EntryMode::Tree);
+        assert_eq!(filenames(&tree), vec!["a", "a-"]);
+        Ok(())
+    }
+
+    #[test]
+    fn remove_finds_trees_and_non_trees() -> crate::Result {
+        let mut tree = mutable::Tree::empty();
+        tree.insert(EntryMode::Tree, "dir", blob())?;
+        tree.insert(EntryMode::Blob, "file", blob())?;
+        assert_eq!(tree.remove("missing".into()), None);
+        assert_eq!(tree.remove("dir".into()).map(|entry| entry.mode), Some(EntryMode::Tree));
+        assert_eq!(
+            tree.remove("file".into()).map(|entry| entry.mode),
+            Some(EntryMode::Blob)
+        );
+        assert!(tree.entries.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn insert_rejects_invalid_filenames() {
+        let mut tree = mutable::Tree::empty();
+        for filename in &["", "a/b", "nul\0", "new\nline"] {
+            assert!(
+                tree.insert(EntryMode::Blob, *filename, blob()).is_err(),
+                "{:?} can't be a filename",
+                filename
+            );
+        }
+        assert!(tree.entries.is_empty());
+    }
+
+    #[test]
+    fn write_to_rejects_unsorted_entries_and_duplicate_filenames() {
+        let entry = |mode, filename: &str| mutable::tree::Entry {
+            mode,
+            filename: filename.into(),
+            oid: blob(),
+        };
+        for (entries, expected_error) in [
+            (
+                vec![entry(EntryMode::Tree, "a"), entry(EntryMode::Blob, "a.rs")],
+                "The tree entry \"a.rs\" must be sorted before \"a\"",
+            ),
+            (
+                vec![entry(EntryMode::Blob, "a"), entry(EntryMode::BlobExecutable, "a")],
+                "The filename \"a\" is used by more than one tree entry",
+            ),
+            (
+                vec![
+                    entry(EntryMode::Blob, "a"),
+                    entry(EntryMode::Blob, "a-"),
+                    entry(EntryMode::Tree, "a"),
+                ],
+                "The filename \"a\" is used by more than one tree entry",
+            ),
+        ] {
+            let err = mutable::Tree { entries }
+                .write_to(Vec::new())
+                .expect_err("invalid trees can't be written");
+            assert_eq!(err.into_inner().expect("our error").to_string(), expected_error);
+        }
+    }
+}
+
+mod tree_editor {
+    use bstr::ByteSlice;
+    use git_hash::ObjectId;
+    use git_object::{immutable, mutable, mutable::tree::editor, tree::EntryMode};
+    use std::collections::HashMap;
+
+    struct Fixture {
+        base: ObjectId,
+        new_blob: ObjectId,
+        edited: ObjectId,
+        trees: HashMap<ObjectId, Vec<u8>>,
+    }
+
+    fn fixture() -> crate::Result<Fixture> {
+        let (dir, trees) = crate::edited_trees()?;
+        let id =
+            |name: &str| -> crate::Result<ObjectId> { Ok(ObjectId::from_hex(std::fs::read(dir.join(name))?.trim())?) };
+        Ok(Fixture {
+            base: id("base-tree-id")?,
+            new_blob: id("new-blob-id")?,
+            edited: id("edited-tree-id")?,
+            trees,
+        })
+    }
+
+    fn write_to(
+        trees: &mut HashMap<ObjectId, Vec<u8>>,
+    ) -> impl FnMut(&mutable::Tree) -> std::io::Result<ObjectId> + '_ {
+        move |tree| {
+            let mut data = Vec::new();
+            tree.write_to(&mut data)?;
+            let mut hasher = git_features::hash::Sha1::default();
+            hasher.update(format!("tree {}\0", data.len()).as_bytes());
+            hasher.update(&data);
+            let id = ObjectId::from(hasher.digest());
+            trees.insert(id, data);
+            Ok(id)
+        }
+    }
+
+    type Find<'a> = Box<dyn for<'b> FnMut(&git_hash::oid, &'b mut Vec<u8>) -> Option<immutable::TreeIter<'b>> + 'a>;
+
+    fn editor_for(fixture: &Fixture) -> crate::Result<mutable::tree::Editor<Find<'_>>> {
+        let root = immutable::Tree::from_bytes(&fixture.trees[&fixture.base])?.into();
+        Ok(mutable::tree::Editor::new(
+            root,
+            Box::new(move |id, buf| crate::find_tree(&fixture.trees, id, buf)),
+        ))
+    }
+
+    #[test]
+    fn edits_yield_the_same_tree_as_git() -> crate::Result {
+        let fixture = fixture()?;
+        let mut editor = editor_for(&fixture)?;
+        editor
+            .upsert("a/b/new", EntryMode::Blob, fixture.new_blob)?
+            .remove("a/x")?
+            .remove("c/y")?
+            .upsert("d/e/f", EntryMode::BlobExecutable, fixture.new_blob)?
+            .upsert("top", EntryMode::Blob, fixture.new_blob)?
+            .remove("does/not/exist")?;
+        let mut written = HashMap::new();
+        let id = editor.write(write_to(&mut written))?;
+        assert_eq!(id, fixture.edited, "the tree 'c' is removed as it becomes empty");
+        assert_eq!(
+            written.len(),
+            5,
+            "only the changed trees are written: the root, 'a', 'a/b', 'd' and 'd/e'"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn without_edits_the_base_tree_is_written() -> crate::Result {
+        let fixture = fixture()?;
+        let mut written = HashMap::new();
+        let id = editor_for(&fixture)?.write(write_to(&mut written))?;
+        assert_eq!(id, fixture.base);
+        assert_eq!(written.len(), 1, "only the root tree is written");
+        Ok(())
+    }
+
+    #[test]
+    fn entries_in_the_way_of_trees_are_replaced_and_trees_can_be_replaced_by_entries() -> crate::Result {
+        let fixture = fixture()?;
+        let mut editor = editor_for(&fixture)?;
+        editor
+            .upsert("top/nested", EntryMode::Blob, fixture.new_blob)?
+            .upsert("a/b/deeper", EntryMode::Blob, fixture.new_blob)?
+            .upsert("a", EntryMode::Link, fixture.new_blob)?;
+        let mut written = HashMap::new();
+        let id = editor.write(write_to(&mut written))?;
+
+        let root = immutable::Tree::from_bytes(&written[&id])?;
+        let entries: Vec<_> = root
+            .entries
+            .iter()
+            .map(|entry| (entry.filename.to_str().expect("UTF-8"), entry.mode))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![("a", EntryMode::Link), ("c", EntryMode::Tree), ("top", EntryMode::Tree)]
+        );
+        assert_eq!(
+            written.len(),
+            2,
+            "the changes in 'a' are discarded, leaving only 'top' and the root"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn new_trees_can_be_created_from_scratch() -> crate::Result {
+        let mut editor = mutable::tree::Editor::new(mutable::Tree::empty(), |_: &git_hash::oid, _: &mut Vec<u8>| {
+            unreachable!("new trees don't need to be looked up")
+        });
+        editor
+            .upsert("dir/file", EntryMode::Blob, ObjectId::null_sha1())?
+            .upsert("dir/sub/file", EntryMode::Blob, ObjectId::null_sha1())?;
+        let mut written = HashMap::new();
+        let id = editor.write(write_to(&mut written))?;
+        let root = immutable::Tree::from_bytes(&written[&id])?;
+        assert_eq!(root.entries.len(), 1);
+        assert_eq!(root.entries[0].filename, "dir");
+        assert_eq!(written.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_paths_and_missing_trees_are_errors() -> crate::Result {
+        let fixture = fixture()?;
+        let mut editor = editor_for(&fixture)?;
+        for path in &["", "a//b", "a/b/"] {
+            assert!(
+                editor.upsert(*path, EntryMode::Blob, fixture.new_blob).is_err(),
+                "{:?} is invalid",
+                path
+            );
+        }
+        assert!(matches!(editor.remove(""), Err(editor::Error::EmptyPath)));
+
+        let mut editor = mutable::tree::Editor::new(mutable::Tree::empty(), |_: &git_hash::oid, _: &mut Vec<u8>| None);
+        editor.upsert("missing", EntryMode::Tree, fixture.base)?;
+        let err = editor
+            .upsert("missing/file", EntryMode::Blob, fixture.new_blob)
+            .map(|_| ())
+            .expect_err("the tree can't be found");
+        assert!(matches!(
+            err,
+            editor::Error::LoadTree(immutable::tree::lookup::Error::FindTree { .. })
+        ));
+        assert_eq!(
+            std::error::Error::source(&err).expect("source").to_string(),
+            format!("The tree at 'missing' with id {} could not be found", fixture.base)
+        );
+        Ok(())
+    }
 }
 
 mod blob {
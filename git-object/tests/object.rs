@@ -1,7 +1,16 @@
-use std::path::PathBuf;
+use git_hash::{oid, ObjectId};
+use git_object::immutable::TreeIter;
+use std::{collections::HashMap, path::PathBuf};
 
+mod blob;
 mod immutable;
+mod kind;
+mod loose;
 mod mutable;
+mod pathspec;
+#[cfg(feature = "serde1")]
+mod serialization;
+mod signed;
 
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -27,3 +36,22 @@ pub fn fixture(path: &str) -> PathBuf {
 fn fixture_bytes(path: &str) -> Vec<u8> {
     fixup(std::fs::read(fixture(path)).unwrap())
 }
+
+/// Return the directory of the `make_edited_trees.sh` fixture along with all trees in it, keyed by their id.
+fn edited_trees() -> Result<(PathBuf, HashMap<ObjectId, Vec<u8>>)> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_edited_trees.sh")?;
+    let mut trees = HashMap::new();
+    for entry in std::fs::read_dir(dir.join("trees"))? {
+        let path = entry?.path();
+        let id = ObjectId::from_hex(path.file_name().expect("filename").to_str().expect("UTF-8").as_bytes())?;
+        trees.insert(id, std::fs::read(path)?);
+    }
+    Ok((dir, trees))
+}
+
+/// Load the tree with `id` from `trees` into `buf`.
+fn find_tree<'a>(trees: &HashMap<ObjectId, Vec<u8>>, id: &oid, buf: &'a mut Vec<u8>) -> Option<TreeIter<'a>> {
+    buf.clear();
+    buf.extend_from_slice(trees.get(&id.to_owned())?);
+    Some(TreeIter::from_bytes(buf))
+}
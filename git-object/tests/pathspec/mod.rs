@@ -0,0 +1,65 @@
+use git_object::{
+    bstr::ByteSlice,
+    pathspec::{Pathspecs, Relevance},
+};
+
+fn relevance(is_match: bool, contains_match: bool) -> Relevance {
+    Relevance {
+        is_match,
+        contains_match,
+    }
+}
+
+#[test]
+fn without_pathspecs_everything_matches() {
+    let mut specs = Pathspecs::default();
+    assert!(specs.is_empty());
+    assert_eq!(specs.relevance(b"a".as_bstr(), true), relevance(true, false));
+    specs.push_tree(b"a".as_bstr());
+    specs.pop_tree();
+    assert_eq!(specs.relevance(b"b".as_bstr(), false), relevance(true, false));
+}
+
+#[test]
+fn entries_match_at_or_below_pathspecs_and_trees_leading_up_to_them_contain_matches() {
+    let mut specs = Pathspecs::new(vec!["a/b/", "c"]);
+    assert_eq!(specs.relevance(b"a".as_bstr(), true), relevance(false, true));
+    assert_eq!(
+        specs.relevance(b"a".as_bstr(), false),
+        relevance(false, false),
+        "only trees can contain matches"
+    );
+    assert_eq!(specs.relevance(b"c".as_bstr(), true), relevance(true, false));
+    assert_eq!(
+        specs.relevance(b"cd".as_bstr(), false),
+        relevance(false, false),
+        "paths are matched component-wise"
+    );
+
+    specs.push_tree(b"a".as_bstr());
+    specs.push_tree(b"c".as_bstr());
+    specs.pop_tree();
+    assert_eq!(
+        specs.relevance(b"b".as_bstr(), true),
+        relevance(true, false),
+        "trailing slashes are ignored"
+    );
+    assert_eq!(specs.relevance(b"c".as_bstr(), false), relevance(false, false));
+    specs.push_tree(b"b".as_bstr());
+
+    specs.pop_tree();
+    assert_eq!(
+        specs.relevance(b"anything".as_bstr(), false),
+        relevance(true, false),
+        "entries below a pathspec match"
+    );
+    specs.pop_tree();
+    assert_eq!(specs.relevance(b"d".as_bstr(), false), relevance(true, false));
+
+    specs.reset(Some("x"));
+    assert_eq!(
+        specs.relevance(b"c".as_bstr(), false),
+        relevance(false, false),
+        "resetting starts at the root tree"
+    );
+}
@@ -0,0 +1,31 @@
+use crate::fixture_bytes;
+use git_object::{immutable, mutable};
+
+#[test]
+fn mutable_objects_round_trip_through_json() -> crate::Result {
+    for (kind, path) in &[
+        (git_object::Kind::Commit, "commit/signed-with-encoding.txt"),
+        (git_object::Kind::Commit, "commit/mergetag.txt"),
+        (git_object::Kind::Tag, "tag/signed.txt"),
+        (git_object::Kind::Tree, "tree/everything.tree"),
+    ] {
+        let data = fixture_bytes(path);
+        let object: mutable::Object = immutable::Object::from_bytes(*kind, &data)?.into();
+        let json = serde_json::to_string(&object)?;
+        assert_eq!(serde_json::from_str::<mutable::Object>(&json)?, object, "{}", path);
+    }
+    Ok(())
+}
+
+#[test]
+fn immutable_objects_serialize_like_their_mutable_counterparts() -> crate::Result {
+    let data = fixture_bytes("commit/unsigned.txt");
+    let commit = immutable::Commit::from_bytes(&data)?;
+    let json = serde_json::to_value(&commit)?;
+    assert_eq!(
+        json["author"],
+        serde_json::to_value(&git_actor::Signature::from(commit.author))?,
+        "signatures are the same no matter if they are borrowed or owned"
+    );
+    Ok(())
+}
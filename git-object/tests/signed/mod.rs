@@ -0,0 +1,214 @@
+use crate::fixture_bytes;
+use bstr::ByteSlice;
+use git_object::{immutable, mutable, signed, Kind};
+use std::path::{Path, PathBuf};
+
+fn fixture() -> crate::Result<PathBuf> {
+    git_testtools::scripted_fixture_repo_read_only("make_signed_objects.sh")
+}
+
+fn object(dir: &Path, id: &str) -> crate::Result<(Kind, Vec<u8>)> {
+    let data = std::fs::read(dir.join("objects").join(id))?;
+    let kind = if data.starts_with(b"tree ") {
+        Kind::Commit
+    } else {
+        Kind::Tag
+    };
+    Ok((kind, data))
+}
+
+#[test]
+fn signed_objects_are_split_like_git_does_for_verification() -> crate::Result {
+    let dir = fixture()?;
+    let mut verified = 0;
+    for entry in std::fs::read_dir(dir.join("verified"))? {
+        let entry = entry?;
+        let id = entry.file_name().into_string().expect("valid UTF-8");
+        let (kind, data) = object(&dir, &id)?;
+        let parts = signed::split(kind, git_hash::Kind::Sha1, &data).expect("signed");
+        assert_eq!(
+            parts.payload.as_ref(),
+            std::fs::read(entry.path().join("payload"))?.as_bstr(),
+            "{:?} {} payload",
+            kind,
+            id
+        );
+        assert_eq!(
+            parts.signature.as_ref(),
+            std::fs::read(entry.path().join("signature"))?.as_bstr(),
+            "{:?} {} signature",
+            kind,
+            id
+        );
+        verified += 1;
+    }
+    assert_eq!(verified, 3, "two signed commits and a signed tag");
+    Ok(())
+}
+
+#[test]
+fn unsigned_objects_cannot_be_split() -> crate::Result {
+    let dir = fixture()?;
+    let mut unsigned = 0;
+    for entry in std::fs::read_dir(dir.join("objects"))? {
+        let id = entry?.file_name().into_string().expect("valid UTF-8");
+        if dir.join("verified").join(&id).is_dir() {
+            continue;
+        }
+        let (kind, data) = object(&dir, &id)?;
+        assert_eq!(
+            signed::split(kind, git_hash::Kind::Sha1, &data),
+            None,
+            "{:?} {}",
+            kind,
+            id
+        );
+        unsigned += 1;
+    }
+    assert_eq!(unsigned, 2, "an unsigned commit and an unsigned tag");
+    assert_eq!(signed::split(Kind::Blob, git_hash::Kind::Sha1, b"gpgsig blob\n"), None);
+    Ok(())
+}
+
+#[test]
+fn other_multi_line_headers_remain_in_the_payload() -> crate::Result {
+    let data = fixture_bytes("commit/two-multiline-headers.txt");
+    let parts = signed::split_commit(&data, git_hash::Kind::Sha1).expect("signed");
+    let mut commit: mutable::Commit = immutable::Commit::from_bytes(&data)?.into();
+    let (_, signature) = commit.extra_headers.pop().expect("gpgsig is the last header");
+    let mut payload = Vec::new();
+    commit.write_to(&mut payload)?;
+
+    assert!(payload.contains_str("\nmergetag object "));
+    assert_eq!(parts.payload.as_ref(), payload.as_bstr());
+    assert_eq!(
+        parts.signature.as_ref(),
+        format!("{}\n", signature).as_bytes().as_bstr()
+    );
+    Ok(())
+}
+
+#[test]
+fn signatures_for_sha1_and_sha256_are_returned_separately() {
+    let data = b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+author a <a@example.com> 1 +0000
+committer c <c@example.com> 1 +0000
+gpgsig -----BEGIN PGP SIGNATURE-----
+ sha1
+ -----END PGP SIGNATURE-----
+gpgsig-sha256 -----BEGIN PGP SIGNATURE-----
+ sha256
+ -----END PGP SIGNATURE-----
+
+message
+";
+    let expected_payload = b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+author a <a@example.com> 1 +0000
+committer c <c@example.com> 1 +0000
+
+message
+";
+    for (hash_kind, expected_signature) in &[
+        (
+            git_hash::Kind::Sha1,
+            "-----BEGIN PGP SIGNATURE-----\nsha1\n-----END PGP SIGNATURE-----\n",
+        ),
+        (
+            git_hash::Kind::Sha256,
+            "-----BEGIN PGP SIGNATURE-----\nsha256\n-----END PGP SIGNATURE-----\n",
+        ),
+    ] {
+        let parts = signed::split_commit(data, *hash_kind).expect("signed");
+        assert_eq!(
+            parts.payload.as_ref(),
+            expected_payload.as_bstr(),
+            "both signatures are removed from the payload"
+        );
+        assert_eq!(parts.signature.as_ref(), expected_signature.as_bytes().as_bstr());
+    }
+
+    let sha1_only = data.replace("gpgsig-sha256", "x-sha256");
+    assert_eq!(
+        signed::split_commit(&sha1_only, git_hash::Kind::Sha256),
+        None,
+        "the SHA1 signature isn't used for SHA256"
+    );
+}
+
+#[test]
+fn verification_is_delegated_to_the_caller() -> crate::Result {
+    let data = fixture_bytes("tag/signed.txt");
+    let signer = signed::verify(Kind::Tag, git_hash::Kind::Sha1, &data, |payload, signature| {
+        assert!(payload.ends_with(b"\n"), "the payload doesn't contain the signature");
+        if signature.starts_with(b"-----BEGIN PGP SIGNATURE-----") {
+            Ok("trusted signer")
+        } else {
+            Err(std::fmt::Error)
+        }
+    })?;
+    assert_eq!(signer, "trusted signer");
+
+    let err = signed::verify(Kind::Tag, git_hash::Kind::Sha1, &data, |_payload, _signature| {
+        Err::<(), _>(std::fmt::Error)
+    })
+    .expect_err("verification failed");
+    assert!(matches!(err, signed::Error::Verify(std::fmt::Error)));
+    assert!(std::error::Error::source(&err).is_some());
+
+    let unsigned = fixture_bytes("tag/whitespace.txt");
+    assert!(matches!(
+        signed::verify(Kind::Tag, git_hash::Kind::Sha1, &unsigned, |_payload, _signature| Ok::<
+            _,
+            std::fmt::Error,
+        >(
+            ()
+        )),
+        Err(signed::Error::Unsigned)
+    ));
+    Ok(())
+}
+
+#[test]
+fn merged_tags_are_parsed_and_split_like_git_does_for_verification() -> crate::Result {
+    let dir = fixture()?.join("mergetag");
+    let id = |name: &str| -> crate::Result<git_hash::ObjectId> {
+        Ok(git_hash::ObjectId::from_hex(std::fs::read(dir.join(name))?.trim())?)
+    };
+    let data = std::fs::read(dir.join("commit"))?;
+    let commit = immutable::Commit::from_bytes(&data)?;
+
+    let tags = commit.extra_headers().mergetags().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(tags.len(), 1);
+    let tag = &tags[0];
+    assert_eq!(tag.name, "signed-side-tag");
+    assert_eq!(tag.target(), id("target-id")?);
+    assert!(
+        commit.parents().any(|parent| parent == tag.target()),
+        "merged tags point to a parent"
+    );
+    assert!(tag.pgp_signature.is_some());
+
+    let tags_data = commit.extra_headers().mergetags_data().collect::<Vec<_>>();
+    assert_eq!(tags_data.len(), 1);
+    let tag_data = &tags_data[0];
+    let mut hasher = git_features::hash::Sha1::default();
+    hasher.update(format!("tag {}\0", tag_data.len()).as_bytes());
+    hasher.update(tag_data);
+    assert_eq!(
+        git_hash::ObjectId::from(hasher.digest()),
+        id("tag-id")?,
+        "the data is the merged tag object"
+    );
+
+    let parts = signed::split(Kind::Tag, git_hash::Kind::Sha1, tag_data).expect("signed");
+    let verified = dir.join("verified");
+    assert_eq!(
+        parts.payload.as_ref(),
+        std::fs::read(verified.join("payload"))?.as_bstr()
+    );
+    assert_eq!(
+        parts.signature.as_ref(),
+        std::fs::read(verified.join("signature"))?.as_bstr()
+    );
+    Ok(())
+}
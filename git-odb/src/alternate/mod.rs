@@ -17,7 +17,11 @@
 //!
 //! Based on the [canonical implementation](https://github.com/git/git/blob/master/sha1-file.c#L598:L609).
 use crate::store::compound;
-use std::{fs, io, path::PathBuf};
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
 
 ///
 pub mod parse;
@@ -38,35 +42,77 @@ pub enum Error {
     Cycle(Vec<PathBuf>),
 }
 
+/// The maximum amount of nested alternates to follow, the same value as used by git.
+pub const MAX_DEPTH: usize = 5;
+
+/// The outcome of [`resolve_with_outcome()`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// All alternate object directories in the order git would use them, each one listed only once.
+    pub alternates: Vec<PathBuf>,
+    /// Object directories whose alternates were ignored as they are nested more than [`MAX_DEPTH`] levels deep, which
+    /// git warns about.
+    pub nested_too_deep: Vec<PathBuf>,
+}
+
 /// Given an objects directory, try to resolve alternate object directories possibly located in the
 /// `./info/alternates` file.
 /// If no alternate object database was resolved, the resulting `Vec` is empty (it is not an error
 /// if there are no alternates).
-/// It is an error once a repository is seen again as it would lead to a cycle.
+/// It is an error if an objects directory lists one of the directories leading to it as this would be a cycle.
+/// Alternates nested deeper than [`MAX_DEPTH`] are ignored, use [`resolve_with_outcome()`] to learn about them.
+///
+/// Relative paths are resolved relative to the objects directory containing the `alternates` file they are listed in.
 pub fn resolve(objects_directory: impl Into<PathBuf>) -> Result<Vec<PathBuf>, Error> {
-    let relative_base = objects_directory.into();
-    let mut dirs = vec![(0, relative_base.clone())];
-    let mut out = Vec::new();
-    let mut seen = vec![relative_base.canonicalize()?];
-    while let Some((depth, dir)) = dirs.pop() {
-        match fs::read(dir.join("info").join("alternates")) {
-            Ok(input) => {
-                for path in parse::content(&input)?.into_iter() {
-                    let path = relative_base.join(path);
-                    let path_canonicalized = path.canonicalize()?;
-                    if seen.contains(&path_canonicalized) {
-                        return Err(Error::Cycle(seen));
-                    }
-                    seen.push(path_canonicalized);
-                    dirs.push((depth + 1, path));
-                }
-            }
-            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
-            Err(err) => return Err(err.into()),
-        };
-        if depth != 0 {
-            out.push(dir);
+    resolve_with_outcome(objects_directory).map(|outcome| outcome.alternates)
+}
+
+/// Like [`resolve()`], but also returns the objects directories whose alternates were ignored as they are nested too deeply.
+///
+/// Alternates are followed depth-first like git does, and objects directories reachable through more than one alternate
+/// are only listed once.
+pub fn resolve_with_outcome(objects_directory: impl Into<PathBuf>) -> Result<Outcome, Error> {
+    let objects_directory = objects_directory.into();
+    let objects_directory_canonicalized = objects_directory.canonicalize()?;
+    let mut out = Outcome::default();
+    let mut seen = HashSet::new();
+    seen.insert(objects_directory_canonicalized.clone());
+    let mut chain = vec![objects_directory_canonicalized];
+    resolve_recursive(&objects_directory, 0, &mut chain, &mut seen, &mut out)?;
+    Ok(out)
+}
+
+/// Follow the alternates of `dir`, with `chain` being the canonicalized directories leading to it, and `seen` being all
+/// canonicalized directories encountered so far.
+fn resolve_recursive(
+    dir: &Path,
+    depth: usize,
+    chain: &mut Vec<PathBuf>,
+    seen: &mut HashSet<PathBuf>,
+    out: &mut Outcome,
+) -> Result<(), Error> {
+    let input = match fs::read(dir.join("info").join("alternates")) {
+        Ok(input) => input,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+    if depth == MAX_DEPTH {
+        out.nested_too_deep.push(dir.to_owned());
+        return Ok(());
+    }
+    for path in parse::content(&input)?.into_iter() {
+        let path = dir.join(path);
+        let path_canonicalized = path.canonicalize()?;
+        if chain.contains(&path_canonicalized) {
+            return Err(Error::Cycle(chain.clone()));
+        }
+        if !seen.insert(path_canonicalized.clone()) {
+            continue;
         }
+        out.alternates.push(path.clone());
+        chain.push(path_canonicalized);
+        resolve_recursive(&path, depth + 1, chain, seen, out)?;
+        chain.pop();
     }
-    Ok(out)
+    Ok(())
 }
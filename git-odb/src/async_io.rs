@@ -0,0 +1,98 @@
+//! Look up objects from async code without blocking the executor, available with the `async-io` feature.
+//!
+//! Object lookups are CPU and IO bound, so they are performed on a thread pool for blocking operations by [`Unblock`], which turns
+//! any implementation of the blocking [`Find`][crate::Find] trait into an implementation of the async [`Find`] trait.
+use crate::pack;
+use async_trait::async_trait;
+use git_hash::ObjectId;
+use std::sync::Arc;
+
+/// An object found by [`Find::find()`], owning its data.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Object {
+    /// The kind of the object
+    pub kind: git_object::Kind,
+    /// The fully decoded object data
+    pub data: Vec<u8>,
+    /// Provides access to preserve the pack location of the object, if it was found in a pack.
+    pub pack_location: Option<pack::bundle::Location>,
+}
+
+impl Object {
+    /// Borrow this instance as [`data::Object`][crate::data::Object], for example to decode it.
+    pub fn to_borrowed(&self) -> crate::data::Object<'_> {
+        crate::data::Object {
+            kind: self.kind,
+            data: &self.data,
+            pack_location: self.pack_location.clone(),
+        }
+    }
+}
+
+/// The async counterpart of the blocking [`Find`][crate::Find] trait.
+#[async_trait]
+pub trait Find {
+    /// The error returned by [`find()`][Find::find()]
+    type Error: std::error::Error + Send + 'static;
+
+    /// Find an object matching `id` in the database and return it with its data, or `None` if it doesn't exist.
+    async fn find(&self, id: ObjectId) -> Result<Option<Object>, Self::Error>;
+
+    /// Find the location of the object with `id` in a pack, or `None` if it isn't stored in a pack.
+    async fn location_by_id(&self, id: ObjectId) -> Option<pack::bundle::Location>;
+}
+
+/// Adapt the blocking object database `T` to the async [`Find`] trait by performing lookups on a thread pool
+/// for blocking operations.
+///
+/// As lookups happen on other threads, no pack cache can be used across calls.
+pub struct Unblock<T>(pub Arc<T>);
+
+impl<T> Clone for Unblock<T> {
+    fn clone(&self) -> Self {
+        Unblock(Arc::clone(&self.0))
+    }
+}
+
+impl<T> From<Arc<T>> for Unblock<T> {
+    fn from(db: Arc<T>) -> Self {
+        Unblock(db)
+    }
+}
+
+impl<T> From<T> for Unblock<T> {
+    fn from(db: T) -> Self {
+        Unblock(Arc::new(db))
+    }
+}
+
+#[async_trait]
+impl<T> Find for Unblock<T>
+where
+    T: crate::Find + Send + Sync + 'static,
+    T::Error: Send,
+{
+    type Error = T::Error;
+
+    async fn find(&self, id: ObjectId) -> Result<Option<Object>, Self::Error> {
+        let db = Arc::clone(&self.0);
+        blocking::unblock(move || -> Result<_, T::Error> {
+            let mut data = Vec::new();
+            let (kind, pack_location) = match db.find(id, &mut data, &mut pack::cache::Never)? {
+                Some(object) => (object.kind, object.pack_location),
+                None => return Ok(None),
+            };
+            Ok(Some(Object {
+                kind,
+                data,
+                pack_location,
+            }))
+        })
+        .await
+    }
+
+    async fn location_by_id(&self, id: ObjectId) -> Option<pack::bundle::Location> {
+        let db = Arc::clone(&self.0);
+        blocking::unblock(move || db.location_by_id(id, &mut Vec::new())).await
+    }
+}
@@ -15,6 +15,9 @@
 //!   * This is the database closely resembling the object database in a git repository, and probably what most people would want to use.
 //! * [`linked::Store`]
 //!   * A database containing various [`compound::Stores`][compound::Store] as gathered from `alternates` files.
+//! * [`memory::Store`]
+//!   * A database keeping written objects in memory, optionally reading all other objects from a base database like a
+//!     [`linked::Store`] which is never written to.
 pub use git_pack as pack;
 pub use pack::{data, Find, FindExt};
 
@@ -23,5 +26,10 @@ pub use store::*;
 
 pub mod alternate;
 
+pub mod shallow;
+
 mod write;
 pub use write::Write;
+
+#[cfg(feature = "async-io")]
+pub mod async_io;
@@ -0,0 +1,84 @@
+//! The `shallow` file of shallow clones, listing the commits whose parents are not present in the object database.
+//!
+//! A shallow file in `<git-dir>/shallow` contains one hex encoded commit id per line, like so:
+//!
+//! ```text
+//! 2f8ac2bb9bc79ec2a4b8ae8a5c2ef1a0c5faf2e1
+//! b9a6e7c1fb2d50c2ab5d7ba6e6e1c5f0a69d81f7
+//! ```
+//!
+//! These commits form the boundary of the history that was fetched, and traversals need to treat them as if they had no
+//! parents. Based on the [canonical implementation](https://github.com/git/git/blob/master/shallow.c).
+use git_hash::{oid, ObjectId};
+use std::{io, path::Path};
+
+/// Returned by [`Boundary::from_bytes()`] and [`Boundary::from_git_dir()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Line {line_number} of the shallow file is not a hex encoded object id: '{line}'")]
+    Parse { line_number: usize, line: String },
+}
+
+/// The commits at the boundary of a shallow clone, whose parents are not present in the object database.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+pub struct Boundary {
+    /// Sorted and without duplicates to allow binary searches.
+    commits: Vec<ObjectId>,
+}
+
+/// Initialization
+impl Boundary {
+    /// Parse the contents of a `shallow` file from `data`, ignoring empty lines.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let mut commits = Vec::new();
+        for (line_number, line) in data.split(|b| *b == b'\n').enumerate() {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.is_empty() {
+                continue;
+            }
+            commits.push(ObjectId::from_hex(line).map_err(|_| Error::Parse {
+                line_number: line_number + 1,
+                line: String::from_utf8_lossy(line).into_owned(),
+            })?);
+        }
+        commits.sort();
+        commits.dedup();
+        Ok(Boundary { commits })
+    }
+
+    /// Read the `shallow` file from the repository at `git_dir`, returning an empty boundary if the repository is not
+    /// shallow as there is no such file.
+    pub fn from_git_dir(git_dir: impl AsRef<Path>) -> Result<Self, Error> {
+        match std::fs::read(git_dir.as_ref().join("shallow")) {
+            Ok(data) => Self::from_bytes(&data),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Boundary::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Access
+impl Boundary {
+    /// Return true if the commit with `id` is part of the boundary, hence its parents must not be looked up.
+    pub fn contains(&self, id: impl AsRef<oid>) -> bool {
+        self.commits.binary_search(&id.as_ref().to_owned()).is_ok()
+    }
+
+    /// Return all commits of the boundary, sorted by id.
+    pub fn commits(&self) -> &[ObjectId] {
+        &self.commits
+    }
+
+    /// The amount of commits in the boundary.
+    pub fn len(&self) -> usize {
+        self.commits.len()
+    }
+
+    /// Return true if the repository isn't shallow.
+    pub fn is_empty(&self) -> bool {
+        self.commits.is_empty()
+    }
+}
@@ -2,7 +2,9 @@ use crate::{
     pack,
     store::{compound, loose},
 };
+use git_features::zlib;
 use git_pack::data;
+use std::io;
 
 /// Returned by [`compound::Store::find()`]
 #[derive(thiserror::Error, Debug)]
@@ -12,6 +14,35 @@ pub enum Error {
     Loose(#[from] loose::find::Error),
     #[error("An error occurred while obtaining an object from the packed object store")]
     Pack(#[from] pack::data::decode_entry::Error),
+    #[error("Object {id} is missing in this partial clone and has to be fetched from the promisor remote")]
+    MissingPromisor { id: git_hash::ObjectId },
+    #[error("The replacements of object {id} are nested too deeply or form a cycle")]
+    ReplacementDepthExceeded { id: git_hash::ObjectId },
+}
+
+/// A reader streaming the data of an object, as returned by [`compound::Store::stream()`].
+pub struct Stream<'a> {
+    /// The kind of the object.
+    pub kind: git_object::Kind,
+    /// The size of the object's data in bytes, which is the amount of bytes that can be read from this stream.
+    pub size: u64,
+    inner: StreamInner<'a>,
+}
+
+enum StreamInner<'a> {
+    Loose(loose::find::Stream),
+    Packed(zlib::stream::inflate::ReadBoxed<&'a [u8]>),
+    Decoded(&'a [u8]),
+}
+
+impl<'a> io::Read for Stream<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            StreamInner::Loose(stream) => stream.read(buf),
+            StreamInner::Packed(stream) => stream.read(buf),
+            StreamInner::Decoded(data) => data.read(buf),
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
@@ -21,9 +52,21 @@ pub(crate) struct PackLocation {
 }
 
 impl compound::Store {
+    /// Return true if the given object `id` is contained in the store.
+    ///
+    /// This only performs pack index lookups and a file-existence check for loose objects, without decompressing any data.
+    /// Like in git, the [empty tree][git_hash::ObjectId::empty_tree()] is always contained.
+    pub fn contains(&self, id: impl AsRef<git_hash::oid>) -> bool {
+        let id = id.as_ref();
+        self.internal_find_packed(id).is_some() || self.loose.contains(id) || is_empty_tree(id)
+    }
+
     /// Find an object as identified by [`ObjectId`][git_hash::ObjectId] and store its data in full in the provided `buffer`.
     /// This will search the object in all contained object databases.
     /// Use a `pack_cache` to accelerate pack access by reducing the amount of work duplication, or [`pack::cache::Never`] to disable any caching.
+    ///
+    /// The [empty tree][git_hash::ObjectId::empty_tree()] is always found, even if it isn't stored in the database.
+    /// If this store [is a promisor][compound::Store::is_promisor()], missing objects cause an [`Error::MissingPromisor`].
     pub fn find<'a>(
         &self,
         id: impl AsRef<git_hash::oid>,
@@ -40,7 +83,64 @@ impl compound::Store {
         if self.loose.contains(id) {
             return self.loose.find(id, buffer).map_err(Into::into);
         }
-        Ok(None)
+        self.fail_if_promised(id)?;
+        Ok(empty_tree(id, buffer))
+    }
+
+    /// Find an object as identified by [`ObjectId`][git_hash::ObjectId] and return a reader to obtain its data incrementally,
+    /// which allows to handle objects too large to be held in memory at once.
+    ///
+    /// Loose objects and packed objects that are not deltified are decompressed as they are read. Deltified objects
+    /// have to be resolved in full to be applied to their base, which is done using `buffer` and `pack_cache` just like
+    /// in [`find()`][compound::Store::find()], with the returned stream reading from `buffer`.
+    pub fn stream<'a>(
+        &'a self,
+        id: impl AsRef<git_hash::oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl pack::cache::DecodeEntry,
+    ) -> Result<Option<Stream<'a>>, Error> {
+        let id = id.as_ref();
+        for bundle in &self.bundles {
+            if let Some(idx) = find_pack_index(bundle, id) {
+                let entry = bundle.pack.entry(bundle.index.pack_offset_at_index(idx));
+                if let (Some(kind), Some(stream)) = (entry.header.as_kind(), bundle.pack.stream_entry(&entry)) {
+                    return Ok(Some(Stream {
+                        kind,
+                        size: entry.decompressed_size,
+                        inner: StreamInner::Packed(stream),
+                    }));
+                }
+                let object = bundle.get_object_by_index(idx, buffer, pack_cache)?;
+                return Ok(Some(Stream {
+                    kind: object.kind,
+                    size: object.data.len() as u64,
+                    inner: StreamInner::Decoded(object.data),
+                }));
+            }
+        }
+        if let Some(stream) = self.loose.stream(id)? {
+            return Ok(Some(Stream {
+                kind: stream.kind,
+                size: stream.size,
+                inner: StreamInner::Loose(stream),
+            }));
+        }
+        self.fail_if_promised(id)?;
+        Ok(empty_tree(id, buffer).map(|object| Stream {
+            kind: object.kind,
+            size: 0,
+            inner: StreamInner::Decoded(object.data),
+        }))
+    }
+
+    /// Called with the `id` of an object that isn't stored in this database to fail if it is expected to be available
+    /// on a promisor remote.
+    fn fail_if_promised(&self, id: &git_hash::oid) -> Result<(), Error> {
+        if self.is_promisor() && !is_empty_tree(id) {
+            Err(Error::MissingPromisor { id: id.to_owned() })
+        } else {
+            Ok(())
+        }
     }
 
     /// Internal-use function to look up a packed object index or loose object.
@@ -79,3 +179,17 @@ impl compound::Store {
 fn find_pack_index(bundle: &git_pack::Bundle, id: &git_hash::oid) -> Option<u32> {
     bundle.index.lookup(id)
 }
+
+pub(crate) fn is_empty_tree(id: &git_hash::oid) -> bool {
+    id.to_owned().is_empty_tree()
+}
+
+/// Return the empty tree if `id` is the one of the empty tree, which is assumed to be present in every object database.
+pub(crate) fn empty_tree<'a>(id: &git_hash::oid, buffer: &'a mut Vec<u8>) -> Option<data::Object<'a>> {
+    if is_empty_tree(id) {
+        buffer.clear();
+        Some(data::Object::new(git_object::Kind::Tree, buffer))
+    } else {
+        None
+    }
+}
@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use crate::{
     pack,
@@ -28,27 +31,45 @@ impl compound::Store {
         if !loose_objects.is_dir() {
             return Err(Error::Inaccessible(loose_objects));
         }
-        let packs = match std::fs::read_dir(loose_objects.join("pack")) {
-            Ok(entries) => {
-                let mut packs_and_sizes = entries
-                    .filter_map(Result::ok)
-                    .filter_map(|e| e.metadata().map(|md| (e.path(), md)).ok())
-                    .filter(|(_, md)| md.file_type().is_file())
-                    .filter(|(p, _)| {
-                        p.extension().unwrap_or_default() == "idx"
-                            && p.file_name().unwrap_or_default().to_string_lossy().starts_with("pack-")
-                    })
-                    .map(|(p, md)| pack::Bundle::at(p).map(|b| (b, md.len())))
-                    .collect::<Result<Vec<_>, _>>()?;
-                packs_and_sizes.sort_by_key(|e| e.1);
-                packs_and_sizes.into_iter().rev().map(|(b, _)| b).collect()
-            }
-            Err(_) => Vec::new(),
-        };
-
+        let packs = index_paths_by_size(&loose_objects.join("pack"))
+            .into_iter()
+            .map(pack::Bundle::at)
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(compound::Store {
             loose: loose::Store::at(loose_objects),
+            has_promisor_packs: has_promisor_packs(&packs),
             bundles: packs,
+            refresh_mode: Default::default(),
+            last_refresh: Instant::now(),
         })
     }
 }
+
+/// Return true if any of the given `bundles` has a `.promisor` file next to its pack, as written by git for packs received
+/// from a promisor remote in a partial clone.
+pub(crate) fn has_promisor_packs(bundles: &[pack::Bundle]) -> bool {
+    bundles
+        .iter()
+        .any(|bundle| bundle.pack.path().with_extension("promisor").is_file())
+}
+
+/// Return the paths to all pack indices in `pack_directory`, largest pack first, or nothing if the directory can't be read.
+pub(crate) fn index_paths_by_size(pack_directory: &Path) -> Vec<PathBuf> {
+    match std::fs::read_dir(pack_directory) {
+        Ok(entries) => {
+            let mut paths_and_sizes: Vec<_> = entries
+                .filter_map(Result::ok)
+                .filter_map(|e| e.metadata().map(|md| (e.path(), md)).ok())
+                .filter(|(_, md)| md.file_type().is_file())
+                .filter(|(p, _)| {
+                    p.extension().unwrap_or_default() == "idx"
+                        && p.file_name().unwrap_or_default().to_string_lossy().starts_with("pack-")
+                })
+                .map(|(p, md)| (p, md.len()))
+                .collect();
+            paths_and_sizes.sort_by_key(|e| e.1);
+            paths_and_sizes.into_iter().rev().map(|(p, _)| p).collect()
+        }
+        Err(_) => Vec::new(),
+    }
+}
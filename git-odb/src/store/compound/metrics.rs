@@ -0,0 +1,175 @@
+use crate::{
+    pack,
+    store::{compound, loose},
+};
+use pack::data::entry::Header;
+use std::{collections::HashMap, io, path::PathBuf};
+
+/// Returned by [`compound::Store::metrics()`]
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    LooseIter(#[from] loose::iter::Error),
+    #[error("Could not read the loose object directory at '{path}'")]
+    Io { source: io::Error, path: PathBuf },
+}
+
+/// How to count loose objects when computing [`Metrics`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum LooseObjects {
+    /// Count every loose object, which requires listing all of the 256 fan-out directories.
+    Exact,
+    /// Count the loose objects in a single fan-out directory and multiply them by 256, which is what git does to decide if
+    /// `git gc --auto` should run. It is fast and accurate enough for large amounts of objects.
+    Estimate,
+}
+
+/// Configure what [`compound::Store::metrics()`] computes.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Options {
+    /// How to count loose objects.
+    pub loose_objects: LooseObjects,
+    /// If true, the headers of all pack entries are decoded to learn about delta chains, which takes time proportional to the
+    /// amount of packed objects. Otherwise the delta related fields of [`Metrics`] are zero.
+    pub delta_chains: bool,
+}
+
+impl Default for Options {
+    /// Count loose objects exactly and compute delta chains.
+    fn default() -> Self {
+        Options {
+            loose_objects: LooseObjects::Exact,
+            delta_chains: true,
+        }
+    }
+}
+
+/// Statistics about an object database, as returned by [`compound::Store::metrics()`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+pub struct Metrics {
+    /// The amount of packs.
+    pub num_packs: usize,
+    /// The amount of objects in all packs, which may contain duplicates if the same object is stored in multiple packs.
+    pub num_packed_objects: u64,
+    /// The size of all pack data files in bytes.
+    pub packs_size_in_bytes: u64,
+    /// The amount of loose objects, which is an estimate if [`LooseObjects::Estimate`] was used.
+    pub num_loose_objects: u64,
+    /// The amount of pack entries which are deltas.
+    pub num_deltas: u64,
+    /// The length of the longest delta chain, which is the amount of deltas to apply to a base object to obtain an object.
+    pub longest_delta_chain: usize,
+}
+
+impl Metrics {
+    /// Add the `other` metrics to ours, for example to obtain the metrics of multiple databases.
+    pub fn merge(&mut self, other: &Metrics) {
+        self.num_packs += other.num_packs;
+        self.num_packed_objects += other.num_packed_objects;
+        self.packs_size_in_bytes += other.packs_size_in_bytes;
+        self.num_loose_objects += other.num_loose_objects;
+        self.num_deltas += other.num_deltas;
+        self.longest_delta_chain = self.longest_delta_chain.max(other.longest_delta_chain);
+    }
+}
+
+impl compound::Store {
+    /// Compute statistics about our packs and loose objects as configured by `options`, without reading any object data.
+    pub fn metrics(&self, options: Options) -> Result<Metrics, Error> {
+        let mut out = Metrics {
+            num_packs: self.bundles.len(),
+            num_loose_objects: match options.loose_objects {
+                LooseObjects::Exact => {
+                    let mut count = 0;
+                    for id in self.loose.iter() {
+                        id?;
+                        count += 1;
+                    }
+                    count
+                }
+                LooseObjects::Estimate => estimate_loose_objects(&self.loose)?,
+            },
+            ..Default::default()
+        };
+        for bundle in &self.bundles {
+            out.num_packed_objects += bundle.index.num_objects() as u64;
+            out.packs_size_in_bytes += bundle.pack.data_len() as u64;
+            if options.delta_chains {
+                let (num_deltas, longest_delta_chain) = delta_chains(bundle);
+                out.num_deltas += num_deltas;
+                out.longest_delta_chain = out.longest_delta_chain.max(longest_delta_chain);
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Count the objects in the `17` fan-out directory like git does and extrapolate to all 256 directories.
+fn estimate_loose_objects(db: &loose::Store) -> Result<u64, Error> {
+    let path = db.path.join("17");
+    let entries = match std::fs::read_dir(&path) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(source) => return Err(Error::Io { source, path }),
+    };
+    let mut count = 0;
+    for entry in entries {
+        let entry = entry.map_err(|source| Error::Io {
+            source,
+            path: path.clone(),
+        })?;
+        let name = entry.file_name();
+        let is_object = name
+            .to_str()
+            .map_or(false, |name| git_hash::Kind::from_hex_len(2 + name.len()).is_some());
+        if is_object {
+            count += 1;
+        }
+    }
+    Ok(count * 256)
+}
+
+/// Return the amount of delta entries in `bundle` along with the length of its longest delta chain.
+///
+/// Deltas whose base isn't contained in the pack, as in thin packs, count as if their base was.
+fn delta_chains(bundle: &pack::Bundle) -> (u64, usize) {
+    let index = &bundle.index;
+    let num_objects = index.num_objects() as usize;
+    let mut depth_by_offset = HashMap::<u64, usize>::with_capacity(num_objects);
+    let mut chain = Vec::new();
+    let (mut num_deltas, mut longest) = (0, 0);
+    // In pack order, bases of offset deltas are seen before the deltas, so chains are usually known already.
+    for entry in index.iter_in_pack_order() {
+        let mut pack_offset = entry.pack_offset;
+        let base_depth = loop {
+            if let Some(depth) = depth_by_offset.get(&pack_offset) {
+                break *depth;
+            }
+            let base_offset = match bundle.pack.entry(pack_offset).header {
+                Header::OfsDelta { base_distance } => Header::verified_base_pack_offset(pack_offset, base_distance),
+                Header::RefDelta { base_id } => index
+                    .lookup(base_id)
+                    .map(|base_index| index.pack_offset_at_index(base_index)),
+                _base_object => {
+                    depth_by_offset.insert(pack_offset, 0);
+                    break 0;
+                }
+            };
+            chain.push(pack_offset);
+            match base_offset {
+                Some(base_offset) if chain.len() <= num_objects => pack_offset = base_offset,
+                _missing_base_or_cycle => break 0,
+            }
+        };
+        for (distance, pack_offset) in chain.drain(..).rev().enumerate() {
+            depth_by_offset.insert(pack_offset, base_depth + distance + 1);
+        }
+        let depth = depth_by_offset[&entry.pack_offset];
+        if depth > 0 {
+            num_deltas += 1;
+            longest = longest.max(depth);
+        }
+    }
+    (num_deltas, longest)
+}
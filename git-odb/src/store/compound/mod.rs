@@ -5,6 +5,14 @@ use crate::{pack, store::loose};
 pub mod find;
 ///
 pub mod init;
+///
+pub mod metrics;
+///
+pub mod refresh;
+#[doc(inline)]
+pub use refresh::RefreshMode;
+///
+pub mod repack;
 mod write;
 
 /// An object database with tiered lookup packs and loose objects.
@@ -14,4 +22,21 @@ pub struct Store {
     pub loose: loose::Store,
     /// All packs in the `objects/packs` directory
     pub bundles: Vec<pack::Bundle>,
+    /// Determines if [`find_or_refresh()`][Store::find_or_refresh()] picks up packs that were added after instantiation.
+    pub refresh_mode: RefreshMode,
+    /// The last time the packs were scanned.
+    last_refresh: std::time::Instant,
+    /// True if at least one of our packs is a promisor pack.
+    has_promisor_packs: bool,
+}
+
+impl Store {
+    /// Return true if at least one of our packs was received from a promisor remote, as indicated by a `.promisor` file
+    /// next to it, which makes this store part of a _partial clone_.
+    ///
+    /// Objects missing in such a store are assumed to be available on the promisor remote, which is why looking them up
+    /// fails with [`find::Error::MissingPromisor`] instead of indicating that the object doesn't exist.
+    pub fn is_promisor(&self) -> bool {
+        self.has_promisor_packs
+    }
 }
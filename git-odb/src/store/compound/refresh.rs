@@ -0,0 +1,100 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use git_pack::data;
+
+use crate::{pack, store::compound};
+
+/// Returned by [`compound::Store::refresh()`] and [`compound::Store::find_or_refresh()`]
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Find(#[from] compound::find::Error),
+    #[error(transparent)]
+    Pack(#[from] pack::bundle::Error),
+}
+
+/// Determines if and how often the `objects/pack` directory is rescanned for new packs if an object cannot be found,
+/// as packs may be added or replaced by other processes at any time.
+///
+/// Loose objects are always found as they are looked up on disk directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RefreshMode {
+    /// Never rescan, the packs seen during instantiation are all there is.
+    Never,
+    /// Rescan if an object isn't found, but not more often than once per `min_interval`.
+    OnMiss {
+        /// The minimal time between rescans, to avoid hitting the file system for each missing object.
+        min_interval: Duration,
+    },
+}
+
+impl Default for RefreshMode {
+    /// Rescan on miss at most once per second.
+    fn default() -> Self {
+        RefreshMode::OnMiss {
+            min_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Refreshing the view on disk
+impl compound::Store {
+    /// Rescan the `objects/pack` directory to add new packs and drop the ones that were deleted, and return true if the
+    /// set of packs changed. Packs that are still present are reused.
+    ///
+    /// If a new pack can't be opened, for example because it was deleted by a concurrent repack after the directory was
+    /// listed, the error is returned and the packs known so far remain untouched.
+    pub fn refresh(&mut self) -> Result<bool, Error> {
+        self.last_refresh = Instant::now();
+        let index_paths = compound::init::index_paths_by_size(&self.loose.path.join("pack"));
+        let mut new_bundles = HashMap::new();
+        for index_path in &index_paths {
+            if !self.bundles.iter().any(|bundle| bundle.index.path() == index_path) {
+                new_bundles.insert(index_path.to_owned(), pack::Bundle::at(index_path)?);
+            }
+        }
+
+        let has_new_bundles = !new_bundles.is_empty();
+        let mut existing: HashMap<_, _> = std::mem::take(&mut self.bundles)
+            .into_iter()
+            .map(|bundle| (bundle.index.path().to_owned(), bundle))
+            .collect();
+        self.bundles = index_paths
+            .into_iter()
+            .map(|index_path| {
+                existing
+                    .remove(&index_path)
+                    .or_else(|| new_bundles.remove(&index_path))
+                    .expect("each index path is either known or was opened")
+            })
+            .collect();
+        self.has_promisor_packs = compound::init::has_promisor_packs(&self.bundles);
+        Ok(has_new_bundles || !existing.is_empty())
+    }
+
+    /// Like [`find()`][compound::Store::find()], but if the object isn't found [refresh][compound::Store::refresh()] the
+    /// packs according to our [`refresh_mode`][compound::Store::refresh_mode] and try again.
+    pub fn find_or_refresh<'a>(
+        &mut self,
+        id: impl AsRef<git_hash::oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl pack::cache::DecodeEntry,
+    ) -> Result<Option<data::Object<'a>>, Error> {
+        let id = id.as_ref();
+        if !self.contains(id) && self.is_refresh_due() {
+            self.refresh()?;
+        }
+        self.find(id, buffer, pack_cache).map_err(Into::into)
+    }
+
+    pub(crate) fn is_refresh_due(&self) -> bool {
+        match self.refresh_mode {
+            RefreshMode::Never => false,
+            RefreshMode::OnMiss { min_interval } => self.last_refresh.elapsed() >= min_interval,
+        }
+    }
+}
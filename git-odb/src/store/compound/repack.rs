@@ -0,0 +1,398 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Seek, SeekFrom},
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::progress::{self, Progress};
+use git_hash::ObjectId;
+
+use crate::{
+    pack,
+    pack::data::output,
+    store::{compound, loose},
+};
+
+/// Returned by [`compound::Store::repack()`]
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    LooseIter(#[from] loose::iter::Error),
+    #[error("Could not read loose object {id}")]
+    LooseFind { source: loose::find::Error, id: ObjectId },
+    #[error("Loose object {id} disappeared while repacking")]
+    LooseNotFound { id: ObjectId },
+    #[error("Could not decode packed object {id}")]
+    PackDecode {
+        source: pack::data::decode_entry::Error,
+        id: ObjectId,
+    },
+    #[error(transparent)]
+    EntryCreate(#[from] output::entry::Error),
+    #[error("An IO error occurred while writing the new pack")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    BundleWrite(#[from] pack::bundle::write::Error),
+    #[error(transparent)]
+    BundleInit(#[from] pack::bundle::Error),
+    #[error("Could not delete '{path}' after repacking")]
+    Delete { source: std::io::Error, path: PathBuf },
+    #[error("Cannot write packs using {kind:?} hashes")]
+    UnsupportedHashKind { kind: git_hash::Kind },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// The extensions of files next to a pack which are deleted along with it, if present.
+const OPTIONAL_PACK_FILE_EXTENSIONS: &[&str] = &["bitmap", "promisor", "keep"];
+
+/// Configure the behaviour of [`compound::Store::repack()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If true, loose objects are added to the new pack and all loose objects contained in it are deleted afterwards.
+    pub include_loose_objects: bool,
+    /// The amount of threads to use when indexing the new pack. If `None`, all logical cores are used.
+    pub thread_limit: Option<usize>,
+    /// How to compress objects which can't be copied from an existing pack as is.
+    pub compression: git_features::zlib::Compression,
+}
+
+impl Default for Options {
+    /// Include loose objects and use all logical cores.
+    fn default() -> Self {
+        Options {
+            include_loose_objects: true,
+            thread_limit: None,
+            compression: Default::default(),
+        }
+    }
+}
+
+/// The outcome of [`compound::Store::repack()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Information about the newly written pack and its index.
+    pub bundle: pack::bundle::write::Outcome,
+    /// The amount of objects in the new pack.
+    pub num_objects: usize,
+    /// The amount of pack entries which were copied from an existing pack without decompressing them, including deltas.
+    pub copied_entries: usize,
+    /// The amount of delta entries which were copied as is as their base object was written before them.
+    pub reused_deltas: usize,
+    /// The paths to pack data and index files, along with their `.bitmap`, `.promisor` and `.keep` files, which were deleted
+    /// after the new pack was put in place.
+    pub removed_pack_files: Vec<PathBuf>,
+    /// The amount of loose objects which were deleted as they are contained in the new pack.
+    pub removed_loose_objects: usize,
+}
+
+enum Source {
+    Packed {
+        bundle: usize,
+        pack_offset: u64,
+        entry_end: u64,
+        /// The id of the base object if the entry is an offset delta.
+        ofs_delta_base: Option<ObjectId>,
+    },
+    Loose,
+}
+
+/// Maintenance
+impl compound::Store {
+    /// Write all objects in all packs and, if configured in `options`, all loose objects, into a single new pack with a freshly
+    /// generated index and replace our [bundles][compound::Store::bundles] with it. Afterwards, the original pack files and the
+    /// packed loose objects are deleted.
+    ///
+    /// Pack entries are copied without decompressing them, which includes deltas as long as their base object was written
+    /// to the new pack before them. This is always the case for offset deltas within the same source pack.
+    /// All other deltas are resolved and stored as base object, as are loose objects.
+    ///
+    /// The new pack is indexed like a received pack, which validates each object, and moved into place before any file is deleted.
+    /// Thus readers will always see all objects, even though they may temporarily see them twice.
+    ///
+    /// Like in git, packs with a `.keep` file are neither written into the new pack nor deleted. If any of the repacked packs
+    /// was received from a promisor remote, the new pack is marked as such with a `.promisor` file.
+    pub fn repack(
+        &mut self,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let pack_directory = self.loose.path.join("pack");
+        let hash_kind = self
+            .bundles
+            .first()
+            .map_or_else(git_hash::Kind::default, |bundle| bundle.index.hash_kind());
+        if hash_kind != git_hash::Kind::Sha1 {
+            return Err(Error::UnsupportedHashKind { kind: hash_kind });
+        }
+        let plan = self.repack_plan(options.include_loose_objects)?;
+
+        let mut copied_entries = 0;
+        let mut reused_deltas = 0;
+        let mut pack_file = tempfile::tempfile_in(&pack_directory)?;
+        {
+            let mut entries_progress = progress.add_child("creating entries");
+            entries_progress.init(Some(plan.len()), progress::count("entries"));
+            let mut written = HashMap::<ObjectId, (usize, git_object::Kind)>::with_capacity(plan.len());
+            let mut buf = Vec::new();
+            let mut plan_iter = plan.iter().enumerate();
+            let entries = std::iter::from_fn(|| {
+                let (index, (id, source)) = plan_iter.next()?;
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Some(Err(Error::Interrupted));
+                }
+                let (entry, copied) =
+                    match self.repack_entry(*id, source, index, &written, &mut buf, options.compression) {
+                        Ok(v) => v,
+                        Err(err) => return Some(Err(err)),
+                    };
+                if copied {
+                    copied_entries += 1;
+                    if let output::entry::Kind::DeltaRef { .. } = entry.kind {
+                        reused_deltas += 1;
+                    }
+                }
+                written.insert(*id, (index, entry.object_kind));
+                entries_progress.inc();
+                Some(Ok(vec![entry]))
+            });
+            let mut write_progress = progress.add_child("writing");
+            write_progress.init(None, progress::bytes());
+            for bytes_written in output::bytes::FromEntriesIter::new(
+                entries,
+                &mut pack_file,
+                plan.len() as u32,
+                pack::data::Version::V2,
+                hash_kind,
+            ) {
+                let bytes_written = bytes_written.map_err(|err| match err {
+                    output::bytes::Error::Io(err) => Error::Io(err),
+                    output::bytes::Error::Input(err) => err,
+                })?;
+                write_progress.inc_by(bytes_written as usize);
+            }
+        }
+
+        pack_file.seek(SeekFrom::Start(0))?;
+        let mut bundle = pack::Bundle::write_to_directory(
+            std::io::BufReader::new(pack_file),
+            Some(&pack_directory),
+            progress.add_child("indexing"),
+            should_interrupt,
+            pack::bundle::write::Options {
+                thread_limit: options.thread_limit,
+                compression: options.compression,
+                ..Default::default()
+            },
+        )?;
+        // Packs are only discovered with the `pack-` prefix git uses, move the data file first like git does.
+        for path in bundle.data_path.iter_mut().chain(bundle.index_path.iter_mut()) {
+            let prefixed = path.with_file_name(format!(
+                "pack-{}",
+                path.file_name().expect("written files have a name").to_string_lossy()
+            ));
+            std::fs::rename(&*path, &prefixed)?;
+            *path = prefixed;
+        }
+        let new_bundle = bundle
+            .to_bundle()
+            .expect("a directory was provided and thus an index was written")?;
+        let (kept_bundles, old_bundles): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.bundles).into_iter().partition(is_kept);
+        if old_bundles
+            .iter()
+            .any(|b| b.pack.path().with_extension("promisor").is_file())
+        {
+            std::fs::File::create(new_bundle.pack.path().with_extension("promisor"))?;
+        }
+        self.bundles = std::iter::once(new_bundle).chain(kept_bundles).collect();
+        self.has_promisor_packs = compound::init::has_promisor_packs(&self.bundles);
+
+        let old_paths: Vec<_> = old_bundles
+            .iter()
+            .filter(|b| bundle.data_path.as_deref() != Some(b.pack.path()))
+            .flat_map(|b| {
+                let pack_path = b.pack.path();
+                std::iter::once((b.index.path().to_owned(), false))
+                    .chain(std::iter::once((pack_path.to_owned(), false)))
+                    .chain(
+                        OPTIONAL_PACK_FILE_EXTENSIONS
+                            .iter()
+                            .map(move |extension| (pack_path.with_extension(extension), true)),
+                    )
+            })
+            .collect();
+        drop(old_bundles);
+
+        let mut removed_pack_files = Vec::with_capacity(old_paths.len());
+        for (path, is_optional) in old_paths {
+            match std::fs::remove_file(&path) {
+                Ok(()) => removed_pack_files.push(path),
+                Err(err) if is_optional && err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(Error::Delete { source: err, path }),
+            }
+        }
+        let mut removed_loose_objects = 0;
+        if options.include_loose_objects {
+            let loose_objects = self.loose.iter().collect::<Result<Vec<_>, _>>()?;
+            for id in loose_objects {
+                if self.bundles[0].index.lookup(id).is_none() {
+                    continue;
+                }
+                let path = loose::sha1_path(&id, self.loose.path.clone());
+                std::fs::remove_file(&path).map_err(|err| Error::Delete {
+                    source: err,
+                    path: path.clone(),
+                })?;
+                if let Some(fan_out_dir) = path.parent() {
+                    std::fs::remove_dir(fan_out_dir).ok();
+                }
+                removed_loose_objects += 1;
+            }
+        }
+
+        Ok(Outcome {
+            bundle,
+            num_objects: plan.len(),
+            copied_entries,
+            reused_deltas,
+            removed_pack_files,
+            removed_loose_objects,
+        })
+    }
+
+    /// Determine which objects to write in which order, with entries of each pack in pack order so that offset deltas
+    /// can see their base first.
+    fn repack_plan(&self, include_loose_objects: bool) -> Result<Vec<(ObjectId, Source)>, Error> {
+        let mut seen = HashSet::new();
+        let mut plan = Vec::new();
+        for (bundle_index, bundle) in self.bundles.iter().enumerate().filter(|(_, bundle)| !is_kept(bundle)) {
+            let mut entries: Vec<_> = bundle.index.iter().map(|e| (e.pack_offset, e.oid)).collect();
+            entries.sort_by_key(|e| e.0);
+            let entry_ends: Vec<_> = entries
+                .iter()
+                .skip(1)
+                .map(|e| e.0)
+                .chain(std::iter::once(bundle.pack.pack_end() as u64))
+                .collect();
+            for ((pack_offset, id), entry_end) in entries.iter().zip(entry_ends) {
+                if seen.insert(*id) {
+                    let entry = bundle.pack.entry(*pack_offset);
+                    let ofs_delta_base = match entry.header {
+                        pack::data::entry::Header::OfsDelta { base_distance } => {
+                            let base_offset = entry.base_pack_offset(base_distance);
+                            entries
+                                .binary_search_by_key(&base_offset, |e| e.0)
+                                .ok()
+                                .map(|base_index| entries[base_index].1)
+                        }
+                        _ => None,
+                    };
+                    plan.push((
+                        *id,
+                        Source::Packed {
+                            bundle: bundle_index,
+                            pack_offset: *pack_offset,
+                            entry_end,
+                            ofs_delta_base,
+                        },
+                    ));
+                }
+            }
+        }
+        if include_loose_objects {
+            for id in self.loose.iter() {
+                let id = id?;
+                if seen.insert(id) {
+                    plan.push((id, Source::Loose));
+                }
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Produce the entry at `index` of the new pack, returning it along with `true` if it was copied from a pack as is.
+    fn repack_entry(
+        &self,
+        id: ObjectId,
+        source: &Source,
+        index: usize,
+        written: &HashMap<ObjectId, (usize, git_object::Kind)>,
+        buf: &mut Vec<u8>,
+        compression: git_features::zlib::Compression,
+    ) -> Result<(output::Entry, bool), Error> {
+        let count = output::Count {
+            id,
+            entry_pack_location: None,
+        };
+        match *source {
+            Source::Packed {
+                bundle,
+                pack_offset,
+                entry_end,
+                ofs_delta_base,
+            } => {
+                let bundle = &self.bundles[bundle];
+                if bundle.pack.version() == pack::data::Version::V2 {
+                    let entry = bundle.pack.entry(pack_offset);
+                    let compressed_data = bundle
+                        .pack
+                        .entry_slice(entry.data_offset..entry_end)
+                        .expect("entries from the index are within the pack");
+                    use pack::data::entry::Header::*;
+                    let base = match entry.header {
+                        OfsDelta { .. } => ofs_delta_base.and_then(|base_id| written.get(&base_id)),
+                        RefDelta { base_id } => written.get(&base_id),
+                        _base_object => {
+                            return Ok((
+                                output::Entry {
+                                    id,
+                                    object_kind: entry.header.as_kind().expect("base objects have a kind"),
+                                    kind: output::entry::Kind::Base,
+                                    decompressed_size: entry.decompressed_size as usize,
+                                    compressed_data: compressed_data.to_owned(),
+                                },
+                                true,
+                            ))
+                        }
+                    };
+                    if let Some((base_index, base_kind)) = base {
+                        return Ok((
+                            output::Entry {
+                                id,
+                                object_kind: *base_kind,
+                                kind: output::entry::Kind::DeltaRef {
+                                    nth_before: index - base_index,
+                                },
+                                decompressed_size: entry.decompressed_size as usize,
+                                compressed_data: compressed_data.to_owned(),
+                            },
+                            true,
+                        ));
+                    }
+                }
+                let object = bundle
+                    .find(id, buf, &mut pack::cache::Never)
+                    .map_err(|err| Error::PackDecode { source: err, id })?
+                    .expect("objects listed in the index can be found");
+                Ok((output::Entry::from_data(&count, &object, compression)?, false))
+            }
+            Source::Loose => {
+                let object = self
+                    .loose
+                    .find(id, buf)
+                    .map_err(|err| Error::LooseFind { source: err, id })?
+                    .ok_or(Error::LooseNotFound { id })?;
+                Ok((output::Entry::from_data(&count, &object, compression)?, false))
+            }
+        }
+    }
+}
+
+/// Return true if the pack of `bundle` has a `.keep` file next to it, which excludes it from being repacked.
+fn is_kept(bundle: &pack::Bundle) -> bool {
+    bundle.pack.path().with_extension("keep").is_file()
+}
@@ -8,16 +8,86 @@ use crate::{
 };
 use git_pack::{data::Object, find::Entry};
 
+/// The maximum amount of replacements to follow for a single object, the same as in git.
+const MAX_REPLACEMENT_DEPTH: usize = 5;
+
 impl linked::Store {
+    /// Return the id of the object to use in place of `id` according to our [`replacements`][linked::Store::replacements],
+    /// which is `id` itself if it isn't replaced.
+    pub fn replacement_of<'a>(&'a self, id: &'a oid) -> Result<&'a oid, compound::find::Error> {
+        let mut current = id;
+        if self.replacements.is_empty() {
+            return Ok(current);
+        }
+        for _ in 0..MAX_REPLACEMENT_DEPTH {
+            match self.replacements.get(&current.to_owned()) {
+                Some(replacement) => current = replacement.as_ref(),
+                None => return Ok(current),
+            }
+        }
+        if self.replacements.contains_key(&current.to_owned()) {
+            Err(compound::find::Error::ReplacementDepthExceeded { id: id.to_owned() })
+        } else {
+            Ok(current)
+        }
+    }
+
     /// Return true if the given object `id` is contained in the store.
+    ///
+    /// This is as cheap as [`compound::Store::contains()`] for each of the linked databases.
     pub fn contains(&self, id: impl AsRef<oid>) -> bool {
         let id = id.as_ref();
+        self.dbs.iter().any(|db| db.contains(id))
+    }
+
+    /// Find an object as identified by [`ObjectId`][git_hash::ObjectId] and return a reader to obtain its data incrementally.
+    ///
+    /// See [`compound::Store::stream()`] for details. Objects missing in a [partial clone][linked::Store::is_promisor()]
+    /// cause an error as [`fetch_promised`][linked::Store::fetch_promised] is not called here.
+    pub fn stream<'a>(
+        &'a self,
+        id: impl AsRef<oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl pack::cache::DecodeEntry,
+    ) -> Result<Option<compound::find::Stream<'a>>, compound::find::Error> {
+        let id = self.replacement_of(id.as_ref())?;
         for db in self.dbs.iter() {
-            if db.internal_find_packed(id).is_some() || db.loose.contains(id) {
-                return true;
+            if db.contains(id) {
+                return db.stream(id, buffer, pack_cache);
             }
         }
-        false
+        if self.is_promisor() {
+            return Err(compound::find::Error::MissingPromisor { id: id.to_owned() });
+        }
+        Ok(None)
+    }
+
+    /// [Refresh][compound::Store::refresh()] the packs of all linked databases and return true if any of them changed.
+    pub fn refresh(&mut self) -> Result<bool, compound::refresh::Error> {
+        let mut changed = false;
+        for db in self.dbs.iter_mut() {
+            changed |= db.refresh()?;
+        }
+        Ok(changed)
+    }
+
+    /// Like [`find()`][crate::Find::find()], but if the object isn't found refresh the packs of all linked databases
+    /// according to their [`refresh_mode`][compound::Store::refresh_mode] and try again.
+    ///
+    /// See [`compound::Store::find_or_refresh()`] for details.
+    pub fn find_or_refresh<'a>(
+        &mut self,
+        id: impl AsRef<oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl pack::cache::DecodeEntry,
+    ) -> Result<Option<Object<'a>>, compound::refresh::Error> {
+        let id = id.as_ref();
+        if !self.contains(id) {
+            for db in self.dbs.iter_mut().filter(|db| db.is_refresh_due()) {
+                db.refresh()?;
+            }
+        }
+        crate::Find::find(self, id, buffer, pack_cache).map_err(Into::into)
     }
 }
 
@@ -30,7 +100,7 @@ impl crate::Find for linked::Store {
         buffer: &'a mut Vec<u8>,
         pack_cache: &mut impl pack::cache::DecodeEntry,
     ) -> Result<Option<Object<'a>>, Self::Error> {
-        let id = id.as_ref();
+        let id = self.replacement_of(id.as_ref())?;
         for db in self.dbs.iter() {
             match db.internal_find_packed(id) {
                 Some(compound::find::PackLocation {
@@ -49,7 +119,13 @@ impl crate::Find for linked::Store {
                 }
             }
         }
-        Ok(None)
+        if !self.is_promisor() || compound::find::is_empty_tree(id) {
+            return Ok(compound::find::empty_tree(id, buffer));
+        }
+        match self.fetch_promised.as_ref().and_then(|fetch| fetch(id, buffer)) {
+            Some(kind) => Ok(Some(Object::new(kind, buffer))),
+            None => Err(compound::find::Error::MissingPromisor { id: id.to_owned() }),
+        }
     }
 
     fn location_by_id(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<pack::bundle::Location> {
@@ -0,0 +1,78 @@
+use crate::{
+    pack,
+    store::{compound, linked},
+    Find,
+};
+use git_hash::oid;
+use git_pack::{data::Object, find::Entry};
+use std::{cell::RefCell, sync::Arc};
+
+/// A lightweight handle to a shared [`linked::Store`] for use by a single thread, owning a pack cache and a buffer for
+/// object data.
+///
+/// It implements [`Find`] using its own pack cache, hence callers don't have to keep one around. Create one
+/// per thread with [`linked::Store::to_handle()`] to access a store from multiple threads.
+pub struct Handle<Cache = pack::cache::Never> {
+    store: Arc<linked::Store>,
+    pack_cache: RefCell<Cache>,
+    buf: Vec<u8>,
+}
+
+/// Handle creation
+impl linked::Store {
+    /// Create a new [`Handle`] to this store which doesn't cache decoded pack entries.
+    pub fn to_handle(self: &Arc<Self>) -> Handle {
+        self.to_handle_with_cache(pack::cache::Never)
+    }
+
+    /// Create a new [`Handle`] to this store using `pack_cache` to speed up decoding pack entries, for example the one
+    /// created with [`new_pack_cache()`][linked::Store::new_pack_cache()].
+    pub fn to_handle_with_cache<Cache>(self: &Arc<Self>, pack_cache: Cache) -> Handle<Cache> {
+        Handle {
+            store: Arc::clone(self),
+            pack_cache: RefCell::new(pack_cache),
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl<Cache> Handle<Cache>
+where
+    Cache: pack::cache::DecodeEntry,
+{
+    /// The store this handle provides access to.
+    pub fn store(&self) -> &Arc<linked::Store> {
+        &self.store
+    }
+
+    /// Find the object with `id` and place its data into our own buffer, which is overwritten by the next call.
+    pub fn find_object(&mut self, id: impl AsRef<oid>) -> Result<Option<Object<'_>>, compound::find::Error> {
+        self.store.find(id, &mut self.buf, self.pack_cache.get_mut())
+    }
+}
+
+impl<Cache> Find for Handle<Cache>
+where
+    Cache: pack::cache::DecodeEntry,
+{
+    type Error = compound::find::Error;
+
+    /// Find an object like [`linked::Store`] does, but use our own pack cache instead of `_pack_cache`, which is ignored
+    /// and can be set to [`pack::cache::Never`].
+    fn find<'a>(
+        &self,
+        id: impl AsRef<oid>,
+        buffer: &'a mut Vec<u8>,
+        _pack_cache: &mut impl pack::cache::DecodeEntry,
+    ) -> Result<Option<Object<'a>>, Self::Error> {
+        self.store.find(id, buffer, &mut *self.pack_cache.borrow_mut())
+    }
+
+    fn location_by_id(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<pack::bundle::Location> {
+        self.store.location_by_id(id, buf)
+    }
+
+    fn entry_by_location(&self, location: &pack::bundle::Location) -> Option<Entry<'_>> {
+        self.store.entry_by_location(location)
+    }
+}
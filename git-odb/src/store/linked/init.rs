@@ -26,7 +26,12 @@ impl linked::Store {
             !dbs.is_empty(),
             "we can rely on at least one compound database to be present"
         );
-        Ok(linked::Store { dbs })
+        Ok(linked::Store {
+            dbs,
+            delta_base_cache_limit: linked::DEFAULT_DELTA_BASE_CACHE_LIMIT,
+            fetch_promised: None,
+            replacements: Default::default(),
+        })
     }
 }
 
@@ -0,0 +1,13 @@
+use crate::store::{compound::metrics, linked};
+
+impl linked::Store {
+    /// Compute the [metrics][metrics::Metrics] of all linked databases as configured by `options`, and
+    /// [merge][metrics::Metrics::merge()] them.
+    pub fn metrics(&self, options: metrics::Options) -> Result<metrics::Metrics, metrics::Error> {
+        let mut out = metrics::Metrics::default();
+        for db in &self.dbs {
+            out.merge(&db.metrics(options)?);
+        }
+        Ok(out)
+    }
+}
@@ -1,5 +1,17 @@
 //! An object database representing a list of [compound databases][compound::Store] commonly created using _git alternates_.
 use crate::store::compound;
+use git_hash::ObjectId;
+use std::collections::HashMap;
+
+/// The default for [`Store::delta_base_cache_limit`], matching the default of git's `core.deltaBaseCacheLimit`.
+pub const DEFAULT_DELTA_BASE_CACHE_LIMIT: usize = 96 * 1024 * 1024;
+
+/// A function to obtain an object missing in a [partial clone][Store::is_promisor()] on demand, typically by fetching it
+/// from the promisor remote.
+///
+/// It is called with the id of the missing object and a buffer to place its data in, returning the object's kind
+/// or `None` if it couldn't be obtained.
+pub type FetchPromisedFn = dyn Fn(&git_hash::oid, &mut Vec<u8>) -> Option<git_object::Kind> + Send + Sync;
 
 /// A database with a list of [compound databases][compound::Store] created by traversing git `alternates` files.
 ///
@@ -7,6 +19,49 @@ use crate::store::compound;
 pub struct Store {
     /// The compound databases containing the actual objects.
     pub dbs: Vec<compound::Store>,
+    /// The amount of memory in bytes pack caches created for this store may use for decoded delta bases,
+    /// see [`new_pack_cache()`][Store::new_pack_cache()].
+    ///
+    /// It corresponds to `core.deltaBaseCacheLimit` and defaults to [`DEFAULT_DELTA_BASE_CACHE_LIMIT`].
+    pub delta_base_cache_limit: usize,
+    /// If set, it is called to obtain objects which are missing in a [partial clone][Store::is_promisor()] when
+    /// [finding][crate::Find::find()] them, instead of failing with
+    /// [`MissingPromisor`][compound::find::Error::MissingPromisor].
+    pub fetch_promised: Option<Box<FetchPromisedFn>>,
+    /// A mapping of object ids to the ids of the objects to return instead when [finding][crate::Find::find()] or
+    /// [streaming][Store::stream()] them, as defined by the references in `refs/replace/` in git repositories.
+    ///
+    /// It is empty by default, thus no object is replaced unless the map is populated by the caller.
+    /// Replacements are followed transitively, just like in git.
+    pub replacements: HashMap<ObjectId, ObjectId>,
+}
+
+impl Store {
+    /// Return true if any of the linked databases [is a promisor][compound::Store::is_promisor()], making this
+    /// a partial clone.
+    pub fn is_promisor(&self) -> bool {
+        self.dbs.iter().any(|db| db.is_promisor())
+    }
+
+    /// Return true if the object with `id` isn't stored in this [partial clone][Store::is_promisor()], but is expected to
+    /// be available on the promisor remote.
+    ///
+    /// Use it to learn whether [`find()`][crate::Find::find()] would fail with
+    /// [`MissingPromisor`][compound::find::Error::MissingPromisor] without looking up the object.
+    pub fn is_promised(&self, id: impl AsRef<git_hash::oid>) -> bool {
+        let id = id.as_ref();
+        self.is_promisor() && !compound::find::is_empty_tree(id) && !self.contains(id)
+    }
+
+    /// Create a pack cache which holds at most [`delta_base_cache_limit`][Store::delta_base_cache_limit] bytes of
+    /// decoded objects, for use with [`find()`][crate::Find::find()] and friends.
+    ///
+    /// Its [memory usage][crate::pack::cache::lru::MemoryCappedHashmap::memory_usage_in_bytes()] can be queried at any time
+    /// to help tuning the limit for long-running processes.
+    #[cfg(feature = "pack-cache-lru-dynamic")]
+    pub fn new_pack_cache(&self) -> crate::pack::cache::lru::MemoryCappedHashmap {
+        crate::pack::cache::lru::MemoryCappedHashmap::new(self.delta_base_cache_limit)
+    }
 }
 
 ///
@@ -14,6 +69,14 @@ pub mod init;
 
 mod find;
 
+mod handle;
+pub use handle::Handle;
+
+mod metrics;
+
+///
+pub mod prefetch;
+
 ///
 mod write;
 
@@ -0,0 +1,117 @@
+use git_hash::ObjectId;
+
+use crate::{
+    pack,
+    store::{compound, linked},
+};
+
+/// The outcome of [`linked::Store::prefetch()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of objects decoded from packs.
+    pub packed_objects: usize,
+    /// The amount of loose objects decoded.
+    pub loose_objects: usize,
+    /// Objects which were requested but are not contained in any of the linked databases.
+    pub missing: Vec<ObjectId>,
+}
+
+enum Location {
+    Packed {
+        db: usize,
+        bundle: usize,
+        entry_index: u32,
+        pack_offset: u64,
+    },
+    Loose {
+        db: usize,
+    },
+}
+
+impl linked::Store {
+    /// Decode all objects identified by `ids` and put them into `object_cache`, using `pack_cache` to accelerate delta resolution.
+    ///
+    /// Objects are decoded in the order they are stored on disk, that is packed objects sorted by pack and offset and then
+    /// loose objects, which is considerably faster than looking them up in the order they are needed if many objects in the
+    /// same pack share delta bases. Use this ahead of pipelines that know their access set, like blame or archive generation,
+    /// and obtain objects with [`find_cached()`][crate::FindExt::find_cached()] using the same `object_cache` afterwards.
+    ///
+    /// Passing [`pack::cache::Never`] as `object_cache` merely warms the `pack_cache`. Ids which aren't found are
+    /// reported in [`Outcome::missing`], duplicates are decoded only once.
+    pub fn prefetch(
+        &self,
+        ids: impl IntoIterator<Item = impl Into<ObjectId>>,
+        pack_cache: &mut impl pack::cache::DecodeEntry,
+        object_cache: &mut impl pack::cache::Object,
+    ) -> Result<Outcome, compound::find::Error> {
+        let mut out = Outcome {
+            packed_objects: 0,
+            loose_objects: 0,
+            missing: Vec::new(),
+        };
+        let mut plan: Vec<_> = ids
+            .into_iter()
+            .map(Into::into)
+            .filter_map(|id| match self.location_for_prefetch(&id) {
+                Some(location) => Some((location, id)),
+                None => {
+                    out.missing.push(id);
+                    None
+                }
+            })
+            .collect();
+        plan.sort_by_key(|(location, id)| match *location {
+            Location::Packed {
+                db,
+                bundle,
+                pack_offset,
+                ..
+            } => (db, false, bundle, pack_offset, *id),
+            Location::Loose { db } => (db, true, 0, 0, *id),
+        });
+        plan.dedup_by_key(|(_, id)| *id);
+
+        let mut buf = Vec::new();
+        for (location, id) in plan {
+            match location {
+                Location::Packed {
+                    db,
+                    bundle,
+                    entry_index,
+                    ..
+                } => {
+                    let object =
+                        self.dbs[db].internal_get_packed_object_by_index(bundle, entry_index, &mut buf, pack_cache)?;
+                    object_cache.put(id, object.kind, object.data);
+                    out.packed_objects += 1;
+                }
+                Location::Loose { db } => match self.dbs[db].loose.find(id, &mut buf)? {
+                    Some(object) => {
+                        object_cache.put(id, object.kind, object.data);
+                        out.loose_objects += 1;
+                    }
+                    None => out.missing.push(id),
+                },
+            }
+        }
+        Ok(out)
+    }
+
+    fn location_for_prefetch(&self, id: &git_hash::oid) -> Option<Location> {
+        self.dbs
+            .iter()
+            .enumerate()
+            .find_map(|(db_index, db)| match db.internal_find_packed(id) {
+                Some(compound::find::PackLocation {
+                    bundle_index,
+                    entry_index,
+                }) => Some(Location::Packed {
+                    db: db_index,
+                    bundle: bundle_index,
+                    entry_index,
+                    pack_offset: db.bundles[bundle_index].index.pack_offset_at_index(entry_index),
+                }),
+                None => db.loose.contains(id).then(|| Location::Loose { db: db_index }),
+            })
+    }
+}
@@ -1,7 +1,12 @@
 use crate::store::loose::{sha1_path, Store, HEADER_READ_UNCOMPRESSED_BYTES};
 use git_features::zlib;
 use git_pack::{data, loose::object::header};
-use std::{convert::TryInto, fs, io::Read, path::PathBuf};
+use std::{
+    convert::TryInto,
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
 
 /// Returned by [`Store::find()`]
 #[derive(thiserror::Error, Debug)]
@@ -22,6 +27,21 @@ pub enum Error {
     },
 }
 
+/// A reader streaming the decompressed data of a loose object, as returned by [`Store::stream()`].
+pub struct Stream {
+    /// The kind of the object.
+    pub kind: git_object::Kind,
+    /// The size of the object's data in bytes, which is the amount of bytes that can be read from this stream.
+    pub size: u64,
+    inner: zlib::stream::inflate::ReadBoxed<io::BufReader<fs::File>>,
+}
+
+impl io::Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
 /// Object lookup
 impl Store {
     const OPEN_ACTION: &'static str = "open";
@@ -64,6 +84,49 @@ impl Store {
         }
     }
 
+    /// Return a reader over the object identified by the given [`ObjectId`][git_hash::ObjectId] if present in this database,
+    /// which decompresses the object's data as it is read.
+    ///
+    /// Use this instead of [`find()`][Store::find()] for objects that are too large to be held in memory at once, like huge blobs.
+    /// Returns `Ok<None>` if there was no such object.
+    pub fn stream(&self, id: impl AsRef<git_hash::oid>) -> Result<Option<Stream>, Error> {
+        let path = sha1_path(id.as_ref(), self.path.clone());
+        let file = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(Error::Io {
+                    source: err,
+                    action: Self::OPEN_ACTION,
+                    path,
+                })
+            }
+        };
+        let mut inner = zlib::stream::inflate::ReadBoxed {
+            inner: io::BufReader::new(file),
+            decompressor: Box::new(zlib::Decompress::new(true)),
+        };
+
+        // Headers are tiny, so reading byte by byte is fine and leaves the reader positioned at the first byte of data.
+        // Headers without null byte are rejected once they reach the maximum length, and truncated ones at the end of the file.
+        let mut header_buf = [0u8; header::MAX_LEN];
+        let mut header_len = 0;
+        let (kind, size) = loop {
+            let bytes_read = inner.read(&mut header_buf[header_len..][..1]).map_err(|e| Error::Io {
+                source: e,
+                action: "deflate",
+                path: path.to_owned(),
+            })?;
+            header_len += bytes_read;
+            match header::decode(&header_buf[..header_len]) {
+                Ok((kind, size, _header_size)) => break (kind, size),
+                Err(header::Error::Incomplete) if bytes_read != 0 => continue,
+                Err(err) => return Err(err.into()),
+            }
+        };
+        Ok(Some(Stream { kind, size, inner }))
+    }
+
     fn find_inner<'a>(&self, id: &git_hash::oid, buf: &'a mut Vec<u8>) -> Result<data::Object<'a>, Error> {
         let path = sha1_path(id, self.path.clone());
 
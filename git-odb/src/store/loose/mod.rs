@@ -6,6 +6,8 @@ use std::path::PathBuf;
 pub struct Store {
     /// The directory in which objects are stored, containing 256 folders representing the hashes first byte.
     pub path: PathBuf,
+    /// How to compress objects when writing them, defaulting to [`Compression::fast()`][git_features::zlib::Compression::fast()].
+    pub compression: git_features::zlib::Compression,
 }
 
 /// Initialization
@@ -17,20 +19,24 @@ impl Store {
     pub fn at(objects_directory: impl Into<PathBuf>) -> Store {
         Store {
             path: objects_directory.into(),
+            compression: Default::default(),
         }
     }
+
+    /// Compress newly written objects according to `compression`, to trade CPU time for the size of objects on disk.
+    pub fn with_compression(mut self, compression: git_features::zlib::Compression) -> Self {
+        self.compression = compression;
+        self
+    }
 }
 
+/// Returns the path to the object with `id` within the objects directory `root`, using the first byte of the hash
+/// as directory and the remaining bytes as file name, both hex-encoded, for hashes of any kind.
 pub(crate) fn sha1_path(id: &git_hash::oid, mut root: PathBuf) -> PathBuf {
-    match id.kind() {
-        git_hash::Kind::Sha1 => {
-            let hex = id.to_sha1_hex();
-            let buf = std::str::from_utf8(&hex).expect("ascii only in hex");
-            root.push(&buf[..2]);
-            root.push(&buf[2..]);
-            root
-        }
-    }
+    let hex = id.to_string();
+    root.push(&hex[..2]);
+    root.push(&hex[2..]);
+    root
 }
 
 ///
@@ -40,4 +46,8 @@ pub mod iter;
 #[doc(inline)]
 pub use iter::Iter;
 ///
+pub mod prune;
+///
+pub mod verify;
+///
 pub mod write;
@@ -0,0 +1,252 @@
+use crate::store::loose::{iter, sha1_path, Store};
+use git_hash::ObjectId;
+use git_object::{immutable, tree::EntryMode, Kind};
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+/// Returned by [`Store::prune_unreachable()`] and [`reachable()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error<E: std::error::Error + 'static> {
+    #[error("Could not find object {id}")]
+    Find { source: E, id: ObjectId },
+    #[error("Object {id} is reachable but does not exist in the database")]
+    NotFound { id: ObjectId },
+    #[error("{kind} object {id} could not be decoded")]
+    Decode {
+        source: immutable::object::decode::Error,
+        kind: Kind,
+        id: ObjectId,
+    },
+    #[error(transparent)]
+    Iter(#[from] iter::Error),
+    #[error("Could not {action} loose object at '{path}'")]
+    Io {
+        source: std::io::Error,
+        action: &'static str,
+        path: PathBuf,
+    },
+}
+
+/// Configure the behaviour of [`Store::prune_unreachable()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Unreachable objects modified more recently than this are kept, as they might be about to be referenced
+    /// by a concurrent operation.
+    pub grace_period: Duration,
+    /// If true, nothing will be deleted and the returned [`Outcome`] reports what would have been deleted.
+    pub dry_run: bool,
+}
+
+impl Default for Options {
+    /// A grace period of two weeks like git uses by default, without dry-run.
+    fn default() -> Self {
+        Options {
+            grace_period: Duration::from_secs(60 * 60 * 24 * 14),
+            dry_run: false,
+        }
+    }
+}
+
+/// The outcome of [`Store::prune_unreachable()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of objects reachable from the given roots, in all object stores.
+    pub reachable_objects: usize,
+    /// Unreachable loose objects older than the grace period which were deleted, or would have been deleted in dry-run mode.
+    pub pruned: Vec<ObjectId>,
+    /// The amount of bytes on disk used by all `pruned` objects.
+    pub pruned_bytes: u64,
+    /// Unreachable loose objects that were kept as they are younger than the grace period.
+    pub kept_recent: Vec<ObjectId>,
+}
+
+/// The reachability bitmaps of packs, which provide all objects reachable from selected commits without traversing them.
+#[derive(Default)]
+pub struct Bitmaps<'a> {
+    bitmaps: Vec<(&'a git_pack::index::File, git_pack::bitmap::File, Vec<u32>)>,
+}
+
+impl<'a> Bitmaps<'a> {
+    /// Load the bitmaps of all `bundles` which have a `.bitmap` file next to their index.
+    pub fn from_bundles(
+        bundles: impl IntoIterator<Item = &'a git_pack::Bundle>,
+    ) -> Result<Self, git_pack::bitmap::init::Error> {
+        let mut bitmaps = Vec::new();
+        for bundle in bundles {
+            let path = bundle.index.path().with_extension("bitmap");
+            if path.is_file() {
+                let bitmap = git_pack::bitmap::File::at(path, &bundle.index)?;
+                bitmaps.push((
+                    &bundle.index,
+                    bitmap,
+                    git_pack::bitmap::index_positions_in_pack_order(&bundle.index),
+                ));
+            }
+        }
+        Ok(Bitmaps { bitmaps })
+    }
+
+    /// Call `f` with all objects reachable from `id` and return true if one of our bitmaps covers it, or return false otherwise.
+    fn for_each_reachable(&self, id: &ObjectId, mut f: impl FnMut(ObjectId)) -> bool {
+        for (index, bitmap, pack_order) in &self.bitmaps {
+            if let Some(objects) = bitmap.lookup(index, id) {
+                objects.for_each_set_bit(|bit| {
+                    f(index.oid_at_index(pack_order[bit]).to_owned());
+                    Some(())
+                });
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Return the ids of all objects reachable from `roots` by following commits to their trees and parents, trees to their entries
+/// and tags to their targets, looking them up in `db`.
+///
+/// Commits covered by one of the `bitmaps` are not traversed, as the bitmap already provides all objects reachable from them.
+/// Blobs and submodule commits are not looked up, and objects referenced but missing in `db` are an error as the object database
+/// would be corrupted.
+pub fn reachable<Find>(
+    roots: impl IntoIterator<Item = impl Into<ObjectId>>,
+    bitmaps: &Bitmaps<'_>,
+    db: &Find,
+) -> Result<HashSet<ObjectId>, Error<Find::Error>>
+where
+    Find: crate::Find,
+{
+    let mut seen = HashSet::new();
+    let mut next: Vec<_> = roots
+        .into_iter()
+        .map(Into::into)
+        .filter(|id| seen.insert(*id))
+        .collect();
+    let mut buf = Vec::new();
+    while let Some(id) = next.pop() {
+        if bitmaps.for_each_reachable(&id, |id| {
+            seen.insert(id);
+        }) {
+            continue;
+        }
+        let object = db
+            .find(id, &mut buf, &mut git_pack::cache::Never)
+            .map_err(|err| Error::Find { source: err, id })?
+            .ok_or(Error::NotFound { id })?;
+        let decode_err = |source| Error::Decode {
+            source,
+            kind: object.kind,
+            id,
+        };
+        match object.kind {
+            Kind::Commit => {
+                for token in immutable::CommitIter::from_bytes(object.data) {
+                    match token.map_err(decode_err)? {
+                        immutable::commit::iter::Token::Tree { id } | immutable::commit::iter::Token::Parent { id } => {
+                            if seen.insert(id) {
+                                next.push(id);
+                            }
+                        }
+                        _a_token_past_the_parents => break,
+                    }
+                }
+            }
+            Kind::Tree => {
+                for entry in immutable::TreeIter::from_bytes(object.data) {
+                    let entry = entry.map_err(decode_err)?;
+                    let id = entry.oid.to_owned();
+                    match entry.mode {
+                        EntryMode::Commit => {}
+                        EntryMode::Tree => {
+                            if seen.insert(id) {
+                                next.push(id);
+                            }
+                        }
+                        EntryMode::Blob | EntryMode::BlobExecutable | EntryMode::Link => {
+                            seen.insert(id);
+                        }
+                    }
+                }
+            }
+            Kind::Tag => {
+                for token in immutable::TagIter::from_bytes(object.data) {
+                    if let Some(id) = token.map_err(decode_err)?.into_id() {
+                        if seen.insert(id) {
+                            next.push(id);
+                        }
+                        break;
+                    }
+                }
+            }
+            Kind::Blob => {}
+        }
+    }
+    Ok(seen)
+}
+
+/// Maintenance
+impl Store {
+    /// Delete all loose objects in this store which are not reachable from `roots` and which were last modified before the
+    /// [grace period][Options::grace_period], or merely report them if [`dry_run`][Options::dry_run] is set.
+    ///
+    /// `roots` are typically the objects pointed to by all references and reflog entries, and are traversed using `db`,
+    /// which should provide access to all objects including the ones in packs and alternates, with the help of `bitmaps`
+    /// if available, see [`reachable()`].
+    /// Empty fan-out directories are removed as well.
+    pub fn prune_unreachable<Find>(
+        &self,
+        roots: impl IntoIterator<Item = impl Into<ObjectId>>,
+        bitmaps: &Bitmaps<'_>,
+        db: &Find,
+        Options { grace_period, dry_run }: Options,
+    ) -> Result<Outcome, Error<Find::Error>>
+    where
+        Find: crate::Find,
+    {
+        let reachable = reachable(roots, bitmaps, db)?;
+        let now = SystemTime::now();
+        let mut out = Outcome {
+            reachable_objects: reachable.len(),
+            pruned: Vec::new(),
+            pruned_bytes: 0,
+            kept_recent: Vec::new(),
+        };
+        for id in self.iter() {
+            let id = id?;
+            if reachable.contains(&id) {
+                continue;
+            }
+            let path = sha1_path(&id, self.path.clone());
+            let metadata = std::fs::metadata(&path).map_err(|err| Error::Io {
+                source: err,
+                action: "stat",
+                path: path.clone(),
+            })?;
+            let is_recent = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .map_or(true, |age| age < grace_period);
+            if is_recent {
+                out.kept_recent.push(id);
+                continue;
+            }
+            if !dry_run {
+                std::fs::remove_file(&path).map_err(|err| Error::Io {
+                    source: err,
+                    action: "delete",
+                    path: path.clone(),
+                })?;
+                if let Some(fan_out_dir) = path.parent() {
+                    std::fs::remove_dir(fan_out_dir).ok();
+                }
+            }
+            out.pruned.push(id);
+            out.pruned_bytes += metadata.len();
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,138 @@
+use crate::store::loose::{find, iter, Store};
+use git_features::progress::Progress;
+use git_hash::ObjectId;
+use git_object::Kind;
+use git_pack::loose::object::header;
+use std::{
+    io::Read,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Returned by [`Store::verify()`] if verification couldn't be performed.
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Iter(#[from] iter::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// A problem with a single loose object as found by [`Store::verify()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Issue {
+    #[error("Loose object {id} could not be opened or its header could not be read")]
+    Read { id: ObjectId, source: find::Error },
+    #[error("Loose object {id} could not be decompressed")]
+    Decompress { id: ObjectId, source: std::io::Error },
+    #[error("Loose object {id} claims to have {expected} bytes, but has {actual}")]
+    SizeMismatch { id: ObjectId, expected: u64, actual: u64 },
+    #[error("Loose object {id} actually hashes to {actual}")]
+    HashMismatch { id: ObjectId, actual: ObjectId },
+    #[error("{kind} object {id} could not be decoded")]
+    Decode {
+        id: ObjectId,
+        kind: Kind,
+        source: git_object::immutable::object::decode::Error,
+    },
+}
+
+impl Issue {
+    /// The id of the object with the issue.
+    pub fn id(&self) -> &ObjectId {
+        match self {
+            Issue::Read { id, .. }
+            | Issue::Decompress { id, .. }
+            | Issue::SizeMismatch { id, .. }
+            | Issue::HashMismatch { id, .. }
+            | Issue::Decode { id, .. } => id,
+        }
+    }
+}
+
+/// The outcome of [`Store::verify()`].
+#[derive(Debug)]
+pub struct Outcome {
+    /// The amount of loose objects that were checked.
+    pub num_objects: usize,
+    /// All problems found, at most one per object.
+    pub issues: Vec<Issue>,
+}
+
+/// Integrity checks
+impl Store {
+    /// Check all loose objects for integrity by decompressing them, validating their header and size, hashing their content to
+    /// compare it to their id and decoding commits, trees and tags.
+    ///
+    /// Problems with individual objects are collected as [issues][Issue] in the returned [`Outcome`], which is valid if there are
+    /// none, whereas failing to list the objects or being interrupted through `should_interrupt` is an error.
+    /// Objects removed while the verification is ongoing are skipped.
+    ///
+    /// Note that only objects with SHA1 ids are hashed.
+    pub fn verify(&self, mut progress: impl Progress, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        progress.init(None, git_features::progress::count("objects"));
+        let mut out = Outcome {
+            num_objects: 0,
+            issues: Vec::new(),
+        };
+        let mut buf = Vec::new();
+        for id in self.iter() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let id = id?;
+            match self.verify_object(id, &mut buf) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(issue) => out.issues.push(issue),
+            }
+            out.num_objects += 1;
+            progress.inc();
+        }
+        Ok(out)
+    }
+
+    /// Returns `Ok(false)` if the object doesn't exist (anymore).
+    fn verify_object(&self, id: ObjectId, buf: &mut Vec<u8>) -> Result<bool, Issue> {
+        let mut stream = match self.stream(id).map_err(|source| Issue::Read { id, source })? {
+            Some(stream) => stream,
+            None => return Ok(false),
+        };
+        buf.clear();
+        stream
+            .read_to_end(buf)
+            .map_err(|source| Issue::Decompress { id, source })?;
+        if buf.len() as u64 != stream.size {
+            return Err(Issue::SizeMismatch {
+                id,
+                expected: stream.size,
+                actual: buf.len() as u64,
+            });
+        }
+
+        if id.kind() == git_hash::Kind::Sha1 {
+            let mut header_buf = [0u8; 64];
+            let header_len =
+                header::encode(stream.kind, stream.size, &mut header_buf[..]).expect("header fits into buffer");
+            let mut hasher = git_features::hash::Sha1::default();
+            hasher.update(&header_buf[..header_len]);
+            hasher.update(buf);
+            let actual = ObjectId::new_sha1(hasher.digest());
+            if actual != id {
+                return Err(Issue::HashMismatch { id, actual });
+            }
+        }
+
+        if stream.kind != Kind::Blob {
+            git_pack::data::Object::new(stream.kind, buf)
+                .decode()
+                .map_err(|source| Issue::Decode {
+                    id,
+                    kind: stream.kind,
+                    source,
+                })?;
+        }
+        Ok(true)
+    }
+}
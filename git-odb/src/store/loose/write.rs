@@ -21,6 +21,8 @@ pub enum Error {
         source: tempfile::PersistError,
         target: PathBuf,
     },
+    #[error("Objects with {kind:?} hashes cannot be written yet")]
+    UnsupportedHashKind { kind: git_hash::Kind },
 }
 
 impl crate::write::Write for Store {
@@ -46,6 +48,7 @@ impl crate::write::Write for Store {
                 to.flush()?;
                 self.finalize_object(to)
             }
+            git_hash::Kind::Sha256 => Err(Error::UnsupportedHashKind { kind: hash }),
         }
     }
 
@@ -70,6 +73,7 @@ impl crate::write::Write for Store {
                 to.flush()?;
                 self.finalize_object(to)
             }
+            git_hash::Kind::Sha256 => Err(Error::UnsupportedHashKind { kind: hash }),
         }
     }
 }
@@ -84,13 +88,17 @@ impl Store {
         hash: git_hash::Kind,
     ) -> Result<hash::Write<CompressedTempfile>, Error> {
         let mut to = hash::Write::new(
-            deflate::Write::new(NamedTempFile::new_in(&self.path).map_err(|err| Error::Io {
-                source: err,
-                message: "create named temp file in",
-                path: self.path.to_owned(),
-            })?),
+            deflate::Write::with_compression(
+                NamedTempFile::new_in(&self.path).map_err(|err| Error::Io {
+                    source: err,
+                    message: "create named temp file in",
+                    path: self.path.to_owned(),
+                })?,
+                self.compression,
+            ),
             hash,
-        );
+        )
+        .map_err(|_| Error::UnsupportedHashKind { kind: hash })?;
 
         git_pack::loose::object::header::encode(kind, size, &mut to).map_err(|err| Error::Io {
             source: err,
@@ -0,0 +1,170 @@
+//! An object database keeping all objects in memory, optionally on top of another database to read objects from.
+use crate::{linked, pack};
+use git_hash::{oid, ObjectId};
+use git_pack::{data::Object, find::Entry};
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    io::{self, Read},
+    sync::RwLock,
+};
+
+/// An object database storing written objects in memory, which is useful in tests or to stage objects before deciding
+/// whether to persist them, for example when performing a dry-run.
+///
+/// If created [with a base][Store::with_base()], objects not written to memory are looked up in the base database, which
+/// is never written to. That way, a [`linked::Store`] of a repository can be used as if it was writable without changing it.
+pub struct Store<T = linked::Store> {
+    objects: RwLock<HashMap<ObjectId, (git_object::Kind, Vec<u8>)>>,
+    base: Option<T>,
+}
+
+/// Initialization
+impl Store {
+    /// Create a new empty database without base.
+    pub fn new() -> Self {
+        Store {
+            objects: Default::default(),
+            base: None,
+        }
+    }
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Store::new()
+    }
+}
+
+impl<T> Store<T> {
+    /// Create a new empty database which looks up objects it doesn't have in memory in `base`.
+    pub fn with_base(base: T) -> Self {
+        Store {
+            objects: Default::default(),
+            base: Some(base),
+        }
+    }
+
+    /// The database objects are looked up in if they aren't in memory, if set.
+    pub fn base(&self) -> Option<&T> {
+        self.base.as_ref()
+    }
+
+    /// Return true if the object with `id` was written to memory, without consulting the base.
+    pub fn contains(&self, id: impl AsRef<oid>) -> bool {
+        self.objects
+            .read()
+            .expect("no panic while holding the lock")
+            .contains_key(&id.as_ref().to_owned())
+    }
+
+    /// The amount of objects written to memory.
+    pub fn len(&self) -> usize {
+        self.objects.read().expect("no panic while holding the lock").len()
+    }
+
+    /// Return true if no object was written to memory yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the ids of all objects written to memory, in no particular order.
+    pub fn ids(&self) -> Vec<ObjectId> {
+        self.objects
+            .read()
+            .expect("no panic while holding the lock")
+            .keys()
+            .cloned()
+            .collect()
+    }
+}
+
+impl<T> crate::Find for Store<T>
+where
+    T: crate::Find,
+{
+    type Error = T::Error;
+
+    /// Find objects in memory first, and in the base database otherwise.
+    fn find<'a>(
+        &self,
+        id: impl AsRef<oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl pack::cache::DecodeEntry,
+    ) -> Result<Option<Object<'a>>, Self::Error> {
+        let id = id.as_ref();
+        let kind = match self
+            .objects
+            .read()
+            .expect("no panic while holding the lock")
+            .get(&id.to_owned())
+        {
+            Some((kind, data)) => {
+                buffer.clear();
+                buffer.extend_from_slice(data);
+                Some(*kind)
+            }
+            None => None,
+        };
+        match (kind, self.base.as_ref()) {
+            (Some(kind), _) => Ok(Some(Object::new(kind, buffer))),
+            (None, Some(base)) => base.find(id, buffer, pack_cache),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn location_by_id(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<pack::bundle::Location> {
+        let id = id.as_ref();
+        if self.contains(id) {
+            return None;
+        }
+        self.base.as_ref().and_then(|base| base.location_by_id(id, buf))
+    }
+
+    fn entry_by_location(&self, location: &pack::bundle::Location) -> Option<Entry<'_>> {
+        self.base.as_ref().and_then(|base| base.entry_by_location(location))
+    }
+}
+
+impl<T> crate::write::Write for Store<T> {
+    type Error = io::Error;
+
+    /// Write the object to memory, even if the base database contains it already.
+    fn write_stream(
+        &self,
+        kind: git_object::Kind,
+        size: u64,
+        from: impl io::Read,
+        hash: git_hash::Kind,
+    ) -> Result<ObjectId, Self::Error> {
+        match hash {
+            git_hash::Kind::Sha1 => {
+                let mut data = Vec::with_capacity(size.try_into().expect("object size to fit into usize"));
+                from.take(size).read_to_end(&mut data)?;
+                if data.len() as u64 != size {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        format!("Expected {} bytes of object data, but got only {}", size, data.len()),
+                    ));
+                }
+
+                let mut header_buf = [0u8; 64];
+                let header_len = git_pack::loose::object::header::encode(kind, size, &mut header_buf[..])?;
+                let mut hasher = git_features::hash::Sha1::default();
+                hasher.update(&header_buf[..header_len]);
+                hasher.update(&data);
+                let id = ObjectId::new_sha1(hasher.digest());
+
+                self.objects
+                    .write()
+                    .expect("no panic while holding the lock")
+                    .insert(id, (kind, data));
+                Ok(id)
+            }
+            git_hash::Kind::Sha256 => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Sha256 hashes cannot be computed yet",
+            )),
+        }
+    }
+}
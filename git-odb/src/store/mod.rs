@@ -3,6 +3,7 @@ pub use sink::{sink, Sink};
 pub mod compound;
 pub mod linked;
 pub mod loose;
+pub mod memory;
 
 ///
 pub mod sink;
@@ -1,4 +1,4 @@
-use git_features::zlib::stream::deflate;
+use git_features::zlib::{stream::deflate, Compression};
 use std::{
     cell::RefCell,
     convert::TryInto,
@@ -15,12 +15,17 @@ pub struct Sink {
 
 impl Sink {
     /// Enable or disable compression. Compression is disabled by default
-    pub fn compress(mut self, enable: bool) -> Self {
+    pub fn compress(self, enable: bool) -> Self {
         if enable {
-            self.compressor = Some(RefCell::new(deflate::Write::new(io::sink())));
+            self.compression(Compression::default())
         } else {
-            self.compressor = None;
+            Sink { compressor: None }
         }
+    }
+
+    /// Enable compression according to `compression`, to simulate the CPU time spent on writing objects with the given settings.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compressor = Some(RefCell::new(deflate::Write::with_compression(io::sink(), compression)));
         self
     }
 }
@@ -72,6 +77,10 @@ impl crate::write::Write for Sink {
 
                 Ok(hasher.digest().into())
             }
+            git_hash::Kind::Sha256 => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Sha256 hashes cannot be computed yet",
+            )),
         }
     }
 }
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config commit.gpgsign false
+
+echo first > file
+git add file
+git commit -q -m c1
+echo second > file
+git commit -q -am c2
+
+git repack -adbq
+git rev-list --objects --all | wc -l | tr -d ' ' > num-reachable-objects
+git rev-parse HEAD > head-id
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q --object-format=sha256
+git config commit.gpgsign false
+
+git checkout -q -b main
+echo first > packed
+git add packed
+git commit -q -m "packed commit"
+git repack -adq
+
+echo second > loose
+git add loose
+git commit -q -m "loose commit"
@@ -0,0 +1,27 @@
+use git_odb::{
+    async_io::{Find, Unblock},
+    linked::Store,
+};
+use git_testtools::{fixture_path, hex_to_id};
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn loose_and_packed_objects_can_be_found_without_blocking() -> Result {
+    let db = Unblock::from(Store::at(fixture_path("objects"))?);
+    futures_lite::future::block_on(async {
+        let loose_id = hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+        let loose = db.find(loose_id).await?.expect("object exists");
+        assert_eq!(loose.pack_location, None);
+        assert_eq!(db.location_by_id(loose_id).await, None);
+
+        let packed_id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let packed = db.find(packed_id).await?.expect("object exists");
+        assert!(packed.pack_location.is_some());
+        assert_eq!(db.location_by_id(packed_id).await, packed.pack_location);
+        assert_eq!(packed.to_borrowed().decode()?.kind(), packed.kind);
+
+        assert_eq!(db.find(git_hash::ObjectId::null_sha1()).await?, None);
+        Ok::<_, Box<dyn std::error::Error>>(())
+    })
+}
@@ -73,3 +73,59 @@ fn no_alternate_in_first_objects_dir() -> crate::Result {
     assert!(alternate::resolve(tmp.path())?.is_empty());
     Ok(())
 }
+
+#[test]
+fn relative_paths_are_resolved_relative_to_the_objects_directory_listing_them() -> crate::Result {
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    let (from, b) = alternate(tmp.path().join("a"), tmp.path().join("nested").join("b"))?;
+    fs::create_dir_all(b.join("info"))?;
+    fs::create_dir_all(tmp.path().join("nested").join("c"))?;
+    fs::write(b.join("info").join("alternates"), b"../c\n")?;
+
+    let alternates = alternate::resolve(from)?;
+    assert_eq!(alternates.len(), 2);
+    assert_eq!(alternates[0], b);
+    assert_eq!(alternates[1], b.join("..").join("c"));
+    Ok(())
+}
+
+#[test]
+fn alternates_nested_too_deeply_are_ignored() -> crate::Result {
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    let dirs: Vec<_> = (0..=alternate::MAX_DEPTH + 1)
+        .map(|level| tmp.path().join(level.to_string()))
+        .collect();
+    for pair in dirs.windows(2) {
+        alternate(&pair[0], &pair[1])?;
+    }
+
+    let outcome = alternate::resolve_with_outcome(&dirs[0])?;
+    assert_eq!(outcome.alternates, dirs[1..=alternate::MAX_DEPTH]);
+    assert_eq!(
+        outcome.nested_too_deep,
+        vec![dirs[alternate::MAX_DEPTH].clone()],
+        "git warns about these and stops following them"
+    );
+    assert_eq!(alternate::resolve(&dirs[0])?, outcome.alternates);
+    Ok(())
+}
+
+#[test]
+fn alternates_reachable_through_multiple_paths_are_listed_once() -> crate::Result {
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    let (from, b) = alternate(tmp.path().join("a"), tmp.path().join("b"))?;
+    let (c, d) = alternate(tmp.path().join("c"), tmp.path().join("d"))?;
+    alternate(&b, &d)?;
+    fs::write(
+        from.join("info").join("alternates"),
+        format!("{}\n{}", b.display(), c.display()),
+    )?;
+
+    let alternates = alternate::resolve(from)?;
+    assert_eq!(
+        alternates,
+        vec![b, d, c],
+        "d is not a cycle even though it is reachable from b and c"
+    );
+    Ok(())
+}
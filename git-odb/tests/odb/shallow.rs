@@ -0,0 +1,35 @@
+use crate::hex_to_id;
+use git_odb::shallow::Boundary;
+
+#[test]
+fn commits_are_sorted_and_deduplicated() -> crate::Result {
+    let boundary = Boundary::from_bytes(
+        b"b2b2fb904774db46047fb514c4fddf0de30ad792\n\n17d78c64cef6c33a10a604573fd2c429e477fd63\r\nb2b2fb904774db46047fb514c4fddf0de30ad792",
+    )?;
+    assert_eq!(
+        boundary.commits(),
+        &[
+            hex_to_id("17d78c64cef6c33a10a604573fd2c429e477fd63"),
+            hex_to_id("b2b2fb904774db46047fb514c4fddf0de30ad792")
+        ][..]
+    );
+    assert!(boundary.contains(hex_to_id("b2b2fb904774db46047fb514c4fddf0de30ad792")));
+    assert!(!boundary.contains(hex_to_id("a6e0fc49db020c2c4a400bef7560e92b19c625e1")));
+    Ok(())
+}
+
+#[test]
+fn invalid_lines_are_rejected() {
+    let err = Boundary::from_bytes(b"17d78c64cef6c33a10a604573fd2c429e477fd63\nnot-a-hash\n").expect_err("invalid");
+    assert_eq!(
+        err.to_string(),
+        "Line 2 of the shallow file is not a hex encoded object id: 'not-a-hash'"
+    );
+}
+
+#[test]
+fn repositories_without_shallow_file_have_an_empty_boundary() -> crate::Result {
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    assert!(Boundary::from_git_dir(tmp.path())?.is_empty());
+    Ok(())
+}
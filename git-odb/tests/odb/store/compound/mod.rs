@@ -41,3 +41,304 @@ mod locate {
         can_locate(&db(), "dd25c539efbb0ab018caa4cda2d133285634e9b5"); // pack c043
     }
 }
+
+mod contains {
+    use crate::{hex_to_id, odb::store::compound::db};
+
+    #[test]
+    fn loose_and_packed_objects_and_missing_ones() {
+        let db = db();
+        assert!(
+            db.contains(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980")),
+            "loose"
+        );
+        assert!(
+            db.contains(hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0")),
+            "packed"
+        );
+        assert!(!db.contains(git_hash::ObjectId::null_sha1()), "missing");
+    }
+}
+
+mod metrics {
+    use crate::odb::store::compound::db;
+    use git_odb::compound::metrics::{LooseObjects, Metrics, Options};
+
+    #[test]
+    fn packs_loose_objects_and_delta_chains() -> crate::Result {
+        assert_eq!(
+            db().metrics(Options::default())?,
+            Metrics {
+                num_packs: 3,
+                num_packed_objects: 139,
+                packs_size_in_bytes: 104720,
+                num_loose_objects: 7,
+                num_deltas: 27,
+                longest_delta_chain: 6,
+            },
+            "the same as reported by `git verify-pack -v`"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn loose_object_estimates_and_no_delta_chains() -> crate::Result {
+        let metrics = db().metrics(Options {
+            loose_objects: LooseObjects::Estimate,
+            delta_chains: false,
+        })?;
+        assert_eq!(
+            metrics.num_loose_objects, 0,
+            "there is no loose object in the sampled fan-out directory"
+        );
+        assert_eq!(metrics.num_deltas, 0);
+        assert_eq!(metrics.longest_delta_chain, 0);
+        assert_eq!(metrics.num_packed_objects, 139);
+        Ok(())
+    }
+}
+
+mod stream {
+    use crate::{hex_to_id, odb::store::compound::db};
+    use std::io::Read;
+
+    #[test]
+    fn loose_packed_and_deltified_objects_stream_the_same_data_as_find() -> crate::Result {
+        let db = db();
+        for hex_id in &[
+            "37d4e6c5c48ba0d245164c4e10d5f41140cab980",
+            "501b297447a8255d3533c6858bb692575cdefaa0",
+            "4dac9989f96bc5b5b1263b582c08f0c5f0b58542",
+            "dd25c539efbb0ab018caa4cda2d133285634e9b5",
+        ] {
+            let id = hex_to_id(hex_id);
+            let mut buf = Vec::new();
+            let expected = db
+                .find(id, &mut buf, &mut git_pack::cache::Never)?
+                .expect("object exists");
+
+            let mut stream_buf = Vec::new();
+            let mut stream = db
+                .stream(id, &mut stream_buf, &mut git_pack::cache::Never)?
+                .expect("object exists");
+            assert_eq!(stream.kind, expected.kind);
+            assert_eq!(stream.size, expected.data.len() as u64);
+            let mut actual = Vec::new();
+            stream.read_to_end(&mut actual)?;
+            assert_eq!(actual.as_slice(), expected.data, "{}", hex_id);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn missing_objects_yield_none() -> crate::Result {
+        let mut buf = Vec::new();
+        assert!(db()
+            .stream(git_hash::ObjectId::null_sha1(), &mut buf, &mut git_pack::cache::Never)?
+            .is_none());
+        Ok(())
+    }
+}
+
+mod repack {
+    use crate::fixture_path;
+    use git_odb::compound::Store;
+    use std::{collections::BTreeMap, path::Path, sync::atomic::AtomicBool};
+
+    fn copy_recursively(from: &Path, to: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                copy_recursively(&entry.path(), &to.join(entry.file_name()))?;
+            } else {
+                std::fs::copy(entry.path(), to.join(entry.file_name()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn all_objects(db: &Store) -> crate::Result<BTreeMap<git_hash::ObjectId, (git_object::Kind, Vec<u8>)>> {
+        let mut out = BTreeMap::new();
+        let ids = db
+            .bundles
+            .iter()
+            .flat_map(|b| b.index.iter().map(|e| e.oid))
+            .chain(db.loose.iter().map(Result::unwrap))
+            .collect::<Vec<_>>();
+        let mut buf = Vec::new();
+        for id in ids {
+            let object = db
+                .find(id, &mut buf, &mut git_pack::cache::Never)?
+                .expect("object exists");
+            out.insert(id, (object.kind, object.data.to_owned()));
+        }
+        Ok(out)
+    }
+
+    #[test]
+    fn packs_and_loose_objects_are_consolidated_into_a_single_pack() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        copy_recursively(&fixture_path("objects"), dir.path())?;
+        let mut db = Store::at(dir.path())?;
+        let expected = all_objects(&db)?;
+        let num_loose_objects = db.loose.iter().count();
+
+        let outcome = db.repack(
+            git_features::progress::Discard,
+            &AtomicBool::new(false),
+            Default::default(),
+        )?;
+        assert_eq!(outcome.num_objects, expected.len());
+        assert_eq!(
+            outcome.removed_pack_files.len(),
+            3 * 2,
+            "data and index file of each original pack"
+        );
+        assert_eq!(outcome.removed_loose_objects, num_loose_objects);
+        assert!(outcome.copied_entries > 0, "pack entries are copied as is");
+        assert!(outcome.reused_deltas > 0, "offset deltas see their base first");
+
+        assert_eq!(db.bundles.len(), 1);
+        assert_eq!(db.loose.iter().count(), 0, "all loose objects were packed");
+        let reopened = Store::at(dir.path())?;
+        assert_eq!(reopened.bundles.len(), 1, "the original packs were deleted");
+        assert_eq!(all_objects(&db)?, expected);
+        assert_eq!(all_objects(&reopened)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn kept_packs_are_left_alone_and_promisor_packs_mark_the_new_pack() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        copy_recursively(&fixture_path("objects"), dir.path())?;
+        let pack_dir = dir.path().join("pack");
+        let kept = pack_dir.join("pack-11fdfa9e156ab73caae3b6da867192221f2089c2");
+        let promisor = pack_dir.join("pack-a2bf8e71d8c18879e499335762dd95119d93d9f1");
+        std::fs::write(kept.with_extension("keep"), b"")?;
+        std::fs::write(promisor.with_extension("promisor"), b"")?;
+        std::fs::write(promisor.with_extension("bitmap"), b"")?;
+        let mut db = Store::at(dir.path())?;
+        let expected = all_objects(&db)?;
+
+        let outcome = db.repack(
+            git_features::progress::Discard,
+            &AtomicBool::new(false),
+            Default::default(),
+        )?;
+        assert_eq!(
+            outcome.removed_pack_files.len(),
+            2 * 2 + 2,
+            "data and index file of each repacked pack, along with the bitmap and promisor files"
+        );
+        for extension in &["pack", "idx", "keep"] {
+            assert!(kept.with_extension(extension).is_file(), "kept packs remain in place");
+        }
+        for extension in &["pack", "idx", "bitmap", "promisor"] {
+            assert!(
+                !promisor.with_extension(extension).exists(),
+                "all files of repacked packs are removed"
+            );
+        }
+
+        let reopened = Store::at(dir.path())?;
+        assert_eq!(reopened.bundles.len(), 2, "the kept pack and the new one");
+        assert!(
+            reopened.is_promisor(),
+            "the new pack contains objects of a promisor pack"
+        );
+        assert_eq!(db.bundles.len(), 2);
+        assert!(db.is_promisor());
+        assert_eq!(all_objects(&db)?, expected);
+        assert_eq!(all_objects(&reopened)?, expected);
+        Ok(())
+    }
+}
+
+mod refresh {
+    use crate::{fixture_path, hex_to_id};
+    use git_odb::compound::{RefreshMode, Store};
+    use std::time::Duration;
+
+    const PACK: &str = "pack-11fdfa9e156ab73caae3b6da867192221f2089c2";
+
+    fn store_without_packs() -> crate::Result<(tempfile::TempDir, Store)> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("pack"))?;
+        let db = Store::at(dir.path())?;
+        assert!(db.bundles.is_empty());
+        Ok((dir, db))
+    }
+
+    fn add_pack(dir: &tempfile::TempDir) -> crate::Result {
+        for extension in &["pack", "idx"] {
+            let file_name = format!("{}.{}", PACK, extension);
+            std::fs::copy(
+                fixture_path("objects").join("pack").join(&file_name),
+                dir.path().join("pack").join(file_name),
+            )?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn packs_added_later_are_found_after_a_refresh_on_miss() -> crate::Result {
+        let (dir, mut db) = store_without_packs()?;
+        db.refresh_mode = RefreshMode::OnMiss {
+            min_interval: Duration::default(),
+        };
+        add_pack(&dir)?;
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let mut buf = Vec::new();
+        assert!(
+            db.find(id, &mut buf, &mut git_pack::cache::Never)?.is_none(),
+            "the store doesn't see the new pack yet"
+        );
+        assert!(db.find_or_refresh(id, &mut buf, &mut git_pack::cache::Never)?.is_some());
+        assert_eq!(db.bundles.len(), 1);
+        assert!(!db.refresh()?, "nothing changed");
+
+        for extension in &["pack", "idx"] {
+            std::fs::remove_file(dir.path().join("pack").join(format!("{}.{}", PACK, extension)))?;
+        }
+        assert!(db.refresh()?, "deleted packs are dropped");
+        assert!(db.bundles.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn packs_remain_usable_if_new_packs_fail_to_open() -> crate::Result {
+        let (dir, mut db) = store_without_packs()?;
+        add_pack(&dir)?;
+        assert!(db.refresh()?);
+
+        std::fs::write(dir.path().join("pack").join("pack-corrupt.idx"), b"not an index")?;
+        assert!(db.refresh().is_err(), "the new pack can't be opened");
+        assert_eq!(db.bundles.len(), 1, "the packs opened before are kept");
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        assert!(db.find(id, &mut Vec::new(), &mut git_pack::cache::Never)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn refreshes_are_rate_limited_or_can_be_disabled() -> crate::Result {
+        let (dir, mut db) = store_without_packs()?;
+        add_pack(&dir)?;
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let mut buf = Vec::new();
+        for mode in &[
+            RefreshMode::Never,
+            RefreshMode::OnMiss {
+                min_interval: Duration::from_secs(60 * 60),
+            },
+        ] {
+            db.refresh_mode = *mode;
+            assert!(
+                db.find_or_refresh(id, &mut buf, &mut git_pack::cache::Never)?.is_none(),
+                "{:?}",
+                mode
+            );
+        }
+        Ok(())
+    }
+}
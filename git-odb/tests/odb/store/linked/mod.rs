@@ -57,6 +57,186 @@ mod locate {
     }
 }
 
+mod handle {
+    use crate::{hex_to_id, odb::store::linked::db};
+    use git_odb::{pack, FindExt};
+    use std::sync::Arc;
+
+    #[test]
+    fn handles_find_objects_in_other_threads_without_passing_a_cache() -> crate::Result {
+        let db = Arc::new(db());
+        let ids = [
+            "37d4e6c5c48ba0d245164c4e10d5f41140cab980", // loose
+            "501b297447a8255d3533c6858bb692575cdefaa0", // pack 11fd
+            "4dac9989f96bc5b5b1263b582c08f0c5f0b58542", // pack a2bf
+            "dd25c539efbb0ab018caa4cda2d133285634e9b5", // pack c043
+        ];
+        let threads: Vec<_> = ids
+            .iter()
+            .map(|hex| {
+                let mut handle = db.to_handle_with_cache(pack::cache::Never);
+                let id = hex_to_id(hex);
+                std::thread::spawn(move || {
+                    let size_from_buffer = handle.find_object(id)?.expect("object exists").data.len();
+                    let mut buf = Vec::new();
+                    let size = handle.find_existing(id, &mut buf, &mut pack::cache::Never)?.data.len();
+                    assert_eq!(size_from_buffer, size, "both ways of finding objects are equivalent");
+                    Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread
+                .join()
+                .expect("no panic")
+                .map_err(|err| -> Box<dyn std::error::Error> { err })?;
+        }
+        assert_eq!(Arc::strong_count(&db), 1, "handles share the store");
+        Ok(())
+    }
+
+    #[test]
+    fn missing_objects_are_not_found() -> crate::Result {
+        let db = Arc::new(db());
+        let mut handle = db.to_handle();
+        assert!(handle
+            .find_object(hex_to_id("0000000000000000000000000000000000000001"))?
+            .is_none());
+        assert!(Arc::ptr_eq(handle.store(), &db));
+        Ok(())
+    }
+}
+
+mod find_cached {
+    use crate::{hex_to_id, odb::store::linked::db};
+    use git_odb::{pack, FindExt};
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    pub struct ObjectCache {
+        pub objects: HashMap<git_hash::ObjectId, (git_object::Kind, Vec<u8>)>,
+        pub hits: usize,
+    }
+
+    impl pack::cache::Object for ObjectCache {
+        fn put(&mut self, id: git_hash::ObjectId, kind: git_object::Kind, data: &[u8]) {
+            self.objects.insert(id, (kind, data.to_owned()));
+        }
+
+        fn get(&mut self, id: &git_hash::ObjectId, out: &mut Vec<u8>) -> Option<git_object::Kind> {
+            let hits = &mut self.hits;
+            self.objects.get(id).map(|(kind, data)| {
+                *hits += 1;
+                out.clear();
+                out.extend_from_slice(data);
+                *kind
+            })
+        }
+    }
+
+    #[test]
+    fn objects_are_served_from_the_object_cache_once_found() -> crate::Result {
+        let db = db();
+        let mut cache = ObjectCache::default();
+        for hex_id in &[
+            "37d4e6c5c48ba0d245164c4e10d5f41140cab980",
+            "501b297447a8255d3533c6858bb692575cdefaa0",
+            "4dac9989f96bc5b5b1263b582c08f0c5f0b58542",
+        ] {
+            let id = hex_to_id(hex_id);
+            let mut buf = Vec::new();
+            let expected = db
+                .find_cached(id, &mut buf, &mut pack::cache::Never, &mut cache)?
+                .expect("object exists");
+            assert_eq!(cache.hits, 0, "the first lookup goes to the database");
+
+            let mut cached_buf = Vec::new();
+            let actual = db
+                .find_cached(id, &mut cached_buf, &mut pack::cache::Never, &mut cache)?
+                .expect("object is cached");
+            assert_eq!(cache.hits, 1);
+            assert_eq!(actual.kind, expected.kind);
+            assert_eq!(actual.data, expected.data);
+            cache.hits = 0;
+        }
+        assert!(db
+            .find_cached(
+                git_hash::ObjectId::null_sha1(),
+                &mut Vec::new(),
+                &mut pack::cache::Never,
+                &mut cache
+            )?
+            .is_none());
+        assert_eq!(cache.objects.len(), 3, "missing objects are not cached");
+        Ok(())
+    }
+}
+
+mod prefetch {
+    use crate::{hex_to_id, odb::store::linked::db};
+    use git_odb::{pack, Find, FindExt};
+
+    #[test]
+    fn objects_are_decoded_into_the_object_cache_and_missing_ones_are_reported() -> crate::Result {
+        let db = db();
+        let ids = [
+            hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"), // loose
+            hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"), // pack 11fd
+            hex_to_id("4dac9989f96bc5b5b1263b582c08f0c5f0b58542"), // pack a2bf
+            hex_to_id("dd25c539efbb0ab018caa4cda2d133285634e9b5"), // pack c043
+            hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"),
+            git_hash::ObjectId::null_sha1(),
+        ];
+        let mut cache = super::find_cached::ObjectCache::default();
+        let outcome = db.prefetch(ids.iter().copied(), &mut pack::cache::Never, &mut cache)?;
+        assert_eq!(outcome.packed_objects, 3, "duplicates are decoded only once");
+        assert_eq!(outcome.loose_objects, 1);
+        assert_eq!(outcome.missing, vec![git_hash::ObjectId::null_sha1()]);
+        assert_eq!(cache.objects.len(), 4);
+
+        for id in &ids[..4] {
+            let mut buf = Vec::new();
+            let expected = db.find(id, &mut buf, &mut pack::cache::Never)?.expect("object exists");
+            let (expected_kind, expected_data) = (expected.kind, expected.data.to_owned());
+            let actual = db
+                .find_cached(id, &mut buf, &mut pack::cache::Never, &mut cache)?
+                .expect("object is cached");
+            assert_eq!(actual.kind, expected_kind);
+            assert_eq!(actual.data, expected_data);
+        }
+        assert_eq!(cache.hits, 4, "all objects are served from the cache after prefetching");
+        Ok(())
+    }
+}
+
+mod empty_tree {
+    use crate::odb::store::linked::db;
+    use git_hash::ObjectId;
+    use git_odb::{pack, Find};
+
+    #[test]
+    fn is_always_present_even_if_not_stored() -> crate::Result {
+        let db = db();
+        let empty_tree = ObjectId::empty_tree(git_hash::Kind::Sha1);
+        assert!(db.contains(empty_tree));
+
+        let mut buf = b"garbage".to_vec();
+        let object = db
+            .find(empty_tree, &mut buf, &mut pack::cache::Never)?
+            .expect("present");
+        assert_eq!(object.kind, git_object::Kind::Tree);
+        assert!(object.data.is_empty());
+
+        let empty_blob = ObjectId::empty_blob(git_hash::Kind::Sha1);
+        assert!(
+            !db.contains(empty_blob),
+            "only the empty tree is special, just like in git"
+        );
+        assert!(db.find(empty_blob, &mut buf, &mut pack::cache::Never)?.is_none());
+        Ok(())
+    }
+}
+
 mod init {
     use git_odb::linked;
     use std::convert::TryFrom;
@@ -91,3 +271,152 @@ mod init {
         assert_eq!(db.dbs[0].bundles.len(), 3)
     }
 }
+
+mod sha256 {
+    use crate::{hex_to_id, scripted_fixture_repo_read_only};
+    use git_odb::{linked, pack, Find};
+
+    #[test]
+    fn loose_and_packed_objects_can_be_iterated_and_found() -> crate::Result {
+        let repo = scripted_fixture_repo_read_only("make_sha256_repo.sh")?;
+        let db = linked::Store::at(repo.join(".git").join("objects"))?;
+        let bundle = &db.dbs[0].bundles[0];
+        assert_eq!(bundle.index.hash_kind(), git_hash::Kind::Sha256);
+        assert_eq!(bundle.pack.hash_kind(), git_hash::Kind::Sha256);
+        assert_eq!(bundle.index.num_objects(), 3);
+
+        let mut loose_ids = db.dbs[0].loose.iter().collect::<Result<Vec<_>, _>>()?;
+        loose_ids.sort();
+        assert_eq!(
+            loose_ids,
+            vec![
+                hex_to_id("822c7586d6ea37218a2362a932ad944c9511e6764e5d0df9b68a0e691e6f66cf"),
+                hex_to_id("862968c242c953d4ef84eb964c1228d6514cf81fa1533ef6dee8ed9c88884d8b"),
+                hex_to_id("8e57afe4b9ab5713ce94fb5f1aa4ee7e2922b2b9ec5ee51a661b6af0bf8312cf"),
+            ]
+        );
+
+        let mut buf = Vec::new();
+        for (hex_id, kind, data_suffix) in &[
+            (
+                "862968c242c953d4ef84eb964c1228d6514cf81fa1533ef6dee8ed9c88884d8b",
+                git_object::Kind::Commit,
+                &b"loose commit\n"[..],
+            ),
+            (
+                "8e57afe4b9ab5713ce94fb5f1aa4ee7e2922b2b9ec5ee51a661b6af0bf8312cf",
+                git_object::Kind::Blob,
+                &b"second\n"[..],
+            ),
+            (
+                "dafa1aa2c841882f7441d7aa5be0081303d33b43e310e4d4aeacd324d4e11f50",
+                git_object::Kind::Commit,
+                &b"packed commit\n"[..],
+            ),
+            (
+                "b072c39d9f76ea50f0983c5c3b3f13d63fa11f1410a97765b5c55fec4330e47a",
+                git_object::Kind::Blob,
+                &b"first\n"[..],
+            ),
+        ] {
+            let id = hex_to_id(hex_id);
+            assert_eq!(id.kind(), git_hash::Kind::Sha256);
+            let object = db.find(id, &mut buf, &mut pack::cache::Never)?.expect("object exists");
+            assert_eq!(object.kind, *kind);
+            assert!(object.data.ends_with(data_suffix), "{} has unexpected content", hex_id);
+        }
+        Ok(())
+    }
+}
+
+mod promisor {
+    use crate::{fixture_path, hex_to_id};
+    use git_odb::{compound, linked::Store, pack, Find};
+
+    const PACK: &str = "pack-11fdfa9e156ab73caae3b6da867192221f2089c2";
+
+    fn partial_clone() -> crate::Result<(tempfile::TempDir, Store)> {
+        let dir = tempfile::tempdir()?;
+        let pack_dir = dir.path().join("pack");
+        std::fs::create_dir(&pack_dir)?;
+        for extension in &["pack", "idx"] {
+            let file_name = format!("{}.{}", PACK, extension);
+            std::fs::copy(
+                fixture_path("objects").join("pack").join(&file_name),
+                pack_dir.join(file_name),
+            )?;
+        }
+        assert!(
+            !Store::at(dir.path())?.is_promisor(),
+            "packs are only promisor packs if marked as such"
+        );
+        std::fs::write(pack_dir.join(format!("{}.promisor", PACK)), b"")?;
+        let db = Store::at(dir.path())?;
+        assert!(db.is_promisor());
+        Ok((dir, db))
+    }
+
+    #[test]
+    fn missing_objects_are_reported_as_promised() -> crate::Result {
+        let (_dir, db) = partial_clone()?;
+        let mut buf = Vec::new();
+        assert!(db
+            .find(
+                hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"),
+                &mut buf,
+                &mut pack::cache::Never
+            )?
+            .is_some());
+        assert!(
+            db.find(
+                git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1),
+                &mut buf,
+                &mut pack::cache::Never
+            )?
+            .is_some(),
+            "the empty tree is never missing"
+        );
+
+        assert!(!db.is_promised(hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0")));
+        assert!(!db.is_promised(git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1)));
+
+        let missing = hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+        assert!(db.is_promised(missing));
+        for err in [
+            db.find(missing, &mut buf, &mut pack::cache::Never).map(|_| ()),
+            db.stream(missing, &mut buf, &mut pack::cache::Never).map(|_| ()),
+        ] {
+            assert!(matches!(
+                err,
+                Err(compound::find::Error::MissingPromisor { id }) if id == missing
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn missing_objects_can_be_fetched_on_demand() -> crate::Result {
+        let (_dir, mut db) = partial_clone()?;
+        let missing = hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+        db.fetch_promised = Some(Box::new(move |id, buf| {
+            (id == missing).then(|| {
+                buf.clear();
+                buf.extend_from_slice(b"fetched");
+                git_object::Kind::Blob
+            })
+        }));
+
+        let mut buf = Vec::new();
+        let object = db.find(missing, &mut buf, &mut pack::cache::Never)?.expect("fetched");
+        assert_eq!(object.kind, git_object::Kind::Blob);
+        assert_eq!(object.data, b"fetched");
+        assert!(
+            matches!(
+                db.find(git_hash::ObjectId::null_sha1(), &mut buf, &mut pack::cache::Never),
+                Err(compound::find::Error::MissingPromisor { .. })
+            ),
+            "objects the hook can't obtain are still missing"
+        );
+        Ok(())
+    }
+}
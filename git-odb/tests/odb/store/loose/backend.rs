@@ -53,6 +53,26 @@ mod write {
         }
         Ok(())
     }
+
+    #[test]
+    fn write_with_configured_compression() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path()).with_compression(git_features::zlib::Compression::none());
+        let mut buf = Vec::new();
+        let mut buf2 = Vec::new();
+
+        let oid = object_ids()[5];
+        let obj = locate_oid(oid, &mut buf);
+        let actual = db.write_buf(obj.kind, obj.data, git_hash::Kind::Sha1)?;
+        assert_eq!(actual, oid);
+        assert_eq!(db.find(oid, &mut buf2)?.expect("id present").data, obj.data);
+        let size_on_disk = std::fs::metadata(dir.path().join(&oid.to_string()[..2]).join(&oid.to_string()[2..]))?.len();
+        assert!(
+            size_on_disk > obj.data.len() as u64,
+            "uncompressed objects are stored as is, with header and zlib framing"
+        );
+        Ok(())
+    }
 }
 
 mod locate {
@@ -80,7 +100,7 @@ mod locate {
             target: b"ffa700b4aca13b80cb6b98a078e7c96804f8e0ec".as_bstr(),
             name: b"1.0.0".as_bstr(),
             target_kind: Kind::Commit,
-            message: b"for the signature".as_bstr(),
+            message: Some(b"for the signature".as_bstr()),
             pgp_signature: Some(
                 b"-----BEGIN PGP SIGNATURE-----
 Comment: GPGTools - https://gpgtools.org
@@ -168,6 +188,32 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
         Ok(())
     }
 
+    #[test]
+    fn blob_big_streamed() -> Result<(), Box<dyn std::error::Error>> {
+        let id = hex_to_id("a706d7cd20fc8ce71489f34b50cf01011c104193");
+        let mut stream = ldb().stream(id)?.expect("object exists");
+        assert_eq!(stream.kind, Kind::Blob);
+        let mut streamed = Vec::new();
+        std::io::Read::read_to_end(&mut stream, &mut streamed)?;
+        assert_eq!(streamed.len() as u64, stream.size);
+
+        let mut buf = Vec::new();
+        assert_eq!(
+            streamed,
+            locate_oid(id, &mut buf).data,
+            "streamed data is the same as the one read at once"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stream_not_existing() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(ldb()
+            .stream(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab989"))?
+            .is_none());
+        Ok(())
+    }
+
     fn try_locate<'a>(hex: &str, buf: &'a mut Vec<u8>) -> Option<git_pack::data::Object<'a>> {
         ldb().find(hex_to_id(hex), buf).ok().flatten()
     }
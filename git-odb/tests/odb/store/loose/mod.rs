@@ -14,3 +14,165 @@ fn signature(time: u32) -> git_actor::immutable::Signature<'static> {
         },
     }
 }
+
+mod prune {
+    use git_object::{mutable, tree::EntryMode, Kind};
+    use git_odb::{loose, Write};
+    use std::time::Duration;
+
+    type Fixture = (tempfile::TempDir, loose::Store, git_hash::ObjectId, git_hash::ObjectId);
+
+    fn store_with_objects() -> crate::Result<Fixture> {
+        let dir = tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path());
+        let reachable_blob = db.write_buf(Kind::Blob, b"reachable", git_hash::Kind::Sha1)?;
+        let unreachable_blob = db.write_buf(Kind::Blob, b"unreachable", git_hash::Kind::Sha1)?;
+        let tree = db.write(
+            &mutable::Tree {
+                entries: vec![mutable::tree::Entry {
+                    mode: EntryMode::Blob,
+                    filename: "file".into(),
+                    oid: reachable_blob,
+                }],
+            }
+            .into(),
+            git_hash::Kind::Sha1,
+        )?;
+        Ok((dir, db, tree, unreachable_blob))
+    }
+
+    #[test]
+    fn dry_run_reports_unreachable_objects_without_deleting_them() -> crate::Result {
+        let (dir, db, tree, unreachable_blob) = store_with_objects()?;
+        let odb = git_odb::linked::Store::at(dir.path())?;
+        let outcome = db.prune_unreachable(
+            Some(tree),
+            &Default::default(),
+            &odb,
+            loose::prune::Options {
+                grace_period: Duration::default(),
+                dry_run: true,
+            },
+        )?;
+        assert_eq!(outcome.reachable_objects, 2, "the tree and its blob");
+        assert_eq!(outcome.pruned, vec![unreachable_blob]);
+        assert!(outcome.pruned_bytes > 0);
+        assert!(db.contains(unreachable_blob), "nothing is deleted in dry-run mode");
+        Ok(())
+    }
+
+    #[test]
+    fn unreachable_objects_are_deleted_once_the_grace_period_is_over() -> crate::Result {
+        let (dir, db, tree, unreachable_blob) = store_with_objects()?;
+        let odb = git_odb::linked::Store::at(dir.path())?;
+        let outcome = db.prune_unreachable(Some(tree), &Default::default(), &odb, loose::prune::Options::default())?;
+        assert!(outcome.pruned.is_empty());
+        assert_eq!(
+            outcome.kept_recent,
+            vec![unreachable_blob],
+            "recently written objects are protected by the grace period"
+        );
+        assert!(db.contains(unreachable_blob));
+
+        let outcome = db.prune_unreachable(
+            Some(tree),
+            &Default::default(),
+            &odb,
+            loose::prune::Options {
+                grace_period: Duration::default(),
+                dry_run: false,
+            },
+        )?;
+        assert_eq!(outcome.pruned, vec![unreachable_blob]);
+        assert!(!db.contains(unreachable_blob));
+        assert!(db.contains(tree), "reachable objects are kept");
+        Ok(())
+    }
+
+    #[test]
+    fn commits_covered_by_bitmaps_are_not_traversed() -> crate::Result {
+        let repo = git_testtools::scripted_fixture_repo_read_only("make_repo_with_bitmap.sh")?;
+        let num_reachable_objects: usize = std::fs::read_to_string(repo.join("num-reachable-objects"))?
+            .trim()
+            .parse()?;
+        let odb = git_odb::linked::Store::at(repo.join(".git").join("objects"))?;
+        let head = git_hash::ObjectId::from_hex(std::fs::read_to_string(repo.join("head-id"))?.trim().as_bytes())?;
+        let bitmaps = loose::prune::Bitmaps::from_bundles(odb.dbs.iter().flat_map(|db| db.bundles.iter()))?;
+
+        let empty_dir = tempfile::tempdir()?;
+        let empty_odb = git_odb::linked::Store::at(empty_dir.path())?;
+        let reachable = loose::prune::reachable(Some(head), &bitmaps, &empty_odb)?;
+        assert_eq!(
+            reachable.len(),
+            num_reachable_objects,
+            "all objects are known from the bitmap without looking up a single one"
+        );
+        assert!(
+            matches!(
+                loose::prune::reachable(Some(head), &Default::default(), &empty_odb),
+                Err(loose::prune::Error::NotFound { id }) if id == head
+            ),
+            "without bitmaps, objects have to be looked up"
+        );
+        Ok(())
+    }
+}
+
+mod verify {
+    use git_object::Kind;
+    use git_odb::{loose, Write};
+    use std::sync::atomic::AtomicBool;
+
+    fn object_path(db: &loose::Store, id: git_hash::ObjectId) -> std::path::PathBuf {
+        let hex = id.to_string();
+        db.path.join(&hex[..2]).join(&hex[2..])
+    }
+
+    #[test]
+    fn fixture_objects_are_valid() -> crate::Result {
+        let db = loose::Store::at(crate::fixture_path("objects"));
+        let outcome = db.verify(git_features::progress::Discard, &AtomicBool::new(false))?;
+        assert_eq!(outcome.num_objects, 7);
+        assert!(outcome.issues.is_empty(), "{:?}", outcome.issues);
+        Ok(())
+    }
+
+    #[test]
+    fn structural_errors_and_corrupt_objects_are_reported_as_issues() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path());
+        let valid = db.write_buf(Kind::Blob, b"valid", git_hash::Kind::Sha1)?;
+        let invalid_commit = db.write_buf(Kind::Commit, b"not a commit", git_hash::Kind::Sha1)?;
+        let corrupt = db.write_buf(Kind::Blob, b"corrupt", git_hash::Kind::Sha1)?;
+        std::fs::remove_file(object_path(&db, corrupt))?;
+        std::fs::copy(object_path(&db, valid), object_path(&db, corrupt))?;
+
+        let outcome = db.verify(git_features::progress::Discard, &AtomicBool::new(false))?;
+        assert_eq!(outcome.num_objects, 3);
+        assert_eq!(outcome.issues.len(), 2);
+        for issue in &outcome.issues {
+            match issue {
+                loose::verify::Issue::Decode { id, kind, .. } => {
+                    assert_eq!(*id, invalid_commit);
+                    assert_eq!(*kind, Kind::Commit);
+                }
+                loose::verify::Issue::HashMismatch { id, actual } => {
+                    assert_eq!(*id, corrupt);
+                    assert_eq!(*actual, valid, "the content is the one of the valid object");
+                }
+                unexpected => panic!("unexpected issue: {:?}", unexpected),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn interruptions_are_errors() -> crate::Result {
+        let db = loose::Store::at(crate::fixture_path("objects"));
+        assert!(matches!(
+            db.verify(git_features::progress::Discard, &AtomicBool::new(true)),
+            Err(loose::verify::Error::Interrupted)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    fixture_path,
+    store::loose::backend::{locate_oid, object_ids},
+};
+use git_odb::{memory, pack, Find, Write};
+
+#[test]
+fn write_and_find() -> crate::Result {
+    let db = memory::Store::new();
+    assert!(db.is_empty());
+    let mut buf = Vec::new();
+    let mut buf2 = Vec::new();
+    for oid in object_ids() {
+        let obj = locate_oid(oid, &mut buf);
+        let actual = db.write(&obj.decode()?.into(), git_hash::Kind::Sha1)?;
+        assert_eq!(actual, oid);
+        assert!(db.contains(oid));
+        let found = db.find(oid, &mut buf2, &mut pack::cache::Never)?.expect("id present");
+        assert_eq!(found.kind, obj.kind);
+        assert_eq!(found.data, obj.data);
+        assert_eq!(found.pack_location, None);
+    }
+    assert_eq!(db.len(), object_ids().len());
+    assert!(db
+        .find(git_hash::ObjectId::null_sha1(), &mut buf2, &mut pack::cache::Never)?
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn writes_go_to_memory_while_the_base_is_only_read() -> crate::Result {
+    let base = git_odb::linked::Store::at(fixture_path("objects"))?;
+    let db = memory::Store::with_base(base);
+    let mut buf = Vec::new();
+
+    let existing = object_ids()[0];
+    assert!(db.find(existing, &mut buf, &mut pack::cache::Never)?.is_some());
+    assert!(!db.contains(existing), "objects from the base aren't in memory");
+
+    let new_id = db.write_buf(git_object::Kind::Blob, b"only in memory", git_hash::Kind::Sha1)?;
+    assert!(db.contains(new_id));
+    assert_eq!(
+        db.find(new_id, &mut buf, &mut pack::cache::Never)?
+            .expect("present in memory")
+            .data,
+        b"only in memory"
+    );
+    assert!(
+        !db.base().expect("base is set").contains(new_id),
+        "the base database is never written to"
+    );
+    Ok(())
+}
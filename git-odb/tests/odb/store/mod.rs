@@ -1,4 +1,5 @@
 pub mod compound;
 pub mod linked;
 pub mod loose;
+pub mod memory;
 pub mod sink;
@@ -0,0 +1,239 @@
+//! Bitmaps compressed with the _Enhanced Word-Aligned Hybrid_ scheme as used by git's reachability bitmaps.
+//!
+//! Each bit corresponds to an object in a pack, ordered by its position in the pack data file.
+use byteorder::{BigEndian, ByteOrder};
+
+///
+pub mod decode {
+    /// Returned by [`decode()`][super::decode()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+    }
+}
+
+const WORD_BITS: usize = 64;
+const MAX_RUNNING_LEN: u64 = (1 << 32) - 1;
+const MAX_LITERAL_WORDS: u64 = (1 << 31) - 1;
+
+/// A compressed bitmap, which can be [decompressed][Vec::decompress()] or queried for its set bits directly.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Vec {
+    num_bits: usize,
+    /// Run-length words followed by the amount of literal words they announce.
+    words: std::vec::Vec<u64>,
+}
+
+/// Decode a bitmap from `data` and return it along with the remaining bytes.
+pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
+    let corrupt = |message| decode::Error::Corrupt { message };
+    if data.len() < 8 {
+        return Err(corrupt("Bitmap header is truncated"));
+    }
+    let num_bits = BigEndian::read_u32(&data[..4]) as usize;
+    let num_words = BigEndian::read_u32(&data[4..8]) as usize;
+    let data = &data[8..];
+    let words_len = num_words
+        .checked_mul(8)
+        .filter(|len| data.len() >= len + 4)
+        .ok_or_else(|| corrupt("Bitmap words are truncated"))?;
+    let (words_data, data) = data.split_at(words_len);
+    let _last_run_length_word_position = BigEndian::read_u32(&data[..4]);
+
+    let max_uncompressed_words = ((num_bits + WORD_BITS - 1) / WORD_BITS) as u64;
+    let (mut pos, mut uncompressed_words) = (0, 0);
+    while pos < num_words {
+        let rlw = BigEndian::read_u64(&words_data[pos * 8..]);
+        uncompressed_words += running_len(rlw) + literal_words(rlw);
+        if uncompressed_words > max_uncompressed_words {
+            return Err(corrupt("The bitmap words describe more bits than the bitmap has"));
+        }
+        pos += 1 + literal_words(rlw) as usize;
+    }
+    if pos != num_words {
+        return Err(corrupt("A run-length word announces more literal words than there are"));
+    }
+    let words = words_data.chunks(8).map(BigEndian::read_u64).collect();
+    Ok((Vec { num_bits, words }, &data[4..]))
+}
+
+fn running_bit(rlw: u64) -> bool {
+    rlw & 1 == 1
+}
+
+fn running_len(rlw: u64) -> u64 {
+    (rlw >> 1) & MAX_RUNNING_LEN
+}
+
+fn literal_words(rlw: u64) -> u64 {
+    rlw >> 33
+}
+
+impl Vec {
+    /// Create a compressed bitmap from uncompressed `words` with `num_bits` bits, with bit `n` being bit `n % 64` of word `n / 64`.
+    ///
+    /// Bits past `num_bits` are cleared, and missing words are assumed to be zero.
+    pub fn from_words(words: &[u64], num_bits: usize) -> Self {
+        let num_words = (num_bits + WORD_BITS - 1) / WORD_BITS;
+        let word_at = |index: usize| {
+            let word = words.get(index).copied().unwrap_or(0);
+            let bits_in_word = num_bits - index * WORD_BITS;
+            if bits_in_word < WORD_BITS {
+                word & ((1 << bits_in_word) - 1)
+            } else {
+                word
+            }
+        };
+        let mut out = std::vec::Vec::new();
+        let mut index = 0;
+        while index < num_words {
+            let first = word_at(index);
+            let (mut running_len, running_bit) = (0, first == u64::MAX);
+            if first == 0 || first == u64::MAX {
+                while index < num_words && word_at(index) == first && running_len < MAX_RUNNING_LEN {
+                    running_len += 1;
+                    index += 1;
+                }
+            }
+            let rlw_position = out.len();
+            out.push(0);
+            let mut literal_words = 0;
+            while index < num_words && literal_words < MAX_LITERAL_WORDS {
+                let word = word_at(index);
+                if word == 0 || word == u64::MAX {
+                    break;
+                }
+                out.push(word);
+                literal_words += 1;
+                index += 1;
+            }
+            out[rlw_position] = running_bit as u64 | running_len << 1 | literal_words << 33;
+        }
+        Vec { num_bits, words: out }
+    }
+
+    /// The amount of bits in this bitmap, set or not.
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    /// Return all bits as uncompressed words, with bit `n` being bit `n % 64` of word `n / 64`.
+    pub fn decompress(&self) -> std::vec::Vec<u64> {
+        let mut out = std::vec::Vec::with_capacity((self.num_bits + WORD_BITS - 1) / WORD_BITS);
+        for (fill, fill_words, literals) in self.runs() {
+            out.extend(std::iter::repeat(fill).take(fill_words));
+            out.extend_from_slice(literals);
+        }
+        out.resize((self.num_bits + WORD_BITS - 1) / WORD_BITS, 0);
+        out
+    }
+
+    /// Call `f(index)` for each set bit in ascending order, stopping early if it returns `None`, which is then returned.
+    pub fn for_each_set_bit(&self, mut f: impl FnMut(usize) -> Option<()>) -> Option<()> {
+        let mut word_index = 0;
+        let mut for_each_bit_in = |word_index: usize, mut word: u64| {
+            while word != 0 {
+                let bit = word.trailing_zeros() as usize;
+                f(word_index * WORD_BITS + bit)?;
+                word &= word - 1;
+            }
+            Some(())
+        };
+        for (fill, fill_words, literals) in self.runs() {
+            if fill != 0 {
+                for index in word_index..word_index + fill_words {
+                    for_each_bit_in(index, fill)?;
+                }
+            }
+            word_index += fill_words;
+            for literal in literals {
+                for_each_bit_in(word_index, *literal)?;
+                word_index += 1;
+            }
+        }
+        Some(())
+    }
+
+    /// Return the amount of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.runs()
+            .map(|(fill, fill_words, literals)| {
+                fill.count_ones() as usize * fill_words
+                    + literals.iter().map(|word| word.count_ones() as usize).sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Return true if the bit at `index` is set.
+    pub fn is_set(&self, index: usize) -> bool {
+        if index >= self.num_bits {
+            return false;
+        }
+        let (mut word_index, mask) = (index / WORD_BITS, 1 << (index % WORD_BITS));
+        for (fill, fill_words, literals) in self.runs() {
+            if word_index < fill_words {
+                return fill & mask != 0;
+            }
+            word_index -= fill_words;
+            if let Some(word) = literals.get(word_index) {
+                return word & mask != 0;
+            }
+            word_index -= literals.len();
+        }
+        false
+    }
+
+    /// Iterate over each run-length word as tuple of the word to repeat, the amount of repetitions, and the literal words
+    /// following them.
+    fn runs(&self) -> impl Iterator<Item = (u64, usize, &[u64])> {
+        let mut pos = 0;
+        std::iter::from_fn(move || {
+            let rlw = *self.words.get(pos)?;
+            let literals = literal_words(rlw) as usize;
+            let fill = if running_bit(rlw) { u64::MAX } else { 0 };
+            let run = (
+                fill,
+                running_len(rlw) as usize,
+                &self.words[pos + 1..pos + 1 + literals],
+            );
+            pos += 1 + literals;
+            Some(run)
+        })
+    }
+
+    /// Return a new bitmap with all bits set that are set in `self` or `other`.
+    pub fn or(&self, other: &Vec) -> Vec {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Return a new bitmap with all bits set that are set in both, `self` and `other`.
+    pub fn and(&self, other: &Vec) -> Vec {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// Return a new bitmap with all bits set that are set in `self`, but not in `other`.
+    pub fn and_not(&self, other: &Vec) -> Vec {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// Return a new bitmap with all bits set that are set in either `self` or `other`, but not in both.
+    pub fn xor(&self, other: &Vec) -> Vec {
+        self.combine(other, |a, b| a ^ b)
+    }
+
+    fn combine(&self, other: &Vec, op: impl Fn(u64, u64) -> u64) -> Vec {
+        let (lhs, rhs) = (self.decompress(), other.decompress());
+        let num_bits = self.num_bits.max(other.num_bits);
+        let words: std::vec::Vec<_> = (0..lhs.len().max(rhs.len()))
+            .map(|index| {
+                op(
+                    lhs.get(index).copied().unwrap_or(0),
+                    rhs.get(index).copied().unwrap_or(0),
+                )
+            })
+            .collect();
+        Vec::from_words(&words, num_bits)
+    }
+}
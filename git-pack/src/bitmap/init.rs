@@ -0,0 +1,141 @@
+use crate::{bitmap, bitmap::ewah, index};
+use byteorder::{BigEndian, ByteOrder};
+use git_hash::ObjectId;
+use std::{collections::HashMap, path::Path};
+
+/// Returned by [`bitmap::File::at()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open pack bitmap file at '{path}'")]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("{message}")]
+    Corrupt { message: String },
+    #[error("Unsupported bitmap version: {version}")]
+    UnsupportedVersion { version: u16 },
+    #[error("The bitmap was created for pack {actual}, but the index belongs to pack {expected}")]
+    PackChecksumMismatch { expected: ObjectId, actual: ObjectId },
+    #[error("Could not decode the {what} bitmap")]
+    Ewah {
+        source: ewah::decode::Error,
+        what: &'static str,
+    },
+}
+
+const SIGNATURE: &[u8] = b"BITM";
+const HEADER_SIZE: usize = SIGNATURE.len() + 2 + 2 + 4;
+const FLAG_NAME_HASH_CACHE: u16 = 0x4;
+
+/// Instantiation
+impl bitmap::File {
+    /// Open the bitmap file at the given `path`, typically the path of the `index` with the `bitmap` extension, and validate
+    /// that it belongs to the pack `index` refers to.
+    pub fn at(path: impl AsRef<Path>, index: &index::File) -> Result<bitmap::File, Error> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|err| Error::Io {
+            source: err,
+            path: path.to_owned(),
+        })?;
+        let corrupt = |message: &str| Error::Corrupt {
+            message: message.into(),
+        };
+        let hash_len = index.hash_kind().len_in_bytes();
+        if data.len() < HEADER_SIZE + hash_len * 2 {
+            return Err(corrupt("Pack bitmap is too small for even an empty bitmap"));
+        }
+        if &data[..SIGNATURE.len()] != SIGNATURE {
+            return Err(corrupt("Pack bitmap signature mismatch"));
+        }
+        let version = BigEndian::read_u16(&data[4..6]);
+        if version != 1 {
+            return Err(Error::UnsupportedVersion { version });
+        }
+        let flags = BigEndian::read_u16(&data[6..8]);
+        let num_entries = BigEndian::read_u32(&data[8..12]);
+        let pack_checksum = ObjectId::from_bytes(&data[HEADER_SIZE..HEADER_SIZE + hash_len]);
+        if pack_checksum != index.pack_checksum() {
+            return Err(Error::PackChecksumMismatch {
+                expected: index.pack_checksum(),
+                actual: pack_checksum,
+            });
+        }
+
+        let d = &data[HEADER_SIZE + hash_len..data.len() - hash_len];
+        let (commits, d) = ewah::decode(d).map_err(|err| Error::Ewah {
+            source: err,
+            what: "commit type",
+        })?;
+        let (trees, d) = ewah::decode(d).map_err(|err| Error::Ewah {
+            source: err,
+            what: "tree type",
+        })?;
+        let (blobs, d) = ewah::decode(d).map_err(|err| Error::Ewah {
+            source: err,
+            what: "blob type",
+        })?;
+        let (tags, mut d) = ewah::decode(d).map_err(|err| Error::Ewah {
+            source: err,
+            what: "tag type",
+        })?;
+
+        let mut entries = Vec::with_capacity(num_entries as usize);
+        let mut entry_by_index_position = HashMap::with_capacity(num_entries as usize);
+        for entry_index in 0..num_entries as usize {
+            if d.len() < 6 {
+                return Err(corrupt("Pack bitmap entries are truncated"));
+            }
+            let index_position = BigEndian::read_u32(&d[..4]);
+            let (xor_offset, flags) = (d[4], d[5]);
+            if index_position >= index.num_objects() {
+                return Err(Error::Corrupt {
+                    message: format!(
+                        "Bitmap entry refers to object at index position {}, but there are only {} objects",
+                        index_position,
+                        index.num_objects()
+                    ),
+                });
+            }
+            if xor_offset as usize > entry_index {
+                return Err(corrupt("Bitmap entry refers to a bitmap before the first one"));
+            }
+            let (bitmap, rest) = ewah::decode(&d[6..]).map_err(|err| Error::Ewah {
+                source: err,
+                what: "commit",
+            })?;
+            d = rest;
+            entry_by_index_position.insert(index_position, entry_index);
+            entries.push(bitmap::Entry {
+                index_position,
+                xor_offset,
+                flags,
+                bitmap,
+            });
+        }
+
+        let name_hashes = if flags & FLAG_NAME_HASH_CACHE == FLAG_NAME_HASH_CACHE {
+            let len = index.num_objects() as usize * 4;
+            if d.len() < len {
+                return Err(corrupt("Pack bitmap name-hash cache is truncated"));
+            }
+            Some(d[..len].chunks(4).map(BigEndian::read_u32).collect())
+        } else {
+            None
+        };
+
+        Ok(bitmap::File {
+            path: path.to_owned(),
+            version,
+            pack_checksum,
+            commits,
+            trees,
+            blobs,
+            tags,
+            entries,
+            entry_by_index_position,
+            name_hashes,
+        })
+    }
+}
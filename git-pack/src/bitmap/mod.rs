@@ -0,0 +1,113 @@
+//! Reachability bitmaps stored in `.bitmap` files alongside pack indices, allowing to determine the objects reachable from
+//! selected commits without traversing the object graph.
+//!
+//! Bits in each bitmap correspond to objects in the pack in the order they are stored in the pack data file, see
+//! [`index_positions_in_pack_order()`] to map them to objects in the pack index.
+use crate::index;
+use git_hash::ObjectId;
+use std::{collections::HashMap, path::PathBuf};
+
+///
+pub mod ewah;
+
+///
+pub mod init;
+
+///
+pub mod reachable;
+
+/// A bitmap of all objects reachable from a commit, possibly stored as difference to the bitmap of another commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The position of the commit in the pack index, as used by [`index::File::oid_at_index()`].
+    pub index_position: u32,
+    /// If not 0, `bitmap` has to be xor'ed with the resolved bitmap of the entry this many entries before this one.
+    pub xor_offset: u8,
+    /// Flags as provided by git, for instance to indicate that the commit was selected for reuse.
+    pub flags: u8,
+    /// The bitmap as stored, see [`File::bitmap_at_index()`] to obtain it after xor'ing.
+    pub bitmap: ewah::Vec,
+}
+
+/// A `.bitmap` file providing reachability bitmaps for selected commits in a pack.
+pub struct File {
+    path: PathBuf,
+    version: u16,
+    pack_checksum: ObjectId,
+    commits: ewah::Vec,
+    trees: ewah::Vec,
+    blobs: ewah::Vec,
+    tags: ewah::Vec,
+    entries: Vec<Entry>,
+    entry_by_index_position: HashMap<u32, usize>,
+    name_hashes: Option<Vec<u32>>,
+}
+
+/// Access
+impl File {
+    /// The path from which this file was read.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+    /// The version of the file format, currently always 1.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+    /// The checksum of the pack data file the bitmaps refer to.
+    pub fn pack_checksum(&self) -> git_hash::ObjectId {
+        self.pack_checksum
+    }
+    /// All stored entries in the order they appear in the file.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+    /// A bitmap with all objects of the given `kind` set.
+    pub fn type_bitmap(&self, kind: git_object::Kind) -> &ewah::Vec {
+        match kind {
+            git_object::Kind::Commit => &self.commits,
+            git_object::Kind::Tree => &self.trees,
+            git_object::Kind::Blob => &self.blobs,
+            git_object::Kind::Tag => &self.tags,
+        }
+    }
+    /// The hash of the path at which the object at `index_position` in the pack index was first seen, as used to find good
+    /// delta bases, or `None` if the file doesn't contain name hashes.
+    pub fn name_hash(&self, index_position: u32) -> Option<u32> {
+        self.name_hashes
+            .as_ref()
+            .and_then(|hashes| hashes.get(index_position as usize).copied())
+    }
+
+    /// Return the bitmap of all objects reachable from the commit at `index_position` in the pack index, or `None` if there is
+    /// no bitmap for it.
+    pub fn bitmap_at_index(&self, index_position: u32) -> Option<ewah::Vec> {
+        let mut entry_index = *self.entry_by_index_position.get(&index_position)?;
+        let mut bitmap = self.entries[entry_index].bitmap.clone();
+        loop {
+            let xor_offset = self.entries[entry_index].xor_offset as usize;
+            if xor_offset == 0 {
+                break Some(bitmap);
+            }
+            entry_index -= xor_offset;
+            bitmap = bitmap.xor(&self.entries[entry_index].bitmap);
+        }
+    }
+
+    /// Return the bitmap of all objects reachable from the commit with `id`, looking it up in `index`, the index of the
+    /// pack this file belongs to.
+    pub fn lookup(&self, index: &index::File, id: impl AsRef<git_hash::oid>) -> Option<ewah::Vec> {
+        index
+            .lookup(id)
+            .and_then(|index_position| self.bitmap_at_index(index_position))
+    }
+}
+
+/// Return the position of each object in `index` in the order of the objects in the pack, which is the order of
+/// bits in a bitmap.
+///
+/// Thus the object corresponding to bit `n` is `index.oid_at_index(positions[n])`.
+pub fn index_positions_in_pack_order(index: &index::File) -> Vec<u32> {
+    let mut positions: Vec<_> = (0..index.num_objects()).collect();
+    positions.sort_by_key(|position| index.pack_offset_at_index(*position));
+    positions
+}
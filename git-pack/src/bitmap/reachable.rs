@@ -0,0 +1,151 @@
+use crate::{bitmap, bitmap::ewah, cache, find, index, FindExt};
+use git_hash::ObjectId;
+use git_object::{immutable, tree::EntryMode, Kind};
+
+/// Returned by [`bitmap::File::reachable()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error<FindErr: std::error::Error + 'static> {
+    #[error(transparent)]
+    Find(#[from] find::existing::Error<FindErr>),
+    #[error("Object {id} is reachable but not contained in the pack")]
+    NotInPack { id: ObjectId },
+    #[error("{kind} object {id} could not be decoded")]
+    Decode {
+        source: immutable::object::decode::Error,
+        kind: Kind,
+        id: ObjectId,
+    },
+}
+
+/// The outcome of [`bitmap::File::reachable()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// A bitmap with a bit set for each object reachable from the wanted objects, but not from the ones we have.
+    pub objects: ewah::Vec,
+    /// The amount of objects which had to be looked up and decoded as they weren't covered by any bitmap.
+    pub traversed_objects: usize,
+}
+
+impl Outcome {
+    /// Return the ids of all [`objects`][Outcome::objects], using `index` to map bits to objects.
+    pub fn object_ids(&self, index: &index::File) -> Vec<ObjectId> {
+        let pack_order = bitmap::index_positions_in_pack_order(index);
+        let mut ids = Vec::with_capacity(self.objects.count_ones());
+        self.objects.for_each_set_bit(|bit| {
+            ids.push(index.oid_at_index(pack_order[bit]).to_owned());
+            Some(())
+        });
+        ids
+    }
+}
+
+impl bitmap::File {
+    /// Compute the set of objects in the pack of `index` which are reachable from `wants`, but not from `haves`, as needed to
+    /// create a pack for a client which announced these.
+    ///
+    /// Commits with a bitmap contribute it as a whole, whereas the remaining objects are traversed by looking them up in `db`
+    /// until an object with bitmap or an object that was seen before is encountered.
+    /// The result is the difference of the bitmaps of all wanted and all present objects.
+    ///
+    /// Wanted objects and all objects reachable from them have to be contained in the pack, whereas objects in `haves`
+    /// that are unknown to the pack are ignored.
+    pub fn reachable<Find>(
+        &self,
+        index: &index::File,
+        wants: impl IntoIterator<Item = impl Into<ObjectId>>,
+        haves: impl IntoIterator<Item = impl Into<ObjectId>>,
+        db: &Find,
+    ) -> Result<Outcome, Error<Find::Error>>
+    where
+        Find: crate::Find,
+    {
+        let mut pack_position_by_index = vec![0; index.num_objects() as usize];
+        for (pack_position, index_position) in bitmap::index_positions_in_pack_order(index).into_iter().enumerate() {
+            pack_position_by_index[index_position as usize] = pack_position as u32;
+        }
+        let mut traversed_objects = 0;
+        let mut walk =
+            |tips: Vec<ObjectId>| self.reachable_bits(index, &pack_position_by_index, tips, db, &mut traversed_objects);
+        let wants = walk(wants.into_iter().map(Into::into).collect())?;
+        let haves = walk(
+            haves
+                .into_iter()
+                .map(Into::into)
+                .filter(|id| index.lookup(id).is_some())
+                .collect(),
+        )?;
+        let num_bits = index.num_objects() as usize;
+        Ok(Outcome {
+            objects: ewah::Vec::from_words(&wants, num_bits).and_not(&ewah::Vec::from_words(&haves, num_bits)),
+            traversed_objects,
+        })
+    }
+
+    fn reachable_bits<Find>(
+        &self,
+        index: &index::File,
+        pack_position_by_index: &[u32],
+        mut next: Vec<ObjectId>,
+        db: &Find,
+        traversed_objects: &mut usize,
+    ) -> Result<Vec<u64>, Error<Find::Error>>
+    where
+        Find: crate::Find,
+    {
+        let mut words = vec![0u64; (index.num_objects() as usize + 63) / 64];
+        let mut buf = Vec::new();
+        while let Some(id) = next.pop() {
+            let index_position = index.lookup(id).ok_or(Error::NotInPack { id })?;
+            let bit = pack_position_by_index[index_position as usize] as usize;
+            let (word, mask) = (bit / 64, 1 << (bit % 64));
+            if words[word] & mask != 0 {
+                continue;
+            }
+            if let Some(bitmap) = self.bitmap_at_index(index_position) {
+                for (word, bits) in words.iter_mut().zip(bitmap.decompress()) {
+                    *word |= bits;
+                }
+                continue;
+            }
+            words[word] |= mask;
+            *traversed_objects += 1;
+
+            let object = db.find_existing(id, &mut buf, &mut cache::Never)?;
+            let decode_err = |source| Error::Decode {
+                source,
+                kind: object.kind,
+                id,
+            };
+            match object.kind {
+                Kind::Commit => {
+                    for token in immutable::CommitIter::from_bytes(object.data) {
+                        match token.map_err(decode_err)? {
+                            immutable::commit::iter::Token::Tree { id }
+                            | immutable::commit::iter::Token::Parent { id } => next.push(id),
+                            _a_token_past_the_parents => break,
+                        }
+                    }
+                }
+                Kind::Tree => {
+                    for entry in immutable::TreeIter::from_bytes(object.data) {
+                        let entry = entry.map_err(decode_err)?;
+                        if entry.mode != EntryMode::Commit {
+                            next.push(entry.oid.to_owned());
+                        }
+                    }
+                }
+                Kind::Tag => {
+                    for token in immutable::TagIter::from_bytes(object.data) {
+                        if let Some(id) = token.map_err(decode_err)?.into_id() {
+                            next.push(id);
+                            break;
+                        }
+                    }
+                }
+                Kind::Blob => {}
+            }
+        }
+        Ok(words)
+    }
+}
@@ -5,12 +5,57 @@ use std::{
 
 mod find;
 ///
+pub mod raw;
+///
 pub mod write;
 
-mod verify {
+///
+pub mod verify {
     use git_features::progress::Progress;
     use std::sync::{atomic::AtomicBool, Arc};
 
+    /// Statistics about a single object in a pack, as returned by
+    /// [`Bundle::verify_integrity_with_statistics()`][super::Bundle::verify_integrity_with_statistics()].
+    #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ObjectStatistics {
+        /// The offset at which the object's entry starts in the pack.
+        pub pack_offset: u64,
+        /// The id of the object.
+        pub id: git_hash::ObjectId,
+        /// The kind of the object.
+        pub kind: git_object::Kind,
+        /// The amount of deltas that have to be applied to obtain the object, 0 for objects stored as a whole.
+        pub delta_depth: u32,
+        /// The size of the entry in the pack in bytes, including its header.
+        pub compressed_size: u64,
+        /// The size of the decompressed entry data in bytes, which for deltified objects is the size of the delta.
+        pub decompressed_size: u64,
+        /// The size of the fully decoded object in bytes.
+        pub object_size: u64,
+    }
+
+    /// The checksum of the pack, the outcome of the traversal, the [statistics of each object][ObjectStatistics] sorted by
+    /// pack offset and the progress, as returned by
+    /// [`Bundle::verify_integrity_with_statistics()`][super::Bundle::verify_integrity_with_statistics()].
+    pub type OutcomeWithStatistics<P> = (
+        git_hash::ObjectId,
+        crate::index::traverse::Outcome,
+        Vec<ObjectStatistics>,
+        Option<P>,
+    );
+
+    struct Collector<'a> {
+        local: Vec<ObjectStatistics>,
+        shared: &'a parking_lot::Mutex<Vec<ObjectStatistics>>,
+    }
+
+    impl Drop for Collector<'_> {
+        fn drop(&mut self) {
+            self.shared.lock().append(&mut self.local);
+        }
+    }
+
     impl super::Bundle {
         /// Similar to [`crate::index::File::verify_integrity()`] but more convenient to call as the presence of the
         /// pack file is a given.
@@ -37,6 +82,107 @@ mod verify {
                 should_interrupt,
             )
         }
+
+        /// Like [`verify_integrity()`][super::Bundle::verify_integrity()], which validates the checksums of index and pack, recomputes
+        /// the hash of each object after decoding it in parallel, but also returns [statistics for each object][ObjectStatistics]
+        /// sorted by pack offset.
+        ///
+        /// Note that the statistics are held in memory, about 80 bytes per object.
+        pub fn verify_integrity_with_statistics<C, P>(
+            &self,
+            verify_mode: crate::index::verify::Mode,
+            traversal: crate::index::traverse::Algorithm,
+            make_pack_lookup_cache: impl Fn() -> C + Send + Sync,
+            thread_limit: Option<usize>,
+            progress: Option<P>,
+            should_interrupt: Arc<AtomicBool>,
+        ) -> Result<OutcomeWithStatistics<P>, crate::index::traverse::Error<crate::index::verify::Error>>
+        where
+            P: Progress,
+            C: crate::cache::DecodeEntry,
+        {
+            let sorted_offsets = self.index.sorted_offsets();
+            let statistics = parking_lot::Mutex::new(Vec::with_capacity(self.index.num_objects() as usize));
+            let (id, outcome, progress) = self.index.traverse(
+                &self.pack,
+                progress,
+                || {
+                    let mut encode_buf = Vec::with_capacity(2048);
+                    let mut collector = Collector {
+                        local: Vec::new(),
+                        shared: &statistics,
+                    };
+                    let sorted_offsets = &sorted_offsets;
+                    move |kind, data, index_entry, progress| {
+                        crate::index::File::verify_entry(
+                            verify_mode,
+                            &mut encode_buf,
+                            kind,
+                            data,
+                            index_entry,
+                            progress,
+                        )?;
+                        collector
+                            .local
+                            .push(self.object_statistics(sorted_offsets, kind, data.len(), index_entry));
+                        Ok(())
+                    }
+                },
+                make_pack_lookup_cache,
+                crate::index::traverse::Options {
+                    algorithm: traversal,
+                    thread_limit,
+                    check: crate::index::traverse::SafetyCheck::All,
+                    should_interrupt,
+                },
+            )?;
+            let mut statistics = statistics.into_inner();
+            statistics.sort_by_key(|s| s.pack_offset);
+            Ok((id, outcome, statistics, progress))
+        }
+
+        fn object_statistics(
+            &self,
+            sorted_offsets: &[u64],
+            kind: git_object::Kind,
+            object_size: usize,
+            index_entry: &crate::index::Entry,
+        ) -> ObjectStatistics {
+            let entry = self.pack.entry(index_entry.pack_offset);
+            let next_offset = match sorted_offsets.binary_search(&index_entry.pack_offset) {
+                Ok(pos) => sorted_offsets.get(pos + 1).copied(),
+                Err(_) => None,
+            }
+            .unwrap_or_else(|| self.pack.pack_end() as u64);
+
+            let mut delta_depth = 0;
+            let mut base = entry.clone();
+            loop {
+                let base_offset = match base.header {
+                    crate::data::entry::Header::OfsDelta { base_distance } => base.base_pack_offset(base_distance),
+                    crate::data::entry::Header::RefDelta { base_id } => match self.index.lookup(base_id) {
+                        Some(idx) => self.index.pack_offset_at_index(idx),
+                        None => {
+                            delta_depth += 1;
+                            break;
+                        }
+                    },
+                    _ => break,
+                };
+                delta_depth += 1;
+                base = self.pack.entry(base_offset);
+            }
+
+            ObjectStatistics {
+                pack_offset: index_entry.pack_offset,
+                id: index_entry.oid,
+                kind,
+                delta_depth,
+                compressed_size: next_offset - index_entry.pack_offset,
+                decompressed_size: entry.decompressed_size,
+                object_size: object_size as u64,
+            }
+        }
     }
 }
 
@@ -98,16 +244,13 @@ impl TryFrom<&Path> for Bundle {
             .extension()
             .and_then(|e| e.to_str())
             .ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
-        Ok(match ext {
-            "idx" => Self {
-                index: crate::index::File::at(path)?,
-                pack: crate::data::File::at(path.with_extension("pack"))?,
-            },
-            "pack" => Self {
-                pack: crate::data::File::at(path)?,
-                index: crate::index::File::at(path.with_extension("idx"))?,
-            },
+        let index_path = match ext {
+            "idx" => path.to_owned(),
+            "pack" => path.with_extension("idx"),
             _ => return Err(Error::InvalidPath(path.to_owned())),
-        })
+        };
+        let index = crate::index::File::at(&index_path)?;
+        let pack = crate::data::File::at_with_hash_kind(index_path.with_extension("pack"), index.hash_kind())?;
+        Ok(Self { pack, index })
     }
 }
@@ -0,0 +1,155 @@
+use crate::data;
+use git_hash::ObjectId;
+
+/// The error returned by [`Entries::chain()`] and [`Entries::entry_at_index()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The entry at pack offset {pack_offset} ends past the end of the pack")]
+    OutOfBounds { pack_offset: u64 },
+    #[error("The entry at pack offset {pack_offset} has crc32 {actual}, but the index expected {expected}")]
+    Crc32Mismatch {
+        pack_offset: u64,
+        actual: u32,
+        expected: u32,
+    },
+    #[error("The base of the delta at pack offset {pack_offset} is not contained in the pack")]
+    BaseNotFound { pack_offset: u64 },
+    #[error("The delta chain of object {id} is longer than the amount of objects in the pack")]
+    Cycle { id: ObjectId },
+}
+
+/// A pack entry exactly as it is stored in the pack, suitable for copying it verbatim into another pack.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Entry<'a> {
+    /// The id of the object stored in this entry, which for deltas is the id of the object obtained after applying it.
+    pub id: ObjectId,
+    /// The offset at which the entry starts in the pack.
+    pub pack_offset: u64,
+    /// The decoded entry header, which for [`OfsDelta`][data::entry::Header::OfsDelta] entries refers to the base by its
+    /// distance in _this_ pack, and has to be rewritten when placing the entry into another pack.
+    pub header: data::entry::Header,
+    /// The size of the object, or of the delta, once decompressed.
+    pub decompressed_size: u64,
+    /// The size of the encoded header at the beginning of `data`.
+    pub header_size: usize,
+    /// The header followed by the zlib compressed stream.
+    pub data: &'a [u8],
+}
+
+impl<'a> Entry<'a> {
+    /// The zlib compressed object or delta data right behind the header.
+    pub fn compressed_data(&self) -> &'a [u8] {
+        &self.data[self.header_size..]
+    }
+}
+
+/// Provides raw access to the entries of a [`Bundle`][crate::Bundle], created by [`Bundle::raw_entries()`][crate::Bundle::raw_entries()].
+///
+/// As the size of an entry isn't stored in the pack, all pack offsets are sorted once upon creation to learn where each entry ends
+/// without decompressing it, which is why an instance should be kept around to obtain more than a few entries.
+pub struct Entries<'a> {
+    bundle: &'a crate::Bundle,
+    /// pairs of pack offsets and index file ids, sorted by pack offset.
+    sorted_offsets: Vec<(u64, u32)>,
+}
+
+/// Raw entry access
+impl crate::Bundle {
+    /// Prepare access to our entries as they are stored in the pack, for instance to copy them into a new pack without
+    /// decompressing and recompressing them.
+    pub fn raw_entries(&self) -> Entries<'_> {
+        let mut sorted_offsets: Vec<_> = (0..self.index.num_objects())
+            .map(|index| (self.index.pack_offset_at_index(index), index))
+            .collect();
+        sorted_offsets.sort_unstable();
+        Entries {
+            bundle: self,
+            sorted_offsets,
+        }
+    }
+}
+
+impl<'a> Entries<'a> {
+    /// Return the raw entry of the object with `id` followed by the entries of all of its bases up to and including the base object,
+    /// or `None` if the object isn't contained in the pack.
+    ///
+    /// Copying the returned entries in reverse order allows to reproduce the object in another pack.
+    /// Note that bases of [`RefDelta`][data::entry::Header::RefDelta] entries are expected in the pack as well, which
+    /// doesn't hold for thin packs.
+    pub fn chain(&self, id: impl AsRef<git_hash::oid>) -> Result<Option<Vec<Entry<'a>>>, Error> {
+        let index = match self.bundle.index.lookup(id.as_ref()) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let mut out = vec![self.entry_at_index(index)?];
+        loop {
+            let entry = out.last().expect("at least one entry");
+            let base_index = match entry.header {
+                data::entry::Header::OfsDelta { base_distance } => {
+                    data::entry::Header::verified_base_pack_offset(entry.pack_offset, base_distance)
+                        .and_then(|base_offset| self.index_at_offset(base_offset))
+                }
+                data::entry::Header::RefDelta { base_id } => self.bundle.index.lookup(base_id),
+                _base_object => break,
+            }
+            .ok_or(Error::BaseNotFound {
+                pack_offset: entry.pack_offset,
+            })?;
+            if out.len() > self.sorted_offsets.len() {
+                return Err(Error::Cycle { id: out[0].id });
+            }
+            out.push(self.entry_at_index(base_index)?);
+        }
+        Ok(Some(out))
+    }
+
+    /// Return the raw entry for the object at `index` in the index file, verifying its integrity with the crc32 stored in
+    /// the index if available.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn entry_at_index(&self, index: u32) -> Result<Entry<'a>, Error> {
+        let pack = &self.bundle.pack;
+        let pack_offset = self.bundle.index.pack_offset_at_index(index);
+        let end = match self
+            .sorted_offsets
+            .binary_search_by_key(&pack_offset, |(offset, _)| *offset)
+            .ok()
+            .and_then(|pos| self.sorted_offsets.get(pos + 1))
+        {
+            Some((next_offset, _)) => *next_offset,
+            None => pack.pack_end() as u64,
+        };
+        let data = pack
+            .entry_slice(pack_offset..end)
+            .ok_or(Error::OutOfBounds { pack_offset })?;
+        if let Some(expected) = self.bundle.index.crc32_at_index(index) {
+            let actual = git_features::hash::crc32(data);
+            if actual != expected {
+                return Err(Error::Crc32Mismatch {
+                    pack_offset,
+                    actual,
+                    expected,
+                });
+            }
+        }
+        let entry = pack.entry(pack_offset);
+        Ok(Entry {
+            id: self.bundle.index.oid_at_index(index).to_owned(),
+            pack_offset,
+            header: entry.header,
+            decompressed_size: entry.decompressed_size,
+            header_size: entry.header_size(),
+            data,
+        })
+    }
+
+    fn index_at_offset(&self, pack_offset: u64) -> Option<u32> {
+        self.sorted_offsets
+            .binary_search_by_key(&pack_offset, |(offset, _)| *offset)
+            .ok()
+            .map(|pos| self.sorted_offsets[pos].1)
+    }
+}
@@ -1,6 +1,10 @@
 use std::io;
 
+/// Returned by [write_to_directory][crate::Bundle::write_to_directory()],
+/// [write_to_directory_eagerly][crate::Bundle::write_to_directory_eagerly()] or
+/// [write_stream_to_directory][crate::Bundle::write_stream_to_directory()]
 #[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
 pub enum Error {
     #[error("An IO error occurred when reading the pack or creating a temporary file")]
     Io(#[from] io::Error),
@@ -10,4 +14,19 @@ pub enum Error {
     PeristError(#[from] tempfile::PersistError),
     #[error(transparent)]
     IndexWrite(#[from] crate::index::write::Error),
+    #[error("The base object {id} of a ref-delta could not be looked up")]
+    BaseLookup {
+        id: git_hash::ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("The base object {id} of a ref-delta was not found, but is needed to complete the thin pack")]
+    BaseNotFound { id: git_hash::ObjectId },
+    #[error("The offset-delta at pack offset {pack_offset} has an invalid base distance of {distance}")]
+    BaseOffset { pack_offset: u64, distance: u64 },
+    #[error(transparent)]
+    BaseEntryCreate(#[from] crate::data::output::entry::Error),
+    #[error(transparent)]
+    PackOpen(#[from] crate::data::header::decode::Error),
+    #[error(transparent)]
+    EntryDecode(#[from] crate::data::decode_entry::Error),
 }
@@ -1,15 +1,19 @@
 use filebuffer::FileBuffer;
 
+use crate::data::{output, ResolvedBase};
 use git_features::{interrupt, progress, progress::Progress};
 use std::{
-    io,
+    cell::RefCell,
+    collections::HashMap,
+    convert::TryFrom,
+    io::{self, Seek, SeekFrom},
     path::{Path, PathBuf},
     sync::{atomic::AtomicBool, Arc},
 };
 use tempfile::NamedTempFile;
 
 mod error;
-use error::Error;
+pub use error::Error;
 
 mod types;
 use types::PassThrough;
@@ -141,6 +145,197 @@ impl crate::Bundle {
         })
     }
 
+    /// Like [`write_to_directory()`][crate::Bundle::write_to_directory()], but able to handle _thin_ packs as received when
+    /// fetching, whose ref-deltas refer to base objects which are not contained in the pack.
+    ///
+    /// The bases of ref-deltas are searched in the pack itself first. Only those it doesn't contain are looked up in
+    /// `thin_pack_base_lookup`, typically the object database of the receiving repository, and added to the pack to make it
+    /// self-contained. Ref-deltas are turned into offset-deltas on the way, or into undeltified objects if their base is
+    /// stored after them, which is why the written pack differs from the received one if it contained ref-deltas.
+    ///
+    /// # Note
+    ///
+    /// The `pack` is streamed into a temporary file as all of its entries must be known to resolve ref-deltas.
+    /// Packs with ref-deltas are decoded one additional time to learn the ids of their objects.
+    pub fn write_stream_to_directory<Find>(
+        pack: impl io::BufRead,
+        directory: Option<impl AsRef<Path>>,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        thin_pack_base_lookup: &Find,
+        options: Options,
+    ) -> Result<Outcome, Error>
+    where
+        Find: crate::Find,
+        Find::Error: Send + Sync,
+    {
+        use crate::data::entry::Header;
+
+        let mut read_progress = progress.add_child("read pack");
+        read_progress.init(None, progress::bytes());
+        let pack = progress::Read {
+            reader: pack,
+            progress: progress::ThroughputOnDrop::new(read_progress),
+        };
+
+        let data_file = Arc::new(parking_lot::Mutex::new(match directory.as_ref() {
+            Some(directory) => NamedTempFile::new_in(directory.as_ref())?,
+            None => NamedTempFile::new()?,
+        }));
+        let pack = PassThrough {
+            reader: interrupt::Read {
+                inner: pack,
+                should_interrupt,
+            },
+            writer: Some(data_file.clone()),
+        };
+        let entries = crate::data::BytesToEntriesIter::new_from_header(
+            io::BufReader::new(pack),
+            options.iteration_mode,
+            crate::data::input::EntryDataMode::Ignore,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+        let data_file = Arc::try_unwrap(data_file)
+            .expect("only one handle left after pack was consumed")
+            .into_inner();
+
+        if !entries
+            .iter()
+            .any(|entry| matches!(entry.header, Header::RefDelta { .. }))
+        {
+            return Self::write_to_directory(
+                io::BufReader::new(data_file.reopen()?),
+                directory,
+                progress,
+                should_interrupt,
+                options,
+            );
+        }
+
+        let pack = crate::data::File::at(data_file.path())?;
+        let ofs_delta_bases = ofs_delta_bases(&entries)?;
+        let mut index_by_id = HashMap::with_capacity(entries.len());
+        let ids_and_kinds = resolve_ids(
+            &pack,
+            &entries,
+            &ofs_delta_bases,
+            thin_pack_base_lookup,
+            &mut index_by_id,
+        )?;
+
+        let mut external_bases = Vec::new();
+        let mut external_base_index_by_id = HashMap::new();
+        for entry in &entries {
+            if let Header::RefDelta { base_id } = entry.header {
+                if !index_by_id.contains_key(&base_id) {
+                    external_base_index_by_id.entry(base_id).or_insert_with(|| {
+                        external_bases.push(base_id);
+                        external_bases.len() - 1
+                    });
+                }
+            }
+        }
+
+        let num_bases = external_bases.len();
+        let num_entries = u32::try_from(num_bases + entries.len())
+            .map_err(|_| crate::index::write::Error::IteratorInvariantTooManyObjects(num_bases + entries.len()))?;
+        let mut base_buf = Vec::new();
+        let bases = external_bases.iter().map(|id| {
+            let object = thin_pack_base_lookup
+                .find(id, &mut base_buf, &mut crate::cache::Never)
+                .map_err(|err| Error::BaseLookup {
+                    id: *id,
+                    source: Box::new(err),
+                })?
+                .ok_or(Error::BaseNotFound { id: *id })?;
+            let count = output::Count {
+                id: *id,
+                entry_pack_location: None,
+            };
+            Ok(vec![output::Entry::from_data(&count, &object, options.compression)?])
+        });
+        let mut buf = Vec::new();
+        let pack_entries = entries
+            .iter()
+            .zip(ofs_delta_bases.iter().zip(ids_and_kinds.iter()))
+            .enumerate()
+            .map(|(index, (entry, (ofs_delta_base, &(id, object_kind))))| {
+                let base_index = match entry.header {
+                    Header::RefDelta { base_id } => match index_by_id.get(&base_id) {
+                        Some(&base_index) if base_index < index => Some(num_bases + base_index),
+                        Some(_base_stored_after_delta) => None,
+                        None => Some(external_base_index_by_id[&base_id]),
+                    },
+                    Header::OfsDelta { .. } => ofs_delta_base.map(|base_index| num_bases + base_index),
+                    _base_object => None,
+                };
+                let kind = match base_index {
+                    Some(base_index) => output::entry::Kind::DeltaRef {
+                        nth_before: num_bases + index - base_index,
+                    },
+                    None if entry.header.is_delta() => {
+                        decode_entry(
+                            &pack,
+                            &entries,
+                            &index_by_id,
+                            entry,
+                            &mut buf,
+                            Some(thin_pack_base_lookup),
+                        )?;
+                        let count = output::Count {
+                            id,
+                            entry_pack_location: None,
+                        };
+                        let object = crate::data::Object::new(object_kind, &buf);
+                        return Ok(vec![output::Entry::from_data(&count, &object, options.compression)?]);
+                    }
+                    None => output::entry::Kind::Base,
+                };
+                let data_offset = entry.pack_offset + entry.header_size as u64;
+                Ok(vec![output::Entry {
+                    id,
+                    object_kind,
+                    kind,
+                    decompressed_size: entry.decompressed_size as usize,
+                    compressed_data: pack
+                        .entry_slice(data_offset..data_offset + entry.compressed_size)
+                        .expect("entries were read from this pack")
+                        .to_owned(),
+                }])
+            });
+
+        let mut pack_file = match directory.as_ref() {
+            Some(directory) => tempfile::tempfile_in(directory.as_ref())?,
+            None => tempfile::tempfile()?,
+        };
+        {
+            let mut write_progress = progress.add_child("write self-contained pack");
+            write_progress.init(None, progress::bytes());
+            for bytes_written in output::bytes::FromEntriesIter::new(
+                bases.chain(pack_entries),
+                &mut pack_file,
+                num_entries,
+                crate::data::Version::V2,
+                pack.hash_kind(),
+            ) {
+                let bytes_written = bytes_written.map_err(|err| match err {
+                    output::bytes::Error::Io(err) => Error::Io(err),
+                    output::bytes::Error::Input(err) => err,
+                })?;
+                write_progress.inc_by(bytes_written as usize);
+            }
+        }
+
+        pack_file.seek(SeekFrom::Start(0))?;
+        Self::write_to_directory(
+            io::BufReader::new(pack_file),
+            directory,
+            progress,
+            should_interrupt,
+            options,
+        )
+    }
+
     fn inner_write(
         directory: Option<impl AsRef<Path>>,
         mut progress: impl Progress,
@@ -148,6 +343,7 @@ impl crate::Bundle {
             thread_limit,
             iteration_mode: _,
             index_kind,
+            compression: _,
         }: Options,
         data_file: Arc<parking_lot::Mutex<NamedTempFile>>,
         data_path: PathBuf,
@@ -170,7 +366,7 @@ impl crate::Bundle {
                     should_interrupt,
                 )?;
 
-                let data_path = directory.join(format!("{}.pack", outcome.data_hash.to_sha1_hex_string()));
+                let data_path = directory.join(format!("{}.pack", outcome.data_hash));
                 let index_path = data_path.with_extension("idx");
 
                 Arc::try_unwrap(data_file)
@@ -205,6 +401,145 @@ impl crate::Bundle {
     }
 }
 
+/// Return the index of the base entry of each offset-delta among `entries`, or `None` for all other entries.
+fn ofs_delta_bases(entries: &[crate::data::input::Entry]) -> Result<Vec<Option<usize>>, Error> {
+    use crate::data::entry::Header;
+
+    entries
+        .iter()
+        .map(|entry| match entry.header {
+            Header::OfsDelta { base_distance } => {
+                let invalid_offset = || Error::BaseOffset {
+                    pack_offset: entry.pack_offset,
+                    distance: base_distance,
+                };
+                let base_offset =
+                    Header::verified_base_pack_offset(entry.pack_offset, base_distance).ok_or_else(invalid_offset)?;
+                entries
+                    .binary_search_by_key(&base_offset, |entry| entry.pack_offset)
+                    .map(Some)
+                    .map_err(|_| invalid_offset())
+            }
+            _ => Ok(None),
+        })
+        .collect()
+}
+
+/// Decode all `entries` of `pack` to learn the ids and kinds of their objects, and fill `index_by_id` to map ids to entry indices.
+///
+/// Bases of ref-deltas are looked up in `lookup` only if all entries that can be decoded with bases contained in the pack
+/// have been decoded, and only until one more entry could be decoded that way.
+fn resolve_ids<Find>(
+    pack: &crate::data::File,
+    entries: &[crate::data::input::Entry],
+    ofs_delta_bases: &[Option<usize>],
+    lookup: &Find,
+    index_by_id: &mut HashMap<git_hash::ObjectId, usize>,
+) -> Result<Vec<(git_hash::ObjectId, git_object::Kind)>, Error>
+where
+    Find: crate::Find,
+    Find::Error: Send + Sync,
+{
+    let mut ids_and_kinds = vec![None; entries.len()];
+    let mut num_unresolved = entries.len();
+    let mut use_lookup = false;
+    let mut buf = Vec::new();
+    while num_unresolved != 0 {
+        let mut made_progress = false;
+        let mut missing_base = None;
+        for (index, entry) in entries.iter().enumerate() {
+            if ids_and_kinds[index].is_some()
+                || matches!(ofs_delta_bases[index], Some(base_index) if ids_and_kinds[base_index].is_none())
+            {
+                continue;
+            }
+            let kind = match decode_entry(
+                pack,
+                entries,
+                index_by_id,
+                entry,
+                &mut buf,
+                if use_lookup { Some(lookup) } else { None },
+            ) {
+                Ok(kind) => kind,
+                Err(Error::BaseNotFound { id }) => {
+                    missing_base.get_or_insert(id);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            let id = crate::index::write::compute_hash(kind, &buf, pack.hash_kind())?;
+            index_by_id.insert(id, index);
+            ids_and_kinds[index] = Some((id, kind));
+            num_unresolved -= 1;
+            made_progress = true;
+            if use_lookup {
+                break;
+            }
+        }
+        match (made_progress, use_lookup) {
+            (true, _) => use_lookup = false,
+            (false, false) => use_lookup = true,
+            (false, true) => {
+                return Err(Error::BaseNotFound {
+                    id: missing_base.expect("at least one entry failed to decode"),
+                })
+            }
+        }
+    }
+    Ok(ids_and_kinds
+        .into_iter()
+        .map(|id_and_kind| id_and_kind.expect("all entries resolved"))
+        .collect())
+}
+
+/// Decode `entry` of `pack` into `out` and return its kind. The bases of ref-deltas are looked up among the `entries` which
+/// are already in `index_by_id` first, and in `lookup` if it is set.
+fn decode_entry<Find>(
+    pack: &crate::data::File,
+    entries: &[crate::data::input::Entry],
+    index_by_id: &HashMap<git_hash::ObjectId, usize>,
+    entry: &crate::data::input::Entry,
+    out: &mut Vec<u8>,
+    lookup: Option<&Find>,
+) -> Result<git_object::Kind, Error>
+where
+    Find: crate::Find,
+    Find::Error: Send + Sync,
+{
+    let lookup_error = RefCell::new(None);
+    let outcome = pack.decode_entry(
+        pack.entry(entry.pack_offset),
+        out,
+        // `oid` doesn't hash like `ObjectId`, which is why the owned version is used for lookups.
+        |id, out| match (index_by_id.get(&id.to_owned()), lookup) {
+            (Some(&base_index), _) => Some(ResolvedBase::InPack(pack.entry(entries[base_index].pack_offset))),
+            (None, Some(lookup)) => match lookup.find(id, out, &mut crate::cache::Never) {
+                Ok(object) => object.map(|object| ResolvedBase::OutOfPack {
+                    kind: object.kind,
+                    end: object.data.len(),
+                }),
+                Err(err) => {
+                    *lookup_error.borrow_mut() = Some(Error::BaseLookup {
+                        id: id.to_owned(),
+                        source: Box::new(err),
+                    });
+                    None
+                }
+            },
+            (None, None) => None,
+        },
+        &mut crate::cache::Never,
+    );
+    match outcome {
+        Ok(outcome) => Ok(outcome.kind),
+        Err(crate::data::decode_entry::Error::DeltaBaseUnresolved(id)) => {
+            Err(lookup_error.into_inner().unwrap_or(Error::BaseNotFound { id }))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
 fn new_pack_file_resolver(
     data_path: PathBuf,
 ) -> io::Result<impl Fn(crate::data::EntryRange, &mut Vec<u8>) -> Option<()> + Send + Sync> {
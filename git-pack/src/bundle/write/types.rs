@@ -11,6 +11,8 @@ pub struct Options {
     pub iteration_mode: crate::data::input::Mode,
     /// The version of pack index to write, should be [`crate::index::Version::default()`]
     pub index_kind: crate::index::Version,
+    /// How to compress objects that have to be added to thin packs to make them self-contained.
+    pub compression: git_features::zlib::Compression,
 }
 
 impl Default for Options {
@@ -20,6 +22,7 @@ impl Default for Options {
             thread_limit: None,
             iteration_mode: crate::data::input::Mode::Verify,
             index_kind: Default::default(),
+            compression: Default::default(),
         }
     }
 }
@@ -22,6 +22,110 @@ impl DecodeEntry for Never {
     }
 }
 
+/// A trait to model putting fully decoded objects into a cache by their id, and fetching them.
+///
+/// Unlike [`DecodeEntry`], which caches objects by their location in a pack to speed up delta resolution, it allows to skip
+/// all lookup and decoding work for objects that are accessed repeatedly, as is common in traversals,
+/// see [`FindExt::find_cached()`][crate::FindExt::find_cached()].
+pub trait Object {
+    /// Store the fully decoded `data` of an object with `id` and `kind` in the cache.
+    ///
+    /// It is up to the cache implementation whether that actually happens or not.
+    fn put(&mut self, id: git_hash::ObjectId, kind: git_object::Kind, data: &[u8]);
+    /// Attempt to fetch the object with `id` and store its decoded bytes in `out`, as previously stored with [`Object::put()`],
+    /// and return its `kind`.
+    fn get(&mut self, id: &git_hash::ObjectId, out: &mut Vec<u8>) -> Option<git_object::Kind>;
+}
+
+impl Object for Never {
+    fn put(&mut self, _id: git_hash::ObjectId, _kind: git_object::Kind, _data: &[u8]) {}
+    fn get(&mut self, _id: &git_hash::ObjectId, _out: &mut Vec<u8>) -> Option<git_object::Kind> {
+        None
+    }
+}
+
+/// A least-recently-used cache which evicts entries once the data they hold uses more memory than allowed, used by the
+/// memory-capped implementations of [`DecodeEntry`] and [`Object`].
+#[cfg(any(feature = "pack-cache-lru-dynamic", feature = "object-cache-dynamic"))]
+mod memory_capped {
+    use std::hash::Hash;
+
+    struct Entry<T> {
+        data: Vec<u8>,
+        info: T,
+    }
+
+    impl<T> memory_lru::ResidentSize for Entry<T> {
+        fn resident_size(&self) -> usize {
+            self.data.len()
+        }
+    }
+
+    /// An LRU cache with hash map backing and an eviction rule based on the memory usage for object data in bytes,
+    /// storing `T` along with the data of each object.
+    pub(crate) struct Cache<K, T>(memory_lru::MemoryLruCache<K, Entry<T>>);
+
+    impl<K: Eq + Hash, T: Copy> Cache<K, T> {
+        pub fn new(memory_cap_in_bytes: usize) -> Self {
+            Cache(memory_lru::MemoryLruCache::new(memory_cap_in_bytes))
+        }
+
+        pub fn memory_usage_in_bytes(&self) -> usize {
+            self.0.current_size()
+        }
+
+        pub fn put(&mut self, key: K, data: &[u8], info: T) {
+            self.0.insert(
+                key,
+                Entry {
+                    data: Vec::from(data),
+                    info,
+                },
+            )
+        }
+
+        pub fn get(&mut self, key: &K, out: &mut Vec<u8>) -> Option<T> {
+            self.0.get(key).map(|e| {
+                out.resize(e.data.len(), 0);
+                out.copy_from_slice(&e.data);
+                e.info
+            })
+        }
+    }
+}
+
+/// Implementations of [`Object`] caches.
+#[cfg(feature = "object-cache-dynamic")]
+pub mod object {
+    use super::memory_capped;
+
+    /// An LRU cache with hash map backing and an eviction rule based on the memory usage for object data in bytes.
+    pub struct MemoryCappedHashmap(memory_capped::Cache<git_hash::ObjectId, git_object::Kind>);
+
+    impl MemoryCappedHashmap {
+        /// Return a new instance which evicts least recently used objects if it uses more than `memory_cap_in_bytes`
+        /// object data.
+        pub fn new(memory_cap_in_bytes: usize) -> MemoryCappedHashmap {
+            MemoryCappedHashmap(memory_capped::Cache::new(memory_cap_in_bytes))
+        }
+
+        /// Return the amount of bytes of object data currently held in the cache.
+        pub fn memory_usage_in_bytes(&self) -> usize {
+            self.0.memory_usage_in_bytes()
+        }
+    }
+
+    impl super::Object for MemoryCappedHashmap {
+        fn put(&mut self, id: git_hash::ObjectId, kind: git_object::Kind, data: &[u8]) {
+            self.0.put(id, data, kind)
+        }
+
+        fn get(&mut self, id: &git_hash::ObjectId, out: &mut Vec<u8>) -> Option<git_object::Kind> {
+            self.0.get(id, out)
+        }
+    }
+}
+
 /// Various implementations of [`DecodeEntry`] using least-recently-used algorithms.
 #[cfg(any(feature = "pack-cache-lru-dynamic", feature = "pack-cache-lru-static"))]
 pub mod lru {
@@ -30,47 +134,31 @@ pub mod lru {
     #[cfg(feature = "pack-cache-lru-dynamic")]
     mod memory {
         use super::DecodeEntry;
-        struct Entry {
-            data: Vec<u8>,
-            kind: git_object::Kind,
-            compressed_size: usize,
-        }
-
-        impl memory_lru::ResidentSize for Entry {
-            fn resident_size(&self) -> usize {
-                self.data.len()
-            }
-        }
+        use crate::cache::memory_capped;
 
         /// An LRU cache with hash map backing and an eviction rule based on the memory usage for object data in bytes.
-        pub struct MemoryCappedHashmap(memory_lru::MemoryLruCache<(u32, u64), Entry>);
+        pub struct MemoryCappedHashmap(memory_capped::Cache<(u32, u64), (git_object::Kind, usize)>);
 
         impl MemoryCappedHashmap {
             /// Return a new instance which evicts least recently used items if it uses more than `memory_cap_in_bytes`
             /// object data.
             pub fn new(memory_cap_in_bytes: usize) -> MemoryCappedHashmap {
-                MemoryCappedHashmap(memory_lru::MemoryLruCache::new(memory_cap_in_bytes))
+                MemoryCappedHashmap(memory_capped::Cache::new(memory_cap_in_bytes))
+            }
+
+            /// Return the amount of bytes of object data currently held in the cache.
+            pub fn memory_usage_in_bytes(&self) -> usize {
+                self.0.memory_usage_in_bytes()
             }
         }
 
         impl DecodeEntry for MemoryCappedHashmap {
             fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: git_object::Kind, compressed_size: usize) {
-                self.0.insert(
-                    (pack_id, offset),
-                    Entry {
-                        data: Vec::from(data),
-                        kind,
-                        compressed_size,
-                    },
-                )
+                self.0.put((pack_id, offset), data, (kind, compressed_size))
             }
 
             fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(git_object::Kind, usize)> {
-                self.0.get(&(pack_id, offset)).map(|e| {
-                    out.resize(e.data.len(), 0);
-                    out.copy_from_slice(&e.data);
-                    (e.kind, e.compressed_size)
-                })
+                self.0.get(&(pack_id, offset), out)
             }
         }
     }
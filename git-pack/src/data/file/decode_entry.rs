@@ -90,6 +90,23 @@ impl File {
         self.decompress_entry_from_data_offset(entry.data_offset, out)
     }
 
+    /// Return a reader to stream the decompressed data of the given base object `entry` without holding it in memory at once,
+    /// or `None` if `entry` is a delta object.
+    ///
+    /// Deltified objects can only be obtained in full with [`decode_entry()`][File::decode_entry()] as they have to
+    /// be applied to their base object, which needs all data to be present in memory.
+    pub fn stream_entry(&self, entry: &crate::data::Entry) -> Option<zlib::stream::inflate::ReadBoxed<&[u8]>> {
+        if entry.header.is_delta() {
+            return None;
+        }
+        let offset: usize = entry.data_offset.try_into().expect("offset representable by machine");
+        assert!(offset < self.data.len(), "entry offset out of bounds");
+        Some(zlib::stream::inflate::ReadBoxed {
+            inner: &self.data[offset..],
+            decompressor: Box::new(zlib::Decompress::new(true)),
+        })
+    }
+
     fn assure_v2(&self) {
         assert!(
             matches!(self.version, crate::data::Version::V2),
@@ -106,7 +123,7 @@ impl File {
         assert!(pack_offset <= self.data.len(), "offset out of bounds");
 
         let object_data = &self.data[pack_offset..];
-        crate::data::Entry::from_bytes(object_data, offset)
+        crate::data::Entry::from_bytes(object_data, offset, self.hash_len)
     }
 
     /// Decompress the object expected at the given data offset, sans pack header. This information is only
@@ -1,29 +1,33 @@
 use crate::data;
 use filebuffer::FileBuffer;
-use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use std::{convert::TryFrom, convert::TryInto, path::Path};
 
 /// Instantiation
 impl data::File {
-    /// Try opening a data file at the given `path`.
+    /// Try opening a data file at the given `path`, assuming it uses Sha1 hashes.
     pub fn at(path: impl AsRef<Path>) -> Result<data::File, data::header::decode::Error> {
         data::File::try_from(path.as_ref())
     }
-}
-
-impl TryFrom<&Path> for data::File {
-    type Error = data::header::decode::Error;
 
-    /// Try opening a data file at the given `path`.
-    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+    /// Try opening a data file at the given `path` which uses hashes of the given `hash_kind`.
+    ///
+    /// As pack data files don't store the kind of hash they use, it's typically obtained from the
+    /// [pack index][crate::index::File::hash_kind()].
+    pub fn at_with_hash_kind(
+        path: impl AsRef<Path>,
+        hash_kind: git_hash::Kind,
+    ) -> Result<data::File, data::header::decode::Error> {
         use crate::data::header::N32_SIZE;
 
+        let path = path.as_ref();
+        let hash_len = hash_kind.len_in_bytes();
+
         let data = FileBuffer::open(path).map_err(|e| data::header::decode::Error::Io {
             source: e,
             path: path.to_owned(),
         })?;
         let pack_len = data.len();
-        if pack_len < N32_SIZE * 3 + SHA1_SIZE {
+        if pack_len < N32_SIZE * 3 + hash_len {
             return Err(data::header::decode::Error::Corrupt(format!(
                 "Pack data of size {} is too small for even an empty pack",
                 pack_len
@@ -37,6 +41,17 @@ impl TryFrom<&Path> for data::File {
             id: git_features::hash::crc32(path.as_os_str().to_string_lossy().as_bytes()),
             version: kind,
             num_objects,
+            hash_kind,
+            hash_len,
         })
     }
 }
+
+impl TryFrom<&Path> for data::File {
+    type Error = data::header::decode::Error;
+
+    /// Try opening a data file at the given `path`, assuming it uses Sha1 hashes.
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        data::File::at_with_hash_kind(path, git_hash::Kind::Sha1)
+    }
+}
@@ -1,6 +1,5 @@
 use crate::data::File;
 use git_features::progress::Progress;
-use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use std::sync::atomic::AtomicBool;
 
 /// Returned by [`File::verify_checksum()`]
@@ -14,13 +13,15 @@ pub enum Error {
     },
     #[error("could not read pack file")]
     Io(#[from] std::io::Error),
+    #[error("Cannot compute {kind:?} hashes to verify the pack checksum")]
+    UnsupportedHashKind { kind: git_hash::Kind },
 }
 
 /// Checksums and verify checksums
 impl File {
     /// The checksum in the trailer of this pack data file
     pub fn checksum(&self) -> git_hash::ObjectId {
-        git_hash::ObjectId::from_20_bytes(&self.data[self.data.len() - SHA1_SIZE..])
+        git_hash::ObjectId::from_bytes(&self.data[self.data.len() - self.hash_len..])
     }
 
     /// Verifies that the checksum of the packfile over all bytes preceding it indeed matches the actual checksum,
@@ -36,7 +37,10 @@ impl File {
         mut progress: impl Progress,
         should_interrupt: &AtomicBool,
     ) -> Result<git_hash::ObjectId, Error> {
-        let right_before_trailer = self.data.len() - SHA1_SIZE;
+        if self.hash_kind != git_hash::Kind::Sha1 {
+            return Err(Error::UnsupportedHashKind { kind: self.hash_kind });
+        }
+        let right_before_trailer = self.data.len() - self.hash_len;
         let actual = match git_features::hash::bytes_of_file(
             &self.path,
             right_before_trailer,
@@ -4,6 +4,7 @@ use git_features::{
     hash::Sha1,
     zlib::{stream::inflate::ReadBoxed, Decompress},
 };
+use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use std::{fs, io};
 
 /// An iterator over [`Entries`][input::Entry] in a byte stream.
@@ -86,11 +87,11 @@ where
                         hash,
                     },
                 );
-                let res = crate::data::Entry::from_read(&mut read, self.offset);
+                let res = crate::data::Entry::from_read(&mut read, self.offset, SHA1_SIZE);
                 self.hash = Some(read.write.hash);
                 res
             }
-            None => crate::data::Entry::from_read(&mut self.read, self.offset),
+            None => crate::data::Entry::from_read(&mut self.read, self.offset, SHA1_SIZE),
         }
         .map_err(input::Error::from)?;
 
@@ -3,8 +3,6 @@ use std::{convert::TryInto, path::Path};
 
 use filebuffer::FileBuffer;
 
-use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
-
 mod file;
 pub use file::{decode_entry, verify, ResolvedBase};
 ///
@@ -57,6 +55,8 @@ pub struct File {
     pub id: u32,
     version: Version,
     num_objects: u32,
+    hash_kind: git_hash::Kind,
+    hash_len: usize,
 }
 
 /// Information about the pack data file itself
@@ -69,6 +69,10 @@ impl File {
     pub fn num_objects(&self) -> u32 {
         self.num_objects
     }
+    /// The kind of hash used for the trailing checksum and for the base objects of ref deltas
+    pub fn hash_kind(&self) -> git_hash::Kind {
+        self.hash_kind
+    }
     /// The length of all mapped data, including the pack header and the pack trailer
     pub fn data_len(&self) -> usize {
         self.data.len()
@@ -76,7 +80,7 @@ impl File {
 
     /// The position of the byte one past the last pack entry, or in other terms, the first byte of the trailing hash.
     pub fn pack_end(&self) -> usize {
-        self.data.len() - SHA1_SIZE
+        self.data.len() - self.hash_len
     }
 
     /// The path to the pack data file on disk
@@ -79,6 +79,11 @@ pub mod verify {
             desired: git_hash::ObjectId,
             actual: git_hash::ObjectId,
         },
+        #[error("Cannot compute {kind:?} hashes to verify object {desired}")]
+        UnsupportedHashKind {
+            kind: git_hash::Kind,
+            desired: git_hash::ObjectId,
+        },
     }
 
     impl crate::data::Object<'_> {
@@ -87,7 +92,10 @@ pub mod verify {
         /// hash of `self`.
         pub fn verify_checksum(&self, desired: impl AsRef<git_hash::oid>) -> Result<(), Error> {
             let desired = desired.as_ref();
-            let mut sink = hash::Write::new(io::sink(), desired.kind());
+            let mut sink = hash::Write::new(io::sink(), desired.kind()).map_err(|_| Error::UnsupportedHashKind {
+                kind: desired.kind(),
+                desired: desired.into(),
+            })?;
 
             loose::object::header::encode(self.kind, self.data.len() as u64, &mut sink).expect("hash to always work");
             sink.hash.update(&self.data);
@@ -29,6 +29,10 @@ pub struct FromEntriesIter<I, W> {
     header_info: Option<(crate::data::Version, u32)>,
     /// The pack data version with which pack entries should be written.
     entry_version: crate::data::Version,
+    /// The pack offset of each entry written so far, to allow computing the distance to base objects of deltas.
+    pack_offsets: Vec<u64>,
+    /// The amount of bytes written so far, which is the pack offset of the next entry.
+    written: u64,
     /// If we are done, no additional writes will occour
     is_done: bool,
 }
@@ -66,10 +70,12 @@ where
         );
         FromEntriesIter {
             input,
-            output: hash::Write::new(output, hash_kind),
+            output: hash::Write::new(output, hash_kind).expect("Sha1 is supported, as asserted above"),
             trailer: None,
             entry_version: version,
             header_info: Some((version, num_entries)),
+            pack_offsets: Vec::with_capacity(num_entries as usize),
+            written: 0,
             is_done: false,
         }
     }
@@ -97,9 +103,13 @@ where
         match self.input.next() {
             Some(entries) => {
                 for entry in entries.map_err(Error::Input)? {
-                    let header = entry.to_entry_header(self.entry_version, |_index_offset| {
-                        unimplemented!("a way to calculate pack offsets from object index offsets")
+                    let pack_offset = self.written + written;
+                    let pack_offsets = &self.pack_offsets;
+                    let header = entry.to_entry_header(self.entry_version, |nth_before| {
+                        let base_offset = pack_offsets[pack_offsets.len() - nth_before];
+                        pack_offset - base_offset
                     });
+                    self.pack_offsets.push(pack_offset);
                     written += header.write_to(entry.decompressed_size as u64, &mut self.output)? as u64;
                     written += std::io::copy(&mut &*entry.compressed_data, &mut self.output)? as u64;
                 }
@@ -113,6 +123,7 @@ where
                 self.trailer = Some(git_hash::ObjectId::from(digest));
             }
         };
+        self.written += written;
         Ok(written)
     }
 }
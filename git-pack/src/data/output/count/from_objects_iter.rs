@@ -3,7 +3,7 @@ use dashmap::DashSet;
 use git_features::{parallel, progress::Progress};
 use git_hash::{oid, ObjectId};
 use git_object::immutable;
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 /// Generate [`Count`][output::Count] from input `objects` with object expansion based on [`options`][Options]
 /// to learn which objects would be part of a pack.
@@ -15,6 +15,9 @@ use std::sync::Arc;
 /// * `objects_ids`
 ///   * A list of objects ids to add to the pack. Duplication checks are performed so no object is ever added to a pack twice.
 ///   * Objects may be expanded based on the provided [`options`][Options]
+/// * `shallow_commits`
+///   * The boundary commits of a shallow clone, whose parents are not present in `db` and are not compared to. Other missing
+///     objects are errors.
 /// * `progress`
 ///   * a way to obtain progress information
 /// * `options`
@@ -23,6 +26,7 @@ pub fn from_objects_iter<Find, Iter, Oid, Cache>(
     db: Find,
     make_cache: impl Fn() -> Cache + Send + Clone + Sync + 'static,
     objects_ids: Iter,
+    shallow_commits: impl IntoIterator<Item = ObjectId>,
     progress: impl Progress,
     Options {
         thread_limit,
@@ -50,6 +54,7 @@ where
         size: chunk_size,
     };
     let seen_objs = Arc::new(dashmap::DashSet::<ObjectId>::new());
+    let shallow_commits = Arc::new(shallow_commits.into_iter().collect::<BTreeSet<_>>());
     let progress = Arc::new(parking_lot::Mutex::new(progress));
 
     parallel::reduce::Stepwise::new(
@@ -72,6 +77,7 @@ where
         },
         {
             let seen_objs = Arc::clone(&seen_objs);
+            let shallow_commits = Arc::clone(&shallow_commits);
             move |oids: Vec<Oid>, (buf1, buf2, cache, progress)| {
                 use ObjectExpansion::*;
                 let mut out = Vec::new();
@@ -111,13 +117,15 @@ where
                                             let mut commit_iter = immutable::CommitIter::from_bytes(obj.data);
                                             let tree_id = commit_iter.tree_id().expect("every commit has a tree");
                                             parent_commit_ids.clear();
-                                            for token in commit_iter {
-                                                match token {
-                                                    Ok(immutable::commit::iter::Token::Parent { id }) => {
-                                                        parent_commit_ids.push(id)
+                                            if !shallow_commits.contains(&id) {
+                                                for token in commit_iter {
+                                                    match token {
+                                                        Ok(immutable::commit::iter::Token::Parent { id }) => {
+                                                            parent_commit_ids.push(id)
+                                                        }
+                                                        Ok(_) => break,
+                                                        Err(err) => return Err(Error::CommitDecode(err)),
                                                     }
-                                                    Ok(_) => break,
-                                                    Err(err) => return Err(Error::CommitDecode(err)),
                                                 }
                                             }
                                             let obj = db.find_existing(tree_id, buf1, cache)?;
@@ -471,6 +479,9 @@ mod types {
         /// current tree and turn them into entries as well.
         /// Otherwise, the same as [`AsIs`][ObjectExpansion::AsIs].
         ///
+        /// Shallow commits aren't compared to their ancestors, which aren't present, and are handled like in
+        /// [`TreeContents`][ObjectExpansion::TreeContents] instead.
+        ///
         /// This mode is useful to build a pack containing only new objects compared to a previous state.
         TreeAdditionsComparedToAncestor,
     }
@@ -44,6 +44,7 @@ pub fn from_counts_iter<Find, Cache>(
         version,
         thread_limit,
         chunk_size,
+        compression,
     }: Options,
 ) -> impl Iterator<Item = Result<(ChunkId, Vec<output::Entry>), Error<find::existing::Error<Find::Error>>>>
        + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error<find::existing::Error<Find::Error>>>>
@@ -94,13 +95,13 @@ where
                                 None => {
                                     let obj = db.find_existing(count.id, buf, cache).map_err(Error::FindExisting)?;
                                     stats.decoded_and_recompressed_objects += 1;
-                                    output::Entry::from_data(count, &obj)
+                                    output::Entry::from_data(count, &obj, compression)
                                 }
                             },
                             None => {
                                 let obj = db.find_existing(count.id, buf, cache).map_err(Error::FindExisting)?;
                                 stats.decoded_and_recompressed_objects += 1;
-                                output::Entry::from_data(count, &obj)
+                                output::Entry::from_data(count, &obj, compression)
                             }
                         }?,
                     );
@@ -223,6 +224,8 @@ mod types {
         pub chunk_size: usize,
         /// The pack data version to produce
         pub version: crate::data::Version,
+        /// How to compress objects that can't be copied from an existing pack.
+        pub compression: git_features::zlib::Compression,
     }
 
     impl Default for Options {
@@ -231,6 +234,7 @@ mod types {
                 thread_limit: None,
                 chunk_size: 10,
                 version: Default::default(),
+                compression: Default::default(),
             }
         }
     }
@@ -70,7 +70,7 @@ impl output::Entry {
             return None;
         };
 
-        let pack_entry = crate::data::Entry::from_bytes(entry.data, 0);
+        let pack_entry = crate::data::Entry::from_bytes(entry.data, 0, git_hash::SIZE_OF_SHA1_DIGEST);
         if let Some(expected) = entry.crc32 {
             let actual = hash::crc32(entry.data);
             if actual != expected {
@@ -90,15 +90,19 @@ impl output::Entry {
         }
     }
 
-    /// Create a new instance from the given `oid` and its corresponding git `obj`ect data.
-    pub fn from_data(count: &output::Count, obj: &data::Object<'_>) -> Result<Self, Error> {
+    /// Create a new instance from the given `oid` and its corresponding git `obj`ect data, compressed according to `compression`.
+    pub fn from_data(
+        count: &output::Count,
+        obj: &data::Object<'_>,
+        compression: git_features::zlib::Compression,
+    ) -> Result<Self, Error> {
         Ok(output::Entry {
             id: count.id.to_owned(),
             object_kind: obj.kind,
             kind: Kind::Base,
             decompressed_size: obj.data.len(),
             compressed_data: {
-                let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+                let mut out = git_features::zlib::stream::deflate::Write::with_compression(Vec::new(), compression);
                 if let Err(err) = std::io::copy(&mut &*obj.data, &mut out) {
                     match err.kind() {
                         std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
@@ -113,8 +117,8 @@ impl output::Entry {
 
     /// Transform ourselves into pack entry header of `version` which can be written into a pack.
     ///
-    /// `index_to_pack(nth_before) -> pack_offset` is a function to convert the base object's offset as index into an
-    /// array to an offset into the pack. This information is known to the one calling the method.
+    /// `index_to_pack(nth_before) -> base_distance` is a function to convert the base object's offset as index into an
+    /// array to the distance in bytes between this entry and its base in the pack. This information is known to the one calling the method.
     pub fn to_entry_header(
         &self,
         version: crate::data::Version,
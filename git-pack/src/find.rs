@@ -117,6 +117,28 @@ mod ext {
                 })
         }
 
+        /// Like [`find(…)`][super::Find::find()], but first consults `object_cache` for fully decoded objects and puts
+        /// objects which were found in the database into it.
+        ///
+        /// Objects served by the cache have no [pack location][data::Object::pack_location].
+        fn find_cached<'a>(
+            &self,
+            id: impl AsRef<git_hash::oid>,
+            buffer: &'a mut Vec<u8>,
+            pack_cache: &mut impl crate::cache::DecodeEntry,
+            object_cache: &mut impl crate::cache::Object,
+        ) -> Result<Option<data::Object<'a>>, Self::Error> {
+            let id = id.as_ref().to_owned();
+            if let Some(kind) = object_cache.get(&id, buffer) {
+                return Ok(Some(data::Object::new(kind, buffer)));
+            }
+            let object = self.find(id, buffer, pack_cache)?;
+            if let Some(object) = &object {
+                object_cache.put(id, object.kind, object.data);
+            }
+            Ok(object)
+        }
+
         make_obj_lookup!(
             find_existing_commit,
             immutable::Object::Commit,
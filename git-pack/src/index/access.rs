@@ -1,6 +1,5 @@
 use crate::index::{self, FAN_LEN};
 use byteorder::{BigEndian, ByteOrder};
-use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use std::{convert::TryInto, mem::size_of};
 
 const N32_SIZE: usize = size_of::<u32>();
@@ -31,12 +30,12 @@ impl index::File {
     pub(crate) fn iter_v1(&self) -> impl Iterator<Item = Entry> + '_ {
         match self.version {
             index::Version::V1 => self.data[V1_HEADER_SIZE..]
-                .chunks(N32_SIZE + SHA1_SIZE)
+                .chunks(N32_SIZE + self.hash_len)
                 .take(self.num_objects as usize)
                 .map(|c| {
                     let (ofs, oid) = c.split_at(N32_SIZE);
                     Entry {
-                        oid: git_hash::ObjectId::from_20_bytes(oid),
+                        oid: git_hash::ObjectId::from_bytes(oid),
                         pack_offset: BigEndian::read_u32(ofs) as u64,
                         crc32: None,
                     }
@@ -49,13 +48,13 @@ impl index::File {
         let pack64_offset = self.offset_pack_offset64_v2();
         match self.version {
             index::Version::V2 => izip!(
-                self.data[V2_HEADER_SIZE..].chunks(SHA1_SIZE),
+                self.data[V2_HEADER_SIZE..].chunks(self.hash_len),
                 self.data[self.offset_crc32_v2()..].chunks(N32_SIZE),
                 self.data[self.offset_pack_offset_v2()..].chunks(N32_SIZE)
             )
             .take(self.num_objects as usize)
             .map(move |(oid, crc32, ofs32)| Entry {
-                oid: git_hash::ObjectId::from_20_bytes(oid),
+                oid: git_hash::ObjectId::from_bytes(oid),
                 pack_offset: self.pack_offset_from_offset_v2(ofs32, pack64_offset),
                 crc32: Some(BigEndian::read_u32(crc32)),
             }),
@@ -63,7 +62,7 @@ impl index::File {
         }
     }
 
-    /// Returns 20 bytes sha1 at the given index in our list of (sorted) sha1 hashes.
+    /// Returns the object hash at the given index in our list of (sorted) hashes.
     /// The index ranges from 0 to self.num_objects()
     ///
     /// # Panics
@@ -74,10 +73,11 @@ impl index::File {
             .try_into()
             .expect("an architecture able to hold 32 bits of integer");
         let start = match self.version {
-            index::Version::V2 => V2_HEADER_SIZE + index * SHA1_SIZE,
-            index::Version::V1 => V1_HEADER_SIZE + index * (N32_SIZE + SHA1_SIZE) + N32_SIZE,
+            index::Version::V2 => V2_HEADER_SIZE + index * self.hash_len,
+            index::Version::V1 => V1_HEADER_SIZE + index * (N32_SIZE + self.hash_len) + N32_SIZE,
         };
-        git_hash::oid::try_from(&self.data[start..start + SHA1_SIZE]).expect("20 bytes SHA1 to be alright")
+        git_hash::oid::try_from(&self.data[start..start + self.hash_len])
+            .expect("a hash of the size we inferred to be alright")
     }
 
     /// Returns the offset into our pack data file at which to start reading the object at `index`.
@@ -95,7 +95,7 @@ impl index::File {
                 self.pack_offset_from_offset_v2(&self.data[start..start + N32_SIZE], self.offset_pack_offset64_v2())
             }
             index::Version::V1 => {
-                let start = V1_HEADER_SIZE + index * (N32_SIZE + SHA1_SIZE);
+                let start = V1_HEADER_SIZE + index * (N32_SIZE + self.hash_len);
                 BigEndian::read_u32(&self.data[start..start + N32_SIZE]) as u64
             }
         }
@@ -120,7 +120,7 @@ impl index::File {
         }
     }
 
-    /// Returns the `index` of the given SHA1 for use with the [`oid_at_index()`][index::File::oid_at_index()],
+    /// Returns the `index` of the given hash for use with the [`oid_at_index()`][index::File::oid_at_index()],
     /// [`pack_offset_at_index()`][index::File::pack_offset_at_index()] or [`crc32_at_index()`][index::File::crc32_at_index()].
     pub fn lookup(&self, id: impl AsRef<git_hash::oid>) -> Option<u32> {
         let id = id.as_ref();
@@ -146,7 +146,7 @@ impl index::File {
         None
     }
 
-    /// An iterator over all [`Entries`][Entry] of this index file.
+    /// An iterator over all [`Entries`][Entry] of this index file in index order, i.e. sorted by object id.
     pub fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = Entry> + 'a> {
         match self.version {
             index::Version::V2 => Box::new(self.iter_v2()),
@@ -154,6 +154,22 @@ impl index::File {
         }
     }
 
+    /// An iterator over all [`Entries`][Entry] of this index file in pack order, i.e. sorted by ascending pack offset,
+    /// which is the order in which entries are best read from the pack data file.
+    ///
+    /// Note that all pack offsets are collected and sorted before the first entry is returned.
+    pub fn iter_in_pack_order(&self) -> impl Iterator<Item = Entry> + '_ {
+        let mut offsets_and_indices: Vec<_> = (0..self.num_objects)
+            .map(|index| (self.pack_offset_at_index(index), index))
+            .collect();
+        offsets_and_indices.sort_unstable();
+        offsets_and_indices.into_iter().map(move |(pack_offset, index)| Entry {
+            oid: self.oid_at_index(index).to_owned(),
+            pack_offset,
+            crc32: self.crc32_at_index(index),
+        })
+    }
+
     /// Return a vector of ascending offsets into our respective pack data file.
     ///
     /// Useful to control an iteration over all pack entries in a cache-friendly way.
@@ -176,7 +192,7 @@ impl index::File {
     }
 
     fn offset_crc32_v2(&self) -> usize {
-        V2_HEADER_SIZE + self.num_objects as usize * SHA1_SIZE
+        V2_HEADER_SIZE + self.num_objects as usize * self.hash_len
     }
 
     fn offset_pack_offset_v2(&self) -> usize {
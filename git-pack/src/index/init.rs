@@ -20,6 +20,7 @@ pub enum Error {
 }
 
 const N32_SIZE: usize = size_of::<u32>();
+const N64_SIZE: usize = size_of::<u64>();
 const FOOTER_SIZE: usize = SHA1_SIZE * 2;
 
 /// Instantiation
@@ -71,16 +72,43 @@ impl TryFrom<&Path> for index::File {
 
             (kind, fan, num_objects)
         };
+        let hash_kind = hash_kind_from_len(kind, num_objects, idx_len);
         Ok(index::File {
             data,
             path: path.to_owned(),
             version: kind,
             num_objects,
             fan,
+            hash_kind,
+            hash_len: hash_kind.len_in_bytes(),
         })
     }
 }
 
+/// The index format doesn't store the kind of hash it uses, so infer it from the length of the index file in bytes, `idx_len`.
+///
+/// This is unambiguous as each Sha256 entry is 12 bytes larger than a Sha1 entry, which can't be made up for by the
+/// 8 byte large offsets of which there is at most one per object. Falls back to Sha1 if the length matches neither layout.
+fn hash_kind_from_len(version: Version, num_objects: u32, idx_len: usize) -> git_hash::Kind {
+    let num_objects = num_objects as usize;
+    let sha256_len = git_hash::Kind::Sha256.len_in_bytes();
+    let is_sha256 = match version {
+        Version::V1 => idx_len == FAN_LEN * N32_SIZE + num_objects * (N32_SIZE + sha256_len) + sha256_len * 2,
+        Version::V2 => {
+            let fixed_len =
+                N32_SIZE * 2 + FAN_LEN * N32_SIZE + num_objects * (sha256_len + N32_SIZE * 2) + sha256_len * 2;
+            idx_len.checked_sub(fixed_len).map_or(false, |large_offsets_len| {
+                large_offsets_len % N64_SIZE == 0 && large_offsets_len / N64_SIZE <= num_objects
+            })
+        }
+    };
+    if is_sha256 {
+        git_hash::Kind::Sha256
+    } else {
+        git_hash::Kind::Sha1
+    }
+}
+
 fn read_fan(d: &[u8]) -> ([u32; FAN_LEN], usize) {
     let mut fan = [0; FAN_LEN];
     for (c, f) in d.chunks(N32_SIZE).zip(fan.iter_mut()) {
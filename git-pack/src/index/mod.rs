@@ -108,6 +108,8 @@ pub struct File {
     version: Version,
     num_objects: u32,
     fan: [u32; FAN_LEN],
+    hash_kind: git_hash::Kind,
+    hash_len: usize,
 }
 
 /// Basic file information
@@ -124,6 +126,10 @@ impl File {
     pub fn num_objects(&self) -> u32 {
         self.num_objects
     }
+    /// The kind of hash used for object ids and checksums in this index, as well as in its pack
+    pub fn hash_kind(&self) -> git_hash::Kind {
+        self.hash_kind
+    }
 }
 
 const V2_SIGNATURE: &[u8] = b"\xfftOc";
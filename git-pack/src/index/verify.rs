@@ -1,5 +1,4 @@
 use git_features::progress::{self, Progress};
-use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use git_object::{
     bstr::{BString, ByteSlice},
     immutable::object,
@@ -33,6 +32,8 @@ pub enum Error {
     },
     #[error(transparent)]
     ObjectEncode(#[from] std::io::Error),
+    #[error("Cannot compute {kind:?} hashes to verify the index checksum")]
+    UnsupportedHashKind { kind: git_hash::Kind },
 }
 
 /// Various ways in which a pack and index can be verified
@@ -54,15 +55,15 @@ impl index::File {
     ///
     /// It's a hash over all bytes of the index.
     pub fn index_checksum(&self) -> git_hash::ObjectId {
-        git_hash::ObjectId::from_20_bytes(&self.data[self.data.len() - SHA1_SIZE..])
+        git_hash::ObjectId::from_bytes(&self.data[self.data.len() - self.hash_len..])
     }
 
     /// Returns the hash of the pack data file that this index file corresponds to.
     ///
     /// It should [`crate::data::File::checksum()`] of the corresponding pack data file.
     pub fn pack_checksum(&self) -> git_hash::ObjectId {
-        let from = self.data.len() - SHA1_SIZE * 2;
-        git_hash::ObjectId::from_20_bytes(&self.data[from..from + SHA1_SIZE])
+        let from = self.data.len() - self.hash_len * 2;
+        git_hash::ObjectId::from_bytes(&self.data[from..from + self.hash_len])
     }
 
     /// Validate that our [`index_checksum()`][index::File::index_checksum()] matches the actual contents
@@ -72,7 +73,10 @@ impl index::File {
         mut progress: impl Progress,
         should_interrupt: &AtomicBool,
     ) -> Result<git_hash::ObjectId, Error> {
-        let data_len_without_trailer = self.data.len() - SHA1_SIZE;
+        if self.hash_kind != git_hash::Kind::Sha1 {
+            return Err(Error::UnsupportedHashKind { kind: self.hash_kind });
+        }
+        let data_len_without_trailer = self.data.len() - self.hash_len;
         let actual = match git_features::hash::bytes_of_file(
             &self.path,
             data_len_without_trailer,
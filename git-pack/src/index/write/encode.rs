@@ -27,7 +27,7 @@ pub(crate) fn write_to(
     // Write header
     let mut out = Count::new(std::io::BufWriter::with_capacity(
         8 * 4096,
-        hash::Write::new(out, kind.hash()),
+        hash::Write::new(out, kind.hash())?,
     ));
     out.write_all(V2_SIGNATURE)?;
     out.write_u32::<BigEndian>(kind as u32)?;
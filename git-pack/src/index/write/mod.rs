@@ -179,7 +179,7 @@ impl crate::index::File {
                      decompressed: bytes,
                      ..
                  }| {
-                    modify_base(data, entry, bytes, kind.hash());
+                    modify_base(data, entry, bytes, kind.hash())?;
                     Ok::<_, Error>(())
                 },
             )?;
@@ -221,16 +221,20 @@ fn modify_base(
     pack_entry: &crate::data::Entry,
     decompressed: &[u8],
     hash: git_hash::Kind,
-) {
-    fn compute_hash(kind: git_object::Kind, bytes: &[u8], hash_kind: git_hash::Kind) -> git_hash::ObjectId {
-        let mut write = git_features::hash::Write::new(io::sink(), hash_kind);
-        loose::object::header::encode(kind, bytes.len() as u64, &mut write)
-            .expect("write to sink and hash cannot fail");
-        write.hash.update(bytes);
-        git_hash::ObjectId::from(write.hash.digest())
-    }
-
+) -> io::Result<()> {
     let object_kind = pack_entry.header.as_kind().expect("base object as source of iteration");
-    let id = compute_hash(object_kind, &decompressed, hash);
-    entry.id = id;
+    entry.id = compute_hash(object_kind, &decompressed, hash)?;
+    Ok(())
+}
+
+/// Compute the id of an object of `kind` with the given decompressed `bytes`, using a hash of `hash_kind`.
+pub(crate) fn compute_hash(
+    kind: git_object::Kind,
+    bytes: &[u8],
+    hash_kind: git_hash::Kind,
+) -> io::Result<git_hash::ObjectId> {
+    let mut write = git_features::hash::Write::new(io::sink(), hash_kind)?;
+    loose::object::header::encode(kind, bytes.len() as u64, &mut write).expect("write to sink and hash cannot fail");
+    write.hash.update(bytes);
+    Ok(git_hash::ObjectId::from(write.hash.digest()))
 }
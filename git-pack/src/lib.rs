@@ -4,7 +4,8 @@
 //! is what git packs are concerned about.
 //!
 //! Packs consist of [data files][data::File] and [index files][index::File]. The latter can be generated from a data file
-//! and make accessing objects within a pack feasible.
+//! and make accessing objects within a pack feasible. Optional [bitmap files][bitmap::File] provide the set of objects
+//! reachable from selected commits.
 //!
 //! A [Bundle] conveniently combines a data pack alongside its index to allow [finding][Find] objects or verifying the pack.
 //! Objects returned by `.find(…)` are [objects][data::Object] which know their pack location in order to speed up
@@ -22,6 +23,8 @@ pub mod find;
 pub use find::{Find, FindExt};
 #[doc(inline)]
 
+///
+pub mod bitmap;
 ///
 pub mod cache;
 ///
@@ -1,5 +1,6 @@
 use crate::{index::access::PackOffset, tree::Tree};
 use git_features::progress::{self, Progress};
+use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use std::{
     convert::TryFrom,
     fs, io,
@@ -82,7 +83,7 @@ impl<T> Tree<T> {
             if let Some(previous_offset) = previous_cursor_position {
                 Self::advance_cursor_to_pack_offset(&mut r, pack_offset, previous_offset)?;
             };
-            let entry = crate::data::Entry::from_read(&mut r, pack_offset).map_err(|err| Error::Io {
+            let entry = crate::data::Entry::from_read(&mut r, pack_offset, SHA1_SIZE).map_err(|err| Error::Io {
                 source: err,
                 message: "EOF while parsing header",
             })?;
@@ -26,7 +26,7 @@ where
         resolve(slice.clone(), &mut bytes_buf).ok_or(Error::ResolveFailed {
             pack_offset: slice.start,
         })?;
-        let entry = crate::data::Entry::from_bytes(&bytes_buf, slice.start);
+        let entry = crate::data::Entry::from_bytes(&bytes_buf, slice.start, git_hash::SIZE_OF_SHA1_DIGEST);
         let compressed = &bytes_buf[entry.header_size() as usize..];
         let decompressed_len = entry.decompressed_size as usize;
         Ok((entry, slice.end, decompress_all_at_once(compressed, decompressed_len)?))
@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config commit.gpgsign false
+
+for i in 1 2 3; do
+  mkdir -p dir$i
+  echo $i > dir$i/file
+  git add dir$i
+  git commit -q -m c$i
+done
+git tag -a -m "annotated" v1
+
+git repack -adbq
+
+git rev-parse HEAD > head
+git rev-parse HEAD:dir2/file > blob-at-dir2-file
+git rev-list --objects HEAD | cut -d' ' -f1 > objects-reachable-from-head
+git rev-parse v1 > tag
+git rev-parse HEAD~1 > parent-of-head
+git rev-list --objects v1 --not HEAD~1 | cut -d' ' -f1 > objects-reachable-from-tag-but-not-parent-of-head
@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git config commit.gpgsign false
+
+  for i in 1 2 3 4; do
+    mkdir -p dir$i
+    echo $i > dir$i/file
+    echo $i > file
+    git add .
+    git commit -q -m c$i
+  done
+)
+
+git clone -q --depth 2 "file://$PWD/remote" shallow
+(cd shallow
+  git rev-list HEAD > commits
+  git rev-list --objects HEAD | cut -d' ' -f1 | sort > objects-reachable-from-head
+)
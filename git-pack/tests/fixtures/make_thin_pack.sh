@@ -0,0 +1,20 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config commit.gpgsign false
+
+seq 1 1000 > file
+git add file
+git commit -q -m c1
+
+seq 1 1001 > file
+git commit -q -am c2
+
+git repack -adq
+
+printf 'HEAD\n^HEAD~1\n' | git pack-objects -q --thin --stdout --revs --delta-base-offset > thin.pack
+git rev-list --objects HEAD --not HEAD~1 | wc -l | tr -d ' ' > num-objects-in-thin-pack
+
+printf 'HEAD\n' | git pack-objects -q --stdout --revs > ref-deltas.pack
+git rev-list --objects HEAD | wc -l | tr -d ' ' > num-objects-in-ref-deltas-pack
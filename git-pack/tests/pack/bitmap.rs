@@ -0,0 +1,220 @@
+use git_odb::pack::{bitmap, index};
+use std::path::PathBuf;
+
+fn fixture() -> crate::Result<(PathBuf, index::File, bitmap::File)> {
+    let repo = crate::scripted_fixture_repo_read_only("make_pack_with_bitmap.sh")?;
+    let index_path = std::fs::read_dir(repo.join(".git/objects/pack"))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .find(|path| path.extension().map_or(false, |ext| ext == "idx"))
+        .expect("the repository was repacked");
+    let index = index::File::at(&index_path)?;
+    let bitmap = bitmap::File::at(index_path.with_extension("bitmap"), &index)?;
+    Ok((repo, index, bitmap))
+}
+
+#[test]
+fn type_bitmaps_partition_all_objects() -> crate::Result {
+    let (_repo, index, bitmap) = fixture()?;
+    assert_eq!(bitmap.version(), 1);
+    assert_eq!(bitmap.pack_checksum(), index.pack_checksum());
+    use git_object::Kind::*;
+    let counts: Vec<_> = [Commit, Tree, Blob, Tag]
+        .iter()
+        .map(|kind| bitmap.type_bitmap(*kind).count_ones())
+        .collect();
+    assert_eq!(counts, vec![3, 6, 3, 1]);
+    assert_eq!(counts.iter().sum::<usize>(), index.num_objects() as usize);
+    Ok(())
+}
+
+#[test]
+fn name_hashes_are_ordered_like_the_index() -> crate::Result {
+    let (repo, index, bitmap) = fixture()?;
+    fn pack_name_hash(name: &str) -> u32 {
+        name.bytes()
+            .filter(|b| !b.is_ascii_whitespace())
+            .fold(0, |hash, b| (hash >> 2).wrapping_add((b as u32) << 24))
+    }
+    let blob = git_hash::ObjectId::from_hex(
+        std::fs::read_to_string(repo.join("blob-at-dir2-file"))?
+            .trim()
+            .as_bytes(),
+    )?;
+    let index_position = index.lookup(blob).expect("blob in pack");
+    assert_eq!(
+        bitmap.name_hash(index_position),
+        Some(pack_name_hash("dir2/file")),
+        "git writes name hashes by default"
+    );
+    Ok(())
+}
+
+#[test]
+fn commit_bitmaps_contain_all_reachable_objects() -> crate::Result {
+    let (repo, index, bitmap) = fixture()?;
+    assert_eq!(bitmap.entries().len(), 3, "each commit has its own bitmap");
+
+    let head = git_hash::ObjectId::from_hex(std::fs::read_to_string(repo.join("head"))?.trim().as_bytes())?;
+    let head_bitmap = bitmap.lookup(&index, head).expect("a bitmap for the tip of history");
+    let pack_order = bitmap::index_positions_in_pack_order(&index);
+    let mut actual = Vec::new();
+    head_bitmap.for_each_set_bit(|bit| {
+        actual.push(index.oid_at_index(pack_order[bit]).to_owned());
+        Some(())
+    });
+    actual.sort();
+
+    let mut expected = std::fs::read_to_string(repo.join("objects-reachable-from-head"))?
+        .lines()
+        .map(|hex| git_hash::ObjectId::from_hex(hex.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()?;
+    expected.sort();
+    assert_eq!(actual, expected);
+
+    let tag_position = pack_order[bitmap_tag_bit(&bitmap)];
+    assert!(
+        bitmap.bitmap_at_index(tag_position).is_none(),
+        "only commits have bitmaps"
+    );
+    Ok(())
+}
+
+#[test]
+fn reachable_objects_are_the_difference_of_wanted_and_present_objects() -> crate::Result {
+    let (repo, index, bitmap) = fixture()?;
+    let db = git_odb::linked::Store::at(repo.join(".git/objects"))?;
+    let read_id = |name: &str| -> crate::Result<git_hash::ObjectId> {
+        Ok(git_hash::ObjectId::from_hex(
+            std::fs::read_to_string(repo.join(name))?.trim().as_bytes(),
+        )?)
+    };
+    let tag = read_id("tag")?;
+    let parent_of_head = read_id("parent-of-head")?;
+
+    let outcome = bitmap.reachable(
+        &index,
+        Some(tag),
+        vec![parent_of_head, git_hash::ObjectId::null_sha1()],
+        &db,
+    )?;
+    assert_eq!(
+        outcome.traversed_objects, 1,
+        "only the tag has to be looked up as its commit has a bitmap"
+    );
+    let mut actual = outcome.object_ids(&index);
+    actual.sort();
+    let mut expected = std::fs::read_to_string(repo.join("objects-reachable-from-tag-but-not-parent-of-head"))?
+        .lines()
+        .map(|hex| git_hash::ObjectId::from_hex(hex.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()?;
+    expected.sort();
+    assert_eq!(actual, expected);
+
+    assert!(matches!(
+        bitmap.reachable(
+            &index,
+            Some(git_hash::ObjectId::null_sha1()),
+            None::<git_hash::ObjectId>,
+            &db
+        ),
+        Err(bitmap::reachable::Error::NotInPack { .. })
+    ));
+    Ok(())
+}
+
+fn bitmap_tag_bit(bitmap: &bitmap::File) -> usize {
+    let mut tag_bit = None;
+    bitmap.type_bitmap(git_object::Kind::Tag).for_each_set_bit(|bit| {
+        tag_bit = Some(bit);
+        None
+    });
+    tag_bit.expect("there is one annotated tag")
+}
+
+mod ewah {
+    use git_odb::pack::bitmap::ewah;
+
+    #[test]
+    fn words_survive_a_compression_round_trip() {
+        let words = [
+            0,
+            0,
+            u64::MAX,
+            u64::MAX,
+            u64::MAX,
+            0x1234,
+            0,
+            0x8000_0000_0000_0001,
+            u64::MAX,
+        ];
+        let num_bits = words.len() * 64 - 3;
+        let bitmap = ewah::Vec::from_words(&words, num_bits);
+        assert_eq!(bitmap.num_bits(), num_bits);
+        let mut expected = words.to_vec();
+        *expected.last_mut().unwrap() >>= 3;
+        assert_eq!(bitmap.decompress(), expected, "bits past the end are cleared");
+        assert_eq!(
+            bitmap.count_ones(),
+            expected.iter().map(|w| w.count_ones() as usize).sum::<usize>()
+        );
+        assert!(bitmap.is_set(128) && !bitmap.is_set(0));
+        assert_eq!(bitmap.xor(&bitmap).count_ones(), 0);
+    }
+
+    #[test]
+    fn queries_walk_runs_of_set_bits() {
+        let bitmap = ewah::Vec::from_words(&[0, u64::MAX, u64::MAX, 0b101], 4 * 64);
+        assert_eq!(bitmap.count_ones(), 2 * 64 + 2);
+        assert!(!bitmap.is_set(63) && bitmap.is_set(64) && bitmap.is_set(191) && bitmap.is_set(194));
+        assert!(
+            !bitmap.is_set(193) && !bitmap.is_set(4 * 64),
+            "bits past the end are never set"
+        );
+        let mut bits = Vec::new();
+        bitmap.for_each_set_bit(|bit| {
+            bits.push(bit);
+            Some(())
+        });
+        assert_eq!(bits.len(), bitmap.count_ones());
+        assert_eq!((bits[0], bits[127], bits[128], bits[129]), (64, 191, 192, 194));
+    }
+
+    fn encoded(num_bits: u32, words: &[u64]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&num_bits.to_be_bytes());
+        out.extend_from_slice(&(words.len() as u32).to_be_bytes());
+        for word in words {
+            out.extend_from_slice(&word.to_be_bytes());
+        }
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn decoding_validates_run_lengths_and_word_counts() {
+        let run_of_two_set_words = 1 | 2 << 1;
+        let data = encoded(128, &[run_of_two_set_words]);
+        let (bitmap, rest) = ewah::decode(&data).expect("valid");
+        assert!(rest.is_empty());
+        assert_eq!(bitmap.count_ones(), 128);
+
+        let run_of_too_many_words = 1 | u64::from(u32::MAX) << 1;
+        assert!(ewah::decode(&encoded(128, &[run_of_too_many_words])).is_err());
+        let too_many_literals = 1 << 33;
+        assert!(ewah::decode(&encoded(64, &[too_many_literals])).is_err());
+        let mut word_count_past_the_end = encoded(64, &[0]);
+        word_count_past_the_end[4..8].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(ewah::decode(&word_count_past_the_end).is_err());
+    }
+
+    #[test]
+    fn set_operations() {
+        let (a, b) = (ewah::Vec::from_words(&[0b1100], 4), ewah::Vec::from_words(&[0b1010], 4));
+        assert_eq!(a.or(&b).decompress(), vec![0b1110]);
+        assert_eq!(a.and(&b).decompress(), vec![0b1000]);
+        assert_eq!(a.and_not(&b).decompress(), vec![0b0100]);
+        assert_eq!(a.xor(&b).decompress(), vec![0b0110]);
+    }
+}
@@ -116,7 +116,7 @@ mod write_to_directory {
         sorted_entries.sort_by_key(|e| e.file_name());
         assert_eq!(sorted_entries.len(), 2, "we want a pack and the corresponding index");
 
-        let pack_hash = res.index.data_hash.to_sha1_hex_string();
+        let pack_hash = res.index.data_hash.to_string();
         assert_eq!(file_name(&sorted_entries[0]), format!("{}.idx", pack_hash));
         assert_eq!(Some(sorted_entries[0].path()), index_path);
 
@@ -148,8 +148,216 @@ mod write_to_directory {
                 thread_limit: None,
                 iteration_mode: pack::data::input::Mode::Verify,
                 index_kind: pack::index::Version::V2,
+                compression: Default::default(),
             },
         )
         .map_err(Into::into)
     }
 }
+
+mod write_stream_to_directory {
+    use git_features::progress;
+    use git_odb::pack;
+    use std::sync::atomic::AtomicBool;
+    use tempfile::TempDir;
+
+    #[test]
+    fn thin_packs_are_completed_with_base_objects_from_the_object_database() -> crate::Result {
+        let repo = git_testtools::scripted_fixture_repo_read_only("make_thin_pack.sh")?;
+        let thin_pack = std::fs::read(repo.join("thin.pack"))?;
+        let num_objects_in_thin_pack: u32 = std::fs::read_to_string(repo.join("num-objects-in-thin-pack"))?
+            .trim()
+            .parse()?;
+        let should_interrupt = AtomicBool::new(false);
+        let dir = TempDir::new()?;
+
+        assert!(
+            matches!(
+                pack::Bundle::write_to_directory(
+                    &thin_pack[..],
+                    Some(dir.path()),
+                    progress::Discard,
+                    &should_interrupt,
+                    Default::default()
+                ),
+                Err(pack::bundle::write::Error::IndexWrite(
+                    pack::index::write::Error::IteratorInvariantNoRefDelta
+                ))
+            ),
+            "thin packs can't be indexed without their base objects"
+        );
+
+        let odb = git_odb::linked::Store::at(repo.join(".git").join("objects"))?;
+        let outcome = pack::Bundle::write_stream_to_directory(
+            &thin_pack[..],
+            Some(dir.path()),
+            progress::Discard,
+            &should_interrupt,
+            &odb,
+            Default::default(),
+        )?;
+        assert_eq!(
+            outcome.index.num_objects,
+            num_objects_in_thin_pack + 1,
+            "the base object of the single ref-delta was added"
+        );
+
+        let bundle = outcome.to_bundle().expect("written to directory")?;
+        let mut buf = Vec::new();
+        for entry in bundle.index.iter() {
+            let object = bundle
+                .find(entry.oid, &mut buf, &mut pack::cache::Never)?
+                .expect("each indexed object can be found");
+            object.verify_checksum(entry.oid)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ref_deltas_are_resolved_within_the_pack_before_consulting_the_object_database() -> crate::Result {
+        let repo = git_testtools::scripted_fixture_repo_read_only("make_thin_pack.sh")?;
+        let pack_data = std::fs::read(repo.join("ref-deltas.pack"))?;
+        let num_objects: u32 = std::fs::read_to_string(repo.join("num-objects-in-ref-deltas-pack"))?
+            .trim()
+            .parse()?;
+        assert!(
+            pack::data::BytesToEntriesIter::new_from_header(
+                &pack_data[..],
+                pack::data::input::Mode::Verify,
+                pack::data::input::EntryDataMode::Ignore
+            )?
+            .any(|entry| matches!(entry.map(|e| e.header), Ok(pack::data::entry::Header::RefDelta { .. }))),
+            "the fixture contains ref-deltas"
+        );
+
+        let empty_objects_dir = TempDir::new()?;
+        let odb = git_odb::linked::Store::at(empty_objects_dir.path())?;
+        let dir = TempDir::new()?;
+        let outcome = pack::Bundle::write_stream_to_directory(
+            &pack_data[..],
+            Some(dir.path()),
+            progress::Discard,
+            &AtomicBool::new(false),
+            &odb,
+            Default::default(),
+        )?;
+        assert_eq!(outcome.index.num_objects, num_objects, "no base object had to be added");
+
+        let bundle = outcome.to_bundle().expect("written to directory")?;
+        let mut buf = Vec::new();
+        for entry in bundle.index.iter() {
+            let object = bundle
+                .find(entry.oid, &mut buf, &mut pack::cache::Never)?
+                .expect("each indexed object can be found");
+            object.verify_checksum(entry.oid)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn missing_base_objects_are_an_error() -> crate::Result {
+        let repo = git_testtools::scripted_fixture_repo_read_only("make_thin_pack.sh")?;
+        let thin_pack = std::fs::read(repo.join("thin.pack"))?;
+        let empty_objects_dir = TempDir::new()?;
+        let odb = git_odb::linked::Store::at(empty_objects_dir.path())?;
+
+        assert!(matches!(
+            pack::Bundle::write_stream_to_directory(
+                &thin_pack[..],
+                None::<&std::path::Path>,
+                progress::Discard,
+                &AtomicBool::new(false),
+                &odb,
+                Default::default(),
+            ),
+            Err(pack::bundle::write::Error::BaseNotFound { .. })
+        ));
+        Ok(())
+    }
+}
+
+mod verify_integrity_with_statistics {
+    use crate::{fixture_path, pack::PACKS_AND_INDICES};
+    use git_features::progress;
+    use git_odb::pack;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    #[test]
+    fn statistics_for_each_object_agree_with_the_aggregated_outcome() -> Result<(), Box<dyn std::error::Error>> {
+        for (_, data_path) in PACKS_AND_INDICES {
+            let bundle = pack::Bundle::at(fixture_path(data_path))?;
+            let (_pack_checksum, outcome, statistics, _progress) = bundle.verify_integrity_with_statistics(
+                pack::index::verify::Mode::Sha1Crc32DecodeEncode,
+                pack::index::traverse::Algorithm::Lookup,
+                || pack::cache::Never,
+                None,
+                progress::Discard.into(),
+                Arc::new(Default::default()),
+            )?;
+            assert_eq!(statistics.len(), bundle.index.num_objects() as usize);
+            assert!(
+                statistics.windows(2).all(|w| w[0].pack_offset < w[1].pack_offset),
+                "statistics are sorted by pack offset"
+            );
+
+            let mut objects_per_chain_length = BTreeMap::new();
+            for stat in &statistics {
+                *objects_per_chain_length.entry(stat.delta_depth).or_insert(0) += 1;
+            }
+            assert_eq!(
+                objects_per_chain_length, outcome.objects_per_chain_length,
+                "without cache, each delta chain is resolved in full"
+            );
+            assert_eq!(
+                statistics.iter().map(|s| s.compressed_size).sum::<u64>(),
+                bundle.pack.pack_end() as u64 - statistics[0].pack_offset,
+                "all entries are accounted for, from the first one to the end of the pack"
+            );
+        }
+        Ok(())
+    }
+}
+
+mod raw_entries {
+    use crate::{fixture_path, pack::PACKS_AND_INDICES};
+    use git_odb::pack;
+
+    #[test]
+    fn chains_lead_from_each_object_to_its_base_object() -> crate::Result {
+        for (_, data_path) in PACKS_AND_INDICES {
+            let bundle = pack::Bundle::at(fixture_path(data_path))?;
+            let entries = bundle.raw_entries();
+            let mut buf = Vec::new();
+            let mut total_entry_size = 0;
+            for index in 0..bundle.index.num_objects() {
+                let id = bundle.index.oid_at_index(index);
+                let chain = entries.chain(id)?.expect("object is in pack");
+                assert_eq!(chain[0].id, id.to_owned());
+                assert!(chain.last().expect("at least one entry").header.is_base());
+                assert!(chain[..chain.len() - 1].iter().all(|e| e.header.is_delta()));
+
+                let location = bundle
+                    .find(id, &mut buf, &mut pack::cache::Never)?
+                    .and_then(|o| o.pack_location)
+                    .expect("object is in pack");
+                assert_eq!(
+                    chain[0].data.len(),
+                    location.entry_size,
+                    "entries end where the next one begins"
+                );
+                assert_eq!(
+                    chain[0].compressed_data().len(),
+                    chain[0].data.len() - chain[0].header_size
+                );
+                total_entry_size += chain[0].data.len();
+            }
+            assert_eq!(
+                total_entry_size,
+                bundle.pack.pack_end() - 12,
+                "all entries are accounted for, from the end of the pack header to the end of the pack"
+            );
+            assert!(entries.chain(git_hash::ObjectId::null_sha1())?.is_none());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(feature = "object-cache-dynamic")]
+mod object {
+    use git_object::Kind;
+    use git_pack::cache::{object::MemoryCappedHashmap, Object};
+
+    use crate::hex_to_id;
+
+    fn id(n: u8) -> git_hash::ObjectId {
+        hex_to_id(&format!("{:0>40}", n))
+    }
+
+    #[test]
+    fn objects_are_returned_with_their_kind() {
+        let mut cache = MemoryCappedHashmap::new(100);
+        cache.put(id(1), Kind::Blob, b"hello");
+        let mut out = Vec::new();
+        assert_eq!(cache.get(&id(1), &mut out), Some(Kind::Blob));
+        assert_eq!(out, b"hello");
+        assert_eq!(cache.get(&id(2), &mut out), None, "unknown objects aren't found");
+        assert_eq!(cache.memory_usage_in_bytes(), 5);
+    }
+
+    #[test]
+    fn least_recently_used_objects_are_evicted_once_the_memory_cap_is_exceeded() {
+        let mut cache = MemoryCappedHashmap::new(10);
+        let mut out = Vec::new();
+        cache.put(id(1), Kind::Blob, b"aaaa");
+        cache.put(id(2), Kind::Tree, b"bbbb");
+        assert!(
+            cache.get(&id(1), &mut out).is_some(),
+            "now 2 is the least recently used one"
+        );
+
+        cache.put(id(3), Kind::Commit, b"cccc");
+        assert_eq!(cache.get(&id(2), &mut out), None, "2 was evicted to make room");
+        assert_eq!(cache.get(&id(1), &mut out), Some(Kind::Blob));
+        assert_eq!(cache.get(&id(3), &mut out), Some(Kind::Commit));
+        assert_eq!(out, b"cccc");
+        assert_eq!(cache.memory_usage_in_bytes(), 8);
+    }
+}
+
+#[cfg(feature = "pack-cache-lru-dynamic")]
+mod lru {
+    use git_object::Kind;
+    use git_pack::cache::{lru::MemoryCappedHashmap, DecodeEntry};
+
+    #[test]
+    fn entries_are_keyed_by_pack_and_offset() {
+        let mut cache = MemoryCappedHashmap::new(100);
+        cache.put(0, 12, b"data", Kind::Blob, 3);
+        let mut out = Vec::new();
+        assert_eq!(cache.get(0, 12, &mut out), Some((Kind::Blob, 3)));
+        assert_eq!(out, b"data");
+        assert_eq!(
+            cache.get(1, 12, &mut out),
+            None,
+            "the same offset in another pack is a different entry"
+        );
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted_once_the_memory_cap_is_exceeded() {
+        let mut cache = MemoryCappedHashmap::new(10);
+        let mut out = Vec::new();
+        cache.put(0, 1, b"aaaa", Kind::Blob, 1);
+        cache.put(0, 2, b"bbbb", Kind::Blob, 1);
+        assert!(
+            cache.get(0, 1, &mut out).is_some(),
+            "now offset 2 is the least recently used one"
+        );
+
+        cache.put(0, 3, b"cccc", Kind::Blob, 1);
+        assert_eq!(cache.get(0, 2, &mut out), None, "offset 2 was evicted to make room");
+        assert!(cache.get(0, 1, &mut out).is_some());
+        assert!(cache.get(0, 3, &mut out).is_some());
+        assert_eq!(cache.memory_usage_in_bytes(), 8);
+    }
+
+    #[test]
+    fn entries_larger_than_the_cap_are_not_retained() {
+        let mut cache = MemoryCappedHashmap::new(2);
+        cache.put(0, 1, b"too large", Kind::Blob, 1);
+        assert_eq!(cache.get(0, 1, &mut Vec::new()), None);
+        assert_eq!(cache.memory_usage_in_bytes(), 0);
+    }
+}
@@ -14,10 +14,7 @@ mod method {
     #[test]
     fn checksum() {
         let p = pack_at(SMALL_PACK);
-        assert_eq!(
-            hex::encode(p.checksum().sha1()),
-            "0f3ea84cd1bba10c2a03d736a460635082833e59"
-        );
+        assert_eq!(p.checksum().to_string(), "0f3ea84cd1bba10c2a03d736a460635082833e59");
     }
 
     #[test]
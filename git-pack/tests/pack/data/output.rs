@@ -7,7 +7,7 @@ use git_pack::data::output;
 fn size_of_entry() {
     assert_eq!(
         std::mem::size_of::<output::Entry>(),
-        80,
+        112,
         "The size of the structure shouldn't change unexpectedly"
     )
 }
@@ -16,7 +16,7 @@ fn size_of_entry() {
 fn size_of_count() {
     assert_eq!(
         std::mem::size_of::<output::Count>(),
-        48,
+        64,
         "The size of the structure shouldn't change unexpectedly"
     )
 }
@@ -148,6 +148,7 @@ mod count_and_entries {
                 db.clone(),
                 || pack::cache::Never,
                 commits.chain(std::iter::once(hex_to_id("e3fb53cbb4c346d48732a24f09cf445e49bc63d6"))),
+                None,
                 progress::Discard,
                 count::from_objects_iter::Options {
                     input_object_expansion: expansion_mode,
@@ -202,6 +203,50 @@ mod count_and_entries {
         Ok(())
     }
 
+    #[test]
+    fn parents_of_shallow_commits_are_not_looked_up_but_other_missing_objects_are_errors() -> crate::Result {
+        let repo = git_testtools::scripted_fixture_repo_read_only("make_shallow_repo.sh")?.join("shallow");
+        let db = Arc::new(git_odb::linked::Store::at(repo.join(".git").join("objects"))?);
+        let shallow = git_odb::shallow::Boundary::from_git_dir(repo.join(".git"))?;
+        let commits = std::fs::read_to_string(repo.join("commits"))?
+            .lines()
+            .map(hex_to_id)
+            .collect::<Vec<_>>();
+        let count = |shallow_commits: Vec<git_hash::ObjectId>| {
+            output::count::from_objects_iter(
+                Arc::clone(&db),
+                || pack::cache::Never,
+                commits.clone().into_iter(),
+                shallow_commits,
+                progress::Discard,
+                count::from_objects_iter::Options {
+                    input_object_expansion: count::from_objects_iter::ObjectExpansion::TreeAdditionsComparedToAncestor,
+                    ..Default::default()
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()
+        };
+
+        let mut ids: Vec<_> = count(shallow.commits().to_vec())?
+            .into_iter()
+            .flatten()
+            .map(|count| count.id.to_string())
+            .collect();
+        ids.sort();
+        assert_eq!(
+            ids,
+            std::fs::read_to_string(repo.join("objects-reachable-from-head"))?
+                .lines()
+                .collect::<Vec<_>>(),
+            "the shallow commit contributes its whole tree"
+        );
+        assert!(
+            count(Vec::new()).is_err(),
+            "parents of commits not known to be shallow must be present"
+        );
+        Ok(())
+    }
+
     fn write_and_verify(entries: Vec<output::Entry>, _expected_pack_hash: git_hash::ObjectId) -> crate::Result {
         let tmp_dir = tempfile::TempDir::new()?;
         let pack_file_path = tmp_dir.path().join("new.pack");
@@ -403,6 +403,18 @@ mod file {
             assert_eq!(idx.index_checksum(), hex_to_id(index_checksum));
             assert_eq!(idx.pack_checksum(), hex_to_id(pack_checksum));
             assert_eq!(idx.iter().count(), *num_objects as usize);
+
+            let mut in_index_order: Vec<_> = idx.iter().collect();
+            let in_pack_order: Vec<_> = idx.iter_in_pack_order().collect();
+            assert!(
+                in_pack_order.windows(2).all(|w| w[0].pack_offset < w[1].pack_offset),
+                "entries are sorted by pack offset"
+            );
+            in_index_order.sort_by_key(|e| e.pack_offset);
+            assert_eq!(
+                in_pack_order, in_index_order,
+                "it's the same entries, just in a different order"
+            );
         }
         Ok(())
     }
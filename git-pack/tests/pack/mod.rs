@@ -30,7 +30,9 @@ pub fn fixup(v: Vec<u8>) -> Vec<u8> {
     v.replace(b"\r\n", "\n")
 }
 
+mod bitmap;
 mod bundle;
+mod cache;
 mod data;
 mod index;
 mod iter;
@@ -15,6 +15,9 @@ quick_error! {
         DataLengthLimitExceeded(length_in_bytes: usize) {
             display("The data received claims to be larger than than the maximum allowed size: got {}, exceeds {}", length_in_bytes, MAX_DATA_LEN)
         }
+        LineLengthLimitExceeded { length_in_bytes: usize, limit: usize } {
+            display("The line received claims to be {} bytes long, exceeding the configured limit of {} bytes", length_in_bytes, limit)
+        }
         DataIsEmpty {
             display("Received an invalid empty line")
         }
@@ -0,0 +1,52 @@
+use crate::{Channel, PacketLine};
+
+/// The direction in which a [`Packet`] was transmitted.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Direction {
+    /// The packet line was read from the underlying reader.
+    Read,
+    /// The packet line was written to the underlying writer.
+    Write,
+}
+
+/// A packet line passed to an [`Inspector`] right after it was read or written.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Packet<'a> {
+    /// Whether the line was read or written.
+    pub direction: Direction,
+    /// The side-band the line was transmitted on, or `None` if side-bands are not in use or unknown.
+    ///
+    /// When reading, it is only known while reading through a [`WithSidebands`][crate::read::WithSidebands] reader which
+    /// decodes side-bands.
+    pub band: Option<Channel>,
+    /// The line as it was transmitted, which includes the side-band byte if `band` is set.
+    ///
+    /// When writing in text mode, the newline appended by the [`Writer`][crate::Writer] is not included.
+    pub line: PacketLine<'a>,
+}
+
+impl<'a> Packet<'a> {
+    /// Return the data of the line without the side-band byte if `band` is set, or `None` if it's not a data line.
+    pub fn payload(&self) -> Option<&'a [u8]> {
+        match self.line {
+            PacketLine::Data(data) if self.band.is_some() => Some(&data[1..]),
+            PacketLine::Data(data) => Some(data),
+            PacketLine::Flush | PacketLine::Delimiter | PacketLine::ResponseEnd => None,
+        }
+    }
+}
+
+/// A function receiving every [`Packet`] that is read or written, for example to log all transmitted packet lines similar to what
+/// `GIT_TRACE_PACKET` does, without having to wrap the underlying byte stream.
+pub type Inspector = Box<dyn FnMut(&Packet<'_>) + Send>;
+
+/// Return the channel of `line` if it's a data line with a valid side-band.
+#[cfg(any(feature = "blocking-io", feature = "async-io"))]
+pub(crate) fn band_of(line: &PacketLine<'_>) -> Option<Channel> {
+    use crate::immutable::Band;
+    line.decode_band().ok().map(|band| match band {
+        Band::Data(_) => Channel::Data,
+        Band::Progress(_) => Channel::Progress,
+        Band::Error(_) => Channel::Error,
+    })
+}
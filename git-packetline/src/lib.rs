@@ -9,6 +9,8 @@ pub(crate) const MAX_LINE_LEN: usize = MAX_DATA_LEN + U16_HEX_BYTES;
 pub(crate) const FLUSH_LINE: &[u8] = b"0000";
 pub(crate) const DELIMITER_LINE: &[u8] = b"0001";
 pub(crate) const RESPONSE_END_LINE: &[u8] = b"0002";
+#[cfg(any(feature = "blocking-io", feature = "async-io"))]
+pub(crate) const EMPTY_LINE: &[u8] = b"0004";
 pub(crate) const ERR_PREFIX: &[u8] = b"ERR ";
 
 /// One of three side-band types allowing to multiplex information over a single connection.
@@ -27,6 +29,9 @@ pub enum Channel {
 pub mod immutable;
 pub use immutable::PacketLine;
 
+/// Observe packet lines as they are read and written.
+pub mod inspect;
+
 ///
 pub mod read;
 #[doc(inline)]
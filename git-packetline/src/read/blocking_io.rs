@@ -1,7 +1,11 @@
 use crate::{
     decode,
-    read::{ExhaustiveOutcome, WithSidebands},
-    PacketLine, StreamingPeekableIter, MAX_LINE_LEN, U16_HEX_BYTES,
+    inspect::Inspector,
+    read::{
+        decode_read_line, inspect_read_line, EmptyLinePolicy, ExhaustiveOutcome, HandleProgress, Options,
+        ProgressAction, WithSidebands,
+    },
+    PacketLine, StreamingPeekableIter, EMPTY_LINE, MAX_LINE_LEN, U16_HEX_BYTES,
 };
 use bstr::ByteSlice;
 use std::io;
@@ -11,14 +15,34 @@ impl<T> StreamingPeekableIter<T>
 where
     T: io::Read,
 {
-    fn read_line_inner<'a>(reader: &mut T, buf: &'a mut Vec<u8>) -> io::Result<Result<PacketLine<'a>, decode::Error>> {
+    fn read_line_inner<'a>(
+        reader: &mut T,
+        buf: &'a mut [u8],
+        options: Options,
+    ) -> io::Result<Result<PacketLine<'a>, decode::Error>> {
         let (hex_bytes, data_bytes) = buf.split_at_mut(4);
-        reader.read_exact(hex_bytes)?;
+        loop {
+            reader.read_exact(hex_bytes)?;
+            if hex_bytes != EMPTY_LINE {
+                break;
+            }
+            match options.empty_lines {
+                EmptyLinePolicy::Error => break,
+                EmptyLinePolicy::Skip => continue,
+                EmptyLinePolicy::Surface => return Ok(Ok(PacketLine::Data(&[]))),
+            }
+        }
         let num_data_bytes = match decode::hex_prefix(hex_bytes) {
             Ok(decode::PacketLineOrWantedSize::Line(line)) => return Ok(Ok(line)),
             Ok(decode::PacketLineOrWantedSize::Wanted(additional_bytes)) => additional_bytes as usize,
             Err(err) => return Ok(Err(err)),
         };
+        if num_data_bytes + U16_HEX_BYTES > options.max_line_len {
+            return Ok(Err(decode::Error::LineLengthLimitExceeded {
+                length_in_bytes: num_data_bytes + U16_HEX_BYTES,
+                limit: options.max_line_len,
+            }));
+        }
 
         let (data_bytes, _) = data_bytes.split_at_mut(num_data_bytes);
         reader.read_exact(data_bytes)?;
@@ -34,19 +58,21 @@ where
         reader: &mut T,
         buf: &'a mut Vec<u8>,
         delimiters: &[PacketLine<'static>],
-        fail_on_err_lines: bool,
+        options: Options,
+        inspect: &mut Option<Inspector>,
         buf_resize: bool,
     ) -> ExhaustiveOutcome<'a> {
         (
             false,
             None,
-            Some(match Self::read_line_inner(reader, buf) {
+            Some(match Self::read_line_inner(reader, buf, options) {
                 Ok(Ok(line)) => {
+                    inspect_read_line(inspect, options, &line);
                     if delimiters.contains(&line) {
                         let stopped_at = delimiters.iter().find(|l| **l == line).cloned();
                         buf.clear();
                         return (true, stopped_at, None);
-                    } else if fail_on_err_lines {
+                    } else if options.fail_on_err_lines {
                         if let Some(err) = line.check_error() {
                             let err = err.0.as_bstr().to_string();
                             buf.clear();
@@ -60,7 +86,7 @@ where
                     if buf_resize {
                         buf.resize(len, 0);
                     }
-                    Ok(Ok(crate::decode(buf).expect("only valid data here")))
+                    Ok(Ok(decode_read_line(buf)))
                 }
                 Ok(Err(err)) => {
                     buf.clear();
@@ -88,7 +114,7 @@ where
         if !self.peek_buf.is_empty() {
             std::mem::swap(&mut self.peek_buf, &mut self.buf);
             self.peek_buf.clear();
-            Some(Ok(Ok(crate::decode(&self.buf).expect("only valid data in peek buf"))))
+            Some(Ok(Ok(decode_read_line(&self.buf))))
         } else {
             if self.buf.len() != MAX_LINE_LEN {
                 self.buf.resize(MAX_LINE_LEN, 0);
@@ -97,7 +123,8 @@ where
                 &mut self.read,
                 &mut self.buf,
                 &self.delimiters,
-                self.fail_on_err_lines,
+                self.options,
+                &mut self.inspect,
                 false,
             );
             self.is_done = is_done;
@@ -119,14 +146,15 @@ where
                 &mut self.read,
                 &mut self.peek_buf,
                 &self.delimiters,
-                self.fail_on_err_lines,
+                self.options,
+                &mut self.inspect,
                 true,
             );
             self.is_done = is_done;
             self.stopped_at = stopped_at;
             res
         } else {
-            Some(Ok(Ok(crate::decode(&self.peek_buf).expect("only valid data here"))))
+            Some(Ok(Ok(decode_read_line(&self.peek_buf))))
         }
     }
 
@@ -134,10 +162,13 @@ where
     /// Each invocation of [`read_line()`][io::BufRead::read_line()] returns a packet line.
     ///
     /// Progress or error information will be passed to the given `handle_progress(is_error, text)` function, with `is_error: bool`
-    /// being true in case the `text` is to be interpreted as error.
+    /// being true in case the `text` is to be interpreted as error. It returns a [`ProgressAction`] to decide if reading continues.
     ///
     /// _Please note_ that side bands need to be negotiated with the server.
-    pub fn as_read_with_sidebands<F: FnMut(bool, &[u8])>(&mut self, handle_progress: F) -> WithSidebands<'_, T, F> {
+    pub fn as_read_with_sidebands<F: FnMut(bool, &[u8]) -> ProgressAction>(
+        &mut self,
+        handle_progress: F,
+    ) -> WithSidebands<'_, T, F> {
         WithSidebands::with_progress_handler(self, handle_progress)
     }
 
@@ -145,14 +176,14 @@ where
     ///
     /// The type parameter `F` needs to be configured for this method to be callable using the 'turbofish' operator.
     /// Use [`as_read()`][StreamingPeekableIter::as_read()].
-    pub fn as_read_without_sidebands<F: FnMut(bool, &[u8])>(&mut self) -> WithSidebands<'_, T, F> {
+    pub fn as_read_without_sidebands<F: FnMut(bool, &[u8]) -> ProgressAction>(&mut self) -> WithSidebands<'_, T, F> {
         WithSidebands::without_progress_handler(self)
     }
 
     /// Same as [`as_read_with_sidebands(…)`][StreamingPeekableIter::as_read_with_sidebands()], but for channels without side band support.
     ///
     /// Due to the preconfigured function type this method can be called without 'turbofish'.
-    pub fn as_read(&mut self) -> WithSidebands<'_, T, fn(bool, &[u8])> {
+    pub fn as_read(&mut self) -> WithSidebands<'_, T, HandleProgress> {
         WithSidebands::new(self)
     }
 }
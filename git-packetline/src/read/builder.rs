@@ -0,0 +1,87 @@
+use crate::{
+    inspect::{Inspector, Packet},
+    read::{fault, EmptyLinePolicy, Options},
+    PacketLine, StreamingPeekableIter, MAX_LINE_LEN, U16_HEX_BYTES,
+};
+
+/// A builder to configure and create a [`StreamingPeekableIter`], starting out with its [defaults][Builder::default()].
+///
+/// It's particularly useful to create readers that inject faults into the underlying reader with
+/// [`build_with_faults()`][Builder::build_with_faults()], which allows to test protocol handling against adverse IO conditions.
+#[derive(Default)]
+pub struct Builder {
+    delimiters: &'static [PacketLine<'static>],
+    options: Options,
+    inspect: Option<Inspector>,
+}
+
+/// Configuration
+impl Builder {
+    /// Stop decoding packet lines when receiving one of the given `delimiters`. No delimiters are used by default.
+    pub fn delimiters(mut self, delimiters: &'static [PacketLine<'static>]) -> Self {
+        self.delimiters = delimiters;
+        self
+    }
+
+    /// If `value` is true, stop iteration when receiving an `ERR` packet line,
+    /// see [`StreamingPeekableIter::fail_on_err_lines()`].
+    pub fn fail_on_err_lines(mut self, value: bool) -> Self {
+        self.options.fail_on_err_lines = value;
+        self
+    }
+
+    /// Apply `policy` to empty data lines, see [`StreamingPeekableIter::empty_line_policy()`].
+    pub fn empty_line_policy(mut self, policy: EmptyLinePolicy) -> Self {
+        self.options.empty_lines = policy;
+        self
+    }
+
+    /// Fail with [`LineLengthLimitExceeded`][crate::decode::Error::LineLengthLimitExceeded] when receiving a line
+    /// longer than `bytes`, including its 4 bytes hexadecimal length prefix.
+    ///
+    /// It defaults to and is limited by the largest line length the protocol allows, 65520 bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `bytes` is not large enough to hold a data line with a single byte or exceeds the maximum line length.
+    pub fn max_line_len(mut self, bytes: usize) -> Self {
+        assert!(
+            bytes > U16_HEX_BYTES && bytes <= MAX_LINE_LEN,
+            "the maximum line length must be within {}..={}",
+            U16_HEX_BYTES + 1,
+            MAX_LINE_LEN
+        );
+        self.options.max_line_len = bytes;
+        self
+    }
+
+    /// Call `trace` with every packet line that is read from the underlying reader, which includes delimiters and lines
+    /// that are only peeked at. Lines which fail to decode are not passed.
+    ///
+    /// Use [`inspect()`][Builder::inspect()] to learn about the side-band of lines as well.
+    pub fn trace(self, mut trace: impl FnMut(&PacketLine<'_>) + Send + 'static) -> Self {
+        self.inspect(move |packet| trace(&packet.line))
+    }
+
+    /// Call `inspect` with every packet line that is read from the underlying reader like [`trace()`][Builder::trace()], along
+    /// with its side-band while reading through a [`WithSidebands`][crate::read::WithSidebands] reader that decodes side-bands.
+    ///
+    /// This allows to log all received packet lines like `GIT_TRACE_PACKET` does.
+    pub fn inspect(mut self, inspect: impl FnMut(&Packet<'_>) + Send + 'static) -> Self {
+        self.inspect = Some(Box::new(inspect));
+        self
+    }
+}
+
+/// Instantiation
+impl Builder {
+    /// Create a new instance reading from `read`.
+    pub fn build<T>(self, read: T) -> StreamingPeekableIter<T> {
+        StreamingPeekableIter::from_parts(read, self.delimiters, self.options, self.inspect)
+    }
+
+    /// Create a new instance reading from `read` through a reader that injects the given `faults`.
+    pub fn build_with_faults<T>(self, read: T, faults: fault::Faults) -> StreamingPeekableIter<fault::Read<T>> {
+        self.build(fault::Read::new(read, faults))
+    }
+}
@@ -0,0 +1,98 @@
+//! Inject faults into readers to test the handling of adverse IO conditions, see
+//! [`Builder::build_with_faults()`][crate::read::Builder::build_with_faults()].
+use std::io;
+
+/// The faults to inject with a fault injecting [`Read`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+pub struct Faults {
+    /// If set, no read returns more than this amount of bytes, simulating short reads as they happen with network
+    /// connections and pipes. It must be larger than 0.
+    pub max_bytes_per_read: Option<usize>,
+    /// If set, fail the next read with [`Interrupted`][io::ErrorKind::Interrupted] after this amount of successful
+    /// reads, as if a signal interrupted the system call. With 0, every other read is interrupted.
+    pub interrupt_after_reads: Option<usize>,
+}
+
+/// A reader that injects [`Faults`] when reading from the inner reader, implementing [`io::Read`] and, with the
+/// `async-io` feature, [`AsyncRead`][futures_io::AsyncRead].
+pub struct Read<T> {
+    inner: T,
+    faults: Faults,
+    reads_since_interrupt: usize,
+    interrupted_last_read: bool,
+}
+
+impl<T> Read<T> {
+    /// Create a new instance to read from `inner` while injecting `faults`.
+    ///
+    /// # Panics
+    ///
+    /// If [`max_bytes_per_read`][Faults::max_bytes_per_read] is 0.
+    pub fn new(inner: T, faults: Faults) -> Self {
+        assert_ne!(
+            faults.max_bytes_per_read,
+            Some(0),
+            "reads must be able to make progress"
+        );
+        Read {
+            inner,
+            faults,
+            reads_since_interrupt: 0,
+            interrupted_last_read: false,
+        }
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Return an error if the current read is to be interrupted, or the amount of bytes of a buffer of `len` bytes to use.
+    fn next_read_len(&mut self, len: usize) -> io::Result<usize> {
+        if let Some(interrupt_after_reads) = self.faults.interrupt_after_reads {
+            // Never interrupt twice in a row to assure that retrying makes progress.
+            if self.reads_since_interrupt >= interrupt_after_reads && !self.interrupted_last_read {
+                self.reads_since_interrupt = 0;
+                self.interrupted_last_read = true;
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "injected fault"));
+            }
+            self.reads_since_interrupt += 1;
+            self.interrupted_last_read = false;
+        }
+        Ok(self.faults.max_bytes_per_read.map_or(len, |max| max.min(len)))
+    }
+}
+
+impl<T> io::Read for Read<T>
+where
+    T: io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.next_read_len(buf.len())?;
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+#[cfg(feature = "async-io")]
+mod async_io {
+    use crate::read::fault::Read;
+    use futures_io::AsyncRead;
+    use std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    impl<T> AsyncRead for Read<T>
+    where
+        T: AsyncRead + Unpin,
+    {
+        fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+            let len = match self.next_read_len(buf.len()) {
+                Ok(len) => len,
+                Err(err) => return Poll::Ready(Err(err)),
+            };
+            Pin::new(&mut self.inner).poll_read(cx, &mut buf[..len])
+        }
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(any(feature = "blocking-io", feature = "async-io"))]
 use crate::MAX_LINE_LEN;
-use crate::{PacketLine, U16_HEX_BYTES};
+use crate::{inspect::Inspector, PacketLine, U16_HEX_BYTES};
 
 #[cfg(any(feature = "blocking-io", feature = "async-io"))]
 type ExhaustiveOutcome<'a> = (
@@ -9,6 +9,94 @@ type ExhaustiveOutcome<'a> = (
     Option<std::io::Result<Result<PacketLine<'a>, crate::decode::Error>>>, // actual method result
 );
 
+/// Settings that affect the reading of each line, passed around as a whole to the functions doing the actual IO.
+#[derive(Clone, Copy)]
+struct Options {
+    fail_on_err_lines: bool,
+    empty_lines: EmptyLinePolicy,
+    max_line_len: usize,
+    /// If true, data lines are expected to contain a side-band, which is passed to the inspector.
+    #[cfg(any(feature = "blocking-io", feature = "async-io"))]
+    sidebands: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            fail_on_err_lines: false,
+            empty_lines: EmptyLinePolicy::default(),
+            max_line_len: crate::MAX_LINE_LEN,
+            #[cfg(any(feature = "blocking-io", feature = "async-io"))]
+            sidebands: false,
+        }
+    }
+}
+
+/// Determines how empty data lines (`0004`) are handled by the [`StreamingPeekableIter`].
+///
+/// These are invalid according to the protocol, but some servers send them as keep-alive packets.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum EmptyLinePolicy {
+    /// Produce a [`decode::Error::DataIsEmpty`][crate::decode::Error::DataIsEmpty], the default.
+    Error,
+    /// Silently skip empty lines as if they were never received.
+    Skip,
+    /// Return empty lines as [`PacketLine::Data`] with an empty slice.
+    ///
+    /// Note that readers obtained with [`as_read()`][StreamingPeekableIter::as_read()] and similar methods skip
+    /// empty lines as they would otherwise signal the end of the stream.
+    Surface,
+}
+
+impl Default for EmptyLinePolicy {
+    fn default() -> Self {
+        EmptyLinePolicy::Error
+    }
+}
+
+/// Returned by the progress handler of a [`WithSidebands`] reader to decide whether reading should continue after
+/// it received a message on the progress or error side-band.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum ProgressAction {
+    /// Keep reading.
+    Continue,
+    /// Stop reading and fail the current read operation with an [`Other`][std::io::ErrorKind::Other] error, which
+    /// contains the message if it was received on the error side-band.
+    ///
+    /// Use this to abort as soon as the remote reports a fatal error instead of reading the remaining data.
+    Interrupt,
+}
+
+/// The progress handler of readers obtained with [`as_read()`][StreamingPeekableIter::as_read()], which are used with channels
+/// without side-band support.
+pub type HandleProgress = fn(bool, &[u8]) -> ProgressAction;
+
+/// Pass the `line` that was just read to `inspect`, along with its side-band if these are in use according to `options`.
+#[cfg(any(feature = "blocking-io", feature = "async-io"))]
+fn inspect_read_line(inspect: &mut Option<Inspector>, options: Options, line: &PacketLine<'_>) {
+    if let Some(inspect) = inspect {
+        inspect(&crate::inspect::Packet {
+            direction: crate::inspect::Direction::Read,
+            band: if options.sidebands {
+                crate::inspect::band_of(line)
+            } else {
+                None
+            },
+            line: *line,
+        });
+    }
+}
+
+/// Decode the line previously read into `buf`, which may be an empty data line if these are surfaced.
+#[cfg(any(feature = "blocking-io", feature = "async-io"))]
+fn decode_read_line(buf: &[u8]) -> PacketLine<'_> {
+    if buf.starts_with(crate::EMPTY_LINE) {
+        PacketLine::Data(&[])
+    } else {
+        crate::decode(buf).expect("only valid data here")
+    }
+}
+
 /// Read pack lines one after another, without consuming more than needed from the underlying
 /// [`Read`][std::io::Read]. [`Flush`][PacketLine::Flush] lines cause the reader to stop producing lines forever,
 /// leaving [`Read`][std::io::Read] at the start of whatever comes next.
@@ -19,7 +107,8 @@ pub struct StreamingPeekableIter<T> {
     peek_buf: Vec<u8>,
     #[cfg(any(feature = "blocking-io", feature = "async-io"))]
     buf: Vec<u8>,
-    fail_on_err_lines: bool,
+    options: Options,
+    inspect: Option<Inspector>,
     delimiters: &'static [PacketLine<'static>],
     is_done: bool,
     stopped_at: Option<PacketLine<'static>>,
@@ -27,14 +116,26 @@ pub struct StreamingPeekableIter<T> {
 
 impl<T> StreamingPeekableIter<T> {
     /// Return a new instance from `read` which will stop decoding packet lines when receiving one of the given `delimiters`.
+    ///
+    /// Use a [`Builder`] to configure all aspects of a new instance, including the injection of faults for testing.
     pub fn new(read: T, delimiters: &'static [PacketLine<'static>]) -> Self {
+        Self::from_parts(read, delimiters, Options::default(), None)
+    }
+
+    fn from_parts(
+        read: T,
+        delimiters: &'static [PacketLine<'static>],
+        options: Options,
+        inspect: Option<Inspector>,
+    ) -> Self {
         StreamingPeekableIter {
             read,
             #[cfg(any(feature = "blocking-io", feature = "async-io"))]
             buf: vec![0; MAX_LINE_LEN],
             peek_buf: Vec::new(),
             delimiters,
-            fail_on_err_lines: false,
+            options,
+            inspect,
             is_done: false,
             stopped_at: None,
         }
@@ -82,14 +183,25 @@ impl<T> StreamingPeekableIter<T> {
     /// Use [`stopped_at()]`[StreamingPeekableIter::stopped_at()] to inspect the cause of the end of the iteration.
     /// ne
     pub fn fail_on_err_lines(&mut self, value: bool) {
-        self.fail_on_err_lines = value;
+        self.options.fail_on_err_lines = value;
+    }
+
+    /// Set the `policy` to apply when encountering empty data lines, which some servers use as keep-alive packets.
+    pub fn empty_line_policy(&mut self, policy: EmptyLinePolicy) {
+        self.options.empty_lines = policy;
+    }
+
+    /// Call `inspect` with every packet line read from the underlying reader, which includes delimiters and lines that are
+    /// only peeked at, replacing the previous inspector. Lines which fail to decode are not passed.
+    pub fn set_inspector(&mut self, inspect: Option<Inspector>) {
+        self.inspect = inspect;
     }
 
     /// Replace the reader used with the given `read`, resetting all other iteration state as well.
     pub fn replace(&mut self, read: T) -> T {
         let prev = std::mem::replace(&mut self.read, read);
         self.reset();
-        self.fail_on_err_lines = false;
+        self.options.fail_on_err_lines = false;
         prev
     }
 
@@ -99,6 +211,12 @@ impl<T> StreamingPeekableIter<T> {
     }
 }
 
+mod builder;
+pub use builder::Builder;
+
+///
+pub mod fault;
+
 #[cfg(feature = "blocking-io")]
 mod blocking_io;
 
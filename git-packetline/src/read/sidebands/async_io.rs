@@ -1,6 +1,7 @@
 use crate::{
     decode,
     immutable::{Band, Text},
+    read::{sidebands::interrupted_by_progress_handler, HandleProgress, ProgressAction},
     PacketLine, StreamingPeekableIter, U16_HEX_BYTES,
 };
 use futures_io::{AsyncBufRead, AsyncRead};
@@ -31,12 +32,14 @@ where
 {
     fn drop(&mut self) {
         if let State::Idle { ref mut parent } = self.state {
-            parent.as_mut().unwrap().reset();
+            let parent = parent.as_mut().unwrap();
+            parent.options.sidebands = false;
+            parent.reset();
         }
     }
 }
 
-impl<'a, T> WithSidebands<'a, T, fn(bool, &[u8])>
+impl<'a, T> WithSidebands<'a, T, HandleProgress>
 where
     T: AsyncRead,
 {
@@ -88,12 +91,12 @@ mod tests {
 impl<'a, T, F> WithSidebands<'a, T, F>
 where
     T: AsyncRead + Unpin,
-    F: FnMut(bool, &[u8]) + Unpin,
+    F: FnMut(bool, &[u8]) -> ProgressAction + Unpin,
 {
     /// Create a new instance with the given `parent` provider and the `handle_progress` function.
     ///
     /// Progress or error information will be passed to the given `handle_progress(is_error, text)` function, with `is_error: bool`
-    /// being true in case the `text` is to be interpreted as error.
+    /// being true in case the `text` is to be interpreted as error. It returns a [`ProgressAction`] to decide if reading continues.
     pub fn with_progress_handler(parent: &'a mut StreamingPeekableIter<T>, handle_progress: F) -> Self {
         WithSidebands {
             state: State::Idle { parent: Some(parent) },
@@ -137,7 +140,11 @@ where
     /// next on a call to [`read_line()`][io::BufRead::read_line()].
     pub async fn peek_data_line(&mut self) -> Option<std::io::Result<Result<&[u8], crate::decode::Error>>> {
         match self.state {
-            State::Idle { ref mut parent } => match parent.as_mut().unwrap().peek_line().await {
+            State::Idle { ref mut parent } => match {
+                let parent = parent.as_mut().unwrap();
+                parent.options.sidebands = self.handle_progress.is_some();
+                parent.peek_line().await
+            } {
                 Some(Ok(Ok(crate::PacketLine::Data(line)))) => Some(Ok(Ok(line))),
                 Some(Ok(Err(err))) => Some(Ok(Err(err))),
                 Some(Err(err)) => Some(Err(err)),
@@ -161,7 +168,7 @@ pub struct ReadLineFuture<'a, 'b, T: AsyncRead, F> {
 impl<'a, 'b, T, F> Future for ReadLineFuture<'a, 'b, T, F>
 where
     T: AsyncRead + Unpin,
-    F: FnMut(bool, &[u8]) + Unpin,
+    F: FnMut(bool, &[u8]) -> ProgressAction + Unpin,
 {
     type Output = std::io::Result<usize>;
 
@@ -185,7 +192,7 @@ where
 impl<'a, T, F> AsyncBufRead for WithSidebands<'a, T, F>
 where
     T: AsyncRead + Unpin,
-    F: FnMut(bool, &[u8]) + Unpin,
+    F: FnMut(bool, &[u8]) -> ProgressAction + Unpin,
 {
     fn poll_fill_buf(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
         use futures_lite::FutureExt;
@@ -197,6 +204,7 @@ where
                     match this.state {
                         State::Idle { ref mut parent } => {
                             let parent = parent.take().expect("parent to be present here");
+                            parent.options.sidebands = this.handle_progress.is_some();
                             let inactive = parent as *mut _;
                             this.state = State::ReadLine {
                                 read_line: parent.read_line().boxed_local(),
@@ -228,23 +236,24 @@ where
                                 None => break (0, 0),
                             };
 
+                            if line.as_slice().map_or(false, |d| d.is_empty()) {
+                                // skip keep-alive packets as empty data would signal the end of the stream
+                                continue;
+                            }
                             match this.handle_progress.as_mut() {
                                 Some(handle_progress) => {
                                     let band = line
                                         .decode_band()
                                         .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
                                     const ENCODED_BAND: usize = 1;
-                                    match band {
+                                    let (is_error, text) = match band {
                                         Band::Data(d) => break (U16_HEX_BYTES + ENCODED_BAND, d.len()),
-                                        Band::Progress(d) => {
-                                            let text = Text::from(d).0;
-                                            handle_progress(false, text);
-                                        }
-                                        Band::Error(d) => {
-                                            let text = Text::from(d).0;
-                                            handle_progress(true, text);
-                                        }
+                                        Band::Progress(d) => (false, Text::from(d).0),
+                                        Band::Error(d) => (true, Text::from(d).0),
                                     };
+                                    if handle_progress(is_error, text) == ProgressAction::Interrupt {
+                                        return Poll::Ready(Err(interrupted_by_progress_handler(is_error, text)));
+                                    }
                                 }
                                 None => {
                                     break match line.as_slice() {
@@ -281,7 +290,7 @@ where
 impl<'a, T, F> AsyncRead for WithSidebands<'a, T, F>
 where
     T: AsyncRead + Unpin,
-    F: FnMut(bool, &[u8]) + Unpin,
+    F: FnMut(bool, &[u8]) -> ProgressAction + Unpin,
 {
     fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
         let nread = {
@@ -1,5 +1,6 @@
 use crate::{
     immutable::{Band, Text},
+    read::{sidebands::interrupted_by_progress_handler, HandleProgress, ProgressAction},
     PacketLine, StreamingPeekableIter, U16_HEX_BYTES,
 };
 use std::{io, io::BufRead};
@@ -22,11 +23,12 @@ where
     T: io::Read,
 {
     fn drop(&mut self) {
+        self.parent.options.sidebands = false;
         self.parent.reset();
     }
 }
 
-impl<'a, T> WithSidebands<'a, T, fn(bool, &[u8])>
+impl<'a, T> WithSidebands<'a, T, HandleProgress>
 where
     T: io::Read,
 {
@@ -44,12 +46,12 @@ where
 impl<'a, T, F> WithSidebands<'a, T, F>
 where
     T: io::Read,
-    F: FnMut(bool, &[u8]),
+    F: FnMut(bool, &[u8]) -> ProgressAction,
 {
     /// Create a new instance with the given `parent` provider and the `handle_progress` function.
     ///
     /// Progress or error information will be passed to the given `handle_progress(is_error, text)` function, with `is_error: bool`
-    /// being true in case the `text` is to be interpreted as error.
+    /// being true in case the `text` is to be interpreted as error. It returns a [`ProgressAction`] to decide if reading continues.
     pub fn with_progress_handler(parent: &'a mut StreamingPeekableIter<T>, handle_progress: F) -> Self {
         WithSidebands {
             parent,
@@ -87,6 +89,7 @@ where
     /// Effectively forwards to the parent [StreamingPeekableIter::peek_line()], allowing to see what would be returned
     /// next on a call to [`read_line()`][io::BufRead::read_line()].
     pub fn peek_data_line(&mut self) -> Option<io::Result<Result<&[u8], crate::decode::Error>>> {
+        self.parent.options.sidebands = self.handle_progress.is_some();
         match self.parent.peek_line() {
             Some(Ok(Ok(crate::PacketLine::Data(line)))) => Some(Ok(Ok(line))),
             Some(Ok(Err(err))) => Some(Ok(Err(err))),
@@ -99,32 +102,34 @@ where
 impl<'a, T, F> BufRead for WithSidebands<'a, T, F>
 where
     T: io::Read,
-    F: FnMut(bool, &[u8]),
+    F: FnMut(bool, &[u8]) -> ProgressAction,
 {
     fn fill_buf(&mut self) -> io::Result<&[u8]> {
         if self.pos >= self.cap {
+            self.parent.options.sidebands = self.handle_progress.is_some();
             let (ofs, cap) = loop {
                 let line = match self.parent.read_line() {
                     Some(line) => line?.map_err(|err| io::Error::new(io::ErrorKind::Other, err))?,
                     None => break (0, 0),
                 };
+                if line.as_slice().map_or(false, |d| d.is_empty()) {
+                    // skip keep-alive packets as empty data would signal the end of the stream
+                    continue;
+                }
                 match self.handle_progress.as_mut() {
                     Some(handle_progress) => {
                         let band = line
                             .decode_band()
                             .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
                         const ENCODED_BAND: usize = 1;
-                        match band {
+                        let (is_error, text) = match band {
                             Band::Data(d) => break (U16_HEX_BYTES + ENCODED_BAND, d.len()),
-                            Band::Progress(d) => {
-                                let text = Text::from(d).0;
-                                handle_progress(false, text);
-                            }
-                            Band::Error(d) => {
-                                let text = Text::from(d).0;
-                                handle_progress(true, text);
-                            }
+                            Band::Progress(d) => (false, Text::from(d).0),
+                            Band::Error(d) => (true, Text::from(d).0),
                         };
+                        if handle_progress(is_error, text) == ProgressAction::Interrupt {
+                            return Err(interrupted_by_progress_handler(is_error, text));
+                        }
                     }
                     None => {
                         break match line.as_slice() {
@@ -167,7 +172,7 @@ where
 impl<'a, T, F> io::Read for WithSidebands<'a, T, F>
 where
     T: io::Read,
-    F: FnMut(bool, &[u8]),
+    F: FnMut(bool, &[u8]) -> ProgressAction,
 {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         let nread = {
@@ -7,3 +7,14 @@ pub use blocking_io::WithSidebands;
 mod async_io;
 #[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
 pub use async_io::WithSidebands;
+
+/// Create the error to return if the progress handler interrupted reading after receiving `text`.
+#[cfg(any(feature = "blocking-io", feature = "async-io"))]
+fn interrupted_by_progress_handler(is_error: bool, text: &[u8]) -> std::io::Error {
+    let message = if is_error {
+        format!("The remote reported an error: {}", String::from_utf8_lossy(text))
+    } else {
+        "Reading was interrupted by the progress handler".into()
+    };
+    std::io::Error::new(std::io::ErrorKind::Other, message)
+}
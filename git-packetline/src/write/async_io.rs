@@ -1,4 +1,8 @@
-use crate::{encode, MAX_DATA_LEN, U16_HEX_BYTES};
+use crate::{
+    encode,
+    inspect::{Direction, Inspector, Packet},
+    PacketLine, MAX_DATA_LEN, U16_HEX_BYTES,
+};
 use futures_io::AsyncWrite;
 use std::{
     io,
@@ -13,6 +17,7 @@ pin_project_lite::pin_project! {
         #[pin]
         inner: encode::LineWriter<'static, T>,
         state: State,
+        pub(crate) inspect: Option<Inspector>,
     }
 }
 
@@ -27,6 +32,7 @@ impl<T: AsyncWrite + Unpin> Writer<T> {
         Writer {
             inner: encode::LineWriter::new(write, &[], &[]),
             state: State::Idle,
+            inspect: None,
         }
     }
 
@@ -77,6 +83,13 @@ impl<T: AsyncWrite + Unpin> AsyncWrite for Writer<T> {
                         }
                         *written += n;
                         *written -= U16_HEX_BYTES + this.inner.suffix.len();
+                        if let Some(inspect) = this.inspect.as_mut() {
+                            inspect(&Packet {
+                                direction: Direction::Write,
+                                band: None,
+                                line: PacketLine::Data(data),
+                            });
+                        }
                     }
                     *this.state = State::Idle;
                     return Poll::Ready(Ok(buf.len()));
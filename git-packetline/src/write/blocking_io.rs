@@ -1,4 +1,7 @@
-use crate::{MAX_DATA_LEN, U16_HEX_BYTES};
+use crate::{
+    inspect::{Direction, Inspector, Packet},
+    PacketLine, MAX_DATA_LEN, U16_HEX_BYTES,
+};
 use std::io;
 
 /// An implementor of [`Write`][io::Write] which passes all input to an inner `Write` in packet line data encoding,
@@ -7,6 +10,7 @@ pub struct Writer<T> {
     /// the `Write` implementation to which to propagate packet lines
     inner: T,
     pub(crate) binary: bool,
+    pub(crate) inspect: Option<Inspector>,
 }
 
 impl<T: io::Write> Writer<T> {
@@ -15,6 +19,7 @@ impl<T: io::Write> Writer<T> {
         Writer {
             inner: write,
             binary: true,
+            inspect: None,
         }
     }
 }
@@ -59,6 +64,13 @@ impl<T: io::Write> io::Write for Writer<T> {
             }?;
             // subtract header (and trailng NL) because write-all can't handle writing more than it passes in
             written -= U16_HEX_BYTES + if self.binary { 0 } else { 1 };
+            if let Some(inspect) = self.inspect.as_mut() {
+                inspect(&Packet {
+                    direction: Direction::Write,
+                    band: None,
+                    line: PacketLine::Data(data),
+                });
+            }
             buf = rest;
         }
         Ok(written)
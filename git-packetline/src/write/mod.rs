@@ -1,3 +1,5 @@
+use crate::inspect::{Inspector, Packet};
+
 #[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
 mod async_io;
 #[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
@@ -8,6 +10,9 @@ mod blocking_io;
 #[cfg(feature = "blocking-io")]
 pub use blocking_io::Writer;
 
+mod sidebands;
+pub use sidebands::{Band, WithSidebands};
+
 /// Common methods
 impl<T> Writer<T> {
     /// As [`enable_text_mode()`][Writer::enable_text_mode()], but suitable for chaining.
@@ -20,4 +25,15 @@ impl<T> Writer<T> {
         self.enable_binary_mode();
         self
     }
+    /// As [`set_inspector()`][Writer::set_inspector()], but suitable for chaining.
+    pub fn inspect(mut self, inspect: impl FnMut(&Packet<'_>) + Send + 'static) -> Self {
+        self.set_inspector(Some(Box::new(inspect)));
+        self
+    }
+    /// Call `inspect` with every packet line right after it was written to the inner writer, replacing the previous inspector.
+    ///
+    /// This allows to log all sent packet lines like `GIT_TRACE_PACKET` does.
+    pub fn set_inspector(&mut self, inspect: Option<Inspector>) {
+        self.inspect = inspect;
+    }
 }
@@ -0,0 +1,131 @@
+use crate::{
+    encode,
+    inspect::Inspector,
+    write::sidebands::{empty_line_error, inspect_written_line, Band, MAX_BAND_DATA_LEN},
+    Channel, U16_HEX_BYTES,
+};
+use futures_io::AsyncWrite;
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// An implementor of [`AsyncWrite`] which passes all input to an inner `AsyncWrite` as side-band encoded packet lines,
+/// writing to the data band by default while allowing to interleave [progress][WithSidebands::progress()] and
+/// [error][WithSidebands::error()] messages.
+///
+/// Each `write(…)` call produces one line, or as many lines as it takes if the data doesn't fit into the maximum allowed
+/// line length. A pending write must be completed before writing to another side-band.
+pub struct WithSidebands<T> {
+    inner: encode::LineWriter<'static, T>,
+    state: State,
+    pub(crate) inspect: Option<Inspector>,
+    /// A buffer to hold the line passed to the inspector
+    inspect_buf: Vec<u8>,
+}
+
+enum State {
+    Idle,
+    WriteData { channel: Channel, written: usize },
+}
+
+fn band_prefix(channel: Channel) -> &'static [u8] {
+    match channel {
+        Channel::Data => &[Channel::Data as u8],
+        Channel::Progress => &[Channel::Progress as u8],
+        Channel::Error => &[Channel::Error as u8],
+    }
+}
+
+impl<T: AsyncWrite + Unpin> WithSidebands<T> {
+    /// Create a new instance from the given `write`
+    pub fn new(write: T) -> Self {
+        WithSidebands {
+            inner: encode::LineWriter::new(write, &[], &[]),
+            state: State::Idle,
+            inspect: None,
+            inspect_buf: Vec::new(),
+        }
+    }
+
+    /// Return the inner writer, consuming self.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    /// Return a mutable reference to the inner writer, useful if packet lines like flush lines should be written directly.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner.writer
+    }
+
+    /// Write all of `data` to the side-band `channel`, returning the amount of bytes of `data` written.
+    pub async fn write_band(&mut self, channel: Channel, data: &[u8]) -> io::Result<usize> {
+        futures_lite::future::poll_fn(|cx| self.poll_write_band(cx, channel, data)).await
+    }
+
+    fn poll_write_band(&mut self, cx: &mut Context<'_>, channel: Channel, buf: &[u8]) -> Poll<io::Result<usize>> {
+        loop {
+            match &mut self.state {
+                State::Idle => {
+                    if buf.is_empty() {
+                        return Poll::Ready(Err(empty_line_error()));
+                    }
+                    self.inner.prefix = band_prefix(channel);
+                    self.state = State::WriteData { channel, written: 0 }
+                }
+                State::WriteData {
+                    channel: current_channel,
+                    written,
+                } => {
+                    if *current_channel != channel {
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            "a write to another side-band is still in progress",
+                        )));
+                    }
+                    while *written != buf.len() {
+                        let data = &buf[*written..*written + (buf.len() - *written).min(MAX_BAND_DATA_LEN)];
+                        let n = futures_lite::ready!(Pin::new(&mut self.inner).poll_write(cx, data))?;
+                        if n == 0 {
+                            return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+                        }
+                        *written += n - (U16_HEX_BYTES + 1);
+                        inspect_written_line(&mut self.inspect, &mut self.inspect_buf, channel, data);
+                    }
+                    self.state = State::Idle;
+                    return Poll::Ready(Ok(buf.len()));
+                }
+            }
+        }
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for WithSidebands<T> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_band(cx, Channel::Data, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Band<'_, T> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        this.parent.poll_write_band(cx, this.channel, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.get_mut().parent).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.get_mut().parent).poll_close(cx)
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{
+    inspect::Inspector,
+    write::sidebands::{empty_line_error, inspect_written_line, Band, MAX_BAND_DATA_LEN},
+    Channel,
+};
+use std::io;
+
+/// An implementor of [`Write`][io::Write] which passes all input to an inner `Write` as side-band encoded packet lines,
+/// writing to the data band by default while allowing to interleave [progress][WithSidebands::progress()] and
+/// [error][WithSidebands::error()] messages.
+///
+/// Each `write(…)` call produces one line, or as many lines as it takes if the data doesn't fit into the maximum allowed
+/// line length.
+pub struct WithSidebands<T> {
+    /// the `Write` implementation to which to propagate packet lines
+    inner: T,
+    pub(crate) inspect: Option<Inspector>,
+    /// A buffer to hold the line passed to the inspector
+    inspect_buf: Vec<u8>,
+}
+
+impl<T: io::Write> WithSidebands<T> {
+    /// Create a new instance from the given `write`
+    pub fn new(write: T) -> Self {
+        WithSidebands {
+            inner: write,
+            inspect: None,
+            inspect_buf: Vec::new(),
+        }
+    }
+
+    /// Write all of `data` to the side-band `channel`, returning the amount of bytes of `data` written.
+    pub fn write_band(&mut self, channel: Channel, mut data: &[u8]) -> io::Result<usize> {
+        if data.is_empty() {
+            return Err(empty_line_error());
+        }
+        let len = data.len();
+        while !data.is_empty() {
+            let (line, rest) = data.split_at(data.len().min(MAX_BAND_DATA_LEN));
+            crate::encode::band_to_write(channel, line, &mut self.inner)?;
+            inspect_written_line(&mut self.inspect, &mut self.inspect_buf, channel, line);
+            data = rest;
+        }
+        Ok(len)
+    }
+}
+
+/// Non-IO methods
+impl<T> WithSidebands<T> {
+    /// Return the inner writer, consuming self.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+    /// Return a mutable reference to the inner writer, useful if packet lines like flush lines should be written directly.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+impl<T: io::Write> io::Write for WithSidebands<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_band(Channel::Data, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: io::Write> io::Write for Band<'_, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.parent.write_band(self.channel, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.parent.flush()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    inspect::{Direction, Inspector, Packet},
+    Channel, PacketLine,
+};
+
+#[cfg(feature = "blocking-io")]
+mod blocking_io;
+#[cfg(feature = "blocking-io")]
+pub use blocking_io::WithSidebands;
+
+#[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
+mod async_io;
+#[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
+pub use async_io::WithSidebands;
+
+/// The amount of bytes of data fitting into a single side-band line, as the side-band byte counts towards the line length.
+const MAX_BAND_DATA_LEN: usize = crate::MAX_DATA_LEN - 1;
+
+/// A writer for a single side-band of a [`WithSidebands`] writer, obtained by [`WithSidebands::progress()`]
+/// or [`WithSidebands::error()`].
+pub struct Band<'a, T> {
+    parent: &'a mut WithSidebands<T>,
+    channel: Channel,
+}
+
+/// Common methods
+impl<T> WithSidebands<T> {
+    /// Return a writer which writes each buffer it receives as progress message on side-band 2.
+    pub fn progress(&mut self) -> Band<'_, T> {
+        Band {
+            parent: self,
+            channel: Channel::Progress,
+        }
+    }
+    /// Return a writer which writes each buffer it receives as error message on side-band 3.
+    ///
+    /// Note that the remote typically aborts the connection after receiving an error.
+    pub fn error(&mut self) -> Band<'_, T> {
+        Band {
+            parent: self,
+            channel: Channel::Error,
+        }
+    }
+    /// As [`set_inspector()`][WithSidebands::set_inspector()], but suitable for chaining.
+    pub fn inspect(mut self, inspect: impl FnMut(&Packet<'_>) + Send + 'static) -> Self {
+        self.set_inspector(Some(Box::new(inspect)));
+        self
+    }
+    /// Call `inspect` with every packet line right after it was written to the inner writer along with its side-band,
+    /// replacing the previous inspector.
+    pub fn set_inspector(&mut self, inspect: Option<Inspector>) {
+        self.inspect = inspect;
+    }
+}
+
+impl<T> Band<'_, T> {
+    /// Return the side-band this writer writes to.
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+}
+
+/// Pass the `data` just written to `channel` to `inspect`, using `buf` to assemble the line as it was transmitted.
+fn inspect_written_line(inspect: &mut Option<Inspector>, buf: &mut Vec<u8>, channel: Channel, data: &[u8]) {
+    if let Some(inspect) = inspect {
+        buf.clear();
+        buf.push(channel as u8);
+        buf.extend_from_slice(data);
+        inspect(&Packet {
+            direction: Direction::Write,
+            band: Some(channel),
+            line: PacketLine::Data(buf),
+        });
+    }
+}
+
+/// Create the error to return when trying to write an empty line.
+fn empty_line_error() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "empty packet lines are not permitted as '0004' is invalid",
+    )
+}
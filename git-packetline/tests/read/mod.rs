@@ -70,6 +70,47 @@ pub mod streaming_peek_iter {
         Ok(())
     }
 
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn empty_lines_are_an_error_by_default() -> crate::Result {
+        let mut rd = git_packetline::StreamingPeekableIter::new(&b"00040005a"[..], &[PacketLine::Flush]);
+        let res = rd.read_line().await;
+        assert!(matches!(
+            res.expect("line")?,
+            Err(git_packetline::decode::Error::DataIsEmpty)
+        ));
+        let res = rd.read_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b"a"), "reading can continue");
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn empty_lines_can_be_skipped() -> crate::Result {
+        let mut rd = git_packetline::StreamingPeekableIter::new(&b"000400040005a0000"[..], &[PacketLine::Flush]);
+        rd.empty_line_policy(git_packetline::read::EmptyLinePolicy::Skip);
+        let res = rd.peek_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b"a"));
+        let res = rd.read_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b"a"));
+        let res = rd.read_line().await;
+        assert!(res.is_none());
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn empty_lines_can_be_surfaced() -> crate::Result {
+        let mut rd = git_packetline::StreamingPeekableIter::new(&b"00040005a0000"[..], &[PacketLine::Flush]);
+        rd.empty_line_policy(git_packetline::read::EmptyLinePolicy::Surface);
+        let res = rd.peek_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b""), "peeking works as well");
+        let res = rd.read_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b""));
+        let res = rd.read_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b"a"));
+        let res = rd.read_line().await;
+        assert!(res.is_none());
+        Ok(())
+    }
+
     #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
     async fn peek_non_data() -> crate::Result {
         let mut rd = git_packetline::StreamingPeekableIter::new(&b"000000010002"[..], &[PacketLine::ResponseEnd]);
@@ -197,6 +238,129 @@ pub mod streaming_peek_iter {
         Ok(())
     }
 
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn builder_configures_delimiters_and_err_lines() -> crate::Result {
+        let mut rd = git_packetline::read::Builder::default()
+            .delimiters(&[PacketLine::Delimiter])
+            .fail_on_err_lines(true)
+            .build(&b"0005a0009ERR e0001"[..]);
+        let res = rd.read_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b"a"));
+        let res = rd.read_line().await;
+        assert_eq!(res.expect("line").unwrap_err().to_string(), "e");
+
+        let mut rd = git_packetline::read::Builder::default()
+            .delimiters(&[PacketLine::Delimiter])
+            .build(&b"0009ERR e0001"[..]);
+        let res = rd.read_line().await;
+        assert_eq!(res.expect("line")??.as_bstr(), Some(b"ERR e".as_bstr()));
+        let res = rd.read_line().await;
+        assert!(res.is_none());
+        assert_eq!(rd.stopped_at(), Some(PacketLine::Delimiter));
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn builder_limits_the_line_length() -> crate::Result {
+        let mut rd = git_packetline::read::Builder::default()
+            .max_line_len(8)
+            .build(&b"0008abcd0009abcde"[..]);
+        let res = rd.read_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b"abcd"));
+        let res = rd.read_line().await;
+        assert_eq!(
+            res.expect("line")?.unwrap_err().to_string(),
+            "The line received claims to be 9 bytes long, exceeding the configured limit of 8 bytes"
+        );
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn builder_traces_each_line_read_from_the_underlying_reader() -> crate::Result {
+        let lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut rd = git_packetline::read::Builder::default()
+            .delimiters(&[PacketLine::Flush])
+            .trace({
+                let lines = lines.clone();
+                move |line| lines.lock().unwrap().push(format!("{:?}", line))
+            })
+            .build(&b"0005a00010005b0000"[..]);
+        let res = rd.peek_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b"a"));
+        let res = rd.read_line().await;
+        assert_eq!(res.expect("line")??, PacketLine::Data(b"a"));
+        let count = exhaust(&mut rd).await;
+        assert_eq!(count, 2);
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec!["Data([97])", "Delimiter", "Data([98])", "Flush"],
+            "peeked lines are traced only once, and delimiters are traced as well"
+        );
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn inspectors_receive_each_line_read_without_side_band() -> crate::Result {
+        use git_packetline::inspect::Direction;
+        let packets = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut rd = git_packetline::StreamingPeekableIter::new(&b"0005a0006\x01b0000"[..], &[PacketLine::Flush]);
+        rd.set_inspector(Some(Box::new({
+            let packets = packets.clone();
+            move |packet: &git_packetline::inspect::Packet<'_>| {
+                packets.lock().unwrap().push((
+                    packet.direction,
+                    packet.band,
+                    packet.payload().map(|data| data.as_bstr().to_owned()),
+                ))
+            }
+        })));
+        let count = exhaust(&mut rd).await;
+        assert_eq!(count, 2);
+        assert_eq!(
+            *packets.lock().unwrap(),
+            vec![
+                (Direction::Read, None, Some("a".into())),
+                (Direction::Read, None, Some("\x01b".into())),
+                (Direction::Read, None, None)
+            ],
+            "side-bands are not decoded when reading lines directly"
+        );
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn builder_with_faults_yields_the_same_lines() -> crate::Result {
+        let bytes = fixture_bytes("v1/fetch/01-many-refs.response");
+        for faults in &[
+            git_packetline::read::fault::Faults {
+                max_bytes_per_read: Some(1),
+                interrupt_after_reads: None,
+            },
+            git_packetline::read::fault::Faults {
+                max_bytes_per_read: None,
+                interrupt_after_reads: Some(0),
+            },
+            git_packetline::read::fault::Faults {
+                max_bytes_per_read: Some(3),
+                interrupt_after_reads: Some(2),
+            },
+        ] {
+            let mut expected = git_packetline::StreamingPeekableIter::new(&bytes[..], &[PacketLine::Flush]);
+            let mut rd = git_packetline::read::Builder::default()
+                .delimiters(&[PacketLine::Flush])
+                .build_with_faults(&bytes[..], *faults);
+            let mut count = 0;
+            while let Some(line) = rd.read_line().await {
+                let expected_line = expected.read_line().await.expect("same amount of lines")??;
+                assert_eq!(line??, expected_line, "{:?}", faults);
+                count += 1;
+            }
+            assert_eq!(count, 1561, "{:?}", faults);
+            assert_eq!(rd.stopped_at(), Some(PacketLine::Flush));
+        }
+        Ok(())
+    }
+
     #[maybe_async::maybe_async]
     async fn exhaust(rd: &mut git_packetline::StreamingPeekableIter<&[u8]>) -> i32 {
         let mut count = 0;
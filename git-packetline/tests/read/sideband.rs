@@ -3,7 +3,7 @@ use bstr::{BString, ByteSlice};
 #[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
 use futures_lite::io::AsyncReadExt;
 use git_odb::pack;
-use git_packetline::PacketLine;
+use git_packetline::{read::ProgressAction, PacketLine};
 #[cfg(feature = "blocking-io")]
 use std::io::{BufRead, Read};
 
@@ -51,6 +51,7 @@ async fn read_pack_with_progress_extraction() -> crate::Result {
     let mut do_nothing = |is_err: bool, data: &[u8]| {
         assert!(!is_err);
         seen_texts.push(data.as_bstr().into());
+        ProgressAction::Continue
     };
     let pack_read = rd.as_read_with_sidebands(&mut do_nothing);
     #[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
@@ -68,9 +69,7 @@ async fn read_pack_with_progress_extraction() -> crate::Result {
     let all_but_last = pack_entries.size_hint().0 - 1;
     let last = pack_entries.skip(all_but_last).next().expect("last entry")?;
     assert_eq!(
-        last.trailer
-            .expect("trailer to exist on last entry")
-            .to_sha1_hex_string(),
+        last.trailer.expect("trailer to exist on last entry").to_string(),
         "150a1045f04dc0fc2dbf72313699fda696bf4126"
     );
     assert_eq!(
@@ -124,7 +123,7 @@ async fn read_line_trait_method_reads_one_packet_line_at_a_time() -> crate::Resu
 
     drop(r);
 
-    let mut r = rd.as_read_with_sidebands(|_, _| ());
+    let mut r = rd.as_read_with_sidebands(|_, _| ProgressAction::Continue);
     out.clear();
     r.read_line(&mut out).await?;
     assert_eq!(out, "&");
@@ -212,3 +211,76 @@ async fn handling_of_err_lines() {
         "An error can also be the reason, which is not distinguishable from an EOF"
     );
 }
+
+#[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+async fn inspectors_receive_the_side_band_of_lines_read_with_a_progress_handler() -> crate::Result {
+    use git_packetline::{inspect::Direction, Channel};
+    let packets = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut rd = git_packetline::read::Builder::default()
+        .delimiters(&[PacketLine::Flush])
+        .inspect({
+            let packets = packets.clone();
+            move |packet| {
+                assert_eq!(packet.direction, Direction::Read);
+                packets
+                    .lock()
+                    .unwrap()
+                    .push((packet.band, packet.payload().map(|data| BString::from(data.to_owned()))))
+            }
+        })
+        .build(&b"000a\x02hello0008\x01abc00000005x"[..]);
+    let mut out = Vec::new();
+    rd.as_read_with_sidebands(|_, _| ProgressAction::Continue)
+        .read_to_end(&mut out)
+        .await?;
+    assert_eq!(out.as_bstr(), "abc");
+    let line = rd.read_line().await.expect("line")??;
+    assert_eq!(line, PacketLine::Data(b"x"));
+    assert_eq!(
+        *packets.lock().unwrap(),
+        vec![
+            (Some(Channel::Progress), Some("hello".into())),
+            (Some(Channel::Data), Some("abc".into())),
+            (None, None),
+            (None, Some("x".into()))
+        ],
+        "the side-band is only known while reading with sidebands"
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+async fn progress_handler_can_interrupt_on_error_bands() -> crate::Result {
+    let input = b"000a\x03fatal0008\x01abc0000";
+    let mut rd = git_packetline::StreamingPeekableIter::new(&input[..], &[PacketLine::Flush]);
+    let mut out = Vec::new();
+    rd.as_read_with_sidebands(|_, _| ProgressAction::Continue)
+        .read_to_end(&mut out)
+        .await?;
+    assert_eq!(
+        out.as_bstr(),
+        "abc",
+        "by default, errors are passed on to the handler only"
+    );
+
+    let mut rd = git_packetline::StreamingPeekableIter::new(&input[..], &[PacketLine::Flush]);
+    let mut seen_errors = Vec::<BString>::new();
+    let mut reader = rd.as_read_with_sidebands(|is_err: bool, text: &[u8]| {
+        if is_err {
+            seen_errors.push(text.as_bstr().into());
+            ProgressAction::Interrupt
+        } else {
+            ProgressAction::Continue
+        }
+    });
+    let mut buf = [0u8; 3];
+    let res = reader.read(buf.as_mut()).await;
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "The remote reported an error: fatal",
+        "the read is aborted before data following the error is read"
+    );
+    drop(reader);
+    assert_eq!(seen_errors, vec![BString::from("fatal")]);
+    Ok(())
+}
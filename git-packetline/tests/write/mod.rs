@@ -55,3 +55,119 @@ async fn empty_writes_fail_with_error() {
         "empty packet lines are not permitted as '0004' is invalid"
     );
 }
+
+#[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+async fn inspectors_receive_each_line_written() -> crate::Result {
+    use git_packetline::inspect::Direction;
+    let packets = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut w = Writer::new(Vec::new()).text_mode().inspect({
+        let packets = packets.clone();
+        move |packet| {
+            packets.lock().unwrap().push((
+                packet.direction,
+                packet.band,
+                packet.payload().map(|data| data.as_bstr().to_owned()),
+            ))
+        }
+    });
+    w.write_all(b"hello").await?;
+    w = w.binary_mode();
+    w.write(b"world").await?;
+    assert_eq!(w.into_inner().as_bstr(), b"000ahello\n0009world".as_bstr());
+    assert_eq!(
+        *packets.lock().unwrap(),
+        vec![
+            (Direction::Write, None, Some("hello".into())),
+            (Direction::Write, None, Some("world".into()))
+        ],
+        "the newline appended in text mode is not passed"
+    );
+    Ok(())
+}
+
+mod with_sidebands {
+    use bstr::ByteSlice;
+    #[cfg(all(feature = "async-io", not(feature = "blocking-io")))]
+    use futures_lite::prelude::*;
+    use git_packetline::{read::ProgressAction, write::WithSidebands, Channel, PacketLine};
+    #[cfg(feature = "blocking-io")]
+    use std::io::{Read, Write};
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn data_progress_and_errors_are_written_to_their_band() -> crate::Result {
+        let mut w = WithSidebands::new(Vec::new());
+        w.write_all(b"abc").await?;
+        w.progress().write_all(b"50%\r").await?;
+        w.write(b"d").await?;
+        w.error().write_all(b"fatal").await?;
+        assert_eq!(
+            w.into_inner().as_bstr(),
+            b"0008\x01abc0009\x0250%\r0006\x01d000a\x03fatal".as_bstr()
+        );
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn huge_writes_are_split_into_lines_that_read_back_the_same() -> crate::Result {
+        let data: Vec<u8> = (0..super::MAX_DATA_LEN * 2).map(|n| n as u8).collect();
+        let mut w = WithSidebands::new(Vec::new());
+        w.write(&data).await?;
+        w.progress().write_all(b"done\n").await?;
+        git_packetline::encode::flush_to_write(w.inner_mut()).await?;
+        let buf = w.into_inner();
+        assert_eq!(
+            buf.len(),
+            super::MAX_LINE_LEN * 2 + (4 + 1 + 2) + (4 + 1 + 5) + 4,
+            "the side-band byte takes one byte of each line, requiring another line"
+        );
+
+        let mut rd = git_packetline::StreamingPeekableIter::new(&buf[..], &[PacketLine::Flush]);
+        let mut progress = Vec::new();
+        let mut out = Vec::new();
+        rd.as_read_with_sidebands(|is_err, text| {
+            assert!(!is_err);
+            progress.extend_from_slice(text);
+            ProgressAction::Continue
+        })
+        .read_to_end(&mut out)
+        .await?;
+        assert!(out == data, "the data is read back as written");
+        assert_eq!(progress.as_bstr(), "done", "the reader strips the trailing newline");
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn empty_writes_fail_with_error() {
+        let mut w = WithSidebands::new(Vec::new());
+        let res = w.progress().write(&[]).await;
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "empty packet lines are not permitted as '0004' is invalid"
+        );
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn inspectors_receive_each_line_written_along_with_its_band() -> crate::Result {
+        let packets = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut w = WithSidebands::new(Vec::new()).inspect({
+            let packets = packets.clone();
+            move |packet| {
+                packets.lock().unwrap().push((
+                    packet.band,
+                    packet.line.as_bstr().map(ToOwned::to_owned),
+                    packet.payload().map(|data| data.as_bstr().to_owned()),
+                ))
+            }
+        });
+        w.progress().write_all(b"hi").await?;
+        w.write_all(b"abc").await?;
+        assert_eq!(
+            *packets.lock().unwrap(),
+            vec![
+                (Some(Channel::Progress), Some("\x02hi".into()), Some("hi".into())),
+                (Some(Channel::Data), Some("\x01abc".into()), Some("abc".into()))
+            ]
+        );
+        Ok(())
+    }
+}
@@ -40,7 +40,9 @@ impl Arguments {
                 transport
                     .invoke(
                         Command::Fetch.as_str(),
-                        self.features.iter().filter(|(_, v)| v.is_some()).cloned(),
+                        self.features
+                            .iter()
+                            .filter_map(|(name, value)| value.as_deref().map(|value| (*name, Some(value)))),
                         Some(std::mem::replace(&mut self.args, retained_state).into_iter()),
                     )
                     .await
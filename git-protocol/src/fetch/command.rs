@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 /// The kind of command to invoke on the server side.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 pub enum Command {
@@ -7,8 +9,8 @@ pub enum Command {
     Fetch,
 }
 
-/// A key value pair of values known at compile time.
-pub type Feature = (&'static str, Option<&'static str>);
+/// A key value pair of a name known at compile time and a value which may only be known at runtime, like the name of the agent.
+pub type Feature = (&'static str, Option<Cow<'static, str>>);
 
 impl Command {
     /// Produce the name of the command as known by the server side.
@@ -192,7 +194,7 @@ mod with_io {
                             continue;
                         }
                         match *feature {
-                            "agent" => {}
+                            "agent" | "session-id" => {}
                             _ => panic!("{}: V2 feature/capability {} is not supported", self.as_str(), feature),
                         }
                     }
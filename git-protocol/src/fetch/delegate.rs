@@ -2,6 +2,7 @@ use crate::fetch::{Arguments, Ref, Response};
 use bstr::BString;
 use git_transport::client::Capabilities;
 use std::{
+    borrow::Cow,
     io,
     ops::{Deref, DerefMut},
 };
@@ -40,6 +41,21 @@ pub trait DelegateBlocking {
     fn handshake_extra_parameters(&self) -> Vec<(String, Option<String>)> {
         Vec::new()
     }
+    /// Return the name and version of this client as sent to the server in the `agent` capability, like `my-tool/1.0`,
+    /// or `None` to send [the default][crate::fetch::agent()].
+    ///
+    /// Note that this method is only called once.
+    fn agent(&self) -> Option<String> {
+        None
+    }
+    /// Return the id of this session to send to servers which advertise the `session-id` capability, allowing to correlate
+    /// the logs of client and server, or `None` to not send it.
+    ///
+    /// Like git with `transfer.advertiseSID` unset, nothing is sent by default. Note that this method is only called
+    /// once and only if the server supports it.
+    fn session_id(&self) -> Option<String> {
+        None
+    }
     /// Called before invoking 'ls-refs' on the server to allow providing it with additional `arguments` and to enable `features`.
     /// If the server `capabilities` don't match the requirements abort with an error to abort the entire fetch operation.
     ///
@@ -56,7 +72,7 @@ pub trait DelegateBlocking {
         &mut self,
         _server: &Capabilities,
         _arguments: &mut Vec<BString>,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
     ) -> std::io::Result<LsRefsAction> {
         Ok(LsRefsAction::Continue)
     }
@@ -76,7 +92,7 @@ pub trait DelegateBlocking {
         &mut self,
         _version: git_transport::Protocol,
         _server: &Capabilities,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
         _refs: &[Ref],
     ) -> std::io::Result<Action> {
         Ok(Action::Continue)
@@ -125,11 +141,19 @@ impl<T: DelegateBlocking> DelegateBlocking for Box<T> {
         self.deref().handshake_extra_parameters()
     }
 
+    fn agent(&self) -> Option<String> {
+        self.deref().agent()
+    }
+
+    fn session_id(&self) -> Option<String> {
+        self.deref().session_id()
+    }
+
     fn prepare_ls_refs(
         &mut self,
         _server: &Capabilities,
         _arguments: &mut Vec<BString>,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
     ) -> io::Result<LsRefsAction> {
         self.deref_mut().prepare_ls_refs(_server, _arguments, _features)
     }
@@ -138,7 +162,7 @@ impl<T: DelegateBlocking> DelegateBlocking for Box<T> {
         &mut self,
         _version: git_transport::Protocol,
         _server: &Capabilities,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
         _refs: &[Ref],
     ) -> io::Result<Action> {
         self.deref_mut().prepare_fetch(_version, _server, _features, _refs)
@@ -159,11 +183,19 @@ impl<T: DelegateBlocking> DelegateBlocking for &mut T {
         self.deref().handshake_extra_parameters()
     }
 
+    fn agent(&self) -> Option<String> {
+        self.deref().agent()
+    }
+
+    fn session_id(&self) -> Option<String> {
+        self.deref().session_id()
+    }
+
     fn prepare_ls_refs(
         &mut self,
         _server: &Capabilities,
         _arguments: &mut Vec<BString>,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
     ) -> io::Result<LsRefsAction> {
         self.deref_mut().prepare_ls_refs(_server, _arguments, _features)
     }
@@ -172,7 +204,7 @@ impl<T: DelegateBlocking> DelegateBlocking for &mut T {
         &mut self,
         _version: git_transport::Protocol,
         _server: &Capabilities,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
         _refs: &[Ref],
     ) -> io::Result<Action> {
         self.deref_mut().prepare_fetch(_version, _server, _features, _refs)
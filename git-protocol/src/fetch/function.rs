@@ -1,6 +1,6 @@
 use crate::{
     credentials,
-    fetch::{refs, Action, Arguments, Command, Delegate, Error, LsRefsAction, Response},
+    fetch::{command::Feature, handshake, refs, Action, Arguments, Command, Delegate, Error, LsRefsAction, Response},
 };
 use git_features::{progress, progress::Progress};
 use git_transport::{
@@ -20,13 +20,15 @@ use std::io;
 /// * `progress` is used to emit progress messages.
 ///
 /// _Note_ that depending on the `delegate`, the actual action performed can be `ls-refs`, `clone` or `fetch`.
+///
+/// Returns what was learned about the server during the handshake, for example to log its session id.
 #[maybe_async]
 pub async fn fetch<F, D, T>(
     mut transport: T,
     mut delegate: D,
     mut authenticate: F,
     mut progress: impl Progress,
-) -> Result<(), Error>
+) -> Result<handshake::Outcome, Error>
 where
     F: FnMut(credentials::Action<'_>) -> credentials::Result,
     D: Delegate,
@@ -101,6 +103,13 @@ where
         };
         (actual_protocol, parsed_refs, capabilities)
     }; // this scope is needed, see https://github.com/rust-lang/rust/issues/76149
+    let outcome = handshake::Outcome::from_capabilities(protocol_version, &capabilities);
+    let agent = delegate.agent();
+    let session_id = if capabilities.contains("session-id") {
+        delegate.session_id()
+    } else {
+        None
+    };
 
     let parsed_refs = match parsed_refs {
         Some(refs) => refs,
@@ -113,6 +122,7 @@ where
 
             let ls_refs = Command::LsRefs;
             let mut ls_features = ls_refs.default_features(protocol_version, &capabilities);
+            identify_client(&mut ls_features, agent.as_deref(), session_id.as_deref());
             let mut ls_args = ls_refs.initial_arguments(&ls_features);
             match delegate.prepare_ls_refs(&capabilities, &mut ls_args, &mut ls_features) {
                 Ok(LsRefsAction::Skip) => Vec::new(),
@@ -129,7 +139,7 @@ where
                     let mut remote_refs = transport
                         .invoke(
                             ls_refs.as_str(),
-                            ls_features.into_iter(),
+                            ls_features.iter().map(|(name, value)| (*name, value.as_deref())),
                             if ls_args.is_empty() {
                                 None
                             } else {
@@ -149,8 +159,12 @@ where
 
     let fetch = Command::Fetch;
     let mut fetch_features = fetch.default_features(protocol_version, &capabilities);
+    identify_client(&mut fetch_features, agent.as_deref(), session_id.as_deref());
     match delegate.prepare_fetch(protocol_version, &capabilities, &mut fetch_features, &parsed_refs) {
-        Ok(Action::Cancel) => return indicate_end_of_interaction(transport).await,
+        Ok(Action::Cancel) => {
+            indicate_end_of_interaction(transport).await?;
+            return Ok(outcome);
+        }
         Ok(Action::Continue) => {
             fetch.validate_argument_prefixes_or_panic(protocol_version, &capabilities, &[], &fetch_features);
         }
@@ -190,7 +204,20 @@ where
             }
         }
     }
-    Ok(())
+    Ok(outcome)
+}
+
+/// Replace the default `agent` in `features` if set, and add our `session_id` if set, which must only be the case if the
+/// server supports it.
+fn identify_client(features: &mut Vec<Feature>, agent: Option<&str>, session_id: Option<&str>) {
+    if let Some(agent) = agent {
+        for (_, value) in features.iter_mut().filter(|(name, _)| *name == "agent") {
+            *value = Some(agent.to_owned().into());
+        }
+    }
+    if let Some(session_id) = session_id {
+        features.push(("session-id", Some(session_id.to_owned().into())));
+    }
 }
 
 #[maybe_async]
@@ -212,7 +239,13 @@ fn setup_remote_progress(
     reader.set_progress_handler(Some(Box::new({
         let mut remote_progress = progress.add_child("remote");
         move |is_err: bool, data: &[u8]| {
-            crate::RemoteProgress::translate_to_progress(is_err, data, &mut remote_progress)
+            crate::RemoteProgress::translate_to_progress(is_err, data, &mut remote_progress);
+            // errors are fatal, but empty ones are keep-alive packets sent with 'sideband-all'
+            if is_err && !data.is_empty() {
+                git_transport::client::ProgressAction::Interrupt
+            } else {
+                git_transport::client::ProgressAction::Continue
+            }
         }
     }) as git_transport::client::HandleProgress));
 }
@@ -0,0 +1,32 @@
+use bstr::BString;
+use git_transport::{client::Capabilities, Protocol};
+
+/// What we learned about the server during the handshake, as returned by [`fetch()`][crate::fetch()].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The protocol version the server actually speaks, which may be lower than the requested one.
+    pub server_protocol_version: Protocol,
+    /// The name and version of the server software as advertised with the `agent` capability, like `git/2.33.0`.
+    pub server_agent: Option<BString>,
+    /// The id the server assigned to this session as advertised with the `session-id` capability.
+    ///
+    /// Hosting providers can use it to find the logs of this particular interaction, which helps debugging failures.
+    pub server_session_id: Option<BString>,
+}
+
+impl Outcome {
+    /// Extract all information from the `capabilities` of a server speaking the protocol with `version`.
+    pub fn from_capabilities(version: Protocol, capabilities: &Capabilities) -> Self {
+        let value_of = |name: &str| {
+            capabilities
+                .capability(name)
+                .and_then(|c| c.value().map(ToOwned::to_owned))
+        };
+        Outcome {
+            server_protocol_version: version,
+            server_agent: value_of("agent"),
+            server_session_id: value_of("session-id"),
+        }
+    }
+}
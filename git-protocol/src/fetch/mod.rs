@@ -6,8 +6,10 @@ pub mod command;
 pub use command::Command;
 
 /// Returns the name of the agent as key-value pair, commonly used in HTTP headers.
-pub fn agent() -> (&'static str, Option<&'static str>) {
-    ("agent", Some(concat!("git/oxide-", env!("CARGO_PKG_VERSION"))))
+///
+/// It is sent to servers unless the [delegate][DelegateBlocking::agent()] provides a different name.
+pub fn agent() -> command::Feature {
+    ("agent", Some(concat!("git/oxide-", env!("CARGO_PKG_VERSION")).into()))
 }
 
 ///
@@ -19,6 +21,9 @@ pub use delegate::{Action, DelegateBlocking, LsRefsAction};
 mod error;
 pub use error::Error;
 
+///
+pub mod handshake;
+
 ///
 pub mod refs;
 pub use refs::Ref;
@@ -1,5 +1,5 @@
 use bstr::{BString, ByteSlice};
-use std::io;
+use std::{borrow::Cow, io};
 
 use git_protocol::fetch::{self, Action, Arguments, LsRefsAction, Ref, Response};
 use git_transport::client::Capabilities;
@@ -22,7 +22,7 @@ impl fetch::DelegateBlocking for CloneDelegate {
         &mut self,
         _version: git_transport::Protocol,
         _server: &Capabilities,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
         _refs: &[fetch::Ref],
     ) -> io::Result<Action> {
         match self.abort_with.take() {
@@ -64,7 +64,7 @@ impl fetch::DelegateBlocking for CloneRefInWantDelegate {
         &mut self,
         _server: &Capabilities,
         _arguments: &mut Vec<BString>,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
     ) -> io::Result<LsRefsAction> {
         Ok(LsRefsAction::Skip)
     }
@@ -73,7 +73,7 @@ impl fetch::DelegateBlocking for CloneRefInWantDelegate {
         &mut self,
         _version: git_transport::Protocol,
         _server: &Capabilities,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
         refs: &[fetch::Ref],
     ) -> io::Result<Action> {
         self.refs = refs.to_owned();
@@ -108,17 +108,25 @@ impl fetch::DelegateBlocking for CloneRefInWantDelegate {
 pub struct LsRemoteDelegate {
     refs: Vec<fetch::Ref>,
     abort_with: Option<std::io::Error>,
+    agent: Option<String>,
+    session_id: Option<String>,
 }
 
 impl fetch::DelegateBlocking for LsRemoteDelegate {
     fn handshake_extra_parameters(&self) -> Vec<(String, Option<String>)> {
         vec![("value-only".into(), None), ("key".into(), Some("value".into()))]
     }
+    fn agent(&self) -> Option<String> {
+        self.agent.clone()
+    }
+    fn session_id(&self) -> Option<String> {
+        self.session_id.clone()
+    }
     fn prepare_ls_refs(
         &mut self,
         _server: &Capabilities,
         _arguments: &mut Vec<BString>,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
     ) -> std::io::Result<LsRefsAction> {
         match self.abort_with.take() {
             Some(err) => Err(err),
@@ -129,7 +137,7 @@ impl fetch::DelegateBlocking for LsRemoteDelegate {
         &mut self,
         _version: git_transport::Protocol,
         _server: &Capabilities,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
         refs: &[fetch::Ref],
     ) -> io::Result<fetch::Action> {
         self.refs = refs.to_owned();
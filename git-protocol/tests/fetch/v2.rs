@@ -96,6 +96,63 @@ async fn ls_remote() -> crate::Result {
     Ok(())
 }
 
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn ls_remote_with_agent_and_session_id() -> crate::Result {
+    for (fixture, expected_session_id) in &[
+        ("v2/clone.response", None),
+        ("v2/clone-with-session-id.response", Some("server-1")),
+    ] {
+        let out = Vec::new();
+        let mut delegate = LsRemoteDelegate {
+            agent: Some("my-tool/1.0".into()),
+            session_id: Some("client-1".into()),
+            ..LsRemoteDelegate::default()
+        };
+        let mut transport = transport(
+            out,
+            fixture,
+            Protocol::V2,
+            git_transport::client::git::ConnectMode::Daemon,
+        );
+        let outcome = git_protocol::fetch(
+            &mut transport,
+            &mut delegate,
+            git_protocol::credentials::helper,
+            progress::Discard,
+        )
+        .await?;
+
+        assert_eq!(
+            outcome,
+            fetch::handshake::Outcome {
+                server_protocol_version: Protocol::V2,
+                server_agent: Some("git/2.28.0".into()),
+                server_session_id: expected_session_id.map(Into::into),
+            }
+        );
+        let session_id_line = if expected_session_id.is_some() {
+            "0018session-id=client-1\n"
+        } else {
+            ""
+        };
+        assert_eq!(
+            transport.into_inner().1.as_bstr(),
+            format!(
+                "0044git-upload-pack does/not/matter\0\0version=2\0value-only\0key=value\00014command=ls-refs
+0016agent=my-tool/1.0
+{}0001000csymrefs
+0009peel
+00000000",
+                session_id_line
+            )
+            .as_bytes()
+            .as_bstr(),
+            "the session id is only sent if the server supports it"
+        );
+    }
+    Ok(())
+}
+
 #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
 async fn ls_remote_abort_in_prep_ls_refs() -> crate::Result {
     let out = Vec::new();
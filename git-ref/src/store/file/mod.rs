@@ -86,3 +86,6 @@ pub mod log;
 
 ///
 pub mod transaction;
+
+///
+pub mod update;
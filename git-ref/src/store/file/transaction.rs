@@ -186,6 +186,11 @@ impl<'a> Transaction<'a> {
         self.updates.into_iter().map(|e| e.update).collect()
     }
 
+    /// The edits of this transaction, which are adjusted to contain the previous values of references once it is prepared.
+    pub(crate) fn edits(&self) -> impl Iterator<Item = &RefEdit> {
+        self.updates.iter().map(|e| &e.update)
+    }
+
     /// Prepare for calling [`commit(…)`][Transaction::commit()] in a way that can be rolled back perfectly.
     ///
     /// If the operation succeeds, the transaction can be committed or dropped to cause a rollback automatically.
@@ -0,0 +1,89 @@
+use crate::{
+    mutable::{FullName, Target},
+    store::file,
+    transaction::{Change, Create, LogChange, RefEdit},
+};
+
+mod error {
+    use crate::{mutable::Target, store::file};
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`file::Store::update()`][crate::file::Store::update()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            ExpectationViolated { actual: Option<Target> } {
+                display("The reference was expected to have a different value, but it was {}", actual.as_ref().map_or_else(|| "missing".into(), |target| target.to_string()))
+            }
+            Transaction(err: file::transaction::Error) {
+                display("The reference could not be updated")
+                from()
+                source(err)
+            }
+        }
+    }
+}
+pub use error::Error;
+
+impl file::Store {
+    /// Set the reference `name` to `new` only if its current value is `expected`, without dereferencing symbolic references,
+    /// and return its previous value.
+    ///
+    /// This is a compare-and-swap operation to build optimistic concurrency on, as writers can read a reference, compute
+    /// its new value and apply it with this method, retrying with fresh values if another writer was faster.
+    /// `expected` is interpreted as follows:
+    ///
+    /// * `None` requires the reference not to exist.
+    /// * `Some(Target::Peeled(ObjectId::null_sha1()))` requires the reference to exist, but its value doesn't matter.
+    /// * `Some(value)` requires the reference to exist and have the given `value`.
+    ///
+    /// If the expectation isn't met, [`Error::ExpectationViolated`] is returned with the `actual` value of the reference,
+    /// and nothing is changed. `log` and `committer` are used to write the reflog, and `lock` determines what to do if
+    /// another writer holds the lock on the reference.
+    ///
+    /// Note that only loose references are considered, references that only exist in the `packed-refs` file are seen
+    /// as missing.
+    pub fn update(
+        &self,
+        name: FullName,
+        expected: Option<Target>,
+        new: Target,
+        log: LogChange,
+        committer: &git_actor::Signature,
+        lock: git_lock::acquire::Fail,
+    ) -> Result<Option<Target>, Error> {
+        let must_not_exist = expected.is_none();
+        let edit = RefEdit {
+            change: Change::Update {
+                log,
+                mode: match expected {
+                    None => Create::Only,
+                    Some(previous) => Create::OrUpdate {
+                        previous: Some(previous),
+                    },
+                },
+                new,
+            },
+            name,
+            deref: false,
+        };
+        let transaction = self.transaction(Some(edit), lock).prepare().map_err(|err| match err {
+            file::transaction::Error::MustNotExist { actual, .. }
+            | file::transaction::Error::ReferenceOutOfDate { actual, .. } => {
+                Error::ExpectationViolated { actual: Some(actual) }
+            }
+            file::transaction::Error::MustExist { .. } => Error::ExpectationViolated { actual: None },
+            err => Error::Transaction(err),
+        })?;
+        let previous = |edit: &RefEdit| edit.change.previous_value().map(|target| target.to_owned());
+        if must_not_exist {
+            // Creating references succeeds if they already have the desired value, which isn't what callers asked for.
+            if let Some(actual) = transaction.edits().next().and_then(previous) {
+                return Err(Error::ExpectationViolated { actual: Some(actual) });
+            }
+        }
+        let edits = transaction.commit(committer)?;
+        Ok(edits.first().and_then(previous))
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
     parse::{hex_hash, newline},
-    store::packed,
+    store::{
+        packed,
+        packed::{Header, Peeled},
+    },
 };
 use bstr::{BStr, ByteSlice};
 use nom::{
@@ -11,29 +14,6 @@ use nom::{
     IResult,
 };
 
-#[derive(Debug, PartialEq, Eq)]
-enum Peeled {
-    Unspecified,
-    Partial,
-    Fully,
-}
-
-/// Information parsed from the header of a packed ref file
-#[derive(Debug, PartialEq, Eq)]
-pub struct Header {
-    peeled: Peeled,
-    pub sorted: bool,
-}
-
-impl Default for Header {
-    fn default() -> Self {
-        Header {
-            peeled: Peeled::Unspecified,
-            sorted: false,
-        }
-    }
-}
-
 fn until_newline<'a, E>(input: &'a [u8]) -> IResult<&'a [u8], &'a BStr, E>
 where
     E: ParseError<&'a [u8]>,
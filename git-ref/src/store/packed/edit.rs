@@ -0,0 +1,191 @@
+use crate::{
+    mutable::FullName,
+    store::{
+        packed,
+        packed::{decode, Header, Peeled},
+    },
+};
+use bstr::{BStr, ByteSlice};
+use git_hash::ObjectId;
+use std::io;
+
+/// The change to apply to a single reference as part of an [`Edit`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub enum Change {
+    /// Remove the reference along with its peeled object, if present. Deleting a reference that doesn't exist does nothing.
+    Delete,
+    /// Create the reference or replace its existing record.
+    Update {
+        /// The object the reference points to.
+        target: ObjectId,
+        /// The object the reference ultimately points to if `target` is an annotated tag, written as peel line.
+        ///
+        /// Note that if the header declares the file as [fully peeled][Peeled::Fully], `None` asserts that `target` is
+        /// not an annotated tag.
+        object: Option<ObjectId>,
+    },
+}
+
+/// A change to a reference stored in a `packed-refs` file, to be applied with [`apply()`] or
+/// [`Buffer::edit()`][packed::Buffer::edit()].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Edit {
+    /// The full name of the reference to change.
+    pub name: FullName,
+    /// The way to change it.
+    pub change: Change,
+}
+
+/// Apply `edits` to the `packed-refs` file in `input` and write the result to `out`, returning the amount of records that
+/// were written, deleted or added.
+///
+/// The output is spliced together from the unchanged portions of `input`, which are copied byte-for-byte along with the
+/// header and all peel lines, and the records of the edited references. This keeps the difference to the original minimal
+/// and avoids peeling references again, which matters with huge files.
+///
+/// If `input` is empty and references are added, a header declaring the output as sorted and [fully peeled][Peeled::Fully] is written as
+/// git would. Otherwise `input` must be sorted by reference name, which is assured if it was opened as [`packed::Buffer`].
+pub fn apply(input: &[u8], edits: impl IntoIterator<Item = Edit>, mut out: impl io::Write) -> Result<Outcome, Error> {
+    let mut edits: Vec<_> = edits.into_iter().collect();
+    edits.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+    if let Some(duplicate) = edits.windows(2).find(|pair| pair[0].name == pair[1].name) {
+        return Err(Error::DuplicateEdit {
+            full_name: duplicate[0].name.as_ref().to_owned(),
+        });
+    }
+    let has_updates = edits.iter().any(|edit| matches!(edit.change, Change::Update { .. }));
+    let mut edits = edits.into_iter().peekable();
+    let mut outcome = Outcome::default();
+
+    let (mut cursor, mut line_number) = if input.is_empty() {
+        if has_updates {
+            Header {
+                peeled: Peeled::Fully,
+                sorted: true,
+            }
+            .write_to(&mut out)?;
+        }
+        (input, 1)
+    } else if input[0] == b'#' {
+        let (records, _header) = decode::header::<()>(input).map_err(|_| Error::Header {
+            invalid_first_line: input.lines().next().unwrap_or(input).into(),
+        })?;
+        (records, 2)
+    } else {
+        (input, 1)
+    };
+
+    let offset_of = |slice: &[u8]| input.len() - slice.len();
+    let mut copy_from = 0;
+    let mut previous_name: Option<&BStr> = None;
+    while !cursor.is_empty() {
+        let (rest, reference) = decode::reference::<()>(cursor).map_err(|_| Error::Reference {
+            invalid_line: cursor.lines().next().unwrap_or(cursor).into(),
+            line_number,
+        })?;
+        if previous_name.map_or(false, |previous| previous >= reference.full_name) {
+            return Err(Error::Unsorted {
+                full_name: reference.full_name.to_owned(),
+                line_number,
+            });
+        }
+        previous_name = Some(reference.full_name);
+        let (record_start, record_end) = (offset_of(cursor), offset_of(rest));
+        line_number += if reference.object.is_some() { 2 } else { 1 };
+        cursor = rest;
+
+        while let Some(edit) = edits.next_if(|edit| edit.name.as_ref() < reference.full_name) {
+            out.write_all(&input[copy_from..record_start])?;
+            copy_from = record_start;
+            if let Change::Update { target, object } = edit.change {
+                write_record(&mut out, edit.name.as_ref(), &target, object.as_ref())?;
+                outcome.num_added += 1;
+            }
+        }
+        if let Some(edit) = edits.next_if(|edit| edit.name.as_ref() == reference.full_name) {
+            out.write_all(&input[copy_from..record_start])?;
+            copy_from = record_end;
+            match edit.change {
+                Change::Delete => outcome.num_deleted += 1,
+                Change::Update { target, object } => {
+                    write_record(&mut out, edit.name.as_ref(), &target, object.as_ref())?;
+                    outcome.num_updated += 1;
+                }
+            }
+        }
+    }
+
+    out.write_all(&input[copy_from..])?;
+    for edit in edits {
+        if let Change::Update { target, object } = edit.change {
+            write_record(&mut out, edit.name.as_ref(), &target, object.as_ref())?;
+            outcome.num_added += 1;
+        }
+    }
+    Ok(outcome)
+}
+
+fn write_record(
+    mut out: impl io::Write,
+    full_name: &BStr,
+    target: &ObjectId,
+    object: Option<&ObjectId>,
+) -> io::Result<()> {
+    write!(out, "{} ", target)?;
+    out.write_all(full_name)?;
+    out.write_all(b"\n")?;
+    if let Some(object) = object {
+        writeln!(out, "^{}", object)?;
+    }
+    Ok(())
+}
+
+/// packed-refs specific functionality
+impl packed::Buffer {
+    /// Apply `edits` to this buffer and write the resulting `packed-refs` file to `out`, see [`apply()`] for details.
+    pub fn edit(&self, edits: impl IntoIterator<Item = Edit>, out: impl io::Write) -> Result<Outcome, Error> {
+        apply(self.as_ref(), edits, out)
+    }
+}
+
+/// The result of [`apply()`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+pub struct Outcome {
+    /// The amount of records of existing references that were replaced.
+    pub num_updated: usize,
+    /// The amount of records that were added for references that didn't exist yet.
+    pub num_added: usize,
+    /// The amount of records that were removed.
+    pub num_deleted: usize,
+}
+
+mod error {
+    use bstr::BString;
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`apply()`][super::apply()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            Io(err: std::io::Error) {
+                display("The edited packed-refs file could not be written")
+                from()
+                source(err)
+            }
+            DuplicateEdit { full_name: BString } {
+                display("The reference '{}' was edited more than once", full_name)
+            }
+            Header { invalid_first_line: BString } {
+                display("The header existed but could not be parsed: '{}'", invalid_first_line)
+            }
+            Reference { invalid_line: BString, line_number: usize } {
+                display("Invalid reference in line {}: '{}'", line_number, invalid_line)
+            }
+            Unsorted { full_name: BString, line_number: usize } {
+                display("The reference '{}' in line {} is not sorted", full_name, line_number)
+            }
+        }
+    }
+}
+pub use error::Error;
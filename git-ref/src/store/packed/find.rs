@@ -43,7 +43,14 @@ impl packed::Buffer {
     /// is the beginning of the line at which `name` could be inserted to still be in sort order.
     fn binary_search_by(&self, full_name: FullName<'_>) -> Result<usize, bool> {
         // TODO: remove the runtime constraint once we do lookup correctly
-        let a = self.as_ref();
+        let buf = self.as_ref();
+        // The header is no record and would be reported as parse failure if the search ends up in it.
+        let header_len = if buf.first() == Some(&b'#') {
+            buf.find_byte(b'\n').map_or(buf.len(), |pos| pos + 1)
+        } else {
+            0
+        };
+        let a = &buf[header_len..];
         let search_start_of_record = |ofs: usize| {
             a[..ofs]
                 .rfind(b"\n")
@@ -68,7 +75,7 @@ impl packed::Buffer {
                 })
                 .unwrap_or(&[])
         })
-        .map(search_start_of_record)
+        .map(|ofs| header_len + search_start_of_record(ofs))
         .map_err(|_| encountered_parse_failure)
     }
 }
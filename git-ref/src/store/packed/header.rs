@@ -0,0 +1,36 @@
+use crate::store::{
+    packed,
+    packed::{decode, Header, Peeled},
+};
+use std::io;
+
+/// Parsing and serialization
+impl Header {
+    /// Parse the header from the first line of a `packed-refs` file in `data`, or return `None` if there is no header or
+    /// if it could not be parsed.
+    pub fn from_bytes(data: &[u8]) -> Option<Header> {
+        decode::header::<()>(data).ok().map(|(_rest, header)| header)
+    }
+
+    /// Serialize this instance as first line of a `packed-refs` file into `out`, including the trailing newline.
+    pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
+        out.write_all(b"# pack-refs with:")?;
+        match self.peeled {
+            Peeled::Unspecified => {}
+            Peeled::Partial => out.write_all(b" peeled")?,
+            Peeled::Fully => out.write_all(b" peeled fully-peeled")?,
+        }
+        if self.sorted {
+            out.write_all(b" sorted")?;
+        }
+        out.write_all(b" \n")
+    }
+}
+
+/// packed-refs specific functionality
+impl packed::Buffer {
+    /// Return the header of this buffer, which is always present as only sorted buffers can be opened.
+    pub fn header(&self) -> Header {
+        Header::from_bytes(self.as_ref()).expect("header was validated when opening the buffer")
+    }
+}
@@ -40,6 +40,27 @@ impl<'a> Reference<'a> {
     }
 }
 
+/// The kind of peeling information a `packed-refs` file provides, as declared in its [header][Header].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Peeled {
+    /// Nothing is known about which references are peeled.
+    Unspecified,
+    /// All references to annotated tags below `refs/tags/` are followed by a line with the object they ultimately point to.
+    Partial,
+    /// All references pointing to annotated tags are followed by a line with the object they ultimately point to, hence
+    /// references without such line are known not to point to a tag.
+    Fully,
+}
+
+/// Information parsed from the optional first line of a `packed-refs` file.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Header {
+    /// How much peeling information is present.
+    pub peeled: Peeled,
+    /// If true, the references are sorted by name which is required for lookups.
+    pub sorted: bool,
+}
+
 /// An iterator over references in a packed refs file
 pub struct Iter<'a> {
     /// The position at which to parse the next reference
@@ -50,6 +71,8 @@ pub struct Iter<'a> {
 
 mod decode;
 
+mod header;
+
 ///
 pub mod iter;
 
@@ -58,3 +81,6 @@ pub mod buffer;
 
 ///
 pub mod find;
+
+///
+pub mod edit;
@@ -104,6 +104,11 @@ mod parse {
 
         mktest!(hex_id, b"foobar", "\"foobar\" could not be parsed");
         mktest!(ref_tag, b"reff: hello", "\"reff: hello\" could not be parsed");
+        mktest!(
+            hex_id_of_unknown_hash_length,
+            b"c5241b835b93af497cda80ce0dceb8f49800df1c0",
+            "\"c5241b835b93af497cda80ce0dceb8f49800df1c0\" could not be parsed"
+        );
     }
     mod valid {
         use crate::file::reference::parse::store;
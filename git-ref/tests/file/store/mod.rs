@@ -1,3 +1,4 @@
 mod find_one;
 mod iter;
 mod reflog;
+mod update;
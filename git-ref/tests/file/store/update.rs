@@ -0,0 +1,128 @@
+use git_actor::{Sign, Time};
+use git_lock::acquire::Fail;
+use git_ref::{
+    file,
+    file::{log, update},
+    mutable::Target,
+    transaction::LogChange,
+};
+use git_testtools::hex_to_id;
+use std::convert::TryInto;
+
+fn empty_store() -> crate::Result<(tempfile::TempDir, file::Store)> {
+    let dir = tempfile::TempDir::new()?;
+    let store: file::Store = dir.path().to_owned().into();
+    Ok((dir, store))
+}
+
+fn committer() -> git_actor::Signature {
+    git_actor::Signature {
+        name: "committer".into(),
+        email: "committer@example.com".into(),
+        time: Time {
+            time: 1234,
+            offset: 1800,
+            sign: Sign::Plus,
+        },
+    }
+}
+
+fn reflog_len(store: &file::Store, name: &str) -> crate::Result<usize> {
+    let mut buf = Vec::new();
+    let lines = store
+        .reflog_iter(name, &mut buf)?
+        .expect("existing reflog")
+        .map(|line| line.map(log::mutable::Line::from))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(lines.len())
+}
+
+fn log(message: &str) -> LogChange {
+    LogChange {
+        message: message.into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn references_are_only_created_if_they_do_not_exist() -> crate::Result {
+    let (_dir, store) = empty_store()?;
+    let new = Target::Peeled(hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242"));
+    let previous = store.update(
+        "refs/heads/main".try_into()?,
+        None,
+        new.clone(),
+        log("create"),
+        &committer(),
+        Fail::Immediately,
+    )?;
+    assert_eq!(previous, None, "there was no previous value");
+    assert_eq!(store.find_one_existing("main")?.target().to_owned(), new);
+
+    let err = store
+        .update(
+            "refs/heads/main".try_into()?,
+            None,
+            new.clone(),
+            log("create again"),
+            &committer(),
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert!(
+        matches!(&err, update::Error::ExpectationViolated { actual: Some(actual) } if *actual == new),
+        "existing references violate the expectation even if they already have the new value"
+    );
+    assert_eq!(
+        reflog_len(&store, "refs/heads/main")?,
+        1,
+        "the failed update didn't touch the reflog"
+    );
+    Ok(())
+}
+
+#[test]
+fn references_are_only_updated_if_they_have_the_expected_value() -> crate::Result {
+    let (_dir, store) = empty_store()?;
+    let first = Target::Peeled(hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242"));
+    let second = Target::Peeled(hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"));
+    let update = |expected: Option<Target>, new: &Target| {
+        store.update(
+            "refs/heads/main".try_into().expect("valid name"),
+            expected,
+            new.clone(),
+            log("update"),
+            &committer(),
+            Fail::Immediately,
+        )
+    };
+
+    assert!(
+        matches!(
+            update(Some(first.clone()), &second),
+            Err(update::Error::ExpectationViolated { actual: None })
+        ),
+        "missing references don't have the expected value"
+    );
+    update(None, &first)?;
+    assert_eq!(update(Some(first.clone()), &second)?, Some(first.clone()));
+
+    let err = update(Some(first.clone()), &first).unwrap_err();
+    assert!(
+        matches!(&err, update::Error::ExpectationViolated { actual: Some(actual) } if *actual == second),
+        "the actual value is returned if another writer was faster"
+    );
+    assert_eq!(
+        err.to_string(),
+        "The reference was expected to have a different value, but it was 134385f6d781b7e97062102c6a483440bfda2a03"
+    );
+    assert_eq!(store.find_one_existing("main")?.target().to_owned(), second);
+
+    let any_value = Target::Peeled(git_hash::ObjectId::null_sha1());
+    assert_eq!(
+        update(Some(any_value), &first)?,
+        Some(second),
+        "the null id only requires the reference to exist"
+    );
+    Ok(())
+}
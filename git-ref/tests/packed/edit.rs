@@ -0,0 +1,207 @@
+use bstr::ByteSlice;
+use git_ref::{
+    mutable::FullName,
+    packed,
+    packed::edit::{Change, Edit, Outcome},
+};
+use git_testtools::hex_to_id;
+use std::convert::TryFrom;
+
+const PACKED_REFS: &[u8] =
+    b"# pack-refs with: peeled fully-peeled sorted \n916840c0e2f67d370291042cb5274a597f4fa9bc refs/heads/main
+c4cebba92af964f2d126be90b8a6298c4cf84d45 refs/tags/git-actor-v0.1.0
+^13da90b54699a6b500ec5cd7d175f2cd5a1bed06
+0b92c8a256ae06c189e3b9c30b646d62ac8f7d10 refs/tags/git-actor-v0.1.1
+^916840c0e2f67d370291042cb5274a597f4fa9bc
+";
+
+fn delete(name: &str) -> Edit {
+    Edit {
+        name: FullName::try_from(name).expect("valid name"),
+        change: Change::Delete,
+    }
+}
+
+fn update(name: &str, target: &str, object: Option<&str>) -> Edit {
+    Edit {
+        name: FullName::try_from(name).expect("valid name"),
+        change: Change::Update {
+            target: hex_to_id(target),
+            object: object.map(hex_to_id),
+        },
+    }
+}
+
+fn apply(input: &[u8], edits: Vec<Edit>) -> crate::Result<(Vec<u8>, Outcome)> {
+    let mut out = Vec::new();
+    let outcome = packed::edit::apply(input, edits, &mut out)?;
+    Ok((out, outcome))
+}
+
+#[test]
+fn deletions_keep_all_other_lines_untouched() -> crate::Result {
+    let (out, outcome) = apply(
+        PACKED_REFS,
+        vec![
+            delete("refs/tags/git-actor-v0.1.0"),
+            delete("refs/heads/does-not-exist"),
+        ],
+    )?;
+    assert_eq!(
+        out.as_bstr(),
+        b"# pack-refs with: peeled fully-peeled sorted \n916840c0e2f67d370291042cb5274a597f4fa9bc refs/heads/main
+0b92c8a256ae06c189e3b9c30b646d62ac8f7d10 refs/tags/git-actor-v0.1.1
+^916840c0e2f67d370291042cb5274a597f4fa9bc
+"
+        .as_bstr(),
+        "the peel line of the deleted reference is removed as well"
+    );
+    assert_eq!(
+        outcome,
+        Outcome {
+            num_deleted: 1,
+            ..Default::default()
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn updates_and_additions_are_written_in_sort_order() -> crate::Result {
+    let (out, outcome) = apply(
+        PACKED_REFS,
+        vec![
+            update("refs/tags/v1", "0b92c8a256ae06c189e3b9c30b646d62ac8f7d10", None),
+            update(
+                "refs/tags/git-actor-v0.1.0",
+                "0b92c8a256ae06c189e3b9c30b646d62ac8f7d10",
+                Some("916840c0e2f67d370291042cb5274a597f4fa9bc"),
+            ),
+            update("refs/heads/dev", "916840c0e2f67d370291042cb5274a597f4fa9bc", None),
+            update("refs/a", "916840c0e2f67d370291042cb5274a597f4fa9bc", None),
+        ],
+    )?;
+    assert_eq!(
+        out.as_bstr(),
+        b"# pack-refs with: peeled fully-peeled sorted \n916840c0e2f67d370291042cb5274a597f4fa9bc refs/a
+916840c0e2f67d370291042cb5274a597f4fa9bc refs/heads/dev
+916840c0e2f67d370291042cb5274a597f4fa9bc refs/heads/main
+0b92c8a256ae06c189e3b9c30b646d62ac8f7d10 refs/tags/git-actor-v0.1.0
+^916840c0e2f67d370291042cb5274a597f4fa9bc
+0b92c8a256ae06c189e3b9c30b646d62ac8f7d10 refs/tags/git-actor-v0.1.1
+^916840c0e2f67d370291042cb5274a597f4fa9bc
+0b92c8a256ae06c189e3b9c30b646d62ac8f7d10 refs/tags/v1
+"
+        .as_bstr()
+    );
+    assert_eq!(
+        outcome,
+        Outcome {
+            num_updated: 1,
+            num_added: 3,
+            num_deleted: 0
+        }
+    );
+    assert_eq!(packed::Iter::new(&out)?.filter_map(Result::ok).count(), 6);
+    Ok(())
+}
+
+#[test]
+fn without_edits_the_output_is_the_input() -> crate::Result {
+    let (out, outcome) = apply(PACKED_REFS, Vec::new())?;
+    assert_eq!(out.as_bstr(), PACKED_REFS.as_bstr());
+    assert_eq!(outcome, Outcome::default());
+    Ok(())
+}
+
+#[test]
+fn empty_input_receives_a_header() -> crate::Result {
+    let (out, _) = apply(
+        b"",
+        vec![update(
+            "refs/heads/main",
+            "916840c0e2f67d370291042cb5274a597f4fa9bc",
+            None,
+        )],
+    )?;
+    assert_eq!(
+        out.as_bstr(),
+        b"# pack-refs with: peeled fully-peeled sorted \n916840c0e2f67d370291042cb5274a597f4fa9bc refs/heads/main\n"
+            .as_bstr()
+    );
+
+    let (out, _) = apply(b"", vec![delete("refs/heads/main")])?;
+    assert!(out.is_empty(), "nothing is written if there is nothing to write");
+    Ok(())
+}
+
+#[test]
+fn buffers_can_be_edited_and_provide_their_header() -> crate::Result {
+    let (_keep, path) = crate::packed::write_packed_refs_with(PACKED_REFS)?;
+    let buf = packed::Buffer::open(path, 1024)?;
+    assert_eq!(
+        buf.header(),
+        packed::Header {
+            peeled: packed::Peeled::Fully,
+            sorted: true
+        }
+    );
+
+    let mut out = Vec::new();
+    buf.edit(Some(delete("refs/heads/main")), &mut out)?;
+    assert_eq!(out.lines().count(), 5);
+    Ok(())
+}
+
+#[test]
+fn headers_round_trip() -> crate::Result {
+    for peeled in &[
+        packed::Peeled::Unspecified,
+        packed::Peeled::Partial,
+        packed::Peeled::Fully,
+    ] {
+        for sorted in &[false, true] {
+            let header = packed::Header {
+                peeled: *peeled,
+                sorted: *sorted,
+            };
+            let mut buf = Vec::new();
+            header.write_to(&mut buf)?;
+            assert_eq!(packed::Header::from_bytes(&buf), Some(header));
+        }
+    }
+    Ok(())
+}
+
+mod invalid {
+    use super::{apply, delete, PACKED_REFS};
+
+    #[test]
+    fn duplicate_edits() {
+        let err = apply(PACKED_REFS, vec![delete("refs/heads/main"), delete("refs/heads/main")])
+            .expect_err("duplicates are rejected");
+        assert_eq!(
+            err.to_string(),
+            "The reference 'refs/heads/main' was edited more than once"
+        );
+    }
+
+    #[test]
+    fn unsorted_input() {
+        let err = apply(
+            b"916840c0e2f67d370291042cb5274a597f4fa9bc refs/heads/z
+916840c0e2f67d370291042cb5274a597f4fa9bc refs/heads/a
+",
+            Vec::new(),
+        )
+        .expect_err("unsorted input can't be edited");
+        assert_eq!(err.to_string(), "The reference 'refs/heads/a' in line 2 is not sorted");
+    }
+
+    #[test]
+    fn broken_reference() {
+        let err = apply(b"# pack-refs with: sorted \nbuggy-hash refs/wrong\n", Vec::new())
+            .expect_err("invalid records are rejected");
+        assert_eq!(err.to_string(), "Invalid reference in line 2: 'buggy-hash refs/wrong'");
+    }
+}
@@ -65,6 +65,25 @@ c4cebba92af964f2d126be90b8a6298c4cf84d45 refs/tags/git-actor-v0.1.0
     Ok(())
 }
 
+#[test]
+fn references_sorting_before_all_others_are_not_found_without_error() -> crate::Result {
+    let packed_refs = b"# pack-refs with: peeled fully-peeled sorted \n\
+eae452baaa930ecaa2abe9d746aab45677cdcbd3 refs/heads/main
+a9e0b5d2bde6e28e6b3391d206e178a6dead1b03 refs/tags/before
+^eae452baaa930ecaa2abe9d746aab45677cdcbd3\n";
+    let (_keep, path) = write_packed_refs_with(packed_refs)?;
+
+    let buf = packed::Buffer::open(path, 1024)?;
+    for name in &["refs/a", "refs/before", "refs/heads/a"] {
+        assert_eq!(buf.find(*name)?, None, "the header is never parsed as reference");
+    }
+    assert_eq!(
+        buf.find("refs/tags/before")?.expect("reference exists").object,
+        Some("eae452baaa930ecaa2abe9d746aab45677cdcbd3".into())
+    );
+    Ok(())
+}
+
 #[test]
 #[ignore]
 fn partial_name_to_full_name_conversion_rules_are_applied() {
@@ -7,6 +7,7 @@ pub fn write_packed_refs_with(input: &[u8]) -> crate::Result<(tempfile::TempDir,
     Ok((dir, packed_refs_path))
 }
 
+mod edit;
 mod find;
 pub mod iter;
 mod open;
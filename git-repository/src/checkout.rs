@@ -0,0 +1,201 @@
+//! Write blobs into the working tree without holding them in memory, see [`Repository::checkout_blob()`].
+use crate::Repository;
+use git_features::fs::Policy;
+use git_hash::{oid, ObjectId};
+use git_object::{bstr::ByteSlice, tree::EntryMode, Kind};
+use git_odb::pack;
+use quick_error::quick_error;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Find(err: git_odb::compound::find::Error) {
+            display("The blob could not be looked up")
+            from()
+            source(err)
+        }
+        NotFound { id: ObjectId } {
+            display("The blob {} does not exist", id)
+        }
+        NotABlob { id: ObjectId, kind: Kind } {
+            display("Expected {} to be a blob, but it is a {}", id, kind)
+        }
+        UnsupportedMode { mode: EntryMode } {
+            display("Entries with mode {:?} can't be checked out as files", mode)
+        }
+        Io(err: io::Error, path: PathBuf) {
+            display("Could not write blob to '{}'", path.display())
+            source(err)
+        }
+        HashMismatch { expected: ObjectId, actual: ObjectId, path: PathBuf } {
+            display("The data of blob {} hashed to {} and was not written to '{}'", expected, actual, path.display())
+        }
+    }
+}
+
+impl Repository {
+    /// Write the data of the blob with `id` to the file at `path`, replacing it if it exists, and return the amount of
+    /// bytes written.
+    ///
+    /// The data is streamed from the object database into a temporary file next to `path` while being hashed, and only
+    /// if the hash matches `id` the temporary file is moved into place. That way, damaged object databases can't
+    /// silently write corrupt data into the working tree, and huge blobs don't have to fit into memory.
+    /// The file is created with the permissions new files usually get, and if `mode` is [`EntryMode::BlobExecutable`],
+    /// everyone who can read it can also execute it. Blobs with [`EntryMode::Link`] become symbolic links pointing to their
+    /// data. Either is only done if the file system supports it according to the capabilities of `policy`, for example as
+    /// obtained by [`fs_policy()`][Repository::fs_policy()], otherwise plain files are written instead.
+    ///
+    /// The directory containing `path` must exist.
+    pub fn checkout_blob(
+        &self,
+        id: impl AsRef<oid>,
+        path: impl AsRef<Path>,
+        mode: EntryMode,
+        policy: &Policy,
+    ) -> Result<u64, Error> {
+        let (id, path) = (id.as_ref(), path.as_ref());
+        let (executable, symlink) = match mode {
+            EntryMode::Blob => (false, false),
+            EntryMode::BlobExecutable => (policy.capabilities.executable_bit, false),
+            EntryMode::Link => (false, policy.capabilities.symlink),
+            EntryMode::Tree | EntryMode::Commit => return Err(Error::UnsupportedMode { mode }),
+        };
+        let expected = self.odb.replacement_of(id)?.to_owned();
+        let mut buf = Vec::new();
+        let mut stream = self
+            .odb
+            .stream(id, &mut buf, &mut pack::cache::Never)?
+            .ok_or_else(|| Error::NotFound { id: id.to_owned() })?;
+        if stream.kind != Kind::Blob {
+            return Err(Error::NotABlob {
+                id: id.to_owned(),
+                kind: stream.kind,
+            });
+        }
+
+        let io_err = |err| Error::Io(err, path.to_owned());
+        let mut header = Vec::with_capacity(git_object::loose::header::MAX_LEN);
+        git_object::loose::header::encode(Kind::Blob, stream.size, &mut header).map_err(io_err)?;
+        let verify = |actual: ObjectId| {
+            if actual == expected {
+                Ok(())
+            } else {
+                Err(Error::HashMismatch {
+                    expected,
+                    actual,
+                    path: path.to_owned(),
+                })
+            }
+        };
+
+        if symlink {
+            let mut target = Vec::new();
+            let (written, actual) = copy_hashed(&mut stream, &mut target, &header, expected.kind()).map_err(io_err)?;
+            verify(actual)?;
+            let target = target.to_path().map_err(|_| {
+                io_err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "symbolic link targets must be valid unicode on this platform",
+                ))
+            })?;
+            let (mut tempfile, ()) =
+                Tempfile::create_next_to(path, |link| create_symlink(target, link)).map_err(io_err)?;
+            tempfile.persist(path).map_err(io_err)?;
+            return Ok(written);
+        }
+
+        let (mut tempfile, mut file) = Tempfile::create_next_to(path, |path| {
+            std::fs::OpenOptions::new().write(true).create_new(true).open(path)
+        })
+        .map_err(io_err)?;
+        let (written, actual) = copy_hashed(&mut stream, &mut file, &header, expected.kind()).map_err(io_err)?;
+        if executable {
+            add_executable_bits(&file).map_err(io_err)?;
+        }
+        drop(file);
+        verify(actual)?;
+        tempfile.persist(path).map_err(io_err)?;
+        Ok(written)
+    }
+}
+
+/// Copy all of `stream` into `out` and return the amount of bytes copied along with the id of the blob with the given
+/// `header` and the data of `stream`.
+fn copy_hashed(
+    stream: &mut impl io::Read,
+    out: impl io::Write,
+    header: &[u8],
+    kind: git_hash::Kind,
+) -> io::Result<(u64, ObjectId)> {
+    let mut hasher = git_features::hash::Write::new(out, kind)?;
+    hasher.hash.update(header);
+    let written = io::copy(stream, &mut hasher)?;
+    Ok((written, ObjectId::from(hasher.hash.digest())))
+}
+
+/// A file or symbolic link next to the destination of a checkout, which is removed unless it was moved into place.
+struct Tempfile {
+    path: PathBuf,
+    persisted: bool,
+}
+
+impl Tempfile {
+    /// Call `create` with a non-clashing path in the directory of `destination` until it doesn't fail because something
+    /// already exists at that path.
+    fn create_next_to<T>(destination: &Path, mut create: impl FnMut(&Path) -> io::Result<T>) -> io::Result<(Self, T)> {
+        let file_name = destination
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "The destination has no file name"))?
+            .to_string_lossy();
+        for attempt in 0usize.. {
+            let path = destination.with_file_name(format!(".{}.{}-{}.tmp", file_name, std::process::id(), attempt));
+            match create(&path) {
+                Ok(created) => return Ok((Tempfile { path, persisted: false }, created)),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        unreachable!("there are more attempts than files in a directory")
+    }
+
+    fn persist(&mut self, destination: &Path) -> io::Result<()> {
+        std::fs::rename(&self.path, destination)?;
+        self.persisted = true;
+        Ok(())
+    }
+}
+
+impl Drop for Tempfile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            std::fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+/// Make `file` executable by everyone who can read it, like git does.
+#[cfg(unix)]
+fn add_executable_bits(file: &std::fs::File) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = file.metadata()?.permissions().mode();
+    file.set_permissions(std::fs::Permissions::from_mode(mode | ((mode & 0o444) >> 2)))
+}
+
+#[cfg(not(unix))]
+fn add_executable_bits(_file: &std::fs::File) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,114 @@
+//! Read the few configuration values we need, with a strong bias towards simplicity until there is proper configuration support.
+use git_config::{file::GitConfig, values::Integer};
+use quick_error::quick_error;
+use std::{
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Io(err: std::io::Error, path: PathBuf) {
+            display("Could not read configuration file at '{}'", path.display())
+            source(err)
+        }
+        Parse(err: git_config::parser::Error<'static>, path: PathBuf) {
+            display("Could not parse configuration file at '{}'", path.display())
+            source(err)
+        }
+        InvalidSize{key: &'static str, value: String} {
+            display("The value '{}' of '{}' is not a valid size in bytes", value, key)
+        }
+        InvalidValue{key: &'static str, value: String} {
+            display("The value '{}' of '{}' is invalid", value, key)
+        }
+    }
+}
+
+/// Return the value of `core.deltaBaseCacheLimit` in bytes as configured in the environment or in the `config` file
+/// of the repository at `common_dir`, in that order, or `None` if it is not set.
+///
+/// Like git, the environment is checked for `GIT_CONFIG_COUNT` along with `GIT_CONFIG_KEY_<n>` and `GIT_CONFIG_VALUE_<n>`
+/// pairs, which is how `git -c key=value` passes configuration to sub-processes.
+pub fn delta_base_cache_limit(common_dir: &Path) -> Result<Option<usize>, Error> {
+    const KEY: &str = "core.deltaBaseCacheLimit";
+    if let Some(value) = from_env(KEY) {
+        return Integer::from_str(&value)
+            .ok()
+            .and_then(to_size)
+            .map(Some)
+            .ok_or(Error::InvalidSize { key: KEY, value });
+    }
+
+    let path = common_dir.join("config");
+    let buf = match std::fs::read(&path) {
+        Ok(buf) => buf,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(Error::Io(err, path)),
+    };
+    let config = GitConfig::try_from(&buf).map_err(|err| Error::Parse(err.to_owned(), path))?;
+    match config.get_raw_value("core", None, "deltaBaseCacheLimit") {
+        Ok(value) => Integer::try_from(value.as_ref())
+            .ok()
+            .and_then(to_size)
+            .map(Some)
+            .ok_or_else(|| Error::InvalidSize {
+                key: KEY,
+                value: String::from_utf8_lossy(&value).into_owned(),
+            }),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Return the values of the given `keys` of the `core` section, like `core.fileMode`, as configured in the environment or in the
+/// `config` file of the repository at `common_dir`, in that order, with `None` for each key that is not set.
+pub(crate) fn core_values(common_dir: &Path, keys: &[&'static str]) -> Result<Vec<Option<String>>, Error> {
+    let mut buf = Vec::new();
+    let config = from_file(common_dir, &mut buf)?;
+    Ok(keys
+        .iter()
+        .map(|key| {
+            from_env(key).or_else(|| {
+                let name = key.strip_prefix("core.").expect("only keys in the core section");
+                config
+                    .get_raw_value("core", None, name)
+                    .ok()
+                    .map(|value| String::from_utf8_lossy(&value).into_owned())
+            })
+        })
+        .collect())
+}
+
+/// Read the `config` file of the repository at `common_dir` into `buf` and parse it, which yields an empty configuration if
+/// there is no such file.
+pub(crate) fn from_file<'a>(common_dir: &Path, buf: &'a mut Vec<u8>) -> Result<GitConfig<'a>, Error> {
+    let path = common_dir.join("config");
+    *buf = match std::fs::read(&path) {
+        Ok(buf) => buf,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(Error::Io(err, path)),
+    };
+    GitConfig::try_from(&*buf).map_err(|err| Error::Parse(err.to_owned(), path))
+}
+
+/// Return the last value of `key` in the configuration passed by environment variables, if present.
+fn from_env(key: &str) -> Option<String> {
+    let count: usize = std::env::var("GIT_CONFIG_COUNT").ok()?.parse().ok()?;
+    (0..count).rev().find_map(|index| {
+        let env_key = std::env::var(format!("GIT_CONFIG_KEY_{}", index)).ok()?;
+        if env_key.eq_ignore_ascii_case(key) {
+            std::env::var(format!("GIT_CONFIG_VALUE_{}", index)).ok()
+        } else {
+            None
+        }
+    })
+}
+
+fn to_size(value: Integer) -> Option<usize> {
+    let shift = value.suffix.map_or(0, |suffix| suffix.bitwise_offset());
+    usize::try_from(value.value)
+        .ok()
+        .and_then(|value| value.checked_mul(1 << shift))
+}
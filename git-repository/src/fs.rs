@@ -0,0 +1,56 @@
+//! Compare file system metadata the way git does, taking into account what the file system is capable of and how the
+//! repository is configured, see [`Policy`].
+use crate::{config, Repository};
+use git_config::values::Boolean;
+pub use git_features::fs::{Capabilities, CheckStat, Policy, Stat, Time};
+use std::{convert::TryFrom, path::Path};
+
+/// Create a new [`Policy`] from the configuration of the repository at `common_dir` or the environment, falling back to
+/// the given `capabilities` for each capability that isn't configured.
+pub fn policy_from_config(common_dir: &Path, capabilities: Capabilities) -> Result<Policy, config::Error> {
+    const KEYS: &[&str] = &[
+        "core.fileMode",
+        "core.symlinks",
+        "core.ignoreCase",
+        "core.trustctime",
+        "core.checkStat",
+    ];
+    let mut values = config::core_values(common_dir, KEYS)?.into_iter().zip(KEYS.iter());
+    let mut boolean = |default: bool| -> Result<bool, config::Error> {
+        let (value, key) = values.next().expect("one value per key");
+        match value {
+            Some(value) => Boolean::try_from(value.as_str())
+                .map(Into::into)
+                .map_err(|_| config::Error::InvalidValue { key, value }),
+            None => Ok(default),
+        }
+    };
+    let capabilities = Capabilities {
+        executable_bit: boolean(capabilities.executable_bit)?,
+        symlink: boolean(capabilities.symlink)?,
+        ignore_case: boolean(capabilities.ignore_case)?,
+    };
+    let trust_ctime = boolean(true)?;
+    let check_stat = match values.next().expect("one value per key") {
+        (None, _) => CheckStat::Default,
+        (Some(value), key) => match value.as_str() {
+            "default" => CheckStat::Default,
+            "minimal" => CheckStat::Minimal,
+            _ => return Err(config::Error::InvalidValue { key, value }),
+        },
+    };
+    Ok(Policy {
+        capabilities,
+        trust_ctime,
+        check_stat,
+        use_nsec: false,
+    })
+}
+
+impl Repository {
+    /// Return the [`Policy`] to use when comparing files in our working tree to what was recorded about them,
+    /// as configured in the repository or the environment.
+    pub fn fs_policy(&self) -> Result<Policy, config::Error> {
+        policy_from_config(self.common_dir(), Capabilities::default())
+    }
+}
@@ -1,3 +1,4 @@
+use git_object::bstr::{BStr, BString, ByteSlice};
 use quick_error::quick_error;
 use std::{
     fs::{self, OpenOptions},
@@ -24,6 +25,13 @@ quick_error! {
             display("Could not create directory at '{}'", path.display())
             source(err)
         }
+        CopyTemplate(err: std::io::Error, path: PathBuf) {
+            display("Could not copy the template at '{}'", path.display())
+            source(err)
+        }
+        InvalidConfigKey(key: String) {
+            display("The configuration key '{}' is not of the form 'section.name' or 'section.subsection.name'", key)
+        }
     }
 }
 
@@ -46,6 +54,23 @@ const TPL_CONFIG: &[u8] = include_bytes!("./assets/baseline-init/config");
 const TPL_DESCRIPTION: &[u8] = include_bytes!("./assets/baseline-init/description");
 const TPL_HEAD: &[u8] = include_bytes!("./assets/baseline-init/HEAD");
 
+/// Options to configure [`repository_opts()`].
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct Options {
+    /// If set, the files and directories in this directory are copied into the new `.git` directory instead of the built-in
+    /// sample hooks, `info/exclude` and `description` files, similar to `git init --template` or `init.templateDir`.
+    ///
+    /// A `config` file in the template is used as the initial configuration of the repository.
+    pub template_dir: Option<PathBuf>,
+    /// Values to write into the configuration of the new repository, as pairs of keys like `core.bare` or `remote.origin.url`
+    /// and their values, in order.
+    ///
+    /// They are appended to the configuration of the template, so they take precedence over values of single-valued keys
+    /// like `core.bare` in the template, while multi-valued keys like `remote.origin.fetch` keep the values of the template
+    /// and gain the new ones.
+    pub config: Vec<(String, BString)>,
+}
+
 struct PathCursor<'a>(&'a mut PathBuf);
 
 struct NewDir<'a>(&'a mut PathBuf);
@@ -85,6 +110,7 @@ fn write_file(data: &[u8], path: &Path) -> Result<(), Error> {
         .write(true)
         .create(true)
         .append(false)
+        .truncate(true)
         .open(path)
         .map_err(|e| Error::IoOpen(e, path.to_owned()))?;
     file.write_all(data).map_err(|e| Error::IoWrite(e, path.to_owned()))
@@ -94,7 +120,16 @@ fn create_dir(p: &Path) -> Result<(), Error> {
     fs::create_dir_all(p).map_err(|e| Error::CreateDirectory(e, p.to_owned()))
 }
 
+/// Create a new repository with a working tree in `directory`, placing its `.git` directory into it.
 pub fn repository(directory: impl Into<PathBuf>) -> Result<(), Error> {
+    repository_opts(directory, &Options::default())
+}
+
+/// Like [`repository()`], but with `options` to provide a template directory and initial configuration values.
+pub fn repository_opts(directory: impl Into<PathBuf>, options: &Options) -> Result<(), Error> {
+    if let Some((key, _)) = options.config.iter().find(|(key, _)| split_config_key(key).is_none()) {
+        return Err(Error::InvalidConfigKey(key.to_owned()));
+    }
     let mut cursor = directory.into();
     cursor.push(GIT_DIR_NAME);
 
@@ -103,28 +138,35 @@ pub fn repository(directory: impl Into<PathBuf>) -> Result<(), Error> {
     }
     create_dir(&cursor)?;
 
-    {
-        let mut cursor = NewDir(&mut cursor).at("info")?;
-        write_file(TPL_INFO_EXCLUDE, PathCursor(cursor.as_mut()).at("exclude"))?;
-    }
+    match &options.template_dir {
+        Some(template_dir) => copy_template(template_dir, &cursor)?,
+        None => {
+            {
+                let mut cursor = NewDir(&mut cursor).at("info")?;
+                write_file(TPL_INFO_EXCLUDE, PathCursor(cursor.as_mut()).at("exclude"))?;
+            }
 
-    {
-        let mut cursor = NewDir(&mut cursor).at("hooks")?;
-        for (tpl, filename) in &[
-            (TPL_HOOKS_UPDATE, "update.sample"),
-            (TPL_HOOKS_PREPARE_COMMIT_MSG, "prepare-commit-msg.sample"),
-            (TPL_HOOKS_PRE_RECEIVE, "pre-receive.sample"),
-            (TPL_HOOKS_PRE_REBASE, "pre-rebase.sample"),
-            (TPL_HOOKS_PRE_PUSH, "pre-push.sample"),
-            (TPL_HOOKS_PRE_COMMIT, "pre-commit.sample"),
-            (TPL_HOOKS_PRE_MERGE_COMMIT, "pre-merge-commit.sample"),
-            (TPL_HOOKS_PRE_APPLYPATCH, "pre-applypatch.sample"),
-            (TPL_HOOKS_POST_UPDATE, "post-update.sample"),
-            (TPL_HOOKS_FSMONITOR_WATCHMAN, "fsmonitor-watchman.sample"),
-            (TPL_HOOKS_COMMIT_MSG, "commit-msg.sample"),
-            (TPL_HOOKS_APPLYPATCH_MSG, "applypatch-msg.sample"),
-        ] {
-            write_file(tpl, PathCursor(cursor.as_mut()).at(filename))?;
+            {
+                let mut cursor = NewDir(&mut cursor).at("hooks")?;
+                for (tpl, filename) in &[
+                    (TPL_HOOKS_UPDATE, "update.sample"),
+                    (TPL_HOOKS_PREPARE_COMMIT_MSG, "prepare-commit-msg.sample"),
+                    (TPL_HOOKS_PRE_RECEIVE, "pre-receive.sample"),
+                    (TPL_HOOKS_PRE_REBASE, "pre-rebase.sample"),
+                    (TPL_HOOKS_PRE_PUSH, "pre-push.sample"),
+                    (TPL_HOOKS_PRE_COMMIT, "pre-commit.sample"),
+                    (TPL_HOOKS_PRE_MERGE_COMMIT, "pre-merge-commit.sample"),
+                    (TPL_HOOKS_PRE_APPLYPATCH, "pre-applypatch.sample"),
+                    (TPL_HOOKS_POST_UPDATE, "post-update.sample"),
+                    (TPL_HOOKS_FSMONITOR_WATCHMAN, "fsmonitor-watchman.sample"),
+                    (TPL_HOOKS_COMMIT_MSG, "commit-msg.sample"),
+                    (TPL_HOOKS_APPLYPATCH_MSG, "applypatch-msg.sample"),
+                ] {
+                    write_file(tpl, PathCursor(cursor.as_mut()).at(filename))?;
+                }
+            }
+
+            write_file(TPL_DESCRIPTION, PathCursor(&mut cursor).at("description"))?;
         }
     }
 
@@ -140,13 +182,130 @@ pub fn repository(directory: impl Into<PathBuf>) -> Result<(), Error> {
         create_dir(PathCursor(cursor.as_mut()).at("tags"))?;
     }
 
-    for (tpl, filename) in &[
-        (TPL_HEAD, "HEAD"),
-        (TPL_DESCRIPTION, "description"),
-        (TPL_CONFIG, "config"),
-    ] {
-        write_file(tpl, PathCursor(&mut cursor).at(filename))?;
-    }
+    write_file(TPL_HEAD, PathCursor(&mut cursor).at("HEAD"))?;
 
+    let config_path = PathCursor(&mut cursor).at("config").to_owned();
+    let mut config = match fs::read(&config_path) {
+        Ok(config) => config,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => TPL_CONFIG.to_vec(),
+        Err(err) => return Err(Error::IoOpen(err, config_path)),
+    };
+    append_config_values(&mut config, &options.config);
+    write_file(&config, &config_path)?;
+
+    Ok(())
+}
+
+/// Recursively copy all files and directories in `template_dir` into `git_dir`, keeping their permissions.
+///
+/// Symlinks are recreated with the same target instead of being followed.
+fn copy_template(template_dir: &Path, git_dir: &Path) -> Result<(), Error> {
+    let entries = fs::read_dir(template_dir).map_err(|err| Error::CopyTemplate(err, template_dir.to_owned()))?;
+    for entry in entries {
+        let entry = entry.map_err(|err| Error::CopyTemplate(err, template_dir.to_owned()))?;
+        let (source, destination) = (entry.path(), git_dir.join(entry.file_name()));
+        let file_type = entry
+            .file_type()
+            .map_err(|err| Error::CopyTemplate(err, source.clone()))?;
+        if file_type.is_dir() {
+            create_dir(&destination)?;
+            copy_template(&source, &destination)?;
+        } else if file_type.is_symlink() {
+            fs::read_link(&source)
+                .and_then(|target| symlink(&source, &target, &destination))
+                .map_err(|err| Error::CopyTemplate(err, source))?;
+        } else {
+            fs::copy(&source, &destination).map_err(|err| Error::CopyTemplate(err, source))?;
+        }
+    }
     Ok(())
 }
+
+#[cfg(unix)]
+fn symlink(_source: &Path, target: &Path, destination: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, destination)
+}
+
+#[cfg(windows)]
+fn symlink(source: &Path, target: &Path, destination: &Path) -> std::io::Result<()> {
+    if source.is_dir() {
+        std::os::windows::fs::symlink_dir(target, destination)
+    } else {
+        std::os::windows::fs::symlink_file(target, destination)
+    }
+}
+
+/// Append `values` to the git configuration file in `config`, opening a new section whenever the section of a key changes.
+///
+/// All keys must have been validated before.
+fn append_config_values(config: &mut Vec<u8>, values: &[(String, BString)]) {
+    if !values.is_empty() && !config.is_empty() && !config.ends_with(b"\n") {
+        config.push(b'\n');
+    }
+    let mut current_section = None;
+    for (key, value) in values {
+        let (section, subsection, name) = split_config_key(key).expect("keys are validated before");
+        if current_section != Some((section, subsection)) {
+            config.push(b'[');
+            config.extend_from_slice(section.as_bytes());
+            if let Some(subsection) = subsection {
+                config.extend_from_slice(b" \"");
+                for byte in subsection.bytes() {
+                    if byte == b'"' || byte == b'\\' {
+                        config.push(b'\\');
+                    }
+                    config.push(byte);
+                }
+                config.push(b'"');
+            }
+            config.extend_from_slice(b"]\n");
+            current_section = Some((section, subsection));
+        }
+        config.push(b'\t');
+        config.extend_from_slice(name.as_bytes());
+        config.extend_from_slice(b" = ");
+        write_config_value(config, value.as_ref());
+        config.push(b'\n');
+    }
+}
+
+/// Split `key` into its section, optional subsection and name, or return `None` if it is invalid.
+fn split_config_key(key: &str) -> Option<(&str, Option<&str>, &str)> {
+    let (section, rest) = key.split_at(key.find('.')?);
+    let (subsection, name) = match rest[1..].rfind('.') {
+        Some(pos) => (Some(&rest[1..][..pos]), &rest[1..][pos + 1..]),
+        None => (None, &rest[1..]),
+    };
+    let is_valid_section = !section.is_empty() && section.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-');
+    let is_valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-');
+    let is_valid_subsection = !subsection.unwrap_or_default().contains(&['\n', '\0'][..]);
+    if is_valid_section && is_valid_name && is_valid_subsection {
+        Some((section, subsection, name))
+    } else {
+        None
+    }
+}
+
+/// Write `value` to `out`, quoting and escaping it like git does.
+fn write_config_value(out: &mut Vec<u8>, value: &BStr) {
+    let needs_quotes = value.starts_with(b" ")
+        || value.ends_with(b" ")
+        || value.starts_with(b"\t")
+        || value.ends_with(b"\t")
+        || value.find_byteset(b"#;").is_some();
+    if needs_quotes {
+        out.push(b'"');
+    }
+    for byte in value.bytes() {
+        match byte {
+            b'\n' => out.extend_from_slice(b"\\n"),
+            b'\t' => out.extend_from_slice(b"\\t"),
+            b'"' | b'\\' => out.extend_from_slice(&[b'\\', byte]),
+            _ => out.push(byte),
+        }
+    }
+    if needs_quotes {
+        out.push(b'"');
+    }
+}
@@ -78,17 +78,44 @@ pub mod prelude {
     pub use git_odb::{Find, FindExt, Write};
 }
 
+pub mod checkout;
+
+#[cfg(feature = "git-config")]
+pub mod config;
+
+#[cfg(feature = "git-config")]
+pub mod fs;
+
 pub mod init;
 
 pub mod path;
 pub use path::Path;
 
+pub mod prune;
+
+pub mod replace;
+
 pub mod repository;
 
+pub mod snapshot;
+pub use snapshot::Snapshot;
+
+#[cfg(feature = "git-diff")]
+pub mod tree_diff;
+
+#[cfg(feature = "git-config")]
+pub mod upstream;
+
 pub struct Repository {
     pub refs: git_ref::file::Store,
     pub working_tree: Option<PathBuf>,
     pub odb: git_odb::linked::Store,
+    /// The directory containing objects, references and configuration shared by all working trees, which is the `git_dir`
+    /// unless this is a linked working tree.
+    pub(crate) common_dir: PathBuf,
+    /// The error encountered when reading the configuration during discovery, which caused defaults to be used instead.
+    #[cfg(feature = "git-config")]
+    pub(crate) config_error: Option<config::Error>,
 }
 
 impl Repository {
@@ -99,12 +126,33 @@ impl Repository {
         }
     }
 
+    /// The working tree of this repository, or `None` if it is bare.
+    ///
+    /// Like all paths returned here, it is represented as configured by the [`Canonicalization`][path::Canonicalization]
+    /// used when [discovering][Repository::discover_opts()] the repository.
+    pub fn work_dir(&self) -> Option<&std::path::Path> {
+        self.working_tree.as_deref()
+    }
+    /// The directory holding the `HEAD` of this repository, which is private to its working tree if it is a linked working tree.
     pub fn git_dir(&self) -> &std::path::Path {
         &self.refs.base
     }
+    /// The directory shared by all working trees of this repository, which is the same as the [`git_dir()`][Repository::git_dir()]
+    /// unless this is a linked working tree.
+    pub fn common_dir(&self) -> &std::path::Path {
+        &self.common_dir
+    }
+    /// The directory containing the objects of this repository, inside of the [`common_dir()`][Repository::common_dir()].
     pub fn objects_dir(&self) -> &std::path::Path {
         &self.odb.dbs[0].loose.path
     }
+    /// The error that occurred when reading the repository configuration during discovery, or `None` if it could be read.
+    ///
+    /// Defaults are used for all configuration values in case of an error.
+    #[cfg(feature = "git-config")]
+    pub fn config_error(&self) -> Option<&config::Error> {
+        self.config_error.as_ref()
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
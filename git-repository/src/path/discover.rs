@@ -14,12 +14,43 @@ pub mod existing {
             NoGitRepository(path: PathBuf) {
                 display("Could find a git repository in '{}' or in any of its parents", path.display())
             }
+            ReadGitDirFile(err: std::io::Error, path: PathBuf) {
+                display("Could not read the .git file at '{}'", path.display())
+                source(err)
+            }
+            MalformedGitDirFile(path: PathBuf) {
+                display("The .git file at '{}' does not contain a 'gitdir: <path>' line", path.display())
+            }
+            InvalidGitDir{dot_git_file: PathBuf, git_dir: PathBuf, err: crate::path::is_git::Error} {
+                display("The .git file at '{}' points to '{}' which is not a git directory", dot_git_file.display(), git_dir.display())
+                source(err)
+            }
+            GitDirOutsideAllowedRoots{dot_git_file: PathBuf, git_dir: PathBuf} {
+                display("The .git file at '{}' points to the git directory '{}' which is not inside any of the allowed roots", dot_git_file.display(), git_dir.display())
+            }
         }
     }
+
+    /// Options to configure how [`existing_opts()`][super::existing_opts()] handles `.git` files.
+    #[derive(Default, Debug, Clone, Eq, PartialEq)]
+    pub struct Options {
+        /// If true, a directory with a `.git` file pointing to an invalid git directory, or to one outside of the
+        /// `allowed_git_dir_roots`, is treated like a directory without repository and the search continues in its parent.
+        /// Otherwise an error is returned.
+        pub tolerate_invalid_git_dir_file: bool,
+        /// If not empty, the git directory a `.git` file points to has to be inside of one of these directories.
+        pub allowed_git_dir_roots: Vec<PathBuf>,
+    }
 }
 
 /// Returns the working tree if possible and the found repository is not bare or the git repository itself.
 pub fn existing(directory: impl AsRef<Path>) -> Result<crate::Path, existing::Error> {
+    existing_opts(directory, &existing::Options::default())
+}
+
+/// Like [`existing()`], but with `options` to configure how `.git` files with a `gitdir: <path>` line are handled,
+/// as used by submodules and linked working trees.
+pub fn existing_opts(directory: impl AsRef<Path>, options: &existing::Options) -> Result<crate::Path, existing::Error> {
     let directory = directory.as_ref();
     if !directory.is_dir() {
         return Err(existing::Error::InaccessibleDirectory(directory.into()));
@@ -31,7 +62,11 @@ pub fn existing(directory: impl AsRef<Path>) -> Result<crate::Path, existing::Er
             break Ok(crate::Path::from_dot_git_dir(cursor, kind));
         }
         let git_dir = cursor.join(".git");
-        if let Ok(kind) = path::is_git(&git_dir) {
+        if git_dir.is_file() {
+            if let Some(path) = from_git_dir_file(&git_dir, options)? {
+                break Ok(path);
+            }
+        } else if let Ok(kind) = path::is_git(&git_dir) {
             break Ok(crate::Path::from_dot_git_dir(git_dir, kind));
         }
         match cursor.parent() {
@@ -40,3 +75,49 @@ pub fn existing(directory: impl AsRef<Path>) -> Result<crate::Path, existing::Er
         }
     }
 }
+
+/// Returns `None` if the git directory `dot_git_file` points to is invalid, but that is tolerated as per `options`.
+fn from_git_dir_file(dot_git_file: &Path, options: &existing::Options) -> Result<Option<crate::Path>, existing::Error> {
+    let content =
+        std::fs::read(dot_git_file).map_err(|err| existing::Error::ReadGitDirFile(err, dot_git_file.to_owned()))?;
+    let relative_git_dir = std::str::from_utf8(&content)
+        .ok()
+        .and_then(|content| content.strip_prefix("gitdir: "))
+        .map(str::trim_end)
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| existing::Error::MalformedGitDirFile(dot_git_file.to_owned()))?;
+    let working_tree = dot_git_file.parent().expect(".git file has a parent directory");
+    let git_dir = working_tree.join(relative_git_dir);
+
+    let err = if let Err(err) = path::is_git(&git_dir) {
+        existing::Error::InvalidGitDir {
+            dot_git_file: dot_git_file.to_owned(),
+            git_dir,
+            err,
+        }
+    } else if !is_inside_any(&git_dir, &options.allowed_git_dir_roots) {
+        existing::Error::GitDirOutsideAllowedRoots {
+            dot_git_file: dot_git_file.to_owned(),
+            git_dir,
+        }
+    } else {
+        return Ok(Some(crate::Path::LinkedWorkingTree {
+            working_tree: working_tree.to_owned(),
+            git_dir,
+        }));
+    };
+    if options.tolerate_invalid_git_dir_file {
+        Ok(None)
+    } else {
+        Err(err)
+    }
+}
+
+fn is_inside_any(git_dir: &Path, roots: &[std::path::PathBuf]) -> bool {
+    if roots.is_empty() {
+        return true;
+    }
+    let canonicalize = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_owned());
+    let git_dir = canonicalize(git_dir);
+    roots.iter().any(|root| git_dir.starts_with(canonicalize(root)))
+}
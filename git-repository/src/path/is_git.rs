@@ -31,11 +31,11 @@ pub fn is_bare(git_dir: impl AsRef<Path>) -> bool {
 /// What constitutes a valid git repository, and what's yet to be implemented.
 ///
 /// * [x] a valid head
-/// * [ ] git common directory
+/// * [x] git common directory, as indicated by a `commondir` file in the git directories of linked working trees
 ///   * [ ] respect GIT_COMMON_DIR
-/// * [x] an objects directory
+/// * [x] an objects directory in the common directory
 ///   * [x] respect GIT_OBJECT_DIRECTORY
-/// * [x] a refs directory
+/// * [x] a refs directory in the common directory
 pub fn is_git(git_dir: impl AsRef<Path>) -> Result<crate::Kind, Error> {
     let dot_git = git_dir.as_ref();
 
@@ -47,16 +47,19 @@ pub fn is_git(git_dir: impl AsRef<Path>) -> Result<crate::Kind, Error> {
         }
     }
 
+    let common_dir = std::fs::read(dot_git.join("commondir"))
+        .map(|content| dot_git.join(String::from_utf8_lossy(&content).trim_end()))
+        .unwrap_or_else(|_| dot_git.to_owned());
     {
         let objects_path = std::env::var("GIT_OBJECT_DIRECTORY")
             .map(PathBuf::from)
-            .unwrap_or_else(|_| dot_git.join("objects"));
+            .unwrap_or_else(|_| common_dir.join("objects"));
         if !objects_path.is_dir() {
             return Err(Error::MissingObjectsDirectory(objects_path));
         }
     }
     {
-        let refs_path = dot_git.join("refs");
+        let refs_path = common_dir.join("refs");
         if !refs_path.is_dir() {
             return Err(Error::MissingRefsDirectory(refs_path));
         }
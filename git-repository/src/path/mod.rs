@@ -8,13 +8,39 @@ pub use is_git::{is_bare, is_git};
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Path {
     WorkingTree(PathBuf),
+    /// A working tree with a `.git` file pointing to its `git_dir`, as used by submodules and linked working trees.
+    LinkedWorkingTree {
+        working_tree: PathBuf,
+        git_dir: PathBuf,
+    },
     Repository(PathBuf),
 }
 
+/// Determines how the paths of a [`Repository`][crate::Repository] are represented once it was discovered.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Canonicalization {
+    /// Keep paths as they were discovered, which may be relative to the current working directory or contain `..` components
+    /// if a `.git` file pointed to the git directory.
+    KeepAsIs,
+    /// Make all paths absolute, resolving `.` and `..` components as well as symbolic links.
+    ///
+    /// This costs a system call per path, but makes them stable even if the current working directory changes and
+    /// comparable to paths obtained elsewhere.
+    Absolutize,
+}
+
+impl Default for Canonicalization {
+    fn default() -> Self {
+        Canonicalization::KeepAsIs
+    }
+}
+
 impl AsRef<std::path::Path> for Path {
     fn as_ref(&self) -> &std::path::Path {
         match self {
-            Path::WorkingTree(path) | Path::Repository(path) => path,
+            Path::WorkingTree(path) | Path::LinkedWorkingTree { working_tree: path, .. } | Path::Repository(path) => {
+                path
+            }
         }
     }
 }
@@ -29,7 +55,7 @@ impl Path {
     }
     pub fn kind(&self) -> Kind {
         match self {
-            Path::WorkingTree(_) => Kind::WorkingTree,
+            Path::WorkingTree(_) | Path::LinkedWorkingTree { .. } => Kind::WorkingTree,
             Path::Repository(_) => Kind::Bare,
         }
     }
@@ -37,6 +63,7 @@ impl Path {
     pub fn into_repository_directory(self) -> PathBuf {
         match self {
             Path::WorkingTree(path) => path.join(".git"),
+            Path::LinkedWorkingTree { git_dir, .. } => git_dir,
             Path::Repository(path) => path,
         }
     }
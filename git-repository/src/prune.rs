@@ -0,0 +1,272 @@
+//! Remove loose objects that can't be reached from references, reflogs or the index, see
+//! [`Repository::prune_unreachable_objects()`][crate::Repository::prune_unreachable_objects()].
+use crate::Repository;
+use git_hash::ObjectId;
+use quick_error::quick_error;
+use std::{
+    convert::TryInto,
+    path::{Path, PathBuf},
+};
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Io(err: std::io::Error, path: PathBuf) {
+            display("Could not read '{}'", path.display())
+            source(err)
+        }
+        LooseReference(err: git_ref::file::iter::loose::Error) {
+            display("A loose reference could not be read")
+            from()
+            source(err)
+        }
+        ReadHead(err: git_ref::file::find_one::Error) {
+            display("A HEAD reference could not be read")
+            from()
+            source(err)
+        }
+        PackedRefsOpen(err: git_ref::packed::buffer::open::Error) {
+            display("The packed-refs file could not be opened")
+            from()
+            source(err)
+        }
+        PackedRefsIter(err: git_ref::packed::iter::Error) {
+            display("The packed-refs file could not be read")
+            from()
+            source(err)
+        }
+        Reflog(err: git_ref::file::log::iter::decode::Error, path: PathBuf) {
+            display("The reflog at '{}' could not be parsed", path.display())
+            source(err)
+        }
+        Index(message: &'static str, path: PathBuf) {
+            display("The index at '{}' could not be parsed: {}", path.display(), message)
+        }
+        Bitmap(err: git_odb::pack::bitmap::init::Error) {
+            display("A reachability bitmap could not be loaded")
+            from()
+            source(err)
+        }
+        ObjectStoreInitialization(err: git_odb::linked::init::Error) {
+            display("Could not initialize the object database")
+            from()
+            source(err)
+        }
+        Prune(err: git_odb::loose::prune::Error<git_odb::compound::find::Error>) {
+            display("Unreachable objects could not be pruned")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// Return the ids of all objects directly referenced in the repository at `common_dir`, which are the starting points
+/// for finding all reachable objects.
+///
+/// These are the targets of all loose and packed references, `HEAD`, all objects mentioned in reflogs and all objects in
+/// the index, including the trees of its cache and the shared index of a split index, both for the main working tree and
+/// for all linked working trees along with their own references.
+/// Submodule commits in the index are ignored as they are not stored in this repository.
+///
+/// Index files don't indicate the kind of hash they use, which is why it has to be provided as `hash_kind`.
+pub fn roots(common_dir: &Path, hash_kind: git_hash::Kind) -> Result<Vec<ObjectId>, Error> {
+    let mut out = Vec::new();
+    let refs = git_ref::file::Store::at(common_dir, git_ref::file::WriteReflog::Disable);
+    if let Some(packed) = refs.packed()? {
+        for reference in packed.iter()? {
+            out.push(reference?.target());
+        }
+    }
+
+    let mut git_dirs = vec![common_dir.to_owned()];
+    let worktrees = common_dir.join("worktrees");
+    match std::fs::read_dir(&worktrees) {
+        Ok(entries) => {
+            for entry in entries {
+                git_dirs.push(entry.map_err(|err| Error::Io(err, worktrees.clone()))?.path());
+            }
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(Error::Io(err, worktrees)),
+    }
+    for git_dir in git_dirs {
+        let store = git_ref::file::Store::at(&git_dir, git_ref::file::WriteReflog::Disable);
+        if git_dir.join("refs").is_dir() {
+            let iter = store.loose_iter().map_err(|err| Error::Io(err, git_dir.join("refs")))?;
+            for reference in iter {
+                if let git_ref::mutable::Target::Peeled(id) = reference?.into_target() {
+                    out.push(id);
+                }
+            }
+        }
+        let head = store.find_one("HEAD")?;
+        if let Some(git_ref::mutable::Target::Peeled(id)) = head.map(|head| head.into_target()) {
+            out.push(id);
+        }
+        reflog_ids(&git_dir.join("logs"), &mut out)?;
+
+        let index = git_dir.join("index");
+        let shared_index = match std::fs::read(&index) {
+            Ok(data) => index_ids(&data, hash_kind, &mut out).map_err(|message| Error::Index(message, index))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(Error::Io(err, index)),
+        };
+        if let Some(id) = shared_index {
+            let index = git_dir.join(format!("sharedindex.{}", id));
+            let data = std::fs::read(&index).map_err(|err| Error::Io(err, index.clone()))?;
+            index_ids(&data, hash_kind, &mut out).map_err(|message| Error::Index(message, index))?;
+        }
+    }
+    Ok(out)
+}
+
+impl Repository {
+    /// Delete all loose objects in our object database which can't be reached from any of the [`roots()`] and which were last
+    /// modified before the [grace period][git_odb::loose::prune::Options::grace_period], or merely list them if
+    /// [`dry_run`][git_odb::loose::prune::Options::dry_run] is set.
+    ///
+    /// Commits covered by the reachability bitmap of a pack aren't traversed, as the bitmap lists all objects reachable from them.
+    /// Like `git prune`, object replacements are ignored so that replaced objects are kept as long as they are reachable.
+    /// Objects in alternates are never deleted, and the operation fails without deleting anything if an object is referenced
+    /// but missing, as seen in corrupt repositories.
+    ///
+    /// The kind of hash used in index files is assumed to be the one of our packs, or the default if there are none.
+    pub fn prune_unreachable_objects(
+        &self,
+        options: git_odb::loose::prune::Options,
+    ) -> Result<git_odb::loose::prune::Outcome, Error> {
+        let hash_kind = self.odb.dbs[0]
+            .bundles
+            .first()
+            .map_or_else(git_hash::Kind::default, |bundle| bundle.index.hash_kind());
+        let roots = roots(self.common_dir(), hash_kind)?;
+        let without_replacements;
+        let db = if self.odb.replacements.is_empty() {
+            &self.odb
+        } else {
+            without_replacements = git_odb::linked::Store::at(self.objects_dir())?;
+            &without_replacements
+        };
+        let bitmaps =
+            git_odb::loose::prune::Bitmaps::from_bundles(self.odb.dbs.iter().flat_map(|db| db.bundles.iter()))?;
+        Ok(self.odb.dbs[0].loose.prune_unreachable(roots, &bitmaps, db, options)?)
+    }
+}
+
+fn reflog_ids(dir: &Path, out: &mut Vec<ObjectId>) -> Result<(), Error> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(Error::Io(err, dir.to_owned())),
+    };
+    for entry in entries {
+        let path = entry.map_err(|err| Error::Io(err, dir.to_owned()))?.path();
+        if path.is_dir() {
+            reflog_ids(&path, out)?;
+            continue;
+        }
+        let log = std::fs::read(&path).map_err(|err| Error::Io(err, path.clone()))?;
+        for line in git_ref::file::log::iter::forward(&log) {
+            let line = line.map_err(|err| Error::Reflog(err, path.clone()))?;
+            out.extend(
+                [line.previous_oid(), line.new_oid()]
+                    .iter()
+                    .filter(|id| !id.is_null())
+                    .cloned(),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Extract the ids of all entries in the index file `data` of version 2 to 4, along with the ids of the trees in its cache,
+/// with all ids and the trailing checksum using hashes of `hash_kind`.
+///
+/// Return the id of the shared index if `data` is a split index, as its entries are stored in the shared index.
+fn index_ids(
+    data: &[u8],
+    hash_kind: git_hash::Kind,
+    out: &mut Vec<ObjectId>,
+) -> Result<Option<ObjectId>, &'static str> {
+    const ENTRY_ID_OFFSET: usize = 40;
+    const EXTENDED_FLAG: u16 = 0x4000;
+    const GITLINK_MODE: u32 = 0o160000;
+    let hash_len = hash_kind.len_in_bytes();
+    let entry_path_offset = ENTRY_ID_OFFSET + hash_len + 2;
+
+    let be_u32 = |b: &[u8]| u32::from_be_bytes(b.try_into().expect("4 bytes"));
+    if data.len() < 12 + hash_len || &data[..4] != b"DIRC" {
+        return Err("not an index file");
+    }
+    let version = be_u32(&data[4..8]);
+    if !(2..=4).contains(&version) {
+        return Err("unsupported index version");
+    }
+    let num_entries = be_u32(&data[8..12]);
+    let data = &data[..data.len() - hash_len];
+
+    let mut pos = 12;
+    for _ in 0..num_entries {
+        let header = data.get(pos..pos + entry_path_offset).ok_or("entry is truncated")?;
+        let mode = be_u32(&header[24..28]);
+        if mode & 0o170000 != GITLINK_MODE {
+            out.push(ObjectId::from_bytes(&header[ENTRY_ID_OFFSET..][..hash_len]));
+        }
+        let flags = u16::from_be_bytes([header[entry_path_offset - 2], header[entry_path_offset - 1]]);
+        let mut path_start = pos + entry_path_offset;
+        if version >= 3 && flags & EXTENDED_FLAG != 0 {
+            path_start += 2;
+        }
+        if version == 4 {
+            // skip the varint denoting how much of the previous path to strip.
+            while *data.get(path_start).ok_or("entry is truncated")? & 0x80 != 0 {
+                path_start += 1;
+            }
+            path_start += 1;
+        }
+        let path_len = data
+            .get(path_start..)
+            .and_then(|path| path.iter().position(|b| *b == 0))
+            .ok_or("entry path is not terminated")?;
+        pos = if version == 4 {
+            path_start + path_len + 1
+        } else {
+            // entries are padded with 1 to 8 null bytes to a multiple of 8 bytes.
+            pos + ((path_start - pos + path_len + 8) & !7)
+        };
+    }
+
+    let mut shared_index = None;
+    while let Some(header) = data.get(pos..pos + 8) {
+        let size = be_u32(&header[4..]) as usize;
+        let extension = data.get(pos + 8..pos + 8 + size).ok_or("extension is truncated")?;
+        match &header[..4] {
+            b"TREE" => tree_cache_ids(extension, hash_len, out).ok_or("tree cache extension is malformed")?,
+            b"link" => {
+                // the id of the shared index, followed by bitmaps of its entries which were deleted or replaced.
+                let id = ObjectId::from_bytes(extension.get(..hash_len).ok_or("link extension is truncated")?);
+                shared_index = Some(id).filter(|id| !id.is_null());
+            }
+            _ => {}
+        }
+        pos += 8 + size;
+    }
+    Ok(shared_index)
+}
+
+/// Parse the tree cache extension, whose entries are `<path>\0<entry-count> <subtree-count>\n<id>`, with the id missing for
+/// invalidated entries whose entry count is `-1` and all others having ids of `hash_len` bytes.
+fn tree_cache_ids(mut data: &[u8], hash_len: usize, out: &mut Vec<ObjectId>) -> Option<()> {
+    while !data.is_empty() {
+        let path_end = data.iter().position(|b| *b == 0)?;
+        data = &data[path_end + 1..];
+        let line_end = data.iter().position(|b| *b == b'\n')?;
+        let is_valid = *data.first()? != b'-';
+        data = &data[line_end + 1..];
+        if is_valid {
+            out.push(ObjectId::from_bytes(data.get(..hash_len)?));
+            data = &data[hash_len..];
+        }
+    }
+    Some(())
+}
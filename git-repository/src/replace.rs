@@ -0,0 +1,78 @@
+//! Read object replacements from the references in `refs/replace/`, as created by `git replace`.
+use git_hash::ObjectId;
+use quick_error::quick_error;
+use std::{collections::HashMap, path::Path};
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Io(err: std::io::Error) {
+            display("Loose replacement references could not be listed")
+            from()
+            source(err)
+        }
+        LooseReference(err: git_ref::file::iter::loose::Error) {
+            display("A loose replacement reference could not be read")
+            from()
+            source(err)
+        }
+        PackedRefsOpen(err: git_ref::packed::buffer::open::Error) {
+            display("The packed-refs file could not be opened")
+            from()
+            source(err)
+        }
+        PackedRefsIter(err: git_ref::packed::iter::Error) {
+            display("The packed-refs file could not be read")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// Return a map of replaced object ids to the ids of the objects replacing them, as defined by the references
+/// below `refs/replace/` in the repository at `common_dir`, suitable for use as
+/// [`replacements`][git_odb::linked::Store::replacements].
+///
+/// Like in git, the map is empty if the `GIT_NO_REPLACE_OBJECTS` environment variable is set, and `GIT_REPLACE_REF_BASE`
+/// can be used to read replacements from references other than `refs/replace/`.
+/// References whose name doesn't end in a full hexadecimal object id, or which are symbolic, are ignored.
+pub fn objects(common_dir: &Path) -> Result<HashMap<ObjectId, ObjectId>, Error> {
+    let mut out = HashMap::new();
+    if std::env::var_os("GIT_NO_REPLACE_OBJECTS").is_some() {
+        return Ok(out);
+    }
+    let base = std::env::var("GIT_REPLACE_REF_BASE").unwrap_or_else(|_| "refs/replace/".into());
+    let base = if base.ends_with('/') {
+        base
+    } else {
+        format!("{}/", base)
+    };
+
+    let refs = git_ref::file::Store::at(common_dir, git_ref::file::WriteReflog::Disable);
+    if let Some(packed) = refs.packed()? {
+        for reference in packed.iter()? {
+            let reference = reference?;
+            if let Some(replaced) = replaced_id(&reference.full_name.to_string(), &base) {
+                out.insert(replaced, reference.target());
+            }
+        }
+    }
+    if common_dir.join(&base).is_dir() {
+        for reference in refs.loose_iter_prefixed(&base)? {
+            let reference = reference?;
+            let name = reference.relative_path().to_string_lossy().replace('\\', "/");
+            if let Some(replaced) = replaced_id(&name, &base) {
+                if let git_ref::mutable::Target::Peeled(replacement) = reference.into_target() {
+                    out.insert(replaced, replacement);
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn replaced_id(full_name: &str, base: &str) -> Option<ObjectId> {
+    full_name
+        .strip_prefix(base)
+        .and_then(|hex| ObjectId::from_hex(hex.as_bytes()).ok())
+}
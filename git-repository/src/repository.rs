@@ -9,13 +9,23 @@ mod init {
             crate::init::repository(directory.as_ref())?;
             Ok(Repository::discover(directory).unwrap()) // TODO: a specialized method without discovery
         }
+
+        /// Like [`create_and_init()`][Repository::create_and_init()], but with `options` to provide a template directory
+        /// and initial configuration values.
+        pub fn create_and_init_opts(
+            directory: impl AsRef<Path>,
+            options: &crate::init::Options,
+        ) -> Result<Self, crate::init::Error> {
+            crate::init::repository_opts(directory.as_ref(), options)?;
+            Ok(Repository::discover(directory).unwrap()) // TODO: a specialized method without discovery
+        }
     }
 }
 
 pub mod discover {
-    use crate::{path::discover, Repository};
+    use crate::{path, path::discover, Repository};
     use quick_error::quick_error;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
 
     quick_error! {
         #[derive(Debug)]
@@ -25,23 +35,74 @@ pub mod discover {
                 from()
                 source(err)
             }
+            ReadCommonDir(err: std::io::Error, path: PathBuf) {
+                display("Could not read the common directory of a linked working tree from '{}'", path.display())
+                source(err)
+            }
+            Canonicalize(err: std::io::Error, path: PathBuf) {
+                display("Could not make '{}' absolute", path.display())
+                source(err)
+            }
             ObjectStoreInitialization(err: git_odb::linked::init::Error) {
                 display("Could not initialize the object database")
                 from()
                 source(err)
             }
+            Replace(err: crate::replace::Error) {
+                display("Could not read the object replacements")
+                from()
+                source(err)
+            }
         }
     }
 
+    /// Options to configure [`Repository::discover_opts()`].
+    #[derive(Default, Debug, Clone, Eq, PartialEq)]
+    pub struct Options {
+        /// Configure how the repository is found.
+        pub existing: discover::existing::Options,
+        /// Determine how the paths of the discovered repository are represented.
+        pub canonicalization: path::Canonicalization,
+    }
+
     impl Repository {
+        /// Find a repository in `directory` or any of its parents, keeping its paths as they were found.
         pub fn discover(directory: impl AsRef<Path>) -> Result<Self, Error> {
-            let path = discover::existing(directory)?;
+            Self::discover_opts(directory, &Options::default())
+        }
+
+        /// Like [`discover()`][Repository::discover()], but with `options` to configure how the repository is found and
+        /// how its paths are represented.
+        pub fn discover_opts(directory: impl AsRef<Path>, options: &Options) -> Result<Self, Error> {
+            let path = discover::existing_opts(directory, &options.existing)?;
             let (git_dir, working_tree) = match path {
                 crate::Path::WorkingTree(working_tree) => (working_tree.join(".git"), Some(working_tree)),
+                crate::Path::LinkedWorkingTree { working_tree, git_dir } => (git_dir, Some(working_tree)),
                 crate::Path::Repository(repository) => (repository, None),
             };
+            let common_dir = common_dir(&git_dir)?;
+            let (git_dir, working_tree, common_dir) = match options.canonicalization {
+                path::Canonicalization::KeepAsIs => (git_dir, working_tree, common_dir),
+                path::Canonicalization::Absolutize => (
+                    canonicalize(git_dir)?,
+                    working_tree.map(canonicalize).transpose()?,
+                    canonicalize(common_dir)?,
+                ),
+            };
+            let mut odb = git_odb::linked::Store::at(common_dir.join("objects"))?;
+            #[cfg(feature = "git-config")]
+            let config_error = match crate::config::delta_base_cache_limit(&common_dir) {
+                Ok(limit) => {
+                    if let Some(limit) = limit {
+                        odb.delta_base_cache_limit = limit;
+                    }
+                    None
+                }
+                Err(err) => Some(err),
+            };
+            odb.replacements = crate::replace::objects(&common_dir)?;
             Ok(Repository {
-                odb: git_odb::linked::Store::at(git_dir.join("objects"))?,
+                odb,
                 refs: git_ref::file::Store::at(
                     git_dir,
                     if working_tree.is_none() {
@@ -51,7 +112,27 @@ pub mod discover {
                     },
                 ),
                 working_tree,
+                common_dir,
+                #[cfg(feature = "git-config")]
+                config_error,
             })
         }
     }
+
+    /// Read the `commondir` file of linked working trees, or return `git_dir` if there is none.
+    fn common_dir(git_dir: &Path) -> Result<PathBuf, Error> {
+        let path = git_dir.join("commondir");
+        match std::fs::read(&path) {
+            Ok(content) => {
+                let relative_or_absolute = String::from_utf8_lossy(&content);
+                Ok(git_dir.join(relative_or_absolute.trim_end()))
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(git_dir.to_owned()),
+            Err(err) => Err(Error::ReadCommonDir(err, path)),
+        }
+    }
+
+    fn canonicalize(path: PathBuf) -> Result<PathBuf, Error> {
+        path.canonicalize().map_err(|err| Error::Canonicalize(err, path))
+    }
 }
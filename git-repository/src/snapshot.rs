@@ -0,0 +1,231 @@
+//! A consistent view on the references and objects of a repository, see [`Snapshot`].
+use crate::Repository;
+use git_ref::mutable::{FullName, Target};
+use quick_error::quick_error;
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Io(err: std::io::Error, path: PathBuf) {
+            display("Could not access '{}'", path.display())
+            source(err)
+        }
+        LooseReference(err: git_ref::file::iter::loose::Error) {
+            display("A loose reference could not be read")
+            from()
+            source(err)
+        }
+        ReadHead(err: git_ref::file::find_one::Error) {
+            display("The HEAD reference could not be read")
+            from()
+            source(err)
+        }
+        PackedRefsOpen(err: git_ref::packed::buffer::open::Error) {
+            display("The packed-refs file could not be opened")
+            from()
+            source(err)
+        }
+        PackedRefsIter(err: git_ref::packed::iter::Error) {
+            display("The packed-refs file could not be read")
+            from()
+            source(err)
+        }
+        PackedRefsFind(err: git_ref::packed::find::Error) {
+            display("A reference could not be found in the packed-refs file")
+            from()
+            source(err)
+        }
+        RefName(err: git_validate::refname::Error) {
+            display("The reference name is invalid")
+            from()
+            source(err)
+        }
+        ObjectStoreInitialization(err: git_odb::linked::init::Error) {
+            display("Could not initialize the object database")
+            from()
+            source(err)
+        }
+        Unstable {
+            display("References kept changing while the snapshot was taken")
+        }
+    }
+}
+
+/// The amount of times we try to obtain references that didn't change while opening the object database.
+const MAX_ATTEMPTS: usize = 5;
+/// The maximum amount of symbolic references to follow when peeling.
+const MAX_SYMBOLIC_DEPTH: usize = 5;
+
+/// A consistent view on `HEAD`, all references and the object database of a repository as they were at a single point in time.
+///
+/// It is meant for multi-step read operations, like rendering a page about a repository, which shouldn't observe references
+/// pointing to objects that were removed by a concurrent garbage collection in the mean time. All references are read
+/// when the snapshot is taken and the object database is opened afterwards, which keeps the packs it sees accessible even if
+/// they are deleted later.
+///
+/// Use [`is_stale()`][Snapshot::is_stale()] to learn if the repository changed since and a new snapshot should be taken.
+pub struct Snapshot {
+    /// The object database as seen when the snapshot was taken.
+    ///
+    /// _Note_ that loose objects are read from disk when accessed and may thus disappear, which is why `git gc` will only delete
+    /// unreachable loose objects after a grace period.
+    pub odb: git_odb::linked::Store,
+    packed: Option<git_ref::packed::Buffer>,
+    state: State,
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+}
+
+/// Everything we compare to determine if a snapshot is stale.
+#[derive(PartialEq, Eq)]
+struct State {
+    head: Option<Target>,
+    loose: BTreeMap<FullName, Target>,
+    packed_refs_modified: Option<(SystemTime, u64)>,
+    pack_indices: Vec<PathBuf>,
+}
+
+impl State {
+    fn capture(git_dir: &Path, common_dir: &Path, odb: &git_odb::linked::Store) -> Result<Self, Error> {
+        let head = git_ref::file::Store::at(git_dir, git_ref::file::WriteReflog::Disable)
+            .find_one("HEAD")?
+            .map(|head| head.into_target());
+        let refs = git_ref::file::Store::at(common_dir, git_ref::file::WriteReflog::Disable);
+        let mut loose = BTreeMap::new();
+        if common_dir.join("refs").is_dir() {
+            let iter = refs
+                .loose_iter()
+                .map_err(|err| Error::Io(err, common_dir.join("refs")))?;
+            for reference in iter {
+                let reference = reference?;
+                loose.insert(reference.name(), reference.into_target());
+            }
+        }
+        let packed_refs_path = refs.packed_refs_path();
+        let packed_refs_modified = match std::fs::metadata(&packed_refs_path) {
+            Ok(metadata) => Some((
+                metadata
+                    .modified()
+                    .map_err(|err| Error::Io(err, packed_refs_path.clone()))?,
+                metadata.len(),
+            )),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(Error::Io(err, packed_refs_path)),
+        };
+        let mut pack_indices = Vec::new();
+        for db in &odb.dbs {
+            let pack_dir = db.loose.path.join("pack");
+            let entries = match std::fs::read_dir(&pack_dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(Error::Io(err, pack_dir)),
+            };
+            for entry in entries {
+                let path = entry.map_err(|err| Error::Io(err, pack_dir.clone()))?.path();
+                if path.extension().map_or(false, |ext| ext == "idx") {
+                    pack_indices.push(path);
+                }
+            }
+        }
+        pack_indices.sort();
+        Ok(State {
+            head,
+            loose,
+            packed_refs_modified,
+            pack_indices,
+        })
+    }
+}
+
+impl Repository {
+    /// Take a [`Snapshot`] of our references and objects for a consistent view on them, for example to perform multiple read
+    /// operations while other processes may modify the repository.
+    ///
+    /// The object database of the snapshot is configured like ours.
+    pub fn snapshot(&self) -> Result<Snapshot, Error> {
+        let git_dir = self.git_dir().to_owned();
+        let common_dir = self.common_dir().to_owned();
+        for _ in 0..MAX_ATTEMPTS {
+            let state = State::capture(&git_dir, &common_dir, &self.odb)?;
+            let packed = git_ref::file::Store::at(&common_dir, git_ref::file::WriteReflog::Disable).packed()?;
+            let mut odb = git_odb::linked::Store::at(self.objects_dir())?;
+            odb.delta_base_cache_limit = self.odb.delta_base_cache_limit;
+            odb.replacements = self.odb.replacements.clone();
+
+            let snapshot = Snapshot {
+                odb,
+                packed,
+                state,
+                git_dir: git_dir.clone(),
+                common_dir: common_dir.clone(),
+            };
+            // References may have been changed by a concurrent gc while the packs were opened, in which case
+            // they might point to objects in packs we don't see.
+            if !snapshot.is_stale()? {
+                return Ok(snapshot);
+            }
+        }
+        Err(Error::Unstable)
+    }
+}
+
+impl Snapshot {
+    /// Return true if any reference, `HEAD`, the `packed-refs` file or the list of packs changed since the snapshot was taken.
+    pub fn is_stale(&self) -> Result<bool, Error> {
+        Ok(State::capture(&self.git_dir, &self.common_dir, &self.odb)? != self.state)
+    }
+
+    /// The target of `HEAD` at the time the snapshot was taken, or `None` if there was no `HEAD`.
+    pub fn head(&self) -> Option<&Target> {
+        self.state.head.as_ref()
+    }
+
+    /// Return the object `HEAD` was pointing to at the time the snapshot was taken, following symbolic references, or `None`
+    /// if there is no such object like in a repository without commits.
+    pub fn head_id(&self) -> Result<Option<git_hash::ObjectId>, Error> {
+        let mut target = match self.head() {
+            Some(target) => target.clone(),
+            None => return Ok(None),
+        };
+        for _ in 0..MAX_SYMBOLIC_DEPTH {
+            target = match target {
+                Target::Peeled(id) => return Ok(Some(id)),
+                Target::Symbolic(name) => match self.find_reference(&name.as_ref().to_string())? {
+                    Some(target) => target,
+                    None => return Ok(None),
+                },
+            };
+        }
+        Ok(None)
+    }
+
+    /// Return the target of the reference with the given `full_name`, like `refs/heads/main`, as it was when the snapshot was taken.
+    pub fn find_reference(&self, full_name: &str) -> Result<Option<Target>, Error> {
+        if let Some(target) = self.state.loose.get(&FullName::try_from(full_name)?) {
+            return Ok(Some(target.clone()));
+        }
+        Ok(match &self.packed {
+            Some(packed) => packed.find(full_name)?.map(|r| Target::Peeled(r.target())),
+            None => None,
+        })
+    }
+
+    /// Return the names and targets of all references as they were when the snapshot was taken, sorted by name.
+    pub fn references(&self) -> Result<Vec<(FullName, Target)>, Error> {
+        let mut out = self.state.loose.clone();
+        if let Some(packed) = &self.packed {
+            for reference in packed.iter()? {
+                let reference = reference?;
+                let name = FullName::try_from(reference.full_name)?;
+                out.entry(name).or_insert_with(|| Target::Peeled(reference.target()));
+            }
+        }
+        Ok(out.into_iter().collect())
+    }
+}
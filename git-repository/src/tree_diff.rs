@@ -0,0 +1,299 @@
+//! Compute the changes between the trees of two revisions, see [`Repository::diff_tree_to_tree()`].
+use crate::Repository;
+use git_diff::tree::{recorder, rewrites};
+use git_hash::ObjectId;
+use git_object::{bstr::BString, immutable, tree::EntryMode, Kind};
+use git_odb::{pack, FindExt};
+use git_ref::mutable::Target;
+use quick_error::quick_error;
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        FindReference(err: git_ref::file::find_one::Error) {
+            display("A reference could not be read")
+            from()
+            source(err)
+        }
+        PackedRefsOpen(err: git_ref::packed::buffer::open::Error) {
+            display("The packed-refs file could not be opened")
+            from()
+            source(err)
+        }
+        PackedRefsFind(err: git_ref::packed::find::Error) {
+            display("A reference could not be found in the packed-refs file")
+            from()
+            source(err)
+        }
+        RevSpec { spec: String } {
+            display("The revision '{}' is neither a full object id nor the name of a reference", spec)
+        }
+        FindObject(err: git_odb::pack::find::existing::Error<git_odb::compound::find::Error>) {
+            display("An object could not be found")
+            from()
+            source(err)
+        }
+        NotATree { spec: String, kind: Kind } {
+            display("The revision '{}' points to a {} which cannot be peeled to a tree", spec, kind)
+        }
+        Decode { spec: String, kind: Kind } {
+            display("The {} the revision '{}' points to could not be decoded", kind, spec)
+        }
+        Diff(err: git_diff::tree::changes::Error) {
+            display("The trees could not be compared")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// The maximum amount of symbolic references to follow when resolving a revision.
+const MAX_SYMBOLIC_DEPTH: usize = 5;
+
+/// Configure [`Repository::diff_tree_to_tree()`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+pub struct Options {
+    /// If true, pairs of deleted and added files with the same content and kind are reported as [renames][Change::Rename].
+    ///
+    /// Only exact renames are detected, files which were renamed and modified at the same time are reported as deletion
+    /// and addition.
+    pub track_renames: bool,
+    /// If not empty, only changes to files at or below one of these paths are reported, like `src` or `src/lib.rs`.
+    ///
+    /// Paths are relative to the root of the tree and use slashes as separators, without trailing slashes.
+    pub pathspecs: Vec<BString>,
+}
+
+/// A change to a single file between two trees as returned by [`Repository::diff_tree_to_tree()`].
+///
+/// Changes to directories are not reported, but changes to all files they contain are.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub enum Change {
+    /// The file at `path` was added.
+    Addition {
+        /// The path of the file relative to the root of the tree.
+        path: BString,
+        /// The mode of the added file.
+        entry_mode: EntryMode,
+        /// The id of the added file's content.
+        id: ObjectId,
+    },
+    /// The file at `path` was deleted.
+    Deletion {
+        /// The path of the file relative to the root of the tree.
+        path: BString,
+        /// The mode of the deleted file.
+        entry_mode: EntryMode,
+        /// The id of the deleted file's content.
+        id: ObjectId,
+    },
+    /// The content or mode of the file at `path` changed.
+    Modification {
+        /// The path of the file relative to the root of the tree.
+        path: BString,
+        /// The mode of the file before the change.
+        previous_entry_mode: EntryMode,
+        /// The id of the file's content before the change.
+        previous_id: ObjectId,
+        /// The mode of the file after the change.
+        entry_mode: EntryMode,
+        /// The id of the file's content after the change.
+        id: ObjectId,
+    },
+    /// The file at `source_path` was moved to `path` without changing its content, which is only reported if
+    /// [renames are tracked][Options::track_renames].
+    Rename {
+        /// The path of the file before it was moved.
+        source_path: BString,
+        /// The path of the file after it was moved.
+        path: BString,
+        /// The mode of the file after it was moved.
+        entry_mode: EntryMode,
+        /// The id of the file's content, which is the same before and after the move.
+        id: ObjectId,
+    },
+}
+
+impl Change {
+    /// The path of the file affected by this change, which is its new path in case of a [rename][Change::Rename].
+    pub fn path(&self) -> &BString {
+        match self {
+            Change::Addition { path, .. }
+            | Change::Deletion { path, .. }
+            | Change::Modification { path, .. }
+            | Change::Rename { path, .. } => path,
+        }
+    }
+}
+
+/// The changes returned by [`Repository::diff_tree_to_tree()`], sorted by path.
+pub type Changes = std::vec::IntoIter<Change>;
+
+impl Repository {
+    /// Return the changes of all files needed to turn the tree of the revision `from` into the one of the revision `to`,
+    /// configured by `options`, similar to `git diff-tree -r <from> <to>`.
+    ///
+    /// Revisions are full hexadecimal object ids or the names of references, like `HEAD`, `main` or `refs/tags/v1.0`,
+    /// which can point to commits, trees or tags pointing to either.
+    pub fn diff_tree_to_tree(&self, from: &str, to: &str, options: &Options) -> Result<Changes, Error> {
+        let mut from_buf = Vec::new();
+        let mut to_buf = Vec::new();
+        let from_tree = self.find_tree(from, &mut from_buf)?;
+        let to_tree = self.find_tree(to, &mut to_buf)?;
+
+        let mut recorder = git_diff::tree::Recorder::default();
+        git_diff::tree::Changes::from(Some(from_tree))
+            .with_pathspecs(options.pathspecs.iter().cloned())
+            .needed_to_obtain(
+                to_tree,
+                git_diff::tree::State::default(),
+                |oid, buf| self.odb.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok(),
+                &mut recorder,
+            )?;
+
+        // Directories are represented by the changes of the files they contain.
+        let is_file_change = |change: &recorder::Change| match change {
+            recorder::Change::Addition { entry_mode, .. } | recorder::Change::Deletion { entry_mode, .. } => {
+                entry_mode.is_no_tree()
+            }
+            recorder::Change::Modification {
+                previous_entry_mode,
+                entry_mode,
+                ..
+            } => previous_entry_mode.is_no_tree() && entry_mode.is_no_tree(),
+            recorder::Change::Rename { .. } | recorder::Change::Copy { .. } => {
+                unreachable!("the recorder never tracks rewrites")
+            }
+        };
+        let mut changes: Vec<_> = recorder.records.into_iter().filter(is_file_change).collect();
+        if options.track_renames {
+            changes = rewrites::detect(
+                changes,
+                rewrites::Options {
+                    percentage: 100,
+                    ..Default::default()
+                },
+                |oid, buf| {
+                    self.odb
+                        .find_existing(oid, buf, &mut pack::cache::Never)
+                        .ok()
+                        .map(|obj| obj.data)
+                },
+            );
+        }
+        let mut changes: Vec<_> = changes
+            .into_iter()
+            .map(|change| match change {
+                recorder::Change::Addition { entry_mode, oid, path } => Change::Addition {
+                    path,
+                    entry_mode,
+                    id: oid,
+                },
+                recorder::Change::Deletion { entry_mode, oid, path } => Change::Deletion {
+                    path,
+                    entry_mode,
+                    id: oid,
+                },
+                recorder::Change::Modification {
+                    previous_entry_mode,
+                    previous_oid,
+                    entry_mode,
+                    oid,
+                    path,
+                } => Change::Modification {
+                    path,
+                    previous_entry_mode,
+                    previous_id: previous_oid,
+                    entry_mode,
+                    id: oid,
+                },
+                recorder::Change::Rename {
+                    previous_path,
+                    entry_mode,
+                    oid,
+                    path,
+                    ..
+                } => Change::Rename {
+                    source_path: previous_path,
+                    path,
+                    entry_mode,
+                    id: oid,
+                },
+                recorder::Change::Copy { .. } => unreachable!("copies are not tracked"),
+            })
+            .collect();
+        changes.sort_by(|lhs, rhs| lhs.path().cmp(rhs.path()));
+        Ok(changes.into_iter())
+    }
+
+    /// Resolve `spec` to a tree, placing its data into `buf`.
+    fn find_tree<'a>(&self, spec: &str, buf: &'a mut Vec<u8>) -> Result<immutable::TreeIter<'a>, Error> {
+        let mut id = self.resolve_revision(spec)?;
+        loop {
+            let object = self.odb.find_existing(id, buf, &mut pack::cache::Never)?;
+            id = match object.kind {
+                Kind::Tree => break,
+                Kind::Commit => immutable::CommitIter::from_bytes(object.data).tree_id(),
+                Kind::Tag => immutable::TagIter::from_bytes(object.data).target_id(),
+                Kind::Blob => {
+                    return Err(Error::NotATree {
+                        spec: spec.to_owned(),
+                        kind: object.kind,
+                    })
+                }
+            }
+            .ok_or_else(|| Error::Decode {
+                spec: spec.to_owned(),
+                kind: object.kind,
+            })?;
+        }
+        Ok(immutable::TreeIter::from_bytes(buf))
+    }
+
+    /// Resolve `spec` as full hexadecimal object id, or as reference name like git does, following symbolic references.
+    fn resolve_revision(&self, spec: &str) -> Result<ObjectId, Error> {
+        if spec.len() == 40 {
+            if let Ok(id) = ObjectId::from_hex(spec.as_bytes()) {
+                return Ok(id);
+            }
+        }
+        let packed = git_ref::file::Store::at(self.common_dir(), git_ref::file::WriteReflog::Disable).packed()?;
+        let mut name = spec.to_owned();
+        for _ in 0..MAX_SYMBOLIC_DEPTH {
+            let target = match self.refs.find_one(name.as_str())? {
+                Some(reference) => reference.into_target(),
+                None => match packed.as_ref() {
+                    Some(packed) => match packed_candidates(&name)
+                        .iter()
+                        .map(|candidate| packed.find(candidate.as_str()))
+                        .find_map(|found| found.transpose())
+                        .transpose()?
+                    {
+                        Some(reference) => Target::Peeled(reference.target()),
+                        None => break,
+                    },
+                    None => break,
+                },
+            };
+            match target {
+                Target::Peeled(id) => return Ok(id),
+                Target::Symbolic(full_name) => name = full_name.as_ref().to_string(),
+            }
+        }
+        Err(Error::RevSpec { spec: spec.to_owned() })
+    }
+}
+
+/// The full reference names git tries for `name` in order, following the rules of `git rev-parse`.
+fn packed_candidates(name: &str) -> Vec<String> {
+    if name.starts_with("refs/") {
+        return vec![name.to_owned()];
+    }
+    vec![
+        format!("refs/{}", name),
+        format!("refs/tags/{}", name),
+        format!("refs/heads/{}", name),
+        format!("refs/remotes/{}", name),
+        format!("refs/remotes/{}/HEAD", name),
+    ]
+}
@@ -0,0 +1,140 @@
+//! Find local branches whose configured upstream branch doesn't exist anymore, see [`Repository::gone_upstreams()`].
+use crate::{config, Repository};
+use quick_error::quick_error;
+use std::{collections::BTreeSet, path::Path};
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Io(err: std::io::Error) {
+            display("Loose references could not be listed")
+            from()
+            source(err)
+        }
+        LooseReference(err: git_ref::file::iter::loose::Error) {
+            display("A loose reference could not be read")
+            from()
+            source(err)
+        }
+        PackedRefsOpen(err: git_ref::packed::buffer::open::Error) {
+            display("The packed-refs file could not be opened")
+            from()
+            source(err)
+        }
+        PackedRefsIter(err: git_ref::packed::iter::Error) {
+            display("The packed-refs file could not be read")
+            from()
+            source(err)
+        }
+        Config(err: config::Error) {
+            display("The configuration could not be read")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// A local branch along with the upstream branch it is configured to track.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Upstream {
+    /// The full name of the local branch, like `refs/heads/feature`.
+    pub branch: String,
+    /// The remote as configured in `branch.<name>.remote`, which is `.` if the upstream is a local branch.
+    pub remote: String,
+    /// The name of the upstream branch on the remote as configured in `branch.<name>.merge`, like `refs/heads/main`.
+    pub merge: String,
+    /// The full name of the reference tracking the upstream branch locally, like `refs/remotes/origin/main`, as obtained by
+    /// mapping `merge` with the fetch refspecs of `remote`.
+    pub tracking_ref: String,
+}
+
+/// Return all local branches in the repository at `common_dir` whose upstream is configured, but whose tracking reference
+/// doesn't exist, sorted by branch name.
+///
+/// This is what `git branch -vv` shows as `gone`, which typically happens after the upstream branch was deleted on the remote
+/// and the remote-tracking reference was pruned when fetching, making the local branch a candidate for deletion.
+///
+/// Branches whose upstream can't be mapped to a tracking reference, for instance because the remote is a URL or none of its
+/// fetch refspecs match, are ignored.
+pub fn gone(common_dir: &Path) -> Result<Vec<Upstream>, Error> {
+    let references = reference_names(common_dir)?;
+    let mut buf = Vec::new();
+    let config = config::from_file(common_dir, &mut buf)?;
+    let value = |section: &str, subsection: &str, key: &str| {
+        config
+            .get_raw_value(section, Some(subsection), key)
+            .ok()
+            .map(|value| String::from_utf8_lossy(&value).into_owned())
+    };
+
+    let mut out = Vec::new();
+    for branch in references.iter().filter(|name| name.starts_with("refs/heads/")) {
+        let name = &branch["refs/heads/".len()..];
+        let (remote, merge) = match (value("branch", name, "remote"), value("branch", name, "merge")) {
+            (Some(remote), Some(merge)) => (remote, merge),
+            _ => continue,
+        };
+        let tracking_ref = if remote == "." {
+            Some(merge.clone())
+        } else {
+            config
+                .get_raw_multi_value("remote", Some(&remote), "fetch")
+                .unwrap_or_default()
+                .iter()
+                .find_map(|refspec| map_by_refspec(&String::from_utf8_lossy(refspec), &merge))
+        };
+        if let Some(tracking_ref) = tracking_ref {
+            if !references.contains(&tracking_ref) {
+                out.push(Upstream {
+                    branch: branch.to_owned(),
+                    remote,
+                    merge,
+                    tracking_ref,
+                });
+            }
+        }
+    }
+    Ok(out)
+}
+
+impl Repository {
+    /// Return all local branches whose configured upstream branch is gone, see [`gone()`] for details.
+    pub fn gone_upstreams(&self) -> Result<Vec<Upstream>, Error> {
+        gone(self.common_dir())
+    }
+}
+
+/// Return the full names of all loose and packed references.
+fn reference_names(common_dir: &Path) -> Result<BTreeSet<String>, Error> {
+    let mut out = BTreeSet::new();
+    let refs = git_ref::file::Store::at(common_dir, git_ref::file::WriteReflog::Disable);
+    if let Some(packed) = refs.packed()? {
+        for reference in packed.iter()? {
+            out.insert(reference?.full_name.to_string());
+        }
+    }
+    if common_dir.join("refs").is_dir() {
+        for reference in refs.loose_iter()? {
+            out.insert(reference?.relative_path().to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(out)
+}
+
+/// Map the reference `name` on the remote to its local name using a fetch `refspec` like `+refs/heads/*:refs/remotes/origin/*`,
+/// or return `None` if the refspec doesn't match.
+fn map_by_refspec(refspec: &str, name: &str) -> Option<String> {
+    let refspec = refspec.trim();
+    if refspec.starts_with('^') {
+        return None;
+    }
+    let (src, dst) = refspec.trim_start_matches('+').split_once(':')?;
+    match (src.split_once('*'), dst.split_once('*')) {
+        (Some((src_prefix, src_suffix)), Some((dst_prefix, dst_suffix))) => {
+            let matched = name.strip_prefix(src_prefix)?.strip_suffix(src_suffix)?;
+            Some(format!("{}{}{}", dst_prefix, matched, dst_suffix))
+        }
+        (None, None) if src == name && !dst.is_empty() => Some(dst.to_owned()),
+        _ => None,
+    }
+}
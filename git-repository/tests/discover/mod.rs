@@ -67,6 +67,99 @@ mod existing {
         Ok(())
     }
 
+    mod git_dir_file {
+        use git_repository::{path::discover, Kind};
+
+        #[test]
+        fn points_to_the_git_dir() -> crate::Result {
+            let working_tree = super::repo_path()?.join("linked-working-tree");
+            let path = discover::existing(&working_tree)?;
+            assert_eq!(path.kind(), Kind::WorkingTree);
+            assert_eq!(path.as_ref(), working_tree);
+            assert_eq!(
+                path.into_repository_directory(),
+                working_tree.join("../.git"),
+                "the git dir is relative to the .git file"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn pointing_to_a_missing_git_dir_is_an_error_with_both_paths() -> crate::Result {
+            let dir = super::repo_path()?.join("dangling-git-dir-file");
+            match discover::existing(dir.join("subdir")) {
+                Err(discover::existing::Error::InvalidGitDir {
+                    dot_git_file, git_dir, ..
+                }) => {
+                    assert_eq!(dot_git_file, dir.join(".git"));
+                    assert_eq!(git_dir, dir.join("../does-not-exist"));
+                }
+                res => panic!("expected InvalidGitDir error, got {:?}", res),
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn pointing_to_a_directory_that_is_no_git_dir_is_an_error() -> crate::Result {
+            let dir = super::repo_path()?.join("git-dir-file-to-non-repository");
+            match discover::existing(&dir) {
+                Err(discover::existing::Error::InvalidGitDir { git_dir, .. }) => {
+                    assert_eq!(git_dir, dir.join("../some"));
+                }
+                res => panic!("expected InvalidGitDir error, got {:?}", res),
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn points_to_the_private_git_dir_of_linked_working_trees() -> crate::Result {
+            let working_tree = super::repo_path()?.join("worktree");
+            let path = discover::existing(&working_tree)?;
+            assert_eq!(path.kind(), Kind::WorkingTree);
+            assert!(matches!(path, git_repository::Path::LinkedWorkingTree { .. }));
+            Ok(())
+        }
+
+        #[test]
+        fn pointing_to_a_missing_git_dir_can_be_tolerated() -> crate::Result {
+            let repo = super::repo_path()?;
+            let path = discover::existing_opts(
+                repo.join("dangling-git-dir-file").join("subdir"),
+                &discover::existing::Options {
+                    tolerate_invalid_git_dir_file: true,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(path.kind(), Kind::WorkingTree);
+            assert_eq!(path.as_ref(), repo, "the search continues in the parent directory");
+            Ok(())
+        }
+
+        #[test]
+        fn pointing_outside_of_allowed_roots_is_an_error_unless_tolerated() -> crate::Result {
+            let repo = super::repo_path()?;
+            let working_tree = repo.join("linked-working-tree");
+            let mut options = discover::existing::Options {
+                tolerate_invalid_git_dir_file: false,
+                allowed_git_dir_roots: vec![working_tree.clone()],
+            };
+            assert!(matches!(
+                discover::existing_opts(&working_tree, &options),
+                Err(discover::existing::Error::GitDirOutsideAllowedRoots { .. })
+            ));
+
+            options.tolerate_invalid_git_dir_file = true;
+            assert_eq!(discover::existing_opts(&working_tree, &options)?.as_ref(), repo);
+
+            options.allowed_git_dir_roots = vec![repo];
+            assert!(matches!(
+                discover::existing_opts(&working_tree, &options)?,
+                git_repository::Path::LinkedWorkingTree { .. }
+            ));
+            Ok(())
+        }
+    }
+
     fn repo_path() -> crate::Result<PathBuf> {
         git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")
     }
@@ -12,3 +12,100 @@ git commit -q -m c1
 mkdir -p some/very/deeply/nested/subdir
 
 git init --bare bare.git
+
+mkdir -p linked-working-tree dangling-git-dir-file/subdir git-dir-file-to-non-repository
+echo "gitdir: ../.git" > linked-working-tree/.git
+echo "gitdir: ../does-not-exist" > dangling-git-dir-file/.git
+echo "gitdir: ../some" > git-dir-file-to-non-repository/.git
+
+git worktree add -q --detach worktree
+
+git init -q --bare bare-with-config.git
+git -C bare-with-config.git config core.deltaBaseCacheLimit 2m
+
+git init -q --bare bare-with-broken-config.git
+echo "[core" >> bare-with-broken-config.git/config
+
+git init -q with-replacements
+(cd with-replacements
+  git config commit.gpgsign false
+  git commit -q --allow-empty -m "packed original"
+  git commit -q --allow-empty -m "packed replacement"
+  git replace HEAD~1 HEAD
+  git rev-parse HEAD~1 > ../packed-original-id
+  git pack-refs --all
+
+  git commit -q --allow-empty -m "loose original"
+  git commit -q --allow-empty -m "loose replacement"
+  git replace HEAD~1 HEAD
+  git rev-parse HEAD~1 > ../loose-original-id
+)
+
+git init -q prunable
+(cd prunable
+  git config commit.gpgsign false
+  git commit -q --allow-empty -m "only in reflog"
+  git commit -q --amend --allow-empty -m "on branch"
+  echo staged > staged && git add staged
+  echo unreachable | git hash-object -w --stdin > ../unreachable-id
+)
+
+git init -q prunable-with-worktree
+(cd prunable-with-worktree
+  git config commit.gpgsign false
+  git commit -q --allow-empty -m "on branch"
+  echo only-in-shared-index > shared && git add shared
+  git update-index --split-index
+  git worktree add -q --detach ../prunable-worktree
+  (cd ../prunable-worktree
+    git update-ref refs/bisect/bad "$(git commit-tree -m "only in worktree ref" "$(git write-tree)")"
+  )
+  echo unreachable-with-worktree | git hash-object -w --stdin > ../unreachable-with-worktree-id
+)
+
+git init -q with-gone-upstreams
+(cd with-gone-upstreams
+  git config commit.gpgsign false
+  git commit -q --allow-empty -m "init"
+  git config remote.origin.url https://example.com/repo.git
+  git config remote.origin.fetch '+refs/heads/*:refs/remotes/origin/*'
+  git update-ref refs/remotes/origin/packed HEAD
+  git pack-refs --all
+  git update-ref refs/remotes/origin/loose HEAD
+  for branch in packed loose gone local-gone by-url untracked; do
+    git branch $branch
+  done
+  git config branch.packed.remote origin && git config branch.packed.merge refs/heads/packed
+  git config branch.loose.remote origin && git config branch.loose.merge refs/heads/loose
+  git config branch.gone.remote origin && git config branch.gone.merge refs/heads/gone
+  git config branch.local-gone.remote . && git config branch.local-gone.merge refs/heads/deleted
+  git config branch.by-url.remote https://example.com/repo.git && git config branch.by-url.merge refs/heads/gone
+)
+
+git init -q with-tree-changes
+(cd with-tree-changes
+  git config commit.gpgsign false
+  mkdir -p src/sub docs
+  echo main > src/main.rs && echo lib > src/lib.rs && echo nested > src/sub/mod.rs && echo readme > docs/README
+  echo file-becomes-dir > becomes-dir
+  git add . && git commit -q -m "before"
+  git tag -a -m "annotated" before
+  git pack-refs --all
+
+  echo changed > src/main.rs
+  git mv src/lib.rs src/renamed.rs
+  git rm -q docs/README
+  rm becomes-dir && mkdir becomes-dir && echo inside > becomes-dir/file
+  echo new > added
+  chmod +x src/sub/mod.rs
+  git add . && git commit -q -m "after"
+)
+
+git init -q with-blobs
+(cd with-blobs
+  echo content | git hash-object -w --stdin > ../blob-id
+  echo other | git hash-object -w --stdin > ../corrupt-blob-id
+  printf target | git hash-object -w --stdin > ../link-blob-id
+  blob=$(cat ../blob-id) corrupt=$(cat ../corrupt-blob-id)
+  cp -f .git/objects/${blob:0:2}/${blob:2} .git/objects/${corrupt:0:2}/${corrupt:2}
+)
@@ -0,0 +1,46 @@
+use git_repository::fs::{policy_from_config, Capabilities, CheckStat, Policy};
+
+#[test]
+fn policy_falls_back_to_the_given_capabilities_if_nothing_is_configured() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let capabilities = Capabilities {
+        executable_bit: false,
+        symlink: true,
+        ignore_case: true,
+    };
+    assert_eq!(
+        policy_from_config(dir.path(), capabilities)?,
+        Policy {
+            capabilities,
+            ..Policy::default()
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn policy_is_read_from_the_configuration() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(
+        dir.path().join("config"),
+        "[core]\n\tfilemode = false\n\tsymlinks = false\n\tignorecase = true\n\ttrustctime = false\n\tcheckStat = minimal\n",
+    )?;
+    let policy = policy_from_config(dir.path(), Capabilities::default())?;
+    assert_eq!(
+        policy.capabilities,
+        Capabilities {
+            executable_bit: false,
+            symlink: false,
+            ignore_case: true
+        }
+    );
+    assert!(!policy.trust_ctime);
+    assert_eq!(policy.check_stat, CheckStat::Minimal);
+
+    std::fs::write(dir.path().join("config"), "[core]\n\tcheckStat = sometimes\n")?;
+    assert!(
+        policy_from_config(dir.path(), Capabilities::default()).is_err(),
+        "invalid values are an error"
+    );
+    Ok(())
+}
@@ -1,3 +1,5 @@
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 mod discover;
+mod fs;
+mod repository;
@@ -0,0 +1,565 @@
+mod discover {
+    use git_repository::{path::Canonicalization, repository::discover::Options, Repository};
+    use std::path::PathBuf;
+
+    #[test]
+    fn paths_are_kept_as_is_by_default() -> crate::Result {
+        let repo_path = repo_path()?;
+        let repo = Repository::discover(repo_path.join("linked-working-tree"))?;
+        assert_eq!(repo.work_dir(), Some(repo_path.join("linked-working-tree").as_ref()));
+        assert_eq!(repo.git_dir(), repo_path.join("linked-working-tree").join("../.git"));
+        assert_eq!(repo.common_dir(), repo.git_dir(), "there is no commondir file");
+        assert_eq!(repo.objects_dir(), repo.common_dir().join("objects"));
+        Ok(())
+    }
+
+    #[test]
+    fn paths_can_be_absolutized() -> crate::Result {
+        let repo_path = repo_path()?.canonicalize()?;
+        let repo = Repository::discover_opts(
+            repo_path.join("linked-working-tree"),
+            &Options {
+                canonicalization: Canonicalization::Absolutize,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(repo.work_dir(), Some(repo_path.join("linked-working-tree").as_ref()));
+        assert_eq!(repo.git_dir(), repo_path.join(".git"), "'..' components are resolved");
+        assert_eq!(repo.common_dir(), repo.git_dir());
+        assert_eq!(repo.objects_dir(), repo_path.join(".git").join("objects"));
+        Ok(())
+    }
+
+    #[test]
+    fn bare_repositories_have_no_work_dir() -> crate::Result {
+        let repo = Repository::discover(repo_path()?.join("bare.git"))?;
+        assert_eq!(repo.work_dir(), None);
+        assert_eq!(repo.common_dir(), repo.git_dir());
+        Ok(())
+    }
+
+    #[test]
+    fn linked_working_trees_use_the_objects_of_the_common_dir() -> crate::Result {
+        let repo_path = repo_path()?.canonicalize()?;
+        let repo = Repository::discover_opts(
+            repo_path.join("worktree"),
+            &Options {
+                canonicalization: Canonicalization::Absolutize,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(repo.work_dir(), Some(repo_path.join("worktree").as_ref()));
+        assert_eq!(
+            repo.git_dir(),
+            repo_path.join(".git").join("worktrees").join("worktree")
+        );
+        assert_eq!(repo.common_dir(), repo_path.join(".git"));
+        assert_eq!(repo.objects_dir(), repo_path.join(".git").join("objects"));
+        Ok(())
+    }
+
+    #[test]
+    fn configuration_errors_are_reported_separately_and_defaults_are_used() -> crate::Result {
+        let repo = Repository::discover(repo_path()?.join("bare-with-broken-config.git"))?;
+        assert!(matches!(
+            repo.config_error(),
+            Some(git_repository::config::Error::Parse(..))
+        ));
+        assert_eq!(
+            repo.odb.delta_base_cache_limit,
+            git_repository::odb::linked::DEFAULT_DELTA_BASE_CACHE_LIMIT
+        );
+
+        assert!(Repository::discover(repo_path()?)?.config_error().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn the_delta_base_cache_limit_is_read_from_the_configuration() -> crate::Result {
+        let repo = Repository::discover(repo_path()?)?;
+        assert_eq!(
+            repo.odb.delta_base_cache_limit,
+            git_repository::odb::linked::DEFAULT_DELTA_BASE_CACHE_LIMIT,
+            "the default is used if nothing is configured"
+        );
+
+        let repo = Repository::discover(repo_path()?.join("bare-with-config.git"))?;
+        assert_eq!(repo.odb.delta_base_cache_limit, 2 * 1024 * 1024);
+
+        let mut cache = repo.odb.new_pack_cache();
+        assert_eq!(cache.memory_usage_in_bytes(), 0);
+        git_repository::odb::pack::cache::DecodeEntry::put(
+            &mut cache,
+            0,
+            0,
+            b"data",
+            git_repository::object::Kind::Blob,
+            4,
+        );
+        assert_eq!(cache.memory_usage_in_bytes(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn objects_are_replaced_according_to_packed_and_loose_replace_refs() -> crate::Result {
+        use git_repository::prelude::FindExt;
+
+        let repo_path = repo_path()?;
+        let repo = Repository::discover(repo_path.join("with-replacements"))?;
+        assert_eq!(repo.odb.replacements.len(), 2);
+        let mut buf = Vec::new();
+        for (id_file, expected_message) in &[
+            ("packed-original-id", "packed replacement"),
+            ("loose-original-id", "loose replacement"),
+        ] {
+            let hex_id = std::fs::read_to_string(repo_path.join(id_file))?;
+            let id = git_repository::hash::ObjectId::from_hex(hex_id.trim_end().as_bytes())?;
+            let commit = repo
+                .odb
+                .find_existing_commit(id, &mut buf, &mut git_repository::odb::pack::cache::Never)?;
+            assert_eq!(commit.message.to_string(), format!("{}\n", expected_message));
+        }
+        Ok(())
+    }
+
+    fn repo_path() -> crate::Result<PathBuf> {
+        git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")
+    }
+}
+
+mod snapshot {
+    use git_repository::{refs::mutable::Target, Repository};
+
+    #[test]
+    fn references_are_seen_as_they_were_and_changes_make_it_stale() -> crate::Result {
+        let tmp = git_testtools::scripted_fixture_repo_writable("make_basic_repo.sh")?;
+        let repo = Repository::discover(tmp.path().join("with-replacements"))?;
+        let snapshot = repo.snapshot()?;
+        assert!(!snapshot.is_stale()?, "nothing changed yet");
+        assert!(
+            matches!(snapshot.head(), Some(Target::Symbolic(_))),
+            "HEAD points to a branch"
+        );
+        let head_id = snapshot.head_id()?.expect("HEAD points to a commit");
+        assert_eq!(
+            snapshot.references()?.len(),
+            3,
+            "one branch and two replacements, one of which is packed, with the packed branch being shadowed"
+        );
+
+        std::fs::write(repo.git_dir().join("refs/heads/new"), format!("{}\n", head_id))?;
+        assert!(snapshot.is_stale()?, "a reference was added");
+        assert_eq!(
+            snapshot.find_reference("refs/heads/new")?,
+            None,
+            "the snapshot doesn't see the new reference"
+        );
+        assert_eq!(
+            repo.snapshot()?.find_reference("refs/heads/new")?,
+            Some(Target::Peeled(head_id))
+        );
+        Ok(())
+    }
+}
+
+mod prune {
+    use git_repository::{odb::loose::prune::Options, Repository};
+    use std::time::Duration;
+
+    #[test]
+    fn objects_reachable_from_references_reflogs_or_the_index_are_kept() -> crate::Result {
+        let tmp = git_testtools::scripted_fixture_repo_writable("make_basic_repo.sh")?;
+        let repo = Repository::discover(tmp.path().join("prunable"))?;
+        let unreachable_hex = std::fs::read_to_string(tmp.path().join("unreachable-id"))?;
+        let unreachable = git_repository::hash::ObjectId::from_hex(unreachable_hex.trim_end().as_bytes())?;
+
+        let outcome = repo.prune_unreachable_objects(Options::default())?;
+        assert!(outcome.pruned.is_empty());
+        assert_eq!(
+            outcome.kept_recent,
+            vec![unreachable],
+            "recently written objects are protected by the grace period"
+        );
+
+        let no_grace_period = Options {
+            grace_period: Duration::default(),
+            dry_run: true,
+        };
+        let outcome = repo.prune_unreachable_objects(no_grace_period)?;
+        assert_eq!(
+            outcome.reachable_objects, 4,
+            "the amended commit from the reflog, the branch commit, their empty tree and the staged blob"
+        );
+        assert_eq!(outcome.pruned, vec![unreachable]);
+        assert!(
+            repo.odb.dbs[0].loose.contains(unreachable),
+            "nothing is deleted in dry-run mode"
+        );
+
+        let outcome = repo.prune_unreachable_objects(Options {
+            dry_run: false,
+            ..no_grace_period
+        })?;
+        assert_eq!(outcome.pruned, vec![unreachable]);
+        assert!(!repo.odb.dbs[0].loose.contains(unreachable));
+        assert_eq!(
+            repo.prune_unreachable_objects(no_grace_period)?.reachable_objects,
+            4,
+            "all reachable objects are still present"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn objects_reachable_from_worktree_references_or_a_shared_index_are_kept() -> crate::Result {
+        let tmp = git_testtools::scripted_fixture_repo_writable("make_basic_repo.sh")?;
+        let repo = Repository::discover(tmp.path().join("prunable-with-worktree"))?;
+        let unreachable_hex = std::fs::read_to_string(tmp.path().join("unreachable-with-worktree-id"))?;
+        let unreachable = git_repository::hash::ObjectId::from_hex(unreachable_hex.trim_end().as_bytes())?;
+        assert!(
+            std::fs::read_dir(repo.git_dir())?
+                .filter_map(Result::ok)
+                .any(|entry| entry.file_name().to_string_lossy().starts_with("sharedindex.")),
+            "the index is split"
+        );
+
+        let outcome = repo.prune_unreachable_objects(Options {
+            grace_period: Duration::default(),
+            dry_run: true,
+        })?;
+        assert_eq!(outcome.pruned, vec![unreachable]);
+        assert_eq!(
+            outcome.reachable_objects, 4,
+            "the branch commit, its empty tree, the blob in the shared index and the commit of the worktree reference"
+        );
+        Ok(())
+    }
+}
+
+mod upstream {
+    use git_repository::Repository;
+
+    #[test]
+    fn branches_whose_tracking_reference_is_missing_are_gone() -> crate::Result {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        let repo = Repository::discover(repo_path.join("with-gone-upstreams"))?;
+        let gone = repo.gone_upstreams()?;
+        assert_eq!(
+            gone.iter()
+                .map(|u| (u.branch.as_str(), u.remote.as_str(), u.tracking_ref.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("refs/heads/gone", "origin", "refs/remotes/origin/gone"),
+                ("refs/heads/local-gone", ".", "refs/heads/deleted"),
+            ],
+            "branches tracking packed or loose references are fine, and those without refspec or upstream are ignored"
+        );
+        Ok(())
+    }
+}
+
+mod diff_tree_to_tree {
+    use git_repository::{
+        tree_diff::{Change, Options},
+        Repository,
+    };
+
+    fn summary(changes: impl Iterator<Item = Change>) -> Vec<(char, String)> {
+        changes
+            .map(|change| match change {
+                Change::Addition { path, .. } => ('A', path.to_string()),
+                Change::Deletion { path, .. } => ('D', path.to_string()),
+                Change::Modification { path, .. } => ('M', path.to_string()),
+                Change::Rename { source_path, path, .. } => ('R', format!("{} -> {}", source_path, path)),
+            })
+            .collect()
+    }
+
+    fn repo() -> crate::Result<Repository> {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        Ok(Repository::discover(repo_path.join("with-tree-changes"))?)
+    }
+
+    #[test]
+    fn changes_to_files_are_returned_sorted_by_path() -> crate::Result {
+        let repo = repo()?;
+        let changes = repo.diff_tree_to_tree("before", "HEAD", &Options::default())?;
+        assert_eq!(
+            summary(changes),
+            vec![
+                ('A', "added".into()),
+                ('D', "becomes-dir".into()),
+                ('A', "becomes-dir/file".into()),
+                ('D', "docs/README".into()),
+                ('D', "src/lib.rs".into()),
+                ('M', "src/main.rs".into()),
+                ('A', "src/renamed.rs".into()),
+                ('M', "src/sub/mod.rs".into()),
+            ],
+            "the packed annotated tag is peeled to its tree, directories are only represented by their files and mode changes are modifications"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renames_can_be_tracked_and_paths_can_be_filtered() -> crate::Result {
+        let repo = repo()?;
+        let options = Options {
+            track_renames: true,
+            pathspecs: vec!["src".into(), "doc".into()],
+        };
+        assert_eq!(
+            summary(repo.diff_tree_to_tree("before", "HEAD", &options)?),
+            vec![
+                ('M', "src/main.rs".into()),
+                ('R', "src/lib.rs -> src/renamed.rs".into()),
+                ('M', "src/sub/mod.rs".into()),
+            ],
+            "'doc' is not a parent directory of 'docs/README'"
+        );
+        assert_eq!(
+            summary(repo.diff_tree_to_tree("HEAD", "HEAD", &options)?),
+            vec![],
+            "identical trees have no changes"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_revisions_cannot_be_diffed() -> crate::Result {
+        let repo = repo()?;
+        assert!(repo
+            .diff_tree_to_tree("does-not-exist", "HEAD", &Options::default())
+            .is_err());
+        Ok(())
+    }
+}
+
+mod checkout_blob {
+    use git_repository::{
+        checkout,
+        fs::{Capabilities, Policy},
+        hash::ObjectId,
+        object::tree::EntryMode,
+        Repository,
+    };
+    use std::path::PathBuf;
+
+    fn repo_and_id(id_file: &str) -> crate::Result<(Repository, ObjectId)> {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        let id = ObjectId::from_hex(std::fs::read_to_string(repo_path.join(id_file))?.trim().as_bytes())?;
+        Ok((Repository::discover(repo_path.join("with-blobs"))?, id))
+    }
+
+    fn target() -> crate::Result<(tempfile::TempDir, PathBuf)> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("file");
+        Ok((dir, path))
+    }
+
+    #[test]
+    fn blobs_are_written_to_new_and_existing_files() -> crate::Result {
+        let (repo, id) = repo_and_id("blob-id")?;
+        let (_dir, path) = target()?;
+        assert_eq!(repo.checkout_blob(id, &path, EntryMode::Blob, &Policy::default())?, 8);
+        assert_eq!(std::fs::read(&path)?, b"content\n");
+
+        std::fs::write(&path, b"previous content which is longer")?;
+        assert_eq!(
+            repo.checkout_blob(id, &path, EntryMode::BlobExecutable, &Policy::default())?,
+            8
+        );
+        assert_eq!(std::fs::read(&path)?, b"content\n", "existing files are replaced");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn files_get_the_default_permissions_and_executables_can_be_executed_by_those_who_can_read_them() -> crate::Result {
+        use std::os::unix::fs::PermissionsExt;
+        let (repo, id) = repo_and_id("blob-id")?;
+        let (dir, path) = target()?;
+        let mode = |path: &std::path::Path| -> std::io::Result<u32> {
+            Ok(std::fs::metadata(path)?.permissions().mode() & 0o777)
+        };
+        let default_path = dir.path().join("default");
+        std::fs::write(&default_path, b"")?;
+        let default_mode = mode(&default_path)?;
+
+        repo.checkout_blob(id, &path, EntryMode::Blob, &Policy::default())?;
+        assert_eq!(mode(&path)?, default_mode, "the umask is respected");
+        repo.checkout_blob(id, &path, EntryMode::BlobExecutable, &Policy::default())?;
+        assert_eq!(mode(&path)?, default_mode | ((default_mode & 0o444) >> 2));
+
+        let without_executable_bit = Policy {
+            capabilities: Capabilities {
+                executable_bit: false,
+                ..Capabilities::default()
+            },
+            ..Policy::default()
+        };
+        std::fs::remove_file(&path)?;
+        repo.checkout_blob(id, &path, EntryMode::BlobExecutable, &without_executable_bit)?;
+        assert_eq!(
+            mode(&path)?,
+            default_mode,
+            "executable bits are only set if the file system supports them"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn links_become_symlinks_if_the_file_system_supports_them_or_files_containing_the_target() -> crate::Result {
+        let (repo, id) = repo_and_id("link-blob-id")?;
+        let (_dir, path) = target()?;
+        std::fs::write(&path, b"previous content")?;
+        assert_eq!(repo.checkout_blob(id, &path, EntryMode::Link, &Policy::default())?, 6);
+        assert_eq!(std::fs::read_link(&path)?, std::path::Path::new("target"));
+
+        let without_symlinks = Policy {
+            capabilities: Capabilities {
+                symlink: false,
+                ..Capabilities::default()
+            },
+            ..Policy::default()
+        };
+        repo.checkout_blob(id, &path, EntryMode::Link, &without_symlinks)?;
+        assert!(std::fs::symlink_metadata(&path)?.file_type().is_file());
+        assert_eq!(std::fs::read(&path)?, b"target");
+        Ok(())
+    }
+
+    #[test]
+    fn blobs_whose_data_does_not_match_their_id_are_not_written() -> crate::Result {
+        let (repo, id) = repo_and_id("corrupt-blob-id")?;
+        let (dir, path) = target()?;
+        assert!(matches!(
+            repo.checkout_blob(id, &path, EntryMode::Blob, &Policy::default()),
+            Err(checkout::Error::HashMismatch { expected, .. }) if expected == id
+        ));
+        assert_eq!(
+            std::fs::read_dir(dir.path())?.count(),
+            0,
+            "neither the target nor the temporary file exist"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn only_blobs_can_be_checked_out() -> crate::Result {
+        let (repo, _) = repo_and_id("blob-id")?;
+        let (_dir, path) = target()?;
+        let empty_tree = ObjectId::from_hex(b"4b825dc642cb6eb9a060e54bf8d69288fbee4904")?;
+        assert!(matches!(
+            repo.checkout_blob(empty_tree, &path, EntryMode::Blob, &Policy::default()),
+            Err(checkout::Error::NotABlob { .. })
+        ));
+        assert!(matches!(
+            repo.checkout_blob(ObjectId::null_sha1(), &path, EntryMode::Blob, &Policy::default()),
+            Err(checkout::Error::NotFound { .. })
+        ));
+        assert!(matches!(
+            repo.checkout_blob(empty_tree, &path, EntryMode::Tree, &Policy::default()),
+            Err(checkout::Error::UnsupportedMode { mode: EntryMode::Tree })
+        ));
+        Ok(())
+    }
+}
+
+mod init {
+    use git_repository::{init, Repository};
+
+    #[test]
+    fn templates_replace_the_builtin_samples_and_configuration_values_are_appended() -> crate::Result {
+        let template = tempfile::tempdir()?;
+        std::fs::create_dir(template.path().join("hooks"))?;
+        std::fs::write(template.path().join("hooks").join("pre-commit"), b"#!/bin/sh\n")?;
+        std::fs::write(template.path().join("config"), b"[core]\n\trepositoryformatversion = 0")?;
+
+        let dir = tempfile::tempdir()?;
+        let repo = Repository::create_and_init_opts(
+            dir.path(),
+            &init::Options {
+                template_dir: Some(template.path().to_owned()),
+                config: vec![
+                    ("core.bare".into(), "false".into()),
+                    ("remote.origin.url".into(), "https://example.com/repo.git".into()),
+                    (
+                        "remote.origin.fetch".into(),
+                        "+refs/heads/*:refs/remotes/origin/*".into(),
+                    ),
+                    ("user.name".into(), " needs; \"quotes\"".into()),
+                ],
+            },
+        )?;
+
+        let git_dir = repo.git_dir();
+        assert!(git_dir.join("hooks").join("pre-commit").is_file());
+        assert!(
+            !git_dir.join("hooks").join("pre-commit.sample").exists(),
+            "built-in samples are not written if there is a template"
+        );
+        assert!(git_dir.join("refs").join("heads").is_dir() && git_dir.join("objects").join("pack").is_dir());
+        assert_eq!(
+            std::fs::read_to_string(git_dir.join("config"))?,
+            "[core]\n\trepositoryformatversion = 0\n\
+             [core]\n\tbare = false\n\
+             [remote \"origin\"]\n\turl = https://example.com/repo.git\n\tfetch = +refs/heads/*:refs/remotes/origin/*\n\
+             [user]\n\tname = \" needs; \\\"quotes\\\"\"\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlinks_in_templates_are_recreated_instead_of_followed() -> crate::Result {
+        let template = tempfile::tempdir()?;
+        std::fs::create_dir(template.path().join("hooks"))?;
+        std::fs::write(template.path().join("hooks").join("shared"), b"#!/bin/sh\n")?;
+        std::os::unix::fs::symlink("shared", template.path().join("hooks").join("pre-commit"))?;
+        std::os::unix::fs::symlink("hooks", template.path().join("shared-hooks"))?;
+
+        let dir = tempfile::tempdir()?;
+        let repo = Repository::create_and_init_opts(
+            dir.path(),
+            &init::Options {
+                template_dir: Some(template.path().to_owned()),
+                config: Vec::new(),
+            },
+        )?;
+
+        let git_dir = repo.git_dir();
+        assert_eq!(
+            std::fs::read_link(git_dir.join("hooks").join("pre-commit"))?,
+            std::path::Path::new("shared")
+        );
+        assert_eq!(
+            std::fs::read_link(git_dir.join("shared-hooks"))?,
+            std::path::Path::new("hooks"),
+            "links to directories aren't descended into either"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_configuration_keys_are_rejected() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        for key in &["bare", "core.", ".bare", "core.1bare", "core.ba_re"] {
+            let options = init::Options {
+                config: vec![((*key).into(), "value".into())],
+                ..Default::default()
+            };
+            assert!(
+                matches!(
+                    init::repository_opts(dir.path().join(key), &options),
+                    Err(init::Error::InvalidConfigKey(_))
+                ),
+                "{} is invalid",
+                key
+            );
+            assert!(!dir.path().join(key).exists(), "nothing is written if a key is invalid");
+        }
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
 };
 use async_trait::async_trait;
 use futures_io::{AsyncBufRead, AsyncRead};
+pub use git_packetline::read::ProgressAction;
 use std::{
     io,
     ops::{Deref, DerefMut},
@@ -12,7 +13,10 @@ use std::{
 /// A function `f(is_error, text)` receiving progress or error information.
 /// As it is not a future itself, it must not block. If IO is performed within the function, be sure to spawn
 /// it onto an executor.
-pub type HandleProgress = Box<dyn FnMut(bool, &[u8])>;
+///
+/// It returns [`ProgressAction::Interrupt`] to fail the ongoing read, which allows to stop receiving a pack as soon as the
+/// remote reports a fatal error instead of reading it to the end.
+pub type HandleProgress = Box<dyn FnMut(bool, &[u8]) -> ProgressAction>;
 
 /// This trait exists to get a version of a `git_packetline::Provider` without type parameters.
 /// For the sake of usability, it also implements [`std::io::BufRead`] making it trivial to (eventually)
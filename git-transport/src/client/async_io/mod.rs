@@ -1,5 +1,5 @@
 mod bufread_ext;
-pub use bufread_ext::{ExtendedBufRead, HandleProgress};
+pub use bufread_ext::{ExtendedBufRead, HandleProgress, ProgressAction};
 
 mod request;
 pub use request::RequestWriter;
@@ -2,12 +2,16 @@ use crate::{
     client::{Error, MessageKind},
     Protocol,
 };
+pub use git_packetline::read::ProgressAction;
 use std::{
     io,
     ops::{Deref, DerefMut},
 };
 /// A function `f(is_error, text)` receiving progress or error information.
-pub type HandleProgress = Box<dyn FnMut(bool, &[u8])>;
+///
+/// It returns [`ProgressAction::Interrupt`] to fail the ongoing read, which allows to stop receiving a pack as soon as the
+/// remote reports a fatal error instead of reading it to the end.
+pub type HandleProgress = Box<dyn FnMut(bool, &[u8]) -> ProgressAction>;
 
 /// This trait exists to get a version of a `git_packetline::Provider` without type parameters.
 /// For the sake of usability, it also implements [`std::io::BufRead`] making it trivial to
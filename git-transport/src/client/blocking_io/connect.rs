@@ -1,4 +1,4 @@
-use crate::client::Transport;
+use crate::{client::Transport, Service};
 
 pub use crate::client::non_io_types::connect::Error;
 
@@ -11,7 +11,32 @@ pub use crate::client::non_io_types::connect::Error;
 /// and if compiled in connections to [git repositories over https][crate::client::http::connect()].
 ///
 /// Use `desired_version` to set the desired protocol version to use when connecting, but not that the server may downgrade it.
+///
+/// A trailing smart HTTP service endpoint in an http(s) `url` is ignored, see [`connect_with_service()`] for details.
 pub fn connect(url: &[u8], desired_version: crate::Protocol) -> Result<Box<dyn Transport + Send>, Error> {
+    connect_with_service(url, desired_version, None, Service::UploadPack).map(|(transport, _service)| transport)
+}
+
+/// Like [`connect()`], but also determine the [`Service`] to pass to [`handshake()`][Transport::handshake()], which is
+/// returned along with the transport so fetch and push flows can use the same `url`.
+///
+/// The service is `service` if set, overriding everything else, or the one named by the smart HTTP service endpoint at the end of
+/// an http(s) `url`, like `https://example.com/repo.git/git-receive-pack` or `…/repo.git/info/refs?service=git-upload-pack`,
+/// or `default_service` otherwise. The latter is typically [`Service::UploadPack`] when fetching and [`Service::ReceivePack`]
+/// when pushing. A service endpoint is stripped from an http(s) `url` to connect to the repository itself, while urls of other
+/// schemes are used as is.
+pub fn connect_with_service(
+    url: &[u8],
+    desired_version: crate::Protocol,
+    service: Option<Service>,
+    default_service: Service,
+) -> Result<(Box<dyn Transport + Send>, Service), Error> {
+    let (url, url_service) = crate::client::non_io_types::connect::split_service(url);
+    let service = service.or(url_service).unwrap_or(default_service);
+    connect_inner(url, desired_version).map(|transport| (transport, service))
+}
+
+fn connect_inner(url: &[u8], desired_version: crate::Protocol) -> Result<Box<dyn Transport + Send>, Error> {
     let urlb = url;
     let url = git_url::parse(urlb)?;
     Ok(match url.scheme {
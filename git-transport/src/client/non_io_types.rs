@@ -76,6 +76,32 @@ pub(crate) mod connect {
             }
         }
     }
+
+    /// Split a trailing service endpoint off an http(s) `url` if it names one of the smart HTTP protocol, like
+    /// `<repo>/git-upload-pack` or `<repo>/info/refs?service=git-receive-pack`, and return the url of the repository along
+    /// with the named service. Urls of all other schemes are returned unchanged as these endpoints only exist for HTTP.
+    #[cfg(feature = "blocking-client")]
+    pub(crate) fn split_service(url: &[u8]) -> (&[u8], Option<crate::Service>) {
+        use bstr::ByteSlice;
+        const INFO_REFS: &[u8] = b"/info/refs?service=";
+        if !(url.starts_with(b"http://") || url.starts_with(b"https://")) {
+            return (url, None);
+        }
+        if let Some(pos) = url.rfind(INFO_REFS) {
+            if let Some(service) = crate::Service::from_bytes(&url[pos + INFO_REFS.len()..]) {
+                return (&url[..pos], Some(service));
+            }
+        }
+        if let Some(pos) = url.rfind_byte(b'/') {
+            let name = &url[pos + 1..];
+            if name.starts_with(b"git-") {
+                if let Some(service) = crate::Service::from_bytes(name) {
+                    return (&url[..pos], Some(service));
+                }
+            }
+        }
+        (url, None)
+    }
 }
 
 mod error {
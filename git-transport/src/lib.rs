@@ -32,6 +32,15 @@ impl Service {
             Service::UploadPack => "git-upload-pack",
         }
     }
+
+    /// Parse a service name as recognized by the git transport layer, like `git-upload-pack`, with or without its `git-` prefix.
+    pub fn from_bytes(name: &[u8]) -> Option<Service> {
+        match name.strip_prefix(b"git-").unwrap_or(name) {
+            b"upload-pack" => Some(Service::UploadPack),
+            b"receive-pack" => Some(Service::ReceivePack),
+            _ => None,
+        }
+    }
 }
 
 ///
@@ -39,7 +48,7 @@ pub mod client;
 
 #[doc(inline)]
 #[cfg(feature = "blocking-client")]
-pub use client::connect;
+pub use client::{connect, connect_with_service};
 
 #[cfg(all(feature = "async-client", feature = "blocking-client"))]
 compile_error!("Cannot set both 'blocking-client' and 'async-client' features as they are mutually exclusive");
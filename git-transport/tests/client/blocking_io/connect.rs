@@ -0,0 +1,95 @@
+use git_transport::{client, Protocol, Service};
+
+fn connect(url: &str, service: Option<Service>, default_service: Service) -> crate::Result<(String, Service)> {
+    let (transport, service) = client::connect_with_service(url.as_bytes(), Protocol::V2, service, default_service)?;
+    Ok((transport.to_url(), service))
+}
+
+fn url_of(url: &str) -> crate::Result<String> {
+    Ok(client::connect(url.as_bytes(), Protocol::V2)?.to_url())
+}
+
+#[test]
+fn the_default_service_is_used_if_nothing_else_is_specified() -> crate::Result {
+    let expected_url = url_of("file:///tmp/repo.git")?;
+    assert_eq!(
+        connect("file:///tmp/repo.git", None, Service::ReceivePack)?,
+        (expected_url.clone(), Service::ReceivePack)
+    );
+    assert_eq!(
+        connect("file:///tmp/repo.git", None, Service::UploadPack)?,
+        (expected_url, Service::UploadPack)
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "http-client-curl")]
+fn service_endpoints_in_http_urls_select_the_service_and_are_stripped() -> crate::Result {
+    let expected_url = url_of("https://example.com/repo.git")?;
+    for url in &[
+        "https://example.com/repo.git/git-receive-pack",
+        "https://example.com/repo.git/info/refs?service=git-receive-pack",
+    ] {
+        assert_eq!(
+            connect(url, None, Service::UploadPack)?,
+            (expected_url.clone(), Service::ReceivePack),
+            "{}",
+            url
+        );
+    }
+    assert_eq!(
+        url_of("https://example.com/repo.git/git-upload-pack")?,
+        expected_url,
+        "plain connections ignore the endpoint as well"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "http-client-curl")]
+fn an_explicit_service_overrides_the_one_in_the_url() -> crate::Result {
+    assert_eq!(
+        connect(
+            "http://example.com/repo.git/git-receive-pack",
+            Some(Service::UploadPack),
+            Service::ReceivePack
+        )?,
+        (url_of("http://example.com/repo.git")?, Service::UploadPack)
+    );
+    Ok(())
+}
+
+#[test]
+fn an_explicit_service_overrides_the_default_one() -> crate::Result {
+    assert_eq!(
+        connect("file:///tmp/repo.git", Some(Service::UploadPack), Service::ReceivePack)?,
+        (url_of("file:///tmp/repo.git")?, Service::UploadPack)
+    );
+    Ok(())
+}
+
+#[test]
+fn service_endpoints_are_only_stripped_from_http_urls() -> crate::Result {
+    for url in &[
+        "file:///tmp/repo.git/git-receive-pack",
+        "file:///tmp/repo.git/info/refs?service=git-receive-pack",
+    ] {
+        assert_eq!(
+            connect(url, None, Service::UploadPack)?,
+            (url_of(url)?, Service::UploadPack),
+            "{}",
+            url
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn repositories_named_like_a_service_are_not_mistaken_for_endpoints() -> crate::Result {
+    assert_eq!(
+        connect("file:///tmp/upload-pack", None, Service::ReceivePack)?,
+        (url_of("file:///tmp/upload-pack")?, Service::ReceivePack)
+    );
+    Ok(())
+}
@@ -262,7 +262,8 @@ fn clone_v1() -> crate::Result {
             assert!(!is_err);
             sb.deref()
                 .borrow_mut()
-                .push(std::str::from_utf8(data).expect("valid utf8").to_owned())
+                .push(std::str::from_utf8(data).expect("valid utf8").to_owned());
+            client::ProgressAction::Continue
         }
     })));
     let mut pack = Vec::new();
@@ -413,7 +414,8 @@ Git-Protocol: version=2
             assert!(!is_err);
             sb.deref()
                 .borrow_mut()
-                .push(std::str::from_utf8(data).expect("valid utf8").to_owned())
+                .push(std::str::from_utf8(data).expect("valid utf8").to_owned());
+            client::ProgressAction::Continue
         }
     })));
 
@@ -1,2 +1,3 @@
+mod connect;
 #[cfg(feature = "http-client-curl")]
 mod http;
@@ -106,7 +106,8 @@ async fn handshake_v1_and_request() -> crate::Result {
             sb.deref()
                 .lock()
                 .expect("no poison")
-                .push(std::str::from_utf8(data).expect("valid utf8").to_owned())
+                .push(std::str::from_utf8(data).expect("valid utf8").to_owned());
+            client::ProgressAction::Continue
         }
     })));
 
@@ -310,7 +311,8 @@ async fn handshake_v2_and_request_inner() -> crate::Result {
             sb.deref()
                 .lock()
                 .expect("no poison")
-                .push(std::str::from_utf8(data).expect("valid utf8").to_owned())
+                .push(std::str::from_utf8(data).expect("valid utf8").to_owned());
+            client::ProgressAction::Continue
         }
     })));
 
@@ -0,0 +1,1064 @@
+use git_hash::{oid, ObjectId};
+use git_object::immutable;
+use quick_error::quick_error;
+use std::{
+    borrow::BorrowMut,
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque},
+};
+
+quick_error! {
+    /// The error is part of the item returned by the [Ancestors] iterator.
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        NotFound{oid: ObjectId} {
+            display("The commit {} could not be found", oid)
+        }
+        ObjectDecode(err: immutable::object::decode::Error) {
+            display("An object could not be decoded")
+            source(err)
+            from()
+        }
+        CommitGraph(err: git_commitgraph::file::commit::Error) {
+            display("The parents of a commit could not be obtained from the commit-graph")
+            source(err)
+            from()
+        }
+    }
+}
+
+/// The order in which [`Ancestors`] returns commits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Sorting {
+    /// Commits are returned in breadth-first order, as they are discovered.
+    ///
+    /// This is the default and doesn't require looking up commits before they are returned.
+    BreadthFirst,
+    /// Commits are returned by their commit time, newest first, like `git rev-list` does by default, which requires looking up
+    /// each commit once more when it is discovered to learn its commit time.
+    ///
+    /// A commit is never returned before the commit it was discovered through, even if it claims to be newer, and commits
+    /// with the same commit time are returned in the order they were discovered. That way, commits with skewed or zero
+    /// timestamps as found in imported histories are still returned in a deterministic order.
+    ByCommitTimeNewestFirst,
+    /// Commits are returned in topological order like `git rev-list --topo-order` does, that is no commit is returned before
+    /// all of its children, and the commits of a line of history are returned together instead of being interleaved with
+    /// the commits of other lines of history.
+    ///
+    /// This requires learning the whole graph reachable from the tips before the first commit can be returned, which makes it
+    /// suitable for rendering graphs but slower to start than the other modes.
+    Topological,
+    /// Commits are returned in topological order, but otherwise by their commit time, newest first, like
+    /// `git rev-list --date-order` does.
+    ///
+    /// Like [`Topological`][Sorting::Topological], this requires learning the whole graph reachable from the tips first.
+    TopologicalByCommitTime,
+}
+
+impl Default for Sorting {
+    fn default() -> Self {
+        Sorting::BreadthFirst
+    }
+}
+
+/// The state used and potentially shared by multiple graph traversals.
+#[derive(Default, Clone)]
+pub struct State {
+    next: VecDeque<ObjectId>,
+    /// Commits ordered by commit time and then by order of discovery, used for [`Sorting::ByCommitTimeNewestFirst`].
+    queue: BinaryHeap<(u64, Reverse<u64>, ObjectId)>,
+    /// The amount of commits pushed to the `queue` so far.
+    num_queued: u64,
+    buf: Vec<u8>,
+    /// Seen commits, unless they are tracked in `seen_positions`.
+    seen: BTreeSet<ObjectId>,
+    /// Words of 64 bits keyed by the position in the commit source divided by 64, with a bit set if the commit at this position
+    /// was seen, used only if `seen_by_position` is set. Only words with seen commits are stored.
+    seen_positions: BTreeMap<u32, u64>,
+    /// True if seen commits known to the commit source are tracked in `seen_positions`.
+    seen_by_position: bool,
+    hidden_tips: Vec<ObjectId>,
+    hidden: BTreeSet<ObjectId>,
+    /// Commits whose parents are not traversed as they aren't present in shallow clones.
+    shallow: BTreeSet<ObjectId>,
+    boundary: Vec<ObjectId>,
+    parents: Vec<ObjectId>,
+    /// All commits to return along with their parents, used for topological sorting.
+    graph: BTreeMap<ObjectId, Node>,
+    /// Commits whose children were all returned, with the next one to return last, used for [`Sorting::Topological`].
+    ready: Vec<ObjectId>,
+    /// The distance of discovered commits to the closest tip, with tips having a depth of 1 without being listed, used only
+    /// if the depth is limited.
+    depths: BTreeMap<ObjectId, usize>,
+    /// Ancestors with a lower generation number are neither returned nor traversed.
+    min_generation: Option<u32>,
+    /// The parents of commits that were obtained ahead of time, used if discovery is parallel.
+    discovered: BTreeMap<ObjectId, Vec<ObjectId>>,
+}
+
+/// A function to obtain the parents of all given commits using multiple threads.
+type Discover = Box<dyn Fn(Vec<ObjectId>) -> Result<Vec<(ObjectId, Vec<ObjectId>)>, Error> + Send + Sync>;
+
+/// Collects the parents of commits obtained in parallel.
+struct Discovered(Vec<(ObjectId, Vec<ObjectId>)>);
+
+impl git_features::parallel::Reduce for Discovered {
+    type Input = Result<Vec<(ObjectId, Vec<ObjectId>)>, Error>;
+    type FeedProduce = ();
+    type Output = Vec<(ObjectId, Vec<ObjectId>)>;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.0.extend(item?);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.0)
+    }
+}
+
+/// A commit to be returned in topological order.
+#[derive(Clone)]
+struct Node {
+    /// The parents of the commit which are returned as well.
+    parents: Vec<ObjectId>,
+    /// The amount of children of the commit which weren't returned yet.
+    pending_children: usize,
+}
+
+impl State {
+    fn clear(&mut self) {
+        self.next.clear();
+        self.queue.clear();
+        self.num_queued = 0;
+        self.buf.clear();
+        self.seen.clear();
+        self.seen_positions.clear();
+        self.seen_by_position = false;
+        self.hidden_tips.clear();
+        self.hidden.clear();
+        self.shallow.clear();
+        self.boundary.clear();
+        self.parents.clear();
+        self.graph.clear();
+        self.ready.clear();
+        self.depths.clear();
+        self.min_generation = None;
+        self.discovered.clear();
+    }
+
+    /// Place the parents of `id` into `parents`, taking them from the commits discovered ahead of time if `discover` is set.
+    ///
+    /// If `id` wasn't discovered yet, it is discovered along with all other commits in `next`, which is the frontier of the
+    /// traversal, using `discover` to look them up in parallel. Commits known to the commit `source` are not looked up.
+    fn parents_of<Find>(
+        &mut self,
+        id: &oid,
+        find: &mut Find,
+        source: Option<&dyn CommitSource>,
+        discover: Option<&Discover>,
+        parents: &mut Vec<ObjectId>,
+    ) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    {
+        if let Some(discover) = discover {
+            if !self.discovered.contains_key(id) {
+                let mut ids = Vec::new();
+                for id in std::iter::once(id.to_owned()).chain(self.next.iter().copied()) {
+                    if self.shallow.contains(&id) || self.hidden.contains(&id) || self.discovered.contains_key(&id) {
+                        continue;
+                    }
+                    if let Some(source) = source {
+                        let mut parents = Vec::new();
+                        if source.parents(&id, &mut parents)? {
+                            self.discovered.insert(id, parents);
+                            continue;
+                        }
+                    }
+                    ids.push(id);
+                }
+                self.discovered.extend(discover(ids)?);
+            }
+            if let Some(discovered) = self.discovered.remove(id) {
+                parents.clear();
+                parents.extend(discovered);
+                return Ok(());
+            }
+        }
+        collect_parents(id, &mut self.buf, find, source, parents)
+    }
+
+    /// Clear all state and release the memory it allocated, which otherwise remains allocated to be reused by the next
+    /// traversal. This is useful after traversing huge repositories when the state is kept around.
+    pub fn clear_and_shrink(&mut self) {
+        *self = State::default();
+    }
+
+    /// Return the approximate amount of bytes allocated on the heap by this state, without the overhead of its tree-based
+    /// collections, to learn how much memory traversals need.
+    pub fn memory_usage(&self) -> usize {
+        use std::mem::size_of;
+        let ids = |count: usize| count * size_of::<ObjectId>();
+        ids(self.next.capacity())
+            + self.queue.capacity() * size_of::<(u64, Reverse<u64>, ObjectId)>()
+            + self.buf.capacity()
+            + ids(self.seen.len())
+            + self.seen_positions.len() * size_of::<(u32, u64)>()
+            + ids(self.hidden_tips.capacity())
+            + ids(self.hidden.len())
+            + ids(self.shallow.len())
+            + ids(self.boundary.capacity())
+            + ids(self.parents.capacity())
+            + self
+                .graph
+                .values()
+                .map(|node| ids(1 + node.parents.capacity()) + size_of::<Node>())
+                .sum::<usize>()
+            + ids(self.ready.capacity())
+            + self.depths.len() * size_of::<(ObjectId, usize)>()
+            + self
+                .discovered
+                .values()
+                .map(|parents| ids(1 + parents.capacity()) + size_of::<Vec<ObjectId>>())
+                .sum::<usize>()
+    }
+
+    /// Track seen commits known to the commit `source` in a bitset keyed by their position, migrating the ones seen so far.
+    fn compact_seen(&mut self, source: &dyn CommitSource) {
+        self.seen_positions.clear();
+        self.seen_by_position = true;
+        for id in std::mem::take(&mut self.seen) {
+            self.insert_seen(id, Some(source));
+        }
+    }
+
+    /// Remember `id` as seen and return true if it wasn't seen before.
+    fn insert_seen(&mut self, id: ObjectId, source: Option<&dyn CommitSource>) -> bool {
+        if self.seen_by_position {
+            if let Some(pos) = source.and_then(|source| source.position(&id)) {
+                let (word, bit) = (self.seen_positions.entry(pos / 64).or_insert(0), 1 << (pos % 64));
+                let was_seen = *word & bit != 0;
+                *word |= bit;
+                return !was_seen;
+            }
+        }
+        self.seen.insert(id)
+    }
+
+    /// Return true if `id` is known to have a generation number lower than the cutoff, which means it can't reach any commit
+    /// with a generation number at or above it.
+    fn is_below_cutoff(&self, id: &oid, source: Option<&dyn CommitSource>) -> bool {
+        match (self.min_generation, source) {
+            (Some(min_generation), Some(source)) => source
+                .generation(id)
+                .map_or(false, |generation| generation < min_generation),
+            _ => false,
+        }
+    }
+
+    /// Return true if the parents of `id` are deeper than `max_depth` allows, and learn the depth of its parents otherwise.
+    fn parents_are_too_deep(&mut self, id: &oid, parents: &[ObjectId], max_depth: Option<usize>) -> bool {
+        let max_depth = match max_depth {
+            Some(max_depth) => max_depth,
+            None => return false,
+        };
+        let depth = self.depths.get(id).copied().unwrap_or(1);
+        if depth >= max_depth {
+            return true;
+        }
+        for parent in parents {
+            let parent_depth = self.depths.entry(*parent).or_insert(depth + 1);
+            *parent_depth = (*parent_depth).min(depth + 1);
+        }
+        false
+    }
+
+    fn push_by_time(&mut self, id: ObjectId, commit_time: u64) {
+        self.queue.push((commit_time, Reverse(self.num_queued), id));
+        self.num_queued += 1;
+    }
+
+    /// Mark the commits reachable from the hidden tips that are also reachable from the visible tips in `next`, which is done
+    /// only once before the traversal starts.
+    ///
+    /// Like `git rev-list`, visible and hidden commits are walked together by commit time, newest first, while hidden
+    /// commits pass their mark on to their parents. The walk stops once only hidden commits remain, after walking a few
+    /// more of them to tolerate skewed commit times, so the history shared by visible and hidden tips isn't walked entirely.
+    fn mark_hidden<Find>(
+        &mut self,
+        find: &mut Find,
+        source: Option<&dyn CommitSource>,
+        first_parent_only: bool,
+    ) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    {
+        /// The amount of hidden commits to walk after only hidden commits remain, like git does.
+        const SLOP: usize = 5;
+
+        let mut queue = BinaryHeap::new();
+        let mut queued = BTreeSet::new();
+        let mut parents_of_visible = BTreeMap::<ObjectId, Vec<ObjectId>>::new();
+        let hidden_tips = std::mem::take(&mut self.hidden_tips);
+        self.hidden.extend(hidden_tips.iter().copied());
+        for tip in self.next.iter().chain(hidden_tips.iter()) {
+            if queued.insert(*tip) {
+                let time = commit_time(tip, &mut self.buf, find, source)?;
+                queue.push((time, Reverse(queued.len()), *tip));
+            }
+        }
+
+        let mut slop = SLOP;
+        while let Some((_time, _order, oid)) = queue.pop() {
+            if self.shallow.contains(&oid) {
+                self.parents.clear();
+            } else {
+                collect_parents(&oid, &mut self.buf, find, source, &mut self.parents)?;
+            }
+            let is_hidden = self.hidden.contains(&oid);
+            let num_followed = if is_hidden || !first_parent_only {
+                self.parents.len()
+            } else {
+                1
+            };
+            for (index, parent) in self.parents.iter().enumerate() {
+                if is_hidden {
+                    hide(*parent, &mut self.hidden, &parents_of_visible);
+                }
+                if index < num_followed && !self.is_below_cutoff(parent, source) && queued.insert(*parent) {
+                    let time = commit_time(parent, &mut self.buf, find, source)?;
+                    queue.push((time, Reverse(queued.len()), *parent));
+                }
+            }
+            if !is_hidden {
+                parents_of_visible.insert(oid, self.parents.clone());
+            }
+
+            if queue.iter().all(|(_, _, id)| self.hidden.contains(id)) {
+                slop -= 1;
+                if slop == 0 {
+                    break;
+                }
+            } else {
+                slop = SLOP;
+            }
+        }
+        Ok(())
+    }
+
+    /// Learn all commits to return, which are the ones reachable from the tips in `next`, along with the amount of their
+    /// children, and queue the tips without children for returning them in topological order.
+    #[allow(clippy::too_many_arguments)]
+    fn build_graph<Find, Predicate>(
+        &mut self,
+        find: &mut Find,
+        source: Option<&dyn CommitSource>,
+        discover: Option<&Discover>,
+        predicate: &mut Predicate,
+        sorting: Sorting,
+        first_parent_only: bool,
+        max_depth: Option<usize>,
+    ) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+        Predicate: FnMut(&oid) -> bool,
+    {
+        let tips: Vec<_> = self
+            .next
+            .iter()
+            .copied()
+            .filter(|tip| !self.hidden.contains(tip))
+            .collect();
+        while let Some(oid) = self.next.pop_front() {
+            if self.hidden.contains(&oid) {
+                continue;
+            }
+            let mut parents = Vec::new();
+            if !self.shallow.contains(&oid) {
+                self.parents_of(&oid, find, source, discover, &mut parents)?;
+                if first_parent_only {
+                    parents.truncate(1);
+                }
+                let parents_are_too_deep = self.parents_are_too_deep(&oid, &parents, max_depth);
+                parents.retain(|id| !self.is_below_cutoff(id, source));
+                parents.retain(|id| {
+                    if parents_are_too_deep || self.hidden.contains(id) {
+                        if self.insert_seen(*id, source) {
+                            self.boundary.push(*id);
+                        }
+                        return false;
+                    }
+                    if self.insert_seen(*id, source) && predicate(id) {
+                        self.next.push_back(*id);
+                    }
+                    true
+                });
+            }
+            self.graph.insert(
+                oid,
+                Node {
+                    parents,
+                    pending_children: 0,
+                },
+            );
+        }
+
+        let parents: Vec<_> = self
+            .graph
+            .values()
+            .flat_map(|node| node.parents.iter().copied())
+            .collect();
+        for parent in parents {
+            // parents rejected by the predicate are not part of the graph.
+            if let Some(node) = self.graph.get_mut(&parent) {
+                node.pending_children += 1;
+            }
+        }
+        let graph = &self.graph;
+        let roots: Vec<_> = tips
+            .into_iter()
+            .filter(|tip| graph[tip].pending_children == 0)
+            .collect();
+        match sorting {
+            Sorting::TopologicalByCommitTime => {
+                for tip in roots {
+                    let time = commit_time(&tip, &mut self.buf, find, source)?;
+                    self.push_by_time(tip, time);
+                }
+            }
+            _ => {
+                self.ready.extend(roots.into_iter().rev());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mark `id` as `hidden` along with all ancestors that were already walked as visible commits according to `parents_of_visible`.
+fn hide(id: ObjectId, hidden: &mut BTreeSet<ObjectId>, parents_of_visible: &BTreeMap<ObjectId, Vec<ObjectId>>) {
+    let mut stack = vec![id];
+    while let Some(id) = stack.pop() {
+        if hidden.insert(id) {
+            if let Some(parents) = parents_of_visible.get(&id) {
+                stack.extend(parents.iter().copied());
+            }
+        }
+    }
+}
+
+/// Place the ids of all parents of the commit `oid` into `parents`, taking them from the commit `source` if it knows the commit
+/// to avoid decoding it, or from the commit obtained with `find` otherwise.
+pub(crate) fn collect_parents<Find>(
+    oid: &oid,
+    buf: &mut Vec<u8>,
+    find: &mut Find,
+    source: Option<&dyn CommitSource>,
+    parents: &mut Vec<ObjectId>,
+) -> Result<(), Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+{
+    parents.clear();
+    if let Some(source) = source {
+        if source.parents(oid, parents)? {
+            return Ok(());
+        }
+        parents.clear();
+    }
+    let mut commit_iter = find(oid, buf).ok_or_else(|| Error::NotFound { oid: oid.to_owned() })?;
+    if let Some(Err(decode_tree_err)) = commit_iter.next() {
+        return Err(decode_tree_err.into());
+    }
+    let mut decode_error = None;
+    for token in commit_iter {
+        match token {
+            Ok(immutable::commit::iter::Token::Parent { id }) => parents.push(id),
+            Ok(_a_token_past_the_parents) => break,
+            Err(err) => {
+                decode_error = Some(err);
+                break;
+            }
+        }
+    }
+    match decode_error {
+        Some(err) if overflowing_committer_time(buf).is_none() => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Return the commit time of the commit `oid` in seconds since the unix epoch, taking it from the commit `source` if it knows
+/// the commit or from the commit obtained with `find` otherwise.
+///
+/// Timestamps too large for signatures to be decoded are read from the raw commit, see [`overflowing_committer_time()`].
+pub(crate) fn commit_time<Find>(
+    oid: &oid,
+    buf: &mut Vec<u8>,
+    find: &mut Find,
+    source: Option<&dyn CommitSource>,
+) -> Result<u64, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+{
+    if let Some(time) = source.and_then(|source| source.commit_time(oid)) {
+        return Ok(time);
+    }
+    let mut decode_error = None;
+    for token in find(oid, buf).ok_or_else(|| Error::NotFound { oid: oid.to_owned() })? {
+        match token {
+            Ok(immutable::commit::iter::Token::Committer { signature }) => return Ok(signature.time.time as u64),
+            Ok(_a_token_before_the_committer) => continue,
+            Err(err) => {
+                decode_error = Some(err);
+                break;
+            }
+        }
+    }
+    match decode_error {
+        Some(err) => overflowing_committer_time(buf).ok_or_else(|| err.into()),
+        None => Ok(0),
+    }
+}
+
+/// Return the committer time of the raw commit `data` if one of its signatures has a timestamp which doesn't fit into the
+/// 32 bits of decoded signatures, as seen in imported histories, or `None` if there is no such timestamp.
+///
+/// The committer time is returned in full and saturates at `u64::MAX` to order such commits as the most recent ones.
+pub(crate) fn overflowing_committer_time(data: &[u8]) -> Option<u64> {
+    let mut overflows = false;
+    let mut committer_time = None;
+    for line in data.split(|b| *b == b'\n').take_while(|line| !line.is_empty()) {
+        let is_committer = line.starts_with(b"committer ");
+        if !is_committer && !line.starts_with(b"author ") {
+            continue;
+        }
+        let time = line.rsplitn(3, |b| *b == b' ').nth(1)?;
+        if time.is_empty() || !time.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let time = time.iter().fold(0u64, |time, digit| {
+            time.saturating_mul(10).saturating_add(u64::from(digit - b'0'))
+        });
+        overflows |= time > u64::from(u32::MAX);
+        if is_committer {
+            committer_time = Some(time);
+        }
+    }
+    committer_time.filter(|_| overflows)
+}
+
+/// A source of the parents and commit times of commits which is faster than looking up and decoding them, like a commit-graph.
+///
+/// Commits it doesn't know are looked up with the `find` function of [`Ancestors`] instead, so it doesn't need to know all
+/// commits, like a commit-graph which doesn't contain the commits created after it was written.
+///
+/// It must be `Send` so that [`Ancestors`] using it can still be sent to other threads.
+pub trait CommitSource: Send {
+    /// Place the ids of all parents of the commit `id` into the empty `parents` and return `true`, or return `false` if the
+    /// commit is unknown.
+    fn parents(&self, id: &oid, parents: &mut Vec<ObjectId>) -> Result<bool, Error>;
+
+    /// Return the commit time of the commit `id` in seconds since the unix epoch, or `None` if the commit is unknown.
+    fn commit_time(&self, id: &oid) -> Option<u64>;
+
+    /// Return the generation number of the commit `id`, which is one more than the highest generation number of its parents,
+    /// or `None` if the commit is unknown or the source doesn't provide generation numbers, which is the default.
+    ///
+    /// Generation numbers allow to [cut off the traversal][Ancestors::with_generation_cutoff()] as a commit can't reach
+    /// any commit with the same or a higher generation number.
+    fn generation(&self, _id: &oid) -> Option<u32> {
+        None
+    }
+
+    /// Return the position of the commit `id` if it is known, which is smaller than [`num_positions()`][Self::num_positions()],
+    /// or `None` if the commit is unknown or the source doesn't provide positions, which is the default.
+    ///
+    /// Positions allow to track seen commits [in a compact bitset][Ancestors::with_compact_seen_set()].
+    fn position(&self, _id: &oid) -> Option<u32> {
+        None
+    }
+
+    /// Return the amount of positions that [`position()`][Self::position()] may return.
+    fn num_positions(&self) -> u32 {
+        0
+    }
+}
+
+impl CommitSource for git_commitgraph::Graph {
+    fn parents(&self, id: &oid, parents: &mut Vec<ObjectId>) -> Result<bool, Error> {
+        let commit = match self.commit_by_id(id) {
+            Some(commit) => commit,
+            None => return Ok(false),
+        };
+        for pos in commit.iter_parents() {
+            parents.push(self.id_at(pos?).to_owned());
+        }
+        Ok(true)
+    }
+
+    fn commit_time(&self, id: &oid) -> Option<u64> {
+        self.commit_by_id(id).map(|commit| commit.committer_timestamp())
+    }
+
+    fn generation(&self, id: &oid) -> Option<u32> {
+        self.commit_by_id(id).map(|commit| commit.generation())
+    }
+
+    fn position(&self, id: &oid) -> Option<u32> {
+        self.lookup(id).map(|pos| pos.0)
+    }
+
+    fn num_positions(&self) -> u32 {
+        self.num_commits()
+    }
+}
+
+/// An iterator over the ancestors one or more starting commits
+pub struct Ancestors<Find, Predicate, StateMut> {
+    find: Find,
+    predicate: Predicate,
+    state: StateMut,
+    source: Option<Box<dyn CommitSource>>,
+    discover: Option<Discover>,
+    sorting: Sorting,
+    first_parent_only: bool,
+    max_depth: Option<usize>,
+    /// The amount of commits to skip before returning the first one.
+    skip: usize,
+    /// The amount of commits left to return, if limited.
+    max_count: Option<usize>,
+    /// True once the graph was learned for topological sorting.
+    graph_built: bool,
+    /// True if seen commits should be tracked in a bitset once the traversal starts.
+    compact_seen: bool,
+}
+
+impl<Find, StateMut> Ancestors<Find, fn(&oid) -> bool, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    StateMut: BorrowMut<State>,
+{
+    /// Create a new instance.
+    ///
+    /// * `find` - a way to lookup new object data during traversal by their ObjectId, writing their data into buffer and returning
+    ///    an iterator over commit tokens if the object is present and is a commit. Caching should be implemented within this function
+    ///    as needed. The return value is `Option<CommitIter>` which degenerates all error information. Not finding a commit should also
+    ///    be considered an errors as all objects in the commit graph should be present in the database. Hence [`Error::NotFound`] should
+    ///    be escalated into a more specific error if its encountered by the caller.
+    /// * `state` - all state used for the traversal. If multiple traversals are performed, allocations can be minimized by reusing
+    ///   this state.
+    /// * `tips`
+    ///   * the starting points of the iteration, usually commits
+    ///   * each commit they lead to will only be returned once, including the tip that started it
+    pub fn new(tips: impl IntoIterator<Item = impl Into<ObjectId>>, state: StateMut, find: Find) -> Self {
+        Self::filtered(tips, state, find, |_| true)
+    }
+}
+
+impl<Find, Predicate, StateMut> Ancestors<Find, Predicate, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    Predicate: FnMut(&oid) -> bool,
+    StateMut: BorrowMut<State>,
+{
+    /// Create a new instance with commit filtering enabled.
+    ///
+    /// * `find` - a way to lookup new object data during traversal by their ObjectId, writing their data into buffer and returning
+    ///    an iterator over commit tokens if the object is present and is a commit. Caching should be implemented within this function
+    ///    as needed. The return value is `Option<CommitIter>` which degenerates all error information. Not finding a commit should also
+    ///    be considered an errors as all objects in the commit graph should be present in the database. Hence [`Error::NotFound`] should
+    ///    be escalated into a more specific error if its encountered by the caller.
+    /// * `state` - all state used for the traversal. If multiple traversals are performed, allocations can be minimized by reusing
+    ///   this state.
+    /// * `tips`
+    ///   * the starting points of the iteration, usually commits
+    ///   * each commit they lead to will only be returned once, including the tip that started it
+    /// * `predicate` - indicate whether a given commit should be included in the result as well
+    ///   as whether its parent commits should be traversed.
+    pub fn filtered(
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        mut state: StateMut,
+        find: Find,
+        mut predicate: Predicate,
+    ) -> Self {
+        let tips = tips.into_iter();
+        {
+            let state = state.borrow_mut();
+            state.clear();
+            state.next.reserve(tips.size_hint().0);
+            for tip in tips.map(Into::into) {
+                let was_inserted = state.seen.insert(tip);
+                if was_inserted && predicate(&tip) {
+                    state.next.push_back(tip);
+                }
+            }
+        }
+        Self {
+            find,
+            predicate,
+            state,
+            source: None,
+            discover: None,
+            sorting: Sorting::default(),
+            first_parent_only: false,
+            max_depth: None,
+            skip: 0,
+            max_count: None,
+            graph_built: false,
+            compact_seen: false,
+        }
+    }
+
+    /// Return commits in the given `sorting` order instead of [breadth-first][Sorting::BreadthFirst].
+    pub fn with_sorting(mut self, sorting: Sorting) -> Self {
+        self.sorting = sorting;
+        self
+    }
+
+    /// Obtain the parents and commit times of all commits contained in `commit_graph` from it instead of looking up and decoding
+    /// the commits with `find`, which is considerably faster. Commits not contained in it, like the ones created after it was written,
+    /// are still looked up with `find`.
+    pub fn with_commit_graph(self, commit_graph: git_commitgraph::Graph) -> Self {
+        self.with_commit_source(commit_graph)
+    }
+
+    /// Obtain the parents and commit times of all commits known to `source` from it instead of looking up and decoding the
+    /// commits with `find`, which is how [commit-graphs][Self::with_commit_graph()] speed up the traversal.
+    pub fn with_commit_source(mut self, source: impl CommitSource + 'static) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Look up and decode the commits at the frontier of the traversal on up to `thread_limit` threads, or as many threads as
+    /// there are logical cores if `None`, instead of looking up one commit after another with the `find` function, which
+    /// speeds up the traversal of wide histories with many merges or many tips.
+    ///
+    /// * `new_thread_state()` creates the state for each thread, like a handle to the object database or a pack cache.
+    /// * `find(oid, buf, thread_state)` works just like the `find` function passed upon creation.
+    ///
+    /// Commits are returned in the same order as without parallel discovery, which is only used when traversing
+    /// [breadth-first][Sorting::BreadthFirst] or [topologically][Sorting::Topological], as sorting by commit time requires
+    /// looking up one commit after another. Commits known to the [commit source][Self::with_commit_source()] are still
+    /// taken from it instead. Note that threads are only used if the `parallel` feature toggle of `git-features` is enabled.
+    pub fn with_parallel_discovery<NewState, ThreadState, ThreadFind>(
+        mut self,
+        thread_limit: Option<usize>,
+        new_thread_state: NewState,
+        find: ThreadFind,
+    ) -> Self
+    where
+        NewState: Fn() -> ThreadState + Send + Sync + 'static,
+        ThreadFind: for<'a> Fn(&oid, &'a mut Vec<u8>, &mut ThreadState) -> Option<immutable::CommitIter<'a>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.discover = Some(Box::new(move |ids: Vec<ObjectId>| {
+            let (chunk_size, thread_limit, _) =
+                git_features::parallel::optimize_chunk_size_and_thread_limit(1, Some(ids.len()), thread_limit, None);
+            let chunks: Vec<_> = ids.chunks(chunk_size).map(ToOwned::to_owned).collect();
+            git_features::parallel::in_parallel(
+                chunks.into_iter(),
+                thread_limit,
+                |_thread_id| (new_thread_state(), Vec::new()),
+                |chunk: Vec<ObjectId>, (thread_state, buf)| {
+                    let mut out = Vec::with_capacity(chunk.len());
+                    for id in chunk {
+                        let mut parents = Vec::new();
+                        collect_parents(
+                            &id,
+                            buf,
+                            &mut |oid, buf| find(oid, buf, thread_state),
+                            None,
+                            &mut parents,
+                        )?;
+                        out.push((id, parents));
+                    }
+                    Ok(out)
+                },
+                Discovered(Vec::new()),
+            )
+        }));
+        self
+    }
+
+    /// Hide all commits reachable from the given `tips`, similar to `git rev-list --not <tips>`, making it possible
+    /// to traverse ranges like `main..feature`.
+    ///
+    /// Hidden commits are never returned and their ancestors are not traversed, even if they are also reachable from
+    /// one of the visible tips. Before the traversal begins, visible and hidden commits are walked together by commit time
+    /// like `git rev-list` does, until only hidden commits remain. That way, only the part of the history that is shared by
+    /// visible and hidden tips is walked, instead of everything reachable from the hidden tips, which makes the walk fast
+    /// even in large repositories. Just like in git, commits with a commit time that is far older than the one of their
+    /// ancestors may cause commits to be returned that are actually hidden.
+    pub fn with_hidden(mut self, tips: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        self.state
+            .borrow_mut()
+            .hidden_tips
+            .extend(tips.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only follow the first parent of each commit, like `git rev-list --first-parent`, to obtain the mainline history without
+    /// the commits of merged branches.
+    ///
+    /// Commits [hidden][Self::with_hidden()] by the given tips are still determined by following all parents, and only the
+    /// first parents of returned commits can become part of the [boundary][Self::boundary()].
+    pub fn with_first_parent_only(mut self) -> Self {
+        self.first_parent_only = true;
+        self
+    }
+
+    /// Only traverse commits whose distance to the closest tip is at most `max_depth`, with the tips having a depth of 1, which
+    /// is how shallow clones and fetches with `--depth` limit the history.
+    ///
+    /// The parents of the deepest returned commits become part of the [boundary][Self::boundary()], which makes these commits
+    /// the new shallow commits. Depths are exact if the traversal is breadth-first or topological, but when sorting
+    /// [by commit time][Sorting::ByCommitTimeNewestFirst] they are only exact as long as no commit claims to be older than one
+    /// of its parents.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Skip the first `count` commits that would be returned, like `git rev-list --skip`, which together with
+    /// [`with_max_count()`][Self::with_max_count()] allows to paginate through the history.
+    pub fn with_skip(mut self, count: usize) -> Self {
+        self.skip = count;
+        self
+    }
+
+    /// Stop the traversal after returning `count` commits, like `git rev-list --max-count`, without traversing the remaining
+    /// history.
+    pub fn with_max_count(mut self, count: usize) -> Self {
+        self.max_count = Some(count);
+        self
+    }
+
+    /// Neither return nor traverse ancestors whose generation number is lower than `generation`, as known to the
+    /// [commit source][Self::with_commit_source()], since these can't reach any commit with a generation number of at least
+    /// `generation`.
+    ///
+    /// Using the lowest generation number of the commits of interest as cutoff, queries like `git branch --contains <commit>`
+    /// or finding merge-bases only walk the part of the history that can actually lead to these commits instead of everything
+    /// down to the root commits. Commits unknown to the commit source, like the ones created after writing the commit-graph,
+    /// are assumed to have an infinite generation number and are always traversed, just like the tips.
+    pub fn with_generation_cutoff(mut self, generation: u32) -> Self {
+        self.state.borrow_mut().min_generation = Some(generation);
+        self
+    }
+
+    /// Treat the given `commits` as if they had no parents, which is required to traverse shallow clones whose shallow commits
+    /// reference parents that are not present in the object database.
+    ///
+    /// The shallow commits themselves are returned, but their parents are never looked up, neither for returning them nor
+    /// for [hiding][Self::with_hidden()] commits.
+    pub fn with_shallow(mut self, commits: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        self.state
+            .borrow_mut()
+            .shallow
+            .extend(commits.into_iter().map(Into::into));
+        self
+    }
+
+    /// Track seen commits in a bitset with a bit for each commit known to the [commit source][Self::with_commit_source()],
+    /// keyed by their position like in the commit-graph, instead of storing their ids, which greatly reduces the memory
+    /// needed to traverse huge repositories. Commits unknown to the commit source are still tracked by their id.
+    ///
+    /// The bitset is sparse and nothing is allocated up front, as only words of 64 bits that contain a seen commit are
+    /// stored. Each of these takes 16 bytes plus the overhead of a tree node, so walking the entire history takes about
+    /// twice the memory of a dense bitset, while walking a few commits of a huge commit-graph costs at most one word per commit.
+    ///
+    /// This has no effect without a commit source that provides [positions][CommitSource::position()].
+    pub fn with_compact_seen_set(mut self) -> Self {
+        self.compact_seen = true;
+        self
+    }
+
+    /// Return the state used for the traversal, for example to learn its [memory usage][State::memory_usage()].
+    pub fn state(&self) -> &State {
+        self.state.borrow()
+    }
+
+    /// Return the state used for the traversal to reuse its allocations in the next traversal.
+    pub fn into_state(self) -> StateMut {
+        self.state
+    }
+
+    /// Return all hidden commits that were encountered as parents of returned commits so far, in order of discovery.
+    ///
+    /// These boundary commits are the first excluded ancestors of the traversal, and are only known once the traversal
+    /// reached them. If the [depth is limited][Self::with_max_depth()], they also contain the parents of the deepest returned
+    /// commits.
+    pub fn boundary(&self) -> &[ObjectId] {
+        &self.state.borrow().boundary
+    }
+}
+
+impl<Find, Predicate, StateMut> Ancestors<Find, Predicate, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    Predicate: FnMut(&oid) -> bool,
+    StateMut: BorrowMut<State>,
+{
+    fn next_topological(&mut self) -> Option<Result<ObjectId, Error>> {
+        let state = self.state.borrow_mut();
+        if !self.graph_built {
+            self.graph_built = true;
+            if let Err(err) = state.build_graph(
+                &mut self.find,
+                self.source.as_deref(),
+                self.discover.as_ref(),
+                &mut self.predicate,
+                self.sorting,
+                self.first_parent_only,
+                self.max_depth,
+            ) {
+                return Some(Err(err));
+            }
+        }
+        let oid = match self.sorting {
+            Sorting::TopologicalByCommitTime => state.queue.pop().map(|(_time, _order, oid)| oid),
+            _ => state.ready.pop(),
+        }?;
+        let parents = std::mem::take(
+            &mut state
+                .graph
+                .get_mut(&oid)
+                .expect("returned commits are part of the graph")
+                .parents,
+        );
+        for parent in parents {
+            let node = match state.graph.get_mut(&parent) {
+                Some(node) => node,
+                None => continue,
+            };
+            node.pending_children -= 1;
+            if node.pending_children == 0 {
+                match self.sorting {
+                    Sorting::TopologicalByCommitTime => {
+                        match commit_time(&parent, &mut state.buf, &mut self.find, self.source.as_deref()) {
+                            Ok(time) => state.push_by_time(parent, time),
+                            Err(err) => return Some(Err(err)),
+                        }
+                    }
+                    _ => state.ready.push(parent),
+                }
+            }
+        }
+        Some(Ok(oid))
+    }
+
+    fn next_commit(&mut self) -> Option<Result<ObjectId, Error>> {
+        let state = self.state.borrow_mut();
+        if self.compact_seen {
+            self.compact_seen = false;
+            if let Some(source) = self.source.as_deref().filter(|source| source.num_positions() > 0) {
+                state.compact_seen(source);
+            }
+        }
+        if !state.hidden_tips.is_empty() {
+            if let Err(err) = state.mark_hidden(&mut self.find, self.source.as_deref(), self.first_parent_only) {
+                return Some(Err(err));
+            }
+        }
+        if matches!(self.sorting, Sorting::Topological | Sorting::TopologicalByCommitTime) {
+            return self.next_topological();
+        }
+        if self.sorting == Sorting::ByCommitTimeNewestFirst {
+            // The tips are placed in `next` upon creation and need their commit time before they can be queued.
+            while let Some(tip) = state.next.pop_front() {
+                match commit_time(&tip, &mut state.buf, &mut self.find, self.source.as_deref()) {
+                    Ok(time) => state.push_by_time(tip, time),
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+        }
+        let res = loop {
+            let next = match self.sorting {
+                Sorting::BreadthFirst => state.next.pop_front(),
+                Sorting::ByCommitTimeNewestFirst => state.queue.pop().map(|(_time, _order, oid)| oid),
+                Sorting::Topological | Sorting::TopologicalByCommitTime => {
+                    unreachable!("topological sorting is handled separately")
+                }
+            };
+            match next {
+                Some(oid) if state.hidden.contains(&oid) => continue,
+                res => break res,
+            }
+        };
+        if let Some(oid) = res.filter(|oid| !state.shallow.contains(oid)) {
+            let mut parents = std::mem::take(&mut state.parents);
+            let discover = match self.sorting {
+                Sorting::BreadthFirst => self.discover.as_ref(),
+                _ => None,
+            };
+            let res = state.parents_of(&oid, &mut self.find, self.source.as_deref(), discover, &mut parents);
+            state.parents = parents;
+            if let Err(err) = res {
+                return Some(Err(err));
+            }
+            if self.first_parent_only {
+                state.parents.truncate(1);
+            }
+            let mut parents = std::mem::take(&mut state.parents);
+            let parents_are_too_deep = state.parents_are_too_deep(&oid, &parents, self.max_depth);
+            for id in parents.drain(..) {
+                if state.is_below_cutoff(&id, self.source.as_deref()) {
+                    continue;
+                }
+                if parents_are_too_deep || state.hidden.contains(&id) {
+                    if state.insert_seen(id, self.source.as_deref()) {
+                        state.boundary.push(id);
+                    }
+                    continue;
+                }
+                let was_inserted = state.insert_seen(id, self.source.as_deref());
+                if was_inserted && (self.predicate)(&id) {
+                    match self.sorting {
+                        Sorting::BreadthFirst => state.next.push_back(id),
+                        Sorting::ByCommitTimeNewestFirst => {
+                            match commit_time(&id, &mut state.buf, &mut self.find, self.source.as_deref()) {
+                                Ok(time) => state.push_by_time(id, time),
+                                Err(err) => return Some(Err(err)),
+                            }
+                        }
+                        Sorting::Topological | Sorting::TopologicalByCommitTime => {
+                            unreachable!("topological sorting is handled separately")
+                        }
+                    }
+                }
+            }
+            state.parents = parents;
+        }
+        res.map(Ok)
+    }
+}
+
+impl<Find, Predicate, StateMut> Iterator for Ancestors<Find, Predicate, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    Predicate: FnMut(&oid) -> bool,
+    StateMut: BorrowMut<State>,
+{
+    type Item = Result<ObjectId, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.max_count == Some(0) {
+            return None;
+        }
+        while self.skip > 0 {
+            self.skip -= 1;
+            if let Err(err) = self.next_commit()? {
+                return Some(Err(err));
+            }
+        }
+        let res = self.next_commit();
+        if let (Some(Ok(_)), Some(count)) = (&res, self.max_count.as_mut()) {
+            *count -= 1;
+        }
+        res
+    }
+}
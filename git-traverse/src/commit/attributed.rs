@@ -0,0 +1,198 @@
+use git_hash::{oid, ObjectId};
+use git_object::immutable;
+use quick_error::quick_error;
+use std::{
+    borrow::BorrowMut,
+    collections::{BTreeMap, VecDeque},
+};
+
+/// The maximum amount of tips that can be told apart, one per bit of [`Info::tips`].
+pub const MAX_TIPS: usize = 64;
+
+quick_error! {
+    /// The error returned when instantiating or iterating an [Attributed] traversal.
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        TooManyTips{count: usize} {
+            display("Can only attribute commits to up to {} tips, got {}", MAX_TIPS, count)
+        }
+        NotFound{oid: ObjectId} {
+            display("The commit {} could not be found", oid)
+        }
+        ObjectDecode(err: immutable::object::decode::Error) {
+            display("An object could not be decoded")
+            source(err)
+            from()
+        }
+    }
+}
+
+/// A commit along with the tips it can be reached from.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Info {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// A bitmask with bit `n` set if the commit is reachable from the `n`th tip, in the order they were passed to [`Attributed::new()`].
+    pub tips: u64,
+}
+
+struct Node {
+    parents: Vec<ObjectId>,
+    pending_children: usize,
+    tips: u64,
+}
+
+/// The state used and potentially shared by multiple graph traversals.
+#[derive(Default)]
+pub struct State {
+    tips: Vec<ObjectId>,
+    graph: BTreeMap<ObjectId, Node>,
+    ready: VecDeque<ObjectId>,
+    buf: Vec<u8>,
+}
+
+impl State {
+    fn clear(&mut self) {
+        self.tips.clear();
+        self.graph.clear();
+        self.ready.clear();
+        self.buf.clear();
+    }
+
+    /// Learn the graph reachable from all tips and count the children of each commit within it.
+    fn build_graph<Find>(&mut self, find: &mut Find) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    {
+        let mut queue = VecDeque::new();
+        let tips = std::mem::take(&mut self.tips);
+        for (bit, tip) in tips.iter().copied().enumerate() {
+            let node = self.graph.entry(tip).or_insert_with(|| {
+                queue.push_back(tip);
+                Node {
+                    parents: Vec::new(),
+                    pending_children: 0,
+                    tips: 0,
+                }
+            });
+            node.tips |= 1 << bit;
+        }
+        while let Some(oid) = queue.pop_front() {
+            let mut parents = Vec::new();
+            {
+                let mut commit_iter = find(&oid, &mut self.buf).ok_or(Error::NotFound { oid })?;
+                if let Some(Err(decode_tree_err)) = commit_iter.next() {
+                    return Err(decode_tree_err.into());
+                }
+                for token in commit_iter {
+                    match token {
+                        Ok(immutable::commit::iter::Token::Parent { id }) => parents.push(id),
+                        Ok(_a_token_past_the_parents) => break,
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+            }
+            for parent in &parents {
+                let parent_node = self.graph.entry(*parent).or_insert_with(|| {
+                    queue.push_back(*parent);
+                    Node {
+                        parents: Vec::new(),
+                        pending_children: 0,
+                        tips: 0,
+                    }
+                });
+                parent_node.pending_children += 1;
+            }
+            self.graph.get_mut(&oid).expect("inserted before queueing").parents = parents;
+        }
+        for tip in tips {
+            let is_reachable_from_another_tip = self.graph[&tip].pending_children != 0;
+            if !is_reachable_from_another_tip && !self.ready.contains(&tip) {
+                self.ready.push_back(tip);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the ancestors of multiple tips which reports for each commit the tips it is reachable from,
+/// similar to `git show-branch`.
+///
+/// Commits are returned in topological order, that is each commit is returned only after all of its children that are
+/// reachable from the tips, which is when the set of tips reaching it is known. To achieve this, the whole graph reachable
+/// from all tips is learned on the first call to `next()`.
+pub struct Attributed<Find, StateMut> {
+    find: Find,
+    state: StateMut,
+    graph_built: bool,
+}
+
+impl<Find, StateMut> Attributed<Find, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    StateMut: BorrowMut<State>,
+{
+    /// Create a new instance.
+    ///
+    /// * `tips` - the starting points of the traversal, with the `n`th tip being represented by bit `n` in [`Info::tips`].
+    ///    There may be no more than [`MAX_TIPS`] of them.
+    /// * `state` - all state used for the traversal. If multiple traversals are performed, allocations can be minimized by reusing
+    ///   this state.
+    /// * `find` - a way to lookup new object data during traversal by their ObjectId, writing their data into buffer and returning
+    ///    an iterator over commit tokens if the object is present and is a commit, see [`Ancestors::new()`][super::Ancestors::new()].
+    pub fn new(
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        mut state: StateMut,
+        find: Find,
+    ) -> Result<Self, Error> {
+        {
+            let state = state.borrow_mut();
+            state.clear();
+            state.tips.extend(tips.into_iter().map(Into::into));
+            if state.tips.len() > MAX_TIPS {
+                return Err(Error::TooManyTips {
+                    count: state.tips.len(),
+                });
+            }
+        }
+        Ok(Attributed {
+            find,
+            state,
+            graph_built: false,
+        })
+    }
+}
+
+impl<Find, StateMut> Iterator for Attributed<Find, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    StateMut: BorrowMut<State>,
+{
+    type Item = Result<Info, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let state = self.state.borrow_mut();
+        if !self.graph_built {
+            self.graph_built = true;
+            if let Err(err) = state.build_graph(&mut self.find) {
+                state.clear();
+                return Some(Err(err));
+            }
+        }
+        let id = state.ready.pop_front()?;
+        let (tips, parents) = {
+            let node = state.graph.get_mut(&id).expect("ready commits are part of the graph");
+            (node.tips, std::mem::take(&mut node.parents))
+        };
+        for parent in parents {
+            let parent_node = state.graph.get_mut(&parent).expect("parents are part of the graph");
+            parent_node.tips |= tips;
+            parent_node.pending_children -= 1;
+            if parent_node.pending_children == 0 {
+                state.ready.push_back(parent);
+            }
+        }
+        Some(Ok(Info { id, tips }))
+    }
+}
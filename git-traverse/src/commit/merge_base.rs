@@ -0,0 +1,249 @@
+use git_hash::{oid, ObjectId};
+use git_object::immutable;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+};
+
+use super::ancestors::{collect_parents, commit_time, CommitSource};
+
+/// The error returned by merge-base computations, which is the one of [`Ancestors`][super::Ancestors] as they look up commits
+/// in the same way.
+pub use super::ancestors::Error;
+
+/// The commit is reachable from the first commit.
+const PARENT1: u8 = 1;
+/// The commit is reachable from one of the other commits.
+const PARENT2: u8 = 1 << 1;
+/// The commit is reachable from a merge-base and thus not a merge-base itself.
+const STALE: u8 = 1 << 2;
+/// The commit is a merge-base candidate.
+const RESULT: u8 = 1 << 3;
+
+/// The generation number of commits unknown to the commit source, which are newer than all commits known to it.
+const GENERATION_NUMBER_INFINITY: u32 = u32::MAX;
+
+/// Return the best common ancestor of the commits `first` and `second` for use in a three-way merge, like `git merge-base`
+/// does, or `None` if they don't share any history.
+///
+/// If there are multiple merge-bases, the one with the newest commit time is returned. See [`merge_bases()`] for details on
+/// the parameters.
+pub fn merge_base<Find>(
+    first: impl Into<ObjectId>,
+    second: impl Into<ObjectId>,
+    source: Option<&dyn CommitSource>,
+    find: Find,
+) -> Result<Option<ObjectId>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+{
+    Ok(merge_bases(first, Some(second), source, find)?.into_iter().next())
+}
+
+/// Return all best common ancestors of the commit `first` and a hypothetical merge commit of all `others`, newest first,
+/// like `git merge-base --all` does. The result is empty if they don't share any history.
+///
+/// A best common ancestor is a common ancestor which isn't reachable from any other common ancestor, so there is typically
+/// only one, except for histories with criss-cross merges.
+///
+/// * `source` - a [commit source][CommitSource] like a commit-graph to obtain parents, commit times and generation numbers
+///   from instead of looking up commits with `find`. Generation numbers allow to stop walking commits which can't reach
+///   any of the commits of interest, which is what makes removing redundant merge-bases fast.
+/// * `find` - a way to lookup new object data during traversal by their ObjectId, writing their data into buffer and returning
+///   an iterator over commit tokens if the object is present and is a commit. Not finding a commit is an error.
+///
+/// Commits are walked by generation number and commit time, newest first, and marked with the tips they are reachable from until only commits
+/// reachable from all of them remain, which is known as painting down to the common ancestors. Just like in git, commits
+/// with a commit time that is far older than the one of their ancestors may lead to additional merge-bases.
+pub fn merge_bases<Find>(
+    first: impl Into<ObjectId>,
+    others: impl IntoIterator<Item = impl Into<ObjectId>>,
+    source: Option<&dyn CommitSource>,
+    mut find: Find,
+) -> Result<Vec<ObjectId>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+{
+    let others: Vec<_> = others.into_iter().map(Into::into).collect();
+    bases(first.into(), &others, source, &mut find, &mut Vec::new())
+}
+
+/// Return all best common ancestors of all `commits`, newest first, like `git merge-base --octopus --all` does, which are
+/// the merge-bases to use when merging all of them at once. The result is empty if they don't share any history.
+///
+/// See [`merge_bases()`] for details on the parameters.
+pub fn octopus<Find>(
+    commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+    source: Option<&dyn CommitSource>,
+    mut find: Find,
+) -> Result<Vec<ObjectId>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+{
+    let mut commits = commits.into_iter().map(Into::into);
+    let mut result = match commits.next() {
+        Some(first) => vec![first],
+        None => return Ok(Vec::new()),
+    };
+    let mut buf = Vec::new();
+    for commit in commits {
+        let mut next = Vec::new();
+        for base in &result {
+            for id in bases(*base, &[commit], source, &mut find, &mut buf)? {
+                if !next.contains(&id) {
+                    next.push(id);
+                }
+            }
+        }
+        result = next;
+    }
+    Ok(result)
+}
+
+fn bases<Find>(
+    first: ObjectId,
+    others: &[ObjectId],
+    source: Option<&dyn CommitSource>,
+    find: &mut Find,
+    buf: &mut Vec<u8>,
+) -> Result<Vec<ObjectId>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+{
+    if others.contains(&first) {
+        return Ok(vec![first]);
+    }
+    let (candidates, flags) = paint_down_to_common(&first, others, 0, source, find, buf)?;
+    let mut candidates: Vec<_> = candidates
+        .into_iter()
+        .filter(|(_time, id)| flags[id] & STALE == 0)
+        .collect();
+    if candidates.len() > 1 {
+        remove_redundant(&mut candidates, source, find, buf)?;
+    }
+    candidates.sort_by_key(|(time, _id)| Reverse(*time));
+    Ok(candidates.into_iter().map(|(_time, id)| id).collect())
+}
+
+/// Walk the ancestors of `first` and `others` by generation number and commit time, newest first, and mark them with the
+/// tips they are reachable from until only commits reachable from common ancestors remain. Return the common ancestors
+/// found along with their commit time in order of discovery, along with the marks of all walked commits.
+///
+/// The walk stops at the first commit with a generation number lower than `min_generation`, as it can't reach any commit
+/// with a generation number of at least `min_generation`. Generation numbers and commit times are taken from `source` if
+/// it knows the commit.
+#[allow(clippy::type_complexity)]
+fn paint_down_to_common<Find>(
+    first: &oid,
+    others: &[ObjectId],
+    min_generation: u32,
+    source: Option<&dyn CommitSource>,
+    find: &mut Find,
+    buf: &mut Vec<u8>,
+) -> Result<(Vec<(u64, ObjectId)>, BTreeMap<ObjectId, u8>), Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+{
+    let mut flags = BTreeMap::<ObjectId, u8>::new();
+    let mut queue = BinaryHeap::new();
+    let mut num_queued = 0u64;
+    for (id, flag) in std::iter::once((first, PARENT1)).chain(others.iter().map(|id| (id.as_ref(), PARENT2))) {
+        *flags.entry(id.to_owned()).or_default() |= flag;
+        queue.push((
+            generation(id, source),
+            commit_time(id, buf, find, source)?,
+            Reverse(num_queued),
+            id.to_owned(),
+        ));
+        num_queued += 1;
+    }
+
+    let mut result = Vec::new();
+    let mut parents = Vec::new();
+    while queue
+        .iter()
+        .any(|(_generation, _time, _order, id)| flags[id] & STALE == 0)
+    {
+        let (generation_number, time, _order, id) = queue.pop().expect("non-empty queue");
+        if generation_number < min_generation {
+            break;
+        }
+        let mut marks = flags[&id] & (PARENT1 | PARENT2 | STALE);
+        if marks & (PARENT1 | PARENT2) == PARENT1 | PARENT2 {
+            let id_flags = flags.get_mut(&id).expect("queued commits are marked");
+            if *id_flags & RESULT == 0 {
+                *id_flags |= RESULT;
+                result.push((time, id));
+            }
+            marks |= STALE;
+        }
+        collect_parents(&id, buf, find, source, &mut parents)?;
+        for parent in parents.drain(..) {
+            let parent_flags = flags.entry(parent).or_default();
+            if *parent_flags & marks == marks {
+                continue;
+            }
+            *parent_flags |= marks;
+            queue.push((
+                generation(&parent, source),
+                commit_time(&parent, buf, find, source)?,
+                Reverse(num_queued),
+                parent,
+            ));
+            num_queued += 1;
+        }
+    }
+    Ok((result, flags))
+}
+
+/// Return the generation number of the commit `id` if `source` knows it, or [`GENERATION_NUMBER_INFINITY`] otherwise.
+fn generation(id: &oid, source: Option<&dyn CommitSource>) -> u32 {
+    source
+        .and_then(|source| source.generation(id))
+        .unwrap_or(GENERATION_NUMBER_INFINITY)
+}
+
+/// Remove all `candidates` which are reachable from other candidates.
+///
+/// Each candidate is walked along with all others, but only down to the lowest generation number of the others as no
+/// commit below it can reach them. Candidates which are reachable from others despite having a lower generation number are
+/// found once they are among the others.
+fn remove_redundant<Find>(
+    candidates: &mut Vec<(u64, ObjectId)>,
+    source: Option<&dyn CommitSource>,
+    find: &mut Find,
+    buf: &mut Vec<u8>,
+) -> Result<(), Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+{
+    let mut redundant = vec![false; candidates.len()];
+    for index in 0..candidates.len() {
+        if redundant[index] {
+            continue;
+        }
+        let others: Vec<_> = candidates
+            .iter()
+            .enumerate()
+            .filter(|(other_index, _)| *other_index != index && !redundant[*other_index])
+            .map(|(_, (_time, id))| *id)
+            .collect();
+        let min_generation = others
+            .iter()
+            .map(|id| generation(id, source))
+            .min()
+            .unwrap_or(GENERATION_NUMBER_INFINITY);
+        let (_, flags) = paint_down_to_common(&candidates[index].1, &others, min_generation, source, find, buf)?;
+        if flags[&candidates[index].1] & PARENT2 != 0 {
+            redundant[index] = true;
+        }
+        for (other_index, (_time, id)) in candidates.iter().enumerate() {
+            if other_index != index && flags.get(id).map_or(false, |flags| flags & PARENT1 != 0) {
+                redundant[other_index] = true;
+            }
+        }
+    }
+    let mut redundant = redundant.into_iter();
+    candidates.retain(|_| !redundant.next().expect("one flag per candidate"));
+    Ok(())
+}
@@ -0,0 +1,19 @@
+///
+pub mod ancestors;
+#[doc(inline)]
+pub use ancestors::Ancestors;
+
+///
+pub mod attributed;
+#[doc(inline)]
+pub use attributed::Attributed;
+
+///
+pub mod simplified;
+#[doc(inline)]
+pub use simplified::Simplified;
+
+///
+pub mod merge_base;
+#[doc(inline)]
+pub use merge_base::merge_base;
@@ -0,0 +1,375 @@
+use git_hash::{oid, ObjectId};
+use git_object::{bstr::BString, immutable, mutable};
+use quick_error::quick_error;
+use std::{
+    borrow::BorrowMut,
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+};
+
+quick_error! {
+    /// The error returned when iterating a [Simplified] traversal.
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        NotFound{oid: ObjectId} {
+            display("The commit {} could not be found", oid)
+        }
+        MissingTree{oid: ObjectId} {
+            display("The commit {} has no tree", oid)
+        }
+        ObjectDecode(err: immutable::object::decode::Error) {
+            display("An object could not be decoded")
+            source(err)
+            from()
+        }
+        Lookup(err: immutable::tree::lookup::Error) {
+            display("An entry of a tree could not be looked up")
+            source(err)
+            from()
+        }
+    }
+}
+
+/// A commit changing the paths of a [Simplified] traversal along with its rewritten parents.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Info {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The closest ancestors changing the paths as well, in the order of the parents they were reached through and without
+    /// duplicates, like `git rev-list --parents -- <paths>` prints them.
+    pub parents: Vec<ObjectId>,
+}
+
+struct Node {
+    tree: ObjectId,
+    commit_time: u64,
+    parents: Vec<ObjectId>,
+    /// The parents whose history is followed, or `None` if the commit wasn't reached yet.
+    followed: Option<Vec<ObjectId>>,
+    changes_paths: bool,
+    /// The closest commits changing the paths reachable through the followed parents, or `None` if not yet known.
+    simplified: Option<Vec<ObjectId>>,
+    pending_children: usize,
+}
+
+/// The state used and potentially shared by multiple graph traversals.
+#[derive(Default)]
+pub struct State {
+    tips: Vec<ObjectId>,
+    paths: Vec<BString>,
+    graph: BTreeMap<ObjectId, Node>,
+    entries: BTreeMap<ObjectId, Vec<Option<mutable::tree::Entry>>>,
+    queue: BinaryHeap<(u64, Reverse<u64>, ObjectId)>,
+    num_queued: u64,
+    buf: Vec<u8>,
+    tree_buf: Vec<u8>,
+}
+
+impl State {
+    fn clear(&mut self) {
+        self.tips.clear();
+        self.paths.clear();
+        self.graph.clear();
+        self.entries.clear();
+        self.queue.clear();
+        self.num_queued = 0;
+        self.buf.clear();
+        self.tree_buf.clear();
+    }
+
+    fn push_by_time(&mut self, id: ObjectId) {
+        let commit_time = self.graph[&id].commit_time;
+        self.queue.push((commit_time, Reverse(self.num_queued), id));
+        self.num_queued += 1;
+    }
+
+    /// Decode the commit `id` into the graph unless it is already known.
+    fn load<Find>(&mut self, id: ObjectId, find: &mut Find) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    {
+        if self.graph.contains_key(&id) {
+            return Ok(());
+        }
+        let mut tree = None;
+        let mut parents = Vec::new();
+        let mut commit_time = None;
+        let mut decode_error = None;
+        for token in find(&id, &mut self.buf).ok_or(Error::NotFound { oid: id })? {
+            match token {
+                Ok(immutable::commit::iter::Token::Tree { id }) => tree = Some(id),
+                Ok(immutable::commit::iter::Token::Parent { id }) => parents.push(id),
+                Ok(immutable::commit::iter::Token::Committer { signature }) => {
+                    commit_time = Some(signature.time.time as u64);
+                    break;
+                }
+                Ok(_) => continue,
+                Err(err) => {
+                    decode_error = Some(err);
+                    break;
+                }
+            }
+        }
+        let commit_time = match decode_error {
+            Some(err) => crate::commit::ancestors::overflowing_committer_time(&self.buf).ok_or(err)?,
+            None => commit_time.unwrap_or(0),
+        };
+        self.graph.insert(
+            id,
+            Node {
+                tree: tree.ok_or(Error::MissingTree { oid: id })?,
+                commit_time,
+                parents,
+                followed: None,
+                changes_paths: false,
+                simplified: None,
+                pending_children: 0,
+            },
+        );
+        Ok(())
+    }
+
+    /// Return true if `tree` has the same entries at all paths as `parent_tree`, with `None` being the empty tree of
+    /// root commits.
+    fn is_treesame<FindTree>(
+        &mut self,
+        parent_tree: Option<ObjectId>,
+        tree: ObjectId,
+        find_tree: &mut FindTree,
+    ) -> Result<bool, Error>
+    where
+        FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+    {
+        if parent_tree == Some(tree) {
+            return Ok(true);
+        }
+        if self.paths.is_empty() {
+            return Ok(parent_tree.is_none() && tree == ObjectId::empty_tree(tree.kind()));
+        }
+        self.lookup_entries(tree, find_tree)?;
+        Ok(match parent_tree {
+            Some(parent_tree) => {
+                self.lookup_entries(parent_tree, find_tree)?;
+                self.entries[&tree] == self.entries[&parent_tree]
+            }
+            None => self.entries[&tree].iter().all(Option::is_none),
+        })
+    }
+
+    fn lookup_entries<FindTree>(&mut self, tree: ObjectId, find_tree: &mut FindTree) -> Result<(), Error>
+    where
+        FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+    {
+        if self.entries.contains_key(&tree) {
+            return Ok(());
+        }
+        let mut entries = Vec::with_capacity(self.paths.len());
+        for path in &self.paths {
+            let root = find_tree(&tree, &mut self.buf).ok_or_else(|| immutable::tree::lookup::Error::FindTree {
+                path: BString::default(),
+                id: tree,
+            })?;
+            entries.push(root.lookup_entry(path, &mut self.tree_buf, &mut *find_tree)?);
+        }
+        self.entries.insert(tree, entries);
+        Ok(())
+    }
+
+    /// Learn the simplified graph reachable from all tips, following only the first TREESAME parent of merges that have one,
+    /// and rewrite the parents of all commits changing the paths to the closest ancestors changing them as well.
+    fn build_graph<Find, FindTree>(&mut self, find: &mut Find, find_tree: &mut FindTree) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+        FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+    {
+        let mut queue = self.tips.clone();
+        while let Some(id) = queue.pop() {
+            self.load(id, find)?;
+            let node = &self.graph[&id];
+            if node.followed.is_some() {
+                continue;
+            }
+            let (tree, parents) = (node.tree, node.parents.clone());
+            let (followed, changes_paths) = if parents.is_empty() {
+                (Vec::new(), !self.is_treesame(None, tree, find_tree)?)
+            } else {
+                let mut treesame_parent = None;
+                for parent in &parents {
+                    self.load(*parent, find)?;
+                    let parent_tree = self.graph[parent].tree;
+                    if self.is_treesame(Some(parent_tree), tree, find_tree)? {
+                        treesame_parent = Some(*parent);
+                        break;
+                    }
+                }
+                match treesame_parent {
+                    Some(parent) => (vec![parent], false),
+                    None => (parents, true),
+                }
+            };
+            queue.extend(followed.iter().rev().copied());
+            let node = self.graph.get_mut(&id).expect("loaded above");
+            node.followed = Some(followed);
+            node.changes_paths = changes_paths;
+        }
+
+        // Parents are simplified before their children, without recursion to support long histories.
+        let mut stack: Vec<_> = self.tips.iter().map(|tip| (*tip, false)).collect();
+        while let Some((id, parents_are_simplified)) = stack.pop() {
+            let node = &self.graph[&id];
+            if node.simplified.is_some() {
+                continue;
+            }
+            let followed = node.followed.as_ref().expect("all reachable commits were followed");
+            if parents_are_simplified {
+                let mut simplified = Vec::new();
+                for parent in followed {
+                    for id in self.simplified_as(parent) {
+                        if !simplified.contains(id) {
+                            simplified.push(*id);
+                        }
+                    }
+                }
+                self.graph.get_mut(&id).expect("present").simplified = Some(simplified);
+            } else {
+                stack.push((id, true));
+                stack.extend(
+                    followed
+                        .iter()
+                        .rev()
+                        .filter(|parent| self.graph[*parent].simplified.is_none())
+                        .map(|parent| (*parent, false)),
+                );
+            }
+        }
+
+        let mut children_of = Vec::new();
+        for node in self.graph.values() {
+            if node.changes_paths && node.followed.is_some() {
+                children_of.extend(node.simplified.iter().flatten().copied());
+            }
+        }
+        for id in children_of {
+            self.graph.get_mut(&id).expect("present").pending_children += 1;
+        }
+        for tip in std::mem::take(&mut self.tips) {
+            let ready: Vec<_> = self
+                .simplified_as(&tip)
+                .iter()
+                .copied()
+                .filter(|id| {
+                    self.graph[id].pending_children == 0 && !self.queue.iter().any(|(_, _, queued)| queued == id)
+                })
+                .collect();
+            for id in ready {
+                self.push_by_time(id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the commits changing the paths that the simplified commit `id` is represented by, which is itself if it changes
+    /// them.
+    fn simplified_as<'a>(&'a self, id: &'a ObjectId) -> &'a [ObjectId] {
+        let node = &self.graph[id];
+        if node.changes_paths {
+            std::slice::from_ref(id)
+        } else {
+            node.simplified
+                .as_deref()
+                .expect("parents are simplified before their children")
+        }
+    }
+}
+
+/// An iterator over the ancestors of the given tips which only returns commits changing the given paths, similar to
+/// `git rev-list --date-order --parents -- <paths>`.
+///
+/// Like git's default history simplification, a commit is returned if it is not TREESAME to its parent, that is if the
+/// entries at the paths differ. A merge which is TREESAME to any of its parents is not returned, and only the history of the
+/// first of those parents is followed. The parents of returned commits are rewritten to be their closest ancestors which
+/// are returned as well.
+///
+/// Commits are returned newest first by commit time, but never before all of their children. To achieve this, the whole
+/// simplified graph is learned on the first call to `next()`.
+pub struct Simplified<Find, FindTree, StateMut> {
+    find: Find,
+    find_tree: FindTree,
+    state: StateMut,
+    graph_built: bool,
+}
+
+impl<Find, FindTree, StateMut> Simplified<Find, FindTree, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+    StateMut: BorrowMut<State>,
+{
+    /// Create a new instance.
+    ///
+    /// * `tips` - the starting points of the iteration.
+    /// * `paths` - slash-separated paths relative to the root of the repository, like `src` or `src/lib.rs`, whose entries
+    ///   are compared to determine if a commit changes them. Without any paths, all commits changing their tree are returned.
+    /// * `state` - all state used for the traversal. If multiple traversals are performed, allocations can be minimized by reusing
+    ///   this state.
+    /// * `find` - a way to lookup new object data during traversal by their ObjectId, writing their data into buffer and returning
+    ///   an iterator over commit tokens if the object is present and is a commit, see [`Ancestors::new()`][super::Ancestors::new()].
+    /// * `find_tree` - the same as `find`, but returning an iterator over tree entries if the object is a tree.
+    pub fn new(
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        paths: impl IntoIterator<Item = impl Into<BString>>,
+        mut state: StateMut,
+        find: Find,
+        find_tree: FindTree,
+    ) -> Self {
+        {
+            let state = state.borrow_mut();
+            state.clear();
+            state.tips.extend(tips.into_iter().map(Into::into));
+            state.paths.extend(paths.into_iter().map(Into::into));
+        }
+        Simplified {
+            find,
+            find_tree,
+            state,
+            graph_built: false,
+        }
+    }
+}
+
+impl<Find, FindTree, StateMut> Iterator for Simplified<Find, FindTree, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+    StateMut: BorrowMut<State>,
+{
+    type Item = Result<Info, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let state = self.state.borrow_mut();
+        if !self.graph_built {
+            self.graph_built = true;
+            if let Err(err) = state.build_graph(&mut self.find, &mut self.find_tree) {
+                state.clear();
+                return Some(Err(err));
+            }
+        }
+        let (_time, _order, id) = state.queue.pop()?;
+        let parents = state
+            .graph
+            .get_mut(&id)
+            .expect("queued commits are part of the graph")
+            .simplified
+            .take()
+            .expect("returned commits are simplified");
+        for parent in &parents {
+            let node = state.graph.get_mut(parent).expect("parents are part of the graph");
+            node.pending_children -= 1;
+            if node.pending_children == 0 {
+                state.push_by_time(*parent);
+            }
+        }
+        Some(Ok(Info { id, parents }))
+    }
+}
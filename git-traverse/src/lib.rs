@@ -7,3 +7,6 @@ pub mod commit;
 
 /// Tree traversal
 pub mod tree;
+
+/// Traversal of all objects reachable from commits, tags and trees
+pub mod objects;
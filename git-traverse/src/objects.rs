@@ -0,0 +1,249 @@
+use git_hash::{oid, ObjectId};
+use git_object::{immutable, tree::EntryMode, Kind};
+use quick_error::quick_error;
+use std::{
+    borrow::BorrowMut,
+    collections::{BTreeSet, VecDeque},
+};
+
+quick_error! {
+    /// The error is part of the item returned by the [Reachable] iterator.
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        NotFound{oid: ObjectId} {
+            display("The object {} could not be found", oid)
+        }
+        KindMismatch{oid: ObjectId, expected: Kind, actual: Kind} {
+            display("The object {} was expected to be a {} as claimed by the object referring to it, but is a {}", oid, expected, actual)
+        }
+        ObjectDecode(err: immutable::object::decode::Error) {
+            display("An object could not be decoded")
+            source(err)
+            from()
+        }
+    }
+}
+
+/// An object returned by the [Reachable] iterator.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Info {
+    /// The id of the object.
+    pub id: ObjectId,
+    /// The kind of the object, which for blobs that aren't [looked up][Reachable::with_blob_lookup()] is the one implied
+    /// by the tree entry referring to it.
+    pub kind: Kind,
+}
+
+/// The state used and potentially shared by multiple traversals.
+#[derive(Default, Clone)]
+pub struct State {
+    next: VecDeque<(ObjectId, Option<Kind>)>,
+    buf: Vec<u8>,
+    /// One bit per object position, set if the object at this position was seen.
+    seen_positions: Vec<u64>,
+    /// Seen objects without a position.
+    seen: BTreeSet<ObjectId>,
+}
+
+impl State {
+    fn clear(&mut self) {
+        self.next.clear();
+        self.buf.clear();
+        self.seen_positions.clear();
+        self.seen.clear();
+    }
+
+    /// Remember `id` at `position` as seen and return true if it wasn't seen before.
+    fn insert(&mut self, id: ObjectId, position: Option<usize>) -> bool {
+        match position.filter(|pos| *pos < self.seen_positions.len() * 64) {
+            Some(pos) => {
+                let (word, bit) = (&mut self.seen_positions[pos / 64], 1 << (pos % 64));
+                let was_seen = *word & bit != 0;
+                *word |= bit;
+                !was_seen
+            }
+            None => self.seen.insert(id),
+        }
+    }
+}
+
+/// An iterator over all objects reachable from the given tips, which are commits, tags, trees or blobs, returning each
+/// object exactly once, similar to `git rev-list --objects`.
+///
+/// Commits lead to their root tree and their parents, trees lead to their entries and tags lead to their target. Submodules
+/// are not followed, as the commits they refer to are not part of the repository. Objects are returned in breadth-first
+/// order as they are discovered, which makes this the basis for generating packs, checking connectivity and finding
+/// unreachable objects.
+///
+/// Objects which are looked up must be of the kind claimed by the object referring to them, like a tree entry or the target
+/// of a tag, or [`Error::KindMismatch`] is returned.
+pub struct Reachable<Find, StateMut, Position> {
+    find: Find,
+    state: StateMut,
+    position: Position,
+    lookup_blobs: bool,
+}
+
+impl<Find, StateMut> Reachable<Find, StateMut, fn(&oid) -> Option<usize>>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<(Kind, &'a [u8])>,
+    StateMut: BorrowMut<State>,
+{
+    /// Create a new instance.
+    ///
+    /// * `tips` - the objects to start the traversal at, which are returned as well.
+    /// * `state` - all state used for the traversal. If multiple traversals are performed, allocations can be minimized by reusing
+    ///   this state.
+    /// * `find` - a way to lookup new object data during traversal by their ObjectId, writing their data into buffer and returning
+    ///   the kind of the object along with its data if it is present. Not finding an object is an error as all reachable objects
+    ///   are expected to be present in the database, so [`Error::NotFound`] should be escalated into a more specific error if
+    ///   it's encountered by the caller.
+    pub fn new(tips: impl IntoIterator<Item = impl Into<ObjectId>>, mut state: StateMut, find: Find) -> Self {
+        {
+            let state = state.borrow_mut();
+            state.clear();
+            for tip in tips.into_iter().map(Into::into) {
+                if state.insert(tip, None) {
+                    state.next.push_back((tip, None));
+                }
+            }
+        }
+        Reachable {
+            find,
+            state,
+            position: |_| None,
+            lookup_blobs: false,
+        }
+    }
+}
+
+impl<Find, StateMut, Position> Reachable<Find, StateMut, Position>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<(Kind, &'a [u8])>,
+    StateMut: BorrowMut<State>,
+    Position: FnMut(&oid) -> Option<usize>,
+{
+    /// Use `position` to map object ids to a position smaller than `num_objects`, like the position of an object in a pack
+    /// index, to remember seen objects in a bitset which takes only a bit per object instead of storing their ids.
+    /// Objects without a position are remembered by their id.
+    ///
+    /// This is most useful if most objects of the repository are in a single pack, which is typical after running gc.
+    pub fn with_positions<NewPosition>(
+        mut self,
+        num_objects: usize,
+        mut position: NewPosition,
+    ) -> Reachable<Find, StateMut, NewPosition>
+    where
+        NewPosition: FnMut(&oid) -> Option<usize>,
+    {
+        {
+            let state = self.state.borrow_mut();
+            state.seen_positions.clear();
+            state.seen_positions.resize((num_objects + 63) / 64, 0);
+            for id in std::mem::take(&mut state.seen) {
+                state.insert(id, position(&id));
+            }
+        }
+        Reachable {
+            find: self.find,
+            state: self.state,
+            position,
+            lookup_blobs: self.lookup_blobs,
+        }
+    }
+
+    /// Look up all blobs as well to assure they are present, as needed to check connectivity. Otherwise blobs are returned
+    /// without being looked up, as they don't refer to other objects.
+    pub fn with_blob_lookup(mut self) -> Self {
+        self.lookup_blobs = true;
+        self
+    }
+}
+
+impl<Find, StateMut, Position> Reachable<Find, StateMut, Position>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<(Kind, &'a [u8])>,
+    StateMut: BorrowMut<State>,
+    Position: FnMut(&oid) -> Option<usize>,
+{
+    fn queue(&mut self, id: ObjectId, kind: Kind) {
+        let position = (self.position)(&id);
+        let state = self.state.borrow_mut();
+        if state.insert(id, position) {
+            state.next.push_back((id, Some(kind)));
+        }
+    }
+
+    /// Queue all objects referred to by the object `id` and return its kind, which must be the `expected_kind` if it is known.
+    fn expand(&mut self, id: ObjectId, expected_kind: Option<Kind>, buf: &mut Vec<u8>) -> Result<Kind, Error> {
+        let (kind, data) = (self.find)(&id, buf).ok_or(Error::NotFound { oid: id })?;
+        if let Some(expected) = expected_kind.filter(|expected| *expected != kind) {
+            return Err(Error::KindMismatch {
+                oid: id,
+                expected,
+                actual: kind,
+            });
+        }
+        match kind {
+            Kind::Commit => {
+                for token in immutable::CommitIter::from_bytes(data) {
+                    match token? {
+                        immutable::commit::iter::Token::Tree { id } => self.queue(id, Kind::Tree),
+                        immutable::commit::iter::Token::Parent { id } => self.queue(id, Kind::Commit),
+                        _a_token_past_the_parents => break,
+                    }
+                }
+            }
+            Kind::Tree => {
+                for entry in immutable::TreeIter::from_bytes(data) {
+                    let entry = entry?;
+                    match entry.mode {
+                        EntryMode::Tree => self.queue(entry.oid.to_owned(), Kind::Tree),
+                        EntryMode::Blob | EntryMode::BlobExecutable | EntryMode::Link => {
+                            self.queue(entry.oid.to_owned(), Kind::Blob)
+                        }
+                        EntryMode::Commit => {}
+                    }
+                }
+            }
+            Kind::Tag => {
+                let mut target = None;
+                for token in immutable::TagIter::from_bytes(data) {
+                    match token? {
+                        immutable::tag::iter::Token::Target { id } => target = Some(id),
+                        immutable::tag::iter::Token::TargetKind(kind) => {
+                            if let Some(target) = target.take() {
+                                self.queue(target, kind);
+                            }
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            Kind::Blob => {}
+        }
+        Ok(kind)
+    }
+}
+
+impl<Find, StateMut, Position> Iterator for Reachable<Find, StateMut, Position>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<(Kind, &'a [u8])>,
+    StateMut: BorrowMut<State>,
+    Position: FnMut(&oid) -> Option<usize>,
+{
+    type Item = Result<Info, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, expected_kind) = self.state.borrow_mut().next.pop_front()?;
+        if expected_kind == Some(Kind::Blob) && !self.lookup_blobs {
+            return Some(Ok(Info { id, kind: Kind::Blob }));
+        }
+        let mut buf = std::mem::take(&mut self.state.borrow_mut().buf);
+        let res = self.expand(id, expected_kind, &mut buf);
+        self.state.borrow_mut().buf = buf;
+        Some(res.map(|kind| Info { id, kind }))
+    }
+}
@@ -1,6 +1,6 @@
 use crate::tree::visit::Visit;
 use git_hash::{oid, ObjectId};
-use git_object::{immutable, tree};
+use git_object::{bstr::BString, immutable, pathspec::Pathspecs, tree};
 use quick_error::quick_error;
 use std::{borrow::BorrowMut, collections::VecDeque};
 
@@ -23,11 +23,29 @@ quick_error! {
     }
 }
 
+/// Options to limit a [`traverse_with_options()`] to the parts of a tree that are of interest.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// If set, trees at this depth are not traversed, with the entries of the root tree having a depth of 1.
+    pub max_depth: Option<usize>,
+    /// Only visit entries at or below one of these paths, like `src` or `src/lib.rs`, and don't traverse trees which can't
+    /// contain them.
+    ///
+    /// Pathspecs are paths relative to the root tree using slashes as separators and are matched literally, with trailing
+    /// slashes being ignored. Without pathspecs, which is the default, all entries are visited.
+    pub pathspecs: Vec<BString>,
+    /// If true, the trees leading up to the `pathspecs` are traversed without being visited, so only entries at or below
+    /// one of the `pathspecs` are passed to the delegate.
+    pub matching_only: bool,
+}
+
 /// The state used and potentially shared by multiple tree traversals.
 #[derive(Default, Clone)]
 pub struct State {
-    next: VecDeque<(bool, ObjectId)>,
+    next: VecDeque<(bool, ObjectId, usize)>,
     buf: Vec<u8>,
+    /// The pathspecs of the options, tracking the paths of the visited and scheduled trees. They are reset with each traversal.
+    pathspecs: Pathspecs,
 }
 
 impl State {
@@ -50,10 +68,27 @@ impl State {
 ///    be escalated into a more specific error if its encountered by the caller.
 /// * `delegate` - A way to observe entries and control the iteration while allowing the optimizer to let you pay only for what you use.
 pub fn traverse<StateMut, Find, V>(
+    root: immutable::TreeIter<'_>,
+    state: StateMut,
+    find: Find,
+    delegate: &mut V,
+) -> Result<(), Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+    StateMut: BorrowMut<State>,
+    V: Visit,
+{
+    traverse_with_options(root, state, find, delegate, &Options::default())
+}
+
+/// Like [`traverse()`], but limited to the entries selected by `options`, without looking up trees which can't contain
+/// any of them. This makes listing the files in a directory of a large tree very fast.
+pub fn traverse_with_options<StateMut, Find, V>(
     root: immutable::TreeIter<'_>,
     mut state: StateMut,
     mut find: Find,
     delegate: &mut V,
+    options: &Options,
 ) -> Result<(), Error>
 where
     Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
@@ -62,21 +97,35 @@ where
 {
     let state = state.borrow_mut();
     state.clear();
+    state.pathspecs.reset(options.pathspecs.iter().cloned());
     let mut tree = root;
+    let mut depth = 1;
     loop {
         for entry in tree {
             let entry = entry?;
+            // Entries which don't match are skipped, along with trees which can't contain matches as they aren't looked up.
+            let relevance = state.pathspecs.relevance(entry.filename, entry.mode.is_tree());
+            if !relevance.is_relevant() {
+                continue;
+            }
             match entry.mode {
                 tree::EntryMode::Tree => {
                     use super::visit::Action::*;
                     delegate.push_path_component(entry.filename);
-                    let action = delegate.visit_tree(&entry);
+                    let action = if relevance.is_match || !options.matching_only {
+                        delegate.visit_tree(&entry)
+                    } else {
+                        Continue
+                    };
                     match action {
                         Skip => {}
                         Continue => {
-                            delegate.pop_path_component();
-                            delegate.push_back_tracked_path_component(entry.filename);
-                            state.next.push_back((true, entry.oid.to_owned()))
+                            if options.max_depth.map_or(true, |max_depth| depth < max_depth) {
+                                delegate.pop_path_component();
+                                delegate.push_back_tracked_path_component(entry.filename);
+                                state.next.push_back((true, entry.oid.to_owned(), depth + 1));
+                                state.pathspecs.push_tree(entry.filename);
+                            }
                         }
                         Cancel => {
                             return Err(Error::Cancelled);
@@ -93,10 +142,12 @@ where
             delegate.pop_path_component();
         }
         match state.next.pop_front() {
-            Some((should_pop_path, oid)) => {
+            Some((should_pop_path, oid, tree_depth)) => {
                 if should_pop_path {
                     delegate.pop_front_tracked_path_and_set_current();
                 }
+                state.pathspecs.pop_tree();
+                depth = tree_depth;
                 match find(&oid, &mut state.buf) {
                     Some(tree_iter) => tree = tree_iter,
                     None => return Err(Error::NotFound { oid: oid.to_owned() }),
@@ -161,4 +161,1071 @@ mod ancestor {
             },
         )
     }
+
+    mod hidden {
+        use git_odb::{pack, FindExt};
+        use git_traverse::commit;
+
+        use crate::hex_to_id;
+
+        #[test]
+        fn commits_reachable_from_hidden_tips_are_excluded_and_reported_as_boundary() -> crate::Result {
+            let db = super::db()?;
+            let mut iter = commit::Ancestors::new(
+                Some(hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b")),
+                commit::ancestors::State::default(),
+                move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+            )
+            .with_hidden(Some(hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37")));
+            let oids = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(
+                oids,
+                vec![
+                    hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                    hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+                    hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"),
+                ],
+                "only the commits of the merged branch remain"
+            );
+            assert_eq!(
+                iter.boundary(),
+                &[
+                    hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                    hex_to_id("9556057aee5abb06912922e9f26c46386a816822"),
+                ],
+                "the boundary are the first excluded ancestors"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn the_history_shared_with_hidden_tips_is_not_walked_entirely() -> crate::Result {
+            let dir =
+                git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_long_history.sh")?;
+            let db = git_odb::linked::Store::at(dir.join(".git").join("objects"))?;
+            let mut lookups = std::collections::BTreeSet::new();
+            let mut iter = commit::Ancestors::new(
+                Some(hex_to_id("477c3ef4a2db668d2c70bc2351b1d748a1541cde")),
+                commit::ancestors::State::default(),
+                |oid, buf| {
+                    lookups.insert(oid.to_owned());
+                    db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                },
+            )
+            .with_hidden(Some(hex_to_id("8326cad3579760dbf5b166a15069f6f02ed330ac")));
+            assert_eq!(
+                iter.by_ref().collect::<Result<Vec<_>, _>>()?,
+                vec![
+                    hex_to_id("477c3ef4a2db668d2c70bc2351b1d748a1541cde"),
+                    hex_to_id("3e1b1e63d4a213f15eeffd2596a4c77e824cc971"),
+                ],
+                "like `git rev-list branch ^main`"
+            );
+            assert_eq!(
+                iter.boundary(),
+                &[hex_to_id("2d4fb8252b3becb6c6c0180c547dc7614a07ed4a")]
+            );
+            drop(iter);
+            assert_eq!(
+                lookups.len(),
+                8,
+                "only a few of the 20 commits of the shared history are looked up once only hidden commits remain"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn hiding_a_tip_itself_yields_nothing() -> crate::Result {
+            let db = super::db()?;
+            let tip = hex_to_id("9556057aee5abb06912922e9f26c46386a816822");
+            let mut iter = commit::Ancestors::new(Some(tip), commit::ancestors::State::default(), move |oid, buf| {
+                db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+            })
+            .with_hidden(Some(tip));
+            assert!(iter.next().is_none());
+            assert!(iter.boundary().is_empty());
+            Ok(())
+        }
+    }
+
+    mod sorting {
+        use git_odb::{pack, FindExt};
+        use git_traverse::commit;
+
+        use crate::hex_to_id;
+
+        #[test]
+        fn by_commit_time_newest_first_matches_git_even_with_zero_timestamps() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_dates.sh")?;
+            let db = git_odb::linked::Store::at(dir.join(".git").join("objects"))?;
+            let oids = commit::Ancestors::new(
+                Some(hex_to_id("887bd782d5e8cbe4b55438a53f924db68e3b977a")),
+                commit::ancestors::State::default(),
+                |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+            )
+            .with_sorting(commit::ancestors::Sorting::ByCommitTimeNewestFirst)
+            .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(
+                oids,
+                vec![
+                    hex_to_id("887bd782d5e8cbe4b55438a53f924db68e3b977a"),
+                    hex_to_id("8ef23e71f1d1da91333bb0150cf2d75078f3ddd9"),
+                    hex_to_id("7670c823c509bedb5a146a22fe312d16ce892652"),
+                    hex_to_id("97099ca7c22afec0c186bb2d11a6ae38faaed9a8"),
+                    hex_to_id("352312669f32dc7ac25a6533bad71de385926c91"),
+                    hex_to_id("cefea3a743569d3f5823a85fbe2eb926eb44820b"),
+                ],
+                "the same order as `git rev-list`, whereas breadth-first would return c3 before b1c2"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn by_commit_time_newest_first_handles_timestamps_overflowing_32_bits() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only(
+                "make_traversal_repo_for_commits_with_overflowing_dates.sh",
+            )?;
+            let expected = std::fs::read_to_string(dir.join("expected-order"))?
+                .lines()
+                .map(hex_to_id)
+                .collect::<Vec<_>>();
+            let db = git_odb::linked::Store::at(dir.join(".git").join("objects"))?;
+            let oids = commit::Ancestors::new(Some(expected[0]), commit::ancestors::State::default(), |oid, buf| {
+                db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+            })
+            .with_sorting(commit::ancestors::Sorting::ByCommitTimeNewestFirst)
+            .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(
+                oids, expected,
+                "b1c1 is dated far in the future and is returned right after the merge, like `git rev-list` does"
+            );
+            Ok(())
+        }
+
+        fn sorted(
+            script_name: &str,
+            tip: &str,
+            sorting: commit::ancestors::Sorting,
+        ) -> crate::Result<Vec<git_hash::ObjectId>> {
+            let dir = git_testtools::scripted_fixture_repo_read_only(script_name)?;
+            let db = git_odb::linked::Store::at(dir.join(".git").join("objects"))?;
+            Ok(
+                commit::Ancestors::new(Some(hex_to_id(tip)), commit::ancestors::State::default(), |oid, buf| {
+                    db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                })
+                .with_sorting(sorting)
+                .collect::<Result<Vec<_>, _>>()?,
+            )
+        }
+
+        #[test]
+        fn topological_matches_git_topo_order() -> crate::Result {
+            assert_eq!(
+                sorted(
+                    "make_traversal_repo_for_commits.sh",
+                    "01ec18a3ebf2855708ad3c9d244306bc1fae3e9b",
+                    commit::ancestors::Sorting::Topological
+                )?,
+                vec![
+                    hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                    hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+                    hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"),
+                    hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                    hex_to_id("9556057aee5abb06912922e9f26c46386a816822"),
+                    hex_to_id("17d78c64cef6c33a10a604573fd2c429e477fd63"),
+                    hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7"),
+                    hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+                ],
+                "the merged branch is returned as a whole before the commits of the main branch, like `git rev-list --topo-order`"
+            );
+            assert_eq!(
+                sorted(
+                    "make_traversal_repo_for_commits_with_dates.sh",
+                    "887bd782d5e8cbe4b55438a53f924db68e3b977a",
+                    commit::ancestors::Sorting::Topological
+                )?,
+                vec![
+                    hex_to_id("887bd782d5e8cbe4b55438a53f924db68e3b977a"),
+                    hex_to_id("8ef23e71f1d1da91333bb0150cf2d75078f3ddd9"),
+                    hex_to_id("97099ca7c22afec0c186bb2d11a6ae38faaed9a8"),
+                    hex_to_id("7670c823c509bedb5a146a22fe312d16ce892652"),
+                    hex_to_id("352312669f32dc7ac25a6533bad71de385926c91"),
+                    hex_to_id("cefea3a743569d3f5823a85fbe2eb926eb44820b"),
+                ],
+                "commit times don't matter"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn topological_by_commit_time_matches_git_date_order() -> crate::Result {
+            assert_eq!(
+                sorted(
+                    "make_traversal_repo_for_commits.sh",
+                    "01ec18a3ebf2855708ad3c9d244306bc1fae3e9b",
+                    commit::ancestors::Sorting::TopologicalByCommitTime
+                )?,
+                vec![
+                    hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                    hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                    hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+                    hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"),
+                    hex_to_id("9556057aee5abb06912922e9f26c46386a816822"),
+                    hex_to_id("17d78c64cef6c33a10a604573fd2c429e477fd63"),
+                    hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7"),
+                    hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+                ],
+                "with equal commit times, commits are returned in the order they became ready, like `git rev-list --date-order`"
+            );
+            assert_eq!(
+                sorted(
+                    "make_traversal_repo_for_commits_with_dates.sh",
+                    "887bd782d5e8cbe4b55438a53f924db68e3b977a",
+                    commit::ancestors::Sorting::TopologicalByCommitTime
+                )?,
+                vec![
+                    hex_to_id("887bd782d5e8cbe4b55438a53f924db68e3b977a"),
+                    hex_to_id("8ef23e71f1d1da91333bb0150cf2d75078f3ddd9"),
+                    hex_to_id("7670c823c509bedb5a146a22fe312d16ce892652"),
+                    hex_to_id("97099ca7c22afec0c186bb2d11a6ae38faaed9a8"),
+                    hex_to_id("352312669f32dc7ac25a6533bad71de385926c91"),
+                    hex_to_id("cefea3a743569d3f5823a85fbe2eb926eb44820b"),
+                ]
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn topological_respects_hidden_commits_and_reports_the_boundary() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits.sh")?;
+            let db = git_odb::linked::Store::at(dir.join(".git").join("objects"))?;
+            let mut iter = commit::Ancestors::new(
+                Some(hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b")),
+                commit::ancestors::State::default(),
+                |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+            )
+            .with_hidden(Some(hex_to_id("9556057aee5abb06912922e9f26c46386a816822")))
+            .with_sorting(commit::ancestors::Sorting::Topological);
+            assert_eq!(
+                iter.by_ref().collect::<Result<Vec<_>, _>>()?,
+                vec![
+                    hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                    hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+                    hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"),
+                    hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                ]
+            );
+            assert_eq!(
+                iter.boundary(),
+                &[hex_to_id("9556057aee5abb06912922e9f26c46386a816822")]
+            );
+            Ok(())
+        }
+    }
+
+    mod first_parent {
+        use git_odb::{pack, FindExt};
+        use git_traverse::commit;
+
+        use crate::hex_to_id;
+
+        #[test]
+        fn only_the_first_parent_of_merges_is_followed() -> crate::Result {
+            let mainline = vec![
+                hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                hex_to_id("9556057aee5abb06912922e9f26c46386a816822"),
+                hex_to_id("17d78c64cef6c33a10a604573fd2c429e477fd63"),
+                hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7"),
+                hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+            ];
+            for sorting in &[
+                commit::ancestors::Sorting::BreadthFirst,
+                commit::ancestors::Sorting::ByCommitTimeNewestFirst,
+                commit::ancestors::Sorting::Topological,
+                commit::ancestors::Sorting::TopologicalByCommitTime,
+            ] {
+                let db = super::db()?;
+                let oids = commit::Ancestors::new(
+                    Some(hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b")),
+                    commit::ancestors::State::default(),
+                    move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+                )
+                .with_sorting(*sorting)
+                .with_first_parent_only()
+                .collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(
+                    oids, mainline,
+                    "{:?}: commits of the merged branch are skipped",
+                    sorting
+                );
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn hidden_commits_are_still_determined_by_all_parents() -> crate::Result {
+            let db = super::db()?;
+            let mut iter = commit::Ancestors::new(
+                Some(hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b")),
+                commit::ancestors::State::default(),
+                move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+            )
+            .with_hidden(Some(hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353")))
+            .with_first_parent_only();
+            assert_eq!(
+                iter.by_ref().collect::<Result<Vec<_>, _>>()?,
+                vec![
+                    hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                    hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                ]
+            );
+            assert_eq!(
+                iter.boundary(),
+                &[hex_to_id("9556057aee5abb06912922e9f26c46386a816822")],
+                "the hidden second parent isn't part of the boundary as it isn't followed"
+            );
+            Ok(())
+        }
+    }
+
+    mod limits {
+        use git_odb::{pack, FindExt};
+        use git_traverse::commit;
+        use std::collections::BTreeSet;
+
+        use crate::hex_to_id;
+
+        fn page(skip: usize, max_count: usize) -> crate::Result<Vec<git_hash::ObjectId>> {
+            let db = super::db()?;
+            Ok(commit::Ancestors::new(
+                Some(hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b")),
+                commit::ancestors::State::default(),
+                |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+            )
+            .with_skip(skip)
+            .with_max_count(max_count)
+            .collect::<Result<Vec<_>, _>>()?)
+        }
+
+        #[test]
+        fn skip_and_max_count_paginate_the_history() -> crate::Result {
+            let last_page = page(6, 3)?;
+            assert_eq!(last_page.len(), 2, "the last page is only partially filled");
+            assert_eq!(
+                page(0, 3)?
+                    .into_iter()
+                    .chain(page(3, 3)?)
+                    .chain(last_page)
+                    .collect::<Vec<_>>(),
+                page(0, usize::MAX)?
+            );
+            assert!(page(8, 3)?.is_empty(), "skipping all commits leaves nothing");
+            Ok(())
+        }
+
+        #[test]
+        fn max_depth_limits_the_distance_to_the_tips_and_reports_the_boundary() -> crate::Result {
+            let db = super::db()?;
+            let ancestors = |max_depth, sorting| {
+                commit::Ancestors::new(
+                    Some(hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b")),
+                    commit::ancestors::State::default(),
+                    |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+                )
+                .with_max_depth(max_depth)
+                .with_sorting(sorting)
+            };
+            for sorting in &[
+                commit::ancestors::Sorting::BreadthFirst,
+                commit::ancestors::Sorting::Topological,
+            ] {
+                let mut ancestors = ancestors(2, *sorting);
+                let oids = ancestors.by_ref().collect::<Result<BTreeSet<_>, _>>()?;
+                assert_eq!(
+                    oids,
+                    vec![
+                        hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                        hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                        hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+                    ]
+                    .into_iter()
+                    .collect(),
+                    "{:?}",
+                    sorting
+                );
+                assert_eq!(
+                    ancestors.boundary(),
+                    &[
+                        hex_to_id("9556057aee5abb06912922e9f26c46386a816822"),
+                        hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"),
+                    ],
+                    "the parents of the deepest commits are excluded"
+                );
+            }
+
+            let mut ancestors = ancestors(3, commit::ancestors::Sorting::BreadthFirst);
+            assert_eq!(
+                ancestors.by_ref().count(),
+                5,
+                "c4 is reached through c5 with a depth of 3"
+            );
+            assert_eq!(
+                ancestors.boundary(),
+                &[hex_to_id("17d78c64cef6c33a10a604573fd2c429e477fd63")],
+                "b1c1 is at the same depth, and its parent c4 is returned already"
+            );
+            Ok(())
+        }
+    }
+
+    mod commit_graph {
+        use git_odb::{pack, FindExt};
+        use git_traverse::commit;
+        use std::collections::BTreeSet;
+
+        use crate::hex_to_id;
+
+        #[test]
+        fn parents_of_commits_in_the_graph_are_not_looked_up() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_graph.sh")?;
+            let objects_dir = dir.join(".git").join("objects");
+            let db = git_odb::linked::Store::at(&objects_dir)?;
+            let graph = git_commitgraph::Graph::from_info_dir(objects_dir.join("info"))?;
+            let mut lookups = Vec::new();
+            let oids = commit::Ancestors::new(
+                Some(hex_to_id("04ff4c2c13a98c185df2c48403c066ffe74f9a37")),
+                commit::ancestors::State::default(),
+                |oid, buf| {
+                    lookups.push(oid.to_owned());
+                    db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                },
+            )
+            .with_commit_graph(graph)
+            .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(
+                oids,
+                vec![
+                    hex_to_id("04ff4c2c13a98c185df2c48403c066ffe74f9a37"),
+                    hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                    hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                    hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+                    hex_to_id("9556057aee5abb06912922e9f26c46386a816822"),
+                    hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"),
+                    hex_to_id("17d78c64cef6c33a10a604573fd2c429e477fd63"),
+                    hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7"),
+                    hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+                ],
+                "the order is the same as without commit-graph"
+            );
+            assert_eq!(
+                lookups,
+                vec![hex_to_id("04ff4c2c13a98c185df2c48403c066ffe74f9a37")],
+                "only the commit created after writing the commit-graph is decoded"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn commit_times_of_commits_in_the_graph_are_not_looked_up() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_graph.sh")?;
+            let objects_dir = dir.join(".git").join("objects");
+            let db = git_odb::linked::Store::at(&objects_dir)?;
+            let graph = git_commitgraph::Graph::from_info_dir(objects_dir.join("info"))?;
+            let mut lookups = Vec::new();
+            let num_commits = commit::Ancestors::new(
+                Some(hex_to_id("04ff4c2c13a98c185df2c48403c066ffe74f9a37")),
+                commit::ancestors::State::default(),
+                |oid, buf| {
+                    lookups.push(oid.to_owned());
+                    db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                },
+            )
+            .with_commit_graph(graph)
+            .with_sorting(commit::ancestors::Sorting::ByCommitTimeNewestFirst)
+            .count();
+            assert_eq!(num_commits, 9);
+            assert!(
+                lookups
+                    .iter()
+                    .all(|id| *id == hex_to_id("04ff4c2c13a98c185df2c48403c066ffe74f9a37")),
+                "only the commit created after writing the commit-graph is decoded"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn ancestors_below_the_generation_cutoff_are_not_traversed() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_graph.sh")?;
+            let objects_dir = dir.join(".git").join("objects");
+            let db = git_odb::linked::Store::at(&objects_dir)?;
+            let graph = git_commitgraph::Graph::from_info_dir(objects_dir.join("info"))?;
+            let b1c1 = hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659");
+            let generation = graph.commit_by_id(b1c1).expect("in graph").generation();
+            assert_eq!(generation, 5);
+
+            for sorting in &[
+                commit::ancestors::Sorting::BreadthFirst,
+                commit::ancestors::Sorting::ByCommitTimeNewestFirst,
+                commit::ancestors::Sorting::Topological,
+            ] {
+                let graph = git_commitgraph::Graph::from_info_dir(objects_dir.join("info"))?;
+                let mut lookups = Vec::new();
+                let oids = commit::Ancestors::new(
+                    Some(hex_to_id("04ff4c2c13a98c185df2c48403c066ffe74f9a37")),
+                    commit::ancestors::State::default(),
+                    |oid, buf| {
+                        lookups.push(oid.to_owned());
+                        db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                    },
+                )
+                .with_commit_graph(graph)
+                .with_sorting(*sorting)
+                .with_generation_cutoff(generation)
+                .collect::<Result<BTreeSet<_>, _>>()?;
+                assert_eq!(
+                    oids,
+                    vec![
+                        hex_to_id("04ff4c2c13a98c185df2c48403c066ffe74f9a37"),
+                        hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                        hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                        hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+                        b1c1,
+                    ]
+                    .into_iter()
+                    .collect(),
+                    "c4 and its ancestors have a lower generation and can't reach b1c1"
+                );
+                assert!(
+                    lookups
+                        .iter()
+                        .all(|id| *id == hex_to_id("04ff4c2c13a98c185df2c48403c066ffe74f9a37")),
+                    "the commit without generation number is traversed as well"
+                );
+            }
+            Ok(())
+        }
+    }
+
+    mod commit_source {
+        use git_hash::{oid, ObjectId};
+        use git_odb::{pack, FindExt};
+        use git_traverse::commit;
+        use std::collections::HashMap;
+
+        use crate::hex_to_id;
+
+        /// Commits and their parents, all with the same commit time.
+        struct Parents(HashMap<ObjectId, Vec<ObjectId>>);
+
+        impl commit::ancestors::CommitSource for Parents {
+            fn parents(&self, id: &oid, parents: &mut Vec<ObjectId>) -> Result<bool, commit::ancestors::Error> {
+                Ok(match self.0.get(&id.to_owned()) {
+                    Some(ids) => {
+                        parents.extend_from_slice(ids);
+                        true
+                    }
+                    None => false,
+                })
+            }
+
+            fn commit_time(&self, id: &oid) -> Option<u64> {
+                self.0.get(&id.to_owned()).map(|_| 42)
+            }
+        }
+
+        #[test]
+        fn custom_sources_are_used_for_the_commits_they_know() -> crate::Result {
+            let mut source = Parents(HashMap::new());
+            source.0.insert(
+                hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"),
+                vec![
+                    hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                    hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+                ],
+            );
+            source.0.insert(
+                hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                vec![hex_to_id("9556057aee5abb06912922e9f26c46386a816822")],
+            );
+            source.0.insert(
+                hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+                vec![hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659")],
+            );
+
+            let db = super::db()?;
+            let mut lookups = Vec::new();
+            let oids = commit::Ancestors::new(
+                Some(hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b")),
+                commit::ancestors::State::default(),
+                |oid, buf| {
+                    lookups.push(oid.to_owned());
+                    db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                },
+            )
+            .with_commit_source(source)
+            .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(oids.len(), 8, "all commits are returned");
+            assert_eq!(
+                lookups,
+                vec![
+                    hex_to_id("9556057aee5abb06912922e9f26c46386a816822"),
+                    hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"),
+                    hex_to_id("17d78c64cef6c33a10a604573fd2c429e477fd63"),
+                    hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7"),
+                    hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+                ],
+                "only commits unknown to the source are decoded"
+            );
+            Ok(())
+        }
+    }
+
+    mod state {
+        use git_odb::{pack, FindExt};
+        use git_traverse::commit;
+
+        use crate::hex_to_id;
+
+        #[test]
+        fn state_is_reusable_and_its_memory_can_be_released() -> crate::Result {
+            let db = super::db()?;
+            let tip = hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b");
+            let mut state = commit::ancestors::State::default();
+            assert_eq!(state.memory_usage(), 0);
+
+            let mut iter = commit::Ancestors::new(Some(tip), state, |oid, buf| {
+                db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+            });
+            let first = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(first.len(), 8);
+            state = iter.into_state();
+            let memory_usage = state.memory_usage();
+            assert!(memory_usage > 0, "seen commits and buffers are accounted for");
+
+            let second = commit::Ancestors::new(Some(tip), &mut state, |oid, buf| {
+                db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(second, first, "reused state yields the same result");
+            assert_eq!(state.memory_usage(), memory_usage, "allocations are reused");
+
+            state.clear_and_shrink();
+            assert_eq!(state.memory_usage(), 0);
+            Ok(())
+        }
+
+        #[test]
+        fn compact_seen_set_tracks_commits_in_the_commit_graph_by_position() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_graph.sh")?;
+            let objects_dir = dir.join(".git").join("objects");
+            let db = git_odb::linked::Store::at(&objects_dir)?;
+            let traverse = |compact: bool| -> crate::Result<(Vec<_>, usize)> {
+                let graph = git_commitgraph::Graph::from_info_dir(objects_dir.join("info"))?;
+                let mut iter = commit::Ancestors::new(
+                    Some(hex_to_id("04ff4c2c13a98c185df2c48403c066ffe74f9a37")),
+                    commit::ancestors::State::default(),
+                    |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+                )
+                .with_commit_graph(graph);
+                if compact {
+                    iter = iter.with_compact_seen_set();
+                }
+                let oids = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+                Ok((oids, iter.state().memory_usage()))
+            };
+            let (expected, memory_usage) = traverse(false)?;
+            let (oids, compact_memory_usage) = traverse(true)?;
+            assert_eq!(oids, expected);
+            assert_eq!(oids.len(), 9);
+            assert!(
+                compact_memory_usage < memory_usage,
+                "only the commit not in the graph is stored by id, the others take a bit each"
+            );
+            Ok(())
+        }
+    }
+
+    mod parallel_discovery {
+        use git_odb::{pack, FindExt};
+        use git_traverse::commit;
+        use std::sync::Arc;
+
+        use crate::hex_to_id;
+
+        #[test]
+        fn commits_are_returned_in_the_same_order_without_using_find() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_paths.sh")?;
+            let db = Arc::new(git_odb::linked::Store::at(dir.join(".git").join("objects"))?);
+            let tip = hex_to_id("e9c530721f725fb738f684523369db986bc25cce");
+            for sorting in &[
+                commit::ancestors::Sorting::BreadthFirst,
+                commit::ancestors::Sorting::Topological,
+            ] {
+                let expected = commit::Ancestors::new(Some(tip), commit::ancestors::State::default(), |oid, buf| {
+                    db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                })
+                .with_sorting(*sorting)
+                .collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(expected.len(), 10);
+
+                let mut lookups = Vec::new();
+                let thread_db = Arc::clone(&db);
+                let oids = commit::Ancestors::new(Some(tip), commit::ancestors::State::default(), |oid, buf| {
+                    lookups.push(oid.to_owned());
+                    db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                })
+                .with_sorting(*sorting)
+                .with_parallel_discovery(
+                    Some(2),
+                    || pack::cache::Never,
+                    move |oid, buf, cache| thread_db.find_existing_commit_iter(oid, buf, cache).ok(),
+                )
+                .collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(oids, expected, "the order is deterministic");
+                assert!(lookups.is_empty(), "all commits are looked up by the threads");
+            }
+            Ok(())
+        }
+    }
+
+    mod shallow {
+        use git_odb::{pack, FindExt};
+        use git_traverse::commit;
+
+        use crate::hex_to_id;
+
+        #[test]
+        fn parents_of_shallow_commits_are_not_looked_up() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_shallow_commits.sh")?;
+            let git_dir = dir.join("shallow").join(".git");
+            let db = git_odb::linked::Store::at(git_dir.join("objects"))?;
+            let boundary = git_odb::shallow::Boundary::from_git_dir(&git_dir)?;
+            assert_eq!(boundary.len(), 2, "both parents of the merge commit are shallow");
+
+            let tip = hex_to_id("a6e0fc49db020c2c4a400bef7560e92b19c625e1");
+            assert!(
+                matches!(
+                    commit::Ancestors::new(Some(tip), commit::ancestors::State::default(), |oid, buf| {
+                        db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                    })
+                    .collect::<Result<Vec<_>, _>>(),
+                    Err(commit::ancestors::Error::NotFound { .. })
+                ),
+                "without knowing the shallow boundary, missing parents are an error"
+            );
+
+            let oids = commit::Ancestors::new(Some(tip), commit::ancestors::State::default(), |oid, buf| {
+                db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+            })
+            .with_shallow(boundary.commits().iter().cloned())
+            .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(
+                oids,
+                vec![
+                    tip,
+                    hex_to_id("17d78c64cef6c33a10a604573fd2c429e477fd63"),
+                    hex_to_id("b2b2fb904774db46047fb514c4fddf0de30ad792"),
+                ],
+                "shallow commits are returned, but not their parents"
+            );
+            Ok(())
+        }
+    }
+}
+
+mod attributed {
+    use git_odb::{pack, FindExt};
+    use git_traverse::commit;
+
+    use crate::hex_to_id;
+
+    #[test]
+    fn each_commit_is_attributed_to_all_tips_reaching_it() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits.sh")?;
+        let db = git_odb::linked::Store::at(dir.join(".git").join("objects"))?;
+        let infos = commit::Attributed::new(
+            vec![
+                hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+                hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"),
+            ],
+            commit::attributed::State::default(),
+            move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+        )?
+        .map(|res| res.map(|info| (info.id, info.tips)))
+        .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            infos,
+            vec![
+                (hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"), 0b01),
+                (hex_to_id("ce2e8ffaa9608a26f7b21afc1db89cadb54fd353"), 0b10),
+                (hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"), 0b10),
+                (hex_to_id("9556057aee5abb06912922e9f26c46386a816822"), 0b11),
+                (hex_to_id("17d78c64cef6c33a10a604573fd2c429e477fd63"), 0b11),
+                (hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7"), 0b11),
+                (hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"), 0b11),
+            ],
+            "commits are returned only once all their children are known, in topological order"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn too_many_tips_are_rejected() {
+        let tips = vec![git_hash::ObjectId::null_sha1(); commit::attributed::MAX_TIPS + 1];
+        assert!(matches!(
+            commit::Attributed::new(tips, commit::attributed::State::default(), |_, _| None),
+            Err(commit::attributed::Error::TooManyTips { count: 65 })
+        ));
+    }
+}
+
+mod simplified {
+    use git_odb::{pack, FindExt};
+    use git_traverse::commit;
+
+    use crate::hex_to_id;
+
+    fn simplified(paths: &[&str]) -> crate::Result<Vec<(String, Vec<String>)>> {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_paths.sh")?;
+        let db = git_odb::linked::Store::at(dir.join(".git").join("objects"))?;
+        Ok(commit::Simplified::new(
+            Some(hex_to_id("e9c530721f725fb738f684523369db986bc25cce")),
+            paths.iter().copied(),
+            commit::simplified::State::default(),
+            |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+            |oid, buf| db.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok(),
+        )
+        .map(|res| {
+            res.map(|info| {
+                (
+                    info.id.to_string(),
+                    info.parents.iter().map(ToString::to_string).collect(),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn expected(lines: &[&str]) -> Vec<(String, Vec<String>)> {
+        lines
+            .iter()
+            .map(|line| {
+                let mut ids = line.split(' ').map(ToOwned::to_owned);
+                (ids.next().expect("commit"), ids.collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merges_treesame_to_a_parent_follow_only_that_parent() -> crate::Result {
+        assert_eq!(
+            simplified(&["a"])?,
+            expected(&[
+                "e9c530721f725fb738f684523369db986bc25cce 612933dd7a2aec51566dd06b7b3aab489a92eb0b e285dad20dd0270f75d5239938a3c5ab5bd8dc49",
+                "e285dad20dd0270f75d5239938a3c5ab5bd8dc49 5a30dc9905e0c48150b91a4864e119fe9a4bf0a0",
+                "612933dd7a2aec51566dd06b7b3aab489a92eb0b 5a30dc9905e0c48150b91a4864e119fe9a4bf0a0",
+                "5a30dc9905e0c48150b91a4864e119fe9a4bf0a0",
+            ]),
+            "m2b2 changes 'a' compared to both parents, whereas m1b1 takes it from b1c1 and isn't returned, just like c5 which doesn't touch 'a'"
+        );
+        assert_eq!(
+            simplified(&["a", "dir/c"])?,
+            expected(&[
+                "e9c530721f725fb738f684523369db986bc25cce f4d39c271781d61e5d5e8d963a396efc2a91d5ac e285dad20dd0270f75d5239938a3c5ab5bd8dc49",
+                "e285dad20dd0270f75d5239938a3c5ab5bd8dc49 51245476ca65f84839ecaab46cd3dc592658f8d7",
+                "f4d39c271781d61e5d5e8d963a396efc2a91d5ac 51245476ca65f84839ecaab46cd3dc592658f8d7 612933dd7a2aec51566dd06b7b3aab489a92eb0b",
+                "51245476ca65f84839ecaab46cd3dc592658f8d7 5a30dc9905e0c48150b91a4864e119fe9a4bf0a0",
+                "612933dd7a2aec51566dd06b7b3aab489a92eb0b 5a30dc9905e0c48150b91a4864e119fe9a4bf0a0",
+                "5a30dc9905e0c48150b91a4864e119fe9a4bf0a0",
+            ]),
+            "with more paths, m1b1 differs from each of its parents in one of them"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn directories_and_files_match_git_rev_list() -> crate::Result {
+        assert_eq!(
+            simplified(&["x"])?,
+            expected(&[
+                "2d2e50f516409959630bb263deee5ddf218537c1 8f9a2f61fc95bb295e0c08629ec99e7360236227",
+                "8f9a2f61fc95bb295e0c08629ec99e7360236227",
+            ]),
+            "the merge keeps 'x' of the first parent, so b1c2 is never reached"
+        );
+        assert_eq!(
+            simplified(&["dir"])?,
+            expected(&[
+                "51245476ca65f84839ecaab46cd3dc592658f8d7 3703378534188e562222f0cf5dc596b4aa82a83c",
+                "3703378534188e562222f0cf5dc596b4aa82a83c 5a30dc9905e0c48150b91a4864e119fe9a4bf0a0",
+                "5a30dc9905e0c48150b91a4864e119fe9a4bf0a0",
+            ]),
+            "directories compare all of their content"
+        );
+        assert_eq!(simplified(&["does-not-exist"])?, expected(&[]));
+        Ok(())
+    }
+
+    #[test]
+    fn commits_with_timestamps_overflowing_32_bits_are_supported() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only(
+            "make_traversal_repo_for_commits_with_overflowing_dates.sh",
+        )?;
+        let db = git_odb::linked::Store::at(dir.join(".git").join("objects"))?;
+        let expected_lines = std::fs::read_to_string(dir.join("expected-simplified"))?;
+        let tip = std::fs::read_to_string(dir.join("expected-order"))?;
+        let commits = commit::Simplified::new(
+            Some(hex_to_id(tip.lines().next().expect("at least one commit"))),
+            Some("file"),
+            commit::simplified::State::default(),
+            |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+            |oid, buf| db.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok(),
+        )
+        .map(|res| {
+            res.map(|info| {
+                (
+                    info.id.to_string(),
+                    info.parents.iter().map(ToString::to_string).collect(),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(commits, expected(&expected_lines.lines().collect::<Vec<_>>()));
+        Ok(())
+    }
+
+    #[test]
+    fn without_paths_all_commits_are_returned_with_their_parents() -> crate::Result {
+        let commits = simplified(&[])?;
+        assert_eq!(commits.len(), 10, "each commit changes the tree");
+        assert_eq!(
+            commits[0],
+            expected(&["e9c530721f725fb738f684523369db986bc25cce f4d39c271781d61e5d5e8d963a396efc2a91d5ac e285dad20dd0270f75d5239938a3c5ab5bd8dc49"])[0]
+        );
+        Ok(())
+    }
+}
+
+mod merge_base {
+    use git_hash::ObjectId;
+    use git_odb::{linked::Store, pack, FindExt};
+    use git_traverse::commit;
+    use std::path::PathBuf;
+
+    use crate::hex_to_id;
+
+    fn objects_dir() -> crate::Result<PathBuf> {
+        Ok(
+            git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_merge_bases.sh")?
+                .join(".git")
+                .join("objects"),
+        )
+    }
+
+    fn db() -> crate::Result<Store> {
+        let db = Store::at(objects_dir()?)?;
+        Ok(db)
+    }
+
+    const C1: &str = "e10040a019edbf6b765aeddb1011b65db5a2d604";
+    const C2: &str = "d40e0479464530f53f97f4c5a9a88f86bf85b87b";
+    const D1: &str = "02096170e273b863b3defe262a161644abf8bd41";
+    const A1: &str = "6554cb60956d008855ecbc6543e0e31cb72499e0";
+    const B1: &str = "52f97629525e8b39158c0743b1983158cbd8090d";
+    const A2: &str = "489d6875a10bff692c3be7b09b87a33d8339615a";
+    const B2: &str = "5b94e8b5b9dd8c9b62e710caeddee5b56e8e2ddb";
+    const U1: &str = "ad48233a266ec051442cf3bc2b0ad2d82711545b";
+
+    fn ids(hex: &[&str]) -> Vec<ObjectId> {
+        hex.iter().copied().map(hex_to_id).collect()
+    }
+
+    #[test]
+    fn merge_base_of_two_commits_matches_git() -> crate::Result {
+        let db = db()?;
+        let merge_base = |first: &str, second: &str| {
+            commit::merge_base(hex_to_id(first), hex_to_id(second), None, |oid, buf| {
+                db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+            })
+        };
+        assert_eq!(merge_base(A1, B1)?, Some(hex_to_id(C2)));
+        assert_eq!(merge_base(A1, D1)?, Some(hex_to_id(C1)));
+        assert_eq!(
+            merge_base(A2, A1)?,
+            Some(hex_to_id(A1)),
+            "ancestors are their own merge-base"
+        );
+        assert_eq!(merge_base(A1, A1)?, Some(hex_to_id(A1)));
+        assert_eq!(
+            merge_base(A2, B2)?,
+            Some(hex_to_id(B1)),
+            "the newest of multiple merge-bases is the best"
+        );
+        assert_eq!(merge_base(A2, U1)?, None, "unrelated histories have no merge-base");
+        Ok(())
+    }
+
+    #[test]
+    fn all_merge_bases_of_criss_cross_merges_and_multiple_commits_match_git() -> crate::Result {
+        let db = db()?;
+        let merge_bases = |first: &str, others: &[&str]| {
+            commit::merge_base::merge_bases(hex_to_id(first), ids(others), None, |oid, buf| {
+                db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+            })
+        };
+        assert_eq!(merge_bases(A2, &[B2])?, ids(&[B1, A1]), "like `git merge-base --all`");
+        assert_eq!(
+            merge_bases(A1, &[B1, D1])?,
+            ids(&[C2]),
+            "c1 is reachable from c2 and thus not a best common ancestor"
+        );
+        assert_eq!(merge_bases(U1, &[A2, B2])?, ids(&[]));
+        Ok(())
+    }
+
+    #[test]
+    fn octopus_merge_bases_match_git() -> crate::Result {
+        let db = db()?;
+        let octopus = |commits: &[&str]| {
+            commit::merge_base::octopus(ids(commits), None, |oid, buf| {
+                db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+            })
+        };
+        assert_eq!(
+            octopus(&[A1, B1, D1])?,
+            ids(&[C1]),
+            "like `git merge-base --octopus --all`"
+        );
+        assert_eq!(octopus(&[A2, B2])?, ids(&[B1, A1]));
+        assert_eq!(octopus(&[A2])?, ids(&[A2]));
+        assert_eq!(octopus(&[A1, B1, U1])?, ids(&[]));
+        Ok(())
+    }
+
+    #[test]
+    fn commits_in_the_commit_graph_are_not_looked_up() -> crate::Result {
+        let graph = git_commitgraph::Graph::from_info_dir(objects_dir()?.join("info"))?;
+        fn no_find<'a>(oid: &git_hash::oid, _buf: &'a mut Vec<u8>) -> Option<git_object::immutable::CommitIter<'a>> {
+            panic!("{} is in the commit-graph and isn't looked up", oid)
+        }
+        assert_eq!(
+            commit::merge_base::merge_bases(hex_to_id(A2), ids(&[B2]), Some(&graph), no_find)?,
+            ids(&[B1, A1])
+        );
+        assert_eq!(
+            commit::merge_base::merge_bases(hex_to_id(A1), ids(&[B1, D1]), Some(&graph), no_find)?,
+            ids(&[C2]),
+            "c1 is found to be reachable from c2 while walking only down to the lowest generation of the candidates"
+        );
+        assert_eq!(
+            commit::merge_base::octopus(ids(&[A1, B1, D1]), Some(&graph), no_find)?,
+            ids(&[C1])
+        );
+        assert_eq!(
+            commit::merge_base(hex_to_id(A2), hex_to_id(U1), Some(&graph), no_find)?,
+            None
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit_at() {
+  GIT_COMMITTER_DATE="@$1 +0000" git commit -q --allow-empty -m "$2"
+}
+
+git init -q
+git config commit.gpgsign false
+
+git checkout -q -b main
+commit_at 0 "c1 with zero timestamp"
+commit_at 100 c2
+
+git checkout -q -b branch1
+commit_at 200 b1c1
+commit_at 300 b1c2
+
+git checkout -q main
+commit_at 250 c3
+GIT_COMMITTER_DATE="@400 +0000" git merge branch1 -q -m m1b1
@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit_at() {
+  GIT_COMMITTER_DATE="@$1 +0000" git commit -q --allow-empty -m "$2"
+}
+
+git init -q
+git config commit.gpgsign false
+
+git checkout -q -b main
+for n in $(seq 1 20); do
+  commit_at $((n * 100)) "c$n"
+done
+
+git checkout -q -b branch
+commit_at 2100 b1
+commit_at 2200 b2
+
+git checkout -q main
+commit_at 2150 c21
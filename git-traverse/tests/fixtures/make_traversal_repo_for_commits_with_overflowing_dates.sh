@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit_at() {
+  GIT_AUTHOR_DATE="@$1 +0000" GIT_COMMITTER_DATE="@$1 +0000" git commit -q --allow-empty -m "$2"
+}
+
+git init -q
+git config commit.gpgsign false
+
+git checkout -q -b main
+commit_at 100 c1
+
+git checkout -q -b branch1
+echo b1 > file && git add file
+commit_at 5000000000 "b1c1 with a timestamp overflowing 32 bits"
+
+git checkout -q main
+commit_at 300 c2
+GIT_COMMITTER_DATE="@400 +0000" git merge branch1 -q -m m1b1
+
+git rev-list HEAD > expected-order
+git rev-list --parents HEAD -- file > expected-simplified
@@ -0,0 +1,43 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit_at() {
+  git add -A
+  GIT_COMMITTER_DATE="@$1 +0000" git commit -q --allow-empty -m "$2"
+}
+
+git init -q
+git config commit.gpgsign false
+
+git checkout -q -b main
+mkdir dir
+echo a > a
+echo b > dir/b
+commit_at 100 c1
+echo x > x
+commit_at 200 c2
+echo b2 > dir/b
+commit_at 300 c3
+
+git checkout -q -b branch1
+echo a2 > a
+commit_at 400 b1c1
+echo x2 > x
+commit_at 500 b1c2
+
+git checkout -q main
+echo x3 > x
+commit_at 450 c4
+echo c > dir/c
+commit_at 550 c5
+GIT_COMMITTER_DATE="@600 +0000" git merge branch1 -q -m m1b1 -X ours
+
+git checkout -q -b branch2 HEAD~1
+echo a3 > a
+commit_at 650 b2c1
+
+git checkout -q main
+git merge branch2 -q --no-commit -X ours
+echo merged > a
+commit_at 700 m2b2
+GIT_COMMITTER_DATE="@800 +0000" git tag -a -m "a tree" tree-tag "HEAD^{tree}"
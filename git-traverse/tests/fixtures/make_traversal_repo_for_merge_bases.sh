@@ -0,0 +1,36 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit_at() {
+  GIT_COMMITTER_DATE="@$1 +0000" git commit -q --allow-empty -m "$2"
+}
+
+function merge_at() {
+  GIT_COMMITTER_DATE="@$1 +0000" git merge -q --allow-unrelated-histories -m "$2" "$3"
+}
+
+git init -q
+git config commit.gpgsign false
+
+git checkout -q -b main
+commit_at 100 c1
+commit_at 200 c2
+
+git checkout -q -b d main~1
+commit_at 250 d1
+
+git checkout -q -b a main
+commit_at 300 a1
+
+git checkout -q -b b main
+commit_at 310 b1
+
+git checkout -q a
+merge_at 400 a2 b~0
+git checkout -q b
+merge_at 410 b2 a~1
+
+git checkout -q --orphan unrelated
+commit_at 500 u1
+
+git commit-graph write --no-progress --reachable
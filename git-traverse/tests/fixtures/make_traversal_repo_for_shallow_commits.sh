@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git config commit.gpgsign false
+
+  git checkout -q -b main
+  git commit -q --allow-empty -m c1
+  git commit -q --allow-empty -m c2
+
+  git checkout -q -b branch1
+  git commit -q --allow-empty -m b1c1
+  git commit -q --allow-empty -m b1c2
+
+  git checkout -q main
+  git commit -q --allow-empty -m c3
+  git merge branch1 -q -m m1b1
+)
+
+git clone -q --depth 2 "file://$PWD/remote" shallow
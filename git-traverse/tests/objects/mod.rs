@@ -0,0 +1,173 @@
+use git_hash::{oid, ObjectId};
+use git_object::Kind;
+use git_odb::{linked::Store, pack, FindExt};
+use git_traverse::objects;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::hex_to_id;
+
+fn db() -> crate::Result<Store> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_paths.sh")?;
+    let db = Store::at(dir.join(".git").join("objects"))?;
+    Ok(db)
+}
+
+fn find<'a>(db: &Store, oid: &oid, buf: &'a mut Vec<u8>) -> Option<(Kind, &'a [u8])> {
+    db.find_existing(oid, buf, &mut pack::cache::Never)
+        .ok()
+        .map(|obj| (obj.kind, obj.data))
+}
+
+#[test]
+fn all_objects_reachable_from_commits_are_returned_once() -> crate::Result {
+    let db = db()?;
+    let infos = objects::Reachable::new(
+        Some(hex_to_id("e9c530721f725fb738f684523369db986bc25cce")),
+        objects::State::default(),
+        |oid, buf| find(&db, oid, buf),
+    )
+    .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(infos.len(), 33, "like `git rev-list --objects`");
+    assert_eq!(
+        infos.iter().map(|info| info.id).collect::<BTreeSet<_>>().len(),
+        infos.len(),
+        "there are no duplicates"
+    );
+    let mut counts = BTreeMap::new();
+    for info in &infos {
+        *counts.entry(info.kind).or_insert(0) += 1;
+    }
+    assert_eq!(
+        counts,
+        vec![(Kind::Tree, 13), (Kind::Blob, 10), (Kind::Commit, 10)]
+            .into_iter()
+            .collect()
+    );
+    assert_eq!(
+        infos[..2],
+        [
+            objects::Info {
+                id: hex_to_id("e9c530721f725fb738f684523369db986bc25cce"),
+                kind: Kind::Commit
+            },
+            objects::Info {
+                id: hex_to_id("5c0f11546afb45ebffdde7373a18503e0a63a5b3"),
+                kind: Kind::Tree
+            }
+        ],
+        "objects are returned breadth-first"
+    );
+    Ok(())
+}
+
+#[test]
+fn positions_track_seen_objects_without_changing_the_outcome() -> crate::Result {
+    let db = db()?;
+    let tip = hex_to_id("e9c530721f725fb738f684523369db986bc25cce");
+    let expected = objects::Reachable::new(Some(tip), objects::State::default(), |oid, buf| find(&db, oid, buf))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let positions: BTreeMap<ObjectId, usize> = expected
+        .iter()
+        .step_by(2)
+        .enumerate()
+        .map(|(pos, info)| (info.id, pos))
+        .collect();
+    let actual = objects::Reachable::new(Some(tip), objects::State::default(), |oid, buf| find(&db, oid, buf))
+        .with_positions(positions.len(), |oid| positions.get(&oid.to_owned()).copied())
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        actual, expected,
+        "objects with and without a position, including the tip, are still returned once"
+    );
+    Ok(())
+}
+
+#[test]
+fn tags_lead_to_their_target() -> crate::Result {
+    let db = db()?;
+    let infos = objects::Reachable::new(
+        Some(hex_to_id("943f47f61d83913a9e67e76abc8f1dd28c694bb8")),
+        objects::State::default(),
+        |oid, buf| find(&db, oid, buf),
+    )
+    .map(|res| res.map(|info| (info.id, info.kind)))
+    .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        infos,
+        vec![
+            (hex_to_id("943f47f61d83913a9e67e76abc8f1dd28c694bb8"), Kind::Tag),
+            (hex_to_id("5c0f11546afb45ebffdde7373a18503e0a63a5b3"), Kind::Tree),
+            (hex_to_id("20b117fdd3804508359ec883abe519486f0d19dd"), Kind::Blob),
+            (hex_to_id("1c6410ef8af4617941435a506c6a9456b7990551"), Kind::Tree),
+            (hex_to_id("262294a51b0b4343f9b1d1097cd6c9085f46dc7a"), Kind::Blob),
+            (hex_to_id("e6bfff5c1d0f0ecd501552b43a1e13d8008abc31"), Kind::Blob),
+            (hex_to_id("f2ad6c76f0115a6ba5b00456a849810e7ec0af20"), Kind::Blob),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn blobs_are_only_looked_up_on_request() -> crate::Result {
+    let db = db()?;
+    let find_all_but_blobs =
+        |oid: &oid, buf: &mut Vec<u8>| find(&db, oid, buf).filter(|(kind, _)| *kind != Kind::Blob).is_some();
+    let tree = hex_to_id("5c0f11546afb45ebffdde7373a18503e0a63a5b3");
+    let num_objects = objects::Reachable::new(Some(tree), objects::State::default(), |oid, buf| {
+        if find_all_but_blobs(oid, buf) {
+            find(&db, oid, buf)
+        } else {
+            None
+        }
+    })
+    .collect::<Result<Vec<_>, _>>()?
+    .len();
+    assert_eq!(num_objects, 6, "blobs are known by the trees referring to them");
+
+    let err = objects::Reachable::new(Some(tree), objects::State::default(), |oid, buf| {
+        if find_all_but_blobs(oid, buf) {
+            find(&db, oid, buf)
+        } else {
+            None
+        }
+    })
+    .with_blob_lookup()
+    .collect::<Result<Vec<_>, _>>()
+    .expect_err("missing blobs are detected");
+    assert!(matches!(
+        err,
+        objects::Error::NotFound { oid } if oid == hex_to_id("20b117fdd3804508359ec883abe519486f0d19dd")
+    ));
+    Ok(())
+}
+
+#[test]
+fn objects_must_be_of_the_kind_claimed_by_the_objects_referring_to_them() -> crate::Result {
+    let db = db()?;
+    let subtree = hex_to_id("1c6410ef8af4617941435a506c6a9456b7990551");
+    let err = objects::Reachable::new(
+        Some(hex_to_id("5c0f11546afb45ebffdde7373a18503e0a63a5b3")),
+        objects::State::default(),
+        |oid, buf| {
+            find(&db, oid, buf).map(|(kind, data)| {
+                if oid == subtree {
+                    (Kind::Blob, data)
+                } else {
+                    (kind, data)
+                }
+            })
+        },
+    )
+    .collect::<Result<Vec<_>, _>>()
+    .expect_err("the subtree is claimed to be a blob");
+    assert!(matches!(
+        err,
+        objects::Error::KindMismatch {
+            oid,
+            expected: Kind::Tree,
+            actual: Kind::Blob
+        } if oid == subtree
+    ));
+    Ok(())
+}
@@ -100,3 +100,103 @@ fn basic_nesting() -> crate::Result<()> {
     );
     Ok(())
 }
+
+mod options {
+    use git_odb::{pack, FindExt};
+    use git_traverse::{tree, tree::breadthfirst::Options};
+
+    use crate::hex_to_id;
+
+    /// Return the paths of all visited entries and the ids of all looked up trees, not counting the root tree.
+    fn traverse(options: Options) -> crate::Result<(Vec<String>, Vec<git_hash::ObjectId>)> {
+        let db = super::db()?;
+        let mut buf = Vec::new();
+        let mut buf2 = Vec::new();
+        let mut commit = db.find_existing_commit_iter(
+            hex_to_id("85df34aa34848b8138b2b3dcff5fb5c2b734e0ce"),
+            &mut buf,
+            &mut pack::cache::Never,
+        )?;
+        let mut recorder = tree::Recorder::default();
+        let mut lookups = Vec::new();
+        tree::breadthfirst::traverse_with_options(
+            db.find_existing_tree_iter(
+                commit.tree_id().expect("a tree is available in a commit"),
+                &mut buf2,
+                &mut pack::cache::Never,
+            )?,
+            tree::breadthfirst::State::default(),
+            |oid, buf| {
+                lookups.push(oid.to_owned());
+                db.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok()
+            },
+            &mut recorder,
+            &options,
+        )?;
+        Ok((
+            recorder
+                .records
+                .into_iter()
+                .map(|entry| entry.filepath.to_string())
+                .collect(),
+            lookups,
+        ))
+    }
+
+    #[test]
+    fn max_depth_stops_descending_into_trees() -> crate::Result {
+        let (paths, lookups) = traverse(Options {
+            max_depth: Some(1),
+            ..Default::default()
+        })?;
+        assert_eq!(paths, vec!["a", "b", "c", "d", "e", "f"]);
+        assert!(lookups.is_empty(), "trees at the maximum depth aren't looked up");
+
+        let (paths, _) = traverse(Options {
+            max_depth: Some(2),
+            ..Default::default()
+        })?;
+        assert_eq!(
+            paths,
+            vec!["a", "b", "c", "d", "e", "f", "d/a", "e/b", "f/c", "f/d", "f/z"],
+            "only f/d/x is too deep"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn pathspecs_skip_unrelated_trees() -> crate::Result {
+        let (paths, lookups) = traverse(Options {
+            pathspecs: vec!["f/d/".into(), "a".into()],
+            ..Default::default()
+        })?;
+        assert_eq!(
+            paths,
+            vec!["a", "f", "f/d", "f/d/x"],
+            "trees leading up to a pathspec are visited as well"
+        );
+        assert_eq!(
+            lookups,
+            vec![
+                hex_to_id("70fb16fc77b03e16acb4a5b1a6caf79ba302919a"),
+                hex_to_id("5805b676e247eb9a8046ad0c4d249cd2fb2513df"),
+            ],
+            "only f and f/d are looked up"
+        );
+
+        let (paths, _) = traverse(Options {
+            pathspecs: vec!["f/d".into(), "a".into()],
+            matching_only: true,
+            ..Default::default()
+        })?;
+        assert_eq!(paths, vec!["a", "f/d", "f/d/x"]);
+
+        let (paths, _) = traverse(Options {
+            pathspecs: vec!["f".into()],
+            matching_only: true,
+            max_depth: Some(2),
+        })?;
+        assert_eq!(paths, vec!["f", "f/c", "f/d", "f/z"], "limits can be combined");
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+#![no_main]
+
+use bstr::ByteSlice;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let name = data.as_bstr();
+    let partial = git_validate::reference::name_partial(name);
+    if git_validate::reference::name(name).is_ok() {
+        assert!(partial.is_ok(), "complete names are valid partial names as well");
+    }
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use bstr::ByteSlice;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Don't name this _; Rust may optimize it out.
+    let _a = git_validate::tagname(data.as_bstr());
+});
@@ -24,7 +24,18 @@ pub mod name {
             SingleDot {
                 display("Names must not be a single '.', but may contain it.")
             }
-
+            ComponentStartsWithDot {
+                display("A component of a reference name must not start with a '.'")
+            }
+            ComponentLockFileSuffix {
+                display("A component of a reference name must not end with '.lock'")
+            }
+            EndsWithDot {
+                display("A reference name must not end with a '.'")
+            }
+            SingleAt {
+                display("A reference name must not be '@' as it is an alias for 'HEAD'")
+            }
         }
     }
 }
@@ -53,21 +64,22 @@ fn validate(path: &BStr, mode: Mode) -> Result<&BStr, name::Error> {
     if path[0] == b'/' {
         return Err(name::Error::StartsWithSlash);
     }
-    let mut previous = 0;
-    let mut one_before_previous = 0;
+    if path == "@" {
+        return Err(name::Error::SingleAt);
+    }
     let mut saw_slash = false;
-    for byte in path.iter() {
-        match *byte {
-            b'/' if previous == b'.' && one_before_previous == b'/' => return Err(name::Error::SingleDot),
-            b'/' if previous == b'/' => return Err(name::Error::RepeatedSlash),
+    for (index, component) in path.split(|b| *b == b'/').enumerate() {
+        saw_slash |= index > 0;
+        match component {
+            b"" => return Err(name::Error::RepeatedSlash),
+            b"." => return Err(name::Error::SingleDot),
+            [b'.', ..] => return Err(name::Error::ComponentStartsWithDot),
+            _ if component.ends_with(b".lock") => return Err(name::Error::ComponentLockFileSuffix),
             _ => {}
         }
-
-        if *byte == b'/' {
-            saw_slash = true;
-        }
-        one_before_previous = previous;
-        previous = *byte;
+    }
+    if path.ends_with(b".") {
+        return Err(name::Error::EndsWithDot);
     }
 
     if let Mode::Complete = mode {
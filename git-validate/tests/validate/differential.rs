@@ -0,0 +1,55 @@
+//! Compare our validation of reference names to the one of `git check-ref-format --allow-onelevel` for all combinations of
+//! a few interesting tokens, as servers accepting references must agree with git on what's valid.
+use bstr::{BString, ByteSlice};
+use std::{ffi::OsStr, os::unix::ffi::OsStrExt, process::Command};
+
+/// Tokens which are combined into reference names, each chosen to trigger a rule of `git check-ref-format`.
+const TOKENS: &[&[u8]] = &[
+    b"a", b".", b"/", b"@", b"{", b".lock", b"*", b" ", b"\x01", b"\x7f", b"\xff",
+];
+const MAX_TOKENS: usize = 3;
+
+fn git_accepts(name: &[u8]) -> bool {
+    Command::new("git")
+        .args(&["check-ref-format", "--allow-onelevel"])
+        .arg(OsStr::from_bytes(name))
+        .status()
+        .expect("git is present")
+        .success()
+}
+
+fn names() -> Vec<BString> {
+    let mut out = Vec::new();
+    let mut previous: Vec<BString> = vec![BString::from("")];
+    for _ in 0..MAX_TOKENS {
+        previous = previous
+            .iter()
+            .flat_map(|prefix| {
+                TOKENS.iter().map(move |token| {
+                    let mut name = prefix.clone();
+                    name.extend_from_slice(token);
+                    name
+                })
+            })
+            .collect();
+        out.extend(previous.iter().cloned());
+    }
+    out
+}
+
+#[test]
+fn name_partial_agrees_with_git_check_ref_format() {
+    let divergences: Vec<_> = names()
+        .into_iter()
+        .filter_map(|name| {
+            let ours = git_validate::reference::name_partial(name.as_bstr());
+            (ours.is_ok() != git_accepts(&name)).then(|| format!("{:?}: {:?}", name, ours))
+        })
+        .collect();
+    assert!(
+        divergences.is_empty(),
+        "{} names are judged differently by git:\n{}",
+        divergences.len(),
+        divergences.join("\n")
+    );
+}
@@ -1,2 +1,4 @@
+#[cfg(unix)]
+mod differential;
 mod reference;
 mod tagname;
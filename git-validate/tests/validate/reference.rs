@@ -70,6 +70,18 @@ mod name_partial {
             b"refs//heads/name with spaces",
             RefError::Tag(TagError::InvalidByte(_))
         );
+        mktest!(
+            path_component_starts_with_dot,
+            b"refs/.hidden/main",
+            RefError::ComponentStartsWithDot
+        );
+        mktest!(
+            path_component_with_lock_suffix,
+            b"refs/heads.lock/main",
+            RefError::ComponentLockFileSuffix
+        );
+        mktest!(path_ends_with_dot, b"refs/heads/main.", RefError::EndsWithDot);
+        mktest!(single_at, b"@", RefError::SingleAt);
     }
 }
 
@@ -141,5 +153,17 @@ mod name {
             b"refs//heads/main",
             RefError::RepeatedSlash
         );
+        mktest!(
+            path_component_starts_with_dot,
+            b"refs/.hidden/main",
+            RefError::ComponentStartsWithDot
+        );
+        mktest!(
+            path_component_with_lock_suffix,
+            b"refs/heads.lock/main",
+            RefError::ComponentLockFileSuffix
+        );
+        mktest!(path_ends_with_dot, b"refs/heads/main.", RefError::EndsWithDot);
+        mktest!(single_at, b"@", RefError::SingleAt);
     }
 }
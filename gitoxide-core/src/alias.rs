@@ -0,0 +1,213 @@
+//! Expand `alias.<name>` configuration values into command-line arguments, similar to what git does.
+use anyhow::{anyhow, bail, Context};
+use git_config::{file::GitConfig, values::Boolean};
+use std::{convert::TryFrom, ffi::OsString, path::PathBuf};
+
+/// Return the paths to configuration files that may contain aliases, in order of precedence, i.e. the first file
+/// defining an alias wins.
+///
+/// These are the configuration of the repository the current working directory is in, followed by the global configuration
+/// files of the current user.
+pub fn config_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Ok(path) = git_repository::path::discover::existing(".") {
+        paths.push(path.into_repository_directory().join("config"));
+    }
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    if let Some(home) = &home {
+        paths.push(home.join(".gitconfig"));
+    }
+    match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(config_home) => paths.push(PathBuf::from(config_home).join("git").join("config")),
+        None => paths.extend(home.map(|home| home.join(".config").join("git").join("config"))),
+    }
+    paths
+}
+
+/// Read the value of `alias.<name>` from the first of `config_paths` defining it, skipping files that don't exist.
+pub fn lookup(config_paths: &[PathBuf], name: &str) -> anyhow::Result<Option<String>> {
+    raw_value(config_paths, "alias", name)
+}
+
+/// Return true unless `gitoxide.allowShellAliases` is set to false in the first of `config_paths` defining it, which
+/// prevents aliases starting with `!` from being run by [`expand()`].
+pub fn shell_aliases_allowed(config_paths: &[PathBuf]) -> anyhow::Result<bool> {
+    Ok(match raw_value(config_paths, "gitoxide", "allowShellAliases")? {
+        Some(value) => Boolean::try_from(value.as_str())
+            .map(bool::from)
+            .map_err(|_| anyhow!("gitoxide.allowShellAliases must be a boolean, got '{}'", value))?,
+        None => true,
+    })
+}
+
+/// Read the value of `<section>.<key>` from the first of `config_paths` defining it, skipping files that don't exist.
+fn raw_value(config_paths: &[PathBuf], section: &str, key: &str) -> anyhow::Result<Option<String>> {
+    for path in config_paths {
+        let buf = match std::fs::read(path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err).with_context(|| format!("Could not read configuration at '{}'", path.display()))
+            }
+        };
+        let config = GitConfig::try_from(&buf)
+            .map_err(|err| err.to_owned())
+            .with_context(|| format!("Could not parse configuration at '{}'", path.display()))?;
+        if let Ok(value) = config.get_raw_value(section, None, key) {
+            return Ok(Some(String::from_utf8_lossy(&value).into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// The outcome of [`expand()`].
+#[derive(Debug, PartialEq)]
+pub enum Expansion {
+    /// The arguments with the alias of the subcommand expanded, or unchanged if there was none.
+    Args(Vec<OsString>),
+    /// The subcommand is a shell alias whose value started with `!`, which has to be run with [`run_shell()`] instead.
+    Shell {
+        /// The shell command without the leading `!`.
+        command: String,
+        /// All arguments following the subcommand.
+        args: Vec<OsString>,
+    },
+}
+
+/// Configure how [`expand()`] finds and expands aliases.
+#[derive(Debug, Clone, Copy)]
+pub struct Options<'a> {
+    /// The names of all subcommands, which can't be overridden by aliases.
+    pub builtins: &'a [&'a str],
+    /// The flags which may precede the subcommand and take a value as separate argument, like `--threads` in `--threads 4`.
+    pub flags_with_values: &'a [&'a str],
+    /// If false, shell aliases cause an error instead of being returned as [`Expansion::Shell`].
+    pub allow_shell: bool,
+}
+
+/// Replace the subcommand in `args`, the first argument after the program name that is neither a flag nor the value of one of
+/// the `flags_with_values`, with the arguments its alias expands to as returned by `lookup(name)`. Aliases may refer to other
+/// aliases, but never to `builtins`.
+///
+/// If an alias is a shell alias, its value starting with `!`, the command is returned as [`Expansion::Shell`] along with
+/// the arguments following the subcommand, unless shell aliases aren't allowed.
+pub fn expand(
+    mut args: Vec<OsString>,
+    Options {
+        builtins,
+        flags_with_values,
+        allow_shell,
+    }: Options<'_>,
+    mut lookup: impl FnMut(&str) -> anyhow::Result<Option<String>>,
+) -> anyhow::Result<Expansion> {
+    let subcommand_pos = match subcommand_position(&args, flags_with_values) {
+        Some(pos) => pos,
+        None => return Ok(Expansion::Args(args)),
+    };
+    let mut seen = Vec::<String>::new();
+    loop {
+        let name = match args[subcommand_pos].to_str() {
+            Some(name) if !builtins.contains(&name) => name.to_owned(),
+            _ => return Ok(Expansion::Args(args)),
+        };
+        let value = match lookup(&name)? {
+            Some(value) => value,
+            None => return Ok(Expansion::Args(args)),
+        };
+        if seen.contains(&name) {
+            seen.push(name);
+            bail!("Alias loop detected: {}", seen.join(" -> "));
+        }
+        if let Some(command) = value.trim_start().strip_prefix('!') {
+            if !allow_shell {
+                bail!(
+                    "Shell alias '{}' is not run as gitoxide.allowShellAliases is false",
+                    name
+                );
+            }
+            return Ok(Expansion::Shell {
+                command: command.to_owned(),
+                args: args.split_off(subcommand_pos + 1),
+            });
+        }
+        let expansion = split(&value).with_context(|| format!("Could not expand alias '{}'", name))?;
+        if expansion.is_empty() {
+            bail!("Alias '{}' expands to nothing", name);
+        }
+        args.splice(
+            subcommand_pos..=subcommand_pos,
+            expansion.into_iter().map(OsString::from),
+        );
+        seen.push(name);
+    }
+}
+
+/// Return the position of the subcommand in `args`, skipping the program name, flags and the values of `flags_with_values`.
+fn subcommand_position(args: &[OsString], flags_with_values: &[&str]) -> Option<usize> {
+    let mut pos = 1;
+    while let Some(arg) = args.get(pos) {
+        if arg == "--" {
+            return None;
+        }
+        match arg.to_str() {
+            Some(flag) if flag.starts_with('-') => {
+                pos += if flags_with_values.contains(&flag) { 2 } else { 1 };
+            }
+            _ => return Some(pos),
+        }
+    }
+    None
+}
+
+/// Run the `command` of a shell alias with `sh`, passing `args` to it like git does, and return its exit status.
+pub fn run_shell(command: &str, args: &[OsString]) -> std::io::Result<std::process::ExitStatus> {
+    std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", command))
+        .arg(command)
+        .args(args)
+        .status()
+}
+
+/// Split `value` into words like a shell would, honoring single and double quotes as well as backslash escapes.
+fn split(value: &str) -> anyhow::Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => bail!("Unterminated single quote in '{}'", value),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c) => word.push(c),
+                            None => bail!("Unterminated double quote in '{}'", value),
+                        },
+                        Some(c) => word.push(c),
+                        None => bail!("Unterminated double quote in '{}'", value),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+                None => bail!("Trailing backslash in '{}'", value),
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
@@ -49,6 +49,8 @@ where
         Some(OutputFormat::Human) => drop(print_human_output(&mut out, &stats)),
         #[cfg(feature = "serde1")]
         Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, &stats)?,
+        #[cfg(feature = "serde1")]
+        Some(OutputFormat::JsonLines) => crate::output::write_json_line(out, &stats)?,
         _ => {}
     }
 
@@ -71,5 +73,21 @@ fn print_human_output(out: &mut impl io::Write, stats: &Outcome) -> io::Result<(
         writeln!(out, "unknown")?;
     }
 
+    writeln!(out, "\nlayers, starting with the base")?;
+    for (index, layer) in stats.layers.iter().enumerate() {
+        writeln!(
+            out,
+            "\t{:>2}: {} ({} commits, generations {}..={})",
+            index,
+            layer.path.file_name().map_or_else(
+                || layer.path.display().to_string(),
+                |name| name.to_string_lossy().into_owned()
+            ),
+            layer.statistics.num_commits,
+            layer.statistics.min_generation,
+            layer.statistics.max_generation
+        )?;
+    }
+
     Ok(())
 }
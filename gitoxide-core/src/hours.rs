@@ -2,7 +2,6 @@ use anyhow::{anyhow, bail};
 use bstr::BString;
 use git_repository::{actor, interrupt, object, odb, prelude::*, progress, Progress};
 use itertools::Itertools;
-use rayon::prelude::*;
 use std::{
     collections::{hash_map::Entry, HashMap},
     ffi::OsStr,
@@ -10,6 +9,7 @@ use std::{
     fmt::{Display, Formatter},
     io,
     path::Path,
+    sync::Arc,
     time::Instant,
 };
 
@@ -50,54 +50,44 @@ where
         .find_one_existing(refname.to_string_lossy().as_ref())?
         .peel_to_id_in_place()?
         .to_owned();
+    let db = Arc::new(repo.odb);
 
-    let all_commits = {
+    let (commit_ids, mut authors) = {
         let start = Instant::now();
         let mut progress = progress.add_child("Traverse commit graph");
         progress.init(None, progress::count("commits"));
-        let mut pack_cache = odb::pack::cache::Never;
-        let mut commits: Vec<Vec<u8>> = Vec::new();
+        let handle = db.to_handle_with_cache(db.new_pack_cache());
+        let mut authors = HashMap::new();
+        let mut commits = Vec::new();
         for c in interrupt::Iter::new(
             commit_id.ancestors_iter(|oid, buf| {
                 progress.inc();
-                repo.odb.find_existing(oid, buf, &mut pack_cache).ok().map(|o| {
-                    commits.push(o.data.to_owned());
-                    object::immutable::CommitIter::from_bytes(o.data)
-                })
+                // The handle uses its own pack cache, and authors are extracted while the data is at hand.
+                let commit = handle.find_existing(oid, buf, &mut odb::pack::cache::Never).ok()?;
+                if commit.kind == object::Kind::Commit {
+                    authors.entry(oid.to_owned()).or_insert_with(|| {
+                        object::immutable::CommitIter::from_bytes(commit.data)
+                            .signatures()
+                            .next()
+                            .map(actor::Signature::from)
+                    });
+                }
+                commit.into_commit_iter()
             }),
             || anyhow!("Cancelled by user"),
         ) {
-            c??;
+            commits.push(c??);
         }
         progress.show_throughput(start);
-        commits
+        (commits, authors)
     };
 
     let start = Instant::now();
-    #[allow(clippy::redundant_closure)]
-    let mut all_commits: Vec<actor::Signature> = all_commits
-        .into_par_iter()
-        .map(|commit_data: Vec<u8>| {
-            object::immutable::CommitIter::from_bytes(&commit_data)
-                .signatures()
-                .next()
-                .map(|author| actor::Signature::from(author))
-        })
-        .try_fold(
-            || Vec::new(),
-            |mut out: Vec<_>, item| {
-                out.push(item?);
-                Some(out)
-            },
-        )
-        .try_reduce(
-            || Vec::new(),
-            |mut out, vec| {
-                out.extend(vec.into_iter());
-                Some(out)
-            },
-        )
-        .ok_or_else(|| anyhow!("An error occurred when decoding commits - one commit could not be parsed"))?;
+    let mut all_commits: Vec<actor::Signature> = commit_ids
+        .iter()
+        .map(|id| authors.remove(id).flatten())
+        .collect::<Option<_>>()
+        .ok_or_else(|| anyhow!("An error occurred when decoding commits - one commit could not be found or parsed"))?;
     all_commits.sort_by(|a, b| a.email.cmp(&b.email).then(a.time.time.cmp(&b.time.time).reverse()));
     if all_commits.is_empty() {
         bail!("No commits to process");
@@ -179,7 +169,7 @@ fn estimate_hours(commits: &[actor::Signature]) -> WorkByEmail {
         + commits.iter().rev().tuple_windows().fold(
             0_f32,
             |hours, (cur, next): (&actor::Signature, &actor::Signature)| {
-                let change_in_minutes = (next.time.time - cur.time.time) as f32 / MINUTES_PER_HOUR;
+                let change_in_minutes = next.time.seconds_since(&cur.time) as f32 / MINUTES_PER_HOUR;
                 if change_in_minutes < MAX_COMMIT_DIFFERENCE_IN_MINUTES {
                     hours + change_in_minutes as f32 / MINUTES_PER_HOUR
                 } else {
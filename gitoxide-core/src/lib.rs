@@ -9,6 +9,8 @@ pub enum OutputFormat {
     Human,
     #[cfg(feature = "serde1")]
     Json,
+    #[cfg(feature = "serde1")]
+    JsonLines,
 }
 
 impl OutputFormat {
@@ -17,6 +19,8 @@ impl OutputFormat {
             "human",
             #[cfg(feature = "serde1")]
             "json",
+            #[cfg(feature = "serde1")]
+            "json-lines",
         ]
     }
 }
@@ -30,12 +34,17 @@ impl FromStr for OutputFormat {
             "human" => OutputFormat::Human,
             #[cfg(feature = "serde1")]
             "json" => OutputFormat::Json,
+            #[cfg(feature = "serde1")]
+            "json-lines" => OutputFormat::JsonLines,
             _ => return Err(format!("Invalid output format: '{}'", s)),
         })
     }
 }
 
+pub mod alias;
 pub mod net;
+#[cfg(feature = "serde1")]
+mod output;
 
 pub mod commitgraph;
 #[cfg(feature = "estimate-hours")]
@@ -43,9 +52,11 @@ pub mod hours;
 #[cfg(feature = "organize")]
 pub mod organize;
 pub mod pack;
+pub mod packetline;
 #[cfg(any(feature = "async-client", feature = "blocking-client"))]
 pub mod remote;
 pub mod repository;
+pub mod url;
 
 #[cfg(all(feature = "async-client", feature = "blocking-client"))]
 compile_error!("Cannot set both 'blocking-client' and 'async-client' features as they are mutually exclusive");
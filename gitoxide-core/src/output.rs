@@ -0,0 +1,19 @@
+//! Machine-readable output shared by all commands.
+use std::io;
+
+/// Write `item` as a single line of JSON to `out`, suitable for tools consuming one JSON document per line.
+pub(crate) fn write_json_line(mut out: impl io::Write, item: &impl serde::Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut out, item)?;
+    out.write_all(b"\n")
+}
+
+/// Write each of the given `items` as a single line of JSON to `out`, allowing consumers to process them as a stream.
+pub(crate) fn write_json_lines<T: serde::Serialize>(
+    mut out: impl io::Write,
+    items: impl IntoIterator<Item = T>,
+) -> io::Result<()> {
+    for item in items {
+        write_json_line(&mut out, &item)?;
+    }
+    Ok(())
+}
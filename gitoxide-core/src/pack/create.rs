@@ -5,7 +5,7 @@ use git_repository::{
     hash::ObjectId,
     interrupt,
     object::bstr::ByteVec,
-    odb::{linked, pack},
+    odb::{self, linked, pack},
     prelude::{Finalize, FindExt},
     progress, traverse, Progress,
 };
@@ -95,7 +95,9 @@ pub fn create<W>(
 where
     W: std::io::Write,
 {
-    let db = Arc::new(find_db(repository)?);
+    let git_dir = git_repository::path::discover::existing(repository)?.into_repository_directory();
+    let db = Arc::new(linked::Store::at(git_dir.join("objects"))?);
+    let shallow = odb::shallow::Boundary::from_git_dir(&git_dir)?;
     progress.init(Some(4), progress::steps());
     let tips = tips.into_iter();
     let make_cancellation_err = || anyhow!("Cancelled by user");
@@ -114,6 +116,7 @@ where
                         move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
                     },
                 )
+                .with_shallow(shallow.commits().iter().cloned())
                 .inspect(|_| progress.inc()),
                 make_cancellation_err,
             )
@@ -141,6 +144,7 @@ where
                 Arc::clone(&db),
                 pack::cache::lru::StaticLinkedList::<64>::default,
                 input,
+                shallow.commits().to_vec(),
                 progress.add_child("threads"),
                 pack::data::output::count::from_objects_iter::Options {
                     thread_limit: if nondeterministic_count || matches!(expansion, ObjectExpansion::None) {
@@ -179,6 +183,7 @@ where
                 thread_limit,
                 chunk_size,
                 version: Default::default(),
+                compression: Default::default(),
             },
         ))
     };
@@ -242,20 +247,13 @@ where
     Ok(())
 }
 
-fn find_db(repository: impl AsRef<Path>) -> anyhow::Result<linked::Store> {
-    let path = repository.as_ref();
-    Ok(linked::Store::at(
-        git_repository::path::discover::existing(path)?
-            .into_repository_directory()
-            .join("objects"),
-    )?)
-}
-
 fn print(stats: Statistics, format: OutputFormat, out: impl std::io::Write) -> anyhow::Result<()> {
     match format {
         OutputFormat::Human => human_output(stats, out).map_err(Into::into),
         #[cfg(feature = "serde1")]
         OutputFormat::Json => serde_json::to_writer_pretty(out, &stats).map_err(Into::into),
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => crate::output::write_json_line(out, &stats).map_err(Into::into),
     }
 }
 
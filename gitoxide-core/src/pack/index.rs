@@ -84,6 +84,7 @@ pub fn from_pack(
         thread_limit: ctx.thread_limit,
         iteration_mode: ctx.iteration_mode.into(),
         index_kind: pack::index::Version::default(),
+        compression: Default::default(),
     };
     let out = ctx.out;
     let format = ctx.format;
@@ -109,6 +110,8 @@ pub fn from_pack(
         OutputFormat::Human => drop(human_output(out, res)),
         #[cfg(feature = "serde1")]
         OutputFormat::Json => serde_json::to_writer_pretty(out, &res)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => crate::output::write_json_line(out, &res)?,
     };
     Ok(())
 }
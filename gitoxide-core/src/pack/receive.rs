@@ -10,7 +10,7 @@ use git_repository::{
         transport::client::Capabilities,
     },
 };
-use std::{io, path::PathBuf, sync::atomic::AtomicBool, sync::Arc};
+use std::{borrow::Cow, io, path::PathBuf, sync::atomic::AtomicBool, sync::Arc};
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
 
@@ -34,7 +34,7 @@ impl<W> protocol::fetch::DelegateBlocking for CloneDelegate<W> {
         &mut self,
         server: &Capabilities,
         arguments: &mut Vec<BString>,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
     ) -> io::Result<LsRefsAction> {
         if server.contains("ls-refs") {
             arguments.extend(FILTER.iter().map(|r| format!("ref-prefix {}", r).into()));
@@ -46,7 +46,7 @@ impl<W> protocol::fetch::DelegateBlocking for CloneDelegate<W> {
         &mut self,
         version: transport::Protocol,
         _server: &Capabilities,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
         _refs: &[Ref],
     ) -> io::Result<Action> {
         if version == transport::Protocol::V1 {
@@ -103,6 +103,7 @@ mod blocking_io {
                 thread_limit: self.ctx.thread_limit,
                 index_kind: pack::index::Version::V2,
                 iteration_mode: pack::data::input::Mode::Verify,
+                compression: Default::default(),
             };
             let outcome = pack::bundle::Bundle::write_to_directory(
                 input,
@@ -136,6 +137,11 @@ mod blocking_io {
                 OutputFormat::Json => {
                     serde_json::to_writer_pretty(&mut self.ctx.out, &JsonOutcome::from_outcome_and_refs(outcome, refs))?
                 }
+                #[cfg(feature = "serde1")]
+                OutputFormat::JsonLines => crate::output::write_json_line(
+                    &mut self.ctx.out,
+                    &JsonOutcome::from_outcome_and_refs(outcome, refs),
+                )?,
             };
             Ok(())
         }
@@ -193,6 +199,7 @@ mod async_io {
                 thread_limit: self.ctx.thread_limit,
                 index_kind: pack::index::Version::V2,
                 iteration_mode: pack::data::input::Mode::Verify,
+                compression: Default::default(),
             };
             let outcome = pack::Bundle::write_to_directory(
                 futures_lite::io::BlockOn::new(input),
@@ -228,6 +235,11 @@ mod async_io {
                     &mut self.ctx.out,
                     &JsonOutcome::from_outcome_and_refs(outcome, &refs),
                 )?,
+                #[cfg(feature = "serde1")]
+                OutputFormat::JsonLines => crate::output::write_json_line(
+                    &mut self.ctx.out,
+                    &JsonOutcome::from_outcome_and_refs(outcome, &refs),
+                )?,
             };
             Ok(())
         }
@@ -138,7 +138,7 @@ where
         "idx" => {
             let idx = odb::pack::index::File::at(path).with_context(|| "Could not open pack index file")?;
             let packfile_path = path.with_extension("pack");
-            let pack = odb::pack::data::File::at(&packfile_path)
+            let pack = odb::pack::data::File::at_with_hash_kind(&packfile_path, idx.hash_kind())
                 .map_err(|e| {
                     writeln!(
                         err,
@@ -181,6 +181,8 @@ where
             Some(OutputFormat::Human) => drop(print_statistics(&mut out, stats)),
             #[cfg(feature = "serde1")]
             Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, stats)?,
+            #[cfg(feature = "serde1")]
+            Some(OutputFormat::JsonLines) => crate::output::write_json_line(out, stats)?,
             _ => {}
         };
     }
@@ -0,0 +1,76 @@
+use crate::OutputFormat;
+use anyhow::{bail, Context as AnyhowContext, Result};
+use git_packetline::PacketLine;
+use std::io;
+
+/// A single packet line as it is presented to the user.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde1", serde(tag = "kind", rename_all = "kebab-case"))]
+pub enum Line {
+    Data { data: String },
+    Flush,
+    Delimiter,
+    ResponseEnd,
+}
+
+impl From<PacketLine<'_>> for Line {
+    fn from(line: PacketLine<'_>) -> Self {
+        match line {
+            PacketLine::Data(data) => Line::Data {
+                data: String::from_utf8_lossy(data).into_owned(),
+            },
+            PacketLine::Flush => Line::Flush,
+            PacketLine::Delimiter => Line::Delimiter,
+            PacketLine::ResponseEnd => Line::ResponseEnd,
+        }
+    }
+}
+
+/// Decode all packet lines from `input` until it is depleted and write them to `out` according to `format`,
+/// returning the amount of decoded lines.
+pub fn decode(mut input: impl io::Read, format: OutputFormat, mut out: impl io::Write) -> Result<usize> {
+    let mut buf = Vec::new();
+    input
+        .read_to_end(&mut buf)
+        .with_context(|| "Could not read packet lines from input")?;
+
+    let mut lines = Vec::new();
+    let mut data = buf.as_slice();
+    while !data.is_empty() {
+        match git_packetline::decode::streaming(data)
+            .with_context(|| format!("Could not decode packet line #{}", lines.len() + 1))?
+        {
+            git_packetline::decode::Stream::Complete { line, bytes_consumed } => {
+                lines.push(Line::from(line));
+                data = &data[bytes_consumed..];
+            }
+            git_packetline::decode::Stream::Incomplete { bytes_needed } => bail!(
+                "Input ended within packet line #{}, which needs {} more bytes",
+                lines.len() + 1,
+                bytes_needed
+            ),
+        }
+    }
+
+    let num_lines = lines.len();
+    match format {
+        OutputFormat::Human => print_human_output(&mut out, &lines)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut out, &lines)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => crate::output::write_json_lines(&mut out, &lines)?,
+    }
+    Ok(num_lines)
+}
+
+fn print_human_output(mut out: impl io::Write, lines: &[Line]) -> io::Result<()> {
+    for line in lines {
+        match line {
+            Line::Data { data } => writeln!(out, "data {}", data.strip_suffix('\n').unwrap_or(data))?,
+            Line::Flush => writeln!(out, "flush")?,
+            Line::Delimiter => writeln!(out, "delimiter")?,
+            Line::ResponseEnd => writeln!(out, "response-end")?,
+        }
+    }
+    Ok(())
+}
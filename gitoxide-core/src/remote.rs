@@ -10,7 +10,7 @@ pub mod refs {
 
     pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
 
-    use std::io;
+    use std::{borrow::Cow, io};
 
     #[derive(Default)]
     struct LsRemotes {
@@ -22,7 +22,7 @@ pub mod refs {
             &mut self,
             _version: transport::Protocol,
             _server: &transport::client::Capabilities,
-            _features: &mut Vec<(&str, Option<&str>)>,
+            _features: &mut Vec<(&str, Option<Cow<'_, str>>)>,
             refs: &[Ref],
         ) -> io::Result<Action> {
             self.refs = refs.into();
@@ -89,6 +89,10 @@ pub mod refs {
                                 ctx.out,
                                 &delegate.refs.into_iter().map(JsonRef::from).collect::<Vec<_>>(),
                             )?,
+                            #[cfg(feature = "serde1")]
+                            OutputFormat::JsonLines => {
+                                crate::output::write_json_lines(ctx.out, delegate.refs.into_iter().map(JsonRef::from))?
+                            }
                         }
                         Ok(())
                     })
@@ -142,6 +146,10 @@ pub mod refs {
                     ctx.out,
                     &delegate.refs.into_iter().map(JsonRef::from).collect::<Vec<_>>(),
                 )?,
+                #[cfg(feature = "serde1")]
+                OutputFormat::JsonLines => {
+                    crate::output::write_json_lines(ctx.out, delegate.refs.into_iter().map(JsonRef::from))?
+                }
             };
             Ok(())
         }
@@ -197,17 +205,13 @@ pub mod refs {
     pub(crate) fn print(mut out: impl io::Write, refs: &[Ref]) -> io::Result<()> {
         for r in refs {
             match r {
-                Ref::Direct { path, object } => writeln!(&mut out, "{} {}", object.to_sha1_hex_string(), path),
+                Ref::Direct { path, object } => writeln!(&mut out, "{} {}", object, path),
                 Ref::Peeled { path, object, tag } => {
-                    writeln!(&mut out, "{} {} tag:{}", object.to_sha1_hex_string(), path, tag)
+                    writeln!(&mut out, "{} {} tag:{}", object, path, tag)
+                }
+                Ref::Symbolic { path, target, object } => {
+                    writeln!(&mut out, "{} {} symref-target:{}", object, path, target)
                 }
-                Ref::Symbolic { path, target, object } => writeln!(
-                    &mut out,
-                    "{} {} symref-target:{}",
-                    object.to_sha1_hex_string(),
-                    path,
-                    target
-                ),
             }?;
         }
         Ok(())
@@ -0,0 +1,30 @@
+use crate::OutputFormat;
+use anyhow::{Context as AnyhowContext, Result};
+use std::io;
+
+/// Parse `url` and write its components to `out` according to `format`.
+pub fn parse(url: &str, format: OutputFormat, mut out: impl io::Write) -> Result<git_url::Url> {
+    let url = git_url::parse(url.as_bytes()).with_context(|| format!("Could not parse url '{}'", url))?;
+    match format {
+        OutputFormat::Human => print_human_output(&mut out, &url)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut out, &url)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => crate::output::write_json_line(&mut out, &url)?,
+    }
+    Ok(url)
+}
+
+fn print_human_output(mut out: impl io::Write, url: &git_url::Url) -> io::Result<()> {
+    writeln!(out, "scheme: {}", url.scheme)?;
+    if let Some(user) = &url.user {
+        writeln!(out, "user: {}", user)?;
+    }
+    if let Some(host) = &url.host {
+        writeln!(out, "host: {}", host)?;
+    }
+    if let Some(port) = url.port {
+        writeln!(out, "port: {}", port)?;
+    }
+    writeln!(out, "path: {}", url.path)
+}
@@ -1,5 +1,5 @@
 use crate::{
-    plumbing::lean::options::{self, Args, SubCommands},
+    plumbing::lean::options::{self, free, Args, SubCommands},
     shared::lean::prepare,
 };
 use anyhow::Result;
@@ -111,106 +111,135 @@ pub fn main() -> Result<()> {
             #[cfg(feature = "gitoxide-core-async-client")]
             return futures_lite::future::block_on(res);
         }
-        SubCommands::IndexFromPack(options::IndexFromPack {
-            iteration_mode,
-            pack_path,
-            directory,
-        }) => {
-            use gitoxide_core::pack::index::PathOrRead;
-            let (_handle, progress) = prepare(verbose, "pack-explode", core::pack::index::PROGRESS_RANGE);
-            let input = if let Some(path) = pack_path {
-                PathOrRead::Path(path)
-            } else {
-                #[cfg(feature = "atty")]
-                if atty::is(atty::Stream::Stdin) {
-                    anyhow::bail!("Refusing to read from standard input as no path is given, but it's a terminal.")
-                }
-                PathOrRead::Read(Box::new(std::io::stdin()))
-            };
-            core::pack::index::from_pack(
-                input,
-                directory,
-                DoOrDiscard::from(progress),
-                core::pack::index::Context {
-                    thread_limit,
-                    iteration_mode: iteration_mode.unwrap_or_default(),
-                    format: OutputFormat::Human,
-                    out: io::stdout(),
-                    should_interrupt: &git_repository::interrupt::IS_INTERRUPTED,
-                },
-            )
-        }
-        SubCommands::PackExplode(options::PackExplode {
-            pack_path,
-            sink_compress,
-            object_path,
-            verify,
-            check,
-            delete_pack,
-        }) => {
-            let (_handle, progress) = prepare(verbose, "pack-explode", None);
-            core::pack::explode::pack_or_pack_index(
-                pack_path,
-                object_path,
-                check.unwrap_or_default(),
-                progress,
-                core::pack::explode::Context {
-                    thread_limit,
-                    delete_pack,
-                    sink_compress,
-                    verify,
-                    should_interrupt,
-                },
-            )
-        }
-        SubCommands::PackVerify(options::PackVerify {
-            path,
-            statistics,
-            algorithm,
-            decode,
-            re_encode,
-        }) => {
-            use self::core::pack::verify;
-            let (_handle, progress) = prepare(verbose, "pack-verify", None);
-            core::pack::verify::pack_or_pack_index(
-                path,
-                progress,
-                core::pack::verify::Context {
-                    output_statistics: if statistics {
-                        Some(core::OutputFormat::Human)
-                    } else {
-                        None
+        SubCommands::Free(options::Free { cmd }) => match cmd {
+            free::SubCommands::Pack(free::Pack {
+                cmd:
+                    free::pack::SubCommands::IndexFromData(free::pack::IndexFromData {
+                        iteration_mode,
+                        pack_path,
+                        directory,
+                    }),
+            }) => {
+                use gitoxide_core::pack::index::PathOrRead;
+                let (_handle, progress) = prepare(verbose, "pack-index-from-data", core::pack::index::PROGRESS_RANGE);
+                let input = if let Some(path) = pack_path {
+                    PathOrRead::Path(path)
+                } else {
+                    #[cfg(feature = "atty")]
+                    if atty::is(atty::Stream::Stdin) {
+                        anyhow::bail!("Refusing to read from standard input as no path is given, but it's a terminal.")
+                    }
+                    PathOrRead::Read(Box::new(std::io::stdin()))
+                };
+                core::pack::index::from_pack(
+                    input,
+                    directory,
+                    DoOrDiscard::from(progress),
+                    core::pack::index::Context {
+                        thread_limit,
+                        iteration_mode: iteration_mode.unwrap_or_default(),
+                        format: OutputFormat::Human,
+                        out: io::stdout(),
+                        should_interrupt: &git_repository::interrupt::IS_INTERRUPTED,
                     },
-                    algorithm: algorithm.unwrap_or(verify::Algorithm::LessTime),
-                    thread_limit,
-                    mode: match (decode, re_encode) {
-                        (true, false) => verify::Mode::Sha1Crc32Decode,
-                        (true, true) | (false, true) => verify::Mode::Sha1Crc32DecodeEncode,
-                        (false, false) => verify::Mode::Sha1Crc32,
+                )
+            }
+            free::SubCommands::Pack(free::Pack {
+                cmd:
+                    free::pack::SubCommands::Explode(free::pack::Explode {
+                        pack_path,
+                        sink_compress,
+                        object_path,
+                        verify,
+                        check,
+                        delete_pack,
+                    }),
+            }) => {
+                let (_handle, progress) = prepare(verbose, "pack-explode", None);
+                core::pack::explode::pack_or_pack_index(
+                    pack_path,
+                    object_path,
+                    check.unwrap_or_default(),
+                    progress,
+                    core::pack::explode::Context {
+                        thread_limit,
+                        delete_pack,
+                        sink_compress,
+                        verify,
+                        should_interrupt,
                     },
-                    out: stdout(),
-                    err: stderr(),
-                    should_interrupt,
-                },
-            )
-            .map(|_| ())
-        }
-        SubCommands::CommitGraphVerify(options::CommitGraphVerify { path, statistics }) => {
-            use self::core::commitgraph::verify;
+                )
+            }
+            free::SubCommands::Pack(free::Pack {
+                cmd:
+                    free::pack::SubCommands::Verify(free::pack::Verify {
+                        path,
+                        statistics,
+                        algorithm,
+                        decode,
+                        re_encode,
+                    }),
+            }) => {
+                use self::core::pack::verify;
+                let (_handle, progress) = prepare(verbose, "pack-verify", None);
+                core::pack::verify::pack_or_pack_index(
+                    path,
+                    progress,
+                    core::pack::verify::Context {
+                        output_statistics: if statistics {
+                            Some(core::OutputFormat::Human)
+                        } else {
+                            None
+                        },
+                        algorithm: algorithm.unwrap_or(verify::Algorithm::LessTime),
+                        thread_limit,
+                        mode: match (decode, re_encode) {
+                            (true, false) => verify::Mode::Sha1Crc32Decode,
+                            (true, true) | (false, true) => verify::Mode::Sha1Crc32DecodeEncode,
+                            (false, false) => verify::Mode::Sha1Crc32,
+                        },
+                        out: stdout(),
+                        err: stderr(),
+                        should_interrupt,
+                    },
+                )
+                .map(|_| ())
+            }
+            free::SubCommands::CommitGraph(free::CommitGraph {
+                cmd: free::commitgraph::SubCommands::Verify(free::commitgraph::Verify { path, statistics }),
+            }) => {
+                use self::core::commitgraph::verify;
 
-            verify::graph_or_file(
-                path,
-                verify::Context {
-                    err: stderr(),
-                    out: stdout(),
-                    output_statistics: if statistics {
-                        Some(core::OutputFormat::Human)
-                    } else {
-                        None
+                verify::graph_or_file(
+                    path,
+                    verify::Context {
+                        err: stderr(),
+                        out: stdout(),
+                        output_statistics: if statistics {
+                            Some(core::OutputFormat::Human)
+                        } else {
+                            None
+                        },
                     },
-                },
-            )
-            .map(|_| ())
-        }
+                )
+                .map(|_| ())
+            }
+            free::SubCommands::Packetline(free::Packetline {
+                cmd: free::packetline::SubCommands::Decode(free::packetline::Decode { path }),
+            }) => match path {
+                Some(path) => core::packetline::decode(std::fs::File::open(path)?, OutputFormat::Human, stdout()),
+                None => {
+                    #[cfg(feature = "atty")]
+                    if atty::is(atty::Stream::Stdin) {
+                        anyhow::bail!("Refusing to read from standard input as no path is given, but it's a terminal.")
+                    }
+                    core::packetline::decode(stdin(), OutputFormat::Human, stdout())
+                }
+            }
+            .map(|_| ()),
+            free::SubCommands::Url(free::Url {
+                cmd: free::url::SubCommands::Parse(free::url::Parse { url }),
+            }) => core::url::parse(&url, OutputFormat::Human, stdout()).map(|_| ()),
+        },
     }
 }
@@ -29,43 +29,11 @@ pub struct Args {
 #[argh(subcommand)]
 pub enum SubCommands {
     PackCreate(PackCreate),
-    PackVerify(PackVerify),
-    PackExplode(PackExplode),
-    IndexFromPack(IndexFromPack),
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     RemoteRefList(RemoteRefList),
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     PackReceive(PackReceive),
-    CommitGraphVerify(CommitGraphVerify),
-}
-
-/// Create an index from a packfile.
-///
-/// This command can also be used to stream packs to standard input or to repair partial packs.
-#[derive(FromArgs, PartialEq, Debug)]
-#[argh(subcommand, name = "pack-index-from-data")]
-pub struct IndexFromPack {
-    /// specify how to iterate the pack, defaults to 'verify'
-    ///
-    /// Valid values are
-    ///
-    ///  **as-is** do not do anything and expect the pack file to be valid as per the trailing hash,
-    ///  **verify** the input ourselves and validate that it matches with the hash provided in the pack,
-    ///  **restore** hash the input ourselves and ignore failing entries, instead finish the pack with the hash we computed
-    #[argh(option, short = 'i')]
-    pub iteration_mode: Option<core::pack::index::IterationMode>,
-
-    /// path to the pack file to read (with .pack extension).
-    ///
-    /// If unset, the pack file is expected on stdin.
-    #[argh(option, short = 'p')]
-    pub pack_path: Option<PathBuf>,
-
-    /// the folder into which to place the pack and the generated index file
-    ///
-    /// If unset, only informational output will be provided to standard output.
-    #[argh(positional)]
-    pub directory: Option<PathBuf>,
+    Free(Free),
 }
 
 /// List remote references from a remote identified by a url.
@@ -118,50 +86,6 @@ pub struct PackReceive {
     pub directory: Option<PathBuf>,
 }
 
-/// Explode a pack into loose objects.
-///
-/// This can be useful in case of partially invalidated packs to extract as much information as possible,
-/// or because working with loose objects is easier with custom tooling.
-#[derive(FromArgs, PartialEq, Debug)]
-#[argh(subcommand, name = "pack-explode")]
-pub struct PackExplode {
-    #[argh(switch)]
-    /// read written objects back and assert they match their source. Fail the operation otherwise.
-    ///
-    /// Only relevant if an object directory is set.
-    pub verify: bool,
-
-    /// delete the pack and index file after the operation is successful
-    #[argh(switch)]
-    pub delete_pack: bool,
-
-    /// compress bytes even when using the sink, i.e. no object directory is specified
-    ///
-    /// This helps to determine overhead related to compression. If unset, the sink will
-    /// only create hashes from bytes, which is usually limited by the speed at which input
-    /// can be obtained.
-    #[argh(switch)]
-    pub sink_compress: bool,
-
-    /// the amount of checks to run. Defaults to 'all'.
-    ///
-    /// Allowed values:
-    /// all
-    /// skip-file-checksum
-    /// skip-file-and-object-checksum
-    /// skip-file-and-object-checksum-and-no-abort-on-decode
-    #[argh(option, short = 'c')]
-    pub check: Option<core::pack::explode::SafetyCheck>,
-
-    /// the '.pack' or '.idx' file to explode into loose objects
-    #[argh(positional)]
-    pub pack_path: PathBuf,
-
-    /// the path into which all objects should be written. Commonly '.git/objects'
-    #[argh(positional)]
-    pub object_path: Option<PathBuf>,
-}
-
 /// Verify a pack
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "pack-create")]
@@ -200,49 +124,243 @@ pub struct PackCreate {
     pub tips: Vec<OsString>,
 }
 
-/// Verify a pack
+/// Subcommands that need no git repository to run.
 #[derive(FromArgs, PartialEq, Debug)]
-#[argh(subcommand, name = "pack-verify")]
-pub struct PackVerify {
-    #[argh(switch)]
-    /// decode and parse tags, commits and trees to validate their correctness beyond hashing correctly.
-    ///
-    /// Malformed objects should not usually occur, but could be injected on purpose or accident.
-    /// This will reduce overall performance.
-    pub decode: bool,
-
-    #[argh(switch)]
-    /// decode and parse tags, commits and trees to validate their correctness, and re-encode them.
-    ///
-    /// This flag is primarily to test the implementation of encoding, and requires to decode the object first.
-    /// Encoding an object after decoding it should yield exactly the same bytes.
-    /// This will reduce overall performance even more, as re-encoding requires to transform zero-copy objects into
-    /// owned objects, causing plenty of allocation to occour.
-    pub re_encode: bool,
-
-    #[argh(option)]
-    /// the algorithm used to verify the pack. They differ in costs.
-    ///
-    /// Possible values are "less-time" and "less-memory". Default is "less-memory".
-    pub algorithm: Option<core::pack::verify::Algorithm>,
-
-    /// output statistical information about the pack
-    #[argh(switch, short = 's')]
-    pub statistics: bool,
-    /// the '.pack' or '.idx' file whose checksum to validate.
-    #[argh(positional)]
-    pub path: PathBuf,
+#[argh(subcommand, name = "free")]
+pub struct Free {
+    #[argh(subcommand)]
+    pub cmd: free::SubCommands,
 }
 
-/// Verify a commit graph
-#[derive(FromArgs, PartialEq, Debug)]
-#[argh(subcommand, name = "commit-graph-verify")]
-pub struct CommitGraphVerify {
-    /// the path to '.git/objects/info/', '.git/objects/info/commit-graphs/', or '.git/objects/info/commit-graph' to validate.
-    #[argh(positional)]
-    pub path: PathBuf,
+pub mod free {
+    use argh::FromArgs;
 
-    /// output statistical information about the pack
-    #[argh(switch, short = 's')]
-    pub statistics: bool,
+    #[derive(FromArgs, PartialEq, Debug)]
+    #[argh(subcommand)]
+    pub enum SubCommands {
+        CommitGraph(CommitGraph),
+        Pack(Pack),
+        Packetline(Packetline),
+        Url(Url),
+    }
+
+    /// Subcommands for interacting with commit-graphs
+    #[derive(FromArgs, PartialEq, Debug)]
+    #[argh(subcommand, name = "commit-graph")]
+    pub struct CommitGraph {
+        #[argh(subcommand)]
+        pub cmd: commitgraph::SubCommands,
+    }
+
+    /// Subcommands for interacting with pack files and indices
+    #[derive(FromArgs, PartialEq, Debug)]
+    #[argh(subcommand, name = "pack")]
+    pub struct Pack {
+        #[argh(subcommand)]
+        pub cmd: pack::SubCommands,
+    }
+
+    /// Subcommands for interacting with data in the packet line format used by the git protocol
+    #[derive(FromArgs, PartialEq, Debug)]
+    #[argh(subcommand, name = "packetline")]
+    pub struct Packetline {
+        #[argh(subcommand)]
+        pub cmd: packetline::SubCommands,
+    }
+
+    /// Subcommands for interacting with git URLs
+    #[derive(FromArgs, PartialEq, Debug)]
+    #[argh(subcommand, name = "url")]
+    pub struct Url {
+        #[argh(subcommand)]
+        pub cmd: url::SubCommands,
+    }
+
+    pub mod commitgraph {
+        use argh::FromArgs;
+        use std::path::PathBuf;
+
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand)]
+        pub enum SubCommands {
+            Verify(Verify),
+        }
+
+        /// Verify a commit graph, including checksums, parent references and generation numbers
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand, name = "verify")]
+        pub struct Verify {
+            /// the path to '.git/objects/info/', '.git/objects/info/commit-graphs/', or '.git/objects/info/commit-graph' to validate.
+            #[argh(positional)]
+            pub path: PathBuf,
+
+            /// output statistical information about the commit graph and each of its layers
+            #[argh(switch, short = 's')]
+            pub statistics: bool,
+        }
+    }
+
+    pub mod pack {
+        use argh::FromArgs;
+        use gitoxide_core as core;
+        use std::path::PathBuf;
+
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand)]
+        pub enum SubCommands {
+            Verify(Verify),
+            Explode(Explode),
+            IndexFromData(IndexFromData),
+        }
+
+        /// Create an index from a packfile.
+        ///
+        /// This command can also be used to stream packs to standard input or to repair partial packs.
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand, name = "index-from-data")]
+        pub struct IndexFromData {
+            /// specify how to iterate the pack, defaults to 'verify'
+            ///
+            /// Valid values are
+            ///
+            ///  **as-is** do not do anything and expect the pack file to be valid as per the trailing hash,
+            ///  **verify** the input ourselves and validate that it matches with the hash provided in the pack,
+            ///  **restore** hash the input ourselves and ignore failing entries, instead finish the pack with the hash we computed
+            #[argh(option, short = 'i')]
+            pub iteration_mode: Option<core::pack::index::IterationMode>,
+
+            /// path to the pack file to read (with .pack extension).
+            ///
+            /// If unset, the pack file is expected on stdin.
+            #[argh(option, short = 'p')]
+            pub pack_path: Option<PathBuf>,
+
+            /// the folder into which to place the pack and the generated index file
+            ///
+            /// If unset, only informational output will be provided to standard output.
+            #[argh(positional)]
+            pub directory: Option<PathBuf>,
+        }
+
+        /// Explode a pack into loose objects.
+        ///
+        /// This can be useful in case of partially invalidated packs to extract as much information as possible,
+        /// or because working with loose objects is easier with custom tooling.
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand, name = "explode")]
+        pub struct Explode {
+            #[argh(switch)]
+            /// read written objects back and assert they match their source. Fail the operation otherwise.
+            ///
+            /// Only relevant if an object directory is set.
+            pub verify: bool,
+
+            /// delete the pack and index file after the operation is successful
+            #[argh(switch)]
+            pub delete_pack: bool,
+
+            /// compress bytes even when using the sink, i.e. no object directory is specified
+            ///
+            /// This helps to determine overhead related to compression. If unset, the sink will
+            /// only create hashes from bytes, which is usually limited by the speed at which input
+            /// can be obtained.
+            #[argh(switch)]
+            pub sink_compress: bool,
+
+            /// the amount of checks to run. Defaults to 'all'.
+            ///
+            /// Allowed values:
+            /// all
+            /// skip-file-checksum
+            /// skip-file-and-object-checksum
+            /// skip-file-and-object-checksum-and-no-abort-on-decode
+            #[argh(option, short = 'c')]
+            pub check: Option<core::pack::explode::SafetyCheck>,
+
+            /// the '.pack' or '.idx' file to explode into loose objects
+            #[argh(positional)]
+            pub pack_path: PathBuf,
+
+            /// the path into which all objects should be written. Commonly '.git/objects'
+            #[argh(positional)]
+            pub object_path: Option<PathBuf>,
+        }
+
+        /// Verify the integrity of a pack or index file
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand, name = "verify")]
+        pub struct Verify {
+            #[argh(switch)]
+            /// decode and parse tags, commits and trees to validate their correctness beyond hashing correctly.
+            ///
+            /// Malformed objects should not usually occur, but could be injected on purpose or accident.
+            /// This will reduce overall performance.
+            pub decode: bool,
+
+            #[argh(switch)]
+            /// decode and parse tags, commits and trees to validate their correctness, and re-encode them.
+            ///
+            /// This flag is primarily to test the implementation of encoding, and requires to decode the object first.
+            /// Encoding an object after decoding it should yield exactly the same bytes.
+            /// This will reduce overall performance even more, as re-encoding requires to transform zero-copy objects into
+            /// owned objects, causing plenty of allocation to occour.
+            pub re_encode: bool,
+
+            #[argh(option)]
+            /// the algorithm used to verify the pack. They differ in costs.
+            ///
+            /// Possible values are "less-time" and "less-memory". Default is "less-memory".
+            pub algorithm: Option<core::pack::verify::Algorithm>,
+
+            /// output statistical information about the pack
+            #[argh(switch, short = 's')]
+            pub statistics: bool,
+            /// the '.pack' or '.idx' file whose checksum to validate.
+            #[argh(positional)]
+            pub path: PathBuf,
+        }
+    }
+
+    pub mod packetline {
+        use argh::FromArgs;
+        use std::path::PathBuf;
+
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand)]
+        pub enum SubCommands {
+            Decode(Decode),
+        }
+
+        /// Decode all packet lines of a file and display them
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand, name = "decode")]
+        pub struct Decode {
+            /// the file containing the packet lines to decode.
+            ///
+            /// If unset, packet lines are expected on stdin.
+            #[argh(positional)]
+            pub path: Option<PathBuf>,
+        }
+    }
+
+    pub mod url {
+        use argh::FromArgs;
+
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand)]
+        pub enum SubCommands {
+            Parse(Parse),
+        }
+
+        /// Parse a URL and display its components
+        #[derive(FromArgs, PartialEq, Debug)]
+        #[argh(subcommand, name = "parse")]
+        pub struct Parse {
+            /// the URL to parse.
+            ///
+            /// See here for a list of supported URLs: https://www.git-scm.com/docs/git-clone#_git_urls
+            #[argh(positional)]
+            pub url: String,
+        }
+    }
 }
@@ -3,7 +3,7 @@ use clap::Clap;
 use gitoxide_core as core;
 
 use crate::{
-    plumbing::pretty::options::{Args, Subcommands},
+    plumbing::pretty::options::{free, Args, Subcommands},
     shared::pretty::prepare_and_run,
 };
 use gitoxide_core::pack::verify;
@@ -125,123 +125,157 @@ pub fn main() -> Result<()> {
                 )
             },
         ),
-        Subcommands::PackIndexFromData {
-            iteration_mode,
-            pack_path,
-            directory,
-        } => prepare_and_run(
-            "pack-index-from-data",
-            verbose,
-            progress,
-            progress_keep_open,
-            core::pack::index::PROGRESS_RANGE,
-            move |progress, out, _err| {
-                use gitoxide_core::pack::index::PathOrRead;
-                let input = if let Some(path) = pack_path {
-                    PathOrRead::Path(path)
-                } else {
-                    if atty::is(atty::Stream::Stdin) {
-                        anyhow::bail!("Refusing to read from standard input as no path is given, but it's a terminal.")
-                    }
-                    PathOrRead::Read(Box::new(std::io::stdin()))
-                };
-                core::pack::index::from_pack(
-                    input,
-                    directory,
-                    git_features::progress::DoOrDiscard::from(progress),
-                    core::pack::index::Context {
-                        thread_limit,
-                        iteration_mode,
-                        format,
-                        out,
-                        should_interrupt: &git_repository::interrupt::IS_INTERRUPTED,
-                    },
-                )
-            },
-        ),
-        Subcommands::PackExplode {
-            check,
-            sink_compress,
-            delete_pack,
-            pack_path,
-            object_path,
-            verify,
-        } => prepare_and_run(
-            "pack-explode",
-            verbose,
-            progress,
-            progress_keep_open,
-            None,
-            move |progress, _out, _err| {
-                core::pack::explode::pack_or_pack_index(
-                    pack_path,
-                    object_path,
-                    check,
+        Subcommands::Free(subcommands) => match subcommands {
+            free::Subcommands::CommitGraph(free::commitgraph::Subcommands::Verify { path, statistics }) => {
+                prepare_and_run(
+                    "commit-graph-verify",
+                    verbose,
                     progress,
-                    core::pack::explode::Context {
-                        thread_limit,
-                        delete_pack,
-                        sink_compress,
-                        verify,
-                        should_interrupt,
-                    },
-                )
-            },
-        ),
-        Subcommands::PackVerify {
-            path,
-            algorithm,
-            decode,
-            re_encode,
-            statistics,
-        } => prepare_and_run(
-            "pack-verify",
-            verbose,
-            progress,
-            progress_keep_open,
-            None,
-            move |progress, out, err| {
-                let mode = match (decode, re_encode) {
-                    (true, false) => verify::Mode::Sha1Crc32Decode,
-                    (true, true) | (false, true) => verify::Mode::Sha1Crc32DecodeEncode,
-                    (false, false) => verify::Mode::Sha1Crc32,
-                };
-                let output_statistics = if statistics { Some(format) } else { None };
-                verify::pack_or_pack_index(
-                    path,
-                    progress,
-                    verify::Context {
-                        output_statistics,
-                        out,
-                        err,
-                        thread_limit,
-                        mode,
-                        algorithm,
-                        should_interrupt,
-                    },
-                )
-            },
-        )
-        .map(|_| ()),
-        Subcommands::CommitGraphVerify { path, statistics } => prepare_and_run(
-            "commit-graph-verify",
-            verbose,
-            progress,
-            progress_keep_open,
-            None,
-            move |_progress, out, err| {
-                let output_statistics = if statistics { Some(format) } else { None };
-                core::commitgraph::verify::graph_or_file(
-                    path,
-                    core::commitgraph::verify::Context {
-                        err,
-                        out,
-                        output_statistics,
+                    progress_keep_open,
+                    None,
+                    move |_progress, out, err| {
+                        let output_statistics = if statistics { Some(format) } else { None };
+                        core::commitgraph::verify::graph_or_file(
+                            path,
+                            core::commitgraph::verify::Context {
+                                err,
+                                out,
+                                output_statistics,
+                            },
+                        )
                     },
                 )
-            },
-        )
-        .map(|_| ()),
+                .map(|_| ())
+            }
+            free::Subcommands::Pack(free::pack::Subcommands::IndexFromData {
+                iteration_mode,
+                pack_path,
+                directory,
+            }) => prepare_and_run(
+                "pack-index-from-data",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::pack::index::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    use gitoxide_core::pack::index::PathOrRead;
+                    let input = if let Some(path) = pack_path {
+                        PathOrRead::Path(path)
+                    } else {
+                        if atty::is(atty::Stream::Stdin) {
+                            anyhow::bail!(
+                                "Refusing to read from standard input as no path is given, but it's a terminal."
+                            )
+                        }
+                        PathOrRead::Read(Box::new(std::io::stdin()))
+                    };
+                    core::pack::index::from_pack(
+                        input,
+                        directory,
+                        git_features::progress::DoOrDiscard::from(progress),
+                        core::pack::index::Context {
+                            thread_limit,
+                            iteration_mode,
+                            format,
+                            out,
+                            should_interrupt: &git_repository::interrupt::IS_INTERRUPTED,
+                        },
+                    )
+                },
+            ),
+            free::Subcommands::Pack(free::pack::Subcommands::Explode {
+                check,
+                sink_compress,
+                delete_pack,
+                pack_path,
+                object_path,
+                verify,
+            }) => prepare_and_run(
+                "pack-explode",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, _out, _err| {
+                    core::pack::explode::pack_or_pack_index(
+                        pack_path,
+                        object_path,
+                        check,
+                        progress,
+                        core::pack::explode::Context {
+                            thread_limit,
+                            delete_pack,
+                            sink_compress,
+                            verify,
+                            should_interrupt,
+                        },
+                    )
+                },
+            ),
+            free::Subcommands::Pack(free::pack::Subcommands::Verify {
+                path,
+                algorithm,
+                decode,
+                re_encode,
+                statistics,
+            }) => prepare_and_run(
+                "pack-verify",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, out, err| {
+                    let mode = match (decode, re_encode) {
+                        (true, false) => verify::Mode::Sha1Crc32Decode,
+                        (true, true) | (false, true) => verify::Mode::Sha1Crc32DecodeEncode,
+                        (false, false) => verify::Mode::Sha1Crc32,
+                    };
+                    let output_statistics = if statistics { Some(format) } else { None };
+                    verify::pack_or_pack_index(
+                        path,
+                        progress,
+                        verify::Context {
+                            output_statistics,
+                            out,
+                            err,
+                            thread_limit,
+                            mode,
+                            algorithm,
+                            should_interrupt,
+                        },
+                    )
+                },
+            )
+            .map(|_| ()),
+            free::Subcommands::Packetline(free::packetline::Subcommands::Decode { path }) => prepare_and_run(
+                "packetline-decode",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| match path {
+                    Some(path) => core::packetline::decode(std::fs::File::open(path)?, format, out),
+                    None => {
+                        if atty::is(atty::Stream::Stdin) {
+                            anyhow::bail!(
+                                "Refusing to read from standard input as no path is given, but it's a terminal."
+                            )
+                        }
+                        core::packetline::decode(stdin(), format, out)
+                    }
+                },
+            )
+            .map(|_| ()),
+            free::Subcommands::Url(free::url::Subcommands::Parse { url }) => prepare_and_run(
+                "url-parse",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::url::parse(&url, format, out),
+            )
+            .map(|_| ()),
+        },
     }?;
     Ok(())
 }
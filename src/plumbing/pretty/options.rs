@@ -119,120 +119,209 @@ pub enum Subcommands {
         /// See here for a list of supported URLs: <https://www.git-scm.com/docs/git-clone#_git_urls>
         url: String,
     },
+    /// Subcommands that need no git repository to run.
     #[clap(setting = AppSettings::ColoredHelp)]
     #[clap(setting = AppSettings::DisableVersion)]
-    PackIndexFromData {
-        /// Specify how to iterate the pack, defaults to 'verify'
-        ///
-        /// Valid values are
-        ///
-        ///  **as-is** do not do anything and expect the pack file to be valid as per the trailing hash,
-        ///  **verify** the input ourselves and validate that it matches with the hash provided in the pack,
-        ///  **restore** hash the input ourselves and ignore failing entries, instead finish the pack with the hash we computed
-        #[clap(
-            long,
-            short = 'i',
-            default_value = "verify",
-            possible_values(core::pack::index::IterationMode::variants())
-        )]
-        iteration_mode: core::pack::index::IterationMode,
-
-        /// Path to the pack file to read (with .pack extension).
-        ///
-        /// If unset, the pack file is expected on stdin.
-        #[clap(long, short = 'p')]
-        pack_path: Option<PathBuf>,
+    #[clap(setting = AppSettings::SubcommandRequired)]
+    Free(free::Subcommands),
+}
 
-        /// The folder into which to place the pack and the generated index file
-        ///
-        /// If unset, only informational output will be provided to standard output.
-        #[clap(parse(from_os_str))]
-        directory: Option<PathBuf>,
-    },
-    /// Verify the integrity of a pack or index file
-    #[clap(setting = AppSettings::ColoredHelp)]
-    #[clap(setting = AppSettings::DisableVersion)]
-    PackExplode {
-        #[clap(long)]
-        /// Read written objects back and assert they match their source. Fail the operation otherwise.
-        ///
-        /// Only relevant if an object directory is set.
-        verify: bool,
+pub mod free {
+    use clap::{AppSettings, Clap};
 
-        /// delete the pack and index file after the operation is successful
-        #[clap(long)]
-        delete_pack: bool,
-
-        /// The amount of checks to run
-        #[clap(
-            long,
-            short = 'c',
-            default_value = "all",
-            possible_values(core::pack::explode::SafetyCheck::variants())
-        )]
-        check: core::pack::explode::SafetyCheck,
-
-        /// Compress bytes even when using the sink, i.e. no object directory is specified
-        ///
-        /// This helps to determine overhead related to compression. If unset, the sink will
-        /// only create hashes from bytes, which is usually limited by the speed at which input
-        /// can be obtained.
-        #[clap(long)]
-        sink_compress: bool,
+    #[derive(Debug, Clap)]
+    pub enum Subcommands {
+        /// Subcommands for interacting with commit-graphs
+        #[clap(setting = AppSettings::ColoredHelp)]
+        #[clap(setting = AppSettings::DisableVersion)]
+        #[clap(setting = AppSettings::SubcommandRequired)]
+        CommitGraph(commitgraph::Subcommands),
+        /// Subcommands for interacting with pack files and indices
+        #[clap(setting = AppSettings::ColoredHelp)]
+        #[clap(setting = AppSettings::DisableVersion)]
+        #[clap(setting = AppSettings::SubcommandRequired)]
+        Pack(pack::Subcommands),
+        /// Subcommands for interacting with data in the packet line format used by the git protocol
+        #[clap(setting = AppSettings::ColoredHelp)]
+        #[clap(setting = AppSettings::DisableVersion)]
+        #[clap(setting = AppSettings::SubcommandRequired)]
+        Packetline(packetline::Subcommands),
+        /// Subcommands for interacting with git URLs
+        #[clap(setting = AppSettings::ColoredHelp)]
+        #[clap(setting = AppSettings::DisableVersion)]
+        #[clap(setting = AppSettings::SubcommandRequired)]
+        Url(url::Subcommands),
+    }
 
-        /// The '.pack' or '.idx' file to explode into loose objects
-        #[clap(parse(from_os_str))]
-        pack_path: PathBuf,
+    pub mod commitgraph {
+        use clap::{AppSettings, Clap};
+        use std::path::PathBuf;
 
-        /// The path into which all objects should be written. Commonly '.git/objects'
-        #[clap(parse(from_os_str))]
-        object_path: Option<PathBuf>,
-    },
-    /// Verify the integrity of a pack or index file
-    #[clap(setting = AppSettings::ColoredHelp)]
-    #[clap(setting = AppSettings::DisableVersion)]
-    PackVerify {
-        /// output statistical information about the pack
-        #[clap(long, short = 's')]
-        statistics: bool,
-        /// The algorithm used to verify the pack. They differ in costs.
-        #[clap(
-            long,
-            short = 'a',
-            default_value = "less-time",
-            possible_values(core::pack::verify::Algorithm::variants())
-        )]
-        algorithm: core::pack::verify::Algorithm,
-
-        #[clap(long, conflicts_with("re-encode"))]
-        /// Decode and parse tags, commits and trees to validate their correctness beyond hashing correctly.
-        ///
-        /// Malformed objects should not usually occur, but could be injected on purpose or accident.
-        /// This will reduce overall performance.
-        decode: bool,
+        #[derive(Debug, Clap)]
+        pub enum Subcommands {
+            /// Verify the integrity of a commit graph, including checksums, parent references and generation numbers
+            #[clap(setting = AppSettings::ColoredHelp)]
+            #[clap(setting = AppSettings::DisableVersion)]
+            Verify {
+                /// The path to '.git/objects/info/', '.git/objects/info/commit-graphs/', or '.git/objects/info/commit-graph' to validate.
+                #[clap(parse(from_os_str))]
+                path: PathBuf,
+                /// output statistical information about the commit graph and each of its layers
+                #[clap(long, short = 's')]
+                statistics: bool,
+            },
+        }
+    }
 
-        #[clap(long)]
-        /// Decode and parse tags, commits and trees to validate their correctness, and re-encode them.
-        ///
-        /// This flag is primarily to test the implementation of encoding, and requires to decode the object first.
-        /// Encoding an object after decoding it should yield exactly the same bytes.
-        /// This will reduce overall performance even more, as re-encoding requires to transform zero-copy objects into
-        /// owned objects, causing plenty of allocation to occour.
-        re_encode: bool,
-
-        /// The '.pack' or '.idx' file whose checksum to validate.
-        #[clap(parse(from_os_str))]
-        path: PathBuf,
-    },
-    /// Verify the integrity of a commit graph
-    #[clap(setting = AppSettings::ColoredHelp)]
-    #[clap(setting = AppSettings::DisableVersion)]
-    CommitGraphVerify {
-        /// The path to '.git/objects/info/', '.git/objects/info/commit-graphs/', or '.git/objects/info/commit-graph' to validate.
-        #[clap(parse(from_os_str))]
-        path: PathBuf,
-        /// output statistical information about the pack
-        #[clap(long, short = 's')]
-        statistics: bool,
-    },
+    pub mod pack {
+        use clap::{AppSettings, Clap};
+        use gitoxide_core as core;
+        use std::path::PathBuf;
+
+        #[derive(Debug, Clap)]
+        pub enum Subcommands {
+            /// Create an index from a pack file, which can also be streamed through standard input
+            #[clap(setting = AppSettings::ColoredHelp)]
+            #[clap(setting = AppSettings::DisableVersion)]
+            IndexFromData {
+                /// Specify how to iterate the pack, defaults to 'verify'
+                ///
+                /// Valid values are
+                ///
+                ///  **as-is** do not do anything and expect the pack file to be valid as per the trailing hash,
+                ///  **verify** the input ourselves and validate that it matches with the hash provided in the pack,
+                ///  **restore** hash the input ourselves and ignore failing entries, instead finish the pack with the hash we computed
+                #[clap(
+                    long,
+                    short = 'i',
+                    default_value = "verify",
+                    possible_values(core::pack::index::IterationMode::variants())
+                )]
+                iteration_mode: core::pack::index::IterationMode,
+
+                /// Path to the pack file to read (with .pack extension).
+                ///
+                /// If unset, the pack file is expected on stdin.
+                #[clap(long, short = 'p')]
+                pack_path: Option<PathBuf>,
+
+                /// The folder into which to place the pack and the generated index file
+                ///
+                /// If unset, only informational output will be provided to standard output.
+                #[clap(parse(from_os_str))]
+                directory: Option<PathBuf>,
+            },
+            /// Explode a pack into loose objects
+            #[clap(setting = AppSettings::ColoredHelp)]
+            #[clap(setting = AppSettings::DisableVersion)]
+            Explode {
+                #[clap(long)]
+                /// Read written objects back and assert they match their source. Fail the operation otherwise.
+                ///
+                /// Only relevant if an object directory is set.
+                verify: bool,
+
+                /// delete the pack and index file after the operation is successful
+                #[clap(long)]
+                delete_pack: bool,
+
+                /// The amount of checks to run
+                #[clap(
+                    long,
+                    short = 'c',
+                    default_value = "all",
+                    possible_values(core::pack::explode::SafetyCheck::variants())
+                )]
+                check: core::pack::explode::SafetyCheck,
+
+                /// Compress bytes even when using the sink, i.e. no object directory is specified
+                ///
+                /// This helps to determine overhead related to compression. If unset, the sink will
+                /// only create hashes from bytes, which is usually limited by the speed at which input
+                /// can be obtained.
+                #[clap(long)]
+                sink_compress: bool,
+
+                /// The '.pack' or '.idx' file to explode into loose objects
+                #[clap(parse(from_os_str))]
+                pack_path: PathBuf,
+
+                /// The path into which all objects should be written. Commonly '.git/objects'
+                #[clap(parse(from_os_str))]
+                object_path: Option<PathBuf>,
+            },
+            /// Verify the integrity of a pack or index file
+            #[clap(setting = AppSettings::ColoredHelp)]
+            #[clap(setting = AppSettings::DisableVersion)]
+            Verify {
+                /// output statistical information about the pack
+                #[clap(long, short = 's')]
+                statistics: bool,
+                /// The algorithm used to verify the pack. They differ in costs.
+                #[clap(
+                    long,
+                    short = 'a',
+                    default_value = "less-time",
+                    possible_values(core::pack::verify::Algorithm::variants())
+                )]
+                algorithm: core::pack::verify::Algorithm,
+
+                #[clap(long, conflicts_with("re-encode"))]
+                /// Decode and parse tags, commits and trees to validate their correctness beyond hashing correctly.
+                ///
+                /// Malformed objects should not usually occur, but could be injected on purpose or accident.
+                /// This will reduce overall performance.
+                decode: bool,
+
+                #[clap(long)]
+                /// Decode and parse tags, commits and trees to validate their correctness, and re-encode them.
+                ///
+                /// This flag is primarily to test the implementation of encoding, and requires to decode the object first.
+                /// Encoding an object after decoding it should yield exactly the same bytes.
+                /// This will reduce overall performance even more, as re-encoding requires to transform zero-copy objects into
+                /// owned objects, causing plenty of allocation to occour.
+                re_encode: bool,
+
+                /// The '.pack' or '.idx' file whose checksum to validate.
+                #[clap(parse(from_os_str))]
+                path: PathBuf,
+            },
+        }
+    }
+
+    pub mod packetline {
+        use clap::{AppSettings, Clap};
+        use std::path::PathBuf;
+
+        #[derive(Debug, Clap)]
+        pub enum Subcommands {
+            /// Decode all packet lines of a file and display them
+            #[clap(setting = AppSettings::ColoredHelp)]
+            #[clap(setting = AppSettings::DisableVersion)]
+            Decode {
+                /// The file containing the packet lines to decode.
+                ///
+                /// If unset, packet lines are expected on stdin.
+                #[clap(parse(from_os_str))]
+                path: Option<PathBuf>,
+            },
+        }
+    }
+
+    pub mod url {
+        use clap::{AppSettings, Clap};
+
+        #[derive(Debug, Clap)]
+        pub enum Subcommands {
+            /// Parse a URL and display its components
+            #[clap(setting = AppSettings::ColoredHelp)]
+            #[clap(setting = AppSettings::DisableVersion)]
+            Parse {
+                /// The URL to parse.
+                ///
+                /// See here for a list of supported URLs: <https://www.git-scm.com/docs/git-clone#_git_urls>
+                url: String,
+            },
+        }
+    }
 }
@@ -3,7 +3,7 @@ use crate::{
     shared::pretty::prepare_and_run,
 };
 use anyhow::Result;
-use clap::Clap;
+use clap::{ArgSettings, Clap, IntoApp};
 use git_features::progress::DoOrDiscard;
 use gitoxide_core as core;
 use std::sync::{
@@ -12,7 +12,39 @@ use std::sync::{
 };
 
 pub fn main() -> Result<()> {
-    let args: Args = Args::parse();
+    let app = Args::into_app();
+    let builtins: Vec<_> = app
+        .get_subcommands()
+        .flat_map(|cmd| std::iter::once(cmd.get_name()).chain(cmd.get_all_aliases()))
+        .collect();
+    let flags_with_values: Vec<_> = app
+        .get_arguments()
+        .filter(|arg| arg.is_set(ArgSettings::TakesValue))
+        .flat_map(|arg| {
+            arg.get_long()
+                .map(|long| format!("--{}", long))
+                .into_iter()
+                .chain(arg.get_short().map(|short| format!("-{}", short)))
+        })
+        .collect();
+    let flags_with_values: Vec<_> = flags_with_values.iter().map(String::as_str).collect();
+    let config_paths = core::alias::config_paths();
+    let args = match core::alias::expand(
+        std::env::args_os().collect(),
+        core::alias::Options {
+            builtins: &builtins,
+            flags_with_values: &flags_with_values,
+            allow_shell: core::alias::shell_aliases_allowed(&config_paths)?,
+        },
+        |name| core::alias::lookup(&config_paths, name),
+    )? {
+        core::alias::Expansion::Args(args) => args,
+        core::alias::Expansion::Shell { command, args } => {
+            let status = core::alias::run_shell(&command, &args)?;
+            std::process::exit(status.code().unwrap_or(1));
+        }
+    };
+    let args: Args = Args::parse_from(args);
     let should_interrupt = Arc::new(AtomicBool::new(false));
     git_repository::interrupt::init_handler({
         let should_interrupt = Arc::clone(&should_interrupt);
@@ -61,6 +61,60 @@ title "Porcelain ${kind}"
                 expect_run_sh $WITH_FAILURE "$exe -q tools estimate-hours . foobar"
               }
             )
+            (with "aliases configured in the repository"
+              git config alias.hours "tools estimate-hours"
+              git config alias.h hours
+              git config alias.pii "tools 'estimate-hours' \"--show-pii\""
+              git config alias.tools init
+              git config alias.loop-a loop-b
+              git config alias.loop-b loop-a
+              git config alias.empty "  "
+              git config alias.unterminated "tools 'estimate-hours"
+              git config alias.greet '!echo hello'
+              it "expands the alias and succeeds like the command it stands for" && {
+                WITH_SNAPSHOT="$snapshot/no-args-success" \
+                expect_run_sh $SUCCESSFULLY "$exe hours 2>/dev/null"
+              }
+              it "skips flags before the alias" && {
+                WITH_SNAPSHOT="$snapshot/no-args-success" \
+                expect_run_sh $SUCCESSFULLY "$exe -q hours 2>/dev/null"
+              }
+              it "expands aliases referring to other aliases" && {
+                WITH_SNAPSHOT="$snapshot/no-args-success" \
+                expect_run_sh $SUCCESSFULLY "$exe h 2>/dev/null"
+              }
+              it "splits the alias into words like a shell, honoring quotes" && {
+                WITH_SNAPSHOT="$snapshot/show-pii-success" \
+                expect_run_sh $SUCCESSFULLY "$exe pii 2>/dev/null"
+              }
+              it "never overrides built-in subcommands" && {
+                WITH_SNAPSHOT="$snapshot/no-args-success" \
+                expect_run_sh $SUCCESSFULLY "$exe tools estimate-hours 2>/dev/null"
+              }
+              it "fails if aliases refer to each other in a loop" && {
+                WITH_SNAPSHOT="$snapshot/alias-loop-failure" \
+                expect_run_sh $WITH_FAILURE "$exe loop-a"
+              }
+              it "fails if the alias expands to nothing" && {
+                WITH_SNAPSHOT="$snapshot/alias-empty-failure" \
+                expect_run_sh $WITH_FAILURE "$exe empty"
+              }
+              it "fails if the alias contains an unterminated quote" && {
+                WITH_SNAPSHOT="$snapshot/alias-unterminated-quote-failure" \
+                expect_run_sh $WITH_FAILURE "$exe unterminated"
+              }
+              it "runs shell aliases with the arguments following them" && {
+                WITH_SNAPSHOT="$snapshot/alias-shell-success" \
+                expect_run_sh $SUCCESSFULLY "$exe greet world"
+              }
+              (with "shell aliases disabled in the configuration"
+                git config gitoxide.allowShellAliases false
+                it "fails instead of running the shell alias" && {
+                  WITH_SNAPSHOT="$snapshot/alias-shell-disabled-failure" \
+                  expect_run_sh $WITH_FAILURE "$exe greet world"
+                }
+              )
+            )
           )
         )
       )
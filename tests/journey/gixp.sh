@@ -198,6 +198,12 @@ title "gixp remote-ref-list"
           expect_run $SUCCESSFULLY "$exe_plumbing" --format json remote-ref-list .git
         }
       )
+      (with "--format json-lines"
+        it "generates the correct output with one JSON document per line" && {
+          WITH_SNAPSHOT="$snapshot/file-v-any-json-lines" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --format json-lines remote-ref-list .git
+        }
+      )
       fi
     )
     fi
@@ -250,8 +256,8 @@ title "gixp remote-ref-list"
   )
 )
 
-title "gixp pack-index-from-data"
-(when "running 'pack-index-from-data"
+title "gixp free pack index-from-data"
+(when "running 'pack index-from-data'"
   snapshot="$snapshot/pack-index-from-data"
   PACK_FILE="$fixtures/packs/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.pack"
   (with "a valid and complete pack file"
@@ -259,19 +265,19 @@ title "gixp pack-index-from-data"
       (with "pack file passed as file"
         it "generates an index into a sink and outputs pack and index information" && {
           WITH_SNAPSHOT="$snapshot/no-output-dir-success" \
-          expect_run $SUCCESSFULLY "$exe_plumbing" pack-index-from-data -p "$PACK_FILE"
+          expect_run $SUCCESSFULLY "$exe_plumbing" free pack index-from-data -p "$PACK_FILE"
         }
       )
       (with "pack file passed from stdin"
         it "generates an index into a sink and outputs pack and index information" && {
           WITH_SNAPSHOT="$snapshot/no-output-dir-success" \
-          expect_run $SUCCESSFULLY "$exe_plumbing" pack-index-from-data < "$PACK_FILE"
+          expect_run $SUCCESSFULLY "$exe_plumbing" free pack index-from-data < "$PACK_FILE"
         }
         if test "$kind" = "max"; then
         (with "--format json"
           it "generates the index into a sink and outputs information as JSON" && {
             WITH_SNAPSHOT="$snapshot/no-output-dir-as-json-success" \
-            expect_run $SUCCESSFULLY "$exe_plumbing" --format json pack-index-from-data < "$PACK_FILE"
+            expect_run $SUCCESSFULLY "$exe_plumbing" --format json free pack index-from-data < "$PACK_FILE"
           }
         )
         fi
@@ -281,7 +287,7 @@ title "gixp pack-index-from-data"
       (with "with an output directory specified"
         it "generates an index and outputs information" && {
           WITH_SNAPSHOT="$snapshot/output-dir-success" \
-          expect_run $SUCCESSFULLY "$exe_plumbing" pack-index-from-data -p "$PACK_FILE" "$PWD"
+          expect_run $SUCCESSFULLY "$exe_plumbing" free pack index-from-data -p "$PACK_FILE" "$PWD"
         }
         it "writes the index and pack into the directory (they have the same names, different suffixes)" && {
           WITH_SNAPSHOT="$snapshot/output-dir-content" \
@@ -298,7 +304,7 @@ title "gixp pack-index-from-data"
 
       it "generates an index and outputs information (instead of failing)" && {
         WITH_SNAPSHOT="$snapshot/output-dir-restore-success" \
-        expect_run $SUCCESSFULLY "$exe_plumbing" pack-index-from-data -i restore -p "$PACK_FILE" "$PWD"
+        expect_run $SUCCESSFULLY "$exe_plumbing" free pack index-from-data -i restore -p "$PACK_FILE" "$PWD"
       }
 
       if test "$kind" = "max"; then
@@ -306,21 +312,21 @@ title "gixp pack-index-from-data"
         it "generates the index, overwriting existing files, and outputs information as JSON" && {
           WITH_SNAPSHOT="$snapshot/output-dir-restore-as-json-success" \
           SNAPSHOT_FILTER=remove-paths \
-          expect_run $SUCCESSFULLY "$exe_plumbing" --format json pack-index-from-data -i restore $PWD < "$PACK_FILE"
+          expect_run $SUCCESSFULLY "$exe_plumbing" --format json free pack index-from-data -i restore $PWD < "$PACK_FILE"
         }
       )
       fi
     )
   )
 )
-title "gixp pack-explode"
-(when "running 'pack-explode"
+title "gixp free pack explode"
+(when "running 'pack explode'"
   snapshot="$snapshot/pack-explode"
   PACK_FILE="$fixtures/packs/pack-11fdfa9e156ab73caae3b6da867192221f2089c2"
   (with "no objects directory specified"
     it "explodes the pack successfully and with desired output" && {
       WITH_SNAPSHOT="$snapshot/to-sink-success" \
-      expect_run $SUCCESSFULLY "$exe_plumbing" pack-explode "${PACK_FILE}.idx"
+      expect_run $SUCCESSFULLY "$exe_plumbing" free pack explode "${PACK_FILE}.idx"
     }
 
     (when "using the --delete-pack flag"
@@ -330,7 +336,7 @@ title "gixp pack-explode"
           PACK_FILE="${PACK_FILE##*/}"
           it "explodes the pack successfully and deletes the original pack and index" && {
             WITH_SNAPSHOT="$snapshot/to-sink-delete-pack-success" \
-            expect_run $SUCCESSFULLY "$exe_plumbing" pack-explode --check skip-file-checksum --delete-pack "${PACK_FILE}.pack"
+            expect_run $SUCCESSFULLY "$exe_plumbing" free pack explode --check skip-file-checksum --delete-pack "${PACK_FILE}.pack"
           }
           it "removes the original files" && {
             expect_run $WITH_FAILURE test -e "${PACK_FILE}".pack
@@ -345,7 +351,7 @@ title "gixp pack-explode"
           (with "and all safety checks"
             it "does not explode the file at all" && {
               WITH_SNAPSHOT="$snapshot/broken-delete-pack-to-sink-failure" \
-              expect_run $WITH_FAILURE "$exe_plumbing" pack-explode --sink-compress --check all --delete-pack "${PACK_FILE}.pack"
+              expect_run $WITH_FAILURE "$exe_plumbing" free pack explode --sink-compress --check all --delete-pack "${PACK_FILE}.pack"
             }
 
             it "did not touch index or pack file" && {
@@ -357,7 +363,7 @@ title "gixp pack-explode"
           (with "and no safety checks at all (and an output directory)"
             it "does explode the file" && {
               WITH_SNAPSHOT="$snapshot/broken-delete-pack-to-sink-skip-checks-success" \
-              expect_run $SUCCESSFULLY "$exe_plumbing" pack-explode --verify --check skip-file-and-object-checksum-and-no-abort-on-decode \
+              expect_run $SUCCESSFULLY "$exe_plumbing" free pack explode --verify --check skip-file-and-object-checksum-and-no-abort-on-decode \
                                         --delete-pack "${PACK_FILE}.pack" .
             }
 
@@ -386,14 +392,14 @@ title "gixp pack-explode"
   (with "a non-existing directory specified"
     it "fails with a helpful error message" && {
       WITH_SNAPSHOT="$snapshot/missing-objects-dir-fail" \
-      expect_run $WITH_FAILURE "$exe_plumbing" pack-explode -c skip-file-and-object-checksum "${PACK_FILE}.idx" does-not-exist
+      expect_run $WITH_FAILURE "$exe_plumbing" free pack explode -c skip-file-and-object-checksum "${PACK_FILE}.idx" does-not-exist
     }
   )
   (with "an existing directory specified"
     (sandbox
       it "succeeds" && {
         WITH_SNAPSHOT="$snapshot/with-objects-dir-success" \
-        expect_run $SUCCESSFULLY "$exe_plumbing" pack-explode -c skip-file-and-object-checksum-and-no-abort-on-decode \
+        expect_run $SUCCESSFULLY "$exe_plumbing" free pack explode -c skip-file-and-object-checksum-and-no-abort-on-decode \
                                                  "${PACK_FILE}.pack" .
       }
 
@@ -407,14 +413,14 @@ title "gixp pack-explode"
   )
 )
 
-title "gixp pack-verify"
-(when "running 'pack-verify"
+title "gixp free pack verify"
+(when "running 'pack verify'"
   snapshot="$snapshot/pack-verify"
   (with "a valid pack file"
     PACK_FILE="$fixtures/packs/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.pack"
     it "verifies the pack successfully and with desired output" && {
       WITH_SNAPSHOT="$snapshot/success" \
-      expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify "$PACK_FILE"
+      expect_run $SUCCESSFULLY "$exe_plumbing" free pack verify "$PACK_FILE"
     }
   )
   (with "a valid pack INDEX file"
@@ -422,39 +428,39 @@ title "gixp pack-verify"
     (with "no statistics"
       it "verifies the pack index successfully and with desired output" && {
         WITH_SNAPSHOT="$snapshot/index-success" \
-        expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify "$PACK_INDEX_FILE"
+        expect_run $SUCCESSFULLY "$exe_plumbing" free pack verify "$PACK_INDEX_FILE"
       }
     )
     (with "statistics"
       it "verifies the pack index successfully and with desired output" && {
         WITH_SNAPSHOT="$snapshot/index-with-statistics-success" \
-        expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify --statistics "$PACK_INDEX_FILE"
+        expect_run $SUCCESSFULLY "$exe_plumbing" free pack verify --statistics "$PACK_INDEX_FILE"
       }
 
       (with "and the less-memory algorithm"
         it "verifies the pack index successfully and with desired output" && {
           WITH_SNAPSHOT="$snapshot/index-with-statistics-success" \
-          expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify --algorithm less-memory --statistics "$PACK_INDEX_FILE"
+          expect_run $SUCCESSFULLY "$exe_plumbing" free pack verify --algorithm less-memory --statistics "$PACK_INDEX_FILE"
         }
       )
     )
     (with "decode"
       it "verifies the pack index successfully and with desired output, and decodes all objects" && {
         WITH_SNAPSHOT="$snapshot/index-success" \
-        expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify --algorithm less-memory --decode "$PACK_INDEX_FILE"
+        expect_run $SUCCESSFULLY "$exe_plumbing" free pack verify --algorithm less-memory --decode "$PACK_INDEX_FILE"
       }
     )
     (with "re-encode"
       it "verifies the pack index successfully and with desired output, and re-encodes all objects" && {
         WITH_SNAPSHOT="$snapshot/index-success" \
-        expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify --algorithm less-time --re-encode "$PACK_INDEX_FILE"
+        expect_run $SUCCESSFULLY "$exe_plumbing" free pack verify --algorithm less-time --re-encode "$PACK_INDEX_FILE"
       }
     )
     if test "$kind" = "max"; then
     (with "statistics (JSON)"
       it "verifies the pack index successfully and with desired output" && {
         WITH_SNAPSHOT="$snapshot/index-with-statistics-json-success" \
-        expect_run $SUCCESSFULLY "$exe_plumbing" --format json --threads 1 pack-verify --statistics "$PACK_INDEX_FILE"
+        expect_run $SUCCESSFULLY "$exe_plumbing" --format json --threads 1 free pack verify --statistics "$PACK_INDEX_FILE"
       }
     )
     fi
@@ -466,13 +472,13 @@ title "gixp pack-verify"
       echo $'\0' >> index.idx
       it "fails to verify the pack index and with desired output" && {
         WITH_SNAPSHOT="$snapshot/index-failure" \
-        expect_run $WITH_FAILURE "$exe_plumbing" pack-verify index.idx
+        expect_run $WITH_FAILURE "$exe_plumbing" free pack verify index.idx
       }
     )
   )
 )
-title "gixp commit-graph-verify"
-(when "running 'commit-graph-verify'"
+title "gixp free commit-graph verify"
+(when "running 'commit-graph verify'"
   snapshot="$snapshot/commit-graph-verify"
   (small-repo-in-sandbox
     (with "a valid and complete commit-graph file"
@@ -480,17 +486,54 @@ title "gixp commit-graph-verify"
       (with "statistics"
         it "generates the correct output" && {
           WITH_SNAPSHOT="$snapshot/statistics-success" \
-          expect_run $SUCCESSFULLY "$exe_plumbing" commit-graph-verify -s .git/objects/info
+          expect_run $SUCCESSFULLY "$exe_plumbing" free commit-graph verify -s .git/objects/info
         }
       )
       if test "$kind" = "max"; then
       (with "statistics --format json"
         it "generates the correct output" && {
           WITH_SNAPSHOT="$snapshot/statistics-json-success" \
-          expect_run $SUCCESSFULLY "$exe_plumbing" --format json commit-graph-verify -s .git/objects/info
+          expect_run $SUCCESSFULLY "$exe_plumbing" --format json free commit-graph verify -s .git/objects/info
         }
       )
       fi
     )
   )
 )
+title "gixp free packetline decode"
+(when "running 'packetline decode'"
+  snapshot="$snapshot/packetline-decode"
+  (sandbox
+    printf '0010hello world\n00010000' > lines
+    (with "a file containing packet lines"
+      it "displays all lines" && {
+        WITH_SNAPSHOT="$snapshot/success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" free packetline decode lines
+      }
+    )
+    (with "packet lines passed from stdin"
+      it "displays all lines" && {
+        WITH_SNAPSHOT="$snapshot/success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" free packetline decode < lines
+      }
+    )
+    if test "$kind" = "max"; then
+    (with "--format json-lines"
+      it "displays one line of JSON per packet line" && {
+        WITH_SNAPSHOT="$snapshot/success-json-lines" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --format json-lines free packetline decode lines
+      }
+    )
+    fi
+  )
+)
+title "gixp free url parse"
+(when "running 'url parse'"
+  snapshot="$snapshot/url-parse"
+  (with "an ssh url with user and port"
+    it "displays all of its components" && {
+      WITH_SNAPSHOT="$snapshot/ssh-success" \
+      expect_run $SUCCESSFULLY "$exe_plumbing" free url parse ssh://git@example.com:2222/path/to/repo.git
+    }
+  )
+)
@@ -9,7 +9,7 @@ pub use tempfile;
 static SCRIPT_IDENTITY: Lazy<Mutex<BTreeMap<PathBuf, u32>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
 
 pub fn hex_to_id(hex: &str) -> git_hash::ObjectId {
-    git_hash::ObjectId::from_hex(hex.as_bytes()).expect("40 bytes hex")
+    git_hash::ObjectId::from_hex(hex.as_bytes()).expect("40 or 64 bytes hex")
 }
 
 pub fn fixture_path(path: impl AsRef<str>) -> PathBuf {